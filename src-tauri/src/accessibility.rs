@@ -1,11 +1,79 @@
 // Cross-platform accessibility API for getting UI element info at coordinates
 
+/// Screen rectangle of a UI element, in pixels.
+#[derive(Clone, Copy, serde::Serialize, serde::Deserialize, Debug, PartialEq, Eq)]
+pub struct ElementBounds {
+    pub x: i32,
+    pub y: i32,
+    pub width: i32,
+    pub height: i32,
+}
+
+impl ElementBounds {
+    /// Shift the rectangle, e.g. from screen to monitor-relative coordinates.
+    pub fn offset(self, dx: i32, dy: i32) -> Self {
+        Self {
+            x: self.x + dx,
+            y: self.y + dy,
+            ..self
+        }
+    }
+
+    /// Scale the rectangle, e.g. from logical points to physical pixels.
+    pub fn scale(self, factor: f64) -> Self {
+        let scale = |value: i32| (value as f64 * factor).round() as i32;
+        Self {
+            x: scale(self.x),
+            y: scale(self.y),
+            width: scale(self.width),
+            height: scale(self.height),
+        }
+    }
+}
+
+/// One ancestor on the path from the application window down to an element.
+/// Together with the element's own identifiers this is enough to rebuild a
+/// Playwright / WinAppDriver selector later.
+#[derive(Clone, serde::Serialize, serde::Deserialize, Debug, Default, PartialEq, Eq)]
+pub struct ElementPathSegment {
+    pub role: String,
+    pub name: Option<String>,
+    pub automation_id: Option<String>,
+    pub class_name: Option<String>,
+}
+
+/// Ancestors recorded per element; deeper trees keep the innermost ones.
+const MAX_PATH_SEGMENTS: usize = 12;
+
+fn non_empty(s: String) -> Option<String> {
+    Some(s).filter(|s| !s.is_empty())
+}
+
 #[derive(Clone, serde::Serialize, Debug)]
 pub struct ElementInfo {
     pub name: String,
     pub element_type: String,
+    /// Current value of the element: the text of an edit field, or the state
+    /// of a toggle ("checked" / "unchecked" / "mixed") or selectable item
+    /// ("selected" / "not selected"). Never set for password fields.
     pub value: Option<String>,
     pub app_name: Option<String>,
+    /// Element rectangle in screen coordinates, when the platform exposes it
+    /// and it is non-empty.
+    pub bounds: Option<ElementBounds>,
+    /// Address and tab title of the page, when the element is in a browser.
+    pub page_url: Option<String>,
+    pub page_title: Option<String>,
+    /// Stable identifiers for selector generation: UIA AutomationId /
+    /// AXIdentifier / AT-SPI accessible id, and the UIA class name or raw
+    /// AX / AT-SPI role.
+    pub automation_id: Option<String>,
+    pub class_name: Option<String>,
+    /// Ancestors, outermost first, excluding the element itself.
+    pub ancestor_path: Vec<ElementPathSegment>,
+    /// Expand/collapse state of menus, combo boxes and tree items; `None`
+    /// for elements that cannot expand.
+    pub expanded: Option<bool>,
 }
 
 impl Default for ElementInfo {
@@ -15,8 +83,149 @@ impl Default for ElementInfo {
             element_type: String::new(),
             value: None,
             app_name: None,
+            bounds: None,
+            page_url: None,
+            page_title: None,
+            automation_id: None,
+            class_name: None,
+            ancestor_path: Vec::new(),
+            expanded: None,
+        }
+    }
+}
+
+/// Segments kept in a breadcrumb, counting the element itself.
+const BREADCRUMB_SEGMENTS: usize = 4;
+
+impl ElementInfo {
+    /// Short "where is it" trail such as
+    /// `Settings dialog > Network tab > Proxy group > Port field`: the
+    /// innermost named ancestors followed by the element. `None` when no
+    /// ancestor has a name.
+    pub fn breadcrumb(&self) -> Option<String> {
+        let mut segments: Vec<String> = Vec::new();
+        let mut last_name = "";
+        let named = self
+            .ancestor_path
+            .iter()
+            .filter_map(|segment| Some((segment.name.as_deref()?, segment.role.as_str())))
+            .chain(Some((self.name.as_str(), self.element_type.as_str())));
+        for (name, role) in named {
+            let name = name.trim();
+            // Containers often repeat the name of their only child.
+            if name.is_empty() || name == last_name {
+                continue;
+            }
+            last_name = name;
+            segments.push(match role_label(role) {
+                Some(label) => format!("{} {}", name, label),
+                None => name.to_string(),
+            });
+        }
+        if segments.len() < 2 {
+            return None;
+        }
+        let skip = segments.len().saturating_sub(BREADCRUMB_SEGMENTS);
+        Some(segments[skip..].join(" > "))
+    }
+}
+
+/// One node of an accessibility subtree snapshot (see
+/// `get_subtree_at_point`). Values are left out so a snapshot never carries
+/// field contents.
+#[derive(Clone, serde::Serialize, serde::Deserialize, Debug, Default, PartialEq, Eq)]
+#[serde(default)]
+pub struct AxNode {
+    pub role: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub automation_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub class_name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bounds: Option<ElementBounds>,
+    /// Set on the element the snapshot was taken around.
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub target: bool,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub children: Vec<AxNode>,
+}
+
+impl AxNode {
+    /// Convert the bounds of the whole subtree, e.g. to monitor pixels.
+    pub fn map_bounds(&mut self, convert: &impl Fn(ElementBounds) -> ElementBounds) {
+        self.bounds = self.bounds.map(convert);
+        for child in &mut self.children {
+            child.map_bounds(convert);
+        }
+    }
+
+    /// Names in the snapshot, one per line, as a stand-in for OCR text when
+    /// OCR is off or its models are missing: the target and its children
+    /// first, then the surrounding labels and controls in tree order.
+    /// Blank and repeated names are skipped.
+    pub fn context_text(&self) -> Option<String> {
+        fn find_target(node: &AxNode) -> Option<&AxNode> {
+            if node.target {
+                return Some(node);
+            }
+            node.children.iter().find_map(find_target)
+        }
+        fn collect<'a>(node: &'a AxNode, lines: &mut Vec<&'a str>) {
+            if let Some(name) = node.name.as_deref().map(str::trim) {
+                if !name.is_empty() && !lines.contains(&name) && lines.len() < CONTEXT_TEXT_LINES {
+                    lines.push(name);
+                }
+            }
+            for child in &node.children {
+                collect(child, lines);
+            }
+        }
+
+        let mut lines = Vec::new();
+        if let Some(target) = find_target(self) {
+            collect(target, &mut lines);
+        }
+        collect(self, &mut lines);
+        (!lines.is_empty()).then(|| lines.join("\n"))
+    }
+}
+
+/// Levels below the clicked element's parent kept in a subtree snapshot:
+/// the element and its siblings, and their children.
+const SUBTREE_DEPTH: usize = 2;
+/// Children kept per node, and nodes per snapshot, so a long list or table
+/// cannot make a snapshot huge.
+const SUBTREE_MAX_CHILDREN: usize = 24;
+const SUBTREE_MAX_NODES: usize = 80;
+/// Lines kept by `AxNode::context_text`.
+const CONTEXT_TEXT_LINES: usize = 30;
+
+/// Plain-English label for a role as reported by UIA (localized control
+/// type), AX (raw `AXRole` or role description) or AT-SPI (role name).
+fn role_label(role: &str) -> Option<String> {
+    let role = role.strip_prefix("AX").unwrap_or(role);
+    // "TabGroup" -> "tab group"
+    let mut words = String::new();
+    for (i, c) in role.chars().enumerate() {
+        if c.is_uppercase() && i > 0 && !words.ends_with(' ') {
+            words.push(' ');
         }
+        words.extend(c.to_lowercase());
     }
+    let label = match words.trim() {
+        "" | "unknown" | "pane" | "panel" | "filler" | "custom" | "layout table"
+        | "layout table cell" => return None,
+        "edit" | "entry" | "text field" | "text area" | "combo box" | "password text" => "field",
+        "tab item" | "page tab" => "tab",
+        "tab group" | "page tab list" | "tab list" => "tabs",
+        "frame" | "standard window" => "window",
+        "sheet" => "dialog",
+        "document web" | "web area" => "page",
+        other => other,
+    };
+    Some(label.to_string())
 }
 
 /// Read the value of the currently focused input field via the platform's
@@ -52,26 +261,192 @@ fn cap_value(s: String, max_len: usize) -> String {
 
 const MAX_FIELD_VALUE_CHARS: usize = 2000;
 
-// Windows implementation using UI Automation
+/// Look up the element at a point in the background. The receiver gets the
+/// result whenever the platform API returns, which for a hung provider may be
+/// never, so callers wait on it with a timeout.
+pub fn spawn_element_lookup(x: f64, y: f64) -> std::sync::mpsc::Receiver<Option<ElementInfo>> {
+    spawn_lookup(move || get_element_at_point(x, y))
+}
+
+/// Like `spawn_element_lookup`, for the element with keyboard focus.
+pub fn spawn_focused_element_lookup() -> std::sync::mpsc::Receiver<Option<ElementInfo>> {
+    spawn_lookup(get_focused_element)
+}
+
+/// Like `spawn_element_lookup`, for `get_subtree_at_point`: a snapshot of
+/// the tree around the element at a point, rooted at its parent so that it
+/// holds the element, its siblings and their children.
+pub fn spawn_subtree_lookup(x: f64, y: f64) -> std::sync::mpsc::Receiver<Option<AxNode>> {
+    spawn_lookup(move || get_subtree_at_point(x, y))
+}
+
+/// How long `with_uia` waits for the worker before giving up on a lookup
 #[cfg(target_os = "windows")]
-pub fn get_element_at_point(x: f64, y: f64) -> Option<ElementInfo> {
-    use windows::Win32::Foundation::POINT;
+const UIA_CALL_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(2);
+/// UIA's limits on connecting to and waiting for a provider, in milliseconds
+#[cfg(target_os = "windows")]
+const UIA_CONNECTION_TIMEOUT_MS: u32 = 1000;
+#[cfg(target_os = "windows")]
+const UIA_TRANSACTION_TIMEOUT_MS: u32 = 1500;
+
+/// A worker whose current job has run this long is abandoned for a new one,
+/// since a provider that hangs (or ignores UIA's own timeouts) would block
+/// every lookup queued behind it
+const LOOKUP_STUCK_AFTER: std::time::Duration = std::time::Duration::from_secs(5);
+/// Lookups that may wait behind the running one; more are dropped, and
+/// their callers see the receiver disconnect
+const LOOKUP_QUEUE_LEN: usize = 8;
+
+type LookupJob = Box<dyn FnOnce() + Send>;
+
+#[cfg(target_os = "windows")]
+thread_local! {
+    /// Set on a UIA worker thread to its automation instance, if one could
+    /// be created
+    static WORKER_AUTOMATION: std::cell::RefCell<
+        Option<Option<windows::Win32::UI::Accessibility::IUIAutomation>>,
+    > = const { std::cell::RefCell::new(None) };
+}
+
+struct LookupWorker {
+    jobs: std::sync::mpsc::SyncSender<LookupJob>,
+    /// When the running job started; None while idle
+    busy_since: std::sync::Arc<std::sync::Mutex<Option<std::time::Instant>>>,
+}
+
+impl LookupWorker {
+    /// Start a worker thread serving lookups in order. On Windows it owns
+    /// one `IUIAutomation` instance, created in the MTA with provider
+    /// timeouts set.
+    fn start() -> Option<Self> {
+        use std::sync::{mpsc, Arc, Mutex};
+
+        let (jobs, rx) = mpsc::sync_channel::<LookupJob>(LOOKUP_QUEUE_LEN);
+        let busy_since = Arc::new(Mutex::new(None));
+        let busy = busy_since.clone();
+        std::thread::Builder::new()
+            .name("accessibility-worker".to_string())
+            .spawn(move || {
+                #[cfg(target_os = "windows")]
+                {
+                    let automation = create_uia_automation();
+                    WORKER_AUTOMATION.with(|current| *current.borrow_mut() = Some(automation));
+                }
+                for job in rx {
+                    *busy.lock().unwrap() = Some(std::time::Instant::now());
+                    // A panicking provider call must not take the worker down.
+                    let _ = std::panic::catch_unwind(std::panic::AssertUnwindSafe(job));
+                    *busy.lock().unwrap() = None;
+                }
+            })
+            .ok()?;
+        Some(Self { jobs, busy_since })
+    }
+
+    fn is_stuck(&self) -> bool {
+        self.busy_since
+            .lock()
+            .unwrap()
+            .is_some_and(|since| since.elapsed() > LOOKUP_STUCK_AFTER)
+    }
+}
+
+#[cfg(target_os = "windows")]
+fn create_uia_automation() -> Option<windows::Win32::UI::Accessibility::IUIAutomation> {
+    use windows::core::Interface;
     use windows::Win32::System::Com::{
         CoCreateInstance, CoInitializeEx, CLSCTX_INPROC_SERVER, COINIT_MULTITHREADED,
     };
-    use windows::Win32::UI::Accessibility::{CUIAutomation, IUIAutomation};
+    use windows::Win32::UI::Accessibility::{CUIAutomation, CUIAutomation8, IUIAutomation2};
 
     unsafe {
-        // Initialize COM
         let _ = CoInitializeEx(None, COINIT_MULTITHREADED);
+        // CUIAutomation8 (Windows 8+) supports the timeouts
+        match CoCreateInstance::<_, IUIAutomation2>(&CUIAutomation8, None, CLSCTX_INPROC_SERVER) {
+            Ok(automation) => {
+                let _ = automation.SetConnectionTimeout(UIA_CONNECTION_TIMEOUT_MS);
+                let _ = automation.SetTransactionTimeout(UIA_TRANSACTION_TIMEOUT_MS);
+                automation.cast().ok()
+            }
+            Err(_) => CoCreateInstance(&CUIAutomation, None, CLSCTX_INPROC_SERVER).ok(),
+        }
+    }
+}
 
-        // Create UI Automation instance
-        let automation: IUIAutomation =
-            match CoCreateInstance(&CUIAutomation, None, CLSCTX_INPROC_SERVER) {
-                Ok(a) => a,
-                Err(_) => return None,
-            };
+/// Queue `lookup` on the accessibility worker, returning a receiver for its
+/// result.
+///
+/// One long-lived worker thread serves every lookup over a bounded channel,
+/// so a hung provider holds up at most that thread rather than leaking one
+/// per click; on Windows it also saves initialising COM and creating
+/// `CUIAutomation`, which costs tens of milliseconds and occasionally much
+/// more, per lookup. A worker stuck in a provider call is abandoned for a
+/// new one; the lookups queued behind it are lost, and their callers time
+/// out.
+fn spawn_lookup<T: Send + 'static>(
+    lookup: impl FnOnce() -> Option<T> + Send + 'static,
+) -> std::sync::mpsc::Receiver<Option<T>> {
+    use std::sync::{mpsc, Mutex};
+
+    static WORKER: Mutex<Option<LookupWorker>> = Mutex::new(None);
+
+    let (reply_tx, reply_rx) = mpsc::sync_channel(1);
+    let job: LookupJob = Box::new(move || {
+        let _ = reply_tx.send(lookup());
+    });
+
+    let mut worker = WORKER.lock().unwrap();
+    if worker.as_ref().is_some_and(LookupWorker::is_stuck) {
+        tracing::warn!("Accessibility worker is stuck in a provider call; starting a new one");
+        *worker = None;
+    }
+    if worker.is_none() {
+        *worker = LookupWorker::start();
+    }
+    // Without a worker, or with its queue full, the job is dropped and the
+    // receiver disconnects
+    if let Some(worker) = worker.as_ref() {
+        let _ = worker.jobs.try_send(job);
+    }
+    reply_rx
+}
 
+/// Run `job` with the worker's `IUIAutomation` instance and wait up to
+/// `UIA_CALL_TIMEOUT` for its result. Returns `None` if the instance could
+/// not be created or the worker didn't answer in time.
+#[cfg(target_os = "windows")]
+fn with_uia<T: Send + 'static>(
+    job: impl FnOnce(&windows::Win32::UI::Accessibility::IUIAutomation) -> Option<T> + Send + 'static,
+) -> Option<T> {
+    fn worker_automation() -> Option<Option<windows::Win32::UI::Accessibility::IUIAutomation>> {
+        WORKER_AUTOMATION.with(|current| current.borrow().clone())
+    }
+
+    // Lookups queued by `spawn_lookup` already run on the worker
+    if let Some(automation) = worker_automation() {
+        return automation.as_ref().and_then(job);
+    }
+    spawn_lookup(move || worker_automation().flatten().as_ref().and_then(job))
+        .recv_timeout(UIA_CALL_TIMEOUT)
+        .ok()
+        .flatten()
+}
+
+// Windows implementation using UI Automation
+#[cfg(target_os = "windows")]
+pub fn get_element_at_point(x: f64, y: f64) -> Option<ElementInfo> {
+    with_uia(move |automation| uia_element_at_point(automation, x, y))
+}
+
+#[cfg(target_os = "windows")]
+fn uia_element_at_point(
+    automation: &windows::Win32::UI::Accessibility::IUIAutomation,
+    x: f64,
+    y: f64,
+) -> Option<ElementInfo> {
+    use windows::Win32::Foundation::POINT;
+
+    unsafe {
         // Get element at point
         let point = POINT {
             x: x as i32,
@@ -81,7 +456,137 @@ pub fn get_element_at_point(x: f64, y: f64) -> Option<ElementInfo> {
             Ok(e) => e,
             Err(_) => return None,
         };
+        uia_describe_element(automation, &element, point)
+    }
+}
+
+/// Windows implementation of `get_focused_element`.
+#[cfg(target_os = "windows")]
+pub fn get_focused_element() -> Option<ElementInfo> {
+    use windows::Win32::Foundation::POINT;
+
+    with_uia(|automation| unsafe {
+        let element = automation.GetFocusedElement().ok()?;
+        // The browser lookup needs a point inside the element's window.
+        let rect = element.CurrentBoundingRectangle().ok()?;
+        let center = POINT {
+            x: (rect.left + rect.right) / 2,
+            y: (rect.top + rect.bottom) / 2,
+        };
+        uia_describe_element(automation, &element, center)
+    })
+}
+
+/// Windows implementation of `get_subtree_at_point`.
+#[cfg(target_os = "windows")]
+pub fn get_subtree_at_point(x: f64, y: f64) -> Option<AxNode> {
+    use windows::Win32::Foundation::POINT;
+
+    with_uia(move |automation| unsafe {
+        let point = POINT {
+            x: x as i32,
+            y: y as i32,
+        };
+        let element = automation.ElementFromPoint(point).ok()?;
+        let walker = automation.ControlViewWalker().ok()?;
+        let root = walker
+            .GetParentElement(&element)
+            .unwrap_or_else(|_| element.clone());
+        let mut budget = SUBTREE_MAX_NODES;
+        Some(uia_subtree(
+            automation,
+            &walker,
+            &root,
+            &element,
+            SUBTREE_DEPTH,
+            &mut budget,
+        ))
+    })
+}
+
+/// Snapshot `node` and its descendants down to `depth` levels, spending one
+/// unit of `budget` per node.
+#[cfg(target_os = "windows")]
+unsafe fn uia_subtree(
+    automation: &windows::Win32::UI::Accessibility::IUIAutomation,
+    walker: &windows::Win32::UI::Accessibility::IUIAutomationTreeWalker,
+    node: &windows::Win32::UI::Accessibility::IUIAutomationElement,
+    target: &windows::Win32::UI::Accessibility::IUIAutomationElement,
+    depth: usize,
+    budget: &mut usize,
+) -> AxNode {
+    *budget = budget.saturating_sub(1);
+    let mut snapshot = AxNode {
+        role: node
+            .CurrentLocalizedControlType()
+            .map(|s| s.to_string())
+            .unwrap_or_default(),
+        name: node
+            .CurrentName()
+            .ok()
+            .and_then(|s| non_empty(s.to_string())),
+        automation_id: node
+            .CurrentAutomationId()
+            .ok()
+            .and_then(|s| non_empty(s.to_string())),
+        class_name: node
+            .CurrentClassName()
+            .ok()
+            .and_then(|s| non_empty(s.to_string())),
+        bounds: node
+            .CurrentBoundingRectangle()
+            .ok()
+            .map(|rect| ElementBounds {
+                x: rect.left,
+                y: rect.top,
+                width: rect.right - rect.left,
+                height: rect.bottom - rect.top,
+            })
+            .filter(|b| b.width > 0 && b.height > 0),
+        target: automation
+            .CompareElements(node, target)
+            .map(|same| same.as_bool())
+            .unwrap_or(false),
+        children: Vec::new(),
+    };
+    if depth == 0 {
+        return snapshot;
+    }
+    let mut child = walker.GetFirstChildElement(node).ok();
+    while let Some(current) = child {
+        if *budget == 0 || snapshot.children.len() >= SUBTREE_MAX_CHILDREN {
+            break;
+        }
+        snapshot.children.push(uia_subtree(
+            automation,
+            walker,
+            &current,
+            target,
+            depth - 1,
+            budget,
+        ));
+        child = walker.GetNextSiblingElement(&current).ok();
+    }
+    snapshot
+}
+
+/// Name, type, value, bounds, selectors and browser page of a UIA element.
+/// `point` is a screen point inside it, used to find its top-level window.
+#[cfg(target_os = "windows")]
+fn uia_describe_element(
+    automation: &windows::Win32::UI::Accessibility::IUIAutomation,
+    element: &windows::Win32::UI::Accessibility::IUIAutomationElement,
+    point: windows::Win32::Foundation::POINT,
+) -> Option<ElementInfo> {
+    use windows::Win32::UI::Accessibility::{
+        ExpandCollapseState_Collapsed, ExpandCollapseState_Expanded,
+        ExpandCollapseState_PartiallyExpanded, IUIAutomationExpandCollapsePattern,
+        IUIAutomationSelectionItemPattern, IUIAutomationTogglePattern, IUIAutomationValuePattern,
+        ToggleState_Off, ToggleState_On, UIA_ExpandCollapsePatternId, UIA_SelectionItemPatternId,
+        UIA_TogglePatternId, UIA_ValuePatternId,
+    };
 
+    unsafe {
         // Get element properties using direct methods
         let name = element
             .CurrentName()
@@ -95,14 +600,95 @@ pub fn get_element_at_point(x: f64, y: f64) -> Option<ElementInfo> {
             .map(|s| s.to_string())
             .unwrap_or_default();
 
-        // Value pattern is more complex, skip for now
-        let value = None;
+        // Value: prefer the Value pattern (edits, combo boxes), then fall back
+        // to the state of toggles and selectable items so steps can say
+        // "checked 'Remember me'" rather than just naming the control.
+        let is_password = element
+            .CurrentIsPassword()
+            .ok()
+            .map(|b| b.as_bool())
+            .unwrap_or(false);
+        let value = if is_password {
+            None
+        } else {
+            element
+                .GetCurrentPatternAs::<IUIAutomationValuePattern>(UIA_ValuePatternId)
+                .ok()
+                .and_then(|vp| vp.CurrentValue().ok())
+                .map(|bstr| bstr.to_string())
+                .filter(|s| !s.is_empty())
+                .map(|s| cap_value(s, MAX_FIELD_VALUE_CHARS))
+                .or_else(|| {
+                    let tp = element
+                        .GetCurrentPatternAs::<IUIAutomationTogglePattern>(UIA_TogglePatternId)
+                        .ok()?;
+                    let state = tp.CurrentToggleState().ok()?;
+                    Some(
+                        if state == ToggleState_On {
+                            "checked"
+                        } else if state == ToggleState_Off {
+                            "unchecked"
+                        } else {
+                            "mixed"
+                        }
+                        .to_string(),
+                    )
+                })
+                .or_else(|| {
+                    let sp = element
+                        .GetCurrentPatternAs::<IUIAutomationSelectionItemPattern>(
+                            UIA_SelectionItemPatternId,
+                        )
+                        .ok()?;
+                    let selected = sp.CurrentIsSelected().ok()?.as_bool();
+                    Some(if selected { "selected" } else { "not selected" }.to_string())
+                })
+        };
+
+        // Leaf nodes support the pattern too but can never expand.
+        let expanded = element
+            .GetCurrentPatternAs::<IUIAutomationExpandCollapsePattern>(UIA_ExpandCollapsePatternId)
+            .ok()
+            .and_then(|ep| ep.CurrentExpandCollapseState().ok())
+            .and_then(|state| {
+                if state == ExpandCollapseState_Expanded
+                    || state == ExpandCollapseState_PartiallyExpanded
+                {
+                    Some(true)
+                } else if state == ExpandCollapseState_Collapsed {
+                    Some(false)
+                } else {
+                    None
+                }
+            });
+
+        let bounds = element
+            .CurrentBoundingRectangle()
+            .ok()
+            .map(|rect| ElementBounds {
+                x: rect.left,
+                y: rect.top,
+                width: rect.right - rect.left,
+                height: rect.bottom - rect.top,
+            })
+            .filter(|b| b.width > 0 && b.height > 0);
+
+        let automation_id = element
+            .CurrentAutomationId()
+            .ok()
+            .and_then(|s| non_empty(s.to_string()));
+        let class_name = element
+            .CurrentClassName()
+            .ok()
+            .and_then(|s| non_empty(s.to_string()));
 
-        // Try to get app name by walking up to root
+        // Try to get app name by walking up to root, recording the ancestor
+        // path on the way.
+        let mut ancestor_path = Vec::new();
         let app_name = if let Ok(walker) = automation.ControlViewWalker() {
             let mut current = element.clone();
             let mut root_name = None;
-            for _ in 0..10 {
+            for _ in 0..MAX_PATH_SEGMENTS {
                 if let Ok(parent) = walker.GetParentElement(&current) {
                     if let Ok(n) = parent.CurrentName() {
                         let s = n.to_string();
@@ -110,6 +696,24 @@ pub fn get_element_at_point(x: f64, y: f64) -> Option<ElementInfo> {
                             root_name = Some(s);
                         }
                     }
+                    ancestor_path.push(ElementPathSegment {
+                        role: parent
+                            .CurrentLocalizedControlType()
+                            .map(|s| s.to_string())
+                            .unwrap_or_default(),
+                        name: parent
+                            .CurrentName()
+                            .ok()
+                            .and_then(|s| non_empty(s.to_string())),
+                        automation_id: parent
+                            .CurrentAutomationId()
+                            .ok()
+                            .and_then(|s| non_empty(s.to_string())),
+                        class_name: parent
+                            .CurrentClassName()
+                            .ok()
+                            .and_then(|s| non_empty(s.to_string())),
+                    });
                     current = parent;
                 } else {
                     break;
@@ -119,16 +723,100 @@ pub fn get_element_at_point(x: f64, y: f64) -> Option<ElementInfo> {
         } else {
             None
         };
+        // The walk ends at the desktop root, which is not part of any selector.
+        if ancestor_path.len() < MAX_PATH_SEGMENTS {
+            ancestor_path.pop();
+        }
+        ancestor_path.reverse();
+
+        let (page_url, page_title) = browser_page_at_point(automation, point).unwrap_or_default();
 
         Some(ElementInfo {
             name,
             element_type,
             value,
             app_name,
+            bounds,
+            page_url,
+            page_title,
+            automation_id,
+            class_name,
+            ancestor_path,
+            expanded,
         })
     }
 }
 
+/// URL and tab title of the browser window under `point`, if it is one.
+///
+/// Chromium browsers (Chrome, Edge, Brave, ...) use the `Chrome_WidgetWin_1`
+/// window class and expose the omnibox as the first Edit control; Firefox
+/// uses `MozillaWindowClass` with a `urlbar-input` automation id. The tab
+/// title is the window title minus the trailing " - Browser Name".
+#[cfg(target_os = "windows")]
+unsafe fn browser_page_at_point(
+    automation: &windows::Win32::UI::Accessibility::IUIAutomation,
+    point: windows::Win32::Foundation::POINT,
+) -> Option<(Option<String>, Option<String>)> {
+    use windows::core::{BSTR, VARIANT};
+    use windows::Win32::UI::Accessibility::{
+        IUIAutomationValuePattern, TreeScope_Descendants, UIA_AutomationIdPropertyId,
+        UIA_ControlTypePropertyId, UIA_EditControlTypeId, UIA_ValuePatternId,
+    };
+    use windows::Win32::UI::WindowsAndMessaging::{GetAncestor, WindowFromPoint, GA_ROOT};
+
+    let hwnd = GetAncestor(WindowFromPoint(point), GA_ROOT);
+    if hwnd.0.is_null() {
+        return None;
+    }
+    let window = automation.ElementFromHandle(hwnd).ok()?;
+    let class_name = window.CurrentClassName().ok()?.to_string();
+
+    let condition = match class_name.as_str() {
+        "Chrome_WidgetWin_1" => automation
+            .CreatePropertyCondition(
+                UIA_ControlTypePropertyId,
+                &VARIANT::from(UIA_EditControlTypeId.0),
+            )
+            .ok()?,
+        "MozillaWindowClass" => automation
+            .CreatePropertyCondition(
+                UIA_AutomationIdPropertyId,
+                &VARIANT::from(BSTR::from("urlbar-input")),
+            )
+            .ok()?,
+        _ => return None,
+    };
+
+    let page_url = window
+        .FindFirst(TreeScope_Descendants, &condition)
+        .ok()
+        .and_then(|bar| {
+            bar.GetCurrentPatternAs::<IUIAutomationValuePattern>(UIA_ValuePatternId)
+                .ok()
+        })
+        .and_then(|vp| vp.CurrentValue().ok())
+        .map(|bstr| bstr.to_string())
+        .filter(|url| !url.is_empty());
+
+    let page_title = window
+        .CurrentName()
+        .ok()
+        .map(|title| {
+            let title = title.to_string();
+            match title
+                .rsplit_once(" - ")
+                .or_else(|| title.rsplit_once(" — "))
+            {
+                Some((page, _browser)) => page.to_string(),
+                None => title,
+            }
+        })
+        .filter(|title| !title.is_empty());
+
+    Some((page_url, page_title))
+}
+
 /// Windows implementation of `get_focused_field_value`.
 ///
 /// Uses `IUIAutomation::GetFocusedElement` and tries (in order):
@@ -138,24 +826,28 @@ pub fn get_element_at_point(x: f64, y: f64) -> Option<ElementInfo> {
 /// 4. `IUIAutomationLegacyIAccessiblePattern` (older / MSAA-bridged controls)
 #[cfg(target_os = "windows")]
 pub fn get_focused_field_value() -> Option<FocusedFieldValue> {
-    use windows::Win32::System::Com::{
-        CoCreateInstance, CoInitializeEx, CLSCTX_INPROC_SERVER, COINIT_MULTITHREADED,
-    };
+    with_uia(uia_focused_field_value)
+}
+
+#[cfg(target_os = "windows")]
+fn uia_focused_field_value(
+    automation: &windows::Win32::UI::Accessibility::IUIAutomation,
+) -> Option<FocusedFieldValue> {
     use windows::Win32::UI::Accessibility::{
-        CUIAutomation, IUIAutomation, IUIAutomationLegacyIAccessiblePattern,
-        IUIAutomationTextPattern, IUIAutomationValuePattern, UIA_LegacyIAccessiblePatternId,
-        UIA_TextPatternId, UIA_ValuePatternId,
+        IUIAutomationLegacyIAccessiblePattern, IUIAutomationTextPattern, IUIAutomationValuePattern,
+        UIA_LegacyIAccessiblePatternId, UIA_TextPatternId, UIA_ValuePatternId,
     };
 
     unsafe {
-        let _ = CoInitializeEx(None, COINIT_MULTITHREADED);
-
-        let automation: IUIAutomation =
-            CoCreateInstance(&CUIAutomation, None, CLSCTX_INPROC_SERVER).ok()?;
         let element = automation.GetFocusedElement().ok()?;
 
         // Password short-circuit — never read the actual content.
-        if element.CurrentIsPassword().ok().map(|b| b.as_bool()).unwrap_or(false) {
+        if element
+            .CurrentIsPassword()
+            .ok()
+            .map(|b| b.as_bool())
+            .unwrap_or(false)
+        {
             return Some(FocusedFieldValue {
                 value: "[password]".into(),
                 source: "password",
@@ -179,8 +871,7 @@ pub fn get_focused_field_value() -> Option<FocusedFieldValue> {
         }
 
         // TextPattern — covers multi-line / rich edits where ValuePattern is not implemented.
-        if let Ok(tp) = element.GetCurrentPatternAs::<IUIAutomationTextPattern>(UIA_TextPatternId)
-        {
+        if let Ok(tp) = element.GetCurrentPatternAs::<IUIAutomationTextPattern>(UIA_TextPatternId) {
             if let Ok(range) = tp.DocumentRange() {
                 if let Ok(bstr) = range.GetText(-1) {
                     let s = bstr.to_string();
@@ -196,11 +887,9 @@ pub fn get_focused_field_value() -> Option<FocusedFieldValue> {
         }
 
         // LegacyIAccessiblePattern — older controls bridged through MSAA.
-        if let Ok(legacy) = element
-            .GetCurrentPatternAs::<IUIAutomationLegacyIAccessiblePattern>(
-                UIA_LegacyIAccessiblePatternId,
-            )
-        {
+        if let Ok(legacy) = element.GetCurrentPatternAs::<IUIAutomationLegacyIAccessiblePattern>(
+            UIA_LegacyIAccessiblePatternId,
+        ) {
             if let Ok(bstr) = legacy.CurrentValue() {
                 let s = bstr.to_string();
                 if !s.is_empty() {
@@ -220,8 +909,11 @@ pub fn get_focused_field_value() -> Option<FocusedFieldValue> {
 // macOS implementation using Accessibility API
 #[cfg(target_os = "macos")]
 pub fn get_element_at_point(x: f64, y: f64) -> Option<ElementInfo> {
-    use core_foundation::base::{CFRelease, CFTypeRef, TCFType};
+    use core_foundation::base::{CFRelease, CFType, CFTypeRef, TCFType};
+    use core_foundation::boolean::CFBoolean;
+    use core_foundation::number::CFNumber;
     use core_foundation::string::{CFString, CFStringRef};
+    use core_foundation::url::{CFURLRef, CFURL};
     use std::ptr;
 
     // AX error code for success
@@ -247,6 +939,21 @@ pub fn get_element_at_point(x: f64, y: f64) -> Option<ElementInfo> {
                 attribute: CFStringRef,
                 value: *mut CFTypeRef,
             ) -> i32;
+            fn AXValueGetValue(
+                value: CFTypeRef,
+                the_type: u32,
+                value_ptr: *mut std::ffi::c_void,
+            ) -> u8;
+        }
+
+        // AXValueType constants and the CGPoint / CGSize layouts they fill.
+        const K_AX_VALUE_CG_POINT_TYPE: u32 = 1;
+        const K_AX_VALUE_CG_SIZE_TYPE: u32 = 2;
+        #[repr(C)]
+        #[derive(Default)]
+        struct CgPair {
+            a: f64,
+            b: f64,
         }
 
         let system_wide = AXUIElementCreateSystemWide();
@@ -287,6 +994,7 @@ pub fn get_element_at_point(x: f64, y: f64) -> Option<ElementInfo> {
 
         // Get role (element type)
         let role = get_string_attr(element_at_pos, "AXRole").unwrap_or_default();
+        let role_raw = role.clone();
         // Convert AX role to human-readable type
         let element_type = match role.as_str() {
             "AXButton" => "Button".to_string(),
@@ -324,11 +1032,96 @@ pub fn get_element_at_point(x: f64, y: f64) -> Option<ElementInfo> {
             }
         };
 
-        // Get value
-        let value = get_string_attr(element_at_pos, "AXValue");
+        // Boolean attributes arrive as CFBoolean, or as CFNumber for some
+        // controls (a checkbox's AXValue is 0, 1 or 2 for mixed).
+        let get_number_attr = |attr_name: &str| -> Option<i64> {
+            let attr = cf_string(attr_name);
+            let mut value: CFTypeRef = ptr::null();
+            let result = AXUIElementCopyAttributeValue(
+                element_at_pos,
+                attr.as_concrete_TypeRef(),
+                &mut value,
+            );
+            if result != K_AX_ERROR_SUCCESS || value.is_null() {
+                return None;
+            }
+            let value = CFType::wrap_under_create_rule(value);
+            value
+                .downcast::<CFBoolean>()
+                .map(|b| bool::from(b) as i64)
+                .or_else(|| value.downcast::<CFNumber>().and_then(|n| n.to_i64()))
+        };
 
-        // Walk up the element tree to find the app name
+        // Get value. Checkbox and radio values are numbers, not strings.
+        let value = match role_raw.as_str() {
+            "AXCheckBox" | "AXRadioButton" | "AXSwitch" => {
+                get_number_attr("AXValue").map(|state| {
+                    match state {
+                        0 => "unchecked",
+                        1 => "checked",
+                        _ => "mixed",
+                    }
+                    .to_string()
+                })
+            }
+            _ => get_string_attr(element_at_pos, "AXValue"),
+        };
+        let expanded = get_number_attr("AXExpanded").map(|state| state != 0);
+
+        let automation_id = get_string_attr(element_at_pos, "AXIdentifier").and_then(non_empty);
+        let class_name = non_empty(role_raw.clone());
+
+        // AXPosition / AXSize are AXValue-wrapped CGPoint / CGSize.
+        let get_pair_attr = |attr_name: &str, value_type: u32| -> Option<CgPair> {
+            let attr = cf_string(attr_name);
+            let mut value: CFTypeRef = ptr::null();
+            let result = AXUIElementCopyAttributeValue(
+                element_at_pos,
+                attr.as_concrete_TypeRef(),
+                &mut value,
+            );
+            if result != K_AX_ERROR_SUCCESS || value.is_null() {
+                return None;
+            }
+            let mut pair = CgPair::default();
+            let ok = AXValueGetValue(value, value_type, &mut pair as *mut CgPair as *mut _);
+            CFRelease(value);
+            (ok != 0).then_some(pair)
+        };
+        let bounds = match (
+            get_pair_attr("AXPosition", K_AX_VALUE_CG_POINT_TYPE),
+            get_pair_attr("AXSize", K_AX_VALUE_CG_SIZE_TYPE),
+        ) {
+            (Some(position), Some(size)) => Some(ElementBounds {
+                x: position.a.round() as i32,
+                y: position.b.round() as i32,
+                width: size.a.round() as i32,
+                height: size.b.round() as i32,
+            })
+            .filter(|b| b.width > 0 && b.height > 0),
+            _ => None,
+        };
+
+        // AXURL holds a CFURL rather than a string.
+        let get_url_attr = |element: *mut std::ffi::c_void| -> Option<String> {
+            let attr = cf_string("AXURL");
+            let mut value: CFTypeRef = ptr::null();
+            let result =
+                AXUIElementCopyAttributeValue(element, attr.as_concrete_TypeRef(), &mut value);
+            if result == K_AX_ERROR_SUCCESS && !value.is_null() {
+                let url = CFURL::wrap_under_create_rule(value as CFURLRef);
+                Some(url.get_string().to_string())
+            } else {
+                None
+            }
+        };
+
+        // Walk up the element tree to find the app name, and the enclosing
+        // AXWebArea (browser page) if there is one.
         let mut app_name: Option<String> = None;
+        let mut page_url: Option<String> = None;
+        let mut page_title: Option<String> = None;
+        let mut ancestor_path = Vec::new();
         let mut current_element = element_at_pos;
         for _ in 0..20 {
             // Get parent element
@@ -353,7 +1146,22 @@ pub fn get_element_at_point(x: f64, y: f64) -> Option<ElementInfo> {
 
             // Also check AXRoleDescription for top-level window/app
             if let Some(role) = get_string_attr(parent_value as *mut std::ffi::c_void, "AXRole") {
-                if role == "AXApplication" {
+                if role == "AXWebArea" && page_url.is_none() {
+                    let web_area = parent_value as *mut std::ffi::c_void;
+                    page_url = get_url_attr(web_area).filter(|url| !url.is_empty());
+                    page_title = get_string_attr(web_area, "AXTitle")
+                        .or_else(|| get_string_attr(web_area, "AXDescription"))
+                        .filter(|title| !title.is_empty());
+                }
+                if role != "AXApplication" && ancestor_path.len() < MAX_PATH_SEGMENTS {
+                    let parent = parent_value as *mut std::ffi::c_void;
+                    ancestor_path.push(ElementPathSegment {
+                        name: get_string_attr(parent, "AXTitle").and_then(non_empty),
+                        automation_id: get_string_attr(parent, "AXIdentifier").and_then(non_empty),
+                        class_name: None,
+                        role,
+                    });
+                } else if role == "AXApplication" {
                     // Found the application - get its title
                     if let Some(title) =
                         get_string_attr(parent_value as *mut std::ffi::c_void, "AXTitle")
@@ -379,16 +1187,231 @@ pub fn get_element_at_point(x: f64, y: f64) -> Option<ElementInfo> {
             CFRelease(current_element as *const _);
         }
         CFRelease(element_at_pos as *const _);
+        ancestor_path.reverse();
 
         Some(ElementInfo {
             name,
             element_type,
             value,
             app_name,
+            bounds,
+            page_url,
+            page_title,
+            automation_id,
+            class_name,
+            ancestor_path,
+            expanded,
         })
     }
 }
 
+/// macOS implementation of `get_focused_element`: hit-tests the centre of
+/// the focused element, which yields the same element for ordinary fields
+/// and reuses the full attribute extraction of `get_element_at_point`.
+#[cfg(target_os = "macos")]
+pub fn get_focused_element() -> Option<ElementInfo> {
+    use core_foundation::base::{CFRelease, CFTypeRef, TCFType};
+    use core_foundation::string::{CFString, CFStringRef};
+    use std::ptr;
+
+    const K_AX_ERROR_SUCCESS: i32 = 0;
+    const K_AX_VALUE_CG_POINT_TYPE: u32 = 1;
+    const K_AX_VALUE_CG_SIZE_TYPE: u32 = 2;
+
+    #[repr(C)]
+    #[derive(Default)]
+    struct CgPair {
+        a: f64,
+        b: f64,
+    }
+
+    let center = unsafe {
+        #[link(name = "ApplicationServices", kind = "framework")]
+        extern "C" {
+            fn AXUIElementCreateSystemWide() -> *mut std::ffi::c_void;
+            fn AXUIElementCopyAttributeValue(
+                element: *mut std::ffi::c_void,
+                attribute: CFStringRef,
+                value: *mut CFTypeRef,
+            ) -> i32;
+            fn AXValueGetValue(
+                value: CFTypeRef,
+                the_type: u32,
+                value_ptr: *mut std::ffi::c_void,
+            ) -> u8;
+        }
+
+        let copy_attr = |element: *mut std::ffi::c_void, name: &str| -> Option<CFTypeRef> {
+            let attr = CFString::new(name);
+            let mut value: CFTypeRef = ptr::null();
+            let result =
+                AXUIElementCopyAttributeValue(element, attr.as_concrete_TypeRef(), &mut value);
+            (result == K_AX_ERROR_SUCCESS && !value.is_null()).then_some(value)
+        };
+        let get_pair = |element: *mut std::ffi::c_void, name: &str, value_type: u32| {
+            let value = copy_attr(element, name)?;
+            let mut pair = CgPair::default();
+            let ok = AXValueGetValue(value, value_type, &mut pair as *mut CgPair as *mut _);
+            CFRelease(value);
+            (ok != 0).then_some(pair)
+        };
+
+        let system_wide = AXUIElementCreateSystemWide();
+        if system_wide.is_null() {
+            return None;
+        }
+        let focused = copy_attr(system_wide, "AXFocusedUIElement");
+        CFRelease(system_wide as *const _);
+        let focused = focused? as *mut std::ffi::c_void;
+
+        let position = get_pair(focused, "AXPosition", K_AX_VALUE_CG_POINT_TYPE);
+        let size = get_pair(focused, "AXSize", K_AX_VALUE_CG_SIZE_TYPE);
+        CFRelease(focused as *const _);
+        let (position, size) = (position?, size?);
+        (position.a + size.a / 2.0, position.b + size.b / 2.0)
+    };
+
+    get_element_at_point(center.0, center.1)
+}
+
+/// macOS implementation of `get_subtree_at_point`.
+#[cfg(target_os = "macos")]
+pub fn get_subtree_at_point(x: f64, y: f64) -> Option<AxNode> {
+    use core_foundation::array::CFArray;
+    use core_foundation::base::{CFType, CFTypeRef, TCFType};
+    use core_foundation::string::{CFString, CFStringRef};
+    use std::ffi::c_void;
+    use std::ptr;
+
+    const K_AX_ERROR_SUCCESS: i32 = 0;
+    const K_AX_VALUE_CG_POINT_TYPE: u32 = 1;
+    const K_AX_VALUE_CG_SIZE_TYPE: u32 = 2;
+
+    #[repr(C)]
+    #[derive(Default)]
+    struct CgPair {
+        a: f64,
+        b: f64,
+    }
+
+    #[link(name = "ApplicationServices", kind = "framework")]
+    extern "C" {
+        fn AXUIElementCreateSystemWide() -> *mut c_void;
+        fn AXUIElementCopyElementAtPosition(
+            element: *mut c_void,
+            x: f32,
+            y: f32,
+            element_at_position: *mut *mut c_void,
+        ) -> i32;
+        fn AXUIElementCopyAttributeValue(
+            element: *mut c_void,
+            attribute: CFStringRef,
+            value: *mut CFTypeRef,
+        ) -> i32;
+        fn AXValueGetValue(value: CFTypeRef, the_type: u32, value_ptr: *mut c_void) -> u8;
+    }
+
+    unsafe fn copy_attr(element: &CFType, name: &str) -> Option<CFType> {
+        let attr = CFString::new(name);
+        let mut value: CFTypeRef = ptr::null();
+        let result = AXUIElementCopyAttributeValue(
+            element.as_CFTypeRef() as *mut c_void,
+            attr.as_concrete_TypeRef(),
+            &mut value,
+        );
+        (result == K_AX_ERROR_SUCCESS && !value.is_null())
+            .then(|| CFType::wrap_under_create_rule(value))
+    }
+
+    unsafe fn string_attr(element: &CFType, name: &str) -> Option<String> {
+        copy_attr(element, name)?
+            .downcast::<CFString>()
+            .map(|s| s.to_string())
+            .and_then(non_empty)
+    }
+
+    unsafe fn pair_attr(element: &CFType, name: &str, value_type: u32) -> Option<CgPair> {
+        let value = copy_attr(element, name)?;
+        let mut pair = CgPair::default();
+        let ok = AXValueGetValue(
+            value.as_CFTypeRef(),
+            value_type,
+            &mut pair as *mut CgPair as *mut _,
+        );
+        (ok != 0).then_some(pair)
+    }
+
+    unsafe fn snapshot(
+        element: &CFType,
+        target: &CFType,
+        depth: usize,
+        budget: &mut usize,
+    ) -> AxNode {
+        *budget = budget.saturating_sub(1);
+        let bounds = match (
+            pair_attr(element, "AXPosition", K_AX_VALUE_CG_POINT_TYPE),
+            pair_attr(element, "AXSize", K_AX_VALUE_CG_SIZE_TYPE),
+        ) {
+            (Some(position), Some(size)) => Some(ElementBounds {
+                x: position.a.round() as i32,
+                y: position.b.round() as i32,
+                width: size.a.round() as i32,
+                height: size.b.round() as i32,
+            })
+            .filter(|b| b.width > 0 && b.height > 0),
+            _ => None,
+        };
+        let mut node = AxNode {
+            role: string_attr(element, "AXRole").unwrap_or_default(),
+            name: string_attr(element, "AXTitle").or_else(|| string_attr(element, "AXDescription")),
+            automation_id: string_attr(element, "AXIdentifier"),
+            class_name: string_attr(element, "AXSubrole"),
+            bounds,
+            target: element == target,
+            children: Vec::new(),
+        };
+        if depth == 0 {
+            return node;
+        }
+        let Some(children) =
+            copy_attr(element, "AXChildren").and_then(|value| value.downcast::<CFArray>())
+        else {
+            return node;
+        };
+        for child in children.iter() {
+            if *budget == 0 || node.children.len() >= SUBTREE_MAX_CHILDREN {
+                break;
+            }
+            let child = CFType::wrap_under_get_rule(*child as CFTypeRef);
+            node.children
+                .push(snapshot(&child, target, depth - 1, budget));
+        }
+        node
+    }
+
+    unsafe {
+        let system_wide = AXUIElementCreateSystemWide();
+        if system_wide.is_null() {
+            return None;
+        }
+        let system_wide = CFType::wrap_under_create_rule(system_wide as CFTypeRef);
+        let mut element: *mut c_void = ptr::null_mut();
+        let result = AXUIElementCopyElementAtPosition(
+            system_wide.as_CFTypeRef() as *mut c_void,
+            x as f32,
+            y as f32,
+            &mut element,
+        );
+        if result != K_AX_ERROR_SUCCESS || element.is_null() {
+            return None;
+        }
+        let element = CFType::wrap_under_create_rule(element as CFTypeRef);
+        let root = copy_attr(&element, "AXParent").unwrap_or_else(|| element.clone());
+        let mut budget = SUBTREE_MAX_NODES;
+        Some(snapshot(&root, &element, SUBTREE_DEPTH, &mut budget))
+    }
+}
+
 /// macOS implementation of `get_focused_field_value`.
 ///
 /// Walks: system-wide → focused application → focused UI element, then reads
@@ -478,48 +1501,467 @@ pub fn get_focused_field_value() -> Option<FocusedFieldValue> {
     }
 }
 
-// Linux implementation using AT-SPI
+/// Shared AT-SPI bus connection for the Linux lookups. atspi/zbus need an
+/// async executor while the recorder threads are synchronous, so the
+/// connection lives on a dedicated single-worker tokio runtime and callers
+/// `block_on` it. Connecting costs several bus round-trips, so it is done
+/// once and reused; a failed attempt (no a11y bus yet) is retried next call.
 #[cfg(target_os = "linux")]
-pub fn get_element_at_point(x: f64, y: f64) -> Option<ElementInfo> {
-    // AT-SPI requires async runtime, simplified sync wrapper
-    use std::process::Command;
-
-    // Use gdbus or similar to query AT-SPI
-    // This is a placeholder - full implementation would use atspi crate
-    let output = Command::new("gdbus")
-        .args([
-            "call",
-            "--session",
-            "--dest=org.a11y.atspi.Registry",
-            "--object-path=/org/a11y/atspi/accessible/root",
-            "--method=org.a11y.atspi.Component.GetAccessibleAtPoint",
-            &format!("{}", x as i32),
-            &format!("{}", y as i32),
-            "0", // CoordType: screen
-        ])
-        .output()
+struct AtspiBus {
+    runtime: tokio::runtime::Runtime,
+    conn: atspi::AccessibilityConnection,
+}
+
+#[cfg(target_os = "linux")]
+fn atspi_bus() -> Option<&'static AtspiBus> {
+    use std::sync::Mutex;
+
+    static BUS: Mutex<Option<&'static AtspiBus>> = Mutex::new(None);
+
+    let mut bus = BUS.lock().ok()?;
+    if bus.is_none() {
+        let runtime = tokio::runtime::Builder::new_multi_thread()
+            .worker_threads(1)
+            .enable_all()
+            .build()
+            .ok()?;
+        let conn = runtime
+            .block_on(atspi::AccessibilityConnection::new())
+            .ok()?;
+        *bus = Some(Box::leak(Box::new(AtspiBus { runtime, conn })));
+    }
+    *bus
+}
+
+/// Component interface of the same object as `node` (extents, hit testing).
+#[cfg(target_os = "linux")]
+async fn atspi_component(
+    node: &atspi::proxy::accessible::AccessibleProxy<'_>,
+) -> Option<atspi::proxy::component::ComponentProxy<'static>> {
+    atspi::proxy::component::ComponentProxy::builder(node.inner().connection())
+        .destination(node.inner().destination().to_owned())
+        .ok()?
+        .path(node.inner().path().to_owned())
+        .ok()?
+        .build()
+        .await
+        .ok()
+}
+
+/// Full text of an element through the Text interface, capped.
+#[cfg(target_os = "linux")]
+async fn atspi_text(node: &atspi::proxy::accessible::AccessibleProxy<'_>) -> Option<String> {
+    use atspi::proxy::text::TextProxy;
+
+    let text_proxy = TextProxy::builder(node.inner().connection())
+        .destination(node.inner().destination().to_owned())
+        .ok()?
+        .path(node.inner().path().to_owned())
+        .ok()?
+        .build()
+        .await
         .ok()?;
+    let char_count = text_proxy.character_count().await.ok()?;
+    if char_count <= 0 {
+        return None;
+    }
+    let text = text_proxy.get_text(0, char_count).await.ok()?;
+    if text.is_empty() {
+        return None;
+    }
+    Some(cap_value(text, MAX_FIELD_VALUE_CHARS))
+}
 
-    if output.status.success() {
-        Some(ElementInfo {
-            name: "UI Element".to_string(),
-            element_type: "unknown".to_string(),
-            value: None,
-            app_name: None,
+/// Walk a bounded subtree of the accessibility tree looking for the
+/// element whose state set contains FOCUSED. Returns the first match.
+/// Bounded to avoid pathological wide/deep trees on Linux desktops.
+#[cfg(target_os = "linux")]
+#[allow(clippy::too_many_arguments)]
+async fn atspi_find_focused<'a>(
+    node: atspi::proxy::accessible::AccessibleProxy<'a>,
+    depth: u32,
+    max_depth: u32,
+    max_children: u32,
+    conn: &'a atspi::AccessibilityConnection,
+) -> Option<atspi::proxy::accessible::AccessibleProxy<'a>> {
+    use atspi::connection::P2P;
+    use atspi::State;
+
+    // Check this node's state set.
+    if let Ok(states) = node.get_state().await {
+        if states.contains(State::Focused) {
+            return Some(node);
+        }
+    }
+    if depth >= max_depth {
+        return None;
+    }
+    let children = node.get_children().await.ok()?;
+    for (i, child_ref) in children.into_iter().enumerate() {
+        if i as u32 >= max_children {
+            break;
+        }
+        let child = match conn.object_as_accessible(&child_ref).await {
+            Ok(p) => p,
+            Err(_) => continue,
+        };
+        if let Some(found) = Box::pin(atspi_find_focused(
+            child,
+            depth + 1,
+            max_depth,
+            max_children,
+            conn,
+        ))
+        .await
+        {
+            return Some(found);
+        }
+    }
+    None
+}
+
+/// `DocURL` attribute of a web document, through the Document interface.
+#[cfg(target_os = "linux")]
+async fn atspi_document_url(
+    node: &atspi::proxy::accessible::AccessibleProxy<'_>,
+) -> Option<String> {
+    use atspi::proxy::document::DocumentProxy;
+
+    let document = DocumentProxy::builder(node.inner().connection())
+        .destination(node.inner().destination().to_owned())
+        .ok()?
+        .path(node.inner().path().to_owned())
+        .ok()?
+        .build()
+        .await
+        .ok()?;
+    document
+        .get_attribute_value("DocURL")
+        .await
+        .ok()
+        .filter(|url| !url.is_empty())
+}
+
+/// Name, type, value, bounds, selectors and browser page of an accessible.
+#[cfg(target_os = "linux")]
+async fn atspi_describe<'a>(
+    conn: &'a atspi::AccessibilityConnection,
+    node: atspi::proxy::accessible::AccessibleProxy<'a>,
+    component: atspi::proxy::component::ComponentProxy<'static>,
+    app_name: String,
+) -> ElementInfo {
+    use atspi::connection::P2P;
+    use atspi::{CoordType, State};
+
+    const MAX_DEPTH: usize = 32;
+
+    let name = node.name().await.unwrap_or_default();
+    let role = node.get_role_name().await.unwrap_or_default();
+    let element_type = node
+        .get_localized_role_name()
+        .await
+        .unwrap_or_else(|_| role.clone());
+    let states = node.get_state().await.ok();
+    let has_state = |state: State| states.as_ref().is_some_and(|s| s.contains(state));
+
+    let value = match role.as_str() {
+        "password text" => None,
+        "check box" | "toggle button" | "radio button" | "check menu item" | "radio menu item" => {
+            Some(
+                if has_state(State::Indeterminate) {
+                    "mixed"
+                } else if has_state(State::Checked) {
+                    "checked"
+                } else {
+                    "unchecked"
+                }
+                .to_string(),
+            )
+        }
+        _ if has_state(State::Selectable) => Some(
+            if has_state(State::Selected) {
+                "selected"
+            } else {
+                "not selected"
+            }
+            .to_string(),
+        ),
+        _ if has_state(State::Editable) => atspi_text(&node).await,
+        _ => None,
+    };
+    let expanded = has_state(State::Expandable).then(|| has_state(State::Expanded));
+
+    let bounds = component
+        .get_extents(CoordType::Screen)
+        .await
+        .ok()
+        .map(|(x, y, width, height)| ElementBounds {
+            x,
+            y,
+            width,
+            height,
         })
-    } else {
-        None
+        .filter(|b| b.width > 0 && b.height > 0);
+
+    let automation_id = node.accessible_id().await.ok().and_then(non_empty);
+    let class_name = non_empty(role.clone());
+
+    // Walk up to the application, recording the ancestor path. The
+    // nearest "document web" ancestor is the browser page: it carries the
+    // URL as its DocURL document attribute and the tab title as its name.
+    let (mut page_url, mut page_title) = (None, None);
+    let mut ancestor_path = Vec::new();
+    let mut ancestor = node;
+    for _ in 0..MAX_DEPTH {
+        let Ok(parent_ref) = ancestor.parent().await else {
+            break;
+        };
+        let Ok(parent) = conn.object_as_accessible(&parent_ref).await else {
+            break;
+        };
+        let parent_role = parent.get_role_name().await.unwrap_or_default();
+        if parent_role.is_empty() || parent_role == "application" {
+            break;
+        }
+        if parent_role == "document web" && page_url.is_none() {
+            page_title = parent.name().await.ok().filter(|t| !t.is_empty());
+            page_url = atspi_document_url(&parent).await;
+        }
+        if ancestor_path.len() < MAX_PATH_SEGMENTS {
+            ancestor_path.push(ElementPathSegment {
+                name: parent.name().await.ok().and_then(non_empty),
+                automation_id: parent.accessible_id().await.ok().and_then(non_empty),
+                class_name: None,
+                role: parent_role,
+            });
+        }
+        ancestor = parent;
+    }
+    ancestor_path.reverse();
+
+    ElementInfo {
+        name,
+        element_type,
+        value,
+        app_name: Some(app_name).filter(|n| !n.is_empty()),
+        bounds,
+        page_url,
+        page_title,
+        automation_id,
+        class_name,
+        ancestor_path,
+        expanded,
+    }
+}
+
+/// Deepest accessible at a screen point, with its Component interface and
+/// its application's name.
+///
+/// AT-SPI has no desktop-wide hit test, so we find the application window
+/// containing the point (preferring the active one) and then descend with
+/// `Component.GetAccessibleAtPoint` until it stops returning a deeper child.
+#[cfg(target_os = "linux")]
+async fn atspi_accessible_at_point(
+    conn: &atspi::AccessibilityConnection,
+    x: i32,
+    y: i32,
+) -> Option<(
+    atspi::proxy::accessible::AccessibleProxy<'_>,
+    atspi::proxy::component::ComponentProxy<'static>,
+    String,
+)> {
+    use atspi::connection::P2P;
+    use atspi::proxy::accessible::AccessibleProxy;
+    use atspi::{CoordType, State};
+
+    const MAX_APPS: usize = 64;
+    const MAX_WINDOWS: usize = 32;
+    const MAX_DEPTH: usize = 32;
+
+    let root = conn.root_accessible_on_registry().await.ok()?;
+    let apps = root.get_children().await.ok()?;
+
+    // (window, app name, is active) for every window containing the point.
+    let mut candidates: Vec<(AccessibleProxy<'_>, String, bool)> = Vec::new();
+    for app_ref in apps.into_iter().take(MAX_APPS) {
+        let Ok(app) = conn.object_as_accessible(&app_ref).await else {
+            continue;
+        };
+        let Ok(windows) = app.get_children().await else {
+            continue;
+        };
+        let app_name = app.name().await.unwrap_or_default();
+        for window_ref in windows.into_iter().take(MAX_WINDOWS) {
+            let Ok(window) = conn.object_as_accessible(&window_ref).await else {
+                continue;
+            };
+            let Ok(states) = window.get_state().await else {
+                continue;
+            };
+            if !states.contains(State::Showing) {
+                continue;
+            }
+            let Some(component) = atspi_component(&window).await else {
+                continue;
+            };
+            if component
+                .contains(x, y, CoordType::Screen)
+                .await
+                .unwrap_or(false)
+            {
+                candidates.push((window, app_name.clone(), states.contains(State::Active)));
+            }
+        }
+    }
+    let index = candidates
+        .iter()
+        .position(|(_, _, active)| *active)
+        .unwrap_or(0);
+    if candidates.is_empty() {
+        return None;
+    }
+    let (mut node, app_name, _) = candidates.swap_remove(index);
+
+    Some((node, component, app_name))
+}
+
+// Linux implementation using AT-SPI
+#[cfg(target_os = "linux")]
+pub fn get_element_at_point(x: f64, y: f64) -> Option<ElementInfo> {
+    use atspi::AccessibilityConnection;
+
+    async fn run(conn: &AccessibilityConnection, x: i32, y: i32) -> Option<ElementInfo> {
+        let (node, component, app_name) = atspi_accessible_at_point(conn, x, y).await?;
+        Some(atspi_describe(conn, node, component, app_name).await)
+    }
+
+    let bus = atspi_bus()?;
+    bus.runtime
+        .block_on(run(&bus.conn, x.round() as i32, y.round() as i32))
+}
+
+/// Bus name and object path, which together identify an accessible.
+#[cfg(target_os = "linux")]
+fn atspi_key(node: &atspi::proxy::accessible::AccessibleProxy<'_>) -> (String, String) {
+    (
+        node.inner().destination().to_string(),
+        node.inner().path().to_string(),
+    )
+}
+
+/// Snapshot `node` and its descendants down to `depth` levels, spending one
+/// unit of `budget` per node. `target` is the `atspi_key` of the element the
+/// snapshot is taken around.
+#[cfg(target_os = "linux")]
+async fn atspi_subtree(
+    conn: &atspi::AccessibilityConnection,
+    node: atspi::proxy::accessible::AccessibleProxy<'_>,
+    target: &(String, String),
+    depth: usize,
+    budget: &mut usize,
+) -> AxNode {
+    use atspi::connection::P2P;
+    use atspi::CoordType;
+
+    *budget = budget.saturating_sub(1);
+    let bounds = match atspi_component(&node).await {
+        Some(component) => component
+            .get_extents(CoordType::Screen)
+            .await
+            .ok()
+            .map(|(x, y, width, height)| ElementBounds {
+                x,
+                y,
+                width,
+                height,
+            })
+            .filter(|b| b.width > 0 && b.height > 0),
+        None => None,
+    };
+    let mut snapshot = AxNode {
+        role: node.get_role_name().await.unwrap_or_default(),
+        name: node.name().await.ok().and_then(non_empty),
+        automation_id: node.accessible_id().await.ok().and_then(non_empty),
+        class_name: None,
+        bounds,
+        target: atspi_key(&node) == *target,
+        children: Vec::new(),
+    };
+    if depth == 0 {
+        return snapshot;
+    }
+    let Ok(children) = node.get_children().await else {
+        return snapshot;
+    };
+    for child_ref in children.into_iter().take(SUBTREE_MAX_CHILDREN) {
+        if *budget == 0 {
+            break;
+        }
+        let Ok(child) = conn.object_as_accessible(&child_ref).await else {
+            continue;
+        };
+        snapshot
+            .children
+            .push(Box::pin(atspi_subtree(conn, child, target, depth - 1, budget)).await);
+    }
+    snapshot
+}
+
+/// Linux implementation of `get_subtree_at_point`.
+#[cfg(target_os = "linux")]
+pub fn get_subtree_at_point(x: f64, y: f64) -> Option<AxNode> {
+    use atspi::connection::P2P;
+    use atspi::AccessibilityConnection;
+
+    async fn run(conn: &AccessibilityConnection, x: i32, y: i32) -> Option<AxNode> {
+        let (node, _, _) = atspi_accessible_at_point(conn, x, y).await?;
+        let target = atspi_key(&node);
+        let parent = match node.parent().await {
+            Ok(parent_ref) => conn.object_as_accessible(&parent_ref).await.ok(),
+            Err(_) => None,
+        };
+        let mut budget = SUBTREE_MAX_NODES;
+        let root = parent.unwrap_or(node);
+        Some(atspi_subtree(conn, root, &target, SUBTREE_DEPTH, &mut budget).await)
+    }
+
+    let bus = atspi_bus()?;
+    bus.runtime
+        .block_on(run(&bus.conn, x.round() as i32, y.round() as i32))
+}
+
+/// Linux implementation of `get_focused_element`.
+#[cfg(target_os = "linux")]
+pub fn get_focused_element() -> Option<ElementInfo> {
+    use atspi::connection::P2P;
+    use atspi::AccessibilityConnection;
+
+    async fn run(conn: &AccessibilityConnection) -> Option<ElementInfo> {
+        let root = conn.root_accessible_on_registry().await.ok()?;
+        let focused = atspi_find_focused(root, 0, 12, 64, conn).await?;
+        let component = atspi_component(&focused).await?;
+        let app_name = match focused.get_application().await {
+            Ok(app_ref) => match conn.object_as_accessible(&app_ref).await {
+                Ok(app) => app.name().await.unwrap_or_default(),
+                Err(_) => String::new(),
+            },
+            Err(_) => String::new(),
+        };
+        Some(atspi_describe(conn, focused, component, app_name).await)
     }
+
+    let bus = atspi_bus()?;
+    bus.runtime.block_on(run(&bus.conn))
 }
 
 /// Linux implementation of `get_focused_field_value`.
 ///
-/// Embeds a current-thread tokio runtime, opens an AT-SPI connection, walks
-/// the accessibility tree from the registry root looking for an element
-/// whose state set includes FOCUSED, and reads its text via the Text
-/// interface. On any failure (no bus, no focused element, no text interface,
-/// permission denied) returns `None` so the recorder falls back to the raw
-/// keystroke buffer.
+/// Uses the shared AT-SPI connection (see `atspi_bus`), walks the
+/// accessibility tree from the registry root looking for an element whose
+/// state set includes FOCUSED, and reads its text via the Text interface. On
+/// any failure (no bus, no focused element, no text interface, permission
+/// denied) returns `None` so the recorder falls back to the raw keystroke
+/// buffer.
 ///
 /// Limitations:
 /// - Wayland with weak AT-SPI support (sway, river, hyprland without
@@ -531,59 +1973,11 @@ pub fn get_element_at_point(x: f64, y: f64) -> Option<ElementInfo> {
 /// - Password fields surface as `password text` role; we detect and redact.
 #[cfg(target_os = "linux")]
 pub fn get_focused_field_value() -> Option<FocusedFieldValue> {
-    use atspi::connection::P2P;
-    use atspi::proxy::accessible::AccessibleProxy;
-    use atspi::proxy::text::TextProxy;
-    use atspi::{AccessibilityConnection, State};
-
-    /// Walk a bounded subtree of the accessibility tree looking for the
-    /// element whose state set contains FOCUSED. Returns the first match.
-    /// Bounded to avoid pathological wide/deep trees on Linux desktops.
-    #[allow(clippy::too_many_arguments)]
-    async fn find_focused<'a>(
-        node: AccessibleProxy<'a>,
-        depth: u32,
-        max_depth: u32,
-        max_children: u32,
-        conn: &'a AccessibilityConnection,
-    ) -> Option<AccessibleProxy<'a>> {
-        // Check this node's state set.
-        if let Ok(states) = node.get_state().await {
-            if states.contains(State::Focused) {
-                return Some(node);
-            }
-        }
-        if depth >= max_depth {
-            return None;
-        }
-        let children = node.get_children().await.ok()?;
-        for (i, child_ref) in children.into_iter().enumerate() {
-            if i as u32 >= max_children {
-                break;
-            }
-            let child = match conn.object_as_accessible(&child_ref).await {
-                Ok(p) => p,
-                Err(_) => continue,
-            };
-            if let Some(found) = Box::pin(find_focused(
-                child,
-                depth + 1,
-                max_depth,
-                max_children,
-                conn,
-            ))
-            .await
-            {
-                return Some(found);
-            }
-        }
-        None
-    }
+    use atspi::AccessibilityConnection;
 
-    async fn run() -> Option<FocusedFieldValue> {
-        let conn = AccessibilityConnection::new().await.ok()?;
+    async fn run(conn: &AccessibilityConnection) -> Option<FocusedFieldValue> {
         let root = conn.root_accessible_on_registry().await.ok()?;
-        let focused = find_focused(root, 0, 12, 64, &conn).await?;
+        let focused = atspi_find_focused(root, 0, 12, 64, conn).await?;
 
         // Password detection by role name. AT-SPI exposes "password text"
         // as the canonical role for secure entry widgets.
@@ -597,45 +1991,16 @@ pub fn get_focused_field_value() -> Option<FocusedFieldValue> {
             }
         }
 
-        // Read text via the Text interface. The accessible's path/destination
-        // is reused to construct a TextProxy.
-        let dest = focused.inner().destination();
-        let path = focused.inner().path();
-        let text_proxy = TextProxy::builder(focused.inner().connection())
-            .destination(dest)
-            .ok()?
-            .path(path)
-            .ok()?
-            .build()
-            .await
-            .ok()?;
-        let char_count = text_proxy.character_count().await.ok()?;
-        if char_count <= 0 {
-            return None;
-        }
-        let text = text_proxy.get_text(0, char_count).await.ok()?;
-        if text.is_empty() {
-            return None;
-        }
+        let value = atspi_text(&focused).await?;
         Some(FocusedFieldValue {
-            value: cap_value(text, MAX_FIELD_VALUE_CHARS),
+            value,
             source: "ax_value",
             is_password: false,
         })
     }
 
-    // Embed a current-thread tokio runtime. atspi/zbus require an async
-    // executor; the recorder thread is otherwise synchronous so we spin one
-    // up per call. Cost is ~1-5ms of overhead per flush, dominated by the
-    // bus round-trips that follow.
-    let runtime = match tokio::runtime::Builder::new_current_thread()
-        .enable_all()
-        .build()
-    {
-        Ok(rt) => rt,
-        Err(_) => return None,
-    };
-    runtime.block_on(run())
+    let bus = atspi_bus()?;
+    bus.runtime.block_on(run(&bus.conn))
 }
 
 // Fallback for other platforms
@@ -644,6 +2009,16 @@ pub fn get_element_at_point(_x: f64, _y: f64) -> Option<ElementInfo> {
     None
 }
 
+#[cfg(not(any(target_os = "windows", target_os = "macos", target_os = "linux")))]
+pub fn get_focused_element() -> Option<ElementInfo> {
+    None
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "macos", target_os = "linux")))]
+pub fn get_subtree_at_point(_x: f64, _y: f64) -> Option<AxNode> {
+    None
+}
+
 #[cfg(not(any(target_os = "windows", target_os = "macos", target_os = "linux")))]
 pub fn get_focused_field_value() -> Option<FocusedFieldValue> {
     None
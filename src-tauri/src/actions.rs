@@ -0,0 +1,196 @@
+//! Semantic labels for recorded clicks.
+//!
+//! A click is only "Click X" to the recorder, but the clicked control's role
+//! and its state before and after the click usually say what the user did:
+//! checked a box, opened a menu, switched tabs. `infer_click_action` turns
+//! that into an instruction such as `Expand "Advanced"` that exporters and
+//! the AI prompt can use instead of the generic wording, in the current
+//! locale.
+
+use crate::accessibility::ElementInfo;
+use crate::i18n::{self, fill};
+
+/// Role families the inference distinguishes. Platforms name roles
+/// differently (UIA localized control types, AX roles, AT-SPI role names),
+/// so `role_of` folds them together.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Role {
+    CheckBox,
+    RadioButton,
+    Tab,
+    Menu,
+    MenuItem,
+    ComboBox,
+    TreeItem,
+    Row,
+    ListItem,
+    Link,
+    Other,
+}
+
+/// "AXMenuBarItem" -> "menu bar item", "Check Box" -> "check box".
+fn role_words(role: &str) -> String {
+    let role = role.strip_prefix("AX").unwrap_or(role);
+    let mut words = String::new();
+    for (i, c) in role.chars().enumerate() {
+        if c.is_uppercase() && i > 0 && !words.ends_with(' ') {
+            words.push(' ');
+        }
+        words.extend(c.to_lowercase());
+    }
+    words.trim().to_string()
+}
+
+fn role_of(info: &ElementInfo) -> Role {
+    // The raw AX / AT-SPI role in `class_name` is more specific than the
+    // display type on macOS ("AXMenuBarItem" vs "Menu Bar Item").
+    let candidates = [info.element_type.as_str()]
+        .into_iter()
+        .chain(info.class_name.as_deref());
+    for candidate in candidates {
+        let role = match role_words(candidate).as_str() {
+            "check box" | "checkbox" | "toggle button" | "check menu item" | "switch" => {
+                Role::CheckBox
+            }
+            "radio button" | "radio menu item" => Role::RadioButton,
+            "tab" | "tab item" | "page tab" => Role::Tab,
+            "menu" | "menu bar item" => Role::Menu,
+            "menu item" => Role::MenuItem,
+            "combo box" | "pop up button" | "dropdown" => Role::ComboBox,
+            "tree item" | "tree view item" | "outline row" => Role::TreeItem,
+            "row" | "table row" | "data item" => Role::Row,
+            "list item" => Role::ListItem,
+            "link" | "hyperlink" => Role::Link,
+            _ => continue,
+        };
+        return role;
+    }
+    Role::Other
+}
+
+/// Whether the label for a click on `info` depends on the control's state
+/// after the click, so the recorder should read it again once the UI has
+/// settled.
+pub fn reads_state_after_click(info: &ElementInfo) -> bool {
+    matches!(
+        role_of(info),
+        Role::CheckBox | Role::Menu | Role::MenuItem | Role::ComboBox | Role::TreeItem
+    )
+}
+
+/// Instruction describing a click, from the clicked element as read when the
+/// click happened (`before`) and, if available, once the UI settled
+/// (`after`). `None` when nothing more specific than "Click X" applies.
+pub fn infer_click_action(
+    before: Option<&ElementInfo>,
+    after: Option<&ElementInfo>,
+) -> Option<String> {
+    let element = before.or(after)?;
+    // The second lookup describes whatever is under the pointer by then; a
+    // menu or dialog the click opened may cover the control.
+    let after = after.filter(|a| a.name == element.name && a.element_type == element.element_type);
+    let name = element.name.trim();
+    if name.is_empty() {
+        return None;
+    }
+
+    let before_value = before.and_then(|b| b.value.as_deref());
+    let after_value = after.and_then(|a| a.value.as_deref());
+    // Prefer the observed state after the click; otherwise assume the click
+    // flipped the state it had before.
+    let checked = match (after_value, before_value) {
+        (Some("checked"), _) => Some(true),
+        (Some("unchecked"), _) => Some(false),
+        (_, Some("checked")) => Some(false),
+        (_, Some("unchecked")) => Some(true),
+        _ => None,
+    };
+    let expanded = after
+        .and_then(|a| a.expanded)
+        .or_else(|| before.and_then(|b| b.expanded).map(|was| !was));
+
+    let text = i18n::current().catalog();
+    let template = match (role_of(element), expanded) {
+        (Role::CheckBox, _) => match checked? {
+            true => text.action_check,
+            false => text.action_uncheck,
+        },
+        (Role::RadioButton, _) => text.action_select_option,
+        (Role::Tab, _) => text.action_switch_tab,
+        (Role::Menu | Role::MenuItem, Some(true)) => text.action_open_menu,
+        (Role::Menu | Role::MenuItem, Some(false)) => text.action_close_menu,
+        (Role::MenuItem, None) => text.action_choose,
+        (Role::ComboBox, Some(true)) => text.action_open_drop_down,
+        (Role::TreeItem, Some(true)) => text.action_expand,
+        (Role::TreeItem, Some(false)) => text.action_collapse,
+        (Role::TreeItem | Role::Row | Role::ListItem, None) => text.action_select,
+        (Role::Link, _) => text.action_follow_link,
+        _ => return None,
+    };
+    Some(fill(template, &[("name", name)]))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn element(name: &str, element_type: &str) -> ElementInfo {
+        ElementInfo {
+            name: name.to_string(),
+            element_type: element_type.to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn infer_click_action_uses_state_after_click_when_same_control() {
+        let before = ElementInfo {
+            value: Some("unchecked".to_string()),
+            ..element("Remember me", "check box")
+        };
+        let after = ElementInfo {
+            value: Some("checked".to_string()),
+            ..element("Remember me", "check box")
+        };
+
+        assert_eq!(
+            infer_click_action(Some(&before), Some(&after)).as_deref(),
+            Some("Check \"Remember me\"")
+        );
+        // Without a second reading the state before the click is flipped.
+        assert_eq!(
+            infer_click_action(Some(&after), None).as_deref(),
+            Some("Uncheck \"Remember me\"")
+        );
+    }
+
+    #[test]
+    fn infer_click_action_ignores_a_different_element_after_click() {
+        let before = ElementInfo {
+            expanded: Some(false),
+            class_name: Some("AXMenuBarItem".to_string()),
+            ..element("File", "Menu Bar Item")
+        };
+        let popup = ElementInfo {
+            expanded: Some(false),
+            ..element("New Window", "menu item")
+        };
+
+        assert_eq!(
+            infer_click_action(Some(&before), Some(&popup)).as_deref(),
+            Some("Open the \"File\" menu")
+        );
+    }
+
+    #[test]
+    fn infer_click_action_leaves_plain_buttons_alone() {
+        assert_eq!(
+            infer_click_action(Some(&element("Save", "button")), None),
+            None
+        );
+        assert_eq!(
+            infer_click_action(Some(&element("Network", "tab item")), None).as_deref(),
+            Some("Switch to the \"Network\" tab")
+        );
+    }
+}
@@ -0,0 +1,586 @@
+//! Step descriptions and an introduction written by a language model from
+//! what the recorder captured.
+//!
+//! Unlike the editor's generation, which sends every screenshot, this sends
+//! the steps' metadata: the control's name and role, where it sits, the app,
+//! window and page, and the text read from the screen. Screenshots are only
+//! attached when `AiSettings::send_screenshots` is on. The reply streams back
+//! as `ai-generation-progress` events and the descriptions are saved to the
+//! steps, so it also works without the webview (the API, the CLI).
+//!
+//! OpenAI and Ollama are called through the chat completions API, Anthropic
+//! through its messages API. Each provider has its own base URL and model,
+//! and its API key is the credential `ai_api_key.<provider>`, falling back to
+//! the editor's `ai_api_key`.
+
+use crate::analytics;
+use crate::credentials;
+use crate::database::{is_text_only_step_type, RecordingWithSteps, Step};
+use crate::db_worker::DatabaseWorker;
+use crate::i18n::Locale;
+use crate::settings::SettingsState;
+use base64::{engine::general_purpose, Engine as _};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager};
+
+/// Recognised text sent per step, in characters
+const MAX_SCREEN_TEXT: usize = 600;
+const MAX_TOKENS: u32 = 4096;
+const ANTHROPIC_VERSION: &str = "2023-06-01";
+/// Credential the editor keeps its API key in
+const SHARED_KEY_CREDENTIAL: &str = "ai_api_key";
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AiProvider {
+    #[default]
+    OpenAi,
+    Anthropic,
+    Ollama,
+}
+
+impl AiProvider {
+    fn id(self) -> &'static str {
+        match self {
+            AiProvider::OpenAi => "openai",
+            AiProvider::Anthropic => "anthropic",
+            AiProvider::Ollama => "ollama",
+        }
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            AiProvider::OpenAi => "OpenAI",
+            AiProvider::Anthropic => "Anthropic",
+            AiProvider::Ollama => "Ollama",
+        }
+    }
+
+    fn default_base_url(self) -> &'static str {
+        match self {
+            AiProvider::OpenAi => "https://api.openai.com/v1",
+            AiProvider::Anthropic => "https://api.anthropic.com/v1",
+            AiProvider::Ollama => "http://localhost:11434/v1",
+        }
+    }
+
+    fn default_model(self) -> &'static str {
+        match self {
+            AiProvider::OpenAi => "gpt-4o-mini",
+            AiProvider::Anthropic => "claude-sonnet-4-20250514",
+            AiProvider::Ollama => "llama3.1",
+        }
+    }
+
+    fn requires_api_key(self) -> bool {
+        self != AiProvider::Ollama
+    }
+}
+
+/// A provider's endpoint and model; empty fields use the provider's default
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ProviderConfig {
+    pub base_url: String,
+    pub model: String,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AiSettings {
+    pub provider: AiProvider,
+    pub openai: ProviderConfig,
+    pub anthropic: ProviderConfig,
+    pub ollama: ProviderConfig,
+    /// Attach the steps' screenshots; off sends text only
+    pub send_screenshots: bool,
+}
+
+impl AiSettings {
+    /// The base URL and model to use for `provider`
+    fn endpoint(&self, provider: AiProvider) -> (String, String) {
+        let config = match provider {
+            AiProvider::OpenAi => &self.openai,
+            AiProvider::Anthropic => &self.anthropic,
+            AiProvider::Ollama => &self.ollama,
+        };
+        let or_default = |value: &str, default: &str| {
+            let value = value.trim();
+            (if value.is_empty() { default } else { value }).to_string()
+        };
+        (
+            or_default(&config.base_url, provider.default_base_url()),
+            or_default(&config.model, provider.default_model()),
+        )
+    }
+}
+
+fn api_key(provider: AiProvider) -> Result<Option<String>, String> {
+    let own = credentials::get(&format!("{}.{}", SHARED_KEY_CREDENTIAL, provider.id()))?;
+    match own {
+        Some(key) => Ok(Some(key)),
+        None => credentials::get(SHARED_KEY_CREDENTIAL),
+    }
+}
+
+/// What the model wrote, in the steps' order
+#[derive(Debug, Default, PartialEq)]
+struct Reply {
+    intro: String,
+    /// Per numbered step; `None` where the reply skipped one
+    steps: Vec<Option<String>>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct GeneratedStep {
+    pub step_id: String,
+    pub description: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct GeneratedDocumentation {
+    pub intro: String,
+    /// The descriptions saved, in step order
+    pub steps: Vec<GeneratedStep>,
+}
+
+#[derive(Clone, Serialize)]
+struct GenerationProgress<'a> {
+    recording_id: &'a str,
+    /// Text received since the last event
+    delta: &'a str,
+}
+
+fn system_prompt(locale: Locale) -> String {
+    format!(
+        "You write step-by-step software documentation. You get the steps a user \
+         recorded as the computer saw them: the control they clicked or typed into, \
+         where it is, the app, window or web page, and text read from the screen. \
+         Write a short introduction saying what the guide helps the reader do, then one \
+         clear, imperative instruction per step. Don't invent controls or values that \
+         aren't in the steps. Write in {}.\n\n\
+         Reply in exactly this format and nothing else:\n\n\
+         INTRO:\n<one paragraph>\n\nSTEP 1:\n<instruction>\n\nSTEP 2:\n<instruction>",
+        locale.english_name()
+    )
+}
+
+/// The steps the model describes, in order; headings and callouts are only
+/// context
+fn described_steps(recording: &RecordingWithSteps) -> Vec<&Step> {
+    recording
+        .steps
+        .iter()
+        .filter(|step| !is_text_only_step_type(&step.type_))
+        .collect()
+}
+
+fn step_prompt(number: usize, step: &Step) -> String {
+    let mut lines = vec![format!("STEP {} ({})", number, step.type_)];
+    let mut field = |label: &str, value: Option<&str>| {
+        if let Some(value) = value.map(str::trim).filter(|value| !value.is_empty()) {
+            lines.push(format!("{}: {}", label, value));
+        }
+    };
+    field("Current description", step.description.as_deref());
+    field("Action", step.action.as_deref());
+    field("Element", step.element_name.as_deref());
+    field("Element role", step.element_type.as_deref());
+    field("Location", step.breadcrumb.as_deref());
+    field("App", step.app_name.as_deref());
+    field("Window", step.window_title.as_deref());
+    field("Page title", step.page_title.as_deref());
+    field("Page address", step.page_url.as_deref());
+    let screen_text = step
+        .ocr_text
+        .as_deref()
+        .map(|text| text.chars().take(MAX_SCREEN_TEXT).collect::<String>());
+    field("Text on screen", screen_text.as_deref());
+    lines.join("\n")
+}
+
+/// The user message: the guide's name, then each step, with the headings
+/// and callouts between them for context
+fn user_prompt(recording: &RecordingWithSteps) -> String {
+    let mut sections = vec![format!("Guide: {}", recording.recording.name)];
+    let mut number = 0;
+    for step in &recording.steps {
+        if is_text_only_step_type(&step.type_) {
+            if let Some(text) = step.description.as_deref().or(step.title.as_deref()) {
+                sections.push(format!("({} between steps: {})", step.type_, text));
+            }
+            continue;
+        }
+        number += 1;
+        sections.push(step_prompt(number, step));
+    }
+    sections.join("\n\n")
+}
+
+/// A screenshot to attach, as its media type and base64 data
+fn screenshot(step: &Step) -> Option<(&'static str, String)> {
+    let path = step.screenshot_path.as_deref()?;
+    let bytes = std::fs::read(path)
+        .map_err(|e| tracing::warn!("Failed to read screenshot {}: {}", path, e))
+        .ok()?;
+    let media_type = match path
+        .rsplit('.')
+        .next()
+        .map(str::to_ascii_lowercase)
+        .as_deref()
+    {
+        Some("png") => "image/png",
+        Some("webp") => "image/webp",
+        _ => "image/jpeg",
+    };
+    Some((media_type, general_purpose::STANDARD.encode(bytes)))
+}
+
+fn request_body(
+    provider: AiProvider,
+    model: &str,
+    system: &str,
+    user: &str,
+    images: &[(&'static str, String)],
+) -> Value {
+    match provider {
+        AiProvider::Anthropic => {
+            let mut content = vec![json!({ "type": "text", "text": user })];
+            content.extend(images.iter().map(|(media_type, data)| {
+                json!({
+                    "type": "image",
+                    "source": { "type": "base64", "media_type": media_type, "data": data },
+                })
+            }));
+            json!({
+                "model": model,
+                "max_tokens": MAX_TOKENS,
+                "stream": true,
+                "system": system,
+                "messages": [{ "role": "user", "content": content }],
+            })
+        }
+        AiProvider::OpenAi | AiProvider::Ollama => {
+            let content = if images.is_empty() {
+                json!(user)
+            } else {
+                let mut parts = vec![json!({ "type": "text", "text": user })];
+                parts.extend(images.iter().map(|(media_type, data)| {
+                    json!({
+                        "type": "image_url",
+                        "image_url": { "url": format!("data:{};base64,{}", media_type, data) },
+                    })
+                }));
+                Value::Array(parts)
+            };
+            json!({
+                "model": model,
+                "stream": true,
+                "messages": [
+                    { "role": "system", "content": system },
+                    { "role": "user", "content": content },
+                ],
+            })
+        }
+    }
+}
+
+/// Text in one server-sent event's `data`, if it carries any
+fn delta_text(provider: AiProvider, data: &str) -> Option<String> {
+    let event: Value = serde_json::from_str(data).ok()?;
+    let text = match provider {
+        AiProvider::Anthropic => event
+            .get("delta")
+            .filter(|_| event["type"] == "content_block_delta")?
+            .get("text"),
+        AiProvider::OpenAi | AiProvider::Ollama => {
+            event.get("choices")?.get(0)?.get("delta")?.get("content")
+        }
+    };
+    text.and_then(Value::as_str)
+        .filter(|text| !text.is_empty())
+        .map(str::to_string)
+}
+
+/// Send the request and stream the reply, emitting each piece of text
+async fn stream_reply(
+    app: &AppHandle,
+    recording_id: &str,
+    provider: AiProvider,
+    base_url: &reqwest::Url,
+    api_key: Option<&str>,
+    body: &Value,
+) -> Result<String, String> {
+    let path = match provider {
+        AiProvider::Anthropic => "messages",
+        AiProvider::OpenAi | AiProvider::Ollama => "chat/completions",
+    };
+    let endpoint = crate::build_ai_endpoint(base_url, path)?;
+    let client = crate::ai_http_client(Duration::from_secs(300))?;
+    let mut request = client.post(endpoint).json(body);
+    if let Some(key) = api_key {
+        request = match provider {
+            AiProvider::Anthropic => request
+                .header("x-api-key", key)
+                .header("anthropic-version", ANTHROPIC_VERSION),
+            AiProvider::OpenAi | AiProvider::Ollama => request.bearer_auth(key),
+        };
+    }
+
+    let mut response = request
+        .send()
+        .await
+        .map_err(|e| crate::map_ai_transport_error(&e, base_url))?;
+    let status = response.status();
+    if !status.is_success() {
+        let body = response.text().await.unwrap_or_default();
+        tracing::error!(status = status.as_u16(), body = %body, "AI request failed");
+        if status == reqwest::StatusCode::UNAUTHORIZED {
+            return Err(format!(
+                "Authentication failed. Check your {} API key in Settings.",
+                provider.label()
+            ));
+        }
+        return Err(format!(
+            "AI request failed: {} - {}",
+            status,
+            body.chars().take(800).collect::<String>()
+        ));
+    }
+
+    let mut reply = String::new();
+    let mut pending: Vec<u8> = Vec::new();
+    while let Some(chunk) = response
+        .chunk()
+        .await
+        .map_err(|e| format!("The AI response was interrupted: {}", e))?
+    {
+        pending.extend_from_slice(&chunk);
+        // Events are split on line ends so multi-byte characters stay whole
+        while let Some(end) = pending.iter().position(|byte| *byte == b'\n') {
+            let line: Vec<u8> = pending.drain(..=end).collect();
+            let line = String::from_utf8_lossy(&line);
+            let Some(data) = line.trim().strip_prefix("data:") else {
+                continue;
+            };
+            if let Some(delta) = delta_text(provider, data.trim()) {
+                reply.push_str(&delta);
+                let _ = app.emit(
+                    "ai-generation-progress",
+                    GenerationProgress {
+                        recording_id,
+                        delta: &delta,
+                    },
+                );
+            }
+        }
+    }
+    Ok(reply)
+}
+
+/// Split a reply in the format `system_prompt` asks for
+fn parse_reply(reply: &str, step_count: usize) -> Reply {
+    // Reasoning models may think out loud before answering
+    let reply = reply
+        .rfind("</think>")
+        .map_or(reply, |end| &reply[end + "</think>".len()..]);
+
+    enum Section {
+        None,
+        Intro,
+        Step(usize),
+    }
+    let mut parsed = Reply {
+        intro: String::new(),
+        steps: vec![None; step_count],
+    };
+    let mut section = Section::None;
+    let mut text = String::new();
+    let finish = |section: &Section, text: &mut String, parsed: &mut Reply| {
+        let done = std::mem::take(text).trim().to_string();
+        if done.is_empty() {
+            return;
+        }
+        match section {
+            Section::Intro => parsed.intro = done,
+            Section::Step(index) => {
+                if let Some(step) = parsed.steps.get_mut(*index) {
+                    *step = Some(done);
+                }
+            }
+            Section::None => {}
+        }
+    };
+    for line in reply.lines() {
+        let marker = line.trim().trim_matches(|c| c == '*' || c == '#').trim();
+        let (label, rest) = marker.split_once(':').unwrap_or((marker, ""));
+        let label = label.trim().to_ascii_uppercase();
+        let next = if label == "INTRO" {
+            Some(Section::Intro)
+        } else {
+            label
+                .strip_prefix("STEP ")
+                .and_then(|number| number.trim().parse::<usize>().ok())
+                .filter(|number| *number >= 1 && marker.contains(':'))
+                .map(|number| Section::Step(number - 1))
+        };
+        match next {
+            Some(next) => {
+                finish(&section, &mut text, &mut parsed);
+                section = next;
+                text.push_str(rest.trim().trim_start_matches('*'));
+            }
+            None => {
+                text.push('\n');
+                text.push_str(line);
+            }
+        }
+    }
+    finish(&section, &mut text, &mut parsed);
+    parsed
+}
+
+/// Have the configured provider write an introduction and a description for
+/// each step of `recording_id`, streaming its reply as
+/// `ai-generation-progress` events. The descriptions replace the steps'
+/// current ones; the introduction is returned for the caller to place.
+#[tauri::command]
+pub async fn generate_step_descriptions(
+    app: AppHandle,
+    recording_id: String,
+) -> Result<GeneratedDocumentation, String> {
+    analytics::track("ai_generation");
+    let (settings, locale) = {
+        let settings = app.state::<SettingsState>();
+        let settings = settings.0.lock().unwrap();
+        (settings.ai.clone(), settings.locale)
+    };
+    let recording = {
+        let id = recording_id.clone();
+        app.state::<DatabaseWorker>()
+            .run(move |db| {
+                db.get_recording(&id)
+                    .map_err(|e| e.to_string())?
+                    .ok_or_else(|| format!("Recording not found: {}", id))
+            })
+            .await?
+    };
+    let steps = described_steps(&recording);
+    if steps.is_empty() {
+        return Err("This recording has no steps to describe".to_string());
+    }
+
+    let provider = settings.provider;
+    let (base_url, model) = settings.endpoint(provider);
+    let base_url = crate::validate_ai_base_url(&base_url)?;
+    let api_key = api_key(provider)?.filter(|key| !key.trim().is_empty());
+    if provider.requires_api_key() && api_key.is_none() {
+        return Err(format!("Add a {} API key in Settings", provider.label()));
+    }
+    let images: Vec<_> = if settings.send_screenshots {
+        steps.iter().filter_map(|step| screenshot(step)).collect()
+    } else {
+        Vec::new()
+    };
+    let body = request_body(
+        provider,
+        &model,
+        &system_prompt(locale),
+        &user_prompt(&recording),
+        &images,
+    );
+    tracing::info!(
+        provider = provider.id(),
+        model = %model,
+        steps = steps.len(),
+        screenshots = images.len(),
+        "Generating step descriptions"
+    );
+
+    let reply = stream_reply(
+        &app,
+        &recording_id,
+        provider,
+        &base_url,
+        api_key.as_deref().map(str::trim),
+        &body,
+    )
+    .await?;
+    let reply = parse_reply(&reply, steps.len());
+    if reply.intro.is_empty() && reply.steps.iter().all(Option::is_none) {
+        return Err("The AI reply wasn't in the expected format".to_string());
+    }
+
+    let generated: Vec<GeneratedStep> = steps
+        .iter()
+        .zip(reply.steps)
+        .filter_map(|(step, description)| {
+            Some(GeneratedStep {
+                step_id: step.id.clone(),
+                description: description?,
+            })
+        })
+        .collect();
+    let updates: Vec<(String, String)> = generated
+        .iter()
+        .map(|step| (step.step_id.clone(), step.description.clone()))
+        .collect();
+    app.state::<DatabaseWorker>()
+        .run(move |db| {
+            for (step_id, description) in &updates {
+                db.update_step_description(step_id, description)
+                    .map_err(|e| e.to_string())?;
+            }
+            Ok(())
+        })
+        .await?;
+    Ok(GeneratedDocumentation {
+        intro: reply.intro,
+        steps: generated,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_reply_splits_intro_and_numbered_steps() {
+        let reply = "<think>Two steps.</think>\nINTRO:\nThis guide shows how to\nsign in.\n\n\
+                     **STEP 1:** Open the app.\n\nSTEP 3:\nIgnored, there are two steps.\n\n\
+                     STEP 2:\nClick **Sign in**.";
+
+        let parsed = parse_reply(reply, 2);
+
+        assert_eq!(
+            parsed,
+            Reply {
+                intro: "This guide shows how to\nsign in.".to_string(),
+                steps: vec![
+                    Some("Open the app.".to_string()),
+                    Some("Click **Sign in**.".to_string()),
+                ],
+            }
+        );
+    }
+
+    #[test]
+    fn delta_text_reads_each_providers_stream() {
+        let openai = r#"{"choices":[{"delta":{"content":"Click"}}]}"#;
+        assert_eq!(
+            delta_text(AiProvider::OpenAi, openai).as_deref(),
+            Some("Click")
+        );
+        assert_eq!(delta_text(AiProvider::OpenAi, "[DONE]"), None);
+
+        let anthropic = r#"{"type":"content_block_delta","index":0,"delta":{"type":"text_delta","text":"Open"}}"#;
+        assert_eq!(
+            delta_text(AiProvider::Anthropic, anthropic).as_deref(),
+            Some("Open")
+        );
+        let ping = r#"{"type":"ping"}"#;
+        assert_eq!(delta_text(AiProvider::Anthropic, ping), None);
+    }
+}
@@ -0,0 +1,268 @@
+//! Opt-in usage analytics that never leave the machine.
+//!
+//! With `analytics_enabled` on, `track` counts uses of a feature per day and
+//! `measure` records how long a capture stage took. Both only touch an
+//! in-memory buffer, so the recorder threads never wait on the database;
+//! `flush` writes it to the `usage_counts` and `capture_metrics` tables, and
+//! `report` summarises them for the insights view. Nothing is sent anywhere.
+
+use crate::database::{Database, MetricSample, UsageCount};
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+
+/// Grabbing the frame of a click or key step from the monitor
+pub const METRIC_SCREENSHOT_CAPTURE: &str = "screenshot_capture";
+/// Encoding a step's screenshot to JPEG
+pub const METRIC_SCREENSHOT_ENCODE: &str = "screenshot_encode";
+
+/// Data older than this is pruned when the buffer is flushed
+const RETENTION_DAYS: i64 = 90;
+/// Samples kept between flushes; later ones are dropped until the next flush
+const MAX_PENDING_SAMPLES: usize = 10_000;
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+static PENDING: Mutex<Pending> = Mutex::new(Pending {
+    counts: None,
+    samples: Vec::new(),
+});
+
+struct Pending {
+    /// Uses per (day, feature)
+    counts: Option<HashMap<(String, &'static str), i64>>,
+    samples: Vec<MetricSample>,
+}
+
+/// Turn recording on or off. Turning it off drops anything not yet flushed.
+pub fn set_enabled(enabled: bool) {
+    ENABLED.store(enabled, Ordering::Relaxed);
+    if !enabled {
+        discard_pending();
+    }
+}
+
+fn discard_pending() {
+    if let Ok(mut pending) = PENDING.lock() {
+        pending.counts = None;
+        pending.samples.clear();
+    }
+}
+
+pub fn is_enabled() -> bool {
+    ENABLED.load(Ordering::Relaxed)
+}
+
+fn today() -> String {
+    chrono::Local::now().format("%Y-%m-%d").to_string()
+}
+
+/// Count one use of `feature` today
+pub fn track(feature: &'static str) {
+    if !is_enabled() {
+        return;
+    }
+    if let Ok(mut pending) = PENDING.lock() {
+        *pending
+            .counts
+            .get_or_insert_with(HashMap::new)
+            .entry((today(), feature))
+            .or_default() += 1;
+    }
+}
+
+/// Record that `metric` took `elapsed`
+pub fn measure(metric: &'static str, elapsed: Duration) {
+    if !is_enabled() {
+        return;
+    }
+    if let Ok(mut pending) = PENDING.lock() {
+        if pending.samples.len() < MAX_PENDING_SAMPLES {
+            pending.samples.push(MetricSample {
+                metric: metric.to_string(),
+                value_ms: elapsed.as_secs_f64() * 1000.0,
+                recorded_at: chrono::Utc::now().timestamp_millis(),
+            });
+        }
+    }
+}
+
+/// Write the buffered counts and samples to the database and prune data
+/// older than `RETENTION_DAYS`
+pub fn flush(db: &Database) -> rusqlite::Result<()> {
+    let (counts, samples) = {
+        let Ok(mut pending) = PENDING.lock() else {
+            return Ok(());
+        };
+        (
+            pending.counts.take().unwrap_or_default(),
+            std::mem::take(&mut pending.samples),
+        )
+    };
+    if counts.is_empty() && samples.is_empty() {
+        return Ok(());
+    }
+    let counts: Vec<UsageCount> = counts
+        .into_iter()
+        .map(|((day, feature), count)| UsageCount {
+            day,
+            feature: feature.to_string(),
+            count,
+        })
+        .collect();
+    db.record_usage(&counts, &samples)?;
+
+    let cutoff = chrono::Local::now() - chrono::Duration::days(RETENTION_DAYS);
+    db.prune_usage_data(
+        &cutoff.format("%Y-%m-%d").to_string(),
+        cutoff.timestamp_millis(),
+    )
+}
+
+/// Delete everything recorded so far, flushed or not
+pub fn clear(db: &Database) -> rusqlite::Result<()> {
+    discard_pending();
+    db.clear_usage_data()
+}
+
+#[derive(Debug, Serialize)]
+pub struct FeatureTotal {
+    pub feature: String,
+    pub count: i64,
+}
+
+/// Timing of one capture stage over the report period
+#[derive(Debug, Serialize, PartialEq)]
+pub struct MetricSummary {
+    pub metric: String,
+    pub samples: usize,
+    pub mean_ms: f64,
+    pub p50_ms: f64,
+    pub p95_ms: f64,
+    pub max_ms: f64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct UsageReport {
+    pub enabled: bool,
+    /// Days covered, ending today
+    pub days: i64,
+    /// Most used first
+    pub features: Vec<FeatureTotal>,
+    /// Per-day counts, oldest first
+    pub daily: Vec<UsageCount>,
+    pub metrics: Vec<MetricSummary>,
+}
+
+/// Usage and capture timings of the last `days` days, after flushing the
+/// buffer so the report includes them
+pub fn report(db: &Database, days: i64) -> rusqlite::Result<UsageReport> {
+    flush(db)?;
+    let days = days.clamp(1, RETENTION_DAYS);
+    let since = chrono::Local::now() - chrono::Duration::days(days - 1);
+    let daily = db.usage_counts_since(&since.format("%Y-%m-%d").to_string())?;
+
+    let mut totals: HashMap<&str, i64> = HashMap::new();
+    for count in &daily {
+        *totals.entry(count.feature.as_str()).or_default() += count.count;
+    }
+    let mut features: Vec<FeatureTotal> = totals
+        .into_iter()
+        .map(|(feature, count)| FeatureTotal {
+            feature: feature.to_string(),
+            count,
+        })
+        .collect();
+    features.sort_by(|a, b| b.count.cmp(&a.count).then(a.feature.cmp(&b.feature)));
+
+    let since_midnight = since
+        .date_naive()
+        .and_hms_opt(0, 0, 0)
+        .and_then(|start| start.and_local_timezone(chrono::Local).earliest())
+        .map_or(since.timestamp_millis(), |start| start.timestamp_millis());
+    let metrics = summarize(db.metric_samples_since(since_midnight)?);
+
+    Ok(UsageReport {
+        enabled: is_enabled(),
+        days,
+        features,
+        daily,
+        metrics,
+    })
+}
+
+/// Per-metric count, mean and percentiles, by metric name
+fn summarize(samples: Vec<MetricSample>) -> Vec<MetricSummary> {
+    let mut by_metric: BTreeMap<String, Vec<f64>> = BTreeMap::new();
+    for sample in samples {
+        by_metric
+            .entry(sample.metric)
+            .or_default()
+            .push(sample.value_ms);
+    }
+    by_metric
+        .into_iter()
+        .map(|(metric, mut values)| {
+            values.sort_by(|a, b| a.total_cmp(b));
+            // Nearest-rank percentile
+            let percentile = |p: f64| {
+                let rank = ((p / 100.0) * values.len() as f64).ceil() as usize;
+                values[rank.clamp(1, values.len()) - 1]
+            };
+            MetricSummary {
+                samples: values.len(),
+                mean_ms: values.iter().sum::<f64>() / values.len() as f64,
+                p50_ms: percentile(50.0),
+                p95_ms: percentile(95.0),
+                max_ms: values[values.len() - 1],
+                metric,
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample(metric: &str, value_ms: f64) -> MetricSample {
+        MetricSample {
+            metric: metric.to_string(),
+            value_ms,
+            recorded_at: 0,
+        }
+    }
+
+    #[test]
+    fn summarize_reports_percentiles_per_metric() {
+        let mut samples: Vec<MetricSample> = (1..=20)
+            .map(|ms| sample(METRIC_SCREENSHOT_CAPTURE, ms as f64))
+            .collect();
+        samples.push(sample(METRIC_SCREENSHOT_ENCODE, 8.0));
+
+        let summaries = summarize(samples);
+
+        assert_eq!(
+            summaries,
+            [
+                MetricSummary {
+                    metric: METRIC_SCREENSHOT_CAPTURE.to_string(),
+                    samples: 20,
+                    mean_ms: 10.5,
+                    p50_ms: 10.0,
+                    p95_ms: 19.0,
+                    max_ms: 20.0,
+                },
+                MetricSummary {
+                    metric: METRIC_SCREENSHOT_ENCODE.to_string(),
+                    samples: 1,
+                    mean_ms: 8.0,
+                    p50_ms: 8.0,
+                    p95_ms: 8.0,
+                    max_ms: 8.0,
+                },
+            ]
+        );
+    }
+}
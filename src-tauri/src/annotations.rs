@@ -0,0 +1,1278 @@
+//! Non-destructive screenshot annotations.
+//!
+//! Arrows, boxes, ellipses, text labels and numbered badges are stored per
+//! step as JSON vectors in screenshot pixel coordinates. They're only drawn
+//! onto a copy of the screenshot by `render_annotated_screenshot`, at export
+//! time, so the capture on disk never changes and annotations stay editable.
+//!
+//! Redactions blur or pixelate rectangles to hide customer data. They're
+//! applied before annotations, either at export like the rest or burned into
+//! the capture straight away (see `redact`).
+//!
+//! Exports can also number each screenshot with a badge by the click (see
+//! `StepBadgeStyle`), so printed documents read without the step list.
+//!
+//! The click highlight works the same way: the recorder stores where the
+//! click landed as the step's `click_marker`, and a ring, arrow or spotlight
+//! is drawn in the style of `ClickHighlightStyle` at export, so it can be
+//! moved or removed.
+//!
+//! Both can adapt to the screenshot: with `auto_contrast` set, the
+//! background around the marker is sampled at render time and the colour is
+//! swapped for a light or dark one when it wouldn't stand out, so markers
+//! stay visible on dark IDE themes and white web pages alike.
+
+use crate::accessibility::ElementBounds;
+use crate::database::{is_text_only_step_type, Step};
+use crate::smart_crop::{focus_rect, SmartCropMode, SmartCropSettings};
+use crate::watermark::{Watermark, WatermarkStyle};
+use crate::zoom_inset::{draw_zoom_inset, ZoomInsetStyle};
+use ab_glyph::{FontVec, PxScale};
+use image::{imageops, Rgba, RgbaImage};
+use imageproc::drawing::{
+    draw_filled_circle_mut, draw_filled_rect_mut, draw_polygon_mut, draw_text_mut, text_size,
+};
+use imageproc::point::Point as PixelPoint;
+use imageproc::rect::Rect as PixelRect;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::OnceLock;
+
+const DEFAULT_COLOR: &str = "#FF3B30";
+const DEFAULT_HIGHLIGHT_COLOR: &str = "#FF4500";
+/// Arrow highlights are this many times the highlight radius long
+const ARROW_LENGTH: f32 = 2.5;
+/// Brightness left outside a spotlight highlight
+const SPOTLIGHT_BRIGHTNESS: f32 = 0.45;
+const DEFAULT_STROKE_WIDTH: f32 = 4.0;
+const DEFAULT_TEXT_SIZE: f32 = 24.0;
+
+/// Badge label colour, and the backdrop behind text labels
+const BADGE_TEXT_COLOR: Rgba<u8> = Rgba([255, 255, 255, 255]);
+const LABEL_BACKGROUND: Rgba<u8> = Rgba([255, 255, 255, 230]);
+/// Badge label colour on light badges
+const BADGE_TEXT_COLOR_DARK: Rgba<u8> = Rgba([0, 0, 0, 255]);
+
+/// Contrast ratio (WCAG) below which an auto-contrast marker changes colour
+const MIN_CONTRAST: f32 = 3.0;
+/// Auto-contrast colours for dark and light backgrounds
+const AUTO_COLOR_ON_DARK: Rgba<u8> = Rgba([255, 214, 10, 255]);
+const AUTO_COLOR_ON_LIGHT: Rgba<u8> = Rgba([196, 0, 26, 255]);
+/// Only every this many pixels is sampled when measuring a background
+const BACKGROUND_SAMPLE_STRIDE: usize = 4;
+
+/// System fonts tried, in order, for text labels and badge numbers
+#[cfg(target_os = "windows")]
+const FONT_CANDIDATES: &[&str] = &[
+    "C:\\Windows\\Fonts\\segoeui.ttf",
+    "C:\\Windows\\Fonts\\arial.ttf",
+];
+#[cfg(target_os = "macos")]
+const FONT_CANDIDATES: &[&str] = &[
+    "/System/Library/Fonts/Supplemental/Arial.ttf",
+    "/Library/Fonts/Arial.ttf",
+    "/System/Library/Fonts/Supplemental/Verdana.ttf",
+];
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+const FONT_CANDIDATES: &[&str] = &[
+    "/usr/share/fonts/truetype/dejavu/DejaVuSans.ttf",
+    "/usr/share/fonts/TTF/DejaVuSans.ttf",
+    "/usr/share/fonts/dejavu/DejaVuSans.ttf",
+    "/usr/share/fonts/truetype/liberation/LiberationSans-Regular.ttf",
+    "/usr/share/fonts/liberation-sans/LiberationSans-Regular.ttf",
+    "/usr/share/fonts/truetype/noto/NotoSans-Regular.ttf",
+];
+
+/// A position in screenshot pixels
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Point {
+    pub x: f32,
+    pub y: f32,
+}
+
+/// An area in screenshot pixels
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Rect {
+    pub x: f32,
+    pub y: f32,
+    pub width: f32,
+    pub height: f32,
+}
+
+impl Point {
+    /// The same position on the screenshot resized by `factor`
+    pub fn scaled(self, factor: f32) -> Self {
+        Self {
+            x: self.x * factor,
+            y: self.y * factor,
+        }
+    }
+}
+
+impl Rect {
+    /// The same area on the screenshot resized by `factor`
+    pub fn scaled(self, factor: f32) -> Self {
+        self.mapped(&|point| point.scaled(factor))
+    }
+
+    /// The area covered by this one's corners after `map`, e.g. once the
+    /// screenshot is rotated or flipped
+    pub fn mapped(self, map: &impl Fn(Point) -> Point) -> Self {
+        let a = map(Point {
+            x: self.x,
+            y: self.y,
+        });
+        let b = map(Point {
+            x: self.x + self.width,
+            y: self.y + self.height,
+        });
+        Self {
+            x: a.x.min(b.x),
+            y: a.y.min(b.y),
+            width: (a.x - b.x).abs(),
+            height: (a.y - b.y).abs(),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Shape {
+    /// Line from `from` with an arrowhead at `to`
+    Arrow {
+        from: Point,
+        to: Point,
+    },
+    Rectangle {
+        rect: Rect,
+    },
+    /// Ellipse inscribed in `rect`
+    Ellipse {
+        rect: Rect,
+    },
+    /// Label with its top-left corner at `at`, on a light backdrop
+    Text {
+        at: Point,
+        text: String,
+        #[serde(default = "default_text_size")]
+        size: f32,
+    },
+    /// Filled circle centred on `at` with a short label, usually a number
+    Badge {
+        at: Point,
+        label: String,
+    },
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Annotation {
+    #[serde(flatten)]
+    pub shape: Shape,
+    /// `#RRGGBB` or `#RRGGBBAA`
+    #[serde(default = "default_color")]
+    pub color: String,
+    #[serde(default = "default_stroke_width")]
+    pub stroke_width: f32,
+}
+
+impl Annotation {
+    /// The annotation on the screenshot resized by `factor`, strokes and
+    /// text included
+    pub fn scaled(&self, factor: f32) -> Self {
+        self.mapped(&|point| point.scaled(factor), factor)
+    }
+
+    /// The annotation with its positions moved by `map` and its strokes and
+    /// text sized by `factor`, e.g. once the screenshot is edited
+    pub fn mapped(&self, map: &impl Fn(Point) -> Point, factor: f32) -> Self {
+        let shape = match &self.shape {
+            Shape::Arrow { from, to } => Shape::Arrow {
+                from: map(*from),
+                to: map(*to),
+            },
+            Shape::Rectangle { rect } => Shape::Rectangle {
+                rect: rect.mapped(map),
+            },
+            Shape::Ellipse { rect } => Shape::Ellipse {
+                rect: rect.mapped(map),
+            },
+            Shape::Text { at, text, size } => Shape::Text {
+                at: map(*at),
+                text: text.clone(),
+                size: size * factor,
+            },
+            Shape::Badge { at, label } => Shape::Badge {
+                at: map(*at),
+                label: label.clone(),
+            },
+        };
+        Self {
+            shape,
+            color: self.color.clone(),
+            stroke_width: self.stroke_width * factor,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RedactionStyle {
+    Blur,
+    #[default]
+    Pixelate,
+}
+
+/// An area of the screenshot to hide
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Redaction {
+    pub rect: Rect,
+    #[serde(default)]
+    pub style: RedactionStyle,
+}
+
+impl Redaction {
+    /// The same area in an image whose top-left corner sits at `origin` in
+    /// this one, e.g. the element crop taken from a screenshot.
+    pub fn relative_to(&self, origin: (i32, i32)) -> Self {
+        Self {
+            rect: Rect {
+                x: self.rect.x - origin.0 as f32,
+                y: self.rect.y - origin.1 as f32,
+                ..self.rect
+            },
+            ..*self
+        }
+    }
+
+    /// The same area on the screenshot resized by `factor`
+    pub fn scaled(&self, factor: f32) -> Self {
+        self.mapped(&|point| point.scaled(factor))
+    }
+
+    /// The same area once `map` moves the screenshot's pixels
+    pub fn mapped(&self, map: &impl Fn(Point) -> Point) -> Self {
+        Self {
+            rect: self.rect.mapped(map),
+            ..*self
+        }
+    }
+}
+
+/// Where a step number badge goes
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BadgePlacement {
+    /// Beside the click, falling back to the top-left corner for steps
+    /// without one
+    #[default]
+    Click,
+    TopLeft,
+    TopRight,
+}
+
+/// Step number badges burned into exported screenshots
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct StepBadgeStyle {
+    pub enabled: bool,
+    /// `#RRGGBB` or `#RRGGBBAA`
+    pub color: String,
+    /// Badge radius in logical pixels
+    pub size: f32,
+    pub placement: BadgePlacement,
+    /// Swap `color` for one that stands out when the background is too
+    /// close to it
+    pub auto_contrast: bool,
+}
+
+impl Default for StepBadgeStyle {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            color: DEFAULT_COLOR.to_string(),
+            size: 18.0,
+            placement: BadgePlacement::default(),
+            auto_contrast: false,
+        }
+    }
+}
+
+impl StepBadgeStyle {
+    /// The style in physical pixels of a screenshot at `scale_factor`
+    fn scaled(&self, scale_factor: f32) -> Self {
+        Self {
+            size: self.size * scale_factor,
+            ..self.clone()
+        }
+    }
+
+    /// Centre of the badge on a `width` x `height` screenshot. Beside a click
+    /// it sits up and to the left so the clicked control stays visible, and
+    /// it's always kept fully inside the image.
+    fn position(&self, click: Option<(i32, i32)>, width: u32, height: u32) -> Point {
+        let radius = self.size.max(1.0);
+        let margin = radius * 1.5;
+        let (x, y) = match (self.placement, click) {
+            (BadgePlacement::Click, Some((x, y))) => (x as f32 - margin, y as f32 - margin),
+            (BadgePlacement::TopRight, _) => (width as f32 - margin, margin),
+            _ => (margin, margin),
+        };
+        let clamp = |value: f32, max: u32| value.min(max as f32 - radius).max(radius);
+        Point {
+            x: clamp(x, width),
+            y: clamp(y, height),
+        }
+    }
+}
+
+/// How a step's click marker is highlighted
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HighlightShape {
+    /// A ring with a dot in the middle
+    #[default]
+    Ring,
+    /// An arrow pointing at the click from below and to the right
+    Arrow,
+    /// Everything but a circle around the click is dimmed
+    Spotlight,
+}
+
+/// The highlight drawn at a step's click marker
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ClickHighlightStyle {
+    pub enabled: bool,
+    pub shape: HighlightShape,
+    /// `#RRGGBB` or `#RRGGBBAA`
+    pub color: String,
+    /// Ring or spotlight radius in logical pixels; the arrow is longer
+    pub radius: f32,
+    /// Line thickness in logical pixels
+    pub width: f32,
+    /// Swap `color` for one that stands out when the background around the
+    /// click is too close to it
+    pub auto_contrast: bool,
+}
+
+impl Default for ClickHighlightStyle {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            shape: HighlightShape::Ring,
+            color: DEFAULT_HIGHLIGHT_COLOR.to_string(),
+            radius: 32.0,
+            width: 6.0,
+            auto_contrast: false,
+        }
+    }
+}
+
+impl ClickHighlightStyle {
+    /// The style in physical pixels of a screenshot at `scale_factor`
+    fn scaled(&self, scale_factor: f32) -> Self {
+        Self {
+            radius: self.radius * scale_factor,
+            width: self.width * scale_factor,
+            ..self.clone()
+        }
+    }
+}
+
+fn default_color() -> String {
+    DEFAULT_COLOR.to_string()
+}
+
+fn default_stroke_width() -> f32 {
+    DEFAULT_STROKE_WIDTH
+}
+
+fn default_text_size() -> f32 {
+    DEFAULT_TEXT_SIZE
+}
+
+pub(crate) fn parse_color(hex: &str) -> Option<Rgba<u8>> {
+    let hex = hex.trim().strip_prefix('#')?;
+    if !matches!(hex.len(), 6 | 8) || !hex.is_ascii() {
+        return None;
+    }
+    let channel = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16).ok();
+    let alpha = if hex.len() == 8 { channel(6)? } else { 255 };
+    Some(Rgba([channel(0)?, channel(2)?, channel(4)?, alpha]))
+}
+
+/// WCAG relative luminance of `color`, from 0 (black) to 1 (white)
+fn relative_luminance(color: Rgba<u8>) -> f32 {
+    let linear = |channel: u8| {
+        let c = channel as f32 / 255.0;
+        if c <= 0.039_28 {
+            c / 12.92
+        } else {
+            ((c + 0.055) / 1.055).powf(2.4)
+        }
+    };
+    let [r, g, b, _] = color.0;
+    0.2126 * linear(r) + 0.7152 * linear(g) + 0.0722 * linear(b)
+}
+
+fn contrast_ratio(a: f32, b: f32) -> f32 {
+    (a.max(b) + 0.05) / (a.min(b) + 0.05)
+}
+
+/// Mean luminance of the square of `radius` around `center`, clipped to the
+/// image. `None` when the square misses the image entirely.
+fn background_luminance(image: &RgbaImage, center: Point, radius: f32) -> Option<f32> {
+    let radius = radius.max(1.0);
+    let clip = |value: f32, max: u32| value.round().clamp(0.0, max as f32) as u32;
+    let (left, right) = (
+        clip(center.x - radius, image.width()),
+        clip(center.x + radius, image.width()),
+    );
+    let (top, bottom) = (
+        clip(center.y - radius, image.height()),
+        clip(center.y + radius, image.height()),
+    );
+    let mut total = 0.0;
+    let mut count = 0;
+    for y in (top..bottom).step_by(BACKGROUND_SAMPLE_STRIDE) {
+        for x in (left..right).step_by(BACKGROUND_SAMPLE_STRIDE) {
+            total += relative_luminance(*image.get_pixel(x, y));
+            count += 1;
+        }
+    }
+    (count > 0).then(|| total / count as f32)
+}
+
+/// `preferred`, unless it doesn't stand out from the background within
+/// `radius` of `center`, in which case whichever of the auto-contrast
+/// colours does better, keeping `preferred`'s opacity
+fn contrasting_color(
+    image: &RgbaImage,
+    center: Point,
+    radius: f32,
+    preferred: Rgba<u8>,
+) -> Rgba<u8> {
+    let Some(background) = background_luminance(image, center, radius) else {
+        return preferred;
+    };
+    if contrast_ratio(relative_luminance(preferred), background) >= MIN_CONTRAST {
+        return preferred;
+    }
+    let on_dark = contrast_ratio(relative_luminance(AUTO_COLOR_ON_DARK), background);
+    let on_light = contrast_ratio(relative_luminance(AUTO_COLOR_ON_LIGHT), background);
+    let mut color = if on_dark >= on_light {
+        AUTO_COLOR_ON_DARK
+    } else {
+        AUTO_COLOR_ON_LIGHT
+    };
+    color.0[3] = preferred.0[3];
+    color
+}
+
+/// Font for text labels and badges, loaded once. `None` when none of
+/// `FONT_CANDIDATES` exists, in which case labels are left out.
+pub(crate) fn label_font() -> Option<&'static FontVec> {
+    static FONT: OnceLock<Option<FontVec>> = OnceLock::new();
+    FONT.get_or_init(|| {
+        let font = FONT_CANDIDATES.iter().find_map(|path| {
+            let bytes = std::fs::read(path).ok()?;
+            FontVec::try_from_vec(bytes).ok()
+        });
+        if font.is_none() {
+            tracing::warn!("No font found for annotation labels; text will be left out");
+        }
+        font
+    })
+    .as_ref()
+}
+
+/// Draw a `width`-wide line with round caps by stamping discs along it
+fn stroke_segment(layer: &mut RgbaImage, from: Point, to: Point, width: f32, color: Rgba<u8>) {
+    let radius = (width / 2.0).round().max(1.0) as i32;
+    let length = (to.x - from.x).hypot(to.y - from.y);
+    let steps = length.ceil().max(1.0) as usize;
+    for i in 0..=steps {
+        let t = i as f32 / steps as f32;
+        let x = from.x + (to.x - from.x) * t;
+        let y = from.y + (to.y - from.y) * t;
+        draw_filled_circle_mut(layer, (x.round() as i32, y.round() as i32), radius, color);
+    }
+}
+
+fn stroke_path(layer: &mut RgbaImage, points: &[Point], width: f32, color: Rgba<u8>) {
+    for pair in points.windows(2) {
+        stroke_segment(layer, pair[0], pair[1], width, color);
+    }
+}
+
+fn draw_arrow(layer: &mut RgbaImage, from: Point, to: Point, width: f32, color: Rgba<u8>) {
+    let (dx, dy) = (to.x - from.x, to.y - from.y);
+    let length = dx.hypot(dy);
+    if length < 1.0 {
+        stroke_segment(layer, from, to, width, color);
+        return;
+    }
+    let (ux, uy) = (dx / length, dy / length);
+    let head = (width * 4.0).max(14.0).min(length);
+    let base = Point {
+        x: to.x - ux * head,
+        y: to.y - uy * head,
+    };
+    // Stop the shaft inside the head so its round cap doesn't poke past the tip
+    stroke_segment(layer, from, base, width, color);
+
+    let half = head * 0.5;
+    let corner = |side: f32| {
+        PixelPoint::new(
+            (base.x - uy * half * side).round() as i32,
+            (base.y + ux * half * side).round() as i32,
+        )
+    };
+    let tip = PixelPoint::new(to.x.round() as i32, to.y.round() as i32);
+    let (left, right) = (corner(1.0), corner(-1.0));
+    if tip != left && left != right && right != tip {
+        draw_polygon_mut(layer, &[tip, left, right], color);
+    }
+}
+
+fn outline(rect: &Rect) -> [Point; 5] {
+    let (x0, y0) = (rect.x, rect.y);
+    let (x1, y1) = (rect.x + rect.width, rect.y + rect.height);
+    [
+        Point { x: x0, y: y0 },
+        Point { x: x1, y: y0 },
+        Point { x: x1, y: y1 },
+        Point { x: x0, y: y1 },
+        Point { x: x0, y: y0 },
+    ]
+}
+
+fn ellipse_outline(rect: &Rect) -> Vec<Point> {
+    let (rx, ry) = (rect.width.abs() / 2.0, rect.height.abs() / 2.0);
+    let (cx, cy) = (rect.x + rect.width / 2.0, rect.y + rect.height / 2.0);
+    // Roughly one vertex per 4px of circumference
+    let segments = ((rx + ry) * std::f32::consts::PI / 4.0).ceil().max(16.0) as usize;
+    (0..=segments)
+        .map(|i| {
+            let angle = i as f32 / segments as f32 * std::f32::consts::TAU;
+            Point {
+                x: cx + rx * angle.cos(),
+                y: cy + ry * angle.sin(),
+            }
+        })
+        .collect()
+}
+
+fn filled_rect(x: f32, y: f32, width: f32, height: f32) -> Option<PixelRect> {
+    let (width, height) = (width.round() as u32, height.round() as u32);
+    (width > 0 && height > 0)
+        .then(|| PixelRect::at(x.round() as i32, y.round() as i32).of_size(width, height))
+}
+
+fn draw_label(layer: &mut RgbaImage, at: Point, text: &str, size: f32, color: Rgba<u8>) {
+    let Some(font) = label_font() else {
+        return;
+    };
+    let scale = PxScale::from(size.max(1.0));
+    let line_height = size * 1.2;
+    let padding = size * 0.3;
+    let lines: Vec<&str> = text.lines().collect();
+    let widest = lines
+        .iter()
+        .map(|line| text_size(scale, font, line).0)
+        .max()
+        .unwrap_or(0) as f32;
+
+    if let Some(backdrop) = filled_rect(
+        at.x,
+        at.y,
+        widest + padding * 2.0,
+        line_height * lines.len() as f32 + padding * 2.0,
+    ) {
+        draw_filled_rect_mut(layer, backdrop, LABEL_BACKGROUND);
+    }
+    for (i, line) in lines.iter().enumerate() {
+        draw_text_mut(
+            layer,
+            color,
+            (at.x + padding).round() as i32,
+            (at.y + padding + line_height * i as f32).round() as i32,
+            scale,
+            font,
+            line,
+        );
+    }
+}
+
+fn draw_badge(layer: &mut RgbaImage, at: Point, label: &str, radius: f32, color: Rgba<u8>) {
+    let center = (at.x.round() as i32, at.y.round() as i32);
+    draw_filled_circle_mut(layer, center, radius.round() as i32, color);
+
+    let Some(font) = label_font() else {
+        return;
+    };
+    // White numbers disappear on light badges, such as auto-contrast ones
+    let text_color = if relative_luminance(color) > 0.5 {
+        BADGE_TEXT_COLOR_DARK
+    } else {
+        BADGE_TEXT_COLOR
+    };
+    let scale = PxScale::from(radius * 1.2);
+    let (width, height) = text_size(scale, font, label);
+    draw_text_mut(
+        layer,
+        text_color,
+        center.0 - width as i32 / 2,
+        center.1 - height as i32 / 2,
+        scale,
+        font,
+        label,
+    );
+}
+
+/// Draw `annotations` onto `image`, in order. Each is drawn on its own
+/// layer and blended in, so translucent colours don't darken where a
+/// stroke overlaps itself.
+pub fn composite(image: &mut RgbaImage, annotations: &[Annotation]) {
+    for annotation in annotations {
+        let color = parse_color(&annotation.color)
+            .or_else(|| parse_color(DEFAULT_COLOR))
+            .expect("default colour parses");
+        let width = annotation.stroke_width.max(1.0);
+        let mut layer = RgbaImage::new(image.width(), image.height());
+        match &annotation.shape {
+            Shape::Arrow { from, to } => draw_arrow(&mut layer, *from, *to, width, color),
+            Shape::Rectangle { rect } => stroke_path(&mut layer, &outline(rect), width, color),
+            Shape::Ellipse { rect } => {
+                stroke_path(&mut layer, &ellipse_outline(rect), width, color)
+            }
+            Shape::Text { at, text, size } => draw_label(&mut layer, *at, text, *size, color),
+            Shape::Badge { at, label } => {
+                draw_badge(&mut layer, *at, label, (width * 3.0).max(14.0), color)
+            }
+        }
+        imageops::overlay(image, &layer, 0, 0);
+    }
+}
+
+/// `rect` as whole pixels clipped to a `width` x `height` image, or None if
+/// nothing of it is left. Negative sizes (dragged up or left) are allowed.
+fn clip_rect(rect: &Rect, width: u32, height: u32) -> Option<(u32, u32, u32, u32)> {
+    let (left, right) = if rect.width < 0.0 {
+        (rect.x + rect.width, rect.x)
+    } else {
+        (rect.x, rect.x + rect.width)
+    };
+    let (top, bottom) = if rect.height < 0.0 {
+        (rect.y + rect.height, rect.y)
+    } else {
+        (rect.y, rect.y + rect.height)
+    };
+    let left = left.floor().clamp(0.0, width as f32) as u32;
+    let top = top.floor().clamp(0.0, height as f32) as u32;
+    let right = right.ceil().clamp(0.0, width as f32) as u32;
+    let bottom = bottom.ceil().clamp(0.0, height as f32) as u32;
+    (right > left && bottom > top).then(|| (left, top, right - left, bottom - top))
+}
+
+/// Replace every `block` x `block` cell of `region` with its average colour
+fn pixelate(region: &mut RgbaImage, block: u32) {
+    let (width, height) = region.dimensions();
+    for cell_y in (0..height).step_by(block as usize) {
+        for cell_x in (0..width).step_by(block as usize) {
+            let cell_width = block.min(width - cell_x);
+            let cell_height = block.min(height - cell_y);
+            let mut sum = [0u64; 4];
+            for y in cell_y..cell_y + cell_height {
+                for x in cell_x..cell_x + cell_width {
+                    for (total, channel) in sum.iter_mut().zip(region.get_pixel(x, y).0) {
+                        *total += channel as u64;
+                    }
+                }
+            }
+            let count = (cell_width * cell_height) as u64;
+            let average = Rgba(sum.map(|total| (total / count) as u8));
+            for y in cell_y..cell_y + cell_height {
+                for x in cell_x..cell_x + cell_width {
+                    region.put_pixel(x, y, average);
+                }
+            }
+        }
+    }
+}
+
+/// Blur or pixelate each redaction in `image`. Only pixels inside a
+/// redaction are read, so nothing outside bleeds in and nothing inside can
+/// be recovered from its surroundings. Returns whether any pixel changed,
+/// i.e. whether some redaction overlaps the image.
+pub fn redact(image: &mut RgbaImage, redactions: &[Redaction]) -> bool {
+    let mut changed = false;
+    for redaction in redactions {
+        let Some((x, y, width, height)) = clip_rect(&redaction.rect, image.width(), image.height())
+        else {
+            continue;
+        };
+        let mut region = imageops::crop_imm(image, x, y, width, height).to_image();
+        // Coarse enough to make text unreadable even in small fields
+        let strength = (width.min(height) / 4).clamp(12, 48);
+        match redaction.style {
+            RedactionStyle::Blur => {
+                region = imageproc::filter::gaussian_blur_f32(&region, strength as f32 / 2.0);
+            }
+            RedactionStyle::Pixelate => pixelate(&mut region, strength),
+        }
+        imageops::replace(image, &region, x as i64, y as i64);
+        changed = true;
+    }
+    changed
+}
+
+/// The screenshot at `source` with `redactions` applied and `annotations`
+/// drawn on top. The file itself is left untouched.
+pub fn render_annotated_screenshot(
+    source: &Path,
+    redactions: &[Redaction],
+    annotations: &[Annotation],
+) -> Result<RgbaImage, String> {
+    let mut image = image::open(source)
+        .map_err(|e| format!("Failed to read {}: {}", source.display(), e))?
+        .to_rgba8();
+    redact(&mut image, redactions);
+    composite(&mut image, annotations);
+    Ok(image)
+}
+
+/// Draw the click highlight at `at` in `style.shape`: a ring with a dot in
+/// the middle (like the one the recorder used to burn into captures), an
+/// arrow pointing at it, or a spotlight dimming everything else.
+pub fn draw_click_highlight(image: &mut RgbaImage, at: Point, style: &ClickHighlightStyle) {
+    let mut color = parse_color(&style.color)
+        .or_else(|| parse_color(DEFAULT_HIGHLIGHT_COLOR))
+        .expect("default colour parses");
+    if style.auto_contrast {
+        // Sampled before a spotlight dims the surroundings
+        color = contrasting_color(image, at, style.radius * 1.5, color);
+    }
+    let center = (at.x.round() as i32, at.y.round() as i32);
+    let mut layer = RgbaImage::new(image.width(), image.height());
+    match style.shape {
+        HighlightShape::Ring => {
+            draw_ring(&mut layer, center, style.radius, style.width, color);
+            let dot = (style.radius / 6.0).max(2.0).round() as i32;
+            draw_filled_circle_mut(&mut layer, center, dot, color);
+        }
+        HighlightShape::Arrow => {
+            // From below and to the right, unless that side runs off the image
+            let length = style.radius * ARROW_LENGTH;
+            let step = length / std::f32::consts::SQRT_2;
+            let dx = if at.x + step > image.width() as f32 {
+                -step
+            } else {
+                step
+            };
+            let dy = if at.y + step > image.height() as f32 {
+                -step
+            } else {
+                step
+            };
+            let from = Point {
+                x: at.x + dx,
+                y: at.y + dy,
+            };
+            draw_arrow(&mut layer, from, at, style.width.max(1.0), color);
+        }
+        HighlightShape::Spotlight => {
+            dim_outside_circle(image, center, style.radius);
+            draw_ring(&mut layer, center, style.radius, style.width / 2.0, color);
+        }
+    }
+    imageops::overlay(image, &layer, 0, 0);
+}
+
+/// Draw a `width`-thick circle of `radius` around `center`
+fn draw_ring(layer: &mut RgbaImage, center: (i32, i32), radius: f32, width: f32, color: Rgba<u8>) {
+    let half_width = width.max(1.0) / 2.0;
+    let outer = (radius + half_width).max(1.0).round() as i32;
+    let inner = (radius - half_width).max(0.0).round() as i32;
+
+    // Punch the inside out of a filled circle so the ring has no gaps
+    draw_filled_circle_mut(layer, center, outer, color);
+    if inner > 0 {
+        draw_filled_circle_mut(layer, center, inner, Rgba([0, 0, 0, 0]));
+    }
+}
+
+/// Darken every pixel further than `radius` from `center`
+fn dim_outside_circle(image: &mut RgbaImage, center: (i32, i32), radius: f32) {
+    let radius_sq = radius.max(0.0).powi(2);
+    for (x, y, pixel) in image.enumerate_pixels_mut() {
+        let dx = x as f32 - center.0 as f32;
+        let dy = y as f32 - center.1 as f32;
+        if dx * dx + dy * dy > radius_sq {
+            for channel in &mut pixel.0[..3] {
+                *channel = (*channel as f32 * SPOTLIGHT_BRIGHTNESS).round() as u8;
+            }
+        }
+    }
+}
+
+/// Draw step `number`'s badge onto `image`. `click` is the click position in
+/// image pixels, when it's known and still lines up with the image.
+pub fn draw_step_badge(
+    image: &mut RgbaImage,
+    number: usize,
+    click: Option<(i32, i32)>,
+    style: &StepBadgeStyle,
+) {
+    let mut color = parse_color(&style.color)
+        .or_else(|| parse_color(DEFAULT_COLOR))
+        .expect("default colour parses");
+    let at = style.position(click, image.width(), image.height());
+    if style.auto_contrast {
+        color = contrasting_color(image, at, style.size * 1.5, color);
+    }
+    let mut layer = RgbaImage::new(image.width(), image.height());
+    draw_badge(
+        &mut layer,
+        at,
+        &number.to_string(),
+        style.size.max(1.0),
+        color,
+    );
+    imageops::overlay(image, &layer, 0, 0);
+}
+
+/// Top-left corner of a step's element crop in screenshot pixels. Must match
+/// the padding `recorder::save_element_crop` cut it with.
+pub fn element_crop_origin(bounds: &ElementBounds) -> (i32, i32) {
+    let padding = crate::recorder::ELEMENT_CROP_PADDING;
+    ((bounds.x - padding).max(0), (bounds.y - padding).max(0))
+}
+
+/// Render every annotated or redacted screenshot in `steps` as a PNG in
+/// `out_dir`, returning original path -> rendered path for exporters to
+/// substitute. With `highlight` enabled click markers are drawn, with
+/// `badges` enabled every screenshot is rendered, numbered like the exported
+/// steps (text-only steps take no number), and with `crop` in export mode
+/// every uncropped one is focused on its click. With `inset` enabled a
+/// magnified inset of the click is pasted into a corner after cropping, and
+/// with `watermark` enabled every screenshot is rendered and stamped last.
+/// Annotations, redactions and markers are drawn before cropping, on the
+/// step's lossless original when it has one. Element crops are rendered too when a
+/// redaction reaches them. Steps that fail to render keep their original
+/// screenshot.
+pub fn render_for_export(
+    steps: &[Step],
+    out_dir: &Path,
+    badges: &StepBadgeStyle,
+    crop: &SmartCropSettings,
+    highlight: &ClickHighlightStyle,
+    inset: &ZoomInsetStyle,
+    watermark: &WatermarkStyle,
+) -> HashMap<String, String> {
+    let mut rendered = HashMap::new();
+    if let Err(e) = std::fs::create_dir_all(out_dir) {
+        tracing::error!("Failed to create {}: {}", out_dir.display(), e);
+        return rendered;
+    }
+    let watermark = Watermark::load(watermark);
+    let mut step_number = 0;
+    for step in steps {
+        if !is_text_only_step_type(&step.type_) {
+            step_number += 1;
+        }
+        let annotations = step.annotations.as_deref().unwrap_or_default();
+        let redactions = step.redactions.as_deref().unwrap_or_default();
+        let is_cropped = step.is_cropped.unwrap_or(false);
+        let smart_crop = crop.mode == SmartCropMode::Export && !is_cropped;
+        let marker = step.click_marker.filter(|_| highlight.enabled);
+        if annotations.is_empty()
+            && redactions.is_empty()
+            && marker.is_none()
+            && !badges.enabled
+            && !smart_crop
+            && !inset.enabled
+            && watermark.is_none()
+        {
+            continue;
+        }
+        if let Some(path) = &step.screenshot_path {
+            // Styles and padding are logical; HiDPI captures need them larger
+            let scale_factor = step.scale_factor.unwrap_or(1.0) as f32;
+            let badges = badges.scaled(scale_factor);
+            let highlight = highlight.scaled(scale_factor);
+            let inset = inset.scaled(scale_factor);
+            let padding = (crop.padding as f32 * scale_factor).round() as u32;
+            // The marker is kept in image pixels; the recorded click only
+            // lines up until the screenshot is cropped
+            let click = match (step.click_marker, step.x, step.y, is_cropped) {
+                (Some(at), ..) => Some((at.x.round() as i32, at.y.round() as i32)),
+                (None, Some(x), Some(y), false) => Some((x, y)),
+                _ => None,
+            };
+            let dest = out_dir.join(format!("{}.png", step.id));
+            // Drawn on the lossless original when there is one
+            let source = step.original_path.as_deref().unwrap_or(path);
+            match render_annotated_screenshot(Path::new(source), redactions, annotations).and_then(
+                |mut image| {
+                    if let Some(at) = marker {
+                        draw_click_highlight(&mut image, at, &highlight);
+                    }
+                    let mut click = click;
+                    let focus = smart_crop
+                        .then(|| {
+                            let (width, height) = image.dimensions();
+                            focus_rect(width, height, step.element_bounds, click, padding)
+                        })
+                        .flatten();
+                    if let Some(focus) = focus {
+                        image =
+                            imageops::crop_imm(&image, focus.x, focus.y, focus.width, focus.height)
+                                .to_image();
+                        click = click.map(|(x, y)| (x - focus.x as i32, y - focus.y as i32));
+                    }
+                    if let Some(click) = click.filter(|_| inset.enabled) {
+                        draw_zoom_inset(&mut image, click, &inset);
+                    }
+                    if badges.enabled {
+                        draw_step_badge(&mut image, step_number, click, &badges);
+                    }
+                    if let Some(watermark) = &watermark {
+                        watermark.draw(&mut image, scale_factor);
+                    }
+                    image.save(&dest).map_err(|e| e.to_string())
+                },
+            ) {
+                Ok(()) => {
+                    rendered.insert(path.clone(), dest.to_string_lossy().to_string());
+                }
+                Err(e) => tracing::warn!(step_id = %step.id, "Failed to render annotations: {}", e),
+            }
+        }
+        // After a crop the screenshot no longer lines up with the bounds
+        if let (Some(path), Some(bounds)) = (&step.element_screenshot_path, &step.element_bounds) {
+            if redactions.is_empty() || is_cropped {
+                continue;
+            }
+            let origin = element_crop_origin(bounds);
+            let shifted: Vec<Redaction> = redactions
+                .iter()
+                .map(|redaction| redaction.relative_to(origin))
+                .collect();
+            let dest = out_dir.join(format!("{}_element.png", step.id));
+            let result = image::open(path)
+                .map_err(|e| e.to_string())
+                .map(|image| image.to_rgba8())
+                .and_then(|mut image| {
+                    if redact(&mut image, &shifted) {
+                        image.save(&dest).map_err(|e| e.to_string())?;
+                        rendered.insert(path.clone(), dest.to_string_lossy().to_string());
+                    }
+                    Ok(())
+                });
+            if let Err(e) = result {
+                tracing::warn!(step_id = %step.id, "Failed to redact element crop: {}", e);
+            }
+        }
+    }
+    rendered
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn annotation(shape: Shape) -> Annotation {
+        Annotation {
+            shape,
+            color: "#0000FF".to_string(),
+            stroke_width: 2.0,
+        }
+    }
+
+    #[test]
+    fn scaled_annotations_follow_a_resized_screenshot() {
+        let label = annotation(Shape::Text {
+            at: Point { x: 100.0, y: 40.0 },
+            text: "Save".to_string(),
+            size: 20.0,
+        })
+        .scaled(0.5);
+
+        assert_eq!(label.stroke_width, 1.0);
+        assert_eq!(
+            label.shape,
+            Shape::Text {
+                at: Point { x: 50.0, y: 20.0 },
+                text: "Save".to_string(),
+                size: 10.0,
+            }
+        );
+    }
+
+    #[test]
+    fn annotations_round_trip_as_tagged_json_with_defaults() {
+        let parsed: Vec<Annotation> = serde_json::from_str(
+            r##"[{"kind":"arrow","from":{"x":1,"y":2},"to":{"x":30,"y":40}},
+                {"kind":"text","at":{"x":5,"y":5},"text":"Here","color":"#00FF0080"}]"##,
+        )
+        .unwrap();
+
+        assert_eq!(parsed[0].color, DEFAULT_COLOR);
+        assert_eq!(parsed[0].stroke_width, DEFAULT_STROKE_WIDTH);
+        assert_eq!(
+            parsed[1].shape,
+            Shape::Text {
+                at: Point { x: 5.0, y: 5.0 },
+                text: "Here".to_string(),
+                size: DEFAULT_TEXT_SIZE,
+            }
+        );
+
+        let json = serde_json::to_value(&parsed[0]).unwrap();
+        assert_eq!(json["kind"], "arrow");
+        assert_eq!(json["to"]["y"], 40.0);
+    }
+
+    #[test]
+    fn rectangle_outlines_without_filling() {
+        let mut image = RgbaImage::from_pixel(40, 40, Rgba([255, 255, 255, 255]));
+        composite(
+            &mut image,
+            &[annotation(Shape::Rectangle {
+                rect: Rect {
+                    x: 5.0,
+                    y: 5.0,
+                    width: 30.0,
+                    height: 30.0,
+                },
+            })],
+        );
+
+        assert_eq!(*image.get_pixel(5, 20), Rgba([0, 0, 255, 255]));
+        assert_eq!(*image.get_pixel(20, 35), Rgba([0, 0, 255, 255]));
+        assert_eq!(*image.get_pixel(20, 20), Rgba([255, 255, 255, 255]));
+    }
+
+    #[test]
+    fn translucent_colours_blend_with_the_screenshot() {
+        let mut image = RgbaImage::from_pixel(20, 20, Rgba([255, 255, 255, 255]));
+        composite(
+            &mut image,
+            &[Annotation {
+                color: "#00000080".to_string(),
+                ..annotation(Shape::Arrow {
+                    from: Point { x: 2.0, y: 10.0 },
+                    to: Point { x: 18.0, y: 10.0 },
+                })
+            }],
+        );
+
+        let shaft = image.get_pixel(5, 10);
+        assert!(shaft[0] > 100 && shaft[0] < 155, "{:?}", shaft);
+        assert_eq!(shaft[3], 255);
+    }
+
+    #[test]
+    fn parse_color_accepts_rgb_and_rgba_hex() {
+        assert_eq!(parse_color("#FF8000"), Some(Rgba([255, 128, 0, 255])));
+        assert_eq!(parse_color("#ff800040"), Some(Rgba([255, 128, 0, 64])));
+        assert_eq!(parse_color("red"), None);
+        assert_eq!(parse_color("#FF80"), None);
+    }
+
+    fn checkerboard(size: u32) -> RgbaImage {
+        RgbaImage::from_fn(size, size, |x, y| {
+            if (x + y) % 2 == 0 {
+                Rgba([0, 0, 0, 255])
+            } else {
+                Rgba([255, 255, 255, 255])
+            }
+        })
+    }
+
+    fn redaction(style: RedactionStyle) -> Redaction {
+        Redaction {
+            rect: Rect {
+                x: 8.0,
+                y: 8.0,
+                width: 24.0,
+                height: 24.0,
+            },
+            style,
+        }
+    }
+
+    #[test]
+    fn redactions_default_to_pixelate() {
+        let parsed: Redaction =
+            serde_json::from_str(r#"{"rect":{"x":1,"y":2,"width":3,"height":4}}"#).unwrap();
+        assert_eq!(parsed.style, RedactionStyle::Pixelate);
+        assert_eq!(
+            serde_json::to_value(redaction(RedactionStyle::Blur)).unwrap()["style"],
+            "blur"
+        );
+    }
+
+    #[test]
+    fn pixelate_flattens_blocks_and_leaves_the_rest_alone() {
+        let original = checkerboard(40);
+        let mut image = original.clone();
+        assert!(redact(&mut image, &[redaction(RedactionStyle::Pixelate)]));
+
+        assert_eq!(image.get_pixel(8, 8), image.get_pixel(19, 19));
+        assert_ne!(image.get_pixel(8, 8), original.get_pixel(8, 8));
+        assert_eq!(image.get_pixel(7, 7), original.get_pixel(7, 7));
+        assert_eq!(image.get_pixel(32, 32), original.get_pixel(32, 32));
+    }
+
+    #[test]
+    fn blur_smooths_inside_the_area_only() {
+        let original = checkerboard(40);
+        let mut image = original.clone();
+        assert!(redact(&mut image, &[redaction(RedactionStyle::Blur)]));
+
+        let centre = image.get_pixel(20, 20).0[0];
+        assert!((64..=192).contains(&centre), "centre was {}", centre);
+        assert_eq!(image.get_pixel(7, 20), original.get_pixel(7, 20));
+    }
+
+    #[test]
+    fn redactions_outside_the_image_change_nothing() {
+        let mut image = checkerboard(40);
+        let outside = redaction(RedactionStyle::Pixelate).relative_to((100, 0));
+        assert!(!redact(&mut image, &[outside]));
+        assert_eq!(image, checkerboard(40));
+    }
+
+    #[test]
+    fn step_badges_sit_beside_the_click_and_inside_the_image() {
+        let style = StepBadgeStyle {
+            enabled: true,
+            size: 10.0,
+            ..StepBadgeStyle::default()
+        };
+        assert_eq!(
+            style.position(Some((100, 80)), 200, 200),
+            Point { x: 85.0, y: 65.0 }
+        );
+        assert_eq!(
+            style.position(Some((2, 2)), 200, 200),
+            Point { x: 10.0, y: 10.0 }
+        );
+        assert_eq!(style.position(None, 200, 200), Point { x: 15.0, y: 15.0 });
+
+        let top_right = StepBadgeStyle {
+            placement: BadgePlacement::TopRight,
+            ..style
+        };
+        assert_eq!(
+            top_right.position(Some((100, 80)), 200, 200),
+            Point { x: 185.0, y: 15.0 }
+        );
+    }
+
+    #[test]
+    fn step_badge_is_drawn_in_the_badge_colour() {
+        let mut image = RgbaImage::from_pixel(100, 100, Rgba([255, 255, 255, 255]));
+        let style = StepBadgeStyle {
+            enabled: true,
+            color: "#00FF00".to_string(),
+            size: 12.0,
+            placement: BadgePlacement::TopLeft,
+        };
+        draw_step_badge(&mut image, 3, None, &style);
+
+        // Off-centre so the digit (drawn only when a font is found) is missed
+        assert_eq!(*image.get_pixel(8, 18), Rgba([0, 255, 0, 255]));
+        assert_eq!(*image.get_pixel(60, 60), Rgba([255, 255, 255, 255]));
+    }
+
+    #[test]
+    fn click_highlight_is_a_ring_around_a_dot() {
+        let white = Rgba([255, 255, 255, 255]);
+        let mut image = RgbaImage::from_pixel(100, 100, white);
+        let style = ClickHighlightStyle {
+            color: "#0000FF".to_string(),
+            radius: 20.0,
+            width: 4.0,
+            ..ClickHighlightStyle::default()
+        };
+        draw_click_highlight(&mut image, Point { x: 50.0, y: 50.0 }, &style);
+
+        let blue = Rgba([0, 0, 255, 255]);
+        assert_eq!(*image.get_pixel(50, 50), blue);
+        assert_eq!(*image.get_pixel(70, 50), blue);
+        assert_eq!(*image.get_pixel(60, 50), white);
+        assert_eq!(*image.get_pixel(95, 50), white);
+    }
+
+    #[test]
+    fn arrow_highlight_points_at_the_click_from_inside_the_image() {
+        let white = Rgba([255, 255, 255, 255]);
+        let style = ClickHighlightStyle {
+            shape: HighlightShape::Arrow,
+            color: "#0000FF".to_string(),
+            radius: 20.0,
+            width: 4.0,
+            ..ClickHighlightStyle::default()
+        };
+        let blue = Rgba([0, 0, 255, 255]);
+
+        let mut image = RgbaImage::from_pixel(100, 100, white);
+        draw_click_highlight(&mut image, Point { x: 30.0, y: 30.0 }, &style);
+        assert_eq!(*image.get_pixel(60, 60), blue);
+        assert_eq!(*image.get_pixel(20, 20), white);
+
+        // Near the bottom-right corner it comes from the top left instead
+        let mut image = RgbaImage::from_pixel(100, 100, white);
+        draw_click_highlight(&mut image, Point { x: 90.0, y: 90.0 }, &style);
+        assert_eq!(*image.get_pixel(60, 60), blue);
+    }
+
+    #[test]
+    fn spotlight_highlight_dims_everything_but_the_click() {
+        let mut image = RgbaImage::from_pixel(100, 100, Rgba([200, 200, 200, 255]));
+        let style = ClickHighlightStyle {
+            shape: HighlightShape::Spotlight,
+            radius: 20.0,
+            width: 2.0,
+            ..ClickHighlightStyle::default()
+        };
+        draw_click_highlight(&mut image, Point { x: 50.0, y: 50.0 }, &style);
+
+        assert_eq!(*image.get_pixel(50, 50), Rgba([200, 200, 200, 255]));
+        assert_eq!(*image.get_pixel(5, 5), Rgba([90, 90, 90, 255]));
+    }
+
+    #[test]
+    fn auto_contrast_highlight_swaps_colours_that_blend_in() {
+        let style = ClickHighlightStyle {
+            color: "#202020".to_string(),
+            radius: 20.0,
+            width: 4.0,
+            auto_contrast: true,
+            ..ClickHighlightStyle::default()
+        };
+        let at = Point { x: 50.0, y: 50.0 };
+
+        // A dark ring on a dark IDE theme turns light
+        let mut image = RgbaImage::from_pixel(100, 100, Rgba([30, 30, 30, 255]));
+        draw_click_highlight(&mut image, at, &style);
+        assert_eq!(*image.get_pixel(70, 50), AUTO_COLOR_ON_DARK);
+
+        // but already stands out on a white page
+        let mut image = RgbaImage::from_pixel(100, 100, Rgba([255, 255, 255, 255]));
+        draw_click_highlight(&mut image, at, &style);
+        assert_eq!(*image.get_pixel(70, 50), Rgba([32, 32, 32, 255]));
+
+        // A pale ring on a white page turns dark
+        let pale = ClickHighlightStyle {
+            color: "#F0F0F0".to_string(),
+            ..style
+        };
+        draw_click_highlight(&mut image, at, &pale);
+        assert_eq!(*image.get_pixel(70, 50), AUTO_COLOR_ON_LIGHT);
+    }
+}
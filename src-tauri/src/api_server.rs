@@ -0,0 +1,482 @@
+//! Optional HTTP API on localhost for scripts and internal tools.
+//!
+//! When `ApiServerSettings::enabled` is on, an axum server listens on
+//! `127.0.0.1:<port>` and serves the recording library as JSON: recordings
+//! and their steps, step screenshots and Markdown exports, plus renaming,
+//! step edits and saved documentation. Every request needs
+//! `Authorization: Bearer <token>`; the token is generated on first start and
+//! kept in the OS keychain through `credentials`. The same server answers
+//! MCP clients on `/mcp` (see `mcp`) and streams recorder events over a
+//! WebSocket on `/api/v1/events` (see `event_stream`).
+//!
+//! Locked recordings stay read-only here too: the database triggers reject
+//! the edit and the request fails with 409.
+
+use crate::credentials;
+use crate::database::{Database, Recording, RecordingWithSteps, Step};
+use crate::db_worker::DatabaseWorker;
+use crate::event_stream;
+use crate::mcp;
+use crate::settings::SettingsState;
+use axum::extract::{Path, Request, State};
+use axum::http::{header, StatusCode};
+use axum::middleware::{self, Next};
+use axum::response::{IntoResponse, Response};
+use axum::routing::{get, post, put};
+use axum::{Json, Router};
+use serde::{Deserialize, Serialize};
+use std::net::{Ipv4Addr, SocketAddr};
+use std::sync::{Mutex, RwLock};
+use tauri::{AppHandle, Manager};
+use tokio::sync::oneshot;
+
+/// Keychain entry holding the bearer token
+const TOKEN_CREDENTIAL: &str = "api_server_token";
+pub const DEFAULT_PORT: u16 = 47821;
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ApiServerSettings {
+    /// Start the server with the app
+    pub enabled: bool,
+    /// Port on 127.0.0.1
+    pub port: u16,
+}
+
+impl Default for ApiServerSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            port: DEFAULT_PORT,
+        }
+    }
+}
+
+struct RunningServer {
+    address: SocketAddr,
+    shutdown: oneshot::Sender<()>,
+}
+
+static SERVER: Mutex<Option<RunningServer>> = Mutex::new(None);
+/// The token requests must carry, loaded from the keychain on start
+static TOKEN: RwLock<Option<String>> = RwLock::new(None);
+
+/// The stored token, or a new one when there is none yet
+fn load_or_create_token() -> Result<String, String> {
+    if let Some(token) = credentials::get(TOKEN_CREDENTIAL)? {
+        return Ok(token);
+    }
+    let token = new_token();
+    credentials::set(TOKEN_CREDENTIAL, &token)?;
+    Ok(token)
+}
+
+fn new_token() -> String {
+    format!(
+        "{}{}",
+        uuid::Uuid::new_v4().simple(),
+        uuid::Uuid::new_v4().simple()
+    )
+}
+
+/// Listen on `port`, replacing a server already running on another port
+pub async fn start(app: AppHandle, port: u16) -> Result<SocketAddr, String> {
+    if let Some(address) = address() {
+        if address.port() == port {
+            return Ok(address);
+        }
+        stop();
+    }
+    let token = load_or_create_token()?;
+    let listener = tokio::net::TcpListener::bind((Ipv4Addr::LOCALHOST, port))
+        .await
+        .map_err(|e| format!("Failed to listen on port {}: {}", port, e))?;
+    let address = listener.local_addr().map_err(|e| e.to_string())?;
+    *TOKEN.write().unwrap() = Some(token);
+
+    let (shutdown, shutdown_rx) = oneshot::channel();
+    *SERVER.lock().unwrap() = Some(RunningServer { address, shutdown });
+    tauri::async_runtime::spawn(async move {
+        let served = axum::serve(listener, router(app))
+            .with_graceful_shutdown(async {
+                let _ = shutdown_rx.await;
+            })
+            .await;
+        if let Err(e) = served {
+            tracing::error!("API server stopped: {}", e);
+        }
+    });
+    tracing::info!(%address, "API server listening");
+    Ok(address)
+}
+
+/// Stop the server, if it's running
+pub fn stop() {
+    if let Some(server) = SERVER.lock().unwrap().take() {
+        let _ = server.shutdown.send(());
+        tracing::info!(address = %server.address, "API server stopped");
+    }
+}
+
+fn address() -> Option<SocketAddr> {
+    SERVER.lock().unwrap().as_ref().map(|server| server.address)
+}
+
+fn router(app: AppHandle) -> Router {
+    Router::new()
+        .route(
+            "/api/v1/recordings",
+            get(list_recordings).post(create_recording),
+        )
+        .route(
+            "/api/v1/recordings/{id}",
+            get(get_recording).patch(update_recording),
+        )
+        .route(
+            "/api/v1/recordings/{id}/documentation",
+            put(save_documentation),
+        )
+        .route(
+            "/api/v1/recordings/{id}/export/markdown",
+            get(export_markdown),
+        )
+        .route(
+            "/api/v1/steps/{id}",
+            get(get_step).patch(update_step).delete(delete_step),
+        )
+        .route("/api/v1/steps/{id}/screenshot", get(step_screenshot))
+        .route(event_stream::EVENTS_PATH, get(event_stream::events_socket))
+        .route("/mcp", post(mcp_message))
+        .layer(middleware::from_fn(require_token))
+        .with_state(app)
+}
+
+async fn require_token(request: Request, next: Next) -> Response {
+    let provided = request
+        .headers()
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .or_else(|| query_token(&request));
+    let authorized = match (provided, TOKEN.read().unwrap().as_deref()) {
+        (Some(provided), Some(token)) => tokens_match(provided.trim(), token),
+        _ => false,
+    };
+    if !authorized {
+        return ApiError(StatusCode::UNAUTHORIZED, "Missing or invalid token".into())
+            .into_response();
+    }
+    next.run(request).await
+}
+
+/// `?token=` on the event stream, for WebSocket clients that can't set headers
+fn query_token(request: &Request) -> Option<&str> {
+    if request.uri().path() != event_stream::EVENTS_PATH {
+        return None;
+    }
+    request
+        .uri()
+        .query()?
+        .split('&')
+        .find_map(|pair| pair.strip_prefix("token="))
+}
+
+/// Compares in time independent of where the tokens differ
+fn tokens_match(provided: &str, token: &str) -> bool {
+    provided.len() == token.len()
+        && provided
+            .bytes()
+            .zip(token.bytes())
+            .fold(0, |diff, (a, b)| diff | (a ^ b))
+            == 0
+}
+
+/// A failed request, answered as `{"error": "..."}`
+struct ApiError(StatusCode, String);
+
+type ApiResult<T> = Result<T, ApiError>;
+
+impl ApiError {
+    fn not_found(what: &str, id: &str) -> Self {
+        Self(StatusCode::NOT_FOUND, format!("{} not found: {}", what, id))
+    }
+}
+
+impl From<rusqlite::Error> for ApiError {
+    fn from(error: rusqlite::Error) -> Self {
+        let status = match &error {
+            // The locked-recording triggers abort with a constraint error
+            rusqlite::Error::SqliteFailure(e, _)
+                if e.code == rusqlite::ErrorCode::ConstraintViolation =>
+            {
+                StatusCode::CONFLICT
+            }
+            _ => StatusCode::INTERNAL_SERVER_ERROR,
+        };
+        Self(status, error.to_string())
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        (self.0, Json(serde_json::json!({ "error": self.1 }))).into_response()
+    }
+}
+
+/// Run `f` on the database worker, keeping its SQLite error for the status
+async fn with_db<T: Send + 'static>(
+    app: &AppHandle,
+    f: impl FnOnce(&Database) -> rusqlite::Result<T> + Send + 'static,
+) -> ApiResult<T> {
+    let result = app
+        .state::<DatabaseWorker>()
+        .run(move |db| Ok(f(db)))
+        .await
+        .map_err(|e| ApiError(StatusCode::INTERNAL_SERVER_ERROR, e))?;
+    Ok(result?)
+}
+
+async fn find_step(app: &AppHandle, id: &str) -> ApiResult<Step> {
+    let step_id = id.to_string();
+    with_db(app, move |db| db.get_step(&step_id))
+        .await?
+        .ok_or_else(|| ApiError::not_found("Step", id))
+}
+
+async fn find_recording(app: &AppHandle, id: &str) -> ApiResult<RecordingWithSteps> {
+    let recording_id = id.to_string();
+    with_db(app, move |db| db.get_recording(&recording_id))
+        .await?
+        .ok_or_else(|| ApiError::not_found("Recording", id))
+}
+
+async fn list_recordings(State(app): State<AppHandle>) -> ApiResult<Json<Vec<Recording>>> {
+    with_db(&app, |db| db.list_recordings()).await.map(Json)
+}
+
+#[derive(Deserialize)]
+struct NewRecording {
+    name: String,
+}
+
+async fn create_recording(
+    State(app): State<AppHandle>,
+    Json(body): Json<NewRecording>,
+) -> ApiResult<(StatusCode, Json<serde_json::Value>)> {
+    let id = with_db(&app, move |db| db.create_recording(body.name)).await?;
+    Ok((StatusCode::CREATED, Json(serde_json::json!({ "id": id }))))
+}
+
+async fn get_recording(
+    State(app): State<AppHandle>,
+    Path(id): Path<String>,
+) -> ApiResult<Json<RecordingWithSteps>> {
+    find_recording(&app, &id).await.map(Json)
+}
+
+#[derive(Deserialize)]
+struct RecordingPatch {
+    name: String,
+}
+
+async fn update_recording(
+    State(app): State<AppHandle>,
+    Path(id): Path<String>,
+    Json(patch): Json<RecordingPatch>,
+) -> ApiResult<StatusCode> {
+    find_recording(&app, &id).await?;
+    with_db(&app, move |db| db.update_recording_name(&id, &patch.name)).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[derive(Deserialize)]
+struct Documentation {
+    documentation: String,
+}
+
+async fn save_documentation(
+    State(app): State<AppHandle>,
+    Path(id): Path<String>,
+    Json(body): Json<Documentation>,
+) -> ApiResult<StatusCode> {
+    find_recording(&app, &id).await?;
+    with_db(&app, move |db| {
+        db.save_documentation(&id, &body.documentation)
+    })
+    .await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+async fn export_markdown(State(app): State<AppHandle>, Path(id): Path<String>) -> Response {
+    let (locale, styles) = {
+        let settings = app.state::<SettingsState>();
+        let settings = settings.0.lock().unwrap();
+        (
+            settings.locale,
+            crate::ExportStyles::from_settings(&settings),
+        )
+    };
+    match crate::recording_markdown(&app, id, locale, styles).await {
+        Ok(markdown) => (
+            [(header::CONTENT_TYPE, "text/markdown; charset=utf-8")],
+            markdown,
+        )
+            .into_response(),
+        Err(e) if e.starts_with("Recording not found") => {
+            ApiError(StatusCode::NOT_FOUND, e).into_response()
+        }
+        Err(e) => ApiError(StatusCode::INTERNAL_SERVER_ERROR, e).into_response(),
+    }
+}
+
+async fn get_step(State(app): State<AppHandle>, Path(id): Path<String>) -> ApiResult<Json<Step>> {
+    find_step(&app, &id).await.map(Json)
+}
+
+#[derive(Deserialize)]
+struct StepPatch {
+    title: Option<String>,
+    description: Option<String>,
+}
+
+async fn update_step(
+    State(app): State<AppHandle>,
+    Path(id): Path<String>,
+    Json(patch): Json<StepPatch>,
+) -> ApiResult<Json<Step>> {
+    find_step(&app, &id).await?;
+    let step_id = id.clone();
+    with_db(&app, move |db| {
+        if let Some(title) = &patch.title {
+            db.update_step_title(&step_id, title)?;
+        }
+        if let Some(description) = &patch.description {
+            db.update_step_description(&step_id, description)?;
+        }
+        Ok(())
+    })
+    .await?;
+    find_step(&app, &id).await.map(Json)
+}
+
+async fn delete_step(
+    State(app): State<AppHandle>,
+    Path(id): Path<String>,
+) -> ApiResult<StatusCode> {
+    find_step(&app, &id).await?;
+    with_db(&app, move |db| db.delete_step(&id)).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+async fn step_screenshot(State(app): State<AppHandle>, Path(id): Path<String>) -> Response {
+    let path = match find_step(&app, &id).await {
+        Ok(step) => step.screenshot_path,
+        Err(e) => return e.into_response(),
+    };
+    let Some(path) = path else {
+        return ApiError::not_found("Screenshot of step", &id).into_response();
+    };
+    match tokio::fs::read(&path).await {
+        Ok(bytes) => ([(header::CONTENT_TYPE, content_type(&path))], bytes).into_response(),
+        Err(e) => ApiError(
+            StatusCode::NOT_FOUND,
+            format!("Failed to read screenshot of step {}: {}", id, e),
+        )
+        .into_response(),
+    }
+}
+
+async fn mcp_message(
+    State(app): State<AppHandle>,
+    Json(message): Json<serde_json::Value>,
+) -> Response {
+    let response = tauri::async_runtime::spawn_blocking(move || mcp::handle(&app, message)).await;
+    match response {
+        Ok(Some(response)) => Json(response).into_response(),
+        Ok(None) => StatusCode::ACCEPTED.into_response(),
+        Err(e) => ApiError(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()).into_response(),
+    }
+}
+
+fn content_type(path: &str) -> &'static str {
+    let extension = std::path::Path::new(path)
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .map(str::to_ascii_lowercase);
+    match extension.as_deref() {
+        Some("png") => "image/png",
+        Some("webp") => "image/webp",
+        Some("jpg") | Some("jpeg") => "image/jpeg",
+        _ => "application/octet-stream",
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct ApiServerStatus {
+    pub running: bool,
+    /// e.g. "http://127.0.0.1:47821", while running
+    pub url: Option<String>,
+    /// The bearer token, while running
+    pub token: Option<String>,
+}
+
+fn status() -> ApiServerStatus {
+    let url = address().map(|address| format!("http://{}", address));
+    ApiServerStatus {
+        running: url.is_some(),
+        token: url.as_ref().and_then(|_| TOKEN.read().unwrap().clone()),
+        url,
+    }
+}
+
+#[tauri::command]
+pub fn get_api_server_status() -> ApiServerStatus {
+    status()
+}
+
+/// Turn the server on or off and remember the choice (and `port`, when
+/// given) in the settings, so it starts with the app next time
+#[tauri::command]
+pub async fn set_api_server_enabled(
+    app: AppHandle,
+    db: tauri::State<'_, DatabaseWorker>,
+    settings: tauri::State<'_, SettingsState>,
+    enabled: bool,
+    port: Option<u16>,
+) -> Result<ApiServerStatus, String> {
+    let port = port.unwrap_or_else(|| settings.0.lock().unwrap().api_server.port);
+    if enabled {
+        start(app.clone(), port).await?;
+    } else {
+        stop();
+    }
+    let patch = serde_json::Map::from_iter([(
+        "api_server".to_string(),
+        serde_json::json!({ "enabled": enabled, "port": port }),
+    )]);
+    crate::update_settings(app, db, patch).await?;
+    Ok(status())
+}
+
+/// Replace the token; clients using the old one are refused from now on
+#[tauri::command]
+pub fn regenerate_api_server_token() -> Result<ApiServerStatus, String> {
+    let token = new_token();
+    credentials::set(TOKEN_CREDENTIAL, &token)?;
+    *TOKEN.write().unwrap() = Some(token);
+    tracing::info!("API server token replaced");
+    Ok(status())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tokens_must_match_exactly() {
+        assert!(tokens_match("abc123", "abc123"));
+        assert!(!tokens_match("abc124", "abc123"));
+        assert!(!tokens_match("abc12", "abc123"));
+        assert!(!tokens_match("", "abc123"));
+    }
+}
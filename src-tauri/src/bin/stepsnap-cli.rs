@@ -0,0 +1,5 @@
+//! Headless companion to the app; see `stepsnap_lib::cli`.
+
+fn main() -> std::process::ExitCode {
+    stepsnap_lib::cli::main()
+}
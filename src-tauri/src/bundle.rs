@@ -0,0 +1,308 @@
+//! Recording bundles: one recording and its images in a single zip file.
+//!
+//! A bundle holds `manifest.json` (the recording and its steps as stored)
+//! and the steps' screenshots, element crops and lossless originals under
+//! `images/`, with the steps' paths pointing at those entries. Importing one
+//! creates a new recording with fresh ids, so the same bundle can be
+//! imported twice, or into the library it came from.
+
+use crate::database::{Database, Recording, Step, StepInput};
+use crate::analytics;
+use crate::db_worker::DatabaseWorker;
+use serde::{Deserialize, Serialize};
+use std::fs::{self, File};
+use std::io;
+use std::path::{Path, PathBuf};
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipArchive, ZipWriter};
+
+const MANIFEST: &str = "manifest.json";
+/// Bumped when a change means older versions can't read the bundle
+const FORMAT_VERSION: u32 = 1;
+/// File extension bundles are saved with
+pub const EXTENSION: &str = "stepsnap";
+
+#[derive(Serialize, Deserialize)]
+struct Manifest {
+    format: u32,
+    /// Version of the app that wrote the bundle
+    app_version: String,
+    recording: Recording,
+    /// Image paths are entries of the bundle
+    steps: Vec<Step>,
+}
+
+/// Write `recording_id` to a bundle at `path`. Returns the number of steps.
+pub fn export(db: &Database, recording_id: &str, path: &Path) -> Result<usize, String> {
+    let recording = db
+        .get_recording(recording_id)
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| format!("Recording not found: {}", recording_id))?;
+    let file =
+        File::create(path).map_err(|e| format!("Failed to create {}: {}", path.display(), e))?;
+    let mut zip = ZipWriter::new(file);
+    let write = |zip: &mut ZipWriter<File>| -> Result<usize, Box<dyn std::error::Error>> {
+        let options = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+        // Images are already compressed
+        let stored = SimpleFileOptions::default().compression_method(CompressionMethod::Stored);
+        let mut steps = recording.steps.clone();
+        for (index, step) in steps.iter_mut().enumerate() {
+            let images = [
+                (&mut step.screenshot_path, ""),
+                (&mut step.element_screenshot_path, "_element"),
+                (&mut step.original_path, "_original"),
+            ];
+            for (path, suffix) in images {
+                let Some(source) = path.take() else {
+                    continue;
+                };
+                let source = PathBuf::from(source);
+                let extension = source
+                    .extension()
+                    .and_then(|ext| ext.to_str())
+                    .unwrap_or("jpg");
+                let name = format!("images/{}{}.{}", index, suffix, extension);
+                match File::open(&source) {
+                    Ok(mut image) => {
+                        zip.start_file(name.as_str(), stored)?;
+                        io::copy(&mut image, zip)?;
+                        *path = Some(name);
+                    }
+                    Err(e) => tracing::warn!("Left {} out of the bundle: {}", source.display(), e),
+                }
+            }
+            // Frames and clips only the editor's AI pass uses aren't bundled
+            step.screenshot_after_path = None;
+            step.clip_path = None;
+        }
+        let manifest = Manifest {
+            format: FORMAT_VERSION,
+            app_version: env!("CARGO_PKG_VERSION").to_string(),
+            recording: recording.recording.clone(),
+            steps,
+        };
+        zip.start_file(MANIFEST, options)?;
+        serde_json::to_writer_pretty(&mut *zip, &manifest)?;
+        Ok(manifest.steps.len())
+    };
+    let step_count = write(&mut zip)
+        .and_then(|count| {
+            zip.finish()?;
+            Ok(count)
+        })
+        .map_err(|e| format!("Failed to write bundle: {}", e))?;
+    tracing::info!(
+        recording_id,
+        path = %path.display(),
+        steps = step_count,
+        "Recording bundle exported"
+    );
+    Ok(step_count)
+}
+
+/// Add the recording in the bundle at `path` to the library. Returns the
+/// new recording's id.
+pub fn import(db: &Database, path: &Path) -> Result<String, String> {
+    let file = File::open(path).map_err(|e| format!("Failed to open {}: {}", path.display(), e))?;
+    let mut zip = ZipArchive::new(file).map_err(|e| format!("Not a recording bundle: {}", e))?;
+    let manifest: Manifest = {
+        let entry = zip
+            .by_name(MANIFEST)
+            .map_err(|_| "Not a recording bundle: it has no manifest".to_string())?;
+        serde_json::from_reader(entry).map_err(|e| format!("Invalid bundle manifest: {}", e))?
+    };
+    if manifest.format > FORMAT_VERSION {
+        return Err(format!(
+            "This bundle was made by a newer version ({}); update to import it",
+            manifest.app_version
+        ));
+    }
+
+    // The database moves the images out of here once the steps are saved
+    let staging = std::env::temp_dir().join(format!("stepsnap_import_{}", uuid::Uuid::new_v4()));
+    fs::create_dir_all(&staging).map_err(|e| e.to_string())?;
+    let result = stage_and_save(db, &mut zip, manifest, &staging);
+    let _ = fs::remove_dir_all(&staging);
+    let recording_id = result?;
+    tracing::info!(recording_id = %recording_id, path = %path.display(), "Recording bundle imported");
+    Ok(recording_id)
+}
+
+fn stage_and_save(
+    db: &Database,
+    zip: &mut ZipArchive<File>,
+    manifest: Manifest,
+    staging: &Path,
+) -> Result<String, String> {
+    let mut extract = |name: Option<&str>| -> Result<Option<String>, String> {
+        let Some(name) = name else {
+            return Ok(None);
+        };
+        let mut entry = zip
+            .by_name(name)
+            .map_err(|e| format!("Bundle is missing {}: {}", name, e))?;
+        // Never trust a path from the archive to stay inside `staging`
+        let Some(file_name) = entry
+            .enclosed_name()
+            .and_then(|path| path.file_name().map(PathBuf::from))
+        else {
+            return Err(format!("Invalid image path in bundle: {}", name));
+        };
+        let target = staging.join(file_name);
+        let mut out = File::create(&target).map_err(|e| e.to_string())?;
+        io::copy(&mut entry, &mut out).map_err(|e| e.to_string())?;
+        Ok(Some(target.to_string_lossy().to_string()))
+    };
+
+    let mut inputs = Vec::with_capacity(manifest.steps.len());
+    for step in manifest.steps {
+        let screenshot = extract(step.screenshot_path.as_deref())?;
+        let element_screenshot = extract(step.element_screenshot_path.as_deref())?;
+        let original = extract(step.original_path.as_deref())?;
+        inputs.push(step_input(step, screenshot, element_screenshot, original));
+    }
+
+    let recording = manifest.recording;
+    let recording_id = db
+        .create_recording(recording.name)
+        .map_err(|e| e.to_string())?;
+    let saved = db.save_steps(&recording_id, inputs).and_then(|()| {
+        match recording.documentation.as_deref() {
+            Some(documentation) => db.save_documentation(&recording_id, documentation),
+            None => Ok(()),
+        }
+    });
+    if let Err(e) = saved {
+        if let Ok(cleanup) = db.delete_recording(&recording_id) {
+            for file in cleanup.files {
+                let _ = fs::remove_file(file);
+            }
+        }
+        return Err(format!("Failed to import the recording: {}", e));
+    }
+    Ok(recording_id)
+}
+
+/// `step` as a new step with the given images
+fn step_input(
+    step: Step,
+    screenshot: Option<String>,
+    element_screenshot: Option<String>,
+    screenshot_original: Option<String>,
+) -> StepInput {
+    StepInput {
+        type_: step.type_,
+        x: step.x,
+        y: step.y,
+        text: step.text,
+        timestamp: step.timestamp,
+        screenshot,
+        element_name: step.element_name,
+        element_type: step.element_type,
+        element_value: step.element_value,
+        app_name: step.app_name,
+        description: step.description,
+        is_cropped: step.is_cropped,
+        order_index: Some(step.order_index),
+        title: step.title,
+        screenshot_is_permanent: None,
+        input_source: step.input_source,
+        screenshot_after: None,
+        identified_element_json: step.identified_element_json,
+        clip_path: None,
+        element_bounds: step.element_bounds,
+        page_url: step.page_url,
+        page_title: step.page_title,
+        automation_id: step.automation_id,
+        class_name: step.class_name,
+        element_path: step.element_path,
+        process_name: step.process_name,
+        exe_path: step.exe_path,
+        window_title: step.window_title,
+        breadcrumb: step.breadcrumb,
+        action: step.action,
+        element_screenshot,
+        ax_tree_json: step.ax_tree_json,
+        ocr_text: step.ocr_text,
+        ocr_status: step.ocr_status,
+        ocr_lines: step.ocr_lines,
+        annotations: step.annotations,
+        redactions: step.redactions,
+        click_marker: step.click_marker,
+        scale_factor: step.scale_factor,
+        is_duplicate: step.is_duplicate,
+        screenshot_original,
+    }
+}
+
+#[tauri::command]
+pub async fn export_recording_bundle(
+    db: tauri::State<'_, DatabaseWorker>,
+    recording_id: String,
+    path: String,
+) -> Result<usize, String> {
+    analytics::track("bundle_export");
+    db.run(move |db| export(db, &recording_id, Path::new(&path)))
+        .await
+}
+
+/// Returns the id of the imported recording
+#[tauri::command]
+pub async fn import_recording_bundle(
+    db: tauri::State<'_, DatabaseWorker>,
+    path: String,
+) -> Result<String, String> {
+    analytics::track("bundle_import");
+    db.run(move |db| import(db, Path::new(&path))).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bundles_round_trip_with_their_images() {
+        let dir = std::env::temp_dir().join(format!("stepsnap_bundle_{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(&dir).unwrap();
+        let db = Database::new(dir.join("data")).unwrap();
+        let screenshot = dir.join("shot.png");
+        fs::write(&screenshot, b"png bytes").unwrap();
+        let recording_id = db.create_recording("Reset a password".to_string()).unwrap();
+        let step: StepInput = serde_json::from_value(serde_json::json!({
+            "type_": "click",
+            "x": 10,
+            "y": 20,
+            "text": null,
+            "timestamp": 1,
+            "screenshot": screenshot.to_string_lossy(),
+            "element_name": "Reset",
+            "element_type": null,
+            "element_value": null,
+            "app_name": "Admin",
+            "description": "Click Reset",
+            "is_cropped": null,
+            "order_index": null,
+            "title": null,
+            "screenshot_is_permanent": null,
+        }))
+        .unwrap();
+        db.save_steps(&recording_id, vec![step]).unwrap();
+
+        let bundle = dir.join("reset.stepsnap");
+        assert_eq!(export(&db, &recording_id, &bundle).unwrap(), 1);
+        let imported_id = import(&db, &bundle).unwrap();
+
+        let imported = db.get_recording(&imported_id).unwrap().unwrap();
+        assert_ne!(imported_id, recording_id);
+        assert_eq!(imported.recording.name, "Reset a password");
+        assert_eq!(imported.steps.len(), 1);
+        assert_eq!(
+            imported.steps[0].description.as_deref(),
+            Some("Click Reset")
+        );
+        let image = imported.steps[0].screenshot_path.as_deref().unwrap();
+        assert_eq!(fs::read(image).unwrap(), b"png bytes");
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,1007 @@
+//! Screen capture backends for the recorder and manual captures.
+//!
+//! xcap captures a monitor from scratch on every call, which costs 100-300 ms
+//! a step on high-resolution setups. The native backends keep a capture
+//! session open instead and hand out its latest frame: DXGI desktop
+//! duplication on Windows, a ScreenCaptureKit stream on macOS and a PipeWire
+//! screencast (through the desktop portal, so Wayland only) on Linux. They're
+//! opt-in through the `capture_backend` setting; a capture the native backend
+//! can't take (an unsupported session, a rotated display, a lost duplication)
+//! falls back to xcap.
+//!
+//! Sessions start on the first capture and end with `release`, called when a
+//! recording stops, so the OS's screen recording indicator doesn't stay on.
+
+use image::RgbaImage;
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use xcap::Monitor;
+
+/// Something that can capture a monitor's contents
+pub trait CaptureBackend: Send {
+    /// Named in logs
+    fn name(&self) -> &'static str;
+    /// The monitor's current contents, in physical pixels
+    fn capture(&mut self, monitor: &Monitor) -> Result<RgbaImage, String>;
+}
+
+/// Which backend captures screenshots
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CaptureBackendKind {
+    /// xcap, one capture per call
+    #[default]
+    Standard,
+    /// The platform's streaming capture API, falling back to xcap
+    Native,
+}
+
+/// xcap's per-call capture
+struct Xcap;
+
+impl CaptureBackend for Xcap {
+    fn name(&self) -> &'static str {
+        "xcap"
+    }
+
+    fn capture(&mut self, monitor: &Monitor) -> Result<RgbaImage, String> {
+        monitor.capture_image().map_err(|e| e.to_string())
+    }
+}
+
+static KIND: Mutex<CaptureBackendKind> = Mutex::new(CaptureBackendKind::Standard);
+/// The native backend, once started
+static NATIVE: Mutex<Option<Box<dyn CaptureBackend>>> = Mutex::new(None);
+/// Set when the native backend couldn't start, so it isn't retried on
+/// every capture
+static NATIVE_UNAVAILABLE: AtomicBool = AtomicBool::new(false);
+
+/// Switch backends; applied from the settings
+pub fn configure(kind: CaptureBackendKind) {
+    let previous = std::mem::replace(&mut *KIND.lock().unwrap(), kind);
+    if previous != kind {
+        release();
+        NATIVE_UNAVAILABLE.store(false, Ordering::Relaxed);
+    }
+}
+
+/// The configured backend
+pub fn kind() -> CaptureBackendKind {
+    *KIND.lock().unwrap()
+}
+
+/// End the native backend's capture sessions; the next capture starts them
+/// again
+pub fn release() {
+    if let Some(backend) = NATIVE.lock().unwrap().take() {
+        tracing::debug!("Stopped {} capture", backend.name());
+    }
+}
+
+/// Capture `monitor` with the configured backend
+pub fn capture(monitor: &Monitor) -> Result<RgbaImage, String> {
+    if *KIND.lock().unwrap() == CaptureBackendKind::Native {
+        let mut native = NATIVE.lock().unwrap();
+        if native.is_none() && !NATIVE_UNAVAILABLE.load(Ordering::Relaxed) {
+            match native_backend() {
+                Ok(backend) => {
+                    tracing::info!("Capturing with {}", backend.name());
+                    *native = Some(backend);
+                }
+                Err(e) => {
+                    NATIVE_UNAVAILABLE.store(true, Ordering::Relaxed);
+                    tracing::warn!("Native capture unavailable, using xcap: {}", e);
+                }
+            }
+        }
+        if let Some(backend) = native.as_mut() {
+            match backend.capture(monitor) {
+                Ok(image) => return Ok(image),
+                Err(e) => tracing::debug!("{} capture failed, using xcap: {}", backend.name(), e),
+            }
+        }
+    }
+    Xcap.capture(monitor)
+}
+
+#[cfg(target_os = "windows")]
+fn native_backend() -> Result<Box<dyn CaptureBackend>, String> {
+    Ok(Box::new(dxgi::Dxgi::new()?))
+}
+
+#[cfg(target_os = "macos")]
+fn native_backend() -> Result<Box<dyn CaptureBackend>, String> {
+    Ok(Box::new(screencapturekit::ScreenCaptureKit::new()?))
+}
+
+#[cfg(target_os = "linux")]
+fn native_backend() -> Result<Box<dyn CaptureBackend>, String> {
+    Ok(Box::new(pipewire_impl::PipeWire::new()?))
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "macos", target_os = "linux")))]
+fn native_backend() -> Result<Box<dyn CaptureBackend>, String> {
+    Err("Not supported on this platform".to_string())
+}
+
+/// Byte order of a 4-byte pixel in a native frame
+#[derive(Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(
+    not(any(target_os = "windows", target_os = "macos", target_os = "linux")),
+    allow(dead_code)
+)]
+enum PixelOrder {
+    Bgra,
+    Rgba,
+}
+
+/// Copy a native frame, whose rows are `stride` bytes apart, into an opaque
+/// RGBA image
+#[cfg_attr(
+    not(any(target_os = "windows", target_os = "macos", target_os = "linux")),
+    allow(dead_code)
+)]
+fn to_rgba(width: u32, height: u32, stride: usize, bytes: &[u8], order: PixelOrder) -> RgbaImage {
+    let row_len = width as usize * 4;
+    let mut pixels = Vec::with_capacity(row_len * height as usize);
+    for row in bytes.chunks(stride).take(height as usize) {
+        for pixel in row[..row_len].chunks_exact(4) {
+            match order {
+                PixelOrder::Bgra => pixels.extend_from_slice(&[pixel[2], pixel[1], pixel[0], 255]),
+                PixelOrder::Rgba => pixels.extend_from_slice(&[pixel[0], pixel[1], pixel[2], 255]),
+            }
+        }
+    }
+    RgbaImage::from_raw(width, height, pixels).unwrap_or_default()
+}
+
+// ============================================================================
+// Windows: DXGI desktop duplication
+// ============================================================================
+
+#[cfg(target_os = "windows")]
+mod dxgi {
+    use super::{to_rgba, CaptureBackend, PixelOrder};
+    use image::RgbaImage;
+    use windows::core::Interface;
+    use windows::Win32::Foundation::{E_POINTER, HMODULE};
+    use windows::Win32::Graphics::Direct3D::D3D_DRIVER_TYPE_UNKNOWN;
+    use windows::Win32::Graphics::Direct3D11::*;
+    use windows::Win32::Graphics::Dxgi::Common::*;
+    use windows::Win32::Graphics::Dxgi::*;
+    use xcap::Monitor;
+
+    /// How long to wait for the first frame of a new duplication
+    const FIRST_FRAME_TIMEOUT_MS: u32 = 500;
+
+    /// A duplicated output, with the device of the adapter it's attached to
+    struct Output {
+        /// Top-left corner on the virtual desktop
+        origin: (i32, i32),
+        device: ID3D11Device,
+        context: ID3D11DeviceContext,
+        duplication: IDXGIOutputDuplication,
+        /// CPU-readable copy of the latest frame
+        staging: Option<ID3D11Texture2D>,
+    }
+
+    pub struct Dxgi {
+        outputs: Vec<Output>,
+    }
+
+    // The COM objects are only used by one thread at a time, behind the
+    // backend lock, which D3D11 devices and duplications allow
+    unsafe impl Send for Dxgi {}
+
+    impl Dxgi {
+        pub fn new() -> Result<Self, String> {
+            // Fails where DXGI isn't available at all, such as some remote
+            // sessions
+            unsafe { CreateDXGIFactory1::<IDXGIFactory1>() }.map_err(|e| e.to_string())?;
+            Ok(Self {
+                outputs: Vec::new(),
+            })
+        }
+    }
+
+    /// Start duplicating the output whose top-left corner is `origin`
+    unsafe fn open(origin: (i32, i32)) -> Result<Output, String> {
+        let factory: IDXGIFactory1 = CreateDXGIFactory1().map_err(|e| e.to_string())?;
+        for adapter in (0..).map_while(|i| factory.EnumAdapters1(i).ok()) {
+            for output in (0..).map_while(|i| adapter.EnumOutputs(i).ok()) {
+                let desc = output.GetDesc().map_err(|e| e.to_string())?;
+                let bounds = desc.DesktopCoordinates;
+                if (bounds.left, bounds.top) != origin {
+                    continue;
+                }
+                // Duplicated frames of rotated displays aren't rotated
+                if desc.Rotation != DXGI_MODE_ROTATION_IDENTITY
+                    && desc.Rotation != DXGI_MODE_ROTATION_UNSPECIFIED
+                {
+                    return Err("The display is rotated".to_string());
+                }
+
+                let (mut device, mut context) = (None, None);
+                D3D11CreateDevice(
+                    &adapter,
+                    D3D_DRIVER_TYPE_UNKNOWN,
+                    HMODULE::default(),
+                    D3D11_CREATE_DEVICE_BGRA_SUPPORT,
+                    None,
+                    D3D11_SDK_VERSION,
+                    Some(&mut device),
+                    None,
+                    Some(&mut context),
+                )
+                .map_err(|e| e.to_string())?;
+                let device = device.ok_or("D3D11CreateDevice returned no device")?;
+                let context = context.ok_or("D3D11CreateDevice returned no context")?;
+                let duplication = output
+                    .cast::<IDXGIOutput1>()
+                    .and_then(|output| output.DuplicateOutput(&device))
+                    .map_err(|e| e.to_string())?;
+                return Ok(Output {
+                    origin,
+                    device,
+                    context,
+                    duplication,
+                    staging: None,
+                });
+            }
+        }
+        Err(format!("No display output at {},{}", origin.0, origin.1))
+    }
+
+    impl Output {
+        /// The latest frame. Duplication only hands out a frame when the
+        /// screen changed, so with none pending the staged one is current.
+        unsafe fn frame(&mut self) -> windows::core::Result<RgbaImage> {
+            let timeout = if self.staging.is_some() {
+                0
+            } else {
+                FIRST_FRAME_TIMEOUT_MS
+            };
+            let mut info = DXGI_OUTDUPL_FRAME_INFO::default();
+            let mut resource: Option<IDXGIResource> = None;
+            match self
+                .duplication
+                .AcquireNextFrame(timeout, &mut info, &mut resource)
+            {
+                Ok(()) => {
+                    let staged = resource
+                        .ok_or_else(|| windows::core::Error::from(E_POINTER))
+                        .and_then(|resource| resource.cast::<ID3D11Texture2D>())
+                        .and_then(|texture| self.stage(&texture));
+                    // Release before reading, so the compositor isn't held up
+                    let _ = self.duplication.ReleaseFrame();
+                    staged?;
+                }
+                Err(e) if e.code() == DXGI_ERROR_WAIT_TIMEOUT && self.staging.is_some() => {}
+                Err(e) => return Err(e),
+            }
+            self.read_staged()
+        }
+
+        /// Copy `texture` to the staging texture, creating it to match
+        unsafe fn stage(&mut self, texture: &ID3D11Texture2D) -> windows::core::Result<()> {
+            let mut desc = D3D11_TEXTURE2D_DESC::default();
+            texture.GetDesc(&mut desc);
+            // HDR outputs duplicate in other formats
+            if desc.Format != DXGI_FORMAT_B8G8R8A8_UNORM {
+                return Err(DXGI_ERROR_UNSUPPORTED.into());
+            }
+            let matches = self.staging.as_ref().is_some_and(|staging| {
+                let mut staged = D3D11_TEXTURE2D_DESC::default();
+                staging.GetDesc(&mut staged);
+                (staged.Width, staged.Height) == (desc.Width, desc.Height)
+            });
+            if !matches {
+                desc.MipLevels = 1;
+                desc.ArraySize = 1;
+                desc.SampleDesc = DXGI_SAMPLE_DESC {
+                    Count: 1,
+                    Quality: 0,
+                };
+                desc.Usage = D3D11_USAGE_STAGING;
+                desc.BindFlags = 0;
+                desc.CPUAccessFlags = D3D11_CPU_ACCESS_READ.0 as u32;
+                desc.MiscFlags = 0;
+                let mut staging = None;
+                self.device
+                    .CreateTexture2D(&desc, None, Some(&mut staging))?;
+                self.staging = staging;
+            }
+            if let Some(staging) = &self.staging {
+                self.context.CopyResource(staging, texture);
+            }
+            Ok(())
+        }
+
+        unsafe fn read_staged(&self) -> windows::core::Result<RgbaImage> {
+            let staging = self
+                .staging
+                .as_ref()
+                .ok_or_else(|| windows::core::Error::from(E_POINTER))?;
+            let mut desc = D3D11_TEXTURE2D_DESC::default();
+            staging.GetDesc(&mut desc);
+            let mut mapped = D3D11_MAPPED_SUBRESOURCE::default();
+            self.context
+                .Map(staging, 0, D3D11_MAP_READ, 0, Some(&mut mapped))?;
+            let bytes = std::slice::from_raw_parts(
+                mapped.pData as *const u8,
+                mapped.RowPitch as usize * desc.Height as usize,
+            );
+            let image = to_rgba(
+                desc.Width,
+                desc.Height,
+                mapped.RowPitch as usize,
+                bytes,
+                PixelOrder::Bgra,
+            );
+            self.context.Unmap(staging, 0);
+            Ok(image)
+        }
+    }
+
+    impl CaptureBackend for Dxgi {
+        fn name(&self) -> &'static str {
+            "DXGI desktop duplication"
+        }
+
+        fn capture(&mut self, monitor: &Monitor) -> Result<RgbaImage, String> {
+            let origin = (
+                monitor.x().map_err(|e| e.to_string())?,
+                monitor.y().map_err(|e| e.to_string())?,
+            );
+            let index = match self
+                .outputs
+                .iter()
+                .position(|output| output.origin == origin)
+            {
+                Some(index) => index,
+                None => {
+                    self.outputs.push(unsafe { open(origin)? });
+                    self.outputs.len() - 1
+                }
+            };
+            unsafe { self.outputs[index].frame() }.map_err(|e| {
+                // Duplications are lost on mode changes and desktop switches
+                // (UAC prompts, the lock screen); start over next time
+                self.outputs.remove(index);
+                e.to_string()
+            })
+        }
+    }
+}
+
+// ============================================================================
+// macOS: ScreenCaptureKit
+// ============================================================================
+
+#[cfg(target_os = "macos")]
+mod screencapturekit {
+    use super::{to_rgba, CaptureBackend, PixelOrder};
+    use block2::RcBlock;
+    use image::RgbaImage;
+    use objc2::rc::Retained;
+    use objc2::runtime::ProtocolObject;
+    use objc2::{define_class, msg_send, AllocAnyThread, DefinedClass};
+    use objc2_core_foundation::CFRetained;
+    use objc2_core_media::{CMSampleBuffer, CMSampleBufferGetImageBuffer, CMTime, CMTimeFlags};
+    use objc2_core_video::{
+        CVPixelBuffer, CVPixelBufferGetBaseAddress, CVPixelBufferGetBytesPerRow,
+        CVPixelBufferGetHeight, CVPixelBufferGetWidth, CVPixelBufferLockBaseAddress,
+        CVPixelBufferLockFlags, CVPixelBufferUnlockBaseAddress,
+    };
+    use objc2_foundation::{NSArray, NSError, NSObject, NSObjectProtocol};
+    use objc2_screen_capture_kit::{
+        SCContentFilter, SCDisplay, SCShareableContent, SCStream, SCStreamConfiguration,
+        SCStreamOutput, SCStreamOutputType,
+    };
+    use std::sync::mpsc;
+    use std::sync::{Arc, Condvar, Mutex};
+    use std::time::Duration;
+    use xcap::Monitor;
+
+    /// 'BGRA', kCVPixelFormatType_32BGRA
+    const PIXEL_FORMAT_BGRA: u32 = u32::from_be_bytes(*b"BGRA");
+    /// Streams deliver at most this many frames a second, and only when
+    /// the screen changes
+    const FRAME_RATE: i32 = 30;
+    const START_TIMEOUT: Duration = Duration::from_secs(5);
+    const FIRST_FRAME_TIMEOUT: Duration = Duration::from_secs(1);
+
+    /// The latest frame of a stream
+    struct PixelBuffer(CFRetained<CVPixelBuffer>);
+
+    // Pixel buffers are reference counted and locked for reading
+    unsafe impl Send for PixelBuffer {}
+
+    #[derive(Default)]
+    struct Latest {
+        frame: Mutex<Option<PixelBuffer>>,
+        arrived: Condvar,
+    }
+
+    define_class!(
+        #[unsafe(super(NSObject))]
+        #[name = "StepSnapFrameOutput"]
+        #[ivars = Arc<Latest>]
+        struct FrameOutput;
+
+        unsafe impl NSObjectProtocol for FrameOutput {}
+
+        unsafe impl SCStreamOutput for FrameOutput {
+            #[unsafe(method(stream:didOutputSampleBuffer:ofType:))]
+            fn did_output(
+                &self,
+                _stream: &SCStream,
+                sample_buffer: &CMSampleBuffer,
+                kind: SCStreamOutputType,
+            ) {
+                if kind != SCStreamOutputType::Screen {
+                    return;
+                }
+                // Frames sent while the screen is idle carry no image
+                let Some(buffer) = (unsafe { CMSampleBufferGetImageBuffer(sample_buffer) }) else {
+                    return;
+                };
+                let latest = self.ivars();
+                *latest.frame.lock().unwrap() = Some(PixelBuffer(buffer));
+                latest.arrived.notify_all();
+            }
+        }
+    );
+
+    impl FrameOutput {
+        fn new(latest: Arc<Latest>) -> Retained<Self> {
+            let this = Self::alloc().set_ivars(latest);
+            unsafe { msg_send![super(this), init] }
+        }
+    }
+
+    /// A running stream of one display
+    struct Stream {
+        display_id: u32,
+        stream: Retained<SCStream>,
+        _output: Retained<FrameOutput>,
+        latest: Arc<Latest>,
+    }
+
+    impl Drop for Stream {
+        fn drop(&mut self) {
+            unsafe { self.stream.stopCaptureWithCompletionHandler(None) };
+        }
+    }
+
+    pub struct ScreenCaptureKit {
+        streams: Vec<Stream>,
+    }
+
+    // Streams are thread-safe; the frame output only touches `Latest`
+    unsafe impl Send for ScreenCaptureKit {}
+
+    impl ScreenCaptureKit {
+        pub fn new() -> Result<Self, String> {
+            Ok(Self {
+                streams: Vec::new(),
+            })
+        }
+    }
+
+    fn error_text(error: *mut NSError) -> String {
+        unsafe { error.as_ref() }
+            .map(|error| error.localizedDescription().to_string())
+            .unwrap_or_else(|| "Unknown error".to_string())
+    }
+
+    /// The shareable display with `display_id`; asks for the screen
+    /// recording permission the first time
+    fn find_display(display_id: u32) -> Result<Retained<SCDisplay>, String> {
+        let (sender, result) = mpsc::channel();
+        let handler = RcBlock::new(
+            move |content: *mut SCShareableContent, error: *mut NSError| {
+                let display = match unsafe { content.as_ref() } {
+                    Some(content) => unsafe { content.displays() }
+                        .iter()
+                        .find(|display| unsafe { display.displayID() } == display_id)
+                        .ok_or_else(|| format!("Display {} isn't shareable", display_id)),
+                    None => Err(error_text(error)),
+                };
+                let _ = sender.send(display);
+            },
+        );
+        unsafe { SCShareableContent::getShareableContentWithCompletionHandler(&handler) };
+        result
+            .recv_timeout(START_TIMEOUT)
+            .map_err(|_| "Timed out listing displays".to_string())?
+    }
+
+    fn start(monitor: &Monitor, display_id: u32) -> Result<Stream, String> {
+        let display = find_display(display_id)?;
+        let scale = monitor.scale_factor().unwrap_or(1.0) as f64;
+        let width = (monitor.width().map_err(|e| e.to_string())? as f64 * scale) as usize;
+        let height = (monitor.height().map_err(|e| e.to_string())? as f64 * scale) as usize;
+
+        let filter = unsafe {
+            SCContentFilter::initWithDisplay_excludingWindows(
+                SCContentFilter::alloc(),
+                &display,
+                &NSArray::new(),
+            )
+        };
+        let config = unsafe { SCStreamConfiguration::new() };
+        unsafe {
+            config.setWidth(width);
+            config.setHeight(height);
+            config.setPixelFormat(PIXEL_FORMAT_BGRA);
+            // Match xcap, whose captures have no cursor
+            config.setShowsCursor(false);
+            config.setMinimumFrameInterval(CMTime {
+                value: 1,
+                timescale: FRAME_RATE,
+                flags: CMTimeFlags::Valid,
+                epoch: 0,
+            });
+            config.setQueueDepth(3);
+        }
+        let stream = unsafe {
+            SCStream::initWithFilter_configuration_delegate(
+                SCStream::alloc(),
+                &filter,
+                &config,
+                None,
+            )
+        };
+
+        let latest = Arc::new(Latest::default());
+        let output = FrameOutput::new(latest.clone());
+        unsafe {
+            stream.addStreamOutput_type_sampleHandlerQueue_error(
+                ProtocolObject::from_ref(&*output),
+                SCStreamOutputType::Screen,
+                None,
+            )
+        }
+        .map_err(|error| error.localizedDescription().to_string())?;
+
+        let (sender, started) = mpsc::channel();
+        let handler = RcBlock::new(move |error: *mut NSError| {
+            let _ = sender.send(if error.is_null() {
+                Ok(())
+            } else {
+                Err(error_text(error))
+            });
+        });
+        unsafe { stream.startCaptureWithCompletionHandler(Some(&handler)) };
+        started
+            .recv_timeout(START_TIMEOUT)
+            .map_err(|_| "Timed out starting the stream".to_string())??;
+
+        Ok(Stream {
+            display_id,
+            stream,
+            _output: output,
+            latest,
+        })
+    }
+
+    /// Copy the stream's latest frame, waiting for the first one
+    fn read_latest(latest: &Latest) -> Result<RgbaImage, String> {
+        let frame = latest.frame.lock().unwrap();
+        let (frame, _) = latest
+            .arrived
+            .wait_timeout_while(frame, FIRST_FRAME_TIMEOUT, |frame| frame.is_none())
+            .unwrap();
+        let buffer = &frame.as_ref().ok_or("No frame from the stream yet")?.0;
+        unsafe {
+            CVPixelBufferLockBaseAddress(buffer, CVPixelBufferLockFlags::ReadOnly);
+            let width = CVPixelBufferGetWidth(buffer);
+            let height = CVPixelBufferGetHeight(buffer);
+            let stride = CVPixelBufferGetBytesPerRow(buffer);
+            let base = CVPixelBufferGetBaseAddress(buffer) as *const u8;
+            let image = (!base.is_null()).then(|| {
+                let bytes = std::slice::from_raw_parts(base, stride * height);
+                to_rgba(width as u32, height as u32, stride, bytes, PixelOrder::Bgra)
+            });
+            CVPixelBufferUnlockBaseAddress(buffer, CVPixelBufferLockFlags::ReadOnly);
+            image.ok_or_else(|| "The frame has no pixels".to_string())
+        }
+    }
+
+    impl CaptureBackend for ScreenCaptureKit {
+        fn name(&self) -> &'static str {
+            "ScreenCaptureKit"
+        }
+
+        fn capture(&mut self, monitor: &Monitor) -> Result<RgbaImage, String> {
+            let display_id = monitor.id().map_err(|e| e.to_string())?;
+            let index = match self
+                .streams
+                .iter()
+                .position(|stream| stream.display_id == display_id)
+            {
+                Some(index) => index,
+                None => {
+                    self.streams.push(start(monitor, display_id)?);
+                    self.streams.len() - 1
+                }
+            };
+            read_latest(&self.streams[index].latest).inspect_err(|_| {
+                // A display that was reconfigured or unplugged; start over
+                self.streams.remove(index);
+            })
+        }
+    }
+}
+
+// ============================================================================
+// Linux: PipeWire screencast through the desktop portal
+// ============================================================================
+
+#[cfg(target_os = "linux")]
+mod pipewire_impl {
+    use super::{to_rgba, CaptureBackend, PixelOrder};
+    use ashpd::desktop::screencast::{CursorMode, Screencast, SourceType};
+    use ashpd::desktop::PersistMode;
+    use image::RgbaImage;
+    use pipewire as pw;
+    use pw::spa;
+    use pw::spa::param::format::{FormatProperties, MediaSubtype, MediaType};
+    use pw::spa::param::video::{VideoFormat, VideoInfoRaw};
+    use pw::spa::pod::Pod;
+    use std::os::fd::OwnedFd;
+    use std::sync::mpsc;
+    use std::sync::{Arc, Condvar, Mutex};
+    use std::time::Duration;
+    use xcap::Monitor;
+
+    /// Frames a second the streams are asked for; PipeWire only sends one
+    /// when the screen changes
+    const MAX_FRAME_RATE: u32 = 15;
+    /// Long enough for the user to pick screens in the portal's dialog
+    const START_TIMEOUT: Duration = Duration::from_secs(120);
+    const FIRST_FRAME_TIMEOUT: Duration = Duration::from_secs(1);
+
+    /// Lets later sessions skip the portal's dialog, until the app restarts
+    /// or the user revokes it
+    static RESTORE_TOKEN: Mutex<Option<String>> = Mutex::new(None);
+
+    /// A frame as PipeWire delivered it
+    struct Frame {
+        width: u32,
+        height: u32,
+        stride: usize,
+        order: PixelOrder,
+        bytes: Vec<u8>,
+    }
+
+    #[derive(Default)]
+    struct Latest {
+        frame: Mutex<Option<Frame>>,
+        arrived: Condvar,
+    }
+
+    /// A stream the user shared
+    struct Output {
+        /// Position on the desktop, when the compositor reports it
+        position: Option<(i32, i32)>,
+        latest: Arc<Latest>,
+    }
+
+    pub struct PipeWire {
+        outputs: Vec<Output>,
+        quit: pw::channel::Sender<()>,
+    }
+
+    impl Drop for PipeWire {
+        fn drop(&mut self) {
+            let _ = self.quit.send(());
+        }
+    }
+
+    impl PipeWire {
+        /// Open a screencast session, asking the user which screens to
+        /// share unless an earlier session's choice can be restored
+        pub fn new() -> Result<Self, String> {
+            if std::env::var_os("WAYLAND_DISPLAY").is_none() {
+                return Err("Screencasts are only used on Wayland".to_string());
+            }
+            let (quit, quit_receiver) = pw::channel::channel();
+            let (started, result) = mpsc::channel();
+            std::thread::Builder::new()
+                .name("screencast".to_string())
+                .spawn(move || {
+                    tauri::async_runtime::block_on(run_session(started, quit_receiver));
+                })
+                .map_err(|e| e.to_string())?;
+            let outputs = result
+                .recv_timeout(START_TIMEOUT)
+                .map_err(|_| "Timed out starting the screencast".to_string())??;
+            Ok(Self { outputs, quit })
+        }
+    }
+
+    /// Start the portal session and its streams, report them through
+    /// `started`, and keep them running until `quit`
+    async fn run_session(
+        started: mpsc::Sender<Result<Vec<Output>, String>>,
+        quit: pw::channel::Receiver<()>,
+    ) {
+        let proxy = match Screencast::new().await {
+            Ok(proxy) => proxy,
+            Err(e) => {
+                let _ = started.send(Err(e.to_string()));
+                return;
+            }
+        };
+        let session = match proxy.create_session().await {
+            Ok(session) => session,
+            Err(e) => {
+                let _ = started.send(Err(e.to_string()));
+                return;
+            }
+        };
+        let opened = async {
+            let token = RESTORE_TOKEN.lock().unwrap().clone();
+            proxy
+                .select_sources(
+                    &session,
+                    CursorMode::Hidden,
+                    SourceType::Monitor.into(),
+                    true,
+                    token.as_deref(),
+                    PersistMode::Application,
+                )
+                .await?;
+            let streams = proxy.start(&session, None).await?.response()?;
+            let fd = proxy.open_pipe_wire_remote(&session).await?;
+            Ok::<_, ashpd::Error>((streams, fd))
+        }
+        .await;
+
+        match opened {
+            Ok((streams, fd)) => {
+                if let Some(token) = streams.restore_token() {
+                    *RESTORE_TOKEN.lock().unwrap() = Some(token.to_string());
+                }
+                let shared: Vec<(u32, Option<(i32, i32)>)> = streams
+                    .streams()
+                    .iter()
+                    .map(|stream| (stream.pipe_wire_node_id(), stream.position()))
+                    .collect();
+                // Blocks this thread, which is the session's own
+                if let Err(e) = run_streams(fd, &shared, &started, quit) {
+                    let _ = started.send(Err(e));
+                }
+            }
+            Err(e) => {
+                let _ = started.send(Err(e.to_string()));
+            }
+        }
+        let _ = session.close().await;
+    }
+
+    /// Formats the streams accept, all 4 bytes a pixel
+    fn format_params() -> Result<Vec<u8>, String> {
+        let format = spa::pod::object!(
+            spa::utils::SpaTypes::ObjectParamFormat,
+            spa::param::ParamType::EnumFormat,
+            spa::pod::property!(FormatProperties::MediaType, Id, MediaType::Video),
+            spa::pod::property!(FormatProperties::MediaSubtype, Id, MediaSubtype::Raw),
+            spa::pod::property!(
+                FormatProperties::VideoFormat,
+                Choice,
+                Enum,
+                Id,
+                VideoFormat::BGRx,
+                VideoFormat::BGRx,
+                VideoFormat::BGRA,
+                VideoFormat::RGBx,
+                VideoFormat::RGBA
+            ),
+            spa::pod::property!(
+                FormatProperties::VideoSize,
+                Choice,
+                Range,
+                Rectangle,
+                spa::utils::Rectangle {
+                    width: 1920,
+                    height: 1080
+                },
+                spa::utils::Rectangle {
+                    width: 1,
+                    height: 1
+                },
+                spa::utils::Rectangle {
+                    width: 16384,
+                    height: 16384
+                }
+            ),
+            spa::pod::property!(
+                FormatProperties::VideoFramerate,
+                Choice,
+                Range,
+                Fraction,
+                spa::utils::Fraction {
+                    num: MAX_FRAME_RATE,
+                    denom: 1
+                },
+                spa::utils::Fraction { num: 0, denom: 1 },
+                spa::utils::Fraction {
+                    num: MAX_FRAME_RATE,
+                    denom: 1
+                }
+            ),
+        );
+        spa::pod::serialize::PodSerializer::serialize(
+            std::io::Cursor::new(Vec::new()),
+            &spa::pod::Value::Object(format),
+        )
+        .map(|(bytes, _)| bytes.into_inner())
+        .map_err(|e| format!("Failed to build the stream format: {:?}", e))
+    }
+
+    /// Connect to the portal's PipeWire remote and keep the latest frame of
+    /// each shared stream until `quit`
+    fn run_streams(
+        fd: OwnedFd,
+        shared: &[(u32, Option<(i32, i32)>)],
+        started: &mpsc::Sender<Result<Vec<Output>, String>>,
+        quit: pw::channel::Receiver<()>,
+    ) -> Result<(), String> {
+        pw::init();
+        let mainloop = pw::main_loop::MainLoop::new(None).map_err(|e| e.to_string())?;
+        let context = pw::context::Context::new(&mainloop).map_err(|e| e.to_string())?;
+        let core = context.connect_fd(fd, None).map_err(|e| e.to_string())?;
+        let _quit = quit.attach(mainloop.loop_(), {
+            let mainloop = mainloop.clone();
+            move |_| mainloop.quit()
+        });
+        let params = format_params()?;
+
+        let mut outputs = Vec::new();
+        // Streams stop when dropped, so they're kept until the loop ends
+        let mut running = Vec::new();
+        for &(node_id, position) in shared {
+            let latest = Arc::new(Latest::default());
+            let stream = pw::stream::Stream::new(
+                &core,
+                "stepsnap-capture",
+                pw::properties::properties! {
+                    *pw::keys::MEDIA_TYPE => "Video",
+                    *pw::keys::MEDIA_CATEGORY => "Capture",
+                    *pw::keys::MEDIA_ROLE => "Screen",
+                },
+            )
+            .map_err(|e| e.to_string())?;
+            let frames = latest.clone();
+            let listener = stream
+                .add_local_listener_with_user_data(VideoInfoRaw::default())
+                .param_changed(|_, format, id, param| {
+                    let Some(param) = param else { return };
+                    if id != spa::param::ParamType::Format.as_raw() {
+                        return;
+                    }
+                    if let Ok((MediaType::Video, MediaSubtype::Raw)) =
+                        spa::param::format_utils::parse_format(param)
+                    {
+                        let _ = format.parse(param);
+                    }
+                })
+                .process(move |stream, format| {
+                    let Some(mut buffer) = stream.dequeue_buffer() else {
+                        return;
+                    };
+                    let order = match format.format() {
+                        VideoFormat::BGRx | VideoFormat::BGRA => PixelOrder::Bgra,
+                        VideoFormat::RGBx | VideoFormat::RGBA => PixelOrder::Rgba,
+                        _ => return,
+                    };
+                    let size = format.size();
+                    let Some(data) = buffer.datas_mut().first_mut() else {
+                        return;
+                    };
+                    let chunk = data.chunk();
+                    let (offset, length) = (chunk.offset() as usize, chunk.size() as usize);
+                    let stride = chunk.stride().max(0) as usize;
+                    let Some(bytes) = data.data() else { return };
+                    let Some(bytes) = bytes.get(offset..offset + length) else {
+                        return;
+                    };
+                    if stride < size.width as usize * 4
+                        || bytes.len() < stride * size.height as usize
+                    {
+                        return;
+                    }
+                    // Copied as is; it's converted only when a capture asks
+                    let mut frame = frames.frame.lock().unwrap();
+                    let mut reused = frame.take().map(|frame| frame.bytes).unwrap_or_default();
+                    reused.clear();
+                    reused.extend_from_slice(bytes);
+                    *frame = Some(Frame {
+                        width: size.width,
+                        height: size.height,
+                        stride,
+                        order,
+                        bytes: reused,
+                    });
+                    frames.arrived.notify_all();
+                })
+                .register()
+                .map_err(|e| e.to_string())?;
+            let mut params = [Pod::from_bytes(&params).ok_or("Invalid stream format")?];
+            stream
+                .connect(
+                    spa::utils::Direction::Input,
+                    Some(node_id),
+                    pw::stream::StreamFlags::AUTOCONNECT | pw::stream::StreamFlags::MAP_BUFFERS,
+                    &mut params,
+                )
+                .map_err(|e| e.to_string())?;
+            running.push((stream, listener));
+            outputs.push(Output { position, latest });
+        }
+
+        let _ = started.send(Ok(outputs));
+        mainloop.run();
+        Ok(())
+    }
+
+    impl CaptureBackend for PipeWire {
+        fn name(&self) -> &'static str {
+            "PipeWire screencast"
+        }
+
+        fn capture(&mut self, monitor: &Monitor) -> Result<RgbaImage, String> {
+            let origin = (
+                monitor.x().map_err(|e| e.to_string())?,
+                monitor.y().map_err(|e| e.to_string())?,
+            );
+            // Compositors that don't report positions can still be matched
+            // when only one screen was shared
+            let output = self
+                .outputs
+                .iter()
+                .find(|output| output.position == Some(origin))
+                .or_else(|| (self.outputs.len() == 1).then(|| &self.outputs[0]))
+                .ok_or("That screen wasn't shared")?;
+
+            let frame = output.latest.frame.lock().unwrap();
+            let (frame, _) = output
+                .latest
+                .arrived
+                .wait_timeout_while(frame, FIRST_FRAME_TIMEOUT, |frame| frame.is_none())
+                .unwrap();
+            let frame = frame.as_ref().ok_or("No frame from the screencast yet")?;
+            Ok(to_rgba(
+                frame.width,
+                frame.height,
+                frame.stride,
+                &frame.bytes,
+                frame.order,
+            ))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn converts_padded_native_frames_to_rgba() {
+        // 2x2 BGRA with 4 bytes of padding per row
+        let bytes = [
+            10, 20, 30, 0, 40, 50, 60, 0, 9, 9, 9, 9, //
+            70, 80, 90, 0, 1, 2, 3, 0, 9, 9, 9, 9,
+        ];
+        let image = to_rgba(2, 2, 12, &bytes, PixelOrder::Bgra);
+        assert_eq!(image.get_pixel(0, 0).0, [30, 20, 10, 255]);
+        assert_eq!(image.get_pixel(1, 1).0, [3, 2, 1, 255]);
+
+        let image = to_rgba(2, 2, 12, &bytes, PixelOrder::Rgba);
+        assert_eq!(image.get_pixel(1, 0).0, [40, 50, 60, 255]);
+    }
+}
@@ -0,0 +1,188 @@
+//! Capture latency benchmark.
+//!
+//! Reports of "laggy steps" rarely say where the time goes.
+//! `run_capture_benchmark` takes a few screenshots of every monitor with the
+//! configured backend and times each stage a step goes through: capturing
+//! the frame, drawing the click highlight and step badge, encoding the JPEG
+//! and writing it to disk. The median of each stage is reported per monitor,
+//! so the numbers can be attached to a bug report or used to pick faster
+//! settings. One untimed capture first lets a native backend start its
+//! session.
+
+use crate::annotations::{self, ClickHighlightStyle, Point, StepBadgeStyle};
+use crate::capture_backend::{self, CaptureBackendKind};
+use crate::jpeg;
+use crate::recorder::RecordingState;
+use serde::Serialize;
+use std::time::Instant;
+use tauri::State;
+use xcap::Monitor;
+
+/// Timed runs per monitor
+const RUNS: usize = 5;
+/// The recorder's screenshot quality
+const QUALITY: u8 = 85;
+
+/// Median times of each stage, in milliseconds
+#[derive(Debug, Serialize)]
+pub struct StageTimes {
+    pub capture_ms: f64,
+    pub annotate_ms: f64,
+    pub encode_ms: f64,
+    pub write_ms: f64,
+    /// The four stages added up
+    pub total_ms: f64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct MonitorBenchmark {
+    pub index: usize,
+    pub name: String,
+    pub width: u32,
+    pub height: u32,
+    /// None when the monitor couldn't be captured; see `error`
+    pub times: Option<StageTimes>,
+    /// Size of the encoded screenshot
+    pub jpeg_bytes: usize,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct CaptureBenchmark {
+    pub backend: CaptureBackendKind,
+    pub runs: usize,
+    pub monitors: Vec<MonitorBenchmark>,
+}
+
+/// Middle value of `samples`, or the mean of the middle two
+fn median(samples: &mut [f64]) -> f64 {
+    if samples.is_empty() {
+        return 0.0;
+    }
+    samples.sort_by(f64::total_cmp);
+    let middle = samples.len() / 2;
+    if samples.len() % 2 == 1 {
+        samples[middle]
+    } else {
+        (samples[middle - 1] + samples[middle]) / 2.0
+    }
+}
+
+fn elapsed_ms(started: Instant) -> f64 {
+    started.elapsed().as_secs_f64() * 1000.0
+}
+
+/// Time `RUNS` screenshots of `monitor`, returning the median stage times
+/// and the size of the last JPEG
+fn benchmark_monitor(monitor: &Monitor, index: usize) -> Result<(StageTimes, usize), String> {
+    capture_backend::capture(monitor)?;
+
+    let path = std::env::temp_dir().join(format!("stepsnap_capture_benchmark_{}.jpg", index));
+    let (mut capture, mut annotate, mut encode, mut write) = (
+        Vec::with_capacity(RUNS),
+        Vec::with_capacity(RUNS),
+        Vec::with_capacity(RUNS),
+        Vec::with_capacity(RUNS),
+    );
+    let mut jpeg_bytes = 0;
+    for _ in 0..RUNS {
+        let started = Instant::now();
+        let mut image = capture_backend::capture(monitor)?;
+        capture.push(elapsed_ms(started));
+
+        // What an export draws at a click in the middle of the screen
+        let started = Instant::now();
+        let center = (image.width() as i32 / 2, image.height() as i32 / 2);
+        annotations::draw_click_highlight(
+            &mut image,
+            Point {
+                x: center.0 as f32,
+                y: center.1 as f32,
+            },
+            &ClickHighlightStyle::default(),
+        );
+        annotations::draw_step_badge(&mut image, 1, Some(center), &StepBadgeStyle::default());
+        annotate.push(elapsed_ms(started));
+
+        let started = Instant::now();
+        let bytes = jpeg::encode(&image, QUALITY)?;
+        encode.push(elapsed_ms(started));
+
+        let started = Instant::now();
+        std::fs::write(&path, &bytes)
+            .map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+        write.push(elapsed_ms(started));
+        jpeg_bytes = bytes.len();
+    }
+    let _ = std::fs::remove_file(&path);
+
+    let mut times = StageTimes {
+        capture_ms: median(&mut capture),
+        annotate_ms: median(&mut annotate),
+        encode_ms: median(&mut encode),
+        write_ms: median(&mut write),
+        total_ms: 0.0,
+    };
+    times.total_ms = times.capture_ms + times.annotate_ms + times.encode_ms + times.write_ms;
+    Ok((times, jpeg_bytes))
+}
+
+/// Time capturing, annotating, encoding and writing a screenshot of each
+/// monitor. Refused while recording, which would skew both.
+#[tauri::command]
+pub async fn run_capture_benchmark(
+    state: State<'_, RecordingState>,
+) -> Result<CaptureBenchmark, String> {
+    if *state.is_recording.lock().unwrap() {
+        return Err("Stop recording before running the capture benchmark".to_string());
+    }
+    crate::analytics::track("capture_benchmark");
+
+    tauri::async_runtime::spawn_blocking(|| {
+        let monitors = Monitor::all().map_err(|e| e.to_string())?;
+        let results = monitors
+            .iter()
+            .enumerate()
+            .map(|(index, monitor)| {
+                let (times, jpeg_bytes, error) = match benchmark_monitor(monitor, index) {
+                    Ok((times, jpeg_bytes)) => (Some(times), jpeg_bytes, None),
+                    Err(e) => (None, 0, Some(e)),
+                };
+                MonitorBenchmark {
+                    index,
+                    name: monitor.name().unwrap_or_default(),
+                    width: monitor.width().unwrap_or(0),
+                    height: monitor.height().unwrap_or(0),
+                    times,
+                    jpeg_bytes,
+                    error,
+                }
+            })
+            .collect();
+        // Don't leave a native session (and the OS's recording indicator)
+        // running until the next recording stops
+        capture_backend::release();
+
+        let benchmark = CaptureBenchmark {
+            backend: capture_backend::kind(),
+            runs: RUNS,
+            monitors: results,
+        };
+        tracing::info!(?benchmark, "Capture benchmark finished");
+        Ok(benchmark)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn takes_the_median_sample() {
+        assert_eq!(median(&mut []), 0.0);
+        assert_eq!(median(&mut [40.0, 3.0, 5.0]), 5.0);
+        assert_eq!(median(&mut [9.0, 1.0, 4.0, 2.0]), 3.0);
+    }
+}
@@ -0,0 +1,417 @@
+//! Posting a recording's summary to a Slack or Microsoft Teams channel.
+//!
+//! The summary is the title, step count, an optional note and, when asked
+//! for, a share link (see `share`; uploaded to the static host when one is
+//! set up, so it keeps working while the app is closed).
+//!
+//! Slack posts go through an incoming webhook, which can't carry files, or,
+//! with a bot token and channel set, through the Web API, which uploads the
+//! first screenshot as the cover with the summary as its comment. Teams posts
+//! an Adaptive Card to a channel webhook (a Workflows "post to a channel when
+//! a webhook request is received" URL, or a legacy connector) with a small
+//! cover inlined, since cards can't reference local files. Webhook URLs and
+//! the bot token are secrets and are kept in the keychain.
+
+use crate::database::RecordingWithSteps;
+use crate::db_worker::DatabaseWorker;
+use crate::settings::SettingsState;
+use crate::{analytics, credentials, network, share};
+use base64::Engine;
+use image::codecs::jpeg::JpegEncoder;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
+
+pub const SLACK_WEBHOOK_CREDENTIAL: &str = "slack_webhook_url";
+pub const SLACK_BOT_TOKEN_CREDENTIAL: &str = "slack_bot_token";
+pub const TEAMS_WEBHOOK_CREDENTIAL: &str = "teams_webhook_url";
+const SLACK_API: &str = "https://slack.com/api";
+/// Teams rejects messages over 28 KB, so the inlined cover stays small
+const TEAMS_COVER_WIDTH: u32 = 360;
+const TEAMS_COVER_MAX_BYTES: usize = 16 * 1024;
+const SLACK_COVER_WIDTH: u32 = 1600;
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(60);
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ChatSettings {
+    /// Slack channel ID posts go to with the bot token, e.g. `C0123456789`;
+    /// empty to post through the webhook
+    pub slack_channel_id: String,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ChatPlatform {
+    Slack,
+    Teams,
+}
+
+/// What gets posted about a recording
+struct Summary {
+    title: String,
+    step_count: usize,
+    note: Option<String>,
+    link: Option<String>,
+}
+
+impl Summary {
+    fn step_label(&self) -> String {
+        match self.step_count {
+            1 => "1 step".to_string(),
+            count => format!("{} steps", count),
+        }
+    }
+}
+
+/// `text` with the characters Slack treats as markup escaped
+fn slack_escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+fn slack_blocks(summary: &Summary) -> Value {
+    let mut blocks = vec![
+        json!({
+            "type": "header",
+            "text": { "type": "plain_text", "text": summary.title },
+        }),
+        json!({
+            "type": "context",
+            "elements": [{ "type": "mrkdwn", "text": summary.step_label() }],
+        }),
+    ];
+    if let Some(note) = &summary.note {
+        blocks.push(json!({
+            "type": "section",
+            "text": { "type": "mrkdwn", "text": slack_escape(note) },
+        }));
+    }
+    if let Some(link) = &summary.link {
+        blocks.push(json!({
+            "type": "actions",
+            "elements": [{
+                "type": "button",
+                "text": { "type": "plain_text", "text": "View guide" },
+                "url": link,
+            }],
+        }));
+    }
+    Value::Array(blocks)
+}
+
+/// The summary as one mrkdwn message, for file comments
+fn slack_text(summary: &Summary) -> String {
+    let mut text = format!(
+        "*{}*\n{}",
+        slack_escape(&summary.title),
+        summary.step_label()
+    );
+    if let Some(note) = &summary.note {
+        text.push_str(&format!("\n\n{}", slack_escape(note)));
+    }
+    if let Some(link) = &summary.link {
+        text.push_str(&format!("\n<{}|View guide>", link));
+    }
+    text
+}
+
+/// Adaptive Card message; `cover` is a data URI
+fn teams_card(summary: &Summary, cover: Option<&str>) -> Value {
+    let mut body = vec![
+        json!({
+            "type": "TextBlock",
+            "text": summary.title,
+            "size": "Large",
+            "weight": "Bolder",
+            "wrap": true,
+        }),
+        json!({
+            "type": "TextBlock",
+            "text": summary.step_label(),
+            "isSubtle": true,
+            "spacing": "None",
+        }),
+    ];
+    if let Some(note) = &summary.note {
+        body.push(json!({ "type": "TextBlock", "text": note, "wrap": true }));
+    }
+    if let Some(cover) = cover {
+        body.push(json!({ "type": "Image", "url": cover, "size": "Stretch" }));
+    }
+    let actions: Vec<Value> = summary
+        .link
+        .iter()
+        .map(|link| json!({ "type": "Action.OpenUrl", "title": "View guide", "url": link }))
+        .collect();
+    json!({
+        "type": "message",
+        "attachments": [{
+            "contentType": "application/vnd.microsoft.card.adaptive",
+            "content": {
+                "$schema": "http://adaptivecards.io/schemas/adaptive-card.json",
+                "type": "AdaptiveCard",
+                "version": "1.4",
+                "body": body,
+                "actions": actions,
+            },
+        }],
+    })
+}
+
+/// The first screenshot as a JPEG no wider than `width`
+fn cover_jpeg(recording: &RecordingWithSteps, width: u32, quality: u8) -> Option<Vec<u8>> {
+    let path = recording
+        .steps
+        .iter()
+        .find_map(|step| step.screenshot_path.as_deref())?;
+    let mut cover = image::open(path)
+        .map_err(|e| tracing::warn!("Failed to read cover {}: {}", path, e))
+        .ok()?;
+    if cover.width() > width {
+        cover = cover.resize(width, u32::MAX, image::imageops::FilterType::Triangle);
+    }
+    let mut encoded = Vec::new();
+    JpegEncoder::new_with_quality(&mut encoded, quality)
+        .encode_image(&cover.to_rgb8())
+        .ok()?;
+    Some(encoded)
+}
+
+fn webhook(name: &str, platform: &str) -> Result<String, String> {
+    credentials::get(name)?
+        .map(|url| url.trim().to_string())
+        .filter(|url| !url.is_empty())
+        .ok_or_else(|| format!("Add a {} webhook URL in Settings", platform))
+}
+
+fn client() -> Result<reqwest::Client, String> {
+    network::client_builder()?
+        .timeout(REQUEST_TIMEOUT)
+        .build()
+        .map_err(|e| format!("Failed to create HTTP client: {}", e))
+}
+
+async fn send(request: reqwest::RequestBuilder, platform: &str) -> Result<String, String> {
+    let response = request
+        .send()
+        .await
+        .map_err(|e| format!("Couldn't reach {}: {}", platform, e))?;
+    let status = response.status();
+    let body = response.text().await.unwrap_or_default();
+    match status.as_u16() {
+        200..=299 => Ok(body),
+        403 | 404 => Err(format!(
+            "{} rejected the webhook URL. Check it in Settings.",
+            platform
+        )),
+        _ => Err(format!(
+            "{} request failed: {} {}",
+            platform,
+            status,
+            body.chars().take(300).collect::<String>()
+        )
+        .trim()
+        .to_string()),
+    }
+}
+
+/// A Slack Web API call; Slack reports failures in the body
+async fn slack_api(request: reqwest::RequestBuilder) -> Result<Value, String> {
+    let body = send(request, "Slack").await?;
+    let body: Value =
+        serde_json::from_str(&body).map_err(|e| format!("Unexpected Slack response: {}", e))?;
+    match body["error"].as_str() {
+        _ if body["ok"].as_bool() == Some(true) => Ok(body),
+        Some("invalid_auth" | "not_authed" | "token_revoked") => {
+            Err("Slack rejected the bot token. Check it in Settings.".to_string())
+        }
+        Some("not_in_channel" | "channel_not_found") => {
+            Err("Invite the Slack app to the channel first".to_string())
+        }
+        Some(error) => Err(format!("Slack request failed: {}", error)),
+        None => Err("Slack request failed".to_string()),
+    }
+}
+
+/// Post as the bot: the cover uploaded with the summary as its comment, or
+/// just the summary when there's no cover
+async fn post_slack_bot(
+    token: &str,
+    channel: &str,
+    summary: &Summary,
+    cover: Option<Vec<u8>>,
+) -> Result<(), String> {
+    let client = client()?;
+    let Some(cover) = cover else {
+        slack_api(
+            client
+                .post(format!("{}/chat.postMessage", SLACK_API))
+                .bearer_auth(token)
+                .json(&json!({
+                    "channel": channel,
+                    "text": slack_text(summary),
+                    "blocks": slack_blocks(summary),
+                })),
+        )
+        .await?;
+        return Ok(());
+    };
+
+    let upload = slack_api(
+        client
+            .post(format!("{}/files.getUploadURLExternal", SLACK_API))
+            .bearer_auth(token)
+            .form(&[
+                ("filename", "cover.jpg".to_string()),
+                ("length", cover.len().to_string()),
+            ]),
+    )
+    .await?;
+    let (Some(upload_url), Some(file_id)) =
+        (upload["upload_url"].as_str(), upload["file_id"].as_str())
+    else {
+        return Err("Unexpected Slack response: no upload URL".to_string());
+    };
+    send(client.post(upload_url).body(cover), "Slack").await?;
+    slack_api(
+        client
+            .post(format!("{}/files.completeUploadExternal", SLACK_API))
+            .bearer_auth(token)
+            .json(&json!({
+                "files": [{ "id": file_id, "title": summary.title }],
+                "channel_id": channel,
+                "initial_comment": slack_text(summary),
+            })),
+    )
+    .await?;
+    Ok(())
+}
+
+/// Post a summary of `recording_id` with an optional `note`, and a share link
+/// to it with `include_link`
+#[tauri::command]
+pub async fn post_recording_to_chat(
+    app: AppHandle,
+    platform: ChatPlatform,
+    recording_id: String,
+    note: Option<String>,
+    include_link: bool,
+) -> Result<(), String> {
+    let (chat, upload_link) = {
+        let settings = app.state::<SettingsState>().0.lock().unwrap();
+        (
+            settings.chat.clone(),
+            !settings.share.upload_url.trim().is_empty(),
+        )
+    };
+    let id = recording_id.clone();
+    let recording = app
+        .state::<DatabaseWorker>()
+        .run(move |db| {
+            db.get_recording(&id)
+                .map_err(|e| e.to_string())?
+                .ok_or_else(|| format!("Recording not found: {}", id))
+        })
+        .await?;
+    let link = if include_link {
+        let link =
+            share::create_share_link(app.clone(), recording_id.clone(), None, None, upload_link)
+                .await?;
+        Some(link.url)
+    } else {
+        None
+    };
+    let summary = Summary {
+        title: recording.recording.name.clone(),
+        step_count: recording.steps.len(),
+        note: note
+            .map(|note| note.trim().to_string())
+            .filter(|note| !note.is_empty()),
+        link,
+    };
+
+    match platform {
+        ChatPlatform::Slack => {
+            let bot_token = credentials::get(SLACK_BOT_TOKEN_CREDENTIAL)?
+                .filter(|token| !token.trim().is_empty());
+            let channel = chat.slack_channel_id.trim();
+            match bot_token {
+                Some(token) if !channel.is_empty() => {
+                    let cover = tauri::async_runtime::spawn_blocking(move || {
+                        cover_jpeg(&recording, SLACK_COVER_WIDTH, 85)
+                    })
+                    .await
+                    .map_err(|e| e.to_string())?;
+                    post_slack_bot(token.trim(), channel, &summary, cover).await?;
+                }
+                _ => {
+                    let url = webhook(SLACK_WEBHOOK_CREDENTIAL, "Slack")?;
+                    let message = json!({
+                        "text": slack_text(&summary),
+                        "blocks": slack_blocks(&summary),
+                    });
+                    send(client()?.post(url).json(&message), "Slack").await?;
+                }
+            }
+        }
+        ChatPlatform::Teams => {
+            let url = webhook(TEAMS_WEBHOOK_CREDENTIAL, "Teams")?;
+            let cover = tauri::async_runtime::spawn_blocking(move || {
+                cover_jpeg(&recording, TEAMS_COVER_WIDTH, 60)
+            })
+            .await
+            .map_err(|e| e.to_string())?
+            .filter(|cover| cover.len() <= TEAMS_COVER_MAX_BYTES)
+            .map(|cover| {
+                format!(
+                    "data:image/jpeg;base64,{}",
+                    base64::engine::general_purpose::STANDARD.encode(cover)
+                )
+            });
+            let card = teams_card(&summary, cover.as_deref());
+            send(client()?.post(url).json(&card), "Teams").await?;
+        }
+    }
+
+    analytics::track("chat_post");
+    tracing::info!(recording_id = %recording_id, ?platform, "Posted recording to chat");
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn builds_slack_and_teams_messages() {
+        let summary = Summary {
+            title: "Reset a <password>".to_string(),
+            step_count: 1,
+            note: Some("Q&A follows".to_string()),
+            link: Some("https://docs.example.com/s/abc".to_string()),
+        };
+
+        assert_eq!(
+            slack_text(&summary),
+            "*Reset a &lt;password&gt;*\n1 step\n\nQ&amp;A follows\n<https://docs.example.com/s/abc|View guide>"
+        );
+        let blocks = slack_blocks(&summary);
+        assert_eq!(blocks[0]["text"]["text"], "Reset a <password>");
+        assert_eq!(
+            blocks[3]["elements"][0]["url"],
+            "https://docs.example.com/s/abc"
+        );
+
+        let card = teams_card(&summary, Some("data:image/jpeg;base64,AAAA"));
+        let content = &card["attachments"][0]["content"];
+        assert_eq!(content["body"][1]["text"], "1 step");
+        assert_eq!(content["body"][3]["url"], "data:image/jpeg;base64,AAAA");
+        assert_eq!(
+            content["actions"][0]["url"],
+            "https://docs.example.com/s/abc"
+        );
+    }
+}
@@ -0,0 +1,269 @@
+//! `stepsnap-cli`: the recording library without the GUI.
+//!
+//! Lists recordings, exports them, imports bundles and runs the maintenance
+//! the app does at startup, for scripts and CI jobs that publish guides. It
+//! opens the same data directory as the app (following a relocation), or the
+//! one given with `--data-dir`. Errors go to stderr with a non-zero exit.
+
+use crate::database::Database;
+use crate::settings::Settings;
+use crate::{bundle, data_location, render_recording_markdown, ExportStyles};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+const USAGE: &str = "\
+Usage: stepsnap-cli [--data-dir <dir>] <command>
+
+Commands:
+  list [--json]                         List recordings, newest first
+  export <id> [--format <format>] [--output <path>]
+                                        Export a recording as markdown (default),
+                                        json or bundle; to stdout unless --output
+                                        is given (required for bundles)
+  import <bundle>                       Add a recording from a bundle
+  maintenance                           Recover interrupted recordings, enforce
+                                        the lossless original quota and remove
+                                        unreferenced screenshots
+";
+
+enum Command {
+    List {
+        json: bool,
+    },
+    Export {
+        id: String,
+        format: Format,
+        output: Option<PathBuf>,
+    },
+    Import {
+        path: PathBuf,
+    },
+    Maintenance,
+    Help,
+}
+
+#[derive(Debug, PartialEq)]
+enum Format {
+    Markdown,
+    Json,
+    Bundle,
+}
+
+struct Args {
+    data_dir: Option<PathBuf>,
+    command: Command,
+}
+
+/// Entry point of the `stepsnap-cli` binary
+pub fn main() -> ExitCode {
+    let args = match parse(std::env::args().skip(1)) {
+        Ok(args) => args,
+        Err(e) => {
+            eprintln!("{}\n\n{}", e, USAGE);
+            return ExitCode::from(2);
+        }
+    };
+    if let Command::Help = args.command {
+        print!("{}", USAGE);
+        return ExitCode::SUCCESS;
+    }
+    match run(args) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("error: {}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn parse(args: impl IntoIterator<Item = String>) -> Result<Args, String> {
+    let mut args = args.into_iter();
+    let mut data_dir = None;
+    let mut positional = Vec::new();
+    let mut json = false;
+    let mut format = None;
+    let mut output = None;
+    let mut help = false;
+    while let Some(arg) = args.next() {
+        let mut value = |flag: &str| args.next().ok_or_else(|| format!("{} needs a value", flag));
+        match arg.as_str() {
+            "--data-dir" => data_dir = Some(PathBuf::from(value("--data-dir")?)),
+            "--format" => format = Some(value("--format")?),
+            "--output" | "-o" => output = Some(PathBuf::from(value("--output")?)),
+            "--json" => json = true,
+            "--help" | "-h" => help = true,
+            flag if flag.starts_with('-') => return Err(format!("Unknown option: {}", flag)),
+            _ => positional.push(arg),
+        }
+    }
+    if help {
+        return Ok(Args {
+            data_dir,
+            command: Command::Help,
+        });
+    }
+
+    let mut positional = positional.into_iter();
+    let command = match positional.next().as_deref() {
+        Some("list") => Command::List { json },
+        Some("export") => {
+            let id = positional.next().ok_or("export needs a recording id")?;
+            let format = match format.as_deref() {
+                None | Some("markdown") | Some("md") => Format::Markdown,
+                Some("json") => Format::Json,
+                Some("bundle") => Format::Bundle,
+                Some(other) => return Err(format!("Unknown export format: {}", other)),
+            };
+            if format == Format::Bundle && output.is_none() {
+                return Err("Bundle exports need --output".to_string());
+            }
+            Command::Export { id, format, output }
+        }
+        Some("import") => Command::Import {
+            path: PathBuf::from(positional.next().ok_or("import needs a bundle path")?),
+        },
+        Some("maintenance") => Command::Maintenance,
+        Some("help") | None => Command::Help,
+        Some(other) => return Err(format!("Unknown command: {}", other)),
+    };
+    if let Some(extra) = positional.next() {
+        return Err(format!("Unexpected argument: {}", extra));
+    }
+    Ok(Args { data_dir, command })
+}
+
+fn run(args: Args) -> Result<(), String> {
+    let data_dir = match args.data_dir {
+        Some(dir) => dir,
+        None => data_location::resolve(&default_app_data_dir()?),
+    };
+    if !data_dir.join(data_location::DATABASE_FILE).exists() {
+        return Err(format!("No recording library in {}", data_dir.display()));
+    }
+    let mut db = Database::new(data_dir).map_err(|e| e.to_string())?;
+    let settings = Settings::load(&db);
+    db.set_original_quota(settings.lossless_original_quota);
+
+    match args.command {
+        Command::List { json } => {
+            let recordings = db.list_recordings().map_err(|e| e.to_string())?;
+            if json {
+                let json = serde_json::to_string_pretty(&recordings).map_err(|e| e.to_string())?;
+                println!("{}", json);
+            } else {
+                for recording in recordings {
+                    let created = chrono::DateTime::from_timestamp_millis(recording.created_at)
+                        .map(|time| time.format("%Y-%m-%d %H:%M").to_string())
+                        .unwrap_or_default();
+                    println!(
+                        "{}\t{}\t{} steps\t{}",
+                        recording.id, created, recording.step_count, recording.name
+                    );
+                }
+            }
+        }
+        Command::Export { id, format, output } => match format {
+            Format::Bundle => {
+                let output = output.expect("checked by parse");
+                let steps = bundle::export(&db, &id, &output)?;
+                eprintln!("Exported {} steps to {}", steps, output.display());
+            }
+            Format::Markdown => {
+                // Rendered screenshots are linked by their absolute path
+                let styles = ExportStyles::from_settings(&settings);
+                let markdown = render_recording_markdown(&db, &id, settings.locale, styles)?;
+                write_output(output.as_deref(), &markdown)?;
+            }
+            Format::Json => {
+                let recording = db
+                    .get_recording(&id)
+                    .map_err(|e| e.to_string())?
+                    .ok_or_else(|| format!("Recording not found: {}", id))?;
+                let json = serde_json::to_string_pretty(&recording).map_err(|e| e.to_string())?;
+                write_output(output.as_deref(), &json)?;
+            }
+        },
+        Command::Import { path } => {
+            let id = bundle::import(&db, &path)?;
+            println!("{}", id);
+        }
+        Command::Maintenance => {
+            if let Some(recovered) = db.recover_drafts().map_err(|e| e.to_string())? {
+                println!(
+                    "Recovered {} steps as \"{}\" ({})",
+                    recovered.step_count, recovered.recording_name, recovered.recording_id
+                );
+            }
+            db.enforce_original_quota().map_err(|e| e.to_string())?;
+            let pruned = db.prune_screenshot_objects().map_err(|e| e.to_string())?;
+            println!("Removed {} unreferenced screenshots", pruned);
+            let usage = db.get_storage_usage(true).map_err(|e| e.to_string())?;
+            println!(
+                "{} recordings using {:.1} MB",
+                usage.recordings.len(),
+                usage.total_bytes as f64 / 1_048_576.0
+            );
+        }
+        Command::Help => unreachable!("handled in main"),
+    }
+    Ok(())
+}
+
+fn write_output(output: Option<&Path>, text: &str) -> Result<(), String> {
+    match output {
+        Some(path) => {
+            fs::write(path, text).map_err(|e| format!("Failed to write {}: {}", path.display(), e))
+        }
+        None => {
+            println!("{}", text);
+            Ok(())
+        }
+    }
+}
+
+/// The directory Tauri gives the app (`<data dir>/<identifier>`), worked out
+/// without a running app
+fn default_app_data_dir() -> Result<PathBuf, String> {
+    const IDENTIFIER: &str = "stepsnap";
+    let env_dir = |name: &str| std::env::var_os(name).filter(|value| !value.is_empty());
+    let base = if cfg!(target_os = "windows") {
+        env_dir("APPDATA").map(PathBuf::from)
+    } else if cfg!(target_os = "macos") {
+        env_dir("HOME").map(|home| PathBuf::from(home).join("Library/Application Support"))
+    } else {
+        env_dir("XDG_DATA_HOME")
+            .map(PathBuf::from)
+            .or_else(|| env_dir("HOME").map(|home| PathBuf::from(home).join(".local/share")))
+    };
+    base.map(|base| base.join(IDENTIFIER))
+        .ok_or_else(|| "Can't find the app data directory; pass --data-dir".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(line: &str) -> Result<Args, String> {
+        parse(line.split_whitespace().map(String::from))
+    }
+
+    #[test]
+    fn parses_exports_and_rejects_incomplete_ones() {
+        let parsed = args("--data-dir /tmp/lib export abc --format json -o out.json").unwrap();
+        assert_eq!(parsed.data_dir, Some(PathBuf::from("/tmp/lib")));
+        match parsed.command {
+            Command::Export { id, format, output } => {
+                assert_eq!(id, "abc");
+                assert_eq!(format, Format::Json);
+                assert_eq!(output, Some(PathBuf::from("out.json")));
+            }
+            _ => panic!("expected an export"),
+        }
+
+        assert!(args("export abc --format bundle").is_err());
+        assert!(args("export --format pdf abc").is_err());
+        assert!(args("export").is_err());
+        assert!(args("publish abc").is_err());
+    }
+}
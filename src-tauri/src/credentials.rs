@@ -0,0 +1,91 @@
+//! API tokens kept in the OS keychain rather than the frontend's settings
+//! file: the macOS Keychain, the Windows Credential Manager, or the Secret
+//! Service (GNOME Keyring, KWallet) on Linux.
+//!
+//! Each secret is stored under the app's identifier as the service and its
+//! name (e.g. "ai_api_key") as the account, so it shows up as "stepsnap" in
+//! the OS credential manager and can be removed there too.
+
+use keyring::Entry;
+
+/// Service the secrets are filed under, the bundle identifier
+const SERVICE: &str = "stepsnap";
+const MAX_NAME_LEN: usize = 64;
+
+/// Names are short identifiers so they can't collide with, or be mistaken
+/// for, the secrets themselves
+fn validate_name(name: &str) -> Result<(), String> {
+    let valid_chars = name
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.'));
+    if name.is_empty() || name.len() > MAX_NAME_LEN || !valid_chars {
+        return Err(format!("Invalid credential name: {:?}", name));
+    }
+    Ok(())
+}
+
+fn entry(name: &str) -> Result<Entry, String> {
+    validate_name(name)?;
+    Entry::new(SERVICE, name).map_err(|e| format!("Keychain unavailable: {}", e))
+}
+
+pub fn set(name: &str, secret: &str) -> Result<(), String> {
+    entry(name)?
+        .set_password(secret)
+        .map_err(|e| format!("Failed to store {} in the keychain: {}", name, e))
+}
+
+/// `None` when nothing is stored under `name`
+pub fn get(name: &str) -> Result<Option<String>, String> {
+    match entry(name)?.get_password() {
+        Ok(secret) => Ok(Some(secret)),
+        Err(keyring::Error::NoEntry) => Ok(None),
+        Err(e) => Err(format!("Failed to read {} from the keychain: {}", name, e)),
+    }
+}
+
+/// Succeeds when nothing was stored
+pub fn delete(name: &str) -> Result<(), String> {
+    match entry(name)?.delete_credential() {
+        Ok(()) | Err(keyring::Error::NoEntry) => Ok(()),
+        Err(e) => Err(format!("Failed to delete {} from the keychain: {}", name, e)),
+    }
+}
+
+/// Store `secret` under `name`, replacing what was there. An empty secret
+/// deletes it.
+#[tauri::command]
+pub fn set_credential(name: String, secret: String) -> Result<(), String> {
+    if secret.is_empty() {
+        return delete(&name);
+    }
+    set(&name, &secret)?;
+    tracing::info!(credential = %name, "Credential stored in the keychain");
+    Ok(())
+}
+
+#[tauri::command]
+pub fn get_credential(name: String) -> Result<Option<String>, String> {
+    get(&name)
+}
+
+#[tauri::command]
+pub fn delete_credential(name: String) -> Result<(), String> {
+    delete(&name)?;
+    tracing::info!(credential = %name, "Credential removed from the keychain");
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn names_are_short_identifiers() {
+        assert!(validate_name("ai_api_key").is_ok());
+        assert!(validate_name("smtp.password-2").is_ok());
+        assert!(validate_name("").is_err());
+        assert!(validate_name("sk-live key").is_err());
+        assert!(validate_name(&"a".repeat(MAX_NAME_LEN + 1)).is_err());
+    }
+}
@@ -0,0 +1,258 @@
+//! Where the database and screenshots live.
+//!
+//! By default that's the app data directory. `relocate` moves them
+//! somewhere else (e.g. a synced drive) and leaves a `data_location.json`
+//! pointer behind, which `resolve` follows on every start. Logs, the
+//! frontend's `settings.json` and OCR models stay in the app data
+//! directory.
+
+use crate::database::Database;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const POINTER_FILE: &str = "data_location.json";
+pub(crate) const DATABASE_FILE: &str = "stepsnap.db";
+/// Directories of the data directory that belong to the database
+const DATA_SUBDIRS: &[&str] = &["screenshots", "objects"];
+
+#[derive(Serialize, Deserialize)]
+struct Pointer {
+    path: PathBuf,
+}
+
+/// What `relocate` did, for the settings UI
+#[derive(Debug, Clone, Serialize)]
+pub struct DataRelocation {
+    pub old_dir: String,
+    pub new_dir: String,
+    /// Stored paths pointed at the new directory
+    pub rewritten_paths: usize,
+}
+
+/// The data directory: where the pointer in `app_data_dir` says, or
+/// `app_data_dir` itself. A pointer to a directory without a database (e.g.
+/// a drive that isn't mounted) is ignored rather than starting empty there.
+pub fn resolve(app_data_dir: &Path) -> PathBuf {
+    let pointer = fs::read_to_string(app_data_dir.join(POINTER_FILE))
+        .ok()
+        .and_then(|json| serde_json::from_str::<Pointer>(&json).ok());
+    match pointer {
+        Some(pointer) if pointer.path.join(DATABASE_FILE).exists() => pointer.path,
+        Some(pointer) => {
+            tracing::warn!(
+                "Data directory {} has no database; using {}",
+                pointer.path.display(),
+                app_data_dir.display()
+            );
+            app_data_dir.to_path_buf()
+        }
+        None => app_data_dir.to_path_buf(),
+    }
+}
+
+/// Why `target` can't hold the data moved from `current`, if it can't
+fn check_target(current: &Path, target: &Path) -> Result<(), String> {
+    if !target.is_absolute() {
+        return Err(format!("Path must be absolute: {}", target.display()));
+    }
+    if target == current {
+        return Err("The data is already there".to_string());
+    }
+    if target.starts_with(current) || current.starts_with(target) {
+        return Err("Pick a folder that's neither inside nor above the current one".to_string());
+    }
+    let taken = std::iter::once(DATABASE_FILE)
+        .chain(DATA_SUBDIRS.iter().copied())
+        .find(|name| target.join(name).exists());
+    match taken {
+        Some(name) => Err(format!(
+            "{} already contains {}; pick an empty folder",
+            target.display(),
+            name
+        )),
+        None => Ok(()),
+    }
+}
+
+/// Copy the database and screenshots of `db` to `target`, point every
+/// stored path there and return the database opened at `target`. The old
+/// copy is left in place; see `remove_data`. Nothing is switched if any step
+/// fails, and the partial copy is removed.
+pub fn relocate(
+    db: &Database,
+    app_data_dir: &Path,
+    target: &Path,
+) -> Result<(Database, DataRelocation), String> {
+    let current = db.data_dir().clone();
+    check_target(&current, target)?;
+    fs::create_dir_all(target)
+        .map_err(|e| format!("Failed to create {}: {}", target.display(), e))?;
+    // Resolve symlinks and drive letters so stored paths match later lookups
+    let target = target
+        .canonicalize()
+        .map_err(|e| format!("Invalid path: {}", e))?;
+
+    let copied = copy_data(db, &current, &target).and_then(|()| {
+        let moved = Database::new(target.clone()).map_err(|e| e.to_string())?;
+        let rewritten = moved
+            .rewrite_data_dir(&current, &target)
+            .map_err(|e| e.to_string())?;
+        Ok((moved, rewritten))
+    });
+    let (moved, rewritten_paths) = match copied {
+        Ok(copied) => copied,
+        Err(e) => {
+            remove_data(&target);
+            return Err(e);
+        }
+    };
+
+    if let Err(e) = write_pointer(app_data_dir, &target) {
+        drop(moved);
+        remove_data(&target);
+        return Err(e);
+    }
+    Ok((
+        moved,
+        DataRelocation {
+            old_dir: current.to_string_lossy().to_string(),
+            new_dir: target.to_string_lossy().to_string(),
+            rewritten_paths,
+        },
+    ))
+}
+
+fn copy_data(db: &Database, from: &Path, to: &Path) -> Result<(), String> {
+    db.copy_to(&to.join(DATABASE_FILE))
+        .map_err(|e| format!("Failed to copy the database: {}", e))?;
+    for name in DATA_SUBDIRS {
+        let source = from.join(name);
+        if source.is_dir() {
+            copy_dir(&source, &to.join(name))
+                .map_err(|e| format!("Failed to copy {}: {}", source.display(), e))?;
+        }
+    }
+    Ok(())
+}
+
+fn copy_dir(from: &Path, to: &Path) -> std::io::Result<()> {
+    fs::create_dir_all(to)?;
+    for entry in fs::read_dir(from)? {
+        let entry = entry?;
+        let destination = to.join(entry.file_name());
+        if entry.file_type()?.is_dir() {
+            copy_dir(&entry.path(), &destination)?;
+        } else {
+            fs::copy(entry.path(), destination)?;
+        }
+    }
+    Ok(())
+}
+
+/// Delete the database and screenshots in `dir`, leaving anything else.
+/// Close the database there first.
+pub fn remove_data(dir: &Path) {
+    for name in [DATABASE_FILE, "stepsnap.db-wal", "stepsnap.db-shm"] {
+        let _ = fs::remove_file(dir.join(name));
+    }
+    for name in DATA_SUBDIRS {
+        let _ = fs::remove_dir_all(dir.join(name));
+    }
+}
+
+/// Make `resolve` return `data_dir` from now on
+fn write_pointer(app_data_dir: &Path, data_dir: &Path) -> Result<(), String> {
+    let path = app_data_dir.join(POINTER_FILE);
+    if data_dir == app_data_dir {
+        return match fs::remove_file(&path) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => {
+                Err(format!("Failed to reset the data location: {}", e))
+            }
+            _ => Ok(()),
+        };
+    }
+    let json = serde_json::to_string_pretty(&Pointer {
+        path: data_dir.to_path_buf(),
+    })
+    .map_err(|e| e.to_string())?;
+    fs::write(&path, json).map_err(|e| format!("Failed to save the data location: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::StepInput;
+    use uuid::Uuid;
+
+    struct TestDir {
+        path: PathBuf,
+    }
+
+    impl TestDir {
+        fn new() -> Self {
+            let path = std::env::temp_dir()
+                .join(format!("stepsnap_data_location_test_{}", Uuid::new_v4()));
+            fs::create_dir_all(&path).unwrap();
+            // Relocated paths are canonical, so compare against canonical ones
+            Self {
+                path: path.canonicalize().unwrap(),
+            }
+        }
+    }
+
+    impl Drop for TestDir {
+        fn drop(&mut self) {
+            let _ = fs::remove_dir_all(&self.path);
+        }
+    }
+
+    #[test]
+    fn relocate_copies_rewrites_paths_and_leaves_a_pointer() {
+        let root = TestDir::new();
+        let app_data = root.path.join("app");
+        let db = Database::new(app_data.clone()).unwrap();
+        let recording_id = db.create_recording("Move me".to_string()).unwrap();
+        let capture = root.path.join("capture.png");
+        fs::write(&capture, b"png").unwrap();
+        let step: StepInput = serde_json::from_value(serde_json::json!({
+            "type_": "capture",
+            "timestamp": 1,
+            "screenshot": capture.to_string_lossy(),
+        }))
+        .unwrap();
+        db.save_steps(&recording_id, vec![step]).unwrap();
+
+        let target = root.path.join("synced");
+        let (moved, relocation) = relocate(&db, &app_data, &target).unwrap();
+        drop(db);
+        remove_data(&app_data);
+
+        assert!(relocation.rewritten_paths > 0);
+        assert_eq!(resolve(&app_data), target);
+        assert!(!app_data.join(DATABASE_FILE).exists());
+        let steps = moved.get_recording(&recording_id).unwrap().unwrap().steps;
+        let path = PathBuf::from(steps[0].screenshot_path.as_deref().unwrap());
+        assert!(path.starts_with(&target));
+        assert_eq!(fs::read(path).unwrap(), b"png");
+
+        // Moving back home removes the pointer
+        let (_, back) = relocate(&moved, &app_data, &app_data).unwrap();
+        assert_eq!(back.new_dir, app_data.to_string_lossy());
+        assert!(!app_data.join(POINTER_FILE).exists());
+        assert_eq!(resolve(&app_data), app_data);
+    }
+
+    #[test]
+    fn relocate_refuses_folders_that_hold_data_or_nest() {
+        let root = TestDir::new();
+        let app_data = root.path.join("app");
+        let db = Database::new(app_data.clone()).unwrap();
+
+        assert!(relocate(&db, &app_data, &app_data.join("inner")).is_err());
+        let taken = root.path.join("taken");
+        fs::create_dir_all(taken.join("screenshots")).unwrap();
+        assert!(relocate(&db, &app_data, &taken).is_err());
+        assert_eq!(resolve(&app_data), app_data);
+    }
+}
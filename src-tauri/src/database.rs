@@ -1,9 +1,659 @@
+use crate::accessibility::{AxNode, ElementBounds, ElementPathSegment};
+use crate::annotations::{Annotation, ClickHighlightStyle, Point, Rect, Redaction};
+use crate::descriptions::describe_step;
+use crate::hotkeys::HotkeyAction;
+use crate::i18n;
+use crate::image_edit::ImageEdit;
+use crate::ocr::OcrLine;
+use crate::recorder::HotkeyBinding;
 use rusqlite::{params, Connection, OptionalExtension, Result};
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use uuid::Uuid;
 
+const INSERT_STEP_SQL: &str = "INSERT INTO steps (id, recording_id, type_, x, y, text, timestamp, screenshot_path, element_name, element_type, element_value, app_name, order_index, description, is_cropped, input_source, screenshot_after_path, identified_element_json, clip_path, title, element_bounds, page_url, page_title, automation_id, class_name, element_path, process_name, exe_path, window_title, breadcrumb, action, element_screenshot_path, ax_tree_json, ocr_text, ocr_status, description_generated, ocr_lines, annotations, redactions, click_marker, scale_factor, is_duplicate, original_path)
+     VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17, ?18, ?19, ?20, ?21, ?22, ?23, ?24, ?25, ?26, ?27, ?28, ?29, ?30, ?31, ?32, ?33, ?34, ?35, ?36, ?37, ?38, ?39, ?40, ?41, ?42, ?43)";
+
+/// Triggers rejecting edits to locked recordings. Updates to steps are only
+/// rejected for columns the user edits, so OCR results and repaired file
+/// paths are still written. Only `rewrite_data_dir`, which moves files rather
+/// than editing them, lifts them for a moment.
+const LOCKED_RECORDING_TRIGGERS: &str =
+    "CREATE TRIGGER IF NOT EXISTS locked_steps_insert BEFORE INSERT ON steps
+     WHEN (SELECT locked FROM recordings WHERE id = NEW.recording_id) = 1
+     BEGIN SELECT RAISE(ABORT, 'Recording is locked; unlock it before editing'); END;
+
+     CREATE TRIGGER IF NOT EXISTS locked_steps_update
+     BEFORE UPDATE OF recording_id, type_, x, y, text, order_index, description,
+         description_generated, title, is_cropped, element_bounds, ax_tree_json,
+         annotations, redactions, redactions_reviewed, click_marker, scale_factor
+     ON steps
+     WHEN (SELECT locked FROM recordings WHERE id = OLD.recording_id) = 1
+     BEGIN SELECT RAISE(ABORT, 'Recording is locked; unlock it before editing'); END;
+
+     CREATE TRIGGER IF NOT EXISTS locked_steps_delete BEFORE DELETE ON steps
+     WHEN (SELECT locked FROM recordings WHERE id = OLD.recording_id) = 1
+     BEGIN SELECT RAISE(ABORT, 'Recording is locked; unlock it before editing'); END;
+
+     CREATE TRIGGER IF NOT EXISTS locked_recordings_update
+     BEFORE UPDATE OF name, documentation, documentation_generated_at ON recordings
+     WHEN OLD.locked = 1
+     BEGIN SELECT RAISE(ABORT, 'Recording is locked; unlock it before editing'); END;";
+
+/// The error the triggers raise, for edits they can't see
+fn locked_error() -> rusqlite::Error {
+    rusqlite::Error::SqliteFailure(
+        rusqlite::ffi::Error::new(rusqlite::ffi::SQLITE_CONSTRAINT_TRIGGER),
+        Some("Recording is locked; unlock it before editing".to_string()),
+    )
+}
+
+/// Stamped into `PRAGMA user_version` by `init_schema`. Bump it with each
+/// migration added there, so diagnostics show which schema a database has.
+pub const SCHEMA_VERSION: i64 = 7;
+
+/// Step columns holding file paths, rewritten when the data directory moves
+const STEP_PATH_COLUMNS: &[&str] = &[
+    "screenshot_path",
+    "screenshot_after_path",
+    "element_screenshot_path",
+    "clip_path",
+    "original_path",
+];
+
+/// Text-only step types. They carry no screenshot and let authors structure
+/// long procedures: `heading` starts a section, `note` and `warning` are
+/// callouts attached to the surrounding steps. `marker` is a chapter break
+/// added by hotkey while recording, labelled by its `text`.
+pub const STEP_TYPE_NOTE: &str = "note";
+pub const STEP_TYPE_HEADING: &str = "heading";
+pub const STEP_TYPE_WARNING: &str = "warning";
+pub const STEP_TYPE_MARKER: &str = "marker";
+
+pub fn is_text_only_step_type(type_: &str) -> bool {
+    matches!(
+        type_,
+        STEP_TYPE_NOTE | STEP_TYPE_HEADING | STEP_TYPE_WARNING | STEP_TYPE_MARKER
+    )
+}
+
+/// Case-insensitive `str::replace`. Falls back to an exact match when
+/// lowercasing changes byte lengths, since offsets would no longer line up.
+fn replace_case_insensitive(text: &str, find: &str, replace: &str) -> String {
+    let haystack = text.to_lowercase();
+    let needle = find.to_lowercase();
+    if haystack.len() != text.len() || needle.len() != find.len() {
+        return text.replace(find, replace);
+    }
+
+    let mut out = String::with_capacity(text.len());
+    let mut last = 0;
+    for (start, _) in haystack.match_indices(&needle) {
+        out.push_str(&text[last..start]);
+        out.push_str(replace);
+        last = start + needle.len();
+    }
+    out.push_str(&text[last..]);
+    out
+}
+
+/// FTS5 query matching every word of `search` as a prefix, in any order.
+/// Words are quoted so punctuation and FTS operators in user input are taken
+/// literally. `None` when `search` has no words.
+fn fts_query(search: &str) -> Option<String> {
+    let terms: Vec<String> = search
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(|word| format!("\"{}\"*", word))
+        .collect();
+    if terms.is_empty() {
+        None
+    } else {
+        Some(terms.join(" "))
+    }
+}
+
+/// A stored image of a step
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StepImage {
+    /// The step's screenshot (`screenshot_path`)
+    Screenshot,
+    /// The frame captured after the action (`screenshot_after_path`)
+    After,
+    /// The crop of the clicked control (`element_screenshot_path`)
+    Element,
+}
+
+impl StepImage {
+    fn column(self) -> &'static str {
+        match self {
+            StepImage::Screenshot => "screenshot_path",
+            StepImage::After => "screenshot_after_path",
+            StepImage::Element => "element_screenshot_path",
+        }
+    }
+}
+
+/// A re-encoded file to point a step's image at
+#[derive(Clone, Debug, PartialEq)]
+pub struct ImageReplacement {
+    pub step_id: String,
+    pub image: StepImage,
+    pub path: String,
+    /// Width of the new image over the old one; 1.0 unless it was resized
+    pub scale: f64,
+}
+
+/// Image format screenshots are encoded to
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ScreenshotFormat {
+    #[default]
+    Jpeg,
+    Png,
+}
+
+impl ScreenshotFormat {
+    pub fn extension(self) -> &'static str {
+        match self {
+            ScreenshotFormat::Jpeg => "jpg",
+            ScreenshotFormat::Png => "png",
+        }
+    }
+}
+
+/// Image settings a recording carries in place of the global defaults, so
+/// training material can keep sharp screenshots while quick notes stay
+/// small. Unset fields follow the defaults.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RecordingImageSettings {
+    pub format: Option<ScreenshotFormat>,
+    /// JPEG quality, 10-95
+    pub quality: Option<u8>,
+    /// Wider screenshots are scaled down to this width
+    pub max_width: Option<u32>,
+    /// Click highlight drawn on this recording's screenshots
+    pub highlight: Option<ClickHighlightStyle>,
+}
+
+/// A named set of hotkey bindings, e.g. one for a laptop keyboard and one
+/// for an external one, or a team's shared shortcuts. Actions missing from
+/// `bindings` are unbound while the profile is in use.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct HotkeyProfile {
+    pub name: String,
+    pub bindings: HashMap<HotkeyAction, HotkeyBinding>,
+    /// Milliseconds since the epoch; 0 in exported files
+    #[serde(default)]
+    pub updated_at: i64,
+}
+
+impl RecordingImageSettings {
+    /// Whether new screenshots must be re-encoded to match
+    pub fn overrides_encoding(&self) -> bool {
+        self.format.is_some() || self.quality.is_some() || self.max_width.is_some()
+    }
+}
+
+/// Where newly captured screenshots are written when steps are saved.
+enum ScreenshotStorage {
+    /// The managed, content-addressed object store (see `write_screenshot_object`).
+    Objects(PathBuf),
+    /// A user-chosen folder; files are named `{recording_id}_{step_id}.jpg`
+    /// (`.png` for lossless originals).
+    Folder(PathBuf),
+}
+
+/// A screenshot copied into permanent storage during a batch save. The temp
+/// source is kept until the surrounding transaction commits so a rollback can
+/// restore the previous state. `dest` is None when the frame deduplicated
+/// onto an object that already existed, which a rollback must leave alone.
+struct CopiedScreenshot {
+    temp: PathBuf,
+    dest: Option<PathBuf>,
+}
+
+/// Resolve the stored screenshot paths (full frame, element crop and lossless
+/// original) for a step being inserted. Permanent
+/// paths are used as-is (when `honor_permanent` is set); temp captures are
+/// copied into `storage` and recorded in `copied` so the caller can finish or
+/// undo the copy once the transaction outcome is known. Either way the step's
+/// reference is counted if the path is a managed object.
+fn store_step_screenshot(
+    conn: &Connection,
+    step: &StepInput,
+    recording_id: &str,
+    step_id: &str,
+    storage: &ScreenshotStorage,
+    honor_permanent: bool,
+    copied: &mut Vec<CopiedScreenshot>,
+) -> Result<StoredScreenshots> {
+    if is_text_only_step_type(&step.type_) {
+        return Ok((None, None, None));
+    }
+
+    let permanent = honor_permanent && step.screenshot_is_permanent.unwrap_or(false);
+    let screenshot = store_screenshot_file(
+        conn,
+        step.screenshot.as_deref(),
+        permanent,
+        &format!("{}_{}", recording_id, step_id),
+        storage,
+        copied,
+    )?;
+    let element_screenshot = store_screenshot_file(
+        conn,
+        step.element_screenshot.as_deref(),
+        permanent,
+        &format!("{}_{}_element", recording_id, step_id),
+        storage,
+        copied,
+    )?;
+    // An original is only worth keeping next to the screenshot it backs
+    let original = match &screenshot {
+        Some(_) => store_screenshot_file(
+            conn,
+            step.screenshot_original.as_deref(),
+            permanent,
+            &format!("{}_{}_original", recording_id, step_id),
+            storage,
+            copied,
+        )?,
+        None => None,
+    };
+    Ok((screenshot, element_screenshot, original))
+}
+
+/// Stored paths of a step's screenshot, element crop and lossless original
+type StoredScreenshots = (Option<String>, Option<String>, Option<String>);
+
+/// Extension of a stored image, "jpg" when it has none
+fn image_extension(path: &Path) -> &str {
+    path.extension()
+        .and_then(|extension| extension.to_str())
+        .unwrap_or("jpg")
+}
+
+/// Store one image of a step (see `store_step_screenshot`). `file_stem`
+/// names the copy in a `Folder` storage.
+fn store_screenshot_file(
+    conn: &Connection,
+    path: Option<&str>,
+    permanent: bool,
+    file_stem: &str,
+    storage: &ScreenshotStorage,
+    copied: &mut Vec<CopiedScreenshot>,
+) -> Result<Option<String>> {
+    if permanent {
+        // Screenshot is already in permanent storage, use it directly
+        if let Some(path) = path {
+            retain_screenshot(conn, path)?;
+        }
+        return Ok(path.map(str::to_string));
+    }
+
+    // Copy screenshot from temp to persistent storage
+    let Some(temp_path) = path.map(PathBuf::from) else {
+        return Ok(None);
+    };
+    if !temp_path.exists() {
+        return Ok(None);
+    }
+
+    match storage {
+        ScreenshotStorage::Objects(objects_dir) => {
+            let Ok(bytes) = fs::read(&temp_path) else {
+                return Ok(None);
+            };
+            let Ok((dest_path, created)) =
+                write_screenshot_object(conn, objects_dir, &bytes, image_extension(&temp_path))
+            else {
+                return Ok(None);
+            };
+
+            let stored = dest_path.to_string_lossy().to_string();
+            retain_screenshot(conn, &stored)?;
+            copied.push(CopiedScreenshot {
+                temp: temp_path,
+                dest: created.then_some(dest_path),
+            });
+            Ok(Some(stored))
+        }
+        ScreenshotStorage::Folder(screenshots_dir) => {
+            let filename = format!("{}.{}", file_stem, image_extension(&temp_path));
+            let dest_path = screenshots_dir.join(&filename);
+            if fs::copy(&temp_path, &dest_path).is_err() {
+                return Ok(None);
+            }
+
+            let stored = dest_path.to_string_lossy().to_string();
+            copied.push(CopiedScreenshot {
+                temp: temp_path,
+                dest: Some(dest_path),
+            });
+            Ok(Some(stored))
+        }
+    }
+}
+
+/// Store `bytes` in the object store under
+/// `<objects_dir>/<ab>/<sha256>.<extension>` and register it in
+/// `screenshot_objects` with no references. Identical frames map to the same
+/// file. Returns the object path and whether this call created the file
+/// (false when the content was already stored).
+fn write_screenshot_object(
+    conn: &Connection,
+    objects_dir: &Path,
+    bytes: &[u8],
+    extension: &str,
+) -> std::io::Result<(PathBuf, bool)> {
+    use sha2::{Digest, Sha256};
+
+    let hash: String = Sha256::digest(bytes)
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect();
+    let shard_dir = objects_dir.join(&hash[..2]);
+    let path = shard_dir.join(format!("{}.{}", hash, extension));
+
+    let created = if path.exists() {
+        false
+    } else {
+        fs::create_dir_all(&shard_dir)?;
+        // Write then rename so a crash never leaves a truncated object behind.
+        let partial = path.with_extension(format!("{}.part", extension));
+        fs::write(&partial, bytes)?;
+        fs::rename(&partial, &path)?;
+        true
+    };
+
+    conn.execute(
+        "INSERT OR IGNORE INTO screenshot_objects (hash, path, size, ref_count, created_at)
+         VALUES (?1, ?2, ?3, 0, ?4)",
+        params![
+            hash,
+            path.to_string_lossy(),
+            bytes.len() as i64,
+            chrono::Utc::now().timestamp_millis()
+        ],
+    )
+    .map_err(std::io::Error::other)?;
+
+    Ok((path, created))
+}
+
+fn is_screenshot_object(conn: &Connection, path: &str) -> Result<bool> {
+    conn.query_row(
+        "SELECT 1 FROM screenshot_objects WHERE path = ?1",
+        params![path],
+        |_| Ok(()),
+    )
+    .optional()
+    .map(|found| found.is_some())
+}
+
+/// Count one more step reference to `path`. No-op for plain files.
+fn retain_screenshot(conn: &Connection, path: &str) -> Result<()> {
+    conn.execute(
+        "UPDATE screenshot_objects SET ref_count = ref_count + 1 WHERE path = ?1",
+        params![path],
+    )?;
+    Ok(())
+}
+
+/// Drop one step reference to `path`. Returns true when nothing references
+/// the file any more and it may be deleted: always for plain files, and for
+/// objects once their count reaches zero (the object row is removed then).
+fn release_screenshot(conn: &Connection, path: &str) -> Result<bool> {
+    let remaining: Option<i64> = conn
+        .query_row(
+            "UPDATE screenshot_objects SET ref_count = MAX(ref_count - 1, 0)
+             WHERE path = ?1 RETURNING ref_count",
+            params![path],
+            |row| row.get(0),
+        )
+        .optional()?;
+
+    match remaining {
+        None => Ok(true),
+        Some(0) => {
+            conn.execute(
+                "DELETE FROM screenshot_objects WHERE path = ?1",
+                params![path],
+            )?;
+            Ok(true)
+        }
+        Some(_) => Ok(false),
+    }
+}
+
+/// Apply the outcome of a transactional save to the screenshots it copied:
+/// temp sources are removed on success, the copies are removed on failure.
+fn settle_copied_screenshots<T>(result: Result<T>, copied: &[CopiedScreenshot]) -> Result<T> {
+    match result {
+        Ok(value) => {
+            for copy in copied {
+                let _ = fs::remove_file(&copy.temp);
+            }
+            Ok(value)
+        }
+        Err(err) => {
+            for dest in copied.iter().filter_map(|copy| copy.dest.as_ref()) {
+                let _ = fs::remove_file(dest);
+            }
+            Err(err)
+        }
+    }
+}
+
+/// Column list matching `map_step_row`.
+const STEP_COLUMNS: &str = "id, recording_id, type_, x, y, text, timestamp, screenshot_path,
+     element_name, element_type, element_value, app_name, order_index, description, is_cropped,
+     ocr_text, ocr_status, input_source, screenshot_after_path,
+     identified_element_json, clip_path, title, element_bounds, page_url, page_title,
+     automation_id, class_name, element_path, process_name, exe_path, window_title, breadcrumb,
+     action, element_screenshot_path, ax_tree_json, description_generated, ocr_lines,
+     annotations, redactions, redactions_reviewed, click_marker, scale_factor,
+     is_duplicate, original_path";
+
+fn map_step_row(row: &rusqlite::Row<'_>) -> Result<Step> {
+    Ok(Step {
+        id: row.get(0)?,
+        recording_id: row.get(1)?,
+        type_: row.get(2)?,
+        x: row.get(3)?,
+        y: row.get(4)?,
+        text: row.get(5)?,
+        timestamp: row.get(6)?,
+        screenshot_path: row.get(7)?,
+        element_name: row.get(8)?,
+        element_type: row.get(9)?,
+        element_value: row.get(10)?,
+        app_name: row.get(11)?,
+        order_index: row.get(12)?,
+        description: row.get(13)?,
+        is_cropped: row.get::<_, Option<i32>>(14)?.map(|v| v != 0),
+        ocr_text: row.get(15)?,
+        ocr_status: row.get(16)?,
+        input_source: row.get(17)?,
+        screenshot_after_path: row.get(18)?,
+        identified_element_json: row.get(19)?,
+        clip_path: row.get(20)?,
+        title: row.get(21)?,
+        element_bounds: row
+            .get::<_, Option<String>>(22)?
+            .and_then(|json| serde_json::from_str(&json).ok()),
+        page_url: row.get(23)?,
+        page_title: row.get(24)?,
+        automation_id: row.get(25)?,
+        class_name: row.get(26)?,
+        element_path: row
+            .get::<_, Option<String>>(27)?
+            .and_then(|json| serde_json::from_str(&json).ok()),
+        process_name: row.get(28)?,
+        exe_path: row.get(29)?,
+        window_title: row.get(30)?,
+        breadcrumb: row.get(31)?,
+        action: row.get(32)?,
+        element_screenshot_path: row.get(33)?,
+        ax_tree_json: row.get(34)?,
+        description_generated: row.get::<_, Option<i32>>(35)?.unwrap_or(0) != 0,
+        ocr_lines: row
+            .get::<_, Option<String>>(36)?
+            .and_then(|json| serde_json::from_str(&json).ok()),
+        annotations: row
+            .get::<_, Option<String>>(37)?
+            .and_then(|json| serde_json::from_str(&json).ok()),
+        redactions: row
+            .get::<_, Option<String>>(38)?
+            .and_then(|json| serde_json::from_str(&json).ok()),
+        redactions_reviewed: row.get::<_, Option<i32>>(39)?.unwrap_or(0) != 0,
+        click_marker: row
+            .get::<_, Option<String>>(40)?
+            .and_then(|json| serde_json::from_str(&json).ok()),
+        scale_factor: row.get(41)?,
+        is_duplicate: row.get::<_, Option<i32>>(42)?.map(|v| v != 0),
+        original_path: row.get(43)?,
+    })
+}
+
+fn map_share_link_row(row: &rusqlite::Row<'_>) -> Result<ShareLink> {
+    let password_hash: Option<String> = row.get(6)?;
+    Ok(ShareLink {
+        token: row.get(0)?,
+        recording_id: row.get(1)?,
+        url: row.get(2)?,
+        created_at: row.get(3)?,
+        expires_at: row.get(4)?,
+        hosted: row.get(5)?,
+        has_password: password_hash.is_some(),
+        password_hash,
+    })
+}
+
+/// Duplicate step `?5` as `?1` in recording `?2`, with new screenshot paths
+/// (`?3`, `?4`, `?6` for the element crop, `?7` for the lossless original). `order_index` is set by the caller's renumbering pass.
+const COPY_STEP_SQL: &str =
+    "INSERT INTO steps (id, recording_id, screenshot_path, screenshot_after_path,
+         element_screenshot_path, order_index, type_, x, y, text, timestamp, element_name, element_type, element_value, app_name,
+         description, is_cropped, ocr_text, ocr_status, input_source, identified_element_json,
+         clip_path, title, element_bounds, page_url, page_title, automation_id, class_name,
+         element_path, process_name, exe_path, window_title, breadcrumb, action, ax_tree_json,
+         description_generated, ocr_lines, annotations, redactions, redactions_reviewed,
+         click_marker, scale_factor, is_duplicate, original_path)
+     SELECT ?1, ?2, ?3, ?4, ?6, order_index, type_, x, y, text, timestamp, element_name, element_type, element_value, app_name,
+         description, is_cropped, ocr_text, ocr_status, input_source, identified_element_json,
+         clip_path, title, element_bounds, page_url, page_title, automation_id, class_name,
+         element_path, process_name, exe_path, window_title, breadcrumb, action, ax_tree_json,
+         description_generated, ocr_lines, annotations, redactions, redactions_reviewed,
+         click_marker, scale_factor, is_duplicate, ?7
+     FROM steps WHERE id = ?5";
+
+/// Copy a plain screenshot file next to itself as
+/// `{recording_id}_{step_id}{suffix}.jpg` (keeping a `.png`) for a duplicated step. Returns None
+/// if the source is missing or the copy fails.
+fn copy_step_file(
+    path: &str,
+    recording_id: &str,
+    step_id: &str,
+    suffix: &str,
+    written: &mut Vec<PathBuf>,
+) -> Option<String> {
+    let source = Path::new(path);
+    let dest = source.with_file_name(format!(
+        "{}_{}{}.{}",
+        recording_id,
+        step_id,
+        suffix,
+        image_extension(source)
+    ));
+    fs::copy(source, &dest).ok()?;
+    let stored = dest.to_string_lossy().to_string();
+    written.push(dest);
+    Some(stored)
+}
+
+/// Run `INSERT_STEP_SQL` for one step. Steps saved without a description
+/// get one from `describe_step`.
+fn execute_step_insert(
+    insert: &mut rusqlite::Statement<'_>,
+    step_id: &str,
+    recording_id: &str,
+    step: &StepInput,
+    (screenshot_path, element_screenshot_path, original_path): StoredScreenshots,
+    order_index: i32,
+) -> Result<usize> {
+    let written = step
+        .description
+        .as_deref()
+        .filter(|description| !description.trim().is_empty());
+    let generated = match written {
+        Some(_) => None,
+        None => describe_step(&step.into()),
+    };
+    insert.execute(params![
+        step_id,
+        recording_id,
+        step.type_,
+        step.x,
+        step.y,
+        step.text,
+        step.timestamp,
+        screenshot_path,
+        step.element_name,
+        step.element_type,
+        step.element_value,
+        step.app_name,
+        order_index,
+        written.map(str::to_string).or_else(|| generated.clone()),
+        step.is_cropped.unwrap_or(false) as i32,
+        step.input_source,
+        step.screenshot_after,
+        step.identified_element_json,
+        step.clip_path,
+        step.title,
+        step.element_bounds
+            .and_then(|bounds| serde_json::to_string(&bounds).ok()),
+        step.page_url,
+        step.page_title,
+        step.automation_id,
+        step.class_name,
+        step.element_path
+            .as_ref()
+            .and_then(|path| serde_json::to_string(path).ok()),
+        step.process_name,
+        step.exe_path,
+        step.window_title,
+        step.breadcrumb,
+        step.action,
+        element_screenshot_path,
+        step.ax_tree_json,
+        step.ocr_text,
+        step.ocr_status.as_deref().unwrap_or("pending"),
+        generated.is_some() as i32,
+        step.ocr_lines
+            .as_ref()
+            .and_then(|lines| serde_json::to_string(lines).ok()),
+        step.annotations
+            .as_ref()
+            .filter(|annotations| !annotations.is_empty())
+            .and_then(|annotations| serde_json::to_string(annotations).ok()),
+        step.redactions
+            .as_ref()
+            .filter(|redactions| !redactions.is_empty())
+            .and_then(|redactions| serde_json::to_string(redactions).ok()),
+        step.click_marker
+            .and_then(|marker| serde_json::to_string(&marker).ok()),
+        step.scale_factor,
+        step.is_duplicate.unwrap_or(false) as i32,
+        original_path
+    ])
+}
+
 #[derive(Debug, Clone)]
 pub struct DeleteRecordingCleanup {
     pub files: Vec<PathBuf>,
@@ -12,7 +662,7 @@ pub struct DeleteRecordingCleanup {
     pub protected_dir: PathBuf,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
 pub struct Recording {
     pub id: String,
     pub name: String,
@@ -31,9 +681,18 @@ pub struct Recording {
     /// than two steps.
     #[serde(default)]
     pub duration_ms: Option<i64>,
+    /// Pinned by the user; favorites lead the statistics recent list.
+    #[serde(default)]
+    pub favorite: bool,
+    /// Set when the recording's screenshots were re-encoded for archival.
+    #[serde(default)]
+    pub archived_at: Option<i64>,
+    /// Read-only: step and documentation edits are rejected until unlocked.
+    #[serde(default)]
+    pub locked: bool,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
 pub struct Step {
     pub id: String,
     pub recording_id: String,
@@ -65,6 +724,89 @@ pub struct Step {
     /// Path to the short animated clip captured around this event (Phase 8a).
     pub clip_path: Option<String>,
     pub title: Option<String>,
+    /// Screen rectangle of the clicked element, relative to the screenshot.
+    /// Lets the editor and exporters crop or zoom to the control.
+    #[serde(default)]
+    pub element_bounds: Option<ElementBounds>,
+    /// Browser address and tab title when the step happened in a web page.
+    #[serde(default)]
+    pub page_url: Option<String>,
+    #[serde(default)]
+    pub page_title: Option<String>,
+    /// Stable identifiers of the clicked element (UIA AutomationId / class
+    /// name or AX / AT-SPI equivalents) and its ancestor path, outermost
+    /// first. Raw material for generated test selectors.
+    #[serde(default)]
+    pub automation_id: Option<String>,
+    #[serde(default)]
+    pub class_name: Option<String>,
+    #[serde(default)]
+    pub element_path: Option<Vec<ElementPathSegment>>,
+    /// Foreground process (file name and full executable path) and window
+    /// title when the step was captured, read from the OS rather than the
+    /// accessibility tree.
+    #[serde(default)]
+    pub process_name: Option<String>,
+    #[serde(default)]
+    pub exe_path: Option<String>,
+    #[serde(default)]
+    pub window_title: Option<String>,
+    /// Where the element sits in the UI, e.g. `Settings dialog > Network tab
+    /// > Port field`.
+    #[serde(default)]
+    pub breadcrumb: Option<String>,
+    /// Instruction inferred from the clicked control's role and state, e.g.
+    /// `Check "Remember me"`. See `actions::infer_click_action`.
+    #[serde(default)]
+    pub action: Option<String>,
+    /// Tight crop of the clicked control, saved next to the full frame when
+    /// its bounds were known. Exports use it as a zoomed inset.
+    #[serde(default)]
+    pub element_screenshot_path: Option<String>,
+    /// JSON `AxNode` snapshot of the accessibility tree around the clicked
+    /// element, when the recorder was asked to take one.
+    #[serde(default)]
+    pub ax_tree_json: Option<String>,
+    /// Set while `description` is the default from `describe_step` rather
+    /// than text the user wrote. Cleared by any edit.
+    #[serde(default)]
+    pub description_generated: bool,
+    /// `ocr_text` line by line with the engine's confidence, so doubtful
+    /// lines can be flagged for review. None for text that didn't come from
+    /// OCR and for steps read before this was recorded.
+    #[serde(default)]
+    pub ocr_lines: Option<Vec<OcrLine>>,
+    /// Arrows, boxes and labels drawn over the screenshot at export time.
+    /// See `annotations::render_annotated_screenshot`.
+    #[serde(default)]
+    pub annotations: Option<Vec<Annotation>>,
+    /// Areas blurred or pixelated at export, for users who keep the
+    /// unredacted capture. Redactions burned in straight away aren't listed.
+    #[serde(default)]
+    pub redactions: Option<Vec<Redaction>>,
+    /// Set once the user has accepted, dismissed or drawn redactions, so
+    /// `pii::suggest_redaction` stops proposing them.
+    #[serde(default)]
+    pub redactions_reviewed: bool,
+    /// Where the click highlight is drawn, in screenshot pixels. Set by the
+    /// recorder for clicks and moved or cleared by the user. Steps recorded
+    /// before this have the ring burned into the screenshot and none here.
+    #[serde(default)]
+    pub click_marker: Option<Point>,
+    /// Display scale of the monitor the screenshot came from. `x`/`y`, the
+    /// bounds and the marker are in its physical pixels; divided by this
+    /// they're the logical pixels the user saw. None for steps recorded
+    /// before it was kept.
+    #[serde(default)]
+    pub scale_factor: Option<f64>,
+    /// Set when the recorder found the frame nearly identical to the previous
+    /// step's and reused its screenshot; see `phash`.
+    #[serde(default)]
+    pub is_duplicate: Option<bool>,
+    /// Lossless PNG of the screenshot. Edits and exports start from it so
+    /// JPEG artifacts don't compound; dropped when over the storage quota.
+    #[serde(default)]
+    pub original_path: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -93,6 +835,53 @@ pub struct StepInput {
     pub identified_element_json: Option<String>,
     #[serde(default)]
     pub clip_path: Option<String>,
+    #[serde(default)]
+    pub element_bounds: Option<ElementBounds>,
+    #[serde(default)]
+    pub page_url: Option<String>,
+    #[serde(default)]
+    pub page_title: Option<String>,
+    #[serde(default)]
+    pub automation_id: Option<String>,
+    #[serde(default)]
+    pub class_name: Option<String>,
+    #[serde(default)]
+    pub element_path: Option<Vec<ElementPathSegment>>,
+    #[serde(default)]
+    pub process_name: Option<String>,
+    #[serde(default)]
+    pub exe_path: Option<String>,
+    #[serde(default)]
+    pub window_title: Option<String>,
+    #[serde(default)]
+    pub breadcrumb: Option<String>,
+    #[serde(default)]
+    pub action: Option<String>,
+    /// Temp path of the element crop; stored like `screenshot`.
+    #[serde(default)]
+    pub element_screenshot: Option<String>,
+    #[serde(default)]
+    pub ax_tree_json: Option<String>,
+    /// Text recognised while recording, when it arrived before the save.
+    #[serde(default)]
+    pub ocr_text: Option<String>,
+    #[serde(default)]
+    pub ocr_status: Option<String>,
+    #[serde(default)]
+    pub ocr_lines: Option<Vec<OcrLine>>,
+    #[serde(default)]
+    pub annotations: Option<Vec<Annotation>>,
+    #[serde(default)]
+    pub redactions: Option<Vec<Redaction>>,
+    #[serde(default)]
+    pub click_marker: Option<Point>,
+    #[serde(default)]
+    pub scale_factor: Option<f64>,
+    #[serde(default)]
+    pub is_duplicate: Option<bool>,
+    /// Temp path of the lossless original; stored like `screenshot`.
+    #[serde(default)]
+    pub screenshot_original: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -101,6 +890,34 @@ pub struct RecordingWithSteps {
     pub steps: Vec<Step>,
 }
 
+/// A step found by `search_step_text`
+#[derive(Debug, Serialize)]
+pub struct StepTextMatch {
+    pub recording_id: String,
+    pub recording_name: String,
+    pub step_id: String,
+    pub order_index: i32,
+    /// Recognised text around the match
+    pub snippet: String,
+}
+
+/// How far text extraction has got for one recording's screenshots
+#[derive(Debug, Default, Serialize, PartialEq, Eq)]
+pub struct RecordingOcrStatus {
+    /// Steps with a screenshot to read
+    pub total: i64,
+    pub completed: i64,
+    /// Labelled from the accessibility tree instead of OCR
+    pub accessibility: i64,
+    pub failed: i64,
+    /// Dropped from a full OCR queue; read again when the recording is opened
+    pub skipped: i64,
+    pub pending: i64,
+    /// Nothing is pending or skipped, so exports include all the text there
+    /// will be
+    pub finished: bool,
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct PaginatedRecordings {
     pub recordings: Vec<Recording>,
@@ -110,6 +927,202 @@ pub struct PaginatedRecordings {
     pub total_pages: i32,
 }
 
+/// Result of turning a crashed session's draft steps into a recording.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RecoveredDraft {
+    pub recording_id: String,
+    pub recording_name: String,
+    pub step_count: usize,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RecordingStatistics {
+    pub total_recordings: i64,
+    pub total_steps: i64,
+    pub favorite_count: i64,
+    /// Favorites first, then the most recently updated recordings.
+    pub recent: Vec<Recording>,
+}
+
+/// New name of a recording after a batch rename.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct RenamedRecording {
+    pub id: String,
+    pub name: String,
+}
+
+/// Expand a batch-rename pattern: `{n}` is the sequence number, zero-padded to
+/// `width` digits, `{name}` the current name and `{date}` the creation date
+/// (YYYY-MM-DD, UTC).
+pub fn apply_name_pattern(
+    pattern: &str,
+    number: i64,
+    width: usize,
+    name: &str,
+    created_at: i64,
+) -> String {
+    let date = chrono::DateTime::from_timestamp_millis(created_at)
+        .map(|d| d.format("%Y-%m-%d").to_string())
+        .unwrap_or_default();
+    let number = format!("{:0width$}", number, width = width);
+    // One pass over the pattern, so braces in the old name stay literal
+    let mut expanded = String::with_capacity(pattern.len() + name.len());
+    let mut rest = pattern;
+    while let Some(start) = rest.find('{') {
+        expanded.push_str(&rest[..start]);
+        rest = &rest[start..];
+        let token = ["{n}", "{name}", "{date}"]
+            .into_iter()
+            .find(|token| rest.starts_with(token));
+        match token {
+            Some(token) => {
+                expanded.push_str(match token {
+                    "{n}" => &number,
+                    "{name}" => name,
+                    _ => &date,
+                });
+                rest = &rest[token.len()..];
+            }
+            None => {
+                expanded.push('{');
+                rest = &rest[1..];
+            }
+        }
+    }
+    expanded.push_str(rest);
+    expanded.trim().to_string()
+}
+
+/// Disk usage of one recording's screenshots, after-frames and clips.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct RecordingStorageUsage {
+    pub recording_id: String,
+    pub name: String,
+    pub file_count: i64,
+    /// All bytes referenced by the recording, including shared objects.
+    pub bytes: i64,
+    /// The part of `bytes` stored in deduplicated objects, which may also be
+    /// referenced by other recordings.
+    pub shared_bytes: i64,
+    pub computed_at: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct StorageUsage {
+    /// Largest recordings first.
+    pub recordings: Vec<RecordingStorageUsage>,
+    /// Library total, counting each shared object once.
+    pub total_bytes: i64,
+}
+
+pub const LINK_KIND_PREREQUISITE: &str = "prerequisite";
+pub const LINK_KIND_SEE_ALSO: &str = "see_also";
+
+/// A directed link from one recording to another.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct RecordingLink {
+    pub from_id: String,
+    pub to_id: String,
+    /// Name of the target recording, for display and export cross-references.
+    pub to_name: String,
+    /// `prerequisite` or `see_also`.
+    pub kind: String,
+    pub created_at: i64,
+}
+
+/// What cloud sync last knew about a recording; see `sync`.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct SyncState {
+    pub recording_id: String,
+    /// Name of the recording's bundle in the sync folder, without extension.
+    pub remote_key: String,
+    /// Version of the bundle when it was last pushed or pulled.
+    pub remote_etag: Option<String>,
+    /// The recording's `updated_at` when it was last synced.
+    pub local_updated_at: i64,
+    pub synced_at: i64,
+    /// Why the recording waits for the user to keep one side, if it does.
+    pub conflict: Option<String>,
+}
+
+/// A shareable link to a snapshot of a recording; see `share`.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct ShareLink {
+    pub token: String,
+    pub recording_id: String,
+    pub url: String,
+    pub created_at: i64,
+    pub expires_at: i64,
+    /// Uploaded to the static host rather than served by the app.
+    pub hosted: bool,
+    /// Salted hash of the password, for links served by the app.
+    #[serde(skip)]
+    pub password_hash: Option<String>,
+    pub has_password: bool,
+}
+
+/// A stretch of transcribed narration, in ms from the start of the audio.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct NarrationSegment {
+    pub start_ms: i64,
+    pub end_ms: i64,
+    pub text: String,
+}
+
+/// The narration transcript of a recording; see `transcription`.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct Narration {
+    pub recording_id: String,
+    pub audio_path: String,
+    /// Wall-clock time (ms) the audio starts at, on the steps' clock.
+    pub started_at: i64,
+    pub language: Option<String>,
+    pub segments: Vec<NarrationSegment>,
+    pub transcribed_at: i64,
+}
+
+/// A screenshot uploaded to an image host; see `image_host`.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct HostedImage {
+    /// SHA-256 of the uploaded file
+    pub sha256: String,
+    /// The host and folder it went to, so changing hosts uploads again
+    pub destination: String,
+    pub url: String,
+    pub uploaded_at: i64,
+    /// When `url` stops working, for expiring links
+    pub expires_at: Option<i64>,
+}
+
+/// One row of the append-only audit trail.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct AuditEntry {
+    pub id: i64,
+    pub recording_id: Option<String>,
+    pub step_id: Option<String>,
+    pub action: String,
+    pub actor: String,
+    /// JSON describing the change (new values, affected step IDs, ...).
+    pub detail: Option<String>,
+    pub created_at: i64,
+}
+
+/// Uses of one feature on one day (local `YYYY-MM-DD`).
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct UsageCount {
+    pub day: String,
+    pub feature: String,
+    pub count: i64,
+}
+
+/// One timing of a capture stage.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct MetricSample {
+    pub metric: String,
+    pub value_ms: f64,
+    pub recorded_at: i64,
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct Notification {
     pub id: String,
@@ -124,8 +1137,16 @@ pub struct Notification {
 pub struct Database {
     conn: Connection,
     data_dir: PathBuf,
+    /// OS user recorded as the actor in the audit log.
+    actor: String,
+    /// Bytes lossless originals may take before the oldest are dropped;
+    /// see `enforce_original_quota`.
+    original_quota: u64,
 }
 
+/// Default cap on the disk space of lossless originals (2 GiB)
+pub const DEFAULT_ORIGINAL_QUOTA: u64 = 2 * 1024 * 1024 * 1024;
+
 impl Database {
     pub fn new(app_data_dir: PathBuf) -> Result<Self> {
         // Ensure directory exists
@@ -135,12 +1156,18 @@ impl Database {
         let db_path = app_data_dir.join("stepsnap.db");
         let conn = Connection::open(&db_path)?;
 
+        let actor = std::env::var("USERNAME")
+            .or_else(|_| std::env::var("USER"))
+            .unwrap_or_else(|_| "unknown".to_string());
         let db = Database {
             conn,
             data_dir: app_data_dir,
+            actor,
+            original_quota: DEFAULT_ORIGINAL_QUOTA,
         };
 
         db.init_schema()?;
+        db.prune_screenshot_objects()?;
         Ok(db)
     }
 
@@ -281,37 +1308,441 @@ impl Database {
                 .execute("ALTER TABLE steps ADD COLUMN clip_path TEXT", [])?;
         }
 
-        // Migration: Add title column if it doesn't exist
-        let has_title: bool = self
+        // Migration: Add element_bounds column (JSON ElementBounds).
+        let has_element_bounds: bool = self
             .conn
-            .prepare("SELECT title FROM steps LIMIT 1")
+            .prepare("SELECT element_bounds FROM steps LIMIT 1")
             .is_ok();
 
-        if !has_title {
+        if !has_element_bounds {
             self.conn
-                .execute("ALTER TABLE steps ADD COLUMN title TEXT", [])?;
+                .execute("ALTER TABLE steps ADD COLUMN element_bounds TEXT", [])?;
         }
 
-        // Migration: Add documentation_generated_at column to recordings if it doesn't exist
-        let has_doc_generated_at: bool = self
+        // Migration: Add browser page columns (URL and tab title).
+        let has_page_url: bool = self
             .conn
-            .prepare("SELECT documentation_generated_at FROM recordings LIMIT 1")
+            .prepare("SELECT page_url FROM steps LIMIT 1")
             .is_ok();
 
-        if !has_doc_generated_at {
+        if !has_page_url {
+            self.conn
+                .execute("ALTER TABLE steps ADD COLUMN page_url TEXT", [])?;
+            self.conn
+                .execute("ALTER TABLE steps ADD COLUMN page_title TEXT", [])?;
+        }
+
+        // Migration: Add selector columns (automation id, class name and the
+        // JSON ancestor path).
+        let has_automation_id: bool = self
+            .conn
+            .prepare("SELECT automation_id FROM steps LIMIT 1")
+            .is_ok();
+
+        if !has_automation_id {
+            self.conn
+                .execute("ALTER TABLE steps ADD COLUMN automation_id TEXT", [])?;
+            self.conn
+                .execute("ALTER TABLE steps ADD COLUMN class_name TEXT", [])?;
+            self.conn
+                .execute("ALTER TABLE steps ADD COLUMN element_path TEXT", [])?;
+        }
+
+        // Migration: Add foreground process and window title columns
+        let has_process_name: bool = self
+            .conn
+            .prepare("SELECT process_name FROM steps LIMIT 1")
+            .is_ok();
+
+        if !has_process_name {
+            self.conn
+                .execute("ALTER TABLE steps ADD COLUMN process_name TEXT", [])?;
+            self.conn
+                .execute("ALTER TABLE steps ADD COLUMN exe_path TEXT", [])?;
+            self.conn
+                .execute("ALTER TABLE steps ADD COLUMN window_title TEXT", [])?;
+        }
+
+        // Migration: Add UI breadcrumb column
+        let has_breadcrumb: bool = self
+            .conn
+            .prepare("SELECT breadcrumb FROM steps LIMIT 1")
+            .is_ok();
+
+        if !has_breadcrumb {
+            self.conn
+                .execute("ALTER TABLE steps ADD COLUMN breadcrumb TEXT", [])?;
+        }
+
+        // Migration: Add inferred action column
+        let has_action: bool = self
+            .conn
+            .prepare("SELECT action FROM steps LIMIT 1")
+            .is_ok();
+
+        if !has_action {
+            self.conn
+                .execute("ALTER TABLE steps ADD COLUMN action TEXT", [])?;
+        }
+
+        // Migration: Add element crop column
+        let has_element_screenshot: bool = self
+            .conn
+            .prepare("SELECT element_screenshot_path FROM steps LIMIT 1")
+            .is_ok();
+
+        if !has_element_screenshot {
             self.conn.execute(
-                "ALTER TABLE recordings ADD COLUMN documentation_generated_at INTEGER",
+                "ALTER TABLE steps ADD COLUMN element_screenshot_path TEXT",
                 [],
             )?;
         }
 
-        // Backfill: For existing recordings with documentation but no documentation_generated_at,
-        // set it to updated_at (assumes docs were in sync at last update)
-        self.conn.execute(
-            "UPDATE recordings SET documentation_generated_at = updated_at
-             WHERE documentation IS NOT NULL AND documentation_generated_at IS NULL",
-            [],
-        )?;
+        // Migration: Add accessibility subtree snapshot column
+        let has_ax_tree_json: bool = self
+            .conn
+            .prepare("SELECT ax_tree_json FROM steps LIMIT 1")
+            .is_ok();
+
+        if !has_ax_tree_json {
+            self.conn
+                .execute("ALTER TABLE steps ADD COLUMN ax_tree_json TEXT", [])?;
+        }
+
+        // Migration: Add flag marking descriptions written by `describe_step`
+        let has_description_generated: bool = self
+            .conn
+            .prepare("SELECT description_generated FROM steps LIMIT 1")
+            .is_ok();
+
+        if !has_description_generated {
+            self.conn.execute(
+                "ALTER TABLE steps ADD COLUMN description_generated INTEGER DEFAULT 0",
+                [],
+            )?;
+        }
+
+        // Migration: Add per-line OCR confidence (JSON `OcrLine` list)
+        let has_ocr_lines: bool = self
+            .conn
+            .prepare("SELECT ocr_lines FROM steps LIMIT 1")
+            .is_ok();
+
+        if !has_ocr_lines {
+            self.conn
+                .execute("ALTER TABLE steps ADD COLUMN ocr_lines TEXT", [])?;
+        }
+
+        // Migration: Add screenshot annotations (JSON `Annotation` list)
+        let has_annotations: bool = self
+            .conn
+            .prepare("SELECT annotations FROM steps LIMIT 1")
+            .is_ok();
+
+        if !has_annotations {
+            self.conn
+                .execute("ALTER TABLE steps ADD COLUMN annotations TEXT", [])?;
+        }
+
+        // Migration: Add redaction areas applied at export (JSON `Redaction` list)
+        let has_redactions: bool = self
+            .conn
+            .prepare("SELECT redactions FROM steps LIMIT 1")
+            .is_ok();
+
+        if !has_redactions {
+            self.conn
+                .execute("ALTER TABLE steps ADD COLUMN redactions TEXT", [])?;
+        }
+
+        // Migration: Add redactions_reviewed flag (suggestions accepted or dismissed)
+        let has_redactions_reviewed: bool = self
+            .conn
+            .prepare("SELECT redactions_reviewed FROM steps LIMIT 1")
+            .is_ok();
+
+        if !has_redactions_reviewed {
+            self.conn.execute(
+                "ALTER TABLE steps ADD COLUMN redactions_reviewed INTEGER NOT NULL DEFAULT 0",
+                [],
+            )?;
+        }
+
+        // Migration: Add click_marker (JSON `Point` the click highlight is drawn at)
+        let has_click_marker: bool = self
+            .conn
+            .prepare("SELECT click_marker FROM steps LIMIT 1")
+            .is_ok();
+
+        if !has_click_marker {
+            self.conn
+                .execute("ALTER TABLE steps ADD COLUMN click_marker TEXT", [])?;
+        }
+
+        // Migration: Add scale_factor (display scale of the captured monitor)
+        let has_scale_factor: bool = self
+            .conn
+            .prepare("SELECT scale_factor FROM steps LIMIT 1")
+            .is_ok();
+
+        if !has_scale_factor {
+            self.conn
+                .execute("ALTER TABLE steps ADD COLUMN scale_factor REAL", [])?;
+        }
+
+        // Migration: Add is_duplicate (frame repeated the previous step's)
+        let has_is_duplicate: bool = self
+            .conn
+            .prepare("SELECT is_duplicate FROM steps LIMIT 1")
+            .is_ok();
+
+        if !has_is_duplicate {
+            self.conn.execute(
+                "ALTER TABLE steps ADD COLUMN is_duplicate INTEGER DEFAULT 0",
+                [],
+            )?;
+        }
+
+        // Migration: Add original_path (lossless PNG kept with the screenshot)
+        let has_original_path: bool = self
+            .conn
+            .prepare("SELECT original_path FROM steps LIMIT 1")
+            .is_ok();
+
+        if !has_original_path {
+            self.conn
+                .execute("ALTER TABLE steps ADD COLUMN original_path TEXT", [])?;
+        }
+
+        // Migration: Add title column if it doesn't exist
+        let has_title: bool = self
+            .conn
+            .prepare("SELECT title FROM steps LIMIT 1")
+            .is_ok();
+
+        if !has_title {
+            self.conn
+                .execute("ALTER TABLE steps ADD COLUMN title TEXT", [])?;
+        }
+
+        // Migration: Add documentation_generated_at column to recordings if it doesn't exist
+        let has_doc_generated_at: bool = self
+            .conn
+            .prepare("SELECT documentation_generated_at FROM recordings LIMIT 1")
+            .is_ok();
+
+        if !has_doc_generated_at {
+            self.conn.execute(
+                "ALTER TABLE recordings ADD COLUMN documentation_generated_at INTEGER",
+                [],
+            )?;
+        }
+
+        // Backfill: For existing recordings with documentation but no documentation_generated_at,
+        // set it to updated_at (assumes docs were in sync at last update)
+        self.conn.execute(
+            "UPDATE recordings SET documentation_generated_at = updated_at
+             WHERE documentation IS NOT NULL AND documentation_generated_at IS NULL",
+            [],
+        )?;
+
+        // Content-addressed screenshot store: one row per stored frame, with
+        // the number of step rows referencing it.
+        self.conn.execute(
+            "CREATE TABLE IF NOT EXISTS screenshot_objects (
+                hash TEXT PRIMARY KEY,
+                path TEXT NOT NULL UNIQUE,
+                size INTEGER NOT NULL,
+                ref_count INTEGER NOT NULL DEFAULT 0,
+                created_at INTEGER NOT NULL
+            )",
+            [],
+        )?;
+
+        // Migration: Add archived_at to recordings
+        let has_archived_at: bool = self
+            .conn
+            .prepare("SELECT archived_at FROM recordings LIMIT 1")
+            .is_ok();
+
+        if !has_archived_at {
+            self.conn
+                .execute("ALTER TABLE recordings ADD COLUMN archived_at INTEGER", [])?;
+        }
+
+        // Migration: Add locked flag to recordings
+        let has_locked: bool = self
+            .conn
+            .prepare("SELECT locked FROM recordings LIMIT 1")
+            .is_ok();
+
+        if !has_locked {
+            self.conn.execute(
+                "ALTER TABLE recordings ADD COLUMN locked INTEGER NOT NULL DEFAULT 0",
+                [],
+            )?;
+        }
+
+        // Migration: Add per-recording image settings
+        let has_image_settings: bool = self
+            .conn
+            .prepare("SELECT image_settings FROM recordings LIMIT 1")
+            .is_ok();
+
+        if !has_image_settings {
+            self.conn
+                .execute("ALTER TABLE recordings ADD COLUMN image_settings TEXT", [])?;
+        }
+
+        // Backend settings, one JSON value per `settings::Settings` field
+        self.conn.execute(
+            "CREATE TABLE IF NOT EXISTS settings (
+                key TEXT PRIMARY KEY,
+                value TEXT NOT NULL
+            )",
+            [],
+        )?;
+
+        self.conn.execute(
+            "CREATE TABLE IF NOT EXISTS hotkey_profiles (
+                name TEXT PRIMARY KEY,
+                bindings TEXT NOT NULL,
+                updated_at INTEGER NOT NULL
+            )",
+            [],
+        )?;
+
+        // Locked recordings are enforced here rather than in each method so
+        // every write path (including future ones) is covered. Before
+        // version 7 the steps trigger rejected every column; replace it.
+        if self.schema_version()? < 7 {
+            self.conn
+                .execute("DROP TRIGGER IF EXISTS locked_steps_update", [])?;
+        }
+        self.conn.execute_batch(LOCKED_RECORDING_TRIGGERS)?;
+
+        // Append-only audit trail of recording modifications. The triggers
+        // make rows immutable once written.
+        self.conn.execute_batch(
+            "CREATE TABLE IF NOT EXISTS audit_log (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                recording_id TEXT,
+                step_id TEXT,
+                action TEXT NOT NULL,
+                actor TEXT NOT NULL,
+                detail TEXT,
+                created_at INTEGER NOT NULL
+            );
+
+            CREATE INDEX IF NOT EXISTS idx_audit_log_recording
+                ON audit_log(recording_id, created_at);
+
+            CREATE TRIGGER IF NOT EXISTS audit_log_no_update BEFORE UPDATE ON audit_log
+            BEGIN SELECT RAISE(ABORT, 'Audit log is append-only'); END;
+
+            CREATE TRIGGER IF NOT EXISTS audit_log_no_delete BEFORE DELETE ON audit_log
+            BEGIN SELECT RAISE(ABORT, 'Audit log is append-only'); END;",
+        )?;
+
+        // Cached per-recording disk usage; recomputed when the recording
+        // changes after `computed_at`.
+        self.conn.execute(
+            "CREATE TABLE IF NOT EXISTS storage_usage (
+                recording_id TEXT PRIMARY KEY,
+                file_count INTEGER NOT NULL,
+                bytes INTEGER NOT NULL,
+                shared_bytes INTEGER NOT NULL,
+                computed_at INTEGER NOT NULL
+            )",
+            [],
+        )?;
+
+        // Links between recordings ("prerequisite", "see also").
+        self.conn.execute(
+            "CREATE TABLE IF NOT EXISTS recording_links (
+                from_id TEXT NOT NULL,
+                to_id TEXT NOT NULL,
+                kind TEXT NOT NULL CHECK (kind IN ('prerequisite', 'see_also')),
+                created_at INTEGER NOT NULL,
+                PRIMARY KEY (from_id, to_id, kind),
+                CHECK (from_id <> to_id)
+            )",
+            [],
+        )?;
+
+        // Journal of steps captured but not yet saved. Rows are written by the
+        // recorder as each step is emitted and cleared once the frontend saves
+        // or discards the session; anything left at startup is a crash.
+        self.conn.execute(
+            "CREATE TABLE IF NOT EXISTS draft_steps (
+                id TEXT PRIMARY KEY,
+                step_json TEXT NOT NULL,
+                screenshot_after TEXT,
+                clip_path TEXT,
+                created_at INTEGER NOT NULL
+            )",
+            [],
+        )?;
+
+        // Full-text index of recognised screenshot text, so searches find
+        // steps by labels nobody typed. Triggers keep it in step with
+        // `steps.ocr_text` on every write path; the first run backfills it.
+        let has_step_ocr_fts: bool = self
+            .conn
+            .prepare("SELECT step_id FROM step_ocr_fts LIMIT 1")
+            .is_ok();
+
+        self.conn.execute_batch(
+            "CREATE VIRTUAL TABLE IF NOT EXISTS step_ocr_fts USING fts5(
+                step_id UNINDEXED,
+                recording_id UNINDEXED,
+                ocr_text,
+                tokenize = 'unicode61 remove_diacritics 2'
+            );
+
+            CREATE TRIGGER IF NOT EXISTS step_ocr_fts_insert AFTER INSERT ON steps
+            WHEN NEW.ocr_text IS NOT NULL AND NEW.ocr_text <> ''
+            BEGIN
+                INSERT INTO step_ocr_fts (step_id, recording_id, ocr_text)
+                VALUES (NEW.id, NEW.recording_id, NEW.ocr_text);
+            END;
+
+            CREATE TRIGGER IF NOT EXISTS step_ocr_fts_update
+            AFTER UPDATE OF ocr_text, recording_id ON steps
+            BEGIN
+                DELETE FROM step_ocr_fts WHERE step_id = OLD.id;
+                INSERT INTO step_ocr_fts (step_id, recording_id, ocr_text)
+                SELECT NEW.id, NEW.recording_id, NEW.ocr_text
+                WHERE NEW.ocr_text IS NOT NULL AND NEW.ocr_text <> '';
+            END;
+
+            CREATE TRIGGER IF NOT EXISTS step_ocr_fts_delete AFTER DELETE ON steps
+            BEGIN
+                DELETE FROM step_ocr_fts WHERE step_id = OLD.id;
+            END;",
+        )?;
+
+        if !has_step_ocr_fts {
+            self.conn.execute(
+                "INSERT INTO step_ocr_fts (step_id, recording_id, ocr_text)
+                 SELECT id, recording_id, ocr_text FROM steps
+                 WHERE ocr_text IS NOT NULL AND ocr_text <> ''",
+                [],
+            )?;
+        }
+
+        // Migration: Add favorite flag to recordings
+        let has_favorite: bool = self
+            .conn
+            .prepare("SELECT favorite FROM recordings LIMIT 1")
+            .is_ok();
+
+        if !has_favorite {
+            self.conn.execute(
+                "ALTER TABLE recordings ADD COLUMN favorite INTEGER NOT NULL DEFAULT 0",
+                [],
+            )?;
+        }
 
         // Migration: Create notifications table
         self.conn.execute(
@@ -364,13 +1795,104 @@ impl Database {
             params![thirty_days_ago],
         )?;
 
+        // Opt-in local usage analytics; see `analytics`. Counts are kept per
+        // day, timings as raw samples so percentiles can be computed.
+        self.conn.execute_batch(
+            "CREATE TABLE IF NOT EXISTS usage_counts (
+                day TEXT NOT NULL,
+                feature TEXT NOT NULL,
+                count INTEGER NOT NULL,
+                PRIMARY KEY (day, feature)
+            );
+
+            CREATE TABLE IF NOT EXISTS capture_metrics (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                metric TEXT NOT NULL,
+                value_ms REAL NOT NULL,
+                recorded_at INTEGER NOT NULL
+            );
+
+            CREATE INDEX IF NOT EXISTS idx_capture_metrics_recorded_at
+                ON capture_metrics(recorded_at);",
+        )?;
+
+        // Shareable links; see `share`.
+        self.conn.execute(
+            "CREATE TABLE IF NOT EXISTS share_links (
+                token TEXT PRIMARY KEY,
+                recording_id TEXT NOT NULL,
+                url TEXT NOT NULL,
+                created_at INTEGER NOT NULL,
+                expires_at INTEGER NOT NULL,
+                hosted INTEGER NOT NULL DEFAULT 0,
+                password_hash TEXT
+            )",
+            [],
+        )?;
+
+        // Narration transcripts; see `transcription`.
+        self.conn.execute(
+            "CREATE TABLE IF NOT EXISTS narrations (
+                recording_id TEXT PRIMARY KEY,
+                audio_path TEXT NOT NULL,
+                started_at INTEGER NOT NULL,
+                language TEXT,
+                segments TEXT NOT NULL,
+                transcribed_at INTEGER NOT NULL
+            )",
+            [],
+        )?;
+
+        // Screenshots uploaded to an image host; see `image_host`.
+        self.conn.execute(
+            "CREATE TABLE IF NOT EXISTS hosted_images (
+                sha256 TEXT NOT NULL,
+                destination TEXT NOT NULL,
+                url TEXT NOT NULL,
+                uploaded_at INTEGER NOT NULL,
+                expires_at INTEGER,
+                PRIMARY KEY (sha256, destination)
+            )",
+            [],
+        )?;
+
+        // Cloud sync bookkeeping; see `sync`. Rows outlive their recording so
+        // a local delete can be passed on to the sync folder.
+        self.conn.execute(
+            "CREATE TABLE IF NOT EXISTS sync_state (
+                recording_id TEXT PRIMARY KEY,
+                remote_key TEXT NOT NULL UNIQUE,
+                remote_etag TEXT,
+                local_updated_at INTEGER NOT NULL,
+                synced_at INTEGER NOT NULL,
+                conflict TEXT
+            )",
+            [],
+        )?;
+
+        self.conn
+            .pragma_update(None, "user_version", SCHEMA_VERSION)?;
+
         Ok(())
     }
 
+    /// The schema version stamped into the database file
+    pub fn schema_version(&self) -> Result<i64> {
+        self.conn
+            .query_row("PRAGMA user_version", [], |row| row.get(0))
+    }
+
     pub fn data_dir(&self) -> &PathBuf {
         &self.data_dir
     }
 
+    /// Root of the content-addressed screenshot store.
+    pub fn objects_dir(&self) -> PathBuf {
+        let dir = self.data_dir.join("objects");
+        let _ = fs::create_dir_all(&dir);
+        dir
+    }
+
     pub fn screenshots_dir(&self) -> PathBuf {
         let dir = self.data_dir.join("screenshots");
         let _ = fs::create_dir_all(&dir);
@@ -443,76 +1965,26 @@ impl Database {
         let id = Uuid::new_v4().to_string();
         let now = chrono::Utc::now().timestamp_millis();
 
-        self.conn.execute(
+        let tx = self.conn.unchecked_transaction()?;
+        tx.execute(
             "INSERT INTO recordings (id, name, created_at, updated_at) VALUES (?1, ?2, ?3, ?4)",
             params![id, name, now, now],
         )?;
+        self.audit(
+            &tx,
+            Some(&id),
+            None,
+            "recording_created",
+            Some(serde_json::json!({ "name": name })),
+        )?;
+        tx.commit()?;
 
         Ok(id)
     }
 
     pub fn save_steps(&self, recording_id: &str, steps: Vec<StepInput>) -> Result<()> {
-        let screenshots_dir = self.screenshots_dir();
-
-        for (index, step) in steps.into_iter().enumerate() {
-            let step_id = Uuid::new_v4().to_string();
-
-            // Copy screenshot to persistent storage if exists
-            let persistent_screenshot = if let Some(temp_path) = &step.screenshot {
-                let temp_path = PathBuf::from(temp_path);
-                if temp_path.exists() {
-                    let filename = format!("{}_{}.jpg", recording_id, step_id);
-                    let dest_path = screenshots_dir.join(&filename);
-                    if fs::copy(&temp_path, &dest_path).is_ok() {
-                        // Delete temp file after successful copy
-                        let _ = fs::remove_file(&temp_path);
-                        Some(dest_path.to_string_lossy().to_string())
-                    } else {
-                        None
-                    }
-                } else {
-                    None
-                }
-            } else {
-                None
-            };
-
-            self.conn.execute(
-                "INSERT INTO steps (id, recording_id, type_, x, y, text, timestamp, screenshot_path, element_name, element_type, element_value, app_name, order_index, description, is_cropped, input_source, screenshot_after_path, identified_element_json, clip_path, title)
-                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17, ?18, ?19, ?20)",
-                params![
-                    step_id,
-                    recording_id,
-                    step.type_,
-                    step.x,
-                    step.y,
-                    step.text,
-                    step.timestamp,
-                    persistent_screenshot,
-                    step.element_name,
-                    step.element_type,
-                    step.element_value,
-                    step.app_name,
-                    index as i32,
-                    step.description,
-                    step.is_cropped.unwrap_or(false) as i32,
-                    step.input_source,
-                    step.screenshot_after,
-                    step.identified_element_json,
-                    step.clip_path,
-                    step.title
-                ],
-            )?;
-        }
-
-        // Update recording timestamp
-        let now = chrono::Utc::now().timestamp_millis();
-        self.conn.execute(
-            "UPDATE recordings SET updated_at = ?1 WHERE id = ?2",
-            params![now, recording_id],
-        )?;
-
-        Ok(())
+        let storage = ScreenshotStorage::Objects(self.objects_dir());
+        self.persist_steps(recording_id, steps, &storage, false)
     }
 
     pub fn save_steps_with_path(
@@ -522,6 +1994,32 @@ impl Database {
         steps: Vec<StepInput>,
         custom_screenshot_path: Option<&str>,
     ) -> Result<()> {
+        let storage = self.screenshot_storage(recording_name, custom_screenshot_path);
+        self.persist_steps(recording_id, steps, &storage, true)
+    }
+
+    /// Screenshots go to the object store unless the user picked a custom
+    /// folder, in which case they keep the per-recording folder layout there.
+    fn screenshot_storage(
+        &self,
+        recording_name: &str,
+        custom_screenshot_path: Option<&str>,
+    ) -> ScreenshotStorage {
+        match custom_screenshot_path {
+            Some(path) if !path.is_empty() => ScreenshotStorage::Folder(
+                self.recording_screenshots_dir(recording_name, Some(path)),
+            ),
+            _ => ScreenshotStorage::Objects(self.objects_dir()),
+        }
+    }
+
+    /// Resolve (and create) the recording-specific screenshot folder under
+    /// either the custom screenshot path or the default screenshots dir.
+    pub fn recording_screenshots_dir(
+        &self,
+        recording_name: &str,
+        custom_screenshot_path: Option<&str>,
+    ) -> PathBuf {
         // Determine base screenshots directory
         let base_dir = match custom_screenshot_path {
             Some(path) if !path.is_empty() => PathBuf::from(path),
@@ -532,88 +2030,220 @@ impl Database {
         let sanitized_name = Self::sanitize_dirname(recording_name);
         let screenshots_dir = base_dir.join(&sanitized_name);
         let _ = fs::create_dir_all(&screenshots_dir);
+        screenshots_dir
+    }
 
-        for (index, step) in steps.into_iter().enumerate() {
-            let step_id = Uuid::new_v4().to_string();
-
-            // Handle screenshot: either use existing permanent path or copy from temp
-            let persistent_screenshot = if step.screenshot_is_permanent.unwrap_or(false) {
-                // Screenshot is already in permanent storage, use it directly
-                step.screenshot.clone()
-            } else if let Some(temp_path) = &step.screenshot {
-                // Copy screenshot from temp to persistent storage
-                let temp_path = PathBuf::from(temp_path);
-                if temp_path.exists() {
-                    let filename = format!("{}_{}.jpg", recording_id, step_id);
-                    let dest_path = screenshots_dir.join(&filename);
-                    if fs::copy(&temp_path, &dest_path).is_ok() {
-                        // Delete temp file after successful copy
-                        let _ = fs::remove_file(&temp_path);
-                        Some(dest_path.to_string_lossy().to_string())
-                    } else {
-                        None
-                    }
-                } else {
-                    None
-                }
-            } else {
-                None
-            };
-
-            // Use provided order_index if available, otherwise use enumeration index
-            let final_order_index = step.order_index.unwrap_or(index as i32);
+    /// Insert a batch of steps atomically.
+    ///
+    /// Screenshots are copied into `storage` while the transaction is open. Temp sources are only removed after the commit succeeds; on any
+    /// failure the transaction rolls back and the copies made so far are
+    /// deleted, so a mid-save error leaves neither half a recording in the DB
+    /// nor orphaned files on disk.
+    ///
+    /// `honor_input_hints` enables `screenshot_is_permanent` and caller-provided
+    /// `order_index` values (used by `save_steps_with_path`).
+    fn persist_steps(
+        &self,
+        recording_id: &str,
+        steps: Vec<StepInput>,
+        storage: &ScreenshotStorage,
+        honor_input_hints: bool,
+    ) -> Result<()> {
+        let mut copied: Vec<CopiedScreenshot> = Vec::new();
+        let result = self.persist_steps_in_transaction(
+            recording_id,
+            steps,
+            storage,
+            honor_input_hints,
+            &mut copied,
+        );
+        settle_copied_screenshots(result, &copied)?;
+        self.enforce_original_quota()
+    }
 
-            self.conn.execute(
-                "INSERT INTO steps (id, recording_id, type_, x, y, text, timestamp, screenshot_path, element_name, element_type, element_value, app_name, order_index, description, is_cropped, input_source, screenshot_after_path, identified_element_json, clip_path, title)
-                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17, ?18, ?19, ?20)",
-                params![
-                    step_id,
+    fn persist_steps_in_transaction(
+        &self,
+        recording_id: &str,
+        steps: Vec<StepInput>,
+        storage: &ScreenshotStorage,
+        honor_input_hints: bool,
+        copied: &mut Vec<CopiedScreenshot>,
+    ) -> Result<()> {
+        // Dropping the transaction without committing rolls it back.
+        let tx = self.conn.unchecked_transaction()?;
+        let step_count = steps.len();
+
+        {
+            let mut insert = tx.prepare_cached(INSERT_STEP_SQL)?;
+
+            for (index, step) in steps.into_iter().enumerate() {
+                let step_id = Uuid::new_v4().to_string();
+                let persistent_screenshots = store_step_screenshot(
+                    &tx,
+                    &step,
                     recording_id,
-                    step.type_,
-                    step.x,
-                    step.y,
-                    step.text,
-                    step.timestamp,
-                    persistent_screenshot,
-                    step.element_name,
-                    step.element_type,
-                    step.element_value,
-                    step.app_name,
+                    &step_id,
+                    storage,
+                    honor_input_hints,
+                    copied,
+                )?;
+
+                // Use provided order_index if available, otherwise use enumeration index
+                let final_order_index = if honor_input_hints {
+                    step.order_index.unwrap_or(index as i32)
+                } else {
+                    index as i32
+                };
+
+                execute_step_insert(
+                    &mut insert,
+                    &step_id,
+                    recording_id,
+                    &step,
+                    persistent_screenshots,
                     final_order_index,
-                    step.description,
-                    step.is_cropped.unwrap_or(false) as i32,
-                    step.input_source,
-                    step.screenshot_after,
-                    step.identified_element_json,
-                    step.clip_path,
-                    step.title
-                ],
-            )?;
+                )?;
+            }
+        }
+
+        self.audit(
+            &tx,
+            Some(recording_id),
+            None,
+            "steps_added",
+            Some(serde_json::json!({ "count": step_count })),
+        )?;
+        Self::touch_recording(&tx, recording_id)?;
+        tx.commit()
+    }
+
+    /// Insert a single step at `position` (0-based) within a recording and
+    /// renumber every step's `order_index` so the sequence stays contiguous.
+    /// Positions past the end append. Used for adding a missing step (with an
+    /// imported screenshot) or a text-only note after recording.
+    pub fn insert_step(
+        &self,
+        recording_id: &str,
+        recording_name: &str,
+        position: i32,
+        step: StepInput,
+        custom_screenshot_path: Option<&str>,
+    ) -> Result<Step> {
+        let step_id = Uuid::new_v4().to_string();
+        let storage = self.screenshot_storage(recording_name, custom_screenshot_path);
+        let mut copied: Vec<CopiedScreenshot> = Vec::new();
+        let result = self.insert_step_in_transaction(
+            recording_id,
+            position,
+            &step_id,
+            &step,
+            &storage,
+            &mut copied,
+        );
+        settle_copied_screenshots(result, &copied)?;
+        self.enforce_original_quota()?;
+
+        self.get_step(&step_id)?
+            .ok_or(rusqlite::Error::QueryReturnedNoRows)
+    }
+
+    fn insert_step_in_transaction(
+        &self,
+        recording_id: &str,
+        position: i32,
+        step_id: &str,
+        step: &StepInput,
+        storage: &ScreenshotStorage,
+        copied: &mut Vec<CopiedScreenshot>,
+    ) -> Result<()> {
+        let tx = self.conn.unchecked_transaction()?;
+
+        let ordered_ids = Self::ordered_step_ids(&tx, recording_id)?;
+        let position = position.clamp(0, ordered_ids.len() as i32);
+
+        let persistent_screenshots =
+            store_step_screenshot(&tx, step, recording_id, step_id, storage, true, copied)?;
+        execute_step_insert(
+            &mut tx.prepare_cached(INSERT_STEP_SQL)?,
+            step_id,
+            recording_id,
+            step,
+            persistent_screenshots,
+            position,
+        )?;
+
+        {
+            let mut renumber =
+                tx.prepare_cached("UPDATE steps SET order_index = ?1 WHERE id = ?2")?;
+            for (index, id) in ordered_ids.iter().enumerate() {
+                let index = index as i32;
+                let new_index = if index < position { index } else { index + 1 };
+                renumber.execute(params![new_index, id])?;
+            }
         }
 
-        // Update recording timestamp
+        self.audit(
+            &tx,
+            Some(recording_id),
+            Some(step_id),
+            "step_inserted",
+            Some(serde_json::json!({ "position": position, "type": step.type_ })),
+        )?;
+        Self::touch_recording(&tx, recording_id)?;
+        tx.commit()
+    }
+
+    /// Step IDs of a recording in display order.
+    fn ordered_step_ids(conn: &Connection, recording_id: &str) -> Result<Vec<String>> {
+        let mut stmt = conn.prepare_cached(
+            "SELECT id FROM steps WHERE recording_id = ?1 ORDER BY order_index, timestamp",
+        )?;
+        let rows = stmt.query_map(params![recording_id], |row| row.get(0))?;
+        rows.collect()
+    }
+
+    fn is_recording_locked(conn: &Connection, recording_id: &str) -> Result<bool> {
+        conn.query_row(
+            "SELECT locked FROM recordings WHERE id = ?1",
+            params![recording_id],
+            |row| row.get::<_, i32>(0),
+        )
+        .optional()
+        .map(|locked| locked == Some(1))
+    }
+
+    /// Bump a recording's `updated_at` to now.
+    fn touch_recording(conn: &Connection, recording_id: &str) -> Result<()> {
         let now = chrono::Utc::now().timestamp_millis();
-        self.conn.execute(
+        conn.execute(
             "UPDATE recordings SET updated_at = ?1 WHERE id = ?2",
             params![now, recording_id],
         )?;
-
         Ok(())
     }
 
     pub fn save_documentation(&self, recording_id: &str, documentation: &str) -> Result<()> {
         let now = chrono::Utc::now().timestamp_millis();
-        self.conn.execute(
+        let tx = self.conn.unchecked_transaction()?;
+        tx.execute(
             "UPDATE recordings SET documentation = ?1, updated_at = ?2, documentation_generated_at = ?3 WHERE id = ?4",
             params![documentation, now, now, recording_id],
         )?;
-        Ok(())
+        self.audit(
+            &tx,
+            Some(recording_id),
+            None,
+            "documentation_saved",
+            Some(serde_json::json!({ "length": documentation.len() })),
+        )?;
+        tx.commit()
     }
 
     pub fn list_recordings(&self) -> Result<Vec<Recording>> {
         let mut stmt = self.conn.prepare(
             "SELECT r.id, r.name, r.created_at, r.updated_at, r.documentation, r.documentation_generated_at,
-                    (SELECT COUNT(*) FROM steps WHERE recording_id = r.id) as step_count
+                    (SELECT COUNT(*) FROM steps WHERE recording_id = r.id) as step_count,
+                    r.favorite, r.archived_at, r.locked
              FROM recordings r
              ORDER BY r.updated_at DESC"
         )?;
@@ -629,6 +2259,9 @@ impl Database {
                 step_count: row.get(6)?,
                 first_screenshot_path: None,
                 duration_ms: None,
+                favorite: row.get::<_, i32>(7)? != 0,
+                archived_at: row.get(8)?,
+                locked: row.get::<_, i32>(9)? != 0,
             })
         })?;
 
@@ -643,23 +2276,28 @@ impl Database {
     ) -> Result<PaginatedRecordings> {
         let offset = (page - 1) * per_page;
 
-        // Build the WHERE clause for search
-        let search_clause = if search.is_some() {
-            "WHERE r.name LIKE ?1"
-        } else {
-            ""
-        };
+        // Build the WHERE clause for search. Besides the name, a recording
+        // matches when text recognised in one of its screenshots does.
+        let mut search_clause = String::new();
+        let mut args: Vec<rusqlite::types::Value> = Vec::new();
+        if let Some(search_term) = search {
+            args.push(format!("%{}%", search_term).into());
+            search_clause.push_str("WHERE r.name LIKE ?1");
+            if let Some(ocr_query) = fts_query(search_term) {
+                args.push(ocr_query.into());
+                search_clause.push_str(
+                    " OR r.id IN (SELECT recording_id FROM step_ocr_fts WHERE step_ocr_fts MATCH ?2)",
+                );
+            }
+        }
 
         // Get total count
         let count_sql = format!("SELECT COUNT(*) FROM recordings r {}", search_clause);
-
-        let total_count: i64 = if let Some(ref search_term) = search {
-            let search_pattern = format!("%{}%", search_term);
+        let total_count: i64 =
             self.conn
-                .query_row(&count_sql, params![search_pattern], |row| row.get(0))?
-        } else {
-            self.conn.query_row(&count_sql, [], |row| row.get(0))?
-        };
+                .query_row(&count_sql, rusqlite::params_from_iter(args.iter()), |row| {
+                    row.get(0)
+                })?;
 
         // Calculate total pages
         let total_pages = ((total_count as f64) / (per_page as f64)).ceil() as i32;
@@ -673,15 +2311,18 @@ impl Database {
                        WHERE recording_id = r.id AND screenshot_path IS NOT NULL
                        ORDER BY order_index ASC LIMIT 1) as first_screenshot_path,
                     (SELECT MAX(timestamp) - MIN(timestamp) FROM steps
-                       WHERE recording_id = r.id) as duration_ms
+                       WHERE recording_id = r.id) as duration_ms,
+                    r.favorite, r.archived_at, r.locked
              FROM recordings r
              {}
              ORDER BY r.updated_at DESC
              LIMIT ?{} OFFSET ?{}",
             search_clause,
-            if search.is_some() { "2" } else { "1" },
-            if search.is_some() { "3" } else { "2" }
+            args.len() + 1,
+            args.len() + 2
         );
+        args.push(per_page.into());
+        args.push(offset.into());
 
         let map_row = |row: &rusqlite::Row<'_>| -> Result<Recording> {
             Ok(Recording {
@@ -694,19 +2335,16 @@ impl Database {
                 step_count: row.get(6)?,
                 first_screenshot_path: row.get(7)?,
                 duration_ms: row.get(8)?,
+                favorite: row.get::<_, i32>(9)? != 0,
+                archived_at: row.get(10)?,
+                locked: row.get::<_, i32>(11)? != 0,
             })
         };
 
-        let recordings: Vec<Recording> = if let Some(ref search_term) = search {
-            let search_pattern = format!("%{}%", search_term);
-            let mut stmt = self.conn.prepare(&query_sql)?;
-            let rows = stmt.query_map(params![search_pattern, per_page, offset], map_row)?;
-            rows.collect::<Result<Vec<_>>>()?
-        } else {
-            let mut stmt = self.conn.prepare(&query_sql)?;
-            let rows = stmt.query_map(params![per_page, offset], map_row)?;
-            rows.collect::<Result<Vec<_>>>()?
-        };
+        let mut stmt = self.conn.prepare(&query_sql)?;
+        let recordings = stmt
+            .query_map(rusqlite::params_from_iter(args.iter()), map_row)?
+            .collect::<Result<Vec<_>>>()?;
 
         Ok(PaginatedRecordings {
             recordings,
@@ -717,10 +2355,41 @@ impl Database {
         })
     }
 
+    /// Steps whose recognised screenshot text matches `search`, best match
+    /// first, with the matching passage.
+    pub fn search_step_text(&self, search: &str, limit: i64) -> Result<Vec<StepTextMatch>> {
+        let Some(query) = fts_query(search) else {
+            return Ok(Vec::new());
+        };
+        let mut stmt = self.conn.prepare(
+            "SELECT f.recording_id, r.name, f.step_id, s.order_index,
+                    snippet(step_ocr_fts, 2, '', '', '…', 12)
+             FROM step_ocr_fts f
+             JOIN steps s ON s.id = f.step_id
+             JOIN recordings r ON r.id = f.recording_id
+             WHERE step_ocr_fts MATCH ?1
+             ORDER BY rank
+             LIMIT ?2",
+        )?;
+        let matches = stmt
+            .query_map(params![query, limit], |row| {
+                Ok(StepTextMatch {
+                    recording_id: row.get(0)?,
+                    recording_name: row.get(1)?,
+                    step_id: row.get(2)?,
+                    order_index: row.get(3)?,
+                    snippet: row.get(4)?,
+                })
+            })?
+            .collect::<Result<_>>()?;
+        Ok(matches)
+    }
+
     pub fn get_recording(&self, id: &str) -> Result<Option<RecordingWithSteps>> {
         let mut stmt = self.conn.prepare(
             "SELECT r.id, r.name, r.created_at, r.updated_at, r.documentation, r.documentation_generated_at,
-                    (SELECT COUNT(*) FROM steps WHERE recording_id = r.id) as step_count
+                    (SELECT COUNT(*) FROM steps WHERE recording_id = r.id) as step_count,
+                    r.favorite, r.archived_at, r.locked
              FROM recordings r WHERE r.id = ?1"
         )?;
 
@@ -737,47 +2406,22 @@ impl Database {
                     // Derived below from the loaded steps to avoid extra SQL.
                     first_screenshot_path: None,
                     duration_ms: None,
+                    favorite: row.get::<_, i32>(7)? != 0,
+                    archived_at: row.get(8)?,
+                    locked: row.get::<_, i32>(9)? != 0,
                 })
             })
             .optional()?;
 
         match recording {
             Some(rec) => {
-                let mut stmt = self.conn.prepare(
-                    "SELECT id, recording_id, type_, x, y, text, timestamp, screenshot_path,
-                            element_name, element_type, element_value, app_name, order_index, description, is_cropped,
-                            ocr_text, ocr_status, input_source, screenshot_after_path,
-                            identified_element_json, clip_path, title
-                     FROM steps WHERE recording_id = ?1 ORDER BY order_index"
-                )?;
+                let mut stmt = self.conn.prepare(&format!(
+                    "SELECT {} FROM steps WHERE recording_id = ?1 ORDER BY order_index",
+                    STEP_COLUMNS
+                ))?;
 
                 let steps = stmt
-                    .query_map(params![id], |row| {
-                        Ok(Step {
-                            id: row.get(0)?,
-                            recording_id: row.get(1)?,
-                            type_: row.get(2)?,
-                            x: row.get(3)?,
-                            y: row.get(4)?,
-                            text: row.get(5)?,
-                            timestamp: row.get(6)?,
-                            screenshot_path: row.get(7)?,
-                            element_name: row.get(8)?,
-                            element_type: row.get(9)?,
-                            element_value: row.get(10)?,
-                            app_name: row.get(11)?,
-                            order_index: row.get(12)?,
-                            description: row.get(13)?,
-                            is_cropped: row.get::<_, Option<i32>>(14)?.map(|v| v != 0),
-                            ocr_text: row.get(15)?,
-                            ocr_status: row.get(16)?,
-                            input_source: row.get(17)?,
-                            screenshot_after_path: row.get(18)?,
-                            identified_element_json: row.get(19)?,
-                            clip_path: row.get(20)?,
-                            title: row.get(21)?,
-                        })
-                    })?
+                    .query_map(params![id], map_step_row)?
                     .collect::<Result<Vec<_>>>()?;
 
                 let mut rec = rec;
@@ -805,22 +2449,59 @@ impl Database {
         }
     }
 
+    pub fn get_step(&self, step_id: &str) -> Result<Option<Step>> {
+        self.conn
+            .query_row(
+                &format!("SELECT {} FROM steps WHERE id = ?1", STEP_COLUMNS),
+                params![step_id],
+                map_step_row,
+            )
+            .optional()
+    }
+
+    /// Screenshot of the most recently recorded step, if any
+    pub fn latest_step_screenshot(&self) -> Result<Option<String>> {
+        self.conn
+            .query_row(
+                "SELECT screenshot_path FROM steps WHERE screenshot_path IS NOT NULL
+                 ORDER BY timestamp DESC LIMIT 1",
+                [],
+                |row| row.get(0),
+            )
+            .optional()
+    }
+
     pub fn delete_recording(&self, id: &str) -> Result<DeleteRecordingCleanup> {
         // Collect screenshot paths from steps. Filesystem cleanup is intentionally not
         // performed here because callers typically hold a mutex lock while calling.
-        let mut stmt = self.conn.prepare(
-            "SELECT screenshot_path FROM steps WHERE recording_id = ?1 AND screenshot_path IS NOT NULL"
-        )?;
-
-        let screenshot_paths: Vec<String> = stmt
-            .query_map(params![id], |row| row.get(0))?
-            .filter_map(|r| r.ok())
-            .collect();
+        // Reference releases and row deletes share a transaction so a rejected
+        // delete (e.g. a locked recording) leaves object counts intact.
+        let tx = self.conn.unchecked_transaction()?;
+        let screenshot_paths: Vec<String> = {
+            let mut stmt = tx.prepare(
+                "SELECT screenshot_path FROM steps WHERE recording_id = ?1 AND screenshot_path IS NOT NULL
+                 UNION ALL
+                 SELECT element_screenshot_path FROM steps
+                 WHERE recording_id = ?1 AND element_screenshot_path IS NOT NULL
+                 UNION ALL
+                 SELECT original_path FROM steps
+                 WHERE recording_id = ?1 AND original_path IS NOT NULL",
+            )?;
+            let paths = stmt
+                .query_map(params![id], |row| row.get(0))?
+                .filter_map(|r| r.ok())
+                .collect();
+            paths
+        };
 
         let mut files: Vec<PathBuf> = Vec::new();
         let mut dirs: std::collections::HashSet<PathBuf> = std::collections::HashSet::new();
 
         for path in screenshot_paths {
+            // Objects still used by other recordings stay on disk.
+            if !release_screenshot(&tx, &path)? {
+                continue;
+            }
             let path_buf = PathBuf::from(&path);
             if let Some(parent) = path_buf.parent() {
                 dirs.insert(parent.to_path_buf());
@@ -828,11 +2509,33 @@ impl Database {
             files.push(path_buf);
         }
 
+        // The narration audio is the recording's own copy; see `transcription`.
+        let narration_audio: Option<String> = tx
+            .query_row(
+                "SELECT audio_path FROM narrations WHERE recording_id = ?1",
+                params![id],
+                |row| row.get(0),
+            )
+            .optional()?;
+        files.extend(narration_audio.map(PathBuf::from));
+
         // Delete from database.
-        self.conn
-            .execute("DELETE FROM steps WHERE recording_id = ?1", params![id])?;
-        self.conn
-            .execute("DELETE FROM recordings WHERE id = ?1", params![id])?;
+        self.audit(&tx, Some(id), None, "recording_deleted", None)?;
+        tx.execute("DELETE FROM steps WHERE recording_id = ?1", params![id])?;
+        tx.execute(
+            "DELETE FROM recording_links WHERE from_id = ?1 OR to_id = ?1",
+            params![id],
+        )?;
+        tx.execute(
+            "DELETE FROM storage_usage WHERE recording_id = ?1",
+            params![id],
+        )?;
+        tx.execute(
+            "DELETE FROM narrations WHERE recording_id = ?1",
+            params![id],
+        )?;
+        tx.execute("DELETE FROM recordings WHERE id = ?1", params![id])?;
+        tx.commit()?;
 
         // Protect the default screenshots directory from deletion, even if it is empty.
         let protected_dir = self.get_default_screenshot_path();
@@ -846,344 +2549,3514 @@ impl Database {
     }
 
     pub fn update_recording_name(&self, id: &str, name: &str) -> Result<()> {
-        self.conn.execute(
+        let tx = self.conn.unchecked_transaction()?;
+        tx.execute(
             "UPDATE recordings SET name = ?1 WHERE id = ?2",
             params![name, id],
         )?;
-        Ok(())
-    }
-
-    pub fn update_step_screenshot(
-        &self,
-        step_id: &str,
-        screenshot_path: &str,
-        is_cropped: bool,
-    ) -> Result<()> {
-        self.conn.execute(
-            "UPDATE steps SET screenshot_path = ?1, is_cropped = ?2 WHERE id = ?3",
-            params![screenshot_path, is_cropped as i32, step_id],
+        self.audit(
+            &tx,
+            Some(id),
+            None,
+            "recording_renamed",
+            Some(serde_json::json!({ "name": name })),
         )?;
-        Ok(())
+        tx.commit()
     }
 
-    pub fn reorder_steps(&self, recording_id: &str, step_ids: Vec<String>) -> Result<()> {
-        for (index, step_id) in step_ids.into_iter().enumerate() {
-            self.conn.execute(
-                "UPDATE steps SET order_index = ?1 WHERE id = ?2 AND recording_id = ?3",
-                params![index as i32, step_id, recording_id],
-            )?;
+    /// Rename recordings from `pattern` (see [`apply_name_pattern`]), numbering
+    /// them in the order of `ids` from `start`. All or nothing: a missing or
+    /// locked recording, or a name that expands to nothing, rolls back the
+    /// whole batch.
+    pub fn rename_recordings(
+        &self,
+        ids: &[String],
+        pattern: &str,
+        start: i64,
+        width: usize,
+    ) -> Result<Vec<RenamedRecording>> {
+        let tx = self.conn.unchecked_transaction()?;
+        let mut renamed = Vec::with_capacity(ids.len());
+        {
+            let mut select =
+                tx.prepare_cached("SELECT name, created_at FROM recordings WHERE id = ?1")?;
+            let mut update = tx.prepare_cached("UPDATE recordings SET name = ?1 WHERE id = ?2")?;
+            for (index, id) in ids.iter().enumerate() {
+                let (old_name, created_at): (String, i64) =
+                    select.query_row(params![id], |row| Ok((row.get(0)?, row.get(1)?)))?;
+                let name =
+                    apply_name_pattern(pattern, start + index as i64, width, &old_name, created_at);
+                if name.is_empty() {
+                    return Err(rusqlite::Error::ToSqlConversionFailure(
+                        format!("Pattern produces an empty name for recording {}", id).into(),
+                    ));
+                }
+                update.execute(params![name, id])?;
+                self.audit(
+                    &tx,
+                    Some(id),
+                    None,
+                    "recording_renamed",
+                    Some(serde_json::json!({ "name": name, "previous_name": old_name })),
+                )?;
+                renamed.push(RenamedRecording {
+                    id: id.clone(),
+                    name,
+                });
+            }
         }
+        tx.commit()?;
+        Ok(renamed)
+    }
 
-        let now = chrono::Utc::now().timestamp_millis();
-        self.conn.execute(
-            "UPDATE recordings SET updated_at = ?1 WHERE id = ?2",
-            params![now, recording_id],
-        )?;
-
-        Ok(())
+    /// Flip a recording's favorite flag and return the new value.
+    pub fn toggle_recording_favorite(&self, id: &str) -> Result<bool> {
+        self.conn.query_row(
+            "UPDATE recordings SET favorite = 1 - favorite WHERE id = ?1 RETURNING favorite",
+            params![id],
+            |row| Ok(row.get::<_, i32>(0)? != 0),
+        )
     }
 
-    pub fn update_step_description(&self, step_id: &str, description: &str) -> Result<()> {
-        self.conn.execute(
-            "UPDATE steps SET description = ?1 WHERE id = ?2",
-            params![description, step_id],
-        )?;
-        Ok(())
+    /// Whether `id` is locked; false when it doesn't exist
+    pub fn recording_locked(&self, id: &str) -> Result<bool> {
+        Self::is_recording_locked(&self.conn, id)
     }
 
-    pub fn update_step_title(&self, step_id: &str, title: &str) -> Result<()> {
-        self.conn.execute(
-            "UPDATE steps SET title = ?1 WHERE id = ?2",
-            params![title, step_id],
+    /// Lock or unlock a recording. While locked, the DB triggers reject any
+    /// change to its steps, name or documentation.
+    pub fn set_recording_locked(&self, id: &str, locked: bool) -> Result<()> {
+        let tx = self.conn.unchecked_transaction()?;
+        tx.execute(
+            "UPDATE recordings SET locked = ?1 WHERE id = ?2",
+            params![locked as i32, id],
         )?;
-        Ok(())
+        let action = if locked {
+            "recording_locked"
+        } else {
+            "recording_unlocked"
+        };
+        self.audit(&tx, Some(id), None, action, None)?;
+        tx.commit()
     }
 
-    pub fn delete_step(&self, step_id: &str) -> Result<()> {
-        // Get screenshot path before deleting
-        let screenshot_path: Option<String> = self
+    /// The image settings `recording_id` overrides the global defaults with;
+    /// all unset when it has none (or doesn't exist).
+    pub fn get_recording_image_settings(
+        &self,
+        recording_id: &str,
+    ) -> Result<RecordingImageSettings> {
+        let json: Option<String> = self
             .conn
             .query_row(
-                "SELECT screenshot_path FROM steps WHERE id = ?1",
-                params![step_id],
+                "SELECT image_settings FROM recordings WHERE id = ?1",
+                params![recording_id],
                 |row| row.get(0),
             )
-            .optional()?;
-
-        // Delete screenshot file if exists
-        if let Some(path) = screenshot_path {
-            let _ = fs::remove_file(path);
-        }
-
-        // Delete from database
-        self.conn
-            .execute("DELETE FROM steps WHERE id = ?1", params![step_id])?;
-
-        Ok(())
+            .optional()?
+            .flatten();
+        Ok(json
+            .and_then(|json| serde_json::from_str(&json).ok())
+            .unwrap_or_default())
     }
 
-    pub fn update_step_after_screenshot(
+    /// Replace `recording_id`'s image settings. Settings with nothing set are
+    /// cleared, so the recording follows the global defaults again.
+    pub fn set_recording_image_settings(
         &self,
-        step_id: &str,
-        screenshot_after_path: Option<&str>,
+        recording_id: &str,
+        settings: &RecordingImageSettings,
     ) -> Result<()> {
-        self.conn.execute(
-            "UPDATE steps SET screenshot_after_path = ?1 WHERE id = ?2",
-            params![screenshot_after_path, step_id],
+        let json = (*settings != RecordingImageSettings::default())
+            .then(|| serde_json::to_string(settings).ok())
+            .flatten();
+        let tx = self.conn.unchecked_transaction()?;
+        tx.execute(
+            "UPDATE recordings SET image_settings = ?1 WHERE id = ?2",
+            params![json, recording_id],
         )?;
-        Ok(())
+        self.audit(
+            &tx,
+            Some(recording_id),
+            None,
+            "image_settings_changed",
+            serde_json::to_value(settings).ok(),
+        )?;
+        tx.commit()
     }
 
-    pub fn update_step_identified_element(
-        &self,
-        step_id: &str,
-        identified_element_json: Option<&str>,
-    ) -> Result<()> {
-        self.conn.execute(
-            "UPDATE steps SET identified_element_json = ?1 WHERE id = ?2",
-            params![identified_element_json, step_id],
-        )?;
+    /// Write a consistent copy of the database to `path`, which must not exist
+    pub fn copy_to(&self, path: &Path) -> Result<()> {
+        self.conn
+            .execute("VACUUM INTO ?1", params![path.to_string_lossy()])?;
         Ok(())
     }
 
-    pub fn update_step_clip_path(
-        &self,
-        step_id: &str,
-        clip_path: Option<&str>,
-    ) -> Result<()> {
-        self.conn.execute(
-            "UPDATE steps SET clip_path = ?1 WHERE id = ?2",
-            params![clip_path, step_id],
+    /// Point every stored path under `old_dir` at the same file under
+    /// `new_dir`, in one transaction, returning how many rows changed.
+    /// Locked recordings are rewritten too: their files moved, their
+    /// content didn't.
+    pub fn rewrite_data_dir(&self, old_dir: &Path, new_dir: &Path) -> Result<usize> {
+        let old_prefix = format!("{}{}", old_dir.display(), std::path::MAIN_SEPARATOR);
+        let new_prefix = format!("{}{}", new_dir.display(), std::path::MAIN_SEPARATOR);
+        let tx = self.conn.unchecked_transaction()?;
+        tx.execute_batch(
+            "DROP TRIGGER IF EXISTS locked_steps_update;
+             DROP TRIGGER IF EXISTS locked_recordings_update;",
         )?;
-        Ok(())
+
+        let mut changed = 0;
+        for column in STEP_PATH_COLUMNS {
+            changed += tx.execute(
+                &format!(
+                    "UPDATE steps SET {column} = ?2 || SUBSTR({column}, LENGTH(?1) + 1)
+                     WHERE SUBSTR({column}, 1, LENGTH(?1)) = ?1"
+                ),
+                params![old_prefix, new_prefix],
+            )?;
+        }
+        changed += tx.execute(
+            "UPDATE screenshot_objects SET path = ?2 || SUBSTR(path, LENGTH(?1) + 1)
+             WHERE SUBSTR(path, 1, LENGTH(?1)) = ?1",
+            params![old_prefix, new_prefix],
+        )?;
+        // Generated Markdown embeds image paths anywhere in its text
+        changed += tx.execute(
+            "UPDATE recordings SET documentation = REPLACE(documentation, ?1, ?2)
+             WHERE INSTR(documentation, ?1) > 0",
+            params![old_prefix, new_prefix],
+        )?;
+
+        tx.execute_batch(LOCKED_RECORDING_TRIGGERS)?;
+        self.audit(
+            &tx,
+            None,
+            None,
+            "data_dir_moved",
+            Some(serde_json::json!({
+                "from": old_dir.to_string_lossy(),
+                "to": new_dir.to_string_lossy(),
+            })),
+        )?;
+        tx.commit()?;
+        Ok(changed)
     }
 
-    pub fn update_step_ocr(
-        &self,
-        step_id: &str,
-        ocr_text: Option<&str>,
-        ocr_status: &str,
-    ) -> Result<()> {
-        self.conn.execute(
-            "UPDATE steps SET ocr_text = ?1, ocr_status = ?2 WHERE id = ?3",
-            params![ocr_text, ocr_status, step_id],
+    /// Every saved setting as `(key, JSON value)`
+    pub fn load_settings(&self) -> Result<Vec<(String, String)>> {
+        let mut stmt = self.conn.prepare("SELECT key, value FROM settings")?;
+        let rows = stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?;
+        rows.collect()
+    }
+
+    /// Save `(key, JSON value)` settings, replacing any saved under the same
+    /// keys
+    pub fn save_settings(&self, values: &[(String, String)]) -> Result<()> {
+        let tx = self.conn.unchecked_transaction()?;
+        for (key, value) in values {
+            tx.execute(
+                "INSERT INTO settings (key, value) VALUES (?1, ?2)
+                 ON CONFLICT(key) DO UPDATE SET value = excluded.value",
+                params![key, value],
+            )?;
+        }
+        tx.commit()
+    }
+
+    /// Every hotkey profile, by name
+    pub fn list_hotkey_profiles(&self) -> Result<Vec<HotkeyProfile>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT name, bindings, updated_at FROM hotkey_profiles ORDER BY name COLLATE NOCASE",
         )?;
-        Ok(())
+        let rows = stmt.query_map([], |row| {
+            let bindings: String = row.get(1)?;
+            Ok(HotkeyProfile {
+                name: row.get(0)?,
+                bindings: serde_json::from_str(&bindings).unwrap_or_default(),
+                updated_at: row.get(2)?,
+            })
+        })?;
+        rows.collect()
     }
 
-    // ── Notification CRUD ──────────────────────────────────────────────
+    pub fn get_hotkey_profile(&self, name: &str) -> Result<Option<HotkeyProfile>> {
+        self.conn
+            .query_row(
+                "SELECT bindings, updated_at FROM hotkey_profiles WHERE name = ?1",
+                params![name],
+                |row| {
+                    let bindings: String = row.get(0)?;
+                    Ok(HotkeyProfile {
+                        name: name.to_string(),
+                        bindings: serde_json::from_str(&bindings).unwrap_or_default(),
+                        updated_at: row.get(1)?,
+                    })
+                },
+            )
+            .optional()
+    }
 
-    pub fn create_notification(
+    /// Create the profile `name`, or replace its bindings if it exists
+    pub fn save_hotkey_profile(
         &self,
-        title: Option<&str>,
-        message: &str,
-        variant: &str,
-        log_category: Option<&str>,
-    ) -> Result<Notification> {
-        let id = Uuid::new_v4().to_string();
-        let now = std::time::SystemTime::now()
+        name: &str,
+        bindings: &HashMap<HotkeyAction, HotkeyBinding>,
+    ) -> Result<HotkeyProfile> {
+        let updated_at = std::time::SystemTime::now()
             .duration_since(std::time::UNIX_EPOCH)
             .unwrap_or_default()
             .as_millis() as i64;
-
+        let json = serde_json::to_string(bindings)
+            .map_err(|e| rusqlite::Error::ToSqlConversionFailure(Box::new(e)))?;
         self.conn.execute(
-            "INSERT INTO notifications (id, title, message, variant, is_read, created_at, log_category)
-             VALUES (?1, ?2, ?3, ?4, 0, ?5, ?6)",
-            params![id, title, message, variant, now, log_category],
+            "INSERT INTO hotkey_profiles (name, bindings, updated_at) VALUES (?1, ?2, ?3)
+             ON CONFLICT(name) DO UPDATE SET bindings = excluded.bindings, updated_at = excluded.updated_at",
+            params![name, json, updated_at],
         )?;
-
-        Ok(Notification {
-            id,
-            title: title.map(|s| s.to_string()),
-            message: message.to_string(),
-            variant: variant.to_string(),
-            is_read: false,
-            created_at: now,
-            log_category: log_category.map(|s| s.to_string()),
+        Ok(HotkeyProfile {
+            name: name.to_string(),
+            bindings: bindings.clone(),
+            updated_at,
         })
     }
 
-    pub fn list_notifications(&self, limit: i32, offset: i32) -> Result<Vec<Notification>> {
-        let mut stmt = self.conn.prepare(
-            "SELECT id, title, message, variant, is_read, created_at, log_category
-             FROM notifications
-             ORDER BY created_at DESC
-             LIMIT ?1 OFFSET ?2",
+    pub fn delete_hotkey_profile(&self, name: &str) -> Result<()> {
+        self.conn
+            .execute("DELETE FROM hotkey_profiles WHERE name = ?1", params![name])?;
+        Ok(())
+    }
+
+    /// Library totals plus a short recent list with favorites pinned on top.
+    pub fn get_statistics(&self, recent_limit: i32) -> Result<RecordingStatistics> {
+        let (total_recordings, favorite_count): (i64, i64) = self.conn.query_row(
+            "SELECT COUNT(*), COALESCE(SUM(favorite), 0) FROM recordings",
+            [],
+            |row| Ok((row.get(0)?, row.get(1)?)),
         )?;
+        let total_steps: i64 = self
+            .conn
+            .query_row("SELECT COUNT(*) FROM steps", [], |row| row.get(0))?;
 
-        let rows = stmt.query_map(params![limit, offset], |row| {
-            Ok(Notification {
-                id: row.get(0)?,
-                title: row.get(1)?,
-                message: row.get(2)?,
-                variant: row.get(3)?,
-                is_read: row.get::<_, i32>(4)? != 0,
+        let mut stmt = self.conn.prepare(
+            "SELECT r.id, r.name, r.created_at, r.updated_at, r.documentation, r.documentation_generated_at,
+                    (SELECT COUNT(*) FROM steps WHERE recording_id = r.id) as step_count,
+                    (SELECT screenshot_path FROM steps
+                       WHERE recording_id = r.id AND screenshot_path IS NOT NULL
+                       ORDER BY order_index ASC LIMIT 1) as first_screenshot_path,
+                    r.favorite, r.archived_at, r.locked
+             FROM recordings r
+             ORDER BY r.favorite DESC, r.updated_at DESC
+             LIMIT ?1",
+        )?;
+        let recent = stmt
+            .query_map(params![recent_limit], |row| {
+                Ok(Recording {
+                    id: row.get(0)?,
+                    name: row.get(1)?,
+                    created_at: row.get(2)?,
+                    updated_at: row.get(3)?,
+                    documentation: row.get(4)?,
+                    documentation_generated_at: row.get(5)?,
+                    step_count: row.get(6)?,
+                    first_screenshot_path: row.get(7)?,
+                    duration_ms: None,
+                    favorite: row.get::<_, i32>(8)? != 0,
+                    archived_at: row.get(9)?,
+                    locked: row.get::<_, i32>(10)? != 0,
+                })
+            })?
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(RecordingStatistics {
+            total_recordings,
+            total_steps,
+            favorite_count,
+            recent,
+        })
+    }
+
+    pub fn update_step_screenshot(
+        &self,
+        step_id: &str,
+        screenshot_path: &str,
+        is_cropped: bool,
+    ) -> Result<()> {
+        let tx = self.conn.unchecked_transaction()?;
+        let previous: Option<String> = tx
+            .query_row(
+                "SELECT screenshot_path FROM steps WHERE id = ?1",
+                params![step_id],
+                |row| row.get::<_, Option<String>>(0),
+            )
+            .optional()?
+            .flatten();
+
+        tx.execute(
+            "UPDATE steps SET screenshot_path = ?1, is_cropped = ?2 WHERE id = ?3",
+            params![screenshot_path, is_cropped as i32, step_id],
+        )?;
+
+        self.audit(
+            &tx,
+            None,
+            Some(step_id),
+            "step_screenshot_replaced",
+            Some(serde_json::json!({ "is_cropped": is_cropped })),
+        )?;
+
+        // Swapping onto a new object moves the reference; plain files are
+        // edited in place and are never removed here.
+        let mut orphaned: Vec<PathBuf> = Vec::new();
+        if let Some(previous) = previous.filter(|previous| previous != screenshot_path) {
+            retain_screenshot(&tx, screenshot_path)?;
+            if is_screenshot_object(&tx, &previous)? && release_screenshot(&tx, &previous)? {
+                orphaned.push(PathBuf::from(previous));
+            }
+        }
+        // The new screenshot was made elsewhere and the original no longer
+        // matches it
+        orphaned.extend(Self::swap_step_original(&tx, step_id, None)?);
+        tx.commit()?;
+
+        for path in orphaned {
+            let _ = fs::remove_file(path);
+        }
+        Ok(())
+    }
+
+    /// Whether `path` is a managed object. Objects may be shared between steps,
+    /// so they must never be edited in place.
+    pub fn is_screenshot_object(&self, path: &str) -> Result<bool> {
+        is_screenshot_object(&self.conn, path)
+    }
+
+    pub fn set_original_quota(&mut self, quota: u64) {
+        self.original_quota = quota;
+    }
+
+    /// Drop the lossless originals of the oldest steps until the rest fit in
+    /// the quota. Their screenshots are kept; edits and exports of those
+    /// steps fall back to the JPEG. Files shared by several steps count once.
+    pub fn enforce_original_quota(&self) -> Result<()> {
+        let tx = self.conn.unchecked_transaction()?;
+        let originals: Vec<(String, String)> = {
+            let mut stmt = tx.prepare(
+                "SELECT id, original_path FROM steps WHERE original_path IS NOT NULL
+                 ORDER BY timestamp DESC",
+            )?;
+            let rows = stmt
+                .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?
+                .collect::<Result<_>>()?;
+            rows
+        };
+
+        let mut counted = std::collections::HashSet::new();
+        let mut used: u64 = 0;
+        let mut orphaned = Vec::new();
+        for (step_id, path) in originals {
+            if counted.insert(path.clone()) {
+                used += fs::metadata(&path).map(|meta| meta.len()).unwrap_or(0);
+            }
+            if used > self.original_quota {
+                orphaned.extend(Self::swap_step_original(&tx, &step_id, None)?);
+            }
+        }
+        if !orphaned.is_empty() {
+            self.audit(
+                &tx,
+                None,
+                None,
+                "lossless_originals_pruned",
+                Some(serde_json::json!({ "files": orphaned.len() })),
+            )?;
+        }
+        tx.commit()?;
+
+        for path in orphaned {
+            let _ = fs::remove_file(path);
+        }
+        Ok(())
+    }
+
+    /// Store image bytes (a `extension` file) as a new (unreferenced) object
+    /// and return its path. The reference is taken when a step is pointed at
+    /// it.
+    pub fn store_screenshot_object(
+        &self,
+        bytes: &[u8],
+        extension: &str,
+    ) -> std::io::Result<PathBuf> {
+        write_screenshot_object(&self.conn, &self.objects_dir(), bytes, extension)
+            .map(|(path, _)| path)
+    }
+
+    /// Remove objects that no step references (e.g. left over from an
+    /// interrupted edit).
+    pub fn prune_screenshot_objects(&self) -> Result<usize> {
+        let mut stmt = self
+            .conn
+            .prepare("SELECT path FROM screenshot_objects WHERE ref_count <= 0")?;
+        let paths: Vec<String> = stmt
+            .query_map([], |row| row.get(0))?
+            .collect::<Result<_>>()?;
+
+        for path in &paths {
+            let _ = fs::remove_file(path);
+        }
+        self.conn
+            .execute("DELETE FROM screenshot_objects WHERE ref_count <= 0", [])
+    }
+
+    pub fn reorder_steps(&self, recording_id: &str, step_ids: Vec<String>) -> Result<()> {
+        let tx = self.conn.unchecked_transaction()?;
+        for (index, step_id) in step_ids.iter().enumerate() {
+            tx.execute(
+                "UPDATE steps SET order_index = ?1 WHERE id = ?2 AND recording_id = ?3",
+                params![index as i32, step_id, recording_id],
+            )?;
+        }
+
+        self.audit(
+            &tx,
+            Some(recording_id),
+            None,
+            "steps_reordered",
+            Some(serde_json::json!({ "step_ids": step_ids })),
+        )?;
+        Self::touch_recording(&tx, recording_id)?;
+        tx.commit()
+    }
+
+    pub fn update_step_description(&self, step_id: &str, description: &str) -> Result<()> {
+        let tx = self.conn.unchecked_transaction()?;
+        tx.execute(
+            "UPDATE steps SET description = ?1, description_generated = 0 WHERE id = ?2",
+            params![description, step_id],
+        )?;
+        self.audit(
+            &tx,
+            None,
+            Some(step_id),
+            "step_description_edited",
+            Some(serde_json::json!({ "description": description })),
+        )?;
+        tx.commit()
+    }
+
+    pub fn update_step_title(&self, step_id: &str, title: &str) -> Result<()> {
+        let tx = self.conn.unchecked_transaction()?;
+        tx.execute(
+            "UPDATE steps SET title = ?1 WHERE id = ?2",
+            params![title, step_id],
+        )?;
+        self.audit(
+            &tx,
+            None,
+            Some(step_id),
+            "step_title_edited",
+            Some(serde_json::json!({ "title": title })),
+        )?;
+        tx.commit()
+    }
+
+    /// Replace a step's annotations. An empty list removes them.
+    pub fn update_step_annotations(&self, step_id: &str, annotations: &[Annotation]) -> Result<()> {
+        let json = if annotations.is_empty() {
+            None
+        } else {
+            serde_json::to_string(annotations).ok()
+        };
+        let tx = self.conn.unchecked_transaction()?;
+        tx.execute(
+            "UPDATE steps SET annotations = ?1 WHERE id = ?2",
+            params![json, step_id],
+        )?;
+        self.audit(
+            &tx,
+            None,
+            Some(step_id),
+            "step_annotations_edited",
+            Some(serde_json::json!({ "count": annotations.len() })),
+        )?;
+        tx.commit()
+    }
+
+    /// Replace the redactions applied to a step's screenshot at export. An
+    /// empty list removes them.
+    pub fn update_step_redactions(&self, step_id: &str, redactions: &[Redaction]) -> Result<()> {
+        let json = if redactions.is_empty() {
+            None
+        } else {
+            serde_json::to_string(redactions).ok()
+        };
+        let tx = self.conn.unchecked_transaction()?;
+        tx.execute(
+            "UPDATE steps SET redactions = ?1, redactions_reviewed = 1 WHERE id = ?2",
+            params![json, step_id],
+        )?;
+        self.audit(
+            &tx,
+            None,
+            Some(step_id),
+            "step_redactions_edited",
+            Some(serde_json::json!({ "count": redactions.len() })),
+        )?;
+        tx.commit()
+    }
+
+    /// Move a step's click highlight to `marker`, or remove it with None
+    pub fn update_step_click_marker(&self, step_id: &str, marker: Option<Point>) -> Result<()> {
+        let json = marker.and_then(|marker| serde_json::to_string(&marker).ok());
+        let tx = self.conn.unchecked_transaction()?;
+        tx.execute(
+            "UPDATE steps SET click_marker = ?1 WHERE id = ?2",
+            params![json, step_id],
+        )?;
+        self.audit(
+            &tx,
+            None,
+            Some(step_id),
+            "step_click_marker_edited",
+            Some(serde_json::json!({ "click_marker": marker })),
+        )?;
+        tx.commit()
+    }
+
+    /// Stop suggesting redactions for `step_ids` without adding any
+    pub fn dismiss_redaction_suggestions(&self, step_ids: &[String]) -> Result<()> {
+        let tx = self.conn.unchecked_transaction()?;
+        {
+            let mut update =
+                tx.prepare_cached("UPDATE steps SET redactions_reviewed = 1 WHERE id = ?1")?;
+            for step_id in step_ids {
+                update.execute(params![step_id])?;
+            }
+        }
+        self.audit(
+            &tx,
+            None,
+            None,
+            "redaction_suggestions_dismissed",
+            Some(serde_json::json!({ "step_ids": step_ids })),
+        )?;
+        tx.commit()
+    }
+
+    /// Point a step at redacted copies of its images (None leaves that image
+    /// alone) and drop its pending redactions, in one transaction. A redacted
+    /// screenshot takes `original_path` as its lossless original, or none, so
+    /// the unredacted pixels aren't kept. Object references move to the
+    /// copies. Returns the replaced files that nothing references any more,
+    /// for the caller to delete.
+    pub fn replace_step_images_redacted(
+        &self,
+        step_id: &str,
+        screenshot_path: Option<&str>,
+        screenshot_after_path: Option<&str>,
+        element_screenshot_path: Option<&str>,
+        original_path: Option<&str>,
+    ) -> Result<Vec<PathBuf>> {
+        let tx = self.conn.unchecked_transaction()?;
+        let mut orphaned = Vec::new();
+        if screenshot_path.is_some() {
+            orphaned.extend(Self::swap_step_original(&tx, step_id, original_path)?);
+        }
+        for (column, path) in [
+            ("screenshot_path", screenshot_path),
+            ("screenshot_after_path", screenshot_after_path),
+            ("element_screenshot_path", element_screenshot_path),
+        ] {
+            let Some(path) = path else {
+                continue;
+            };
+            let previous: Option<String> = tx
+                .query_row(
+                    &format!("SELECT {} FROM steps WHERE id = ?1", column),
+                    params![step_id],
+                    |row| row.get::<_, Option<String>>(0),
+                )
+                .optional()?
+                .flatten();
+            tx.execute(
+                &format!("UPDATE steps SET {} = ?1 WHERE id = ?2", column),
+                params![path, step_id],
+            )?;
+            retain_screenshot(&tx, path)?;
+            if let Some(previous) = previous.filter(|previous| previous != path) {
+                if release_screenshot(&tx, &previous)? {
+                    orphaned.push(PathBuf::from(previous));
+                }
+            }
+        }
+        tx.execute(
+            "UPDATE steps SET redactions = NULL, redactions_reviewed = 1 WHERE id = ?1",
+            params![step_id],
+        )?;
+        self.audit(&tx, None, Some(step_id), "step_redactions_burned_in", None)?;
+        tx.commit()?;
+        Ok(orphaned)
+    }
+
+    pub fn delete_step(&self, step_id: &str) -> Result<()> {
+        let tx = self.conn.unchecked_transaction()?;
+
+        // Get screenshot paths before deleting
+        let paths: Option<(Option<String>, Option<String>, Option<String>)> = tx
+            .query_row(
+                "SELECT screenshot_path, element_screenshot_path, original_path
+                 FROM steps WHERE id = ?1",
+                params![step_id],
+                |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+            )
+            .optional()?;
+        let mut orphaned = Vec::new();
+        if let Some((screenshot, element, original)) = paths {
+            for path in [screenshot, element, original].into_iter().flatten() {
+                if release_screenshot(&tx, &path)? {
+                    orphaned.push(path);
+                }
+            }
+        }
+
+        // Delete from database
+        self.audit(&tx, None, Some(step_id), "step_deleted", None)?;
+        tx.execute("DELETE FROM steps WHERE id = ?1", params![step_id])?;
+        tx.commit()?;
+
+        // Delete screenshot files no other step shares
+        for path in orphaned {
+            let _ = fs::remove_file(path);
+        }
+
+        Ok(())
+    }
+
+    /// Delete several steps of a recording in one transaction and renumber the
+    /// remaining steps. Screenshot files are removed only after the commit so a
+    /// failed batch leaves both rows and images in place. Returns the number of
+    /// steps deleted.
+    pub fn delete_steps(&self, recording_id: &str, step_ids: &[String]) -> Result<usize> {
+        let tx = self.conn.unchecked_transaction()?;
+        let mut screenshots: Vec<String> = Vec::new();
+        let mut deleted = 0;
+
+        {
+            let mut select = tx.prepare_cached(
+                "SELECT screenshot_path, screenshot_after_path, element_screenshot_path,
+                     original_path
+                 FROM steps WHERE id = ?1 AND recording_id = ?2",
+            )?;
+            let mut delete =
+                tx.prepare_cached("DELETE FROM steps WHERE id = ?1 AND recording_id = ?2")?;
+            for step_id in step_ids {
+                type Paths = (
+                    Option<String>,
+                    Option<String>,
+                    Option<String>,
+                    Option<String>,
+                );
+                let paths: Option<Paths> = select
+                    .query_row(params![step_id, recording_id], |row| {
+                        Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?))
+                    })
+                    .optional()?;
+                if let Some((before, after, element, original)) = paths {
+                    for path in [before, element, original].into_iter().flatten() {
+                        if release_screenshot(&tx, &path)? {
+                            screenshots.push(path);
+                        }
+                    }
+                    screenshots.extend(after);
+                }
+                deleted += delete.execute(params![step_id, recording_id])?;
+            }
+
+            let mut renumber =
+                tx.prepare_cached("UPDATE steps SET order_index = ?1 WHERE id = ?2")?;
+            for (index, id) in Self::ordered_step_ids(&tx, recording_id)?
+                .iter()
+                .enumerate()
+            {
+                renumber.execute(params![index as i32, id])?;
+            }
+        }
+
+        self.audit(
+            &tx,
+            Some(recording_id),
+            None,
+            "steps_deleted",
+            Some(serde_json::json!({ "step_ids": step_ids, "deleted": deleted })),
+        )?;
+        Self::touch_recording(&tx, recording_id)?;
+        tx.commit()?;
+
+        for path in screenshots {
+            let _ = fs::remove_file(path);
+        }
+
+        Ok(deleted)
+    }
+
+    /// Move or copy steps from one recording into another at `position`
+    /// (0-based, clamped), keeping their relative order. Both recordings are
+    /// renumbered in the same transaction. Copies share managed screenshot
+    /// objects (the reference count is bumped) and duplicate plain files.
+    /// Copying within one recording duplicates the steps; moving requires two
+    /// different recordings. Returns the IDs of the steps in the target.
+    pub fn transfer_steps(
+        &self,
+        source_id: &str,
+        target_id: &str,
+        step_ids: &[String],
+        position: i32,
+        copy: bool,
+    ) -> Result<Vec<String>> {
+        let mut written: Vec<PathBuf> = Vec::new();
+        let result = self.transfer_steps_in_transaction(
+            source_id,
+            target_id,
+            step_ids,
+            position,
+            copy,
+            &mut written,
+        );
+        if result.is_err() {
+            for path in &written {
+                let _ = fs::remove_file(path);
+            }
+        }
+        result
+    }
+
+    fn transfer_steps_in_transaction(
+        &self,
+        source_id: &str,
+        target_id: &str,
+        step_ids: &[String],
+        position: i32,
+        copy: bool,
+        written: &mut Vec<PathBuf>,
+    ) -> Result<Vec<String>> {
+        let tx = self.conn.unchecked_transaction()?;
+        let target_exists: bool = tx
+            .query_row(
+                "SELECT 1 FROM recordings WHERE id = ?1",
+                params![target_id],
+                |_| Ok(()),
+            )
+            .optional()?
+            .is_some();
+        if !target_exists {
+            return Err(rusqlite::Error::QueryReturnedNoRows);
+        }
+
+        // Keep the steps' order from the source recording.
+        let selected: Vec<String> = Self::ordered_step_ids(&tx, source_id)?
+            .into_iter()
+            .filter(|id| step_ids.contains(id))
+            .collect();
+        if selected.len() != step_ids.len() {
+            return Err(rusqlite::Error::QueryReturnedNoRows);
+        }
+
+        let target_ids = Self::ordered_step_ids(&tx, target_id)?;
+        let position = position.clamp(0, target_ids.len() as i32) as usize;
+
+        let mut transferred: Vec<String> = Vec::with_capacity(selected.len());
+        if copy {
+            let mut select = tx.prepare_cached(
+                "SELECT screenshot_path, screenshot_after_path, element_screenshot_path,
+                     original_path
+                 FROM steps WHERE id = ?1",
+            )?;
+            let mut insert = tx.prepare_cached(COPY_STEP_SQL)?;
+            for step_id in &selected {
+                type Paths = (
+                    Option<String>,
+                    Option<String>,
+                    Option<String>,
+                    Option<String>,
+                );
+                let (screenshot, after, element, original): Paths = select
+                    .query_row(params![step_id], |row| {
+                        Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?))
+                    })?;
+                let new_id = Uuid::new_v4().to_string();
+
+                // Objects are shared by reference; plain files are copied.
+                let mut share_or_copy = |path: Option<String>, suffix: &str| -> Result<_> {
+                    Ok(match path {
+                        Some(path) if is_screenshot_object(&tx, &path)? => {
+                            retain_screenshot(&tx, &path)?;
+                            Some(path)
+                        }
+                        Some(path) => copy_step_file(&path, target_id, &new_id, suffix, written),
+                        None => None,
+                    })
+                };
+                let screenshot = share_or_copy(screenshot, "")?;
+                let element = share_or_copy(element, "_element")?;
+                let original = share_or_copy(original, "_original")?;
+                let after = after
+                    .and_then(|path| copy_step_file(&path, target_id, &new_id, "_after", written));
+
+                insert.execute(params![
+                    new_id, target_id, screenshot, after, step_id, element, original
+                ])?;
+                transferred.push(new_id);
+            }
+        } else {
+            let mut update = tx.prepare_cached(
+                "UPDATE steps SET recording_id = ?1 WHERE id = ?2 AND recording_id = ?3",
+            )?;
+            for step_id in &selected {
+                update.execute(params![target_id, step_id, source_id])?;
+            }
+            transferred = selected;
+        }
+
+        {
+            let mut renumber =
+                tx.prepare_cached("UPDATE steps SET order_index = ?1 WHERE id = ?2")?;
+            let mut target_order = target_ids;
+            target_order.splice(position..position, transferred.iter().cloned());
+            for (index, id) in target_order.iter().enumerate() {
+                renumber.execute(params![index as i32, id])?;
+            }
+            if !copy {
+                for (index, id) in Self::ordered_step_ids(&tx, source_id)?.iter().enumerate() {
+                    renumber.execute(params![index as i32, id])?;
+                }
+            }
+        }
+
+        self.audit(
+            &tx,
+            Some(target_id),
+            None,
+            if copy { "steps_copied" } else { "steps_moved" },
+            Some(serde_json::json!({
+                "source_id": source_id,
+                "step_ids": transferred,
+                "position": position,
+            })),
+        )?;
+        Self::touch_recording(&tx, target_id)?;
+        if !copy {
+            Self::touch_recording(&tx, source_id)?;
+        }
+        tx.commit()?;
+        Ok(transferred)
+    }
+
+    /// Find-and-replace across every step description of a recording in one
+    /// transaction. Returns the number of steps whose description changed.
+    pub fn replace_in_step_descriptions(
+        &self,
+        recording_id: &str,
+        find: &str,
+        replace: &str,
+        case_sensitive: bool,
+    ) -> Result<usize> {
+        if find.is_empty() {
+            return Ok(0);
+        }
+
+        let tx = self.conn.unchecked_transaction()?;
+        let mut changed = 0;
+
+        {
+            let mut select = tx.prepare_cached(
+                "SELECT id, description FROM steps WHERE recording_id = ?1 AND description IS NOT NULL",
+            )?;
+            let rows: Vec<(String, String)> = select
+                .query_map(params![recording_id], |row| Ok((row.get(0)?, row.get(1)?)))?
+                .collect::<Result<_>>()?;
+
+            let mut update = tx.prepare_cached(
+                "UPDATE steps SET description = ?1, description_generated = 0 WHERE id = ?2",
+            )?;
+            for (id, description) in rows {
+                let replaced = if case_sensitive {
+                    description.replace(find, replace)
+                } else {
+                    replace_case_insensitive(&description, find, replace)
+                };
+                if replaced != description {
+                    update.execute(params![replaced, id])?;
+                    changed += 1;
+                }
+            }
+        }
+
+        if changed > 0 {
+            self.audit(
+                &tx,
+                Some(recording_id),
+                None,
+                "step_descriptions_replaced",
+                Some(serde_json::json!({
+                    "find": find,
+                    "replace": replace,
+                    "case_sensitive": case_sensitive,
+                    "changed": changed,
+                })),
+            )?;
+            Self::touch_recording(&tx, recording_id)?;
+        }
+        tx.commit()?;
+        Ok(changed)
+    }
+
+    /// Point several steps at new screenshot paths and mark them cropped, all
+    /// in one transaction. Each entry is `(step_id, screenshot_path)`; the
+    /// steps' lossless originals become the `(step_id, path)` crops in
+    /// `originals`, and steps without one lose theirs. Returns the previous
+    /// screenshots that are no longer referenced and can be deleted.
+    pub fn update_step_screenshots_cropped(
+        &self,
+        recording_id: &str,
+        updates: &[(String, String)],
+        originals: &[(String, String)],
+    ) -> Result<Vec<PathBuf>> {
+        let tx = self.conn.unchecked_transaction()?;
+        let mut orphaned = Self::swap_step_screenshots(&tx, recording_id, updates, true)?;
+        for (step_id, _) in updates {
+            let original = originals
+                .iter()
+                .find(|(id, _)| id == step_id)
+                .map(|(_, path)| path.as_str());
+            orphaned.extend(Self::swap_step_original(&tx, step_id, original)?);
+        }
+        let step_ids: Vec<&str> = updates.iter().map(|(id, _)| id.as_str()).collect();
+        self.audit(
+            &tx,
+            Some(recording_id),
+            None,
+            "step_screenshots_cropped",
+            Some(serde_json::json!({ "step_ids": step_ids })),
+        )?;
+        Self::touch_recording(&tx, recording_id)?;
+        tx.commit()?;
+        Ok(orphaned)
+    }
+
+    /// Swap in re-encoded screenshots and mark the recording archived, in one
+    /// transaction. Lossless originals of those steps are dropped too.
+    /// Returns the previous files that can be deleted.
+    pub fn archive_recording(
+        &self,
+        recording_id: &str,
+        updates: &[(String, String)],
+    ) -> Result<Vec<PathBuf>> {
+        let tx = self.conn.unchecked_transaction()?;
+        let mut orphaned = Self::swap_step_screenshots(&tx, recording_id, updates, false)?;
+        for (step_id, _) in updates {
+            orphaned.extend(Self::swap_step_original(&tx, step_id, None)?);
+        }
+        let now = chrono::Utc::now().timestamp_millis();
+        if tx.execute(
+            "UPDATE recordings SET archived_at = ?1 WHERE id = ?2",
+            params![now, recording_id],
+        )? == 0
+        {
+            return Err(rusqlite::Error::QueryReturnedNoRows);
+        }
+        self.audit(
+            &tx,
+            Some(recording_id),
+            None,
+            "recording_archived",
+            Some(serde_json::json!({ "screenshots_reencoded": updates.len() })),
+        )?;
+        tx.commit()?;
+        Ok(orphaned)
+    }
+
+    /// Clear the archived mark. Archived screenshots keep their reduced
+    /// quality; the originals were replaced when archiving.
+    pub fn unarchive_recording(&self, recording_id: &str) -> Result<()> {
+        let tx = self.conn.unchecked_transaction()?;
+        tx.execute(
+            "UPDATE recordings SET archived_at = NULL WHERE id = ?1",
+            params![recording_id],
+        )?;
+        self.audit(&tx, Some(recording_id), None, "recording_unarchived", None)?;
+        tx.commit()
+    }
+
+    /// The stored images of a recording's steps as `(step_id, image, path)`.
+    pub fn step_images(&self, recording_id: &str) -> Result<Vec<(String, StepImage, String)>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT id, screenshot_path, element_screenshot_path FROM steps
+             WHERE recording_id = ?1 ORDER BY order_index",
+        )?;
+        let rows: Vec<(String, Option<String>, Option<String>)> = stmt
+            .query_map(params![recording_id], |row| {
+                Ok((row.get(0)?, row.get(1)?, row.get(2)?))
+            })?
+            .collect::<Result<_>>()?;
+
+        let mut images = Vec::new();
+        for (step_id, screenshot, element) in rows {
+            for (image, path) in [
+                (StepImage::Screenshot, screenshot),
+                (StepImage::Element, element),
+            ] {
+                if let Some(path) = path {
+                    images.push((step_id.clone(), image, path));
+                }
+            }
+        }
+        Ok(images)
+    }
+
+    /// Point a recording's steps at re-encoded images in one transaction.
+    /// Steps whose screenshot was resized get their click position, element
+    /// bounds, marker, annotations and redactions scaled to match, and lose
+    /// their lossless original. Returns
+    /// the previous files no step references any more, which can be deleted.
+    /// Fails on a locked recording.
+    pub fn replace_step_images(
+        &self,
+        recording_id: &str,
+        updates: &[ImageReplacement],
+    ) -> Result<Vec<PathBuf>> {
+        let tx = self.conn.unchecked_transaction()?;
+        // The triggers don't cover image paths
+        if Self::is_recording_locked(&tx, recording_id)? {
+            return Err(locked_error());
+        }
+        let mut orphaned = Vec::new();
+        for ImageReplacement {
+            step_id,
+            image,
+            path,
+            scale,
+        } in updates
+        {
+            let column = image.column();
+            let previous: Option<String> = tx
+                .query_row(
+                    &format!(
+                        "SELECT {} FROM steps WHERE id = ?1 AND recording_id = ?2",
+                        column
+                    ),
+                    params![step_id, recording_id],
+                    |row| row.get(0),
+                )
+                .optional()?
+                .flatten();
+            if tx.execute(
+                &format!(
+                    "UPDATE steps SET {} = ?1 WHERE id = ?2 AND recording_id = ?3",
+                    column
+                ),
+                params![path, step_id, recording_id],
+            )? == 0
+            {
+                return Err(rusqlite::Error::QueryReturnedNoRows);
+            }
+            retain_screenshot(&tx, path)?;
+            if let Some(previous) = previous {
+                if release_screenshot(&tx, &previous)? {
+                    orphaned.push(PathBuf::from(previous));
+                }
+            }
+            if *image == StepImage::Screenshot && *scale != 1.0 {
+                Self::scale_step_geometry(&tx, step_id, *scale)?;
+                // Its original is still the old size
+                orphaned.extend(Self::swap_step_original(&tx, step_id, None)?);
+            }
+        }
+
+        // Sizes changed; recompute them next time they're asked for
+        tx.execute(
+            "DELETE FROM storage_usage WHERE recording_id = ?1",
+            params![recording_id],
+        )?;
+        self.audit(
+            &tx,
+            Some(recording_id),
+            None,
+            "step_images_reencoded",
+            Some(serde_json::json!({ "images": updates.len() })),
+        )?;
+        tx.commit()?;
+        Ok(orphaned)
+    }
+
+    /// Point one of a step's images at `path`, the result of `edit` on the
+    /// `width` x `height` image it replaces, in one transaction. Edits of the
+    /// screenshot move the click position, element bounds, marker,
+    /// annotations and redactions with its pixels, and replace its lossless
+    /// original with `original`. Returns the previous files nothing
+    /// references any more.
+    pub fn replace_step_image_edited(
+        &self,
+        step_id: &str,
+        image: StepImage,
+        path: &str,
+        original: Option<&str>,
+        edit: ImageEdit,
+        (width, height): (u32, u32),
+    ) -> Result<Vec<PathBuf>> {
+        let tx = self.conn.unchecked_transaction()?;
+        let column = image.column();
+        let (recording_id, previous): (String, Option<String>) = tx.query_row(
+            &format!("SELECT recording_id, {} FROM steps WHERE id = ?1", column),
+            params![step_id],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )?;
+        // The triggers don't cover image paths, which startup repairs rewrite
+        if Self::is_recording_locked(&tx, &recording_id)? {
+            return Err(locked_error());
+        }
+        tx.execute(
+            &format!("UPDATE steps SET {} = ?1 WHERE id = ?2", column),
+            params![path, step_id],
+        )?;
+        retain_screenshot(&tx, path)?;
+        let mut orphaned = Vec::new();
+        if let Some(previous) = previous {
+            if release_screenshot(&tx, &previous)? {
+                orphaned.push(PathBuf::from(previous));
+            }
+        }
+        if image == StepImage::Screenshot {
+            orphaned.extend(Self::swap_step_original(&tx, step_id, original)?);
+            Self::map_step_geometry(
+                &tx,
+                step_id,
+                &|point| edit.map_point(point, width, height),
+                edit.scale(width),
+            )?;
+        }
+
+        tx.execute(
+            "DELETE FROM storage_usage WHERE recording_id = ?1",
+            params![recording_id],
+        )?;
+        self.audit(
+            &tx,
+            Some(&recording_id),
+            Some(step_id),
+            "step_image_edited",
+            Some(serde_json::json!({ "image": image, "edit": format!("{:?}", edit) })),
+        )?;
+        Self::touch_recording(&tx, &recording_id)?;
+        tx.commit()?;
+        Ok(orphaned)
+    }
+
+    /// Scale everything a step places on its screenshot by `factor`, after
+    /// the screenshot was resized by it. The display scale grows with it,
+    /// since a logical pixel now covers fewer screenshot pixels.
+    fn scale_step_geometry(conn: &Connection, step_id: &str, factor: f64) -> Result<()> {
+        let factor_f32 = factor as f32;
+        Self::map_step_geometry(conn, step_id, &|point| point.scaled(factor_f32), factor)
+    }
+
+    /// Move everything a step places on its screenshot with `map`, after an
+    /// edit moved the screenshot's pixels the same way. Strokes, text and the
+    /// display scale grow by `factor`, the edit's change in size.
+    fn map_step_geometry(
+        conn: &Connection,
+        step_id: &str,
+        map: &impl Fn(Point) -> Point,
+        factor: f64,
+    ) -> Result<()> {
+        let Some(step) = conn
+            .query_row(
+                &format!("SELECT {} FROM steps WHERE id = ?1", STEP_COLUMNS),
+                params![step_id],
+                map_step_row,
+            )
+            .optional()?
+        else {
+            return Ok(());
+        };
+
+        let factor_f32 = factor as f32;
+        let map_bounds = |bounds: ElementBounds| {
+            let rect = Rect {
+                x: bounds.x as f32,
+                y: bounds.y as f32,
+                width: bounds.width as f32,
+                height: bounds.height as f32,
+            }
+            .mapped(map);
+            ElementBounds {
+                x: rect.x.round() as i32,
+                y: rect.y.round() as i32,
+                width: rect.width.round() as i32,
+                height: rect.height.round() as i32,
+            }
+        };
+        let click = match (step.x, step.y) {
+            (Some(x), Some(y)) => Some(map(Point {
+                x: x as f32,
+                y: y as f32,
+            })),
+            _ => None,
+        };
+        let ax_tree_json =
+            step.ax_tree_json
+                .as_deref()
+                .map(|json| match serde_json::from_str::<AxNode>(json) {
+                    Ok(mut node) => {
+                        node.map_bounds(&map_bounds);
+                        serde_json::to_string(&node).unwrap_or_else(|_| json.to_string())
+                    }
+                    Err(_) => json.to_string(),
+                });
+        conn.execute(
+            "UPDATE steps SET x = ?1, y = ?2, element_bounds = ?3, click_marker = ?4,
+                 annotations = ?5, redactions = ?6, scale_factor = ?7, ax_tree_json = ?8
+             WHERE id = ?9",
+            params![
+                click.map(|click| click.x.round() as i32),
+                click.map(|click| click.y.round() as i32),
+                step.element_bounds
+                    .map(map_bounds)
+                    .and_then(|bounds| serde_json::to_string(&bounds).ok()),
+                step.click_marker
+                    .map(map)
+                    .and_then(|marker| serde_json::to_string(&marker).ok()),
+                step.annotations
+                    .map(|annotations| {
+                        annotations
+                            .iter()
+                            .map(|annotation| annotation.mapped(map, factor_f32))
+                            .collect::<Vec<_>>()
+                    })
+                    .and_then(|annotations| serde_json::to_string(&annotations).ok()),
+                step.redactions
+                    .map(|redactions| {
+                        redactions
+                            .iter()
+                            .map(|redaction| redaction.mapped(map))
+                            .collect::<Vec<_>>()
+                    })
+                    .and_then(|redactions| serde_json::to_string(&redactions).ok()),
+                step.scale_factor.unwrap_or(1.0) * factor,
+                ax_tree_json,
+                step_id
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// Repoint steps at new screenshot files, moving object references along.
+    fn swap_step_screenshots(
+        conn: &Connection,
+        recording_id: &str,
+        updates: &[(String, String)],
+        mark_cropped: bool,
+    ) -> Result<Vec<PathBuf>> {
+        let mut orphaned = Vec::new();
+        let mut select = conn.prepare_cached(
+            "SELECT screenshot_path FROM steps WHERE id = ?1 AND recording_id = ?2",
+        )?;
+        let mut update = conn.prepare_cached(
+            "UPDATE steps SET screenshot_path = ?1, is_cropped = MAX(COALESCE(is_cropped, 0), ?2)
+             WHERE id = ?3 AND recording_id = ?4",
+        )?;
+        for (step_id, screenshot_path) in updates {
+            let previous: Option<String> = select
+                .query_row(params![step_id, recording_id], |row| {
+                    row.get::<_, Option<String>>(0)
+                })
+                .optional()?
+                .flatten();
+            if update.execute(params![
+                screenshot_path,
+                mark_cropped as i32,
+                step_id,
+                recording_id
+            ])? == 0
+            {
+                return Err(rusqlite::Error::QueryReturnedNoRows);
+            }
+            retain_screenshot(conn, screenshot_path)?;
+            if let Some(previous) = previous {
+                if release_screenshot(conn, &previous)? {
+                    orphaned.push(PathBuf::from(previous));
+                }
+            }
+        }
+        Ok(orphaned)
+    }
+
+    /// Point a step at a new lossless original, or at none. Returns the
+    /// previous one when nothing references it any more.
+    fn swap_step_original(
+        conn: &Connection,
+        step_id: &str,
+        path: Option<&str>,
+    ) -> Result<Option<PathBuf>> {
+        let previous: Option<String> = conn
+            .query_row(
+                "SELECT original_path FROM steps WHERE id = ?1",
+                params![step_id],
+                |row| row.get(0),
+            )
+            .optional()?
+            .flatten();
+        if previous.as_deref() == path {
+            return Ok(None);
+        }
+        conn.execute(
+            "UPDATE steps SET original_path = ?1 WHERE id = ?2",
+            params![path, step_id],
+        )?;
+        if let Some(path) = path {
+            retain_screenshot(conn, path)?;
+        }
+        match previous {
+            Some(previous) if release_screenshot(conn, &previous)? => {
+                Ok(Some(PathBuf::from(previous)))
+            }
+            _ => Ok(None),
+        }
+    }
+
+    pub fn update_step_after_screenshot(
+        &self,
+        step_id: &str,
+        screenshot_after_path: Option<&str>,
+    ) -> Result<()> {
+        self.conn.execute(
+            "UPDATE steps SET screenshot_after_path = ?1 WHERE id = ?2",
+            params![screenshot_after_path, step_id],
+        )?;
+        Ok(())
+    }
+
+    pub fn update_step_identified_element(
+        &self,
+        step_id: &str,
+        identified_element_json: Option<&str>,
+    ) -> Result<()> {
+        self.conn.execute(
+            "UPDATE steps SET identified_element_json = ?1 WHERE id = ?2",
+            params![identified_element_json, step_id],
+        )?;
+        Ok(())
+    }
+
+    pub fn update_step_clip_path(
+        &self,
+        step_id: &str,
+        clip_path: Option<&str>,
+    ) -> Result<()> {
+        self.conn.execute(
+            "UPDATE steps SET clip_path = ?1 WHERE id = ?2",
+            params![clip_path, step_id],
+        )?;
+        Ok(())
+    }
+
+    /// Count a recording's screenshot steps by OCR status
+    pub fn recording_ocr_status(&self, recording_id: &str) -> Result<RecordingOcrStatus> {
+        let mut stmt = self.conn.prepare(
+            "SELECT COALESCE(ocr_status, 'pending'), COUNT(*) FROM steps
+             WHERE recording_id = ?1 AND screenshot_path IS NOT NULL
+             GROUP BY 1",
+        )?;
+        let mut status = RecordingOcrStatus::default();
+        let rows = stmt.query_map(params![recording_id], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, i64>(1)?))
+        })?;
+        for row in rows {
+            let (ocr_status, count) = row?;
+            status.total += count;
+            match ocr_status.as_str() {
+                "completed" => status.completed += count,
+                "accessibility" => status.accessibility += count,
+                "failed" => status.failed += count,
+                "skipped" => status.skipped += count,
+                _ => status.pending += count,
+            }
+        }
+        status.finished = status.pending == 0 && status.skipped == 0;
+        Ok(status)
+    }
+
+    /// Store a step's OCR result. A missing or generated description is
+    /// rebuilt with the new text; returns the step's description afterwards.
+    pub fn update_step_ocr(
+        &self,
+        step_id: &str,
+        ocr_text: Option<&str>,
+        ocr_lines: Option<&[OcrLine]>,
+        ocr_status: &str,
+    ) -> Result<Option<String>> {
+        let ocr_lines = ocr_lines.and_then(|lines| serde_json::to_string(lines).ok());
+        let tx = self.conn.unchecked_transaction()?;
+        tx.execute(
+            "UPDATE steps SET ocr_text = ?1, ocr_lines = ?2, ocr_status = ?3 WHERE id = ?4",
+            params![ocr_text, ocr_lines, ocr_status, step_id],
+        )?;
+
+        let step = tx
+            .query_row(
+                &format!("SELECT {} FROM steps WHERE id = ?1", STEP_COLUMNS),
+                params![step_id],
+                map_step_row,
+            )
+            .optional()?;
+        let Some(step) = step else {
+            return tx.commit().map(|_| None);
+        };
+        // A locked recording keeps its description; the trigger would reject
+        // rewriting it and take the OCR result down with it
+        if Self::is_recording_locked(&tx, &step.recording_id)? {
+            tx.commit()?;
+            return Ok(step.description);
+        }
+
+        let written = step
+            .description
+            .as_deref()
+            .is_some_and(|description| !description.trim().is_empty());
+        let description = if written && !step.description_generated {
+            step.description
+        } else {
+            match describe_step(&(&step).into()) {
+                Some(generated) => {
+                    tx.execute(
+                        "UPDATE steps SET description = ?1, description_generated = 1 WHERE id = ?2",
+                        params![generated, step_id],
+                    )?;
+                    Some(generated)
+                }
+                None => step.description,
+            }
+        };
+        tx.commit()?;
+        Ok(description)
+    }
+
+    // ── Storage usage ──────────────────────────────────────────────────
+
+    /// Per-recording disk usage and the library total. Cached figures are
+    /// reused unless the recording was updated since they were computed or
+    /// `force_refresh` is set.
+    pub fn get_storage_usage(&self, force_refresh: bool) -> Result<StorageUsage> {
+        let stale: Vec<String> = {
+            let mut stmt = self.conn.prepare(
+                "SELECT r.id FROM recordings r
+                 LEFT JOIN storage_usage u ON u.recording_id = r.id
+                 WHERE ?1 OR u.computed_at IS NULL OR u.computed_at < r.updated_at",
+            )?;
+            let ids = stmt
+                .query_map(params![force_refresh], |row| row.get(0))?
+                .collect::<Result<_>>()?;
+            ids
+        };
+        for recording_id in &stale {
+            self.refresh_storage_usage(recording_id)?;
+        }
+
+        let mut stmt = self.conn.prepare(
+            "SELECT u.recording_id, r.name, u.file_count, u.bytes, u.shared_bytes, u.computed_at
+             FROM storage_usage u
+             JOIN recordings r ON r.id = u.recording_id
+             ORDER BY u.bytes DESC",
+        )?;
+        let recordings = stmt
+            .query_map([], |row| {
+                Ok(RecordingStorageUsage {
+                    recording_id: row.get(0)?,
+                    name: row.get(1)?,
+                    file_count: row.get(2)?,
+                    bytes: row.get(3)?,
+                    shared_bytes: row.get(4)?,
+                    computed_at: row.get(5)?,
+                })
+            })?
+            .collect::<Result<Vec<_>>>()?;
+
+        let object_bytes: i64 = self.conn.query_row(
+            "SELECT COALESCE(SUM(size), 0) FROM screenshot_objects",
+            [],
+            |row| row.get(0),
+        )?;
+        let total_bytes = object_bytes
+            + recordings
+                .iter()
+                .map(|usage| usage.bytes - usage.shared_bytes)
+                .sum::<i64>();
+
+        Ok(StorageUsage {
+            recordings,
+            total_bytes,
+        })
+    }
+
+    fn refresh_storage_usage(&self, recording_id: &str) -> Result<()> {
+        let mut stmt = self.conn.prepare(
+            "SELECT screenshot_path, screenshot_after_path, clip_path, element_screenshot_path,
+                 original_path
+             FROM steps WHERE recording_id = ?1",
+        )?;
+        let rows: Vec<[Option<String>; 5]> = stmt
+            .query_map(params![recording_id], |row| {
+                Ok([
+                    row.get(0)?,
+                    row.get(1)?,
+                    row.get(2)?,
+                    row.get(3)?,
+                    row.get(4)?,
+                ])
+            })?
+            .collect::<Result<_>>()?;
+
+        // Steps can share a file (deduplicated objects); count it once.
+        let mut seen = std::collections::HashSet::new();
+        let (mut file_count, mut bytes, mut shared_bytes) = (0i64, 0i64, 0i64);
+        for path in rows.into_iter().flatten().flatten() {
+            if !seen.insert(path.clone()) {
+                continue;
+            }
+            let Ok(metadata) = fs::metadata(&path) else {
+                continue;
+            };
+            let size = metadata.len() as i64;
+            file_count += 1;
+            bytes += size;
+            if is_screenshot_object(&self.conn, &path)? {
+                shared_bytes += size;
+            }
+        }
+
+        self.conn.execute(
+            "INSERT OR REPLACE INTO storage_usage (recording_id, file_count, bytes, shared_bytes, computed_at)
+             VALUES (?1, ?2, ?3, ?4, ?5)",
+            params![
+                recording_id,
+                file_count,
+                bytes,
+                shared_bytes,
+                chrono::Utc::now().timestamp_millis()
+            ],
+        )?;
+        Ok(())
+    }
+
+    // ── Recording links ────────────────────────────────────────────────
+
+    /// Link `from_id` to `to_id`. Adding an existing link is a no-op.
+    pub fn add_recording_link(&self, from_id: &str, to_id: &str, kind: &str) -> Result<()> {
+        let tx = self.conn.unchecked_transaction()?;
+        let inserted = tx.execute(
+            "INSERT OR IGNORE INTO recording_links (from_id, to_id, kind, created_at)
+             SELECT ?1, ?2, ?3, ?4
+             WHERE EXISTS (SELECT 1 FROM recordings WHERE id = ?1)
+               AND EXISTS (SELECT 1 FROM recordings WHERE id = ?2)",
+            params![from_id, to_id, kind, chrono::Utc::now().timestamp_millis()],
+        )?;
+        if inserted > 0 {
+            self.audit(
+                &tx,
+                Some(from_id),
+                None,
+                "link_added",
+                Some(serde_json::json!({ "to_id": to_id, "kind": kind })),
+            )?;
+        }
+        tx.commit()
+    }
+
+    pub fn remove_recording_link(&self, from_id: &str, to_id: &str, kind: &str) -> Result<()> {
+        let tx = self.conn.unchecked_transaction()?;
+        let removed = tx.execute(
+            "DELETE FROM recording_links WHERE from_id = ?1 AND to_id = ?2 AND kind = ?3",
+            params![from_id, to_id, kind],
+        )?;
+        if removed > 0 {
+            self.audit(
+                &tx,
+                Some(from_id),
+                None,
+                "link_removed",
+                Some(serde_json::json!({ "to_id": to_id, "kind": kind })),
+            )?;
+        }
+        tx.commit()
+    }
+
+    /// Outgoing links of a recording, prerequisites first.
+    pub fn list_recording_links(&self, recording_id: &str) -> Result<Vec<RecordingLink>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT l.from_id, l.to_id, r.name, l.kind, l.created_at
+             FROM recording_links l
+             JOIN recordings r ON r.id = l.to_id
+             WHERE l.from_id = ?1
+             ORDER BY l.kind = 'prerequisite' DESC, l.created_at",
+        )?;
+        let links = stmt.query_map(params![recording_id], |row| {
+            Ok(RecordingLink {
+                from_id: row.get(0)?,
+                to_id: row.get(1)?,
+                to_name: row.get(2)?,
+                kind: row.get(3)?,
+                created_at: row.get(4)?,
+            })
+        })?;
+        links.collect()
+    }
+
+    // ── Cloud sync ─────────────────────────────────────────────────────
+
+    pub fn list_sync_states(&self) -> Result<Vec<SyncState>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT recording_id, remote_key, remote_etag, local_updated_at, synced_at, conflict
+             FROM sync_state ORDER BY synced_at DESC",
+        )?;
+        let states = stmt.query_map([], |row| {
+            Ok(SyncState {
+                recording_id: row.get(0)?,
+                remote_key: row.get(1)?,
+                remote_etag: row.get(2)?,
+                local_updated_at: row.get(3)?,
+                synced_at: row.get(4)?,
+                conflict: row.get(5)?,
+            })
+        })?;
+        states.collect()
+    }
+
+    /// Record a completed push or pull, replacing whatever was known about
+    /// the recording or its bundle.
+    pub fn save_sync_state(&self, state: &SyncState) -> Result<()> {
+        let tx = self.conn.unchecked_transaction()?;
+        tx.execute(
+            "DELETE FROM sync_state WHERE recording_id = ?1 OR remote_key = ?2",
+            params![state.recording_id, state.remote_key],
+        )?;
+        tx.execute(
+            "INSERT INTO sync_state
+                (recording_id, remote_key, remote_etag, local_updated_at, synced_at, conflict)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            params![
+                state.recording_id,
+                state.remote_key,
+                state.remote_etag,
+                state.local_updated_at,
+                state.synced_at,
+                state.conflict
+            ],
+        )?;
+        tx.commit()
+    }
+
+    /// Flag a recording as conflicting, adding a row if it had none.
+    pub fn set_sync_conflict(
+        &self,
+        recording_id: &str,
+        remote_key: &str,
+        reason: &str,
+    ) -> Result<()> {
+        self.conn.execute(
+            "INSERT INTO sync_state
+                (recording_id, remote_key, remote_etag, local_updated_at, synced_at, conflict)
+             VALUES (?1, ?2, NULL, 0, ?3, ?4)
+             ON CONFLICT(recording_id) DO UPDATE SET conflict = excluded.conflict",
+            params![
+                recording_id,
+                remote_key,
+                chrono::Utc::now().timestamp_millis(),
+                reason
+            ],
+        )?;
+        Ok(())
+    }
+
+    pub fn delete_sync_state(&self, recording_id: &str) -> Result<()> {
+        self.conn.execute(
+            "DELETE FROM sync_state WHERE recording_id = ?1",
+            params![recording_id],
+        )?;
+        Ok(())
+    }
+
+    // ── Share links ────────────────────────────────────────────────────
+
+    pub fn add_share_link(&self, link: &ShareLink) -> Result<()> {
+        self.conn.execute(
+            "INSERT INTO share_links
+                (token, recording_id, url, created_at, expires_at, hosted, password_hash)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+            params![
+                link.token,
+                link.recording_id,
+                link.url,
+                link.created_at,
+                link.expires_at,
+                link.hosted,
+                link.password_hash
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// Links of `recording_id`, or all of them, newest first.
+    pub fn list_share_links(&self, recording_id: Option<&str>) -> Result<Vec<ShareLink>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT token, recording_id, url, created_at, expires_at, hosted, password_hash
+             FROM share_links
+             WHERE ?1 IS NULL OR recording_id = ?1
+             ORDER BY created_at DESC",
+        )?;
+        let links = stmt.query_map(params![recording_id], map_share_link_row)?;
+        links.collect()
+    }
+
+    pub fn get_share_link(&self, token: &str) -> Result<Option<ShareLink>> {
+        self.conn
+            .query_row(
+                "SELECT token, recording_id, url, created_at, expires_at, hosted, password_hash
+                 FROM share_links WHERE token = ?1",
+                params![token],
+                map_share_link_row,
+            )
+            .optional()
+    }
+
+    pub fn delete_share_link(&self, token: &str) -> Result<()> {
+        self.conn
+            .execute("DELETE FROM share_links WHERE token = ?1", params![token])?;
+        Ok(())
+    }
+
+    // ── Narration ──────────────────────────────────────────────────────
+
+    /// Store the transcript of a recording's narration, replacing any earlier one.
+    pub fn save_narration(&self, narration: &Narration) -> Result<()> {
+        let segments = serde_json::to_string(&narration.segments)
+            .map_err(|e| rusqlite::Error::ToSqlConversionFailure(Box::new(e)))?;
+        self.conn.execute(
+            "INSERT OR REPLACE INTO narrations
+                (recording_id, audio_path, started_at, language, segments, transcribed_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            params![
+                narration.recording_id,
+                narration.audio_path,
+                narration.started_at,
+                narration.language,
+                segments,
+                narration.transcribed_at
+            ],
+        )?;
+        Ok(())
+    }
+
+    pub fn get_narration(&self, recording_id: &str) -> Result<Option<Narration>> {
+        self.conn
+            .query_row(
+                "SELECT recording_id, audio_path, started_at, language, segments, transcribed_at
+                 FROM narrations WHERE recording_id = ?1",
+                params![recording_id],
+                |row| {
+                    let segments: String = row.get(4)?;
+                    Ok(Narration {
+                        recording_id: row.get(0)?,
+                        audio_path: row.get(1)?,
+                        started_at: row.get(2)?,
+                        language: row.get(3)?,
+                        segments: serde_json::from_str(&segments).unwrap_or_default(),
+                        transcribed_at: row.get(5)?,
+                    })
+                },
+            )
+            .optional()
+    }
+
+    // ── Hosted images ──────────────────────────────────────────────────
+
+    /// The upload of `sha256` to `destination` that's still valid at `now`
+    pub fn get_hosted_image(
+        &self,
+        sha256: &str,
+        destination: &str,
+        now: i64,
+    ) -> Result<Option<HostedImage>> {
+        self.conn
+            .query_row(
+                "SELECT sha256, destination, url, uploaded_at, expires_at
+                 FROM hosted_images
+                 WHERE sha256 = ?1 AND destination = ?2
+                   AND (expires_at IS NULL OR expires_at > ?3)",
+                params![sha256, destination, now],
+                |row| {
+                    Ok(HostedImage {
+                        sha256: row.get(0)?,
+                        destination: row.get(1)?,
+                        url: row.get(2)?,
+                        uploaded_at: row.get(3)?,
+                        expires_at: row.get(4)?,
+                    })
+                },
+            )
+            .optional()
+    }
+
+    pub fn save_hosted_image(&self, image: &HostedImage) -> Result<()> {
+        self.conn.execute(
+            "INSERT OR REPLACE INTO hosted_images
+                (sha256, destination, url, uploaded_at, expires_at)
+             VALUES (?1, ?2, ?3, ?4, ?5)",
+            params![
+                image.sha256,
+                image.destination,
+                image.url,
+                image.uploaded_at,
+                image.expires_at
+            ],
+        )?;
+        Ok(())
+    }
+
+    // ── Audit log ──────────────────────────────────────────────────────
+
+    /// Append an audit entry. Run on the same connection/transaction as the
+    /// change it describes so both commit or roll back together. When only
+    /// `step_id` is given the recording is looked up from the step row, so
+    /// call this before deleting the step.
+    fn audit(
+        &self,
+        conn: &Connection,
+        recording_id: Option<&str>,
+        step_id: Option<&str>,
+        action: &str,
+        detail: Option<serde_json::Value>,
+    ) -> Result<()> {
+        conn.execute(
+            "INSERT INTO audit_log (recording_id, step_id, action, actor, detail, created_at)
+             VALUES (COALESCE(?1, (SELECT recording_id FROM steps WHERE id = ?2)), ?2, ?3, ?4, ?5, ?6)",
+            params![
+                recording_id,
+                step_id,
+                action,
+                self.actor,
+                detail.map(|detail| detail.to_string()),
+                chrono::Utc::now().timestamp_millis()
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// Audit entries, newest first, optionally limited to one recording.
+    pub fn list_audit_log(
+        &self,
+        recording_id: Option<&str>,
+        limit: i32,
+        offset: i32,
+    ) -> Result<Vec<AuditEntry>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT id, recording_id, step_id, action, actor, detail, created_at
+             FROM audit_log
+             WHERE ?1 IS NULL OR recording_id = ?1
+             ORDER BY id DESC
+             LIMIT ?2 OFFSET ?3",
+        )?;
+        let entries = stmt.query_map(params![recording_id, limit, offset], |row| {
+            Ok(AuditEntry {
+                id: row.get(0)?,
+                recording_id: row.get(1)?,
+                step_id: row.get(2)?,
+                action: row.get(3)?,
+                actor: row.get(4)?,
+                detail: row.get(5)?,
+                created_at: row.get(6)?,
+            })
+        })?;
+        entries.collect()
+    }
+
+    // ── Usage analytics ────────────────────────────────────────────────
+
+    /// Add `counts` to the stored daily counts and store `samples`.
+    pub fn record_usage(&self, counts: &[UsageCount], samples: &[MetricSample]) -> Result<()> {
+        let tx = self.conn.unchecked_transaction()?;
+        for count in counts {
+            tx.execute(
+                "INSERT INTO usage_counts (day, feature, count) VALUES (?1, ?2, ?3)
+                 ON CONFLICT(day, feature) DO UPDATE SET count = count + excluded.count",
+                params![count.day, count.feature, count.count],
+            )?;
+        }
+        for sample in samples {
+            tx.execute(
+                "INSERT INTO capture_metrics (metric, value_ms, recorded_at) VALUES (?1, ?2, ?3)",
+                params![sample.metric, sample.value_ms, sample.recorded_at],
+            )?;
+        }
+        tx.commit()
+    }
+
+    /// Daily counts from `day` on, oldest first.
+    pub fn usage_counts_since(&self, day: &str) -> Result<Vec<UsageCount>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT day, feature, count FROM usage_counts
+             WHERE day >= ?1
+             ORDER BY day, feature",
+        )?;
+        let counts = stmt.query_map(params![day], |row| {
+            Ok(UsageCount {
+                day: row.get(0)?,
+                feature: row.get(1)?,
+                count: row.get(2)?,
+            })
+        })?;
+        counts.collect()
+    }
+
+    pub fn metric_samples_since(&self, recorded_at: i64) -> Result<Vec<MetricSample>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT metric, value_ms, recorded_at FROM capture_metrics
+             WHERE recorded_at >= ?1
+             ORDER BY recorded_at",
+        )?;
+        let samples = stmt.query_map(params![recorded_at], |row| {
+            Ok(MetricSample {
+                metric: row.get(0)?,
+                value_ms: row.get(1)?,
+                recorded_at: row.get(2)?,
+            })
+        })?;
+        samples.collect()
+    }
+
+    /// Drop counts of days before `day` and samples before `recorded_at`.
+    pub fn prune_usage_data(&self, day: &str, recorded_at: i64) -> Result<()> {
+        self.conn
+            .execute("DELETE FROM usage_counts WHERE day < ?1", params![day])?;
+        self.conn.execute(
+            "DELETE FROM capture_metrics WHERE recorded_at < ?1",
+            params![recorded_at],
+        )?;
+        Ok(())
+    }
+
+    pub fn clear_usage_data(&self) -> Result<()> {
+        self.conn
+            .execute_batch("DELETE FROM usage_counts; DELETE FROM capture_metrics;")
+    }
+
+    // ── Draft journal ──────────────────────────────────────────────────
+
+    /// Journal a freshly captured step. `step_json` must deserialize as a
+    /// `StepInput` (the recorder's step payload does).
+    pub fn save_draft_step(&self, step_id: &str, step_json: &str) -> Result<()> {
+        self.conn.execute(
+            "INSERT OR REPLACE INTO draft_steps (id, step_json, created_at) VALUES (?1, ?2, ?3)",
+            params![step_id, step_json, chrono::Utc::now().timestamp_millis()],
+        )?;
+        Ok(())
+    }
+
+    pub fn update_draft_step_after(&self, step_id: &str, screenshot_after: &str) -> Result<()> {
+        self.conn.execute(
+            "UPDATE draft_steps SET screenshot_after = ?1 WHERE id = ?2",
+            params![screenshot_after, step_id],
+        )?;
+        Ok(())
+    }
+
+    pub fn update_draft_step_clip(&self, step_id: &str, clip_path: &str) -> Result<()> {
+        self.conn.execute(
+            "UPDATE draft_steps SET clip_path = ?1 WHERE id = ?2",
+            params![clip_path, step_id],
+        )?;
+        Ok(())
+    }
+
+    pub fn clear_drafts(&self) -> Result<()> {
+        self.conn.execute("DELETE FROM draft_steps", [])?;
+        Ok(())
+    }
+
+    /// Save any journaled draft steps as a new "Recovered recording" and
+    /// remove them from the journal. Returns None when there was nothing to
+    /// recover. Steps whose temp screenshot no longer exists are kept without
+    /// an image. Rows that can't be read are logged and left in the journal
+    /// for the next attempt.
+    pub fn recover_drafts(&self) -> Result<Option<RecoveredDraft>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT id, step_json, screenshot_after, clip_path FROM draft_steps
+             ORDER BY created_at",
+        )?;
+        let rows: Vec<(String, String, Option<String>, Option<String>)> = stmt
+            .query_map([], |row| {
+                Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?))
+            })?
+            .collect::<Result<_>>()?;
+        drop(stmt);
+
+        let mut recovered_ids = Vec::new();
+        let mut steps: Vec<StepInput> = Vec::new();
+        for (id, json, screenshot_after, clip_path) in rows {
+            let mut step: StepInput = match serde_json::from_str(&json) {
+                Ok(step) => step,
+                Err(e) => {
+                    tracing::warn!(draft_id = %id, "Failed to read a draft step: {}", e);
+                    continue;
+                }
+            };
+            step.screenshot_after = screenshot_after.or(step.screenshot_after);
+            step.clip_path = clip_path.or(step.clip_path);
+            steps.push(step);
+            recovered_ids.push(id);
+        }
+
+        if steps.is_empty() {
+            return Ok(None);
+        }
+
+        let started_at = steps.iter().map(|step| step.timestamp).min().unwrap_or(0);
+        let locale = i18n::current();
+        let started = locale.format_date_time(started_at);
+        let recording_name =
+            i18n::fill(locale.catalog().recovered_recording, &[("date", &started)])
+                .trim()
+                .to_string();
+        let step_count = steps.len();
+
+        let recording_id = self.create_recording(recording_name.clone())?;
+        self.save_steps(&recording_id, steps)?;
+        for id in &recovered_ids {
+            self.conn
+                .execute("DELETE FROM draft_steps WHERE id = ?1", params![id])?;
+        }
+
+        Ok(Some(RecoveredDraft {
+            recording_id,
+            recording_name,
+            step_count,
+        }))
+    }
+
+    // ── Notification CRUD ──────────────────────────────────────────────
+
+    pub fn create_notification(
+        &self,
+        title: Option<&str>,
+        message: &str,
+        variant: &str,
+        log_category: Option<&str>,
+    ) -> Result<Notification> {
+        let id = Uuid::new_v4().to_string();
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_millis() as i64;
+
+        self.conn.execute(
+            "INSERT INTO notifications (id, title, message, variant, is_read, created_at, log_category)
+             VALUES (?1, ?2, ?3, ?4, 0, ?5, ?6)",
+            params![id, title, message, variant, now, log_category],
+        )?;
+
+        Ok(Notification {
+            id,
+            title: title.map(|s| s.to_string()),
+            message: message.to_string(),
+            variant: variant.to_string(),
+            is_read: false,
+            created_at: now,
+            log_category: log_category.map(|s| s.to_string()),
+        })
+    }
+
+    pub fn list_notifications(&self, limit: i32, offset: i32) -> Result<Vec<Notification>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT id, title, message, variant, is_read, created_at, log_category
+             FROM notifications
+             ORDER BY created_at DESC
+             LIMIT ?1 OFFSET ?2",
+        )?;
+
+        let rows = stmt.query_map(params![limit, offset], |row| {
+            Ok(Notification {
+                id: row.get(0)?,
+                title: row.get(1)?,
+                message: row.get(2)?,
+                variant: row.get(3)?,
+                is_read: row.get::<_, i32>(4)? != 0,
                 created_at: row.get(5)?,
                 log_category: row.get(6)?,
             })
         })?;
 
-        let mut notifications = Vec::new();
-        for row in rows {
-            notifications.push(row?);
-        }
-        Ok(notifications)
+        let mut notifications = Vec::new();
+        for row in rows {
+            notifications.push(row?);
+        }
+        Ok(notifications)
+    }
+
+    pub fn get_unread_notification_count(&self) -> Result<i64> {
+        let count: i64 = self.conn.query_row(
+            "SELECT COUNT(*) FROM notifications WHERE is_read = 0",
+            [],
+            |row| row.get(0),
+        )?;
+        Ok(count)
+    }
+
+    pub fn mark_notification_read(&self, id: &str) -> Result<()> {
+        self.conn.execute(
+            "UPDATE notifications SET is_read = 1 WHERE id = ?1",
+            params![id],
+        )?;
+        Ok(())
+    }
+
+    pub fn mark_all_notifications_read(&self) -> Result<()> {
+        self.conn
+            .execute("UPDATE notifications SET is_read = 1 WHERE is_read = 0", [])?;
+        Ok(())
+    }
+
+    pub fn delete_notification(&self, id: &str) -> Result<()> {
+        self.conn
+            .execute("DELETE FROM notifications WHERE id = ?1", params![id])?;
+        Ok(())
+    }
+
+    pub fn delete_all_notifications(&self) -> Result<()> {
+        self.conn.execute("DELETE FROM notifications", [])?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::Path;
+
+    struct TestDir {
+        path: PathBuf,
+    }
+
+    impl TestDir {
+        fn new() -> Self {
+            let path = std::env::temp_dir().join(format!("stepsnap_db_test_{}", Uuid::new_v4()));
+            fs::create_dir_all(&path).unwrap();
+            Self { path }
+        }
+
+        fn path(&self) -> &Path {
+            &self.path
+        }
+    }
+
+    impl Drop for TestDir {
+        fn drop(&mut self) {
+            let _ = fs::remove_dir_all(&self.path);
+        }
+    }
+
+    fn sample_step_input(
+        screenshot: Option<String>,
+        screenshot_is_permanent: Option<bool>,
+    ) -> StepInput {
+        StepInput {
+            type_: "capture".to_string(),
+            x: None,
+            y: None,
+            text: None,
+            timestamp: 123,
+            screenshot,
+            element_name: None,
+            element_type: None,
+            element_value: None,
+            app_name: None,
+            description: Some("desc".to_string()),
+            is_cropped: Some(false),
+            order_index: Some(0),
+            title: None,
+            screenshot_is_permanent,
+            input_source: None,
+            screenshot_after: None,
+            identified_element_json: None,
+            clip_path: None,
+            element_bounds: None,
+            page_url: None,
+            page_title: None,
+            automation_id: None,
+            class_name: None,
+            element_path: None,
+            process_name: None,
+            exe_path: None,
+            window_title: None,
+            breadcrumb: None,
+            action: None,
+            element_screenshot: None,
+            ax_tree_json: None,
+            ocr_text: None,
+            ocr_status: None,
+            ocr_lines: None,
+            annotations: None,
+            redactions: None,
+            click_marker: None,
+            scale_factor: None,
+            is_duplicate: None,
+            screenshot_original: None,
+        }
+    }
+
+    #[test]
+    fn save_steps_with_path_copies_temp_screenshots_into_custom_directory() {
+        let test_dir = TestDir::new();
+        let db = Database::new(test_dir.path().to_path_buf()).unwrap();
+        let recording_id = db.create_recording("Recording".to_string()).unwrap();
+        let custom_root = test_dir.path().join("custom-root");
+        let temp_file = test_dir.path().join("temp.jpg");
+        fs::write(&temp_file, b"image-bytes").unwrap();
+
+        db.save_steps_with_path(
+            &recording_id,
+            "Recording",
+            vec![sample_step_input(
+                Some(temp_file.to_string_lossy().to_string()),
+                Some(false),
+            )],
+            Some(custom_root.to_string_lossy().as_ref()),
+        )
+        .unwrap();
+
+        let stored_path: String = db
+            .conn
+            .query_row(
+                "SELECT screenshot_path FROM steps WHERE recording_id = ?1",
+                params![recording_id],
+                |row| row.get(0),
+            )
+            .unwrap();
+
+        assert!(stored_path.starts_with(custom_root.to_string_lossy().as_ref()));
+        assert!(stored_path.contains("Recording"));
+        assert!(!temp_file.exists());
+        assert!(PathBuf::from(stored_path).exists());
+    }
+
+    #[test]
+    fn save_steps_with_path_preserves_existing_permanent_screenshot_paths() {
+        let test_dir = TestDir::new();
+        let db = Database::new(test_dir.path().to_path_buf()).unwrap();
+        let recording_id = db.create_recording("Recording".to_string()).unwrap();
+        let permanent_file = test_dir.path().join("already-there.jpg");
+        fs::write(&permanent_file, b"image-bytes").unwrap();
+
+        db.save_steps_with_path(
+            &recording_id,
+            "Recording",
+            vec![sample_step_input(
+                Some(permanent_file.to_string_lossy().to_string()),
+                Some(true),
+            )],
+            None,
+        )
+        .unwrap();
+
+        let stored_path: String = db
+            .conn
+            .query_row(
+                "SELECT screenshot_path FROM steps WHERE recording_id = ?1",
+                params![recording_id],
+                |row| row.get(0),
+            )
+            .unwrap();
+
+        assert_eq!(stored_path, permanent_file.to_string_lossy().to_string());
+        assert!(permanent_file.exists());
+    }
+
+    #[test]
+    fn save_steps_with_path_rolls_back_batch_and_copies_on_failure() {
+        let test_dir = TestDir::new();
+        let db = Database::new(test_dir.path().to_path_buf()).unwrap();
+        let recording_id = db.create_recording("Recording".to_string()).unwrap();
+        let custom_root = test_dir.path().join("custom-root");
+        let temp_file = test_dir.path().join("temp.jpg");
+        fs::write(&temp_file, b"image-bytes").unwrap();
+
+        db.conn
+            .execute_batch(
+                "CREATE TRIGGER fail_step_insert BEFORE INSERT ON steps
+                 WHEN NEW.type_ = 'boom'
+                 BEGIN SELECT RAISE(ABORT, 'boom'); END;",
+            )
+            .unwrap();
+
+        let mut failing_step = sample_step_input(None, None);
+        failing_step.type_ = "boom".to_string();
+
+        let result = db.save_steps_with_path(
+            &recording_id,
+            "Recording",
+            vec![
+                sample_step_input(Some(temp_file.to_string_lossy().to_string()), Some(false)),
+                failing_step,
+            ],
+            Some(custom_root.to_string_lossy().as_ref()),
+        );
+
+        assert!(result.is_err());
+
+        let step_count: i64 = db
+            .conn
+            .query_row(
+                "SELECT COUNT(*) FROM steps WHERE recording_id = ?1",
+                params![recording_id],
+                |row| row.get(0),
+            )
+            .unwrap();
+        let copied_files = fs::read_dir(custom_root.join("Recording")).unwrap().count();
+
+        assert_eq!(step_count, 0);
+        assert_eq!(copied_files, 0);
+        assert!(temp_file.exists());
+    }
+
+    #[test]
+    fn identical_screenshots_share_one_object_until_last_reference_goes() {
+        let test_dir = TestDir::new();
+        let db = Database::new(test_dir.path().to_path_buf()).unwrap();
+        let recording_id = db.create_recording("Recording".to_string()).unwrap();
+        let first = test_dir.path().join("first.jpg");
+        let second = test_dir.path().join("second.jpg");
+        fs::write(&first, b"same-frame").unwrap();
+        fs::write(&second, b"same-frame").unwrap();
+
+        db.save_steps(
+            &recording_id,
+            vec![
+                sample_step_input(Some(first.to_string_lossy().to_string()), None),
+                sample_step_input(Some(second.to_string_lossy().to_string()), None),
+            ],
+        )
+        .unwrap();
+
+        let steps = db.get_recording(&recording_id).unwrap().unwrap().steps;
+        let object_path = steps[0].screenshot_path.clone().unwrap();
+        assert_eq!(
+            steps[1].screenshot_path.as_deref(),
+            Some(object_path.as_str())
+        );
+        assert!(object_path.starts_with(db.objects_dir().to_string_lossy().as_ref()));
+        assert!(!first.exists() && !second.exists());
+
+        db.delete_step(&steps[0].id).unwrap();
+        assert!(Path::new(&object_path).exists());
+
+        db.delete_step(&steps[1].id).unwrap();
+        assert!(!Path::new(&object_path).exists());
+        assert!(!db.is_screenshot_object(&object_path).unwrap());
+    }
+
+    #[test]
+    fn element_screenshot_is_stored_and_released_with_its_step() {
+        let test_dir = TestDir::new();
+        let db = Database::new(test_dir.path().to_path_buf()).unwrap();
+        let recording_id = db.create_recording("Recording".to_string()).unwrap();
+        let frame = test_dir.path().join("frame.jpg");
+        let crop = test_dir.path().join("frame_element.jpg");
+        fs::write(&frame, b"full-frame").unwrap();
+        fs::write(&crop, b"button-crop").unwrap();
+
+        let mut step = sample_step_input(Some(frame.to_string_lossy().to_string()), None);
+        step.element_screenshot = Some(crop.to_string_lossy().to_string());
+        db.save_steps(&recording_id, vec![step]).unwrap();
+
+        let steps = db.get_recording(&recording_id).unwrap().unwrap().steps;
+        let crop_path = steps[0].element_screenshot_path.clone().unwrap();
+        assert_ne!(
+            steps[0].screenshot_path.as_deref(),
+            Some(crop_path.as_str())
+        );
+        assert_eq!(fs::read(&crop_path).unwrap(), b"button-crop");
+        assert!(!crop.exists());
+
+        db.delete_step(&steps[0].id).unwrap();
+        assert!(!Path::new(&crop_path).exists());
+    }
+
+    #[test]
+    fn get_storage_usage_counts_shared_objects_once_in_total() {
+        let test_dir = TestDir::new();
+        let db = Database::new(test_dir.path().to_path_buf()).unwrap();
+        let first_id = db.create_recording("First".to_string()).unwrap();
+        let second_id = db.create_recording("Second".to_string()).unwrap();
+        let shared_a = test_dir.path().join("shared-a.jpg");
+        let shared_b = test_dir.path().join("shared-b.jpg");
+        let plain = test_dir.path().join("plain.jpg");
+        fs::write(&shared_a, b"same-frame").unwrap();
+        fs::write(&shared_b, b"same-frame").unwrap();
+        fs::write(&plain, b"plain-screenshot").unwrap();
+
+        db.save_steps(
+            &first_id,
+            vec![sample_step_input(
+                Some(shared_a.to_string_lossy().to_string()),
+                None,
+            )],
+        )
+        .unwrap();
+        db.save_steps_with_path(
+            &second_id,
+            "Second",
+            vec![
+                sample_step_input(Some(shared_b.to_string_lossy().to_string()), None),
+                sample_step_input(Some(plain.to_string_lossy().to_string()), Some(true)),
+            ],
+            None,
+        )
+        .unwrap();
+
+        let usage = db.get_storage_usage(false).unwrap();
+
+        let ids: Vec<&str> = usage
+            .recordings
+            .iter()
+            .map(|u| u.recording_id.as_str())
+            .collect();
+        assert_eq!(ids, vec![second_id.as_str(), first_id.as_str()]);
+        assert_eq!(usage.recordings[0].file_count, 2);
+        assert_eq!(usage.recordings[0].bytes, 26);
+        assert_eq!(usage.recordings[0].shared_bytes, 10);
+        assert_eq!(usage.total_bytes, 26);
+
+        // Cached until the recording changes or a refresh is forced.
+        fs::remove_file(&plain).unwrap();
+        assert_eq!(db.get_storage_usage(false).unwrap().total_bytes, 26);
+        assert_eq!(db.get_storage_usage(true).unwrap().total_bytes, 10);
+    }
+
+    #[test]
+    fn rename_recordings_numbers_in_order_and_rolls_back_on_failure() {
+        let test_dir = TestDir::new();
+        let db = Database::new(test_dir.path().to_path_buf()).unwrap();
+        let first = db.create_recording("Login".to_string()).unwrap();
+        let second = db.create_recording("Logout".to_string()).unwrap();
+
+        let renamed = db
+            .rename_recordings(
+                &[second.clone(), first.clone()],
+                "Onboarding {n} \u{2013} {name}",
+                1,
+                2,
+            )
+            .unwrap();
+
+        assert_eq!(renamed[0].name, "Onboarding 01 \u{2013} Logout");
+        assert_eq!(
+            db.get_recording(&first).unwrap().unwrap().recording.name,
+            "Onboarding 02 \u{2013} Login"
+        );
+
+        db.set_recording_locked(&second, true).unwrap();
+        let result = db.rename_recordings(&[first.clone(), second], "Guide {n}", 1, 0);
+        assert!(result.is_err());
+        assert_eq!(
+            db.get_recording(&first).unwrap().unwrap().recording.name,
+            "Onboarding 02 \u{2013} Login"
+        );
+    }
+
+    #[test]
+    fn name_pattern_keeps_braces_in_the_old_name_literal() {
+        // 2024-01-02 UTC
+        let created_at = 1_704_153_600_000;
+        assert_eq!(
+            apply_name_pattern("{n} {name} ({date})", 3, 2, "Setup {date} {n}", created_at),
+            "03 Setup {date} {n} (2024-01-02)"
+        );
+        assert_eq!(
+            apply_name_pattern("{x} {name", 1, 0, "Guide", created_at),
+            "{x} {name"
+        );
+    }
+
+    #[test]
+    fn element_bounds_round_trip_through_steps() {
+        let test_dir = TestDir::new();
+        let db = Database::new(test_dir.path().to_path_buf()).unwrap();
+        let recording_id = db.create_recording("Recording".to_string()).unwrap();
+        let bounds = ElementBounds {
+            x: 40,
+            y: 60,
+            width: 120,
+            height: 32,
+        };
+        let mut step = sample_step_input(None, None);
+        step.element_bounds = Some(bounds);
+
+        db.save_steps(&recording_id, vec![step, sample_step_input(None, None)])
+            .unwrap();
+
+        let steps = db.get_recording(&recording_id).unwrap().unwrap().steps;
+        assert_eq!(steps[0].element_bounds, Some(bounds));
+        assert_eq!(steps[1].element_bounds, None);
+    }
+
+    #[test]
+    fn selector_columns_survive_step_copies() {
+        let test_dir = TestDir::new();
+        let db = Database::new(test_dir.path().to_path_buf()).unwrap();
+        let source_id = db.create_recording("Source".to_string()).unwrap();
+        let target_id = db.create_recording("Target".to_string()).unwrap();
+        let path = vec![ElementPathSegment {
+            role: "window".to_string(),
+            name: Some("Sign in".to_string()),
+            ..Default::default()
+        }];
+        let mut step = sample_step_input(None, None);
+        step.automation_id = Some("LoginButton".to_string());
+        step.class_name = Some("Button".to_string());
+        step.element_path = Some(path.clone());
+        db.save_steps(&source_id, vec![step]).unwrap();
+        let step_id = db.get_recording(&source_id).unwrap().unwrap().steps[0]
+            .id
+            .clone();
+
+        db.transfer_steps(&source_id, &target_id, &[step_id], 0, true)
+            .unwrap();
+
+        let copied = &db.get_recording(&target_id).unwrap().unwrap().steps[0];
+        assert_eq!(copied.automation_id.as_deref(), Some("LoginButton"));
+        assert_eq!(copied.class_name.as_deref(), Some("Button"));
+        assert_eq!(copied.element_path.as_ref(), Some(&path));
+    }
+
+    #[test]
+    fn foreground_process_columns_round_trip_through_steps() {
+        let test_dir = TestDir::new();
+        let db = Database::new(test_dir.path().to_path_buf()).unwrap();
+        let recording_id = db.create_recording("Recording".to_string()).unwrap();
+        let mut step = sample_step_input(None, None);
+        step.process_name = Some("EXCEL.EXE".to_string());
+        step.exe_path = Some(r"C:\Program Files\Microsoft Office\EXCEL.EXE".to_string());
+        step.window_title = Some("Budget.xlsx - Excel".to_string());
+        db.save_steps(&recording_id, vec![step]).unwrap();
+
+        let saved = &db.get_recording(&recording_id).unwrap().unwrap().steps[0];
+        assert_eq!(saved.process_name.as_deref(), Some("EXCEL.EXE"));
+        assert_eq!(
+            saved.exe_path.as_deref(),
+            Some(r"C:\Program Files\Microsoft Office\EXCEL.EXE")
+        );
+        assert_eq!(saved.window_title.as_deref(), Some("Budget.xlsx - Excel"));
+    }
+
+    #[test]
+    fn save_steps_keeps_recorded_ocr_text_and_defaults_status_to_pending() {
+        let test_dir = TestDir::new();
+        let db = Database::new(test_dir.path().to_path_buf()).unwrap();
+        let recording_id = db.create_recording("Recording".to_string()).unwrap();
+        let mut labelled = sample_step_input(None, None);
+        labelled.ocr_text = Some("Save\nCancel".to_string());
+        labelled.ocr_status = Some("accessibility".to_string());
+        db.save_steps(&recording_id, vec![labelled, sample_step_input(None, None)])
+            .unwrap();
+
+        let steps = db.get_recording(&recording_id).unwrap().unwrap().steps;
+        assert_eq!(steps[0].ocr_text.as_deref(), Some("Save\nCancel"));
+        assert_eq!(steps[0].ocr_status.as_deref(), Some("accessibility"));
+        assert_eq!(steps[1].ocr_text, None);
+        assert_eq!(steps[1].ocr_status.as_deref(), Some("pending"));
+    }
+
+    #[test]
+    fn ocr_line_confidence_is_stored_with_the_text() {
+        use crate::ocr::ConfidenceBucket;
+
+        let line = |text: &str, confidence| OcrLine {
+            text: text.to_string(),
+            confidence,
+        };
+        let test_dir = TestDir::new();
+        let db = Database::new(test_dir.path().to_path_buf()).unwrap();
+        let recording_id = db.create_recording("Recording".to_string()).unwrap();
+        let mut recognised = sample_step_input(None, None);
+        recognised.ocr_text = Some("Invoice".to_string());
+        recognised.ocr_lines = Some(vec![line("Invoice", ConfidenceBucket::High)]);
+        db.save_steps(&recording_id, vec![recognised]).unwrap();
+
+        let step = &db.get_recording(&recording_id).unwrap().unwrap().steps[0];
+        assert_eq!(
+            step.ocr_lines,
+            Some(vec![line("Invoice", ConfidenceBucket::High)])
+        );
+
+        let lines = [
+            line("Total", ConfidenceBucket::High),
+            line("$1O4.2", ConfidenceBucket::Low),
+        ];
+        db.update_step_ocr(&step.id, Some("Total\n$1O4.2"), Some(&lines), "completed")
+            .unwrap();
+        assert_eq!(
+            db.get_step(&step.id).unwrap().unwrap().ocr_lines.as_deref(),
+            Some(&lines[..])
+        );
+    }
+
+    #[test]
+    fn annotations_are_stored_per_step_and_copied_with_it() {
+        use crate::annotations::{Point, Shape};
+
+        let test_dir = TestDir::new();
+        let db = Database::new(test_dir.path().to_path_buf()).unwrap();
+        let recording_id = db.create_recording("Recording".to_string()).unwrap();
+        db.save_steps(&recording_id, vec![sample_step_input(None, None)])
+            .unwrap();
+        let step_id = db.get_recording(&recording_id).unwrap().unwrap().steps[0]
+            .id
+            .clone();
+        assert_eq!(db.get_step(&step_id).unwrap().unwrap().annotations, None);
+
+        let arrow = Annotation {
+            shape: Shape::Arrow {
+                from: Point { x: 10.0, y: 10.0 },
+                to: Point { x: 80.0, y: 45.0 },
+            },
+            color: "#FF3B30".to_string(),
+            stroke_width: 4.0,
+        };
+        db.update_step_annotations(&step_id, std::slice::from_ref(&arrow))
+            .unwrap();
+        assert_eq!(
+            db.get_step(&step_id).unwrap().unwrap().annotations,
+            Some(vec![arrow])
+        );
+
+        db.update_step_annotations(&step_id, &[]).unwrap();
+        assert_eq!(db.get_step(&step_id).unwrap().unwrap().annotations, None);
+    }
+
+    #[test]
+    fn click_markers_are_saved_moved_and_removed() {
+        let test_dir = TestDir::new();
+        let db = Database::new(test_dir.path().to_path_buf()).unwrap();
+        let recording_id = db.create_recording("Recording".to_string()).unwrap();
+        let mut input = sample_step_input(None, None);
+        input.click_marker = Some(Point { x: 120.0, y: 48.0 });
+        input.scale_factor = Some(1.5);
+        input.is_duplicate = Some(true);
+        db.save_steps(&recording_id, vec![input]).unwrap();
+        let step = db.get_recording(&recording_id).unwrap().unwrap().steps[0].clone();
+        assert_eq!(step.click_marker, Some(Point { x: 120.0, y: 48.0 }));
+        assert_eq!(step.scale_factor, Some(1.5));
+        assert_eq!(step.is_duplicate, Some(true));
+
+        let moved = Point { x: 64.5, y: 30.0 };
+        db.update_step_click_marker(&step.id, Some(moved)).unwrap();
+        assert_eq!(
+            db.get_step(&step.id).unwrap().unwrap().click_marker,
+            Some(moved)
+        );
+
+        db.update_step_click_marker(&step.id, None).unwrap();
+        assert_eq!(db.get_step(&step.id).unwrap().unwrap().click_marker, None);
+    }
+
+    #[test]
+    fn burning_in_redactions_swaps_images_and_clears_pending_areas() {
+        use crate::annotations::{Rect, RedactionStyle};
+
+        let test_dir = TestDir::new();
+        let db = Database::new(test_dir.path().to_path_buf()).unwrap();
+        let recording_id = db.create_recording("Recording".to_string()).unwrap();
+        let original = test_dir.path().join("original.jpg");
+        fs::write(&original, b"original").unwrap();
+        db.save_steps(
+            &recording_id,
+            vec![sample_step_input(
+                Some(original.to_string_lossy().to_string()),
+                None,
+            )],
+        )
+        .unwrap();
+        let step = db.get_recording(&recording_id).unwrap().unwrap().steps[0].clone();
+
+        let redaction = Redaction {
+            rect: Rect {
+                x: 0.0,
+                y: 0.0,
+                width: 40.0,
+                height: 12.0,
+            },
+            style: RedactionStyle::Blur,
+        };
+        db.update_step_redactions(&step.id, &[redaction]).unwrap();
+        assert_eq!(
+            db.get_step(&step.id).unwrap().unwrap().redactions,
+            Some(vec![redaction])
+        );
+
+        let redacted = test_dir.path().join("redacted.jpg");
+        fs::write(&redacted, b"redacted").unwrap();
+        let redacted = redacted.to_string_lossy().to_string();
+        let stored = step.screenshot_path.clone().unwrap();
+        let orphaned = db
+            .replace_step_images_redacted(&step.id, Some(&redacted), None, None, None)
+            .unwrap();
+
+        let updated = db.get_step(&step.id).unwrap().unwrap();
+        assert_eq!(updated.screenshot_path.as_deref(), Some(redacted.as_str()));
+        assert_eq!(updated.redactions, None);
+        assert!(updated.redactions_reviewed);
+        assert_eq!(orphaned, vec![PathBuf::from(stored)]);
+    }
+
+    #[test]
+    fn dismissing_redaction_suggestions_marks_steps_reviewed() {
+        let test_dir = TestDir::new();
+        let db = Database::new(test_dir.path().to_path_buf()).unwrap();
+        let recording_id = db.create_recording("Recording".to_string()).unwrap();
+        db.save_steps(
+            &recording_id,
+            vec![sample_step_input(None, None), sample_step_input(None, None)],
+        )
+        .unwrap();
+        let steps = db.get_recording(&recording_id).unwrap().unwrap().steps;
+        assert!(!steps[0].redactions_reviewed);
+
+        db.dismiss_redaction_suggestions(std::slice::from_ref(&steps[0].id))
+            .unwrap();
+
+        assert!(
+            db.get_step(&steps[0].id)
+                .unwrap()
+                .unwrap()
+                .redactions_reviewed
+        );
+        assert!(
+            !db.get_step(&steps[1].id)
+                .unwrap()
+                .unwrap()
+                .redactions_reviewed
+        );
+    }
+
+    #[test]
+    fn recording_ocr_status_counts_screenshot_steps_by_status() {
+        let test_dir = TestDir::new();
+        let db = Database::new(test_dir.path().to_path_buf()).unwrap();
+        let recording_id = db.create_recording("Recording".to_string()).unwrap();
+        let mut steps = Vec::new();
+        for name in ["first.jpg", "second.jpg"] {
+            let screenshot = test_dir.path().join(name);
+            fs::write(&screenshot, name).unwrap();
+            steps.push(sample_step_input(
+                Some(screenshot.to_string_lossy().to_string()),
+                None,
+            ));
+        }
+        // No screenshot, so nothing to read
+        steps.push(sample_step_input(None, None));
+        db.save_steps(&recording_id, steps).unwrap();
+
+        let status = db.recording_ocr_status(&recording_id).unwrap();
+        assert_eq!((status.total, status.pending), (2, 2));
+        assert!(!status.finished);
+
+        let steps = db.get_recording(&recording_id).unwrap().unwrap().steps;
+        db.update_step_ocr(&steps[0].id, Some("Save"), None, "completed")
+            .unwrap();
+        db.update_step_ocr(&steps[1].id, None, None, "failed")
+            .unwrap();
+        assert_eq!(
+            db.recording_ocr_status(&recording_id).unwrap(),
+            RecordingOcrStatus {
+                total: 2,
+                completed: 1,
+                failed: 1,
+                finished: true,
+                ..Default::default()
+            }
+        );
+    }
+
+    #[test]
+    fn undescribed_steps_get_a_generated_description_until_the_user_edits_it() {
+        let test_dir = TestDir::new();
+        let db = Database::new(test_dir.path().to_path_buf()).unwrap();
+        let recording_id = db.create_recording("Recording".to_string()).unwrap();
+        let mut click = sample_step_input(None, None);
+        click.type_ = "click".to_string();
+        click.description = None;
+        db.save_steps(&recording_id, vec![click]).unwrap();
+
+        // Nothing to go on until OCR reads the label
+        let step_id = db.get_recording(&recording_id).unwrap().unwrap().steps[0]
+            .id
+            .clone();
+        let description = db
+            .update_step_ocr(
+                &step_id,
+                Some("Billing\nSave changes\nCancel"),
+                None,
+                "completed",
+            )
+            .unwrap();
+        assert_eq!(description.as_deref(), Some("Click \"Save changes\"."));
+        assert!(
+            db.get_step(&step_id)
+                .unwrap()
+                .unwrap()
+                .description_generated
+        );
+
+        db.update_step_description(&step_id, "Save the invoice")
+            .unwrap();
+        let description = db
+            .update_step_ocr(&step_id, Some("Save"), None, "completed")
+            .unwrap();
+        assert_eq!(description.as_deref(), Some("Save the invoice"));
+        assert!(
+            !db.get_step(&step_id)
+                .unwrap()
+                .unwrap()
+                .description_generated
+        );
+    }
+
+    #[test]
+    fn ocr_text_is_searchable_once_recognised() {
+        let test_dir = TestDir::new();
+        let db = Database::new(test_dir.path().to_path_buf()).unwrap();
+        let recording_id = db.create_recording("Billing run".to_string()).unwrap();
+        db.create_recording("Unrelated".to_string()).unwrap();
+        db.save_steps(&recording_id, vec![sample_step_input(None, None)])
+            .unwrap();
+        let step_id = db.get_recording(&recording_id).unwrap().unwrap().steps[0]
+            .id
+            .clone();
+
+        assert!(db
+            .search_step_text("invoice number", 10)
+            .unwrap()
+            .is_empty());
+
+        db.update_step_ocr(
+            &step_id,
+            Some("Customer\nInvoice Number: 1042"),
+            None,
+            "completed",
+        )
+        .unwrap();
+        let matches = db.search_step_text("invoice num", 10).unwrap();
+        assert_eq!(matches.len(), 1);
+        assert_eq!(matches[0].step_id, step_id);
+        assert_eq!(matches[0].recording_name, "Billing run");
+
+        let listed = db
+            .list_recordings_paginated(1, 10, Some("\"Invoice\" number"))
+            .unwrap();
+        assert_eq!(listed.total_count, 1);
+        assert_eq!(listed.recordings[0].id, recording_id);
+
+        db.delete_step(&step_id).unwrap();
+        assert!(db.search_step_text("invoice", 10).unwrap().is_empty());
+    }
+
+    #[test]
+    fn recover_drafts_saves_journaled_steps_as_recording() {
+        let test_dir = TestDir::new();
+        let db = Database::new(test_dir.path().to_path_buf()).unwrap();
+        db.save_draft_step(
+            "draft-1",
+            r#"{"id":"draft-1","type_":"click","x":10,"y":20,"text":null,"timestamp":1700000000000,"screenshot":null}"#,
+        )
+        .unwrap();
+        db.save_draft_step(
+            "draft-2",
+            r#"{"id":"draft-2","type_":"type","text":"hello","timestamp":1700000001000}"#,
+        )
+        .unwrap();
+
+        let recovered = db.recover_drafts().unwrap().unwrap();
+
+        let recording = db.get_recording(&recovered.recording_id).unwrap().unwrap();
+        let types: Vec<&str> = recording.steps.iter().map(|s| s.type_.as_str()).collect();
+        assert_eq!(recovered.step_count, 2);
+        assert_eq!(types, vec!["click", "type"]);
+        assert!(recording.recording.name.starts_with("Recovered recording"));
+        assert!(db.recover_drafts().unwrap().is_none());
+    }
+
+    #[test]
+    fn recover_drafts_keeps_rows_it_cannot_read() {
+        let test_dir = TestDir::new();
+        let db = Database::new(test_dir.path().to_path_buf()).unwrap();
+        db.save_draft_step(
+            "draft-1",
+            r#"{"id":"draft-1","type_":"click","timestamp":1700000000000}"#,
+        )
+        .unwrap();
+        db.save_draft_step("draft-2", "{not json").unwrap();
+
+        let recovered = db.recover_drafts().unwrap().unwrap();
+        assert_eq!(recovered.step_count, 1);
+
+        let left: Vec<String> = db
+            .conn
+            .prepare("SELECT id FROM draft_steps")
+            .unwrap()
+            .query_map([], |row| row.get(0))
+            .unwrap()
+            .collect::<Result<_>>()
+            .unwrap();
+        assert_eq!(left, vec!["draft-2".to_string()]);
+        assert!(db.recover_drafts().unwrap().is_none());
+    }
+
+    #[test]
+    fn archive_recording_swaps_screenshots_and_marks_recording() {
+        let test_dir = TestDir::new();
+        let db = Database::new(test_dir.path().to_path_buf()).unwrap();
+        let recording_id = db.create_recording("Recording".to_string()).unwrap();
+        let original = test_dir.path().join("original.jpg");
+        let archived = test_dir.path().join("archived.jpg");
+        fs::write(&original, b"full-quality").unwrap();
+        fs::write(&archived, b"archived").unwrap();
+        db.save_steps(
+            &recording_id,
+            vec![sample_step_input(
+                Some(original.to_string_lossy().to_string()),
+                None,
+            )],
+        )
+        .unwrap();
+        let step = db
+            .get_recording(&recording_id)
+            .unwrap()
+            .unwrap()
+            .steps
+            .remove(0);
+        let object_path = step.screenshot_path.clone().unwrap();
+
+        let orphaned = db
+            .archive_recording(
+                &recording_id,
+                &[(step.id.clone(), archived.to_string_lossy().to_string())],
+            )
+            .unwrap();
+
+        let recording = db.get_recording(&recording_id).unwrap().unwrap();
+        assert_eq!(orphaned, vec![PathBuf::from(&object_path)]);
+        assert!(recording.recording.archived_at.is_some());
+        assert_eq!(
+            recording.steps[0].screenshot_path.as_deref(),
+            Some(archived.to_string_lossy().as_ref())
+        );
+
+        db.unarchive_recording(&recording_id).unwrap();
+        let recording = db.get_recording(&recording_id).unwrap().unwrap();
+        assert!(recording.recording.archived_at.is_none());
     }
 
-    pub fn get_unread_notification_count(&self) -> Result<i64> {
-        let count: i64 = self.conn.query_row(
-            "SELECT COUNT(*) FROM notifications WHERE is_read = 0",
-            [],
-            |row| row.get(0),
-        )?;
-        Ok(count)
-    }
+    #[test]
+    fn replacing_step_images_moves_references_and_scales_resized_steps() {
+        let test_dir = TestDir::new();
+        let db = Database::new(test_dir.path().to_path_buf()).unwrap();
+        let recording_id = db.create_recording("Recording".to_string()).unwrap();
+        let original = test_dir.path().join("original.jpg");
+        fs::write(&original, b"full-quality").unwrap();
+        let mut input = sample_step_input(Some(original.to_string_lossy().to_string()), None);
+        input.x = Some(100);
+        input.y = Some(40);
+        input.click_marker = Some(Point { x: 120.0, y: 48.0 });
+        input.scale_factor = Some(1.5);
+        db.save_steps(&recording_id, vec![input]).unwrap();
+        db.get_storage_usage(false).unwrap();
+
+        let images = db.step_images(&recording_id).unwrap();
+        assert_eq!(images.len(), 1);
+        let (step_id, image, object_path) = images[0].clone();
+        assert_eq!(image, StepImage::Screenshot);
+
+        let smaller = db.store_screenshot_object(b"smaller", "jpg").unwrap();
+        let smaller = smaller.to_string_lossy().to_string();
+        let orphaned = db
+            .replace_step_images(
+                &recording_id,
+                &[ImageReplacement {
+                    step_id: step_id.clone(),
+                    image: StepImage::Screenshot,
+                    path: smaller.clone(),
+                    scale: 0.5,
+                }],
+            )
+            .unwrap();
 
-    pub fn mark_notification_read(&self, id: &str) -> Result<()> {
-        self.conn.execute(
-            "UPDATE notifications SET is_read = 1 WHERE id = ?1",
-            params![id],
-        )?;
-        Ok(())
+        assert_eq!(orphaned, vec![PathBuf::from(&object_path)]);
+        assert!(!db.is_screenshot_object(&object_path).unwrap());
+        let step = db.get_step(&step_id).unwrap().unwrap();
+        assert_eq!(step.screenshot_path, Some(smaller));
+        assert_eq!((step.x, step.y), (Some(50), Some(20)));
+        assert_eq!(step.click_marker, Some(Point { x: 60.0, y: 24.0 }));
+        assert_eq!(step.scale_factor, Some(0.75));
+        let cached: i64 = db
+            .conn
+            .query_row(
+                "SELECT COUNT(*) FROM storage_usage WHERE recording_id = ?1",
+                params![recording_id],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert_eq!(cached, 0);
     }
 
-    pub fn mark_all_notifications_read(&self) -> Result<()> {
-        self.conn
-            .execute("UPDATE notifications SET is_read = 1 WHERE is_read = 0", [])?;
-        Ok(())
-    }
+    #[test]
+    fn rotating_a_screenshot_moves_the_click_along() {
+        let test_dir = TestDir::new();
+        let db = Database::new(test_dir.path().to_path_buf()).unwrap();
+        let recording_id = db.create_recording("Recording".to_string()).unwrap();
+        let original = test_dir.path().join("original.jpg");
+        fs::write(&original, b"landscape").unwrap();
+        let mut input = sample_step_input(Some(original.to_string_lossy().to_string()), None);
+        input.x = Some(100);
+        input.y = Some(40);
+        input.click_marker = Some(Point { x: 120.0, y: 48.0 });
+        input.scale_factor = Some(2.0);
+        db.save_steps(&recording_id, vec![input]).unwrap();
+        let step = db.get_recording(&recording_id).unwrap().unwrap().steps[0].clone();
+        let object_path = step.screenshot_path.clone().unwrap();
+
+        let rotated = db.store_screenshot_object(b"portrait", "jpg").unwrap();
+        let rotated = rotated.to_string_lossy().to_string();
+        let orphaned = db
+            .replace_step_image_edited(
+                &step.id,
+                StepImage::Screenshot,
+                &rotated,
+                None,
+                ImageEdit::RotateClockwise,
+                (200, 100),
+            )
+            .unwrap();
 
-    pub fn delete_notification(&self, id: &str) -> Result<()> {
-        self.conn
-            .execute("DELETE FROM notifications WHERE id = ?1", params![id])?;
-        Ok(())
+        assert_eq!(orphaned, vec![PathBuf::from(&object_path)]);
+        let step = db.get_step(&step.id).unwrap().unwrap();
+        assert_eq!(step.screenshot_path, Some(rotated));
+        assert_eq!((step.x, step.y), (Some(60), Some(100)));
+        assert_eq!(step.click_marker, Some(Point { x: 52.0, y: 120.0 }));
+        assert_eq!(step.scale_factor, Some(2.0));
     }
 
-    pub fn delete_all_notifications(&self) -> Result<()> {
-        self.conn.execute("DELETE FROM notifications", [])?;
-        Ok(())
+    #[test]
+    fn lossless_originals_over_the_quota_are_dropped_oldest_first() {
+        let test_dir = TestDir::new();
+        let mut db = Database::new(test_dir.path().to_path_buf()).unwrap();
+        let recording_id = db.create_recording("Recording".to_string()).unwrap();
+        let inputs = (0..2)
+            .map(|index| {
+                let screenshot = test_dir.path().join(format!("{}.jpg", index));
+                let original = test_dir.path().join(format!("{}_original.png", index));
+                fs::write(&screenshot, format!("jpeg {}", index)).unwrap();
+                fs::write(&original, format!("lossless {}", index)).unwrap();
+                let mut input =
+                    sample_step_input(Some(screenshot.to_string_lossy().to_string()), None);
+                input.timestamp = index;
+                input.screenshot_original = Some(original.to_string_lossy().to_string());
+                input
+            })
+            .collect();
+        db.save_steps(&recording_id, inputs).unwrap();
+        let steps = db.get_recording(&recording_id).unwrap().unwrap().steps;
+        let older = steps[0].original_path.clone().unwrap();
+        assert!(older.ends_with(".png"));
+        assert!(steps[1].original_path.is_some());
+
+        // Room for one of the two 10-byte originals
+        db.set_original_quota(15);
+        db.enforce_original_quota().unwrap();
+
+        let steps = db.get_recording(&recording_id).unwrap().unwrap().steps;
+        assert_eq!(steps[0].original_path, None);
+        assert!(steps[0].screenshot_path.is_some());
+        assert!(steps[1].original_path.is_some());
+        assert!(!Path::new(&older).exists());
     }
-}
-
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use std::path::Path;
 
-    struct TestDir {
-        path: PathBuf,
-    }
+    #[test]
+    fn locked_recording_rejects_step_and_documentation_edits() {
+        let test_dir = TestDir::new();
+        let db = Database::new(test_dir.path().to_path_buf()).unwrap();
+        let recording_id = db.create_recording("Recording".to_string()).unwrap();
+        db.save_steps(&recording_id, vec![sample_step_input(None, None)])
+            .unwrap();
+        let step_id = db.get_recording(&recording_id).unwrap().unwrap().steps[0]
+            .id
+            .clone();
 
-    impl TestDir {
-        fn new() -> Self {
-            let path = std::env::temp_dir().join(format!("stepsnap_db_test_{}", Uuid::new_v4()));
-            fs::create_dir_all(&path).unwrap();
-            Self { path }
-        }
+        db.set_recording_locked(&recording_id, true).unwrap();
 
-        fn path(&self) -> &Path {
-            &self.path
-        }
-    }
+        let err = db.update_step_description(&step_id, "Edited").unwrap_err();
+        assert!(err.to_string().contains("Recording is locked"));
+        assert!(db.save_documentation(&recording_id, "# Docs").is_err());
+        assert!(db.delete_step(&step_id).is_err());
+        assert!(db.toggle_recording_favorite(&recording_id).is_ok());
 
-    impl Drop for TestDir {
-        fn drop(&mut self) {
-            let _ = fs::remove_dir_all(&self.path);
-        }
-    }
+        // Derived columns are still written
+        db.update_step_ocr(&step_id, Some("Sign in"), None, "completed")
+            .unwrap();
+        db.update_step_after_screenshot(&step_id, Some("/moved/after.jpg"))
+            .unwrap();
+        let step = &db.get_recording(&recording_id).unwrap().unwrap().steps[0];
+        assert_eq!(step.ocr_text.as_deref(), Some("Sign in"));
+        assert_eq!(step.description.as_deref(), Some("desc"));
 
-    fn sample_step_input(
-        screenshot: Option<String>,
-        screenshot_is_permanent: Option<bool>,
-    ) -> StepInput {
-        StepInput {
-            type_: "capture".to_string(),
-            x: None,
-            y: None,
-            text: None,
-            timestamp: 123,
-            screenshot,
-            element_name: None,
-            element_type: None,
-            element_value: None,
-            app_name: None,
-            description: Some("desc".to_string()),
-            is_cropped: Some(false),
-            order_index: Some(0),
-            title: None,
-            screenshot_is_permanent,
-            input_source: None,
-            screenshot_after: None,
-            identified_element_json: None,
-            clip_path: None,
-        }
+        db.set_recording_locked(&recording_id, false).unwrap();
+        db.update_step_description(&step_id, "Edited").unwrap();
     }
 
     #[test]
-    fn save_steps_with_path_copies_temp_screenshots_into_custom_directory() {
+    fn locked_recording_refuses_reencoded_images() {
         let test_dir = TestDir::new();
         let db = Database::new(test_dir.path().to_path_buf()).unwrap();
         let recording_id = db.create_recording("Recording".to_string()).unwrap();
-        let custom_root = test_dir.path().join("custom-root");
-        let temp_file = test_dir.path().join("temp.jpg");
-        fs::write(&temp_file, b"image-bytes").unwrap();
-
-        db.save_steps_with_path(
+        let screenshot = test_dir.path().join("screenshot.jpg");
+        fs::write(&screenshot, b"screenshot").unwrap();
+        db.save_steps(
             &recording_id,
-            "Recording",
             vec![sample_step_input(
-                Some(temp_file.to_string_lossy().to_string()),
-                Some(false),
+                Some(screenshot.to_string_lossy().to_string()),
+                None,
             )],
-            Some(custom_root.to_string_lossy().as_ref()),
         )
         .unwrap();
+        let (step_id, image, path) = db.step_images(&recording_id).unwrap()[0].clone();
+        db.set_recording_locked(&recording_id, true).unwrap();
+        assert!(db.recording_locked(&recording_id).unwrap());
+
+        let reencoded = db.store_screenshot_object(b"reencoded", "jpg").unwrap();
+        let err = db
+            .replace_step_images(
+                &recording_id,
+                &[ImageReplacement {
+                    step_id: step_id.clone(),
+                    image,
+                    path: reencoded.to_string_lossy().to_string(),
+                    scale: 1.0,
+                }],
+            )
+            .unwrap_err();
 
-        let stored_path: String = db
+        assert!(err.to_string().contains("Recording is locked"));
+        let step = db.get_step(&step_id).unwrap().unwrap();
+        assert_eq!(step.screenshot_path, Some(path));
+    }
+
+    #[test]
+    fn recording_image_settings_override_until_cleared() {
+        let test_dir = TestDir::new();
+        let db = Database::new(test_dir.path().to_path_buf()).unwrap();
+        let recording_id = db.create_recording("Recording".to_string()).unwrap();
+        assert_eq!(
+            db.get_recording_image_settings(&recording_id).unwrap(),
+            RecordingImageSettings::default()
+        );
+
+        let settings = RecordingImageSettings {
+            format: Some(ScreenshotFormat::Png),
+            max_width: Some(1280),
+            ..RecordingImageSettings::default()
+        };
+        db.set_recording_image_settings(&recording_id, &settings)
+            .unwrap();
+        let stored = db.get_recording_image_settings(&recording_id).unwrap();
+        assert_eq!(stored, settings);
+        assert!(stored.overrides_encoding());
+
+        db.set_recording_image_settings(&recording_id, &RecordingImageSettings::default())
+            .unwrap();
+        let json: Option<String> = db
             .conn
             .query_row(
-                "SELECT screenshot_path FROM steps WHERE recording_id = ?1",
+                "SELECT image_settings FROM recordings WHERE id = ?1",
                 params![recording_id],
                 |row| row.get(0),
             )
             .unwrap();
+        assert!(json.is_none());
+    }
 
-        assert!(stored_path.starts_with(custom_root.to_string_lossy().as_ref()));
-        assert!(stored_path.contains("Recording"));
-        assert!(!temp_file.exists());
-        assert!(PathBuf::from(stored_path).exists());
+    #[test]
+    fn hotkey_profiles_are_saved_replaced_and_deleted() {
+        let test_dir = TestDir::new();
+        let db = Database::new(test_dir.path().to_path_buf()).unwrap();
+        let mut bindings = crate::hotkeys::default_bindings();
+        db.save_hotkey_profile("Laptop", &bindings).unwrap();
+        bindings.remove(&HotkeyAction::Marker);
+        db.save_hotkey_profile("Desk", &bindings).unwrap();
+
+        let names: Vec<_> = db
+            .list_hotkey_profiles()
+            .unwrap()
+            .into_iter()
+            .map(|profile| profile.name)
+            .collect();
+        assert_eq!(names, vec!["Desk", "Laptop"]);
+
+        // Saving under an existing name replaces its bindings
+        db.save_hotkey_profile("Laptop", &bindings).unwrap();
+        let laptop = db.get_hotkey_profile("Laptop").unwrap().unwrap();
+        assert!(!laptop.bindings.contains_key(&HotkeyAction::Marker));
+        assert_eq!(laptop.bindings, bindings);
+
+        db.delete_hotkey_profile("Desk").unwrap();
+        assert!(db.get_hotkey_profile("Desk").unwrap().is_none());
+        assert_eq!(db.list_hotkey_profiles().unwrap().len(), 1);
     }
 
     #[test]
-    fn save_steps_with_path_preserves_existing_permanent_screenshot_paths() {
+    fn usage_counts_accumulate_per_day_and_old_data_is_pruned() {
+        let test_dir = TestDir::new();
+        let db = Database::new(test_dir.path().to_path_buf()).unwrap();
+        let count = |day: &str, count| UsageCount {
+            day: day.to_string(),
+            feature: "recording_started".to_string(),
+            count,
+        };
+        let sample = |recorded_at| MetricSample {
+            metric: "screenshot_capture".to_string(),
+            value_ms: 12.5,
+            recorded_at,
+        };
+        db.record_usage(
+            &[count("2026-01-01", 2), count("2026-01-02", 1)],
+            &[sample(1_000)],
+        )
+        .unwrap();
+        db.record_usage(&[count("2026-01-02", 3)], &[sample(5_000)])
+            .unwrap();
+
+        assert_eq!(
+            db.usage_counts_since("2026-01-01").unwrap(),
+            vec![count("2026-01-01", 2), count("2026-01-02", 4)]
+        );
+
+        db.prune_usage_data("2026-01-02", 2_000).unwrap();
+        assert_eq!(
+            db.usage_counts_since("2000-01-01").unwrap(),
+            vec![count("2026-01-02", 4)]
+        );
+        assert_eq!(db.metric_samples_since(0).unwrap(), vec![sample(5_000)]);
+
+        db.clear_usage_data().unwrap();
+        assert!(db.usage_counts_since("2000-01-01").unwrap().is_empty());
+        assert!(db.metric_samples_since(0).unwrap().is_empty());
+    }
+
+    #[test]
+    fn audit_log_records_edits_and_is_append_only() {
         let test_dir = TestDir::new();
         let db = Database::new(test_dir.path().to_path_buf()).unwrap();
         let recording_id = db.create_recording("Recording".to_string()).unwrap();
-        let permanent_file = test_dir.path().join("already-there.jpg");
-        fs::write(&permanent_file, b"image-bytes").unwrap();
+        db.save_steps(&recording_id, vec![sample_step_input(None, None)])
+            .unwrap();
+        let step_id = db.get_recording(&recording_id).unwrap().unwrap().steps[0]
+            .id
+            .clone();
+
+        db.update_step_description(&step_id, "Click Save").unwrap();
+        db.delete_step(&step_id).unwrap();
+
+        let actions: Vec<String> = db
+            .list_audit_log(Some(&recording_id), 10, 0)
+            .unwrap()
+            .into_iter()
+            .map(|entry| entry.action)
+            .collect();
+        assert_eq!(
+            actions,
+            vec![
+                "step_deleted",
+                "step_description_edited",
+                "steps_added",
+                "recording_created"
+            ]
+        );
+        assert!(db.conn.execute("DELETE FROM audit_log", []).is_err());
+    }
 
-        db.save_steps_with_path(
+    #[test]
+    fn transfer_steps_moves_and_copies_at_position() {
+        let test_dir = TestDir::new();
+        let db = Database::new(test_dir.path().to_path_buf()).unwrap();
+        let source = db.create_recording("Source".to_string()).unwrap();
+        let target = db.create_recording("Target".to_string()).unwrap();
+        db.save_steps(
+            &source,
+            vec![sample_step_input(None, None), sample_step_input(None, None)],
+        )
+        .unwrap();
+        db.save_steps(
+            &target,
+            vec![sample_step_input(None, None), sample_step_input(None, None)],
+        )
+        .unwrap();
+        let step_ids = |id: &str| -> Vec<String> {
+            db.get_recording(id)
+                .unwrap()
+                .unwrap()
+                .steps
+                .into_iter()
+                .map(|step| step.id)
+                .collect()
+        };
+        let source_steps = step_ids(&source);
+        let target_steps = step_ids(&target);
+
+        let moved = db
+            .transfer_steps(&source, &target, &source_steps[..1], 1, false)
+            .unwrap();
+        assert_eq!(moved, vec![source_steps[0].clone()]);
+        assert_eq!(step_ids(&source), vec![source_steps[1].clone()]);
+        assert_eq!(
+            step_ids(&target),
+            vec![
+                target_steps[0].clone(),
+                source_steps[0].clone(),
+                target_steps[1].clone()
+            ]
+        );
+
+        let copied = db
+            .transfer_steps(&source, &target, &source_steps[1..], 0, true)
+            .unwrap();
+        assert_ne!(copied[0], source_steps[1]);
+        assert_eq!(step_ids(&source), vec![source_steps[1].clone()]);
+        assert_eq!(step_ids(&target)[0], copied[0]);
+    }
+
+    #[test]
+    fn insert_step_places_step_at_position_and_renumbers() {
+        let test_dir = TestDir::new();
+        let db = Database::new(test_dir.path().to_path_buf()).unwrap();
+        let recording_id = db.create_recording("Recording".to_string()).unwrap();
+        db.save_steps(
             &recording_id,
-            "Recording",
-            vec![sample_step_input(
-                Some(permanent_file.to_string_lossy().to_string()),
-                Some(true),
-            )],
-            None,
+            vec![sample_step_input(None, None), sample_step_input(None, None)],
         )
         .unwrap();
 
-        let stored_path: String = db
-            .conn
-            .query_row(
-                "SELECT screenshot_path FROM steps WHERE recording_id = ?1",
-                params![recording_id],
-                |row| row.get(0),
+        let mut note = sample_step_input(None, None);
+        note.type_ = "note".to_string();
+        let inserted = db
+            .insert_step(&recording_id, "Recording", 1, note, None)
+            .unwrap();
+
+        let steps = db.get_recording(&recording_id).unwrap().unwrap().steps;
+        let order: Vec<(String, i32)> = steps
+            .iter()
+            .map(|step| (step.type_.clone(), step.order_index))
+            .collect();
+
+        assert_eq!(inserted.order_index, 1);
+        assert_eq!(
+            order,
+            vec![
+                ("capture".to_string(), 0),
+                ("note".to_string(), 1),
+                ("capture".to_string(), 2),
+            ]
+        );
+    }
+
+    #[test]
+    fn delete_steps_removes_batch_and_renumbers_remaining() {
+        let test_dir = TestDir::new();
+        let db = Database::new(test_dir.path().to_path_buf()).unwrap();
+        let recording_id = db.create_recording("Recording".to_string()).unwrap();
+        db.save_steps(
+            &recording_id,
+            (0..4).map(|_| sample_step_input(None, None)).collect(),
+        )
+        .unwrap();
+        let ids: Vec<String> = db
+            .get_recording(&recording_id)
+            .unwrap()
+            .unwrap()
+            .steps
+            .into_iter()
+            .map(|step| step.id)
+            .collect();
+
+        let deleted = db
+            .delete_steps(&recording_id, &[ids[0].clone(), ids[2].clone()])
+            .unwrap();
+
+        let remaining: Vec<(String, i32)> = db
+            .get_recording(&recording_id)
+            .unwrap()
+            .unwrap()
+            .steps
+            .into_iter()
+            .map(|step| (step.id, step.order_index))
+            .collect();
+        assert_eq!(deleted, 2);
+        assert_eq!(remaining, vec![(ids[1].clone(), 0), (ids[3].clone(), 1)]);
+    }
+
+    #[test]
+    fn replace_in_step_descriptions_counts_changed_steps() {
+        let test_dir = TestDir::new();
+        let db = Database::new(test_dir.path().to_path_buf()).unwrap();
+        let recording_id = db.create_recording("Recording".to_string()).unwrap();
+        db.save_steps(
+            &recording_id,
+            vec![sample_step_input(None, None), sample_step_input(None, None)],
+        )
+        .unwrap();
+        let steps = db.get_recording(&recording_id).unwrap().unwrap().steps;
+        db.update_step_description(&steps[0].id, "Open Acme Portal, then acme settings")
+            .unwrap();
+        db.update_step_description(&steps[1].id, "Click Save")
+            .unwrap();
+
+        let changed = db
+            .replace_in_step_descriptions(&recording_id, "ACME", "Contoso", false)
+            .unwrap();
+
+        let description = db.get_step(&steps[0].id).unwrap().unwrap().description;
+        assert_eq!(changed, 1);
+        assert_eq!(
+            description.as_deref(),
+            Some("Open Contoso Portal, then Contoso settings")
+        );
+    }
+
+    #[test]
+    fn get_statistics_pins_favorites_to_top_of_recent_list() {
+        let test_dir = TestDir::new();
+        let db = Database::new(test_dir.path().to_path_buf()).unwrap();
+        let older = db.create_recording("Older".to_string()).unwrap();
+        let newer = db.create_recording("Newer".to_string()).unwrap();
+        db.conn
+            .execute(
+                "UPDATE recordings SET updated_at = 1 WHERE id = ?1",
+                params![older],
             )
             .unwrap();
 
-        assert_eq!(stored_path, permanent_file.to_string_lossy().to_string());
-        assert!(permanent_file.exists());
+        assert!(db.toggle_recording_favorite(&older).unwrap());
+
+        let stats = db.get_statistics(5).unwrap();
+        let recent: Vec<&str> = stats.recent.iter().map(|r| r.id.as_str()).collect();
+        assert_eq!(stats.total_recordings, 2);
+        assert_eq!(stats.favorite_count, 1);
+        assert_eq!(recent, vec![older.as_str(), newer.as_str()]);
+
+        assert!(!db.toggle_recording_favorite(&older).unwrap());
     }
 
     #[test]
@@ -1209,6 +6082,39 @@ mod tests {
         assert!(!cleanup.dirs.contains(&cleanup.protected_dir));
     }
 
+    #[test]
+    fn sync_state_replaces_rows_for_the_same_recording_or_bundle() {
+        let test_dir = TestDir::new();
+        let db = Database::new(test_dir.path().to_path_buf()).unwrap();
+        let state = SyncState {
+            recording_id: "local-1".to_string(),
+            remote_key: "bundle-1".to_string(),
+            remote_etag: Some("v1".to_string()),
+            local_updated_at: 10,
+            synced_at: 20,
+            conflict: None,
+        };
+        db.save_sync_state(&state).unwrap();
+        db.set_sync_conflict("local-1", "bundle-1", "Changed on both")
+            .unwrap();
+        assert_eq!(
+            db.list_sync_states().unwrap()[0].conflict.as_deref(),
+            Some("Changed on both")
+        );
+
+        // Pulled again as a new local recording
+        let pulled = SyncState {
+            recording_id: "local-2".to_string(),
+            remote_etag: Some("v2".to_string()),
+            ..state
+        };
+        db.save_sync_state(&pulled).unwrap();
+        assert_eq!(db.list_sync_states().unwrap(), vec![pulled]);
+
+        db.delete_sync_state("local-2").unwrap();
+        assert!(db.list_sync_states().unwrap().is_empty());
+    }
+
     #[test]
     fn sanitize_dirname_public_handles_invalid_names() {
         let sanitized = Database::sanitize_dirname_public("CON");
@@ -0,0 +1,136 @@
+//! A dedicated thread for the database work of commands.
+//!
+//! Synchronous commands run on the main thread, so a command that held the
+//! database lock while a large recording was saved stalled hotkeys and every
+//! other command behind it. Commands are async instead and queue their work
+//! here, awaiting the result; jobs run one at a time in the order they were
+//! queued. The API server, MCP and the export integrations use it too,
+//! awaiting `run` from async code or calling `run_blocking` from the blocking
+//! pool. The recorder and OCR threads, startup, and commands that process
+//! images between short queries on the blocking pool (`on_blocking_pool`,
+//! sync) still lock `DatabaseState` directly; the worker takes the same lock
+//! for each job.
+
+use crate::database::Database;
+use crate::{safe_db_lock, DatabaseState};
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::sync::mpsc;
+use tauri::{AppHandle, Manager};
+use tokio::sync::oneshot;
+
+type Job = Box<dyn FnOnce(&mut Database) + Send>;
+
+const JOB_FAILED: &str = "The database job failed unexpectedly";
+
+pub struct DatabaseWorker(mpsc::Sender<Job>);
+
+impl DatabaseWorker {
+    /// Start the worker on the database managed by `app`
+    pub fn spawn(app: AppHandle) -> Result<Self, String> {
+        Self::spawn_with(move |job| {
+            let state = app.state::<DatabaseState>();
+            match safe_db_lock(&state) {
+                Ok(mut db) => job(&mut db),
+                Err(e) => tracing::error!("Database worker couldn't lock the database: {}", e),
+            }
+        })
+    }
+
+    /// Start the worker, with `execute` handing each job the database
+    fn spawn_with(execute: impl Fn(Job) + Send + 'static) -> Result<Self, String> {
+        let (sender, jobs) = mpsc::channel::<Job>();
+        std::thread::Builder::new()
+            .name("database".to_string())
+            .spawn(move || {
+                for job in jobs {
+                    // A panicking job drops its reply, which fails only the
+                    // command that queued it
+                    if catch_unwind(AssertUnwindSafe(|| execute(job))).is_err() {
+                        tracing::error!("Database job panicked");
+                    }
+                }
+            })
+            .map_err(|e| format!("Failed to start the database worker: {}", e))?;
+        Ok(Self(sender))
+    }
+
+    /// Run `work` on the worker and wait for its result without blocking
+    /// the caller's thread
+    pub async fn run<T: Send + 'static>(
+        &self,
+        work: impl FnOnce(&mut Database) -> Result<T, String> + Send + 'static,
+    ) -> Result<T, String> {
+        self.queue(work)?
+            .await
+            .map_err(|_| JOB_FAILED.to_string())?
+    }
+
+    /// Like `run`, for threads outside the async runtime (such as the
+    /// blocking pool). Panics if called from async code.
+    pub fn run_blocking<T: Send + 'static>(
+        &self,
+        work: impl FnOnce(&mut Database) -> Result<T, String> + Send + 'static,
+    ) -> Result<T, String> {
+        self.queue(work)?
+            .blocking_recv()
+            .map_err(|_| JOB_FAILED.to_string())?
+    }
+
+    fn queue<T: Send + 'static>(
+        &self,
+        work: impl FnOnce(&mut Database) -> Result<T, String> + Send + 'static,
+    ) -> Result<oneshot::Receiver<Result<T, String>>, String> {
+        let (reply, result) = oneshot::channel();
+        self.0
+            .send(Box::new(move |db| {
+                let _ = reply.send(work(db));
+            }))
+            .map_err(|_| "The database worker has stopped".to_string())?;
+        Ok(result)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::{Arc, Mutex};
+
+    #[test]
+    fn runs_jobs_in_order_and_survives_panics() {
+        let dir =
+            std::env::temp_dir().join(format!("stepsnap_worker_test_{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let db = Arc::new(Mutex::new(Database::new(dir.clone()).unwrap()));
+        let worker = {
+            let db = db.clone();
+            DatabaseWorker::spawn_with(move |job| {
+                job(&mut db.lock().unwrap_or_else(|poisoned| poisoned.into_inner()))
+            })
+            .unwrap()
+        };
+
+        tauri::async_runtime::block_on(async {
+            let id = worker
+                .run(|db| {
+                    db.create_recording("First".to_string())
+                        .map_err(|e| e.to_string())
+                })
+                .await
+                .unwrap();
+            let panicked = worker
+                .run(|_| -> Result<(), String> { panic!("job failed") })
+                .await;
+            assert!(panicked.is_err());
+
+            let recording = worker
+                .run(move |db| db.get_recording(&id).map_err(|e| e.to_string()))
+                .await
+                .unwrap();
+            assert_eq!(recording.unwrap().recording.name, "First");
+        });
+        let count = worker.run_blocking(|db| db.list_recordings().map_err(|e| e.to_string()));
+        assert_eq!(count.unwrap().len(), 1);
+        drop(worker);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,164 @@
+//! `openscribe://` links, so wiki pages and chat messages can jump into the app.
+//!
+//! - `openscribe://recording/<id>` opens a recording
+//! - `openscribe://record?name=<name>` starts a recording to be saved as `name`
+//! - `openscribe://import?path=<bundle>` imports a recording bundle
+//!
+//! Links are parsed here and queued for the frontend, which takes them with
+//! `take_deep_links` when it mounts and whenever `deep-link` is emitted, so a
+//! link that launched the app isn't lost before the webview listens. Any web
+//! page can open these links, so the frontend asks before recording or
+//! importing.
+
+use serde::Serialize;
+use std::path::Path;
+use std::sync::Mutex;
+use tauri::{App, AppHandle, Emitter, Manager, Url};
+use tauri_plugin_deep_link::DeepLinkExt;
+
+pub const SCHEME: &str = "openscribe";
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "action", rename_all = "snake_case")]
+pub enum DeepLink {
+    OpenRecording { recording_id: String },
+    StartRecording { name: Option<String> },
+    ImportBundle { path: String },
+}
+
+static PENDING: Mutex<Vec<DeepLink>> = Mutex::new(Vec::new());
+
+/// Register the scheme where installers don't, take the link the app was
+/// launched with, and listen for later ones
+pub fn init(app: &App) {
+    // The Windows and macOS installers register the scheme; Linux desktop
+    // files and dev builds don't
+    #[cfg(any(target_os = "linux", all(debug_assertions, windows)))]
+    if let Err(e) = app.deep_link().register_all() {
+        tracing::warn!("Failed to register the {} scheme: {}", SCHEME, e);
+    }
+    if let Ok(Some(urls)) = app.deep_link().get_current() {
+        for url in urls {
+            queue(&url);
+        }
+    }
+    let handle = app.handle().clone();
+    app.deep_link().on_open_url(move |event| {
+        for url in event.urls() {
+            open(&handle, &url);
+        }
+    });
+}
+
+/// Queue `url` for the frontend and bring the main window forward
+fn open(app: &AppHandle, url: &Url) {
+    if queue(url) {
+        let _ = app.emit("deep-link", ());
+    }
+    if let Some(window) = app.get_webview_window("main") {
+        let _ = window.unminimize();
+        let _ = window.show();
+        let _ = window.set_focus();
+    }
+}
+
+fn queue(url: &Url) -> bool {
+    match parse(url) {
+        Ok(link) => {
+            tracing::info!(?link, "Deep link opened");
+            let mut pending = PENDING.lock().unwrap();
+            // The launch link can arrive both ways on some platforms
+            if !pending.contains(&link) {
+                pending.push(link);
+            }
+            true
+        }
+        Err(e) => {
+            tracing::warn!(url = %url, "Ignored deep link: {}", e);
+            false
+        }
+    }
+}
+
+fn parse(url: &Url) -> Result<DeepLink, String> {
+    if url.scheme() != SCHEME {
+        return Err(format!("Not an {} link", SCHEME));
+    }
+    let query = |name: &str| {
+        url.query_pairs()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value.trim().to_string())
+            .filter(|value| !value.is_empty())
+    };
+    match url.host_str() {
+        Some("recording") => {
+            let recording_id = url
+                .path_segments()
+                .and_then(|mut segments| segments.next())
+                .filter(|id| !id.is_empty())
+                .ok_or("Missing recording id")?;
+            Ok(DeepLink::OpenRecording {
+                recording_id: recording_id.to_string(),
+            })
+        }
+        Some("record") => Ok(DeepLink::StartRecording {
+            name: query("name"),
+        }),
+        Some("import") => {
+            let path = query("path").ok_or("Missing bundle path")?;
+            // Only bundles, so a link can't point the importer at any file
+            let bundle = Path::new(&path);
+            if !bundle.is_absolute()
+                || bundle.extension().and_then(|ext| ext.to_str()) != Some(crate::bundle::EXTENSION)
+            {
+                return Err(format!(
+                    "Not a .{} bundle: {}",
+                    crate::bundle::EXTENSION,
+                    path
+                ));
+            }
+            Ok(DeepLink::ImportBundle { path })
+        }
+        _ => Err("Unknown link".to_string()),
+    }
+}
+
+/// Links opened since the last call, oldest first
+#[tauri::command]
+pub fn take_deep_links() -> Vec<DeepLink> {
+    std::mem::take(&mut *PENDING.lock().unwrap())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn link(url: &str) -> Result<DeepLink, String> {
+        parse(&Url::parse(url).unwrap())
+    }
+
+    #[test]
+    fn parses_supported_links() {
+        assert_eq!(
+            link("openscribe://recording/4f1c2b"),
+            Ok(DeepLink::OpenRecording {
+                recording_id: "4f1c2b".to_string()
+            })
+        );
+        assert_eq!(
+            link("openscribe://record?name=Reset%20a%20password"),
+            Ok(DeepLink::StartRecording {
+                name: Some("Reset a password".to_string())
+            })
+        );
+        assert_eq!(
+            link("openscribe://record"),
+            Ok(DeepLink::StartRecording { name: None })
+        );
+        assert!(link("openscribe://recording/").is_err());
+        assert!(link("openscribe://delete/4f1c2b").is_err());
+        assert!(link("https://recording/4f1c2b").is_err());
+        assert!(link("openscribe://import?path=/etc/passwd").is_err());
+        assert!(link("openscribe://import?path=guide.stepsnap").is_err());
+    }
+}
@@ -0,0 +1,286 @@
+//! Default descriptions for steps the user hasn't described.
+//!
+//! The recorder knows the clicked element's name and role, where it sits
+//! (`breadcrumb`) and, once OCR finishes, the text printed around the click.
+//! `describe_step` turns that into an instruction such as
+//! `Click the "Save changes" button in the Billing section.`, which is stored
+//! in the description column with `description_generated` set so it can be
+//! refreshed when better context arrives and never overwrites user text.
+//! The wording comes from the `i18n` catalog of the current locale.
+
+use crate::database::{Step, StepInput};
+use crate::i18n::{self, fill, Catalog, Locale};
+
+/// Recognised lines longer than this are body text rather than a label.
+const MAX_OCR_LABEL_CHARS: usize = 48;
+
+/// The fields of a step a description is built from.
+pub struct StepFacts<'a> {
+    pub type_: &'a str,
+    pub text: Option<&'a str>,
+    pub element_name: Option<&'a str>,
+    pub element_type: Option<&'a str>,
+    pub action: Option<&'a str>,
+    pub breadcrumb: Option<&'a str>,
+    pub ocr_text: Option<&'a str>,
+}
+
+impl<'a> From<&'a Step> for StepFacts<'a> {
+    fn from(step: &'a Step) -> Self {
+        Self {
+            type_: &step.type_,
+            text: step.text.as_deref(),
+            element_name: step.element_name.as_deref(),
+            element_type: step.element_type.as_deref(),
+            action: step.action.as_deref(),
+            breadcrumb: step.breadcrumb.as_deref(),
+            ocr_text: step.ocr_text.as_deref(),
+        }
+    }
+}
+
+impl<'a> From<&'a StepInput> for StepFacts<'a> {
+    fn from(step: &'a StepInput) -> Self {
+        Self {
+            type_: &step.type_,
+            text: step.text.as_deref(),
+            element_name: step.element_name.as_deref(),
+            element_type: step.element_type.as_deref(),
+            action: step.action.as_deref(),
+            breadcrumb: step.breadcrumb.as_deref(),
+            ocr_text: step.ocr_text.as_deref(),
+        }
+    }
+}
+
+fn non_empty(value: Option<&str>) -> Option<&str> {
+    value.map(str::trim).filter(|value| !value.is_empty())
+}
+
+/// Noun for the clicked control, or `None` for generic containers where
+/// naming the role adds nothing ("the Save pane").
+fn role_noun(element_type: &str, text: &Catalog) -> Option<&'static str> {
+    let role = element_type.strip_prefix("AX").unwrap_or(element_type);
+    let mut words = String::new();
+    for (i, c) in role.chars().enumerate() {
+        if c.is_uppercase() && i > 0 && !words.ends_with(' ') {
+            words.push(' ');
+        }
+        words.extend(c.to_lowercase());
+    }
+    let noun = match words.trim() {
+        "button" | "push button" | "split button" => text.role_button,
+        "link" | "hyperlink" => text.role_link,
+        "edit" | "entry" | "text field" | "text area" | "password text" | "search field" => {
+            text.role_field
+        }
+        "check box" | "checkbox" => text.role_checkbox,
+        "radio button" => text.role_option,
+        "tab" | "tab item" | "page tab" => text.role_tab,
+        "menu item" | "menu bar item" => text.role_menu_item,
+        "combo box" | "pop up button" | "dropdown" => text.role_drop_down,
+        "list item" => text.role_list_item,
+        "tree item" | "tree view item" => text.role_item,
+        "icon" | "image" => text.role_icon,
+        _ => return None,
+    };
+    Some(noun)
+}
+
+/// The recognised line nearest the click. OCR reads a crop centred on the
+/// click point and returns lines top to bottom, so that's the middle one.
+fn ocr_label(ocr_text: &str) -> Option<&str> {
+    let lines: Vec<&str> = ocr_text
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && line.chars().count() <= MAX_OCR_LABEL_CHARS)
+        .collect();
+    lines.get(lines.len() / 2).copied()
+}
+
+/// " in the Network tab" from `Settings dialog > Network tab > Port field`:
+/// the innermost named container around the element. The breadcrumb only
+/// ends with the element itself when the element has a name.
+fn section_suffix(breadcrumb: &str, has_element: bool, text: &Catalog) -> Option<String> {
+    let segments: Vec<&str> = breadcrumb.split(" > ").map(str::trim).collect();
+    let section = *segments.iter().rev().nth(usize::from(has_element))?;
+    if section.is_empty() {
+        return None;
+    }
+    // Segments end in a lowercase role label ("Billing group") unless the
+    // container's role was too generic to name.
+    let has_role = section
+        .rsplit_once(' ')
+        .is_some_and(|(_, last)| last.chars().all(|c| c.is_lowercase()));
+    let template = if has_role {
+        text.in_section_role
+    } else {
+        text.in_section
+    };
+    Some(fill(template, &[("section", section)]))
+}
+
+/// Default description for a click or typing step in the current locale.
+/// `None` when there's nothing more specific to say than the step type itself.
+pub fn describe_step(facts: &StepFacts<'_>) -> Option<String> {
+    describe_step_in(i18n::current(), facts)
+}
+
+pub fn describe_step_in(locale: Locale, facts: &StepFacts<'_>) -> Option<String> {
+    let text = locale.catalog();
+    let element = non_empty(facts.element_name);
+    let label = element.or_else(|| non_empty(facts.ocr_text).and_then(ocr_label));
+    // The role only describes the label when it came from the element
+    let role = element
+        .and(non_empty(facts.element_type).and_then(|element_type| role_noun(element_type, text)));
+    let section = non_empty(facts.breadcrumb)
+        .and_then(|breadcrumb| section_suffix(breadcrumb, element.is_some(), text))
+        .unwrap_or_default();
+    let section = section.as_str();
+
+    let sentence = match facts.type_ {
+        "click" => {
+            if let Some(action) = non_empty(facts.action) {
+                format!("{}{}", action, section)
+            } else {
+                match (label?, role) {
+                    (label, Some(role)) => fill(
+                        text.describe_click_role,
+                        &[("label", label), ("role", role), ("section", section)],
+                    ),
+                    (label, None) => fill(
+                        text.describe_click,
+                        &[("label", label), ("section", section)],
+                    ),
+                }
+            }
+        }
+        "type" => {
+            let typed = non_empty(facts.text)?;
+            match (label, role) {
+                (Some(label), Some(role)) => fill(
+                    text.describe_type_role,
+                    &[
+                        ("text", typed),
+                        ("label", label),
+                        ("role", role),
+                        ("section", section),
+                    ],
+                ),
+                (Some(label), None) => fill(
+                    text.describe_type,
+                    &[("text", typed), ("label", label), ("section", section)],
+                ),
+                (None, _) => fill(text.describe_type_text, &[("text", typed)]),
+            }
+        }
+        _ => return None,
+    };
+    Some(format!("{}.", sentence))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn facts<'a>(type_: &'a str) -> StepFacts<'a> {
+        StepFacts {
+            type_,
+            text: None,
+            element_name: None,
+            element_type: None,
+            action: None,
+            breadcrumb: None,
+            ocr_text: None,
+        }
+    }
+
+    #[test]
+    fn click_names_the_element_role_and_section() {
+        let step = StepFacts {
+            element_name: Some("Save changes"),
+            element_type: Some("Button"),
+            breadcrumb: Some("Account window > Billing group > Save changes button"),
+            ..facts("click")
+        };
+
+        assert_eq!(
+            describe_step(&step).as_deref(),
+            Some("Click the \"Save changes\" button in the Billing group.")
+        );
+    }
+
+    #[test]
+    fn click_prefers_the_inferred_action() {
+        let step = StepFacts {
+            element_name: Some("Remember me"),
+            element_type: Some("CheckBox"),
+            action: Some("Check \"Remember me\""),
+            ..facts("click")
+        };
+
+        assert_eq!(
+            describe_step(&step).as_deref(),
+            Some("Check \"Remember me\".")
+        );
+    }
+
+    #[test]
+    fn click_without_an_element_name_uses_the_ocr_line_nearest_the_click() {
+        let step = StepFacts {
+            element_type: Some("Pane"),
+            ocr_text: Some(
+                "Billing\nThis paragraph is far too long to be the label of a control\nSave changes\nCancel",
+            ),
+            ..facts("click")
+        };
+
+        assert_eq!(
+            describe_step(&step).as_deref(),
+            Some("Click \"Save changes\".")
+        );
+    }
+
+    #[test]
+    fn type_mentions_the_target_field() {
+        let step = StepFacts {
+            text: Some("jane@example.com"),
+            element_name: Some("Email"),
+            element_type: Some("Edit"),
+            ..facts("type")
+        };
+
+        assert_eq!(
+            describe_step(&step).as_deref(),
+            Some("Type \"jane@example.com\" into the \"Email\" field.")
+        );
+    }
+
+    #[test]
+    fn descriptions_follow_the_locale() {
+        let step = StepFacts {
+            element_name: Some("Speichern"),
+            element_type: Some("Button"),
+            breadcrumb: Some("Konto window > Abrechnung group > Speichern button"),
+            ..facts("click")
+        };
+
+        assert_eq!(
+            describe_step_in(Locale::De, &step).as_deref(),
+            Some("Klicken Sie auf die Schaltfläche „Speichern“ im Bereich „Abrechnung group“.")
+        );
+    }
+
+    #[test]
+    fn nothing_to_say_yields_none() {
+        assert_eq!(describe_step(&facts("click")), None);
+        assert_eq!(describe_step(&facts("type")), None);
+        assert_eq!(
+            describe_step(&StepFacts {
+                element_name: Some("Desktop"),
+                ..facts("capture")
+            }),
+            None
+        );
+    }
+}
@@ -0,0 +1,320 @@
+//! Crash reports and the diagnostics bundle attached to bug reports.
+//!
+//! `install_panic_hook` writes every panic, with where it happened and a
+//! backtrace, to the `crash` log before the default hook runs. `write_bundle`
+//! zips what support asks for first: the recent log files, both settings
+//! stores with credentials and the home directory masked, the schema version,
+//! and the OS, display server and monitor layout.
+
+use crate::database::SCHEMA_VERSION;
+use crate::logging::{self, CATEGORY_CRASH};
+use crate::settings::Settings;
+use serde::Serialize;
+use serde_json::{json, Value};
+use std::backtrace::Backtrace;
+use std::fs::{self, File};
+use std::io::{self, Write};
+use std::path::Path;
+use std::time::{Duration, SystemTime};
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipWriter};
+
+/// Log files not written to for longer than this are left out of a bundle
+const BUNDLED_LOG_AGE: Duration = Duration::from_secs(7 * 24 * 60 * 60);
+/// The frontend's settings store, in the app data directory
+const FRONTEND_SETTINGS_FILE: &str = "settings.json";
+/// Setting names (lowercase, without `_`) whose string values are masked
+const SECRET_NAME_MARKERS: &[&str] = &[
+    "apikey",
+    "secret",
+    "password",
+    "accesstoken",
+    "authtoken",
+    "credential",
+    // May carry `user:password@`
+    "proxyurl",
+];
+const REDACTED: &str = "[redacted]";
+
+/// Log panics to the `crash` category, then run the default hook so the
+/// message still reaches stderr
+pub fn install_panic_hook() {
+    let default_hook = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        let payload = info.payload();
+        let message = payload
+            .downcast_ref::<&str>()
+            .map(|message| message.to_string())
+            .or_else(|| payload.downcast_ref::<String>().cloned())
+            .unwrap_or_else(|| "Unknown panic".to_string());
+        let location = info
+            .location()
+            .map(|location| format!("{}:{}", location.file(), location.line()));
+        logging::log(
+            CATEGORY_CRASH,
+            "error",
+            &format!("Panic: {}", message),
+            Some(&json!({
+                "location": location,
+                "thread": std::thread::current().name().unwrap_or("unnamed"),
+                "version": env!("CARGO_PKG_VERSION"),
+                "backtrace": Backtrace::force_capture().to_string(),
+            })),
+        );
+        default_hook(info);
+    }));
+}
+
+/// What goes into a bundle besides the log files
+pub struct BundleSources<'a> {
+    pub settings: &'a Settings,
+    /// `None` when the database couldn't be read
+    pub schema_version: Option<i64>,
+    pub app_data_dir: &'a Path,
+    pub data_dir: &'a Path,
+    pub logs_dir: Option<&'a Path>,
+    pub home_dir: Option<&'a Path>,
+    /// See `monitor_topology`
+    pub monitors: Value,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct MonitorSummary {
+    name: String,
+    x: i32,
+    y: i32,
+    width: u32,
+    height: u32,
+    scale_factor: f32,
+    is_primary: bool,
+}
+
+/// Position, size and scaling of every monitor, or the error enumerating
+/// them
+pub fn monitor_topology() -> Value {
+    match xcap::Monitor::all() {
+        Ok(monitors) => {
+            let summaries: Vec<MonitorSummary> = monitors
+                .iter()
+                .enumerate()
+                .map(|(index, monitor)| MonitorSummary {
+                    name: monitor
+                        .name()
+                        .unwrap_or_else(|_| format!("Monitor {}", index + 1)),
+                    x: monitor.x().unwrap_or(0),
+                    y: monitor.y().unwrap_or(0),
+                    width: monitor.width().unwrap_or(0),
+                    height: monitor.height().unwrap_or(0),
+                    scale_factor: monitor.scale_factor().unwrap_or(1.0),
+                    is_primary: monitor.is_primary().unwrap_or(false),
+                })
+                .collect();
+            json!(summaries)
+        }
+        Err(e) => json!({ "error": e.to_string() }),
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn display_server() -> Option<String> {
+    Some(crate::display::detect_display_server().to_string())
+}
+
+#[cfg(not(target_os = "linux"))]
+fn display_server() -> Option<String> {
+    None
+}
+
+fn system_info(sources: &BundleSources) -> Value {
+    json!({
+        "appVersion": env!("CARGO_PKG_VERSION"),
+        "os": std::env::consts::OS,
+        "family": std::env::consts::FAMILY,
+        "arch": std::env::consts::ARCH,
+        "displayServer": display_server(),
+        "desktop": std::env::var("XDG_CURRENT_DESKTOP").ok(),
+        "schemaVersion": sources.schema_version,
+        "expectedSchemaVersion": SCHEMA_VERSION,
+        "sqliteVersion": rusqlite::version(),
+        "dataDirRelocated": sources.data_dir != sources.app_data_dir,
+        "logLevel": sources.settings.log_level,
+        "createdAt": chrono::Local::now().to_rfc3339(),
+    })
+}
+
+/// Mask credentials and replace the home directory with `~` in every string,
+/// so a bundle can be attached to a public issue
+fn sanitize(value: &mut Value, home: Option<&str>) {
+    match value {
+        Value::Object(fields) => {
+            for (name, field) in fields.iter_mut() {
+                let is_set = field.as_str().is_some_and(|text| !text.is_empty());
+                if is_set && is_secret_name(name) {
+                    *field = Value::String(REDACTED.to_string());
+                } else {
+                    sanitize(field, home);
+                }
+            }
+        }
+        Value::Array(items) => items.iter_mut().for_each(|item| sanitize(item, home)),
+        Value::String(text) => {
+            if let Some(home) = home.filter(|home| !home.is_empty()) {
+                *text = text.replace(home, "~");
+            }
+        }
+        _ => {}
+    }
+}
+
+fn is_secret_name(name: &str) -> bool {
+    let name = name.to_ascii_lowercase().replace(['_', '-'], "");
+    SECRET_NAME_MARKERS.iter().any(|marker| name.contains(marker))
+}
+
+/// Write the diagnostics zip to `destination` and return the names of the
+/// files in it. A partial zip is removed if writing fails.
+pub fn write_bundle(destination: &Path, sources: &BundleSources) -> Result<Vec<String>, String> {
+    let file = File::create(destination)
+        .map_err(|e| format!("Failed to create {}: {}", destination.display(), e))?;
+    let mut zip = ZipWriter::new(file);
+    let written = write_entries(&mut zip, sources)
+        .and_then(|entries| zip.finish().map(|_| entries).map_err(io::Error::from));
+    written.map_err(|e| {
+        let _ = fs::remove_file(destination);
+        format!("Failed to write the diagnostics bundle: {}", e)
+    })
+}
+
+fn write_entries(zip: &mut ZipWriter<File>, sources: &BundleSources) -> io::Result<Vec<String>> {
+    let options = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+    let home = sources.home_dir.map(|home| home.to_string_lossy().to_string());
+    let mut entries = Vec::new();
+    let mut add_json = |zip: &mut ZipWriter<File>, name: &str, mut value: Value| {
+        sanitize(&mut value, home.as_deref());
+        zip.start_file(name, options)?;
+        zip.write_all(serde_json::to_string_pretty(&value)?.as_bytes())?;
+        entries.push(name.to_string());
+        Ok::<(), io::Error>(())
+    };
+
+    add_json(zip, "system.json", system_info(sources))?;
+    add_json(zip, "monitors.json", sources.monitors.clone())?;
+    add_json(zip, "settings.json", serde_json::to_value(sources.settings)?)?;
+    let frontend_settings = fs::read_to_string(sources.app_data_dir.join(FRONTEND_SETTINGS_FILE))
+        .ok()
+        .and_then(|json| serde_json::from_str::<Value>(&json).ok());
+    if let Some(frontend_settings) = frontend_settings {
+        add_json(zip, "frontend-settings.json", frontend_settings)?;
+    }
+
+    if let Some(logs_dir) = sources.logs_dir {
+        for path in recent_log_files(logs_dir)? {
+            let Some(file_name) = path.file_name().and_then(|name| name.to_str()) else {
+                continue;
+            };
+            let name = format!("logs/{}", file_name);
+            zip.start_file(name.as_str(), options)?;
+            io::copy(&mut File::open(&path)?, zip)?;
+            entries.push(name);
+        }
+    }
+    Ok(entries)
+}
+
+/// `.log` files in `logs_dir` written to within `BUNDLED_LOG_AGE`, by name
+fn recent_log_files(logs_dir: &Path) -> io::Result<Vec<std::path::PathBuf>> {
+    let cutoff = SystemTime::now()
+        .checked_sub(BUNDLED_LOG_AGE)
+        .unwrap_or(SystemTime::UNIX_EPOCH);
+    let mut files: Vec<_> = fs::read_dir(logs_dir)?
+        .flatten()
+        .filter(|entry| entry.path().extension().is_some_and(|ext| ext == "log"))
+        .filter(|entry| {
+            entry
+                .metadata()
+                .and_then(|metadata| metadata.modified())
+                .is_ok_and(|modified| modified >= cutoff)
+        })
+        .map(|entry| entry.path())
+        .collect();
+    files.sort();
+    Ok(files)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Read;
+
+    #[test]
+    fn sanitize_masks_credentials_and_the_home_directory() {
+        let mut settings = json!({
+            "openaiApiKey": "sk-live",
+            "smtp_password": "hunter2",
+            "emptyApiKey": "",
+            "screenshotPath": "/home/ada/Pictures/steps",
+            "hotkeys": { "capture": { "key": "S", "ctrl": true } },
+        });
+
+        sanitize(&mut settings, Some("/home/ada"));
+
+        assert_eq!(settings["openaiApiKey"], REDACTED);
+        assert_eq!(settings["smtp_password"], REDACTED);
+        assert_eq!(settings["emptyApiKey"], "");
+        assert_eq!(settings["screenshotPath"], "~/Pictures/steps");
+        assert_eq!(settings["hotkeys"]["capture"]["key"], "S");
+    }
+
+    #[test]
+    fn write_bundle_zips_reports_settings_and_logs() {
+        let dir = std::env::temp_dir()
+            .join(format!("stepsnap_diagnostics_{}", uuid::Uuid::new_v4()));
+        let logs_dir = dir.join("logs");
+        fs::create_dir_all(&logs_dir).unwrap();
+        fs::write(
+            logs_dir.join("recorder.2026-01-01.log"),
+            "[INFO] Recording started\n",
+        )
+        .unwrap();
+        fs::write(logs_dir.join("notes.txt"), "not a log").unwrap();
+        fs::write(dir.join(FRONTEND_SETTINGS_FILE), r#"{"openaiApiKey":"sk-live"}"#).unwrap();
+        let settings = Settings::default();
+        let destination = dir.join("bundle.zip");
+
+        let entries = write_bundle(
+            &destination,
+            &BundleSources {
+                settings: &settings,
+                schema_version: Some(SCHEMA_VERSION),
+                app_data_dir: &dir,
+                data_dir: &dir,
+                logs_dir: Some(&logs_dir),
+                home_dir: None,
+                monitors: json!([]),
+            },
+        )
+        .unwrap();
+
+        assert_eq!(
+            entries,
+            [
+                "system.json",
+                "monitors.json",
+                "settings.json",
+                "frontend-settings.json",
+                "logs/recorder.2026-01-01.log",
+            ]
+        );
+        let mut archive = zip::ZipArchive::new(File::open(&destination).unwrap()).unwrap();
+        let mut frontend = String::new();
+        archive
+            .by_name("frontend-settings.json")
+            .unwrap()
+            .read_to_string(&mut frontend)
+            .unwrap();
+        assert!(!frontend.contains("sk-live"));
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,275 @@
+//! Emailing a recording as an HTML how-to through the user's SMTP server.
+//!
+//! The recording is rendered to Markdown (see `render_markdown_source`)
+//! and converted to an HTML body with its screenshots as inline `cid:` parts,
+//! so it reads the same in any mail client without loading remote images.
+//! The recording bundle can be attached for colleagues who use the app; there
+//! is no PDF attachment, since PDFs are only rendered by the frontend's
+//! exporter. The SMTP password is kept in the keychain as `smtp_password`.
+
+use crate::database::Database;
+use crate::db_worker::DatabaseWorker;
+use crate::settings::SettingsState;
+use crate::{
+    analytics, bundle, credentials, export, render_markdown_source, ExportStyles, MarkdownSource,
+};
+use lettre::message::header::ContentType;
+use lettre::message::{Attachment, Mailbox, MultiPart, SinglePart};
+use lettre::transport::smtp::authentication::Credentials;
+use lettre::{Message, SmtpTransport, Transport};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
+
+pub const PASSWORD_CREDENTIAL: &str = "smtp_password";
+pub const DEFAULT_PORT: u16 = 587;
+/// Most servers reject messages over 25 MB once encoded, about a third more
+const MAX_ATTACHMENT_BYTES: usize = 18 * 1024 * 1024;
+const SEND_TIMEOUT: Duration = Duration::from_secs(60);
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SmtpSecurity {
+    /// Upgrade a plain connection, usually on port 587
+    #[default]
+    StartTls,
+    /// TLS from the start, usually on port 465
+    Tls,
+    /// Unencrypted, for relays on the local network
+    None,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct EmailSettings {
+    /// SMTP server; empty until set up
+    pub host: String,
+    pub port: u16,
+    pub security: SmtpSecurity,
+    /// Empty for servers that don't need a login
+    pub username: String,
+    pub from_address: String,
+    pub from_name: String,
+}
+
+impl Default for EmailSettings {
+    fn default() -> Self {
+        Self {
+            host: String::new(),
+            port: DEFAULT_PORT,
+            security: SmtpSecurity::default(),
+            username: String::new(),
+            from_address: String::new(),
+            from_name: String::new(),
+        }
+    }
+}
+
+/// The message body: `note` as paragraphs above the rendered `markdown`
+fn html_body(markdown: &str, note: Option<&str>) -> String {
+    let mut html = String::from(
+        "<!DOCTYPE html><html><head><meta charset=\"utf-8\"></head>\
+         <body style=\"font-family: -apple-system, 'Segoe UI', Roboto, sans-serif; \
+         line-height: 1.5; color: #1f1f1f; max-width: 720px;\">",
+    );
+    for paragraph in note
+        .unwrap_or_default()
+        .split("\n\n")
+        .map(str::trim)
+        .filter(|paragraph| !paragraph.is_empty())
+    {
+        html.push_str(&format!(
+            "<p>{}</p>",
+            export::escape_html(paragraph).replace('\n', "<br>")
+        ));
+    }
+    html.push_str(&export::markdown_to_html(markdown));
+    html.push_str("</body></html>");
+    html
+}
+
+fn image_type(path: &Path) -> ContentType {
+    let mime = match path.extension().and_then(|ext| ext.to_str()) {
+        Some("png") => "image/png",
+        Some("webp") => "image/webp",
+        Some("gif") => "image/gif",
+        _ => "image/jpeg",
+    };
+    ContentType::parse(mime).expect("valid MIME type")
+}
+
+fn mailbox(address: &str, name: &str) -> Result<Mailbox, String> {
+    let address = address
+        .trim()
+        .parse()
+        .map_err(|_| format!("Invalid email address: {}", address))?;
+    let name = Some(name.trim().to_string()).filter(|name| !name.is_empty());
+    Ok(Mailbox::new(name, address))
+}
+
+struct EmailRequest {
+    recording_id: String,
+    to: Vec<String>,
+    subject: Option<String>,
+    note: Option<String>,
+    attach_bundle: bool,
+}
+
+fn send(app: &AppHandle, request: EmailRequest) -> Result<(), String> {
+    let (config, locale, styles) = {
+        let settings = app.state::<SettingsState>();
+        let settings = settings.0.lock().unwrap();
+        (
+            settings.email.clone(),
+            settings.locale,
+            ExportStyles::from_settings(&settings),
+        )
+    };
+    if config.host.trim().is_empty() || config.from_address.trim().is_empty() {
+        return Err("Set up an SMTP server and sender address in Settings".to_string());
+    }
+    let recipients = request
+        .to
+        .iter()
+        .filter(|address| !address.trim().is_empty())
+        .map(|address| mailbox(address, ""))
+        .collect::<Result<Vec<_>, _>>()?;
+    if recipients.is_empty() {
+        return Err("Add at least one recipient".to_string());
+    }
+
+    let db = app.state::<DatabaseWorker>();
+    let id = request.recording_id.clone();
+    let source = db.run_blocking(move |db| MarkdownSource::load(db, &id))?;
+    let name = source.recording.recording.name.clone();
+    let markdown = render_markdown_source(source, locale, styles);
+
+    let mut images = Vec::new();
+    let markdown = export::rewrite_image_links(&markdown, |path| {
+        let cid = format!("step-image-{}", images.len() + 1);
+        images.push((cid.clone(), path.to_string()));
+        Some(format!("cid:{}", cid))
+    });
+    let mut related = MultiPart::related().singlepart(SinglePart::html(html_body(
+        &markdown,
+        request.note.as_deref(),
+    )));
+    let mut total_bytes = 0;
+    for (cid, path) in images {
+        let bytes = std::fs::read(&path).map_err(|e| format!("Failed to read {}: {}", path, e))?;
+        total_bytes += bytes.len();
+        related = related
+            .singlepart(Attachment::new_inline(cid).body(bytes, image_type(Path::new(&path))));
+    }
+
+    let body = if request.attach_bundle {
+        let path = std::env::temp_dir().join(format!(
+            "stepsnap_email_{}.{}",
+            uuid::Uuid::new_v4(),
+            bundle::EXTENSION
+        ));
+        let (id, bundle_path) = (request.recording_id.clone(), path.clone());
+        let exported = db.run_blocking(move |db| bundle::export(db, &id, &bundle_path));
+        let bytes = exported.and_then(|_| std::fs::read(&path).map_err(|e| e.to_string()));
+        let _ = std::fs::remove_file(&path);
+        let bytes = bytes?;
+        total_bytes += bytes.len();
+        let file_name = format!(
+            "{}.{}",
+            Database::sanitize_dirname_public(&name),
+            bundle::EXTENSION
+        );
+        MultiPart::mixed()
+            .multipart(related)
+            .singlepart(Attachment::new(file_name).body(
+                bytes,
+                ContentType::parse("application/zip").expect("valid MIME type"),
+            ))
+    } else {
+        related
+    };
+    if total_bytes > MAX_ATTACHMENT_BYTES {
+        return Err(format!(
+            "The email would be about {} MB, more than most mail servers accept",
+            total_bytes * 4 / 3 / (1024 * 1024)
+        ));
+    }
+
+    let mut message = Message::builder()
+        .from(mailbox(&config.from_address, &config.from_name)?)
+        .subject(
+            request
+                .subject
+                .filter(|subject| !subject.trim().is_empty())
+                .unwrap_or_else(|| name.clone()),
+        );
+    for recipient in recipients {
+        message = message.to(recipient);
+    }
+    let message = message.multipart(body).map_err(|e| e.to_string())?;
+
+    let host = config.host.trim();
+    let mut transport = match config.security {
+        SmtpSecurity::StartTls => SmtpTransport::starttls_relay(host),
+        SmtpSecurity::Tls => SmtpTransport::relay(host),
+        SmtpSecurity::None => Ok(SmtpTransport::builder_dangerous(host)),
+    }
+    .map_err(|e| format!("Invalid SMTP server {}: {}", host, e))?
+    .port(config.port)
+    .timeout(Some(SEND_TIMEOUT));
+    if !config.username.trim().is_empty() {
+        let password = credentials::get(PASSWORD_CREDENTIAL)?.unwrap_or_default();
+        transport = transport.credentials(Credentials::new(
+            config.username.trim().to_string(),
+            password,
+        ));
+    }
+    transport
+        .build()
+        .send(&message)
+        .map_err(|e| format!("Failed to send the email: {}", e))?;
+    analytics::track("email_export");
+    tracing::info!(recording_id = %request.recording_id, recipients = request.to.len(), "Recording emailed");
+    Ok(())
+}
+
+/// Email `recording_id` to `to` through the configured SMTP server, with
+/// `note` above the steps and the recording bundle attached if asked
+#[tauri::command]
+pub async fn send_recording_email(
+    app: AppHandle,
+    recording_id: String,
+    to: Vec<String>,
+    subject: Option<String>,
+    note: Option<String>,
+    attach_bundle: bool,
+) -> Result<(), String> {
+    let request = EmailRequest {
+        recording_id,
+        to,
+        subject,
+        note,
+        attach_bundle,
+    };
+    tauri::async_runtime::spawn_blocking(move || send(&app, request))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn html_body_escapes_the_note_and_keeps_inline_images() {
+        let html = html_body(
+            "### Step 1\n\nClick **Save**.\n\n![Step 1](<cid:step-image-1>)\n",
+            Some("Hi <team>,\nhere's how.\n\nThanks"),
+        );
+        assert!(html.contains("<p>Hi &lt;team&gt;,<br>here's how.</p><p>Thanks</p>"));
+        assert!(html.contains("<strong>Save</strong>"));
+        assert!(html
+            .contains("<img style=\"max-width: 100%;\" src=\"cid:step-image-1\" alt=\"Step 1\""));
+    }
+}
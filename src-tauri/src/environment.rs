@@ -0,0 +1,236 @@
+//! First-run checks of what recording needs from the system.
+//!
+//! Missing permissions don't fail loudly: captures come back black, clicks
+//! aren't seen and steps lose their element names. `run_environment_check`
+//! reports each requirement with what to do about it, so onboarding can walk
+//! the user through fixing it before the first recording.
+
+use crate::ocr::{OcrProvider, RECOGNITION_MODELS};
+use serde::Serialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CheckStatus {
+    Passed,
+    /// Recording works, with something missing or degraded
+    Warning,
+    /// Recording won't work until this is fixed
+    Failed,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct EnvironmentCheck {
+    /// "screen_capture", "accessibility", "input", "data_dir",
+    /// "screenshot_dir" or "ocr"
+    pub id: &'static str,
+    pub status: CheckStatus,
+    pub detail: String,
+    /// What the user can do about a warning or failure
+    pub fix: Option<String>,
+}
+
+impl EnvironmentCheck {
+    fn passed(id: &'static str, detail: impl Into<String>) -> Self {
+        Self {
+            id,
+            status: CheckStatus::Passed,
+            detail: detail.into(),
+            fix: None,
+        }
+    }
+
+    fn problem(
+        id: &'static str,
+        status: CheckStatus,
+        detail: impl Into<String>,
+        fix: impl Into<String>,
+    ) -> Self {
+        Self {
+            id,
+            status,
+            detail: detail.into(),
+            fix: Some(fix.into()),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct EnvironmentReport {
+    /// No check failed
+    pub ready: bool,
+    pub checks: Vec<EnvironmentCheck>,
+}
+
+impl EnvironmentReport {
+    pub fn new(checks: Vec<EnvironmentCheck>) -> Self {
+        Self {
+            ready: checks.iter().all(|check| check.status != CheckStatus::Failed),
+            checks,
+        }
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn is_wayland() -> bool {
+    crate::display::is_wayland()
+}
+
+#[cfg(not(target_os = "linux"))]
+fn is_wayland() -> bool {
+    false
+}
+
+/// `permission_granted` is the macOS Screen Recording permission (always
+/// granted elsewhere). Also needs at least one display to capture.
+pub fn screen_capture_check(permission_granted: bool) -> EnvironmentCheck {
+    const ID: &str = "screen_capture";
+    if !permission_granted {
+        return EnvironmentCheck::problem(
+            ID,
+            CheckStatus::Failed,
+            "Screen Recording permission is not granted, so screenshots would be blank",
+            "Allow StepSnap under System Settings > Privacy & Security > Screen Recording, then restart StepSnap",
+        );
+    }
+    match xcap::Monitor::all() {
+        Ok(monitors) if monitors.is_empty() => EnvironmentCheck::problem(
+            ID,
+            CheckStatus::Failed,
+            "No displays were found to capture",
+            "Check that a display is connected and the session isn't locked",
+        ),
+        Ok(monitors) if is_wayland() => EnvironmentCheck::problem(
+            ID,
+            CheckStatus::Warning,
+            format!("{} display(s) found on Wayland", monitors.len()),
+            "Your desktop may ask for permission to share the screen on the first capture",
+        ),
+        Ok(monitors) => {
+            EnvironmentCheck::passed(ID, format!("{} display(s) found", monitors.len()))
+        }
+        Err(e) => EnvironmentCheck::problem(
+            ID,
+            CheckStatus::Failed,
+            format!("Displays couldn't be read: {}", e),
+            "Check the screen capture permissions of your desktop environment",
+        ),
+    }
+}
+
+/// `granted` is the macOS Accessibility permission (always granted
+/// elsewhere), needed for element names and keyboard capture
+pub fn accessibility_check(granted: bool) -> EnvironmentCheck {
+    const ID: &str = "accessibility";
+    if granted {
+        return EnvironmentCheck::passed(ID, "UI element names can be read");
+    }
+    EnvironmentCheck::problem(
+        ID,
+        CheckStatus::Failed,
+        "Accessibility permission is not granted, so clicks, keys and element names aren't recorded",
+        "Allow StepSnap under System Settings > Privacy & Security > Accessibility",
+    )
+}
+
+/// `listener_error` is why the global input listener stopped, if it did
+pub fn input_check(listener_error: Option<String>) -> EnvironmentCheck {
+    const ID: &str = "input";
+    if let Some(error) = listener_error {
+        return EnvironmentCheck::problem(
+            ID,
+            CheckStatus::Failed,
+            format!("Global mouse and keyboard input can't be read: {}", error),
+            if cfg!(target_os = "macos") {
+                "Allow StepSnap under Privacy & Security > Accessibility and Input Monitoring, then restart StepSnap"
+            } else if cfg!(target_os = "linux") {
+                "Run StepSnap in an X11 session, or make sure XWayland is running"
+            } else {
+                "Restart StepSnap; if it keeps failing, attach a diagnostics bundle to a bug report"
+            },
+        );
+    }
+    if is_wayland() {
+        return EnvironmentCheck::problem(
+            ID,
+            CheckStatus::Warning,
+            "On Wayland, clicks are only seen in apps running through XWayland",
+            "Log in with an X11 session to record native Wayland apps",
+        );
+    }
+    EnvironmentCheck::passed(ID, "Mouse and keyboard input is being received")
+}
+
+/// Whether files can be created in `dir`, by writing and removing a probe
+pub fn writable_dir_check(id: &'static str, dir: &Path) -> EnvironmentCheck {
+    let probe = dir.join(format!(".stepsnap-write-check-{}", uuid::Uuid::new_v4()));
+    let written = fs::create_dir_all(dir).and_then(|()| fs::write(&probe, b"ok"));
+    let _ = fs::remove_file(&probe);
+    match written {
+        Ok(()) => EnvironmentCheck::passed(id, format!("{} is writable", dir.display())),
+        Err(e) => EnvironmentCheck::problem(
+            id,
+            CheckStatus::Failed,
+            format!("Can't write to {}: {}", dir.display(), e),
+            "Pick another folder in Settings > Storage, or fix the folder's permissions",
+        ),
+    }
+}
+
+/// Whether OCR has an engine: installed ONNX models or, on Windows and
+/// macOS, the platform engine
+pub fn ocr_check(ocr_enabled: bool, models_dirs: &[PathBuf]) -> EnvironmentCheck {
+    const ID: &str = "ocr";
+    if !ocr_enabled {
+        return EnvironmentCheck::passed(ID, "OCR is turned off");
+    }
+    let installed = RECOGNITION_MODELS
+        .iter()
+        .filter(|model| model.is_installed(models_dirs))
+        .count();
+    if installed > 0 {
+        return EnvironmentCheck::passed(ID, format!("{} OCR model(s) installed", installed));
+    }
+    let platform = OcrProvider::available()
+        .into_iter()
+        .find(|provider| *provider != OcrProvider::Onnx);
+    EnvironmentCheck::problem(
+        ID,
+        CheckStatus::Warning,
+        match platform {
+            Some(provider) => format!(
+                "No OCR models are installed; the {:?} engine is used instead",
+                provider
+            ),
+            None => "No OCR models are installed, so screenshot text won't be read".to_string(),
+        },
+        "Download a model under Settings > General > OCR",
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn report_is_ready_unless_a_check_failed() {
+        let input = input_check(None);
+        let failed = accessibility_check(false);
+
+        assert!(EnvironmentReport::new(vec![input, ocr_check(false, &[])]).ready);
+        assert!(!EnvironmentReport::new(vec![failed]).ready);
+    }
+
+    #[test]
+    fn writable_dir_check_leaves_no_probe_behind() {
+        let dir = std::env::temp_dir()
+            .join(format!("stepsnap_env_check_{}", uuid::Uuid::new_v4()));
+
+        let check = writable_dir_check("data_dir", &dir);
+
+        assert_eq!(check.status, CheckStatus::Passed);
+        assert_eq!(fs::read_dir(&dir).unwrap().count(), 0);
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,115 @@
+//! Recorder events streamed over a WebSocket for companion tools.
+//!
+//! The API server (see `api_server`) upgrades `/api/v1/events` to a
+//! WebSocket that receives every event in `STREAMED_EVENTS` as it's emitted,
+//! as one JSON text message: `{"event": "new-step", "payload": {...},
+//! "timestamp": <ms>}`. The events are the ones the webview already gets, so
+//! an OBS overlay or a second-screen viewer sees exactly what the app does.
+//! Browser sources can't set headers, so this endpoint also takes the bearer
+//! token as `?token=`.
+//!
+//! The stream is live only: a client that falls behind by more than
+//! `BUFFERED_EVENTS` gets a `lagged` message with the number it missed.
+
+use axum::extract::ws::{Message, WebSocket, WebSocketUpgrade};
+use axum::response::Response;
+use std::sync::OnceLock;
+use tauri::{AppHandle, Listener};
+use tokio::sync::broadcast;
+
+pub const EVENTS_PATH: &str = "/api/v1/events";
+/// Events forwarded to WebSocket clients
+pub const STREAMED_EVENTS: &[&str] = &[
+    "recording-started",
+    "recording-paused",
+    "recording-resumed",
+    "recording-stopped",
+    "new-step",
+    "marker-added",
+    "ocr-step-complete",
+];
+/// Messages kept for slow clients before they start missing some
+const BUFFERED_EVENTS: usize = 256;
+
+fn channel() -> &'static broadcast::Sender<String> {
+    static CHANNEL: OnceLock<broadcast::Sender<String>> = OnceLock::new();
+    CHANNEL.get_or_init(|| broadcast::channel(BUFFERED_EVENTS).0)
+}
+
+/// The message sent for `event`; `payload` is the event's JSON payload
+fn envelope(event: &str, payload: &str, timestamp: i64) -> String {
+    let payload = serde_json::from_str(payload).unwrap_or(serde_json::Value::Null);
+    serde_json::json!({
+        "event": event,
+        "payload": payload,
+        "timestamp": timestamp,
+    })
+    .to_string()
+}
+
+/// Forward the streamed events to connected clients from now on
+pub fn init(app: &AppHandle) {
+    for &event in STREAMED_EVENTS {
+        app.listen_any(event, move |emitted| {
+            // No subscribers is the usual case and not an error
+            let _ = channel().send(envelope(
+                event,
+                emitted.payload(),
+                chrono::Utc::now().timestamp_millis(),
+            ));
+        });
+    }
+}
+
+pub async fn events_socket(upgrade: WebSocketUpgrade) -> Response {
+    upgrade.on_upgrade(stream)
+}
+
+async fn stream(mut socket: WebSocket) {
+    let mut events = channel().subscribe();
+    tracing::info!("Event stream client connected");
+    loop {
+        tokio::select! {
+            event = events.recv() => {
+                let text = match event {
+                    Ok(text) => text,
+                    Err(broadcast::error::RecvError::Lagged(missed)) => serde_json::json!({
+                        "event": "lagged",
+                        "payload": { "missed": missed },
+                        "timestamp": chrono::Utc::now().timestamp_millis(),
+                    })
+                    .to_string(),
+                    Err(broadcast::error::RecvError::Closed) => break,
+                };
+                if socket.send(Message::Text(text.into())).await.is_err() {
+                    break;
+                }
+            }
+            incoming = socket.recv() => match incoming {
+                // Clients have nothing to say; pings are answered by axum
+                Some(Ok(Message::Close(_))) | Some(Err(_)) | None => break,
+                Some(Ok(_)) => {}
+            },
+        }
+    }
+    tracing::info!("Event stream client disconnected");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn wraps_payloads_with_the_event_name() {
+        let message: serde_json::Value =
+            serde_json::from_str(&envelope("new-step", r#"{"id":"s1"}"#, 42)).unwrap();
+        assert_eq!(
+            message,
+            serde_json::json!({ "event": "new-step", "payload": { "id": "s1" }, "timestamp": 42 })
+        );
+
+        let message: serde_json::Value =
+            serde_json::from_str(&envelope("recording-stopped", "", 1)).unwrap();
+        assert_eq!(message["payload"], serde_json::Value::Null);
+    }
+}
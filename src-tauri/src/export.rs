@@ -0,0 +1,449 @@
+//! Backend rendering of a recording into a Markdown document.
+//!
+//! The webview exporters work from the AI-generated documentation. This
+//! renderer builds a document straight from the stored steps so backend
+//! features can produce output without round-tripping through the frontend.
+//!
+//! Text-only step types are rendered structurally: `heading` and `marker`
+//! start a new section, `note` and `warning` become callouts, and none of
+//! them consume a step number. Linked recordings become cross-references:
+//! prerequisites in a callout under the title, "see also" links in a closing
+//! section. Headings, callout labels and fallback descriptions are in the
+//! given locale.
+
+use crate::database::{
+    RecordingLink, RecordingWithSteps, Step, LINK_KIND_PREREQUISITE, LINK_KIND_SEE_ALSO,
+    STEP_TYPE_HEADING, STEP_TYPE_MARKER, STEP_TYPE_NOTE, STEP_TYPE_WARNING,
+};
+use crate::i18n::{fill, Catalog, Locale};
+
+/// Render a recording as Markdown, with `links` (its outgoing recording
+/// links) as cross-references.
+pub fn render_markdown(
+    recording: &RecordingWithSteps,
+    links: &[RecordingLink],
+    locale: Locale,
+) -> String {
+    let text = locale.catalog();
+    let mut out = format!("# {}\n\n", recording.recording.name.trim());
+    let mut step_number = 0;
+    let mut current_page: Option<&str> = None;
+
+    let prerequisites = link_names(links, LINK_KIND_PREREQUISITE);
+    if !prerequisites.is_empty() {
+        out.push_str(&callout(
+            text.before_you_begin,
+            &fill(
+                text.complete_first,
+                &[("recordings", &prerequisites.join(", "))],
+            ),
+        ));
+    }
+
+    for step in &recording.steps {
+        match step.type_.as_str() {
+            STEP_TYPE_HEADING | STEP_TYPE_MARKER => {
+                let heading = first_non_empty(&[&step.title, &step.text, &step.description]);
+                if let Some(heading) = heading {
+                    out.push_str(&format!("## {}\n\n", single_line(heading)));
+                }
+            }
+            STEP_TYPE_NOTE | STEP_TYPE_WARNING => {
+                let label = if step.type_ == STEP_TYPE_WARNING {
+                    text.warning
+                } else {
+                    text.note
+                };
+                let body = first_non_empty(&[&step.description, &step.text, &step.title]);
+                if let Some(body) = body {
+                    out.push_str(&callout(label, body));
+                }
+            }
+            _ => {
+                step_number += 1;
+                let page = step.page_url.as_deref().filter(|url| !url.is_empty());
+                let new_page = page.filter(|url| current_page != Some(*url));
+                current_page = page.or(current_page);
+                out.push_str(&render_action_step(step, step_number, new_page, text));
+            }
+        }
+    }
+
+    let see_also = link_names(links, LINK_KIND_SEE_ALSO);
+    if !see_also.is_empty() {
+        out.push_str(&format!("## {}\n\n", text.see_also));
+        for name in see_also {
+            out.push_str(&format!("- {}\n", name));
+        }
+    }
+
+    out.trim_end().to_string() + "\n"
+}
+
+/// Emphasised target names of the links of one kind.
+fn link_names(links: &[RecordingLink], kind: &str) -> Vec<String> {
+    links
+        .iter()
+        .filter(|link| link.kind == kind)
+        .map(|link| format!("*{}*", single_line(&link.to_name)))
+        .collect()
+}
+
+/// `new_page` is the browser URL when it differs from the previous step's, so
+/// generated text can say where the action happens.
+fn render_action_step(
+    step: &Step,
+    number: usize,
+    new_page: Option<&str>,
+    text: &Catalog,
+) -> String {
+    let number = number.to_string();
+    let heading = match first_non_empty(&[&step.title]) {
+        Some(title) => fill(
+            text.step_titled,
+            &[("n", &number), ("title", &single_line(title))],
+        ),
+        None => fill(text.step, &[("n", &number)]),
+    };
+    let mut out = format!("### {}\n\n", heading);
+
+    let body = first_non_empty(&[&step.description])
+        .map(|description| description.to_string())
+        .unwrap_or_else(|| match new_page {
+            Some(url) => text.on_page(url, &fallback_description(step, text)),
+            None => fallback_description(step, text),
+        });
+    out.push_str(body.trim());
+    out.push_str("\n\n");
+
+    if let Some(path) = step.screenshot_path.as_deref().filter(|p| !p.is_empty()) {
+        let alt = fill(text.step, &[("n", &number)]);
+        out.push_str(&format!("![{}](<{}>)\n\n", alt, path));
+    }
+    if let Some(path) = step
+        .element_screenshot_path
+        .as_deref()
+        .filter(|p| !p.is_empty())
+    {
+        let alt = fill(text.step_detail, &[("n", &number)]);
+        out.push_str(&format!("![{}](<{}>)\n\n", alt, path));
+    }
+
+    out
+}
+
+/// `markdown` with the target of each image (`![alt](<path>)`, as rendered
+/// here) replaced by what `rewrite` returns for it, or kept when it returns
+/// `None`. Lets a document written elsewhere point at copied or uploaded
+/// screenshots instead of the local files.
+pub fn rewrite_image_links(
+    markdown: &str,
+    mut rewrite: impl FnMut(&str) -> Option<String>,
+) -> String {
+    let mut out = String::with_capacity(markdown.len());
+    let mut rest = markdown;
+    while let Some(start) = rest.find("](<") {
+        let is_image = rest[..start]
+            .rfind("![")
+            .is_some_and(|open| !rest[open..start].contains('\n'));
+        let target = &rest[start + 3..];
+        let Some(end) = target.find(">)").filter(|_| is_image) else {
+            out.push_str(&rest[..start + 3]);
+            rest = target;
+            continue;
+        };
+        out.push_str(&rest[..start + 3]);
+        let path = &target[..end];
+        out.push_str(&rewrite(path).unwrap_or_else(|| path.to_string()));
+        rest = &target[end..];
+    }
+    out.push_str(rest);
+    out
+}
+
+pub fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// `markdown` as an HTML fragment, with images sized to fit since email
+/// clients and shared pages don't get a stylesheet for them.
+pub fn markdown_to_html(markdown: &str) -> String {
+    let parser = pulldown_cmark::Parser::new_ext(markdown, pulldown_cmark::Options::all());
+    let mut html = String::new();
+    pulldown_cmark::html::push_html(&mut html, parser);
+    html.replace("<img ", "<img style=\"max-width: 100%;\" ")
+}
+
+/// Used when a step has no description yet (e.g. generation has not run).
+fn fallback_description(step: &Step, text: &Catalog) -> String {
+    let element = step.element_name.as_deref().filter(|name| !name.is_empty());
+    let state = step.element_value.as_deref().filter(|value| {
+        matches!(
+            *value,
+            "checked" | "unchecked" | "mixed" | "selected" | "not selected"
+        )
+    });
+    if let Some(action) = step.action.as_deref().filter(|action| !action.is_empty()) {
+        return format!("{}.", action);
+    }
+    match (step.type_.as_str(), element) {
+        ("click", Some(name)) => match state {
+            Some(state) => fill(
+                text.click_named_state,
+                &[("name", name), ("state", text.state(state))],
+            ),
+            None => fill(text.click_named, &[("name", name)]),
+        },
+        ("click", None) => text.click_highlighted.to_string(),
+        ("type", _) => match step.text.as_deref().filter(|typed| !typed.is_empty()) {
+            Some(typed) => match element {
+                Some(name) => fill(text.type_into, &[("text", typed), ("name", name)]),
+                None => fill(text.type_text, &[("text", typed)]),
+            },
+            None => text.enter_recorded_text.to_string(),
+        },
+        _ => text.verify_screen.to_string(),
+    }
+}
+
+fn callout(label: &str, body: &str) -> String {
+    let mut out = String::new();
+    for (index, line) in body.trim().lines().enumerate() {
+        if index == 0 {
+            out.push_str(&format!("> **{}:** {}\n", label, line));
+        } else {
+            out.push_str(&format!("> {}\n", line));
+        }
+    }
+    out.push('\n');
+    out
+}
+
+fn first_non_empty<'a>(candidates: &[&'a Option<String>]) -> Option<&'a str> {
+    candidates
+        .iter()
+        .filter_map(|candidate| (*candidate).as_deref())
+        .map(str::trim)
+        .find(|value| !value.is_empty())
+}
+
+fn single_line(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::Recording;
+
+    fn step(type_: &str, title: Option<&str>, description: Option<&str>) -> Step {
+        Step {
+            id: format!("{}-id", type_),
+            recording_id: "rec-1".to_string(),
+            type_: type_.to_string(),
+            timestamp: 1,
+            description: description.map(str::to_string),
+            title: title.map(str::to_string),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn render_markdown_structures_text_only_steps_without_numbering_them() {
+        let recording = RecordingWithSteps {
+            recording: Recording {
+                id: "rec-1".to_string(),
+                name: "Onboarding".to_string(),
+                created_at: 1,
+                updated_at: 1,
+                documentation: None,
+                documentation_generated_at: None,
+                step_count: 4,
+                first_screenshot_path: None,
+                duration_ms: None,
+                favorite: false,
+                archived_at: None,
+                locked: false,
+            },
+            steps: vec![
+                step("heading", Some("Sign in"), None),
+                step("click", Some("Open the portal"), Some("Click **Portal**.")),
+                step("warning", None, Some("Use your work account.")),
+                step("click", None, Some("Click **Sign in**.")),
+            ],
+        };
+
+        let markdown = render_markdown(&recording, &[], Locale::En);
+
+        assert!(markdown.starts_with("# Onboarding\n\n## Sign in\n\n"));
+        assert!(markdown.contains("### Step 1: Open the portal\n\nClick **Portal**."));
+        assert!(markdown.contains("> **Warning:** Use your work account."));
+        assert!(markdown.contains("### Step 2\n\nClick **Sign in**."));
+    }
+
+    #[test]
+    fn render_markdown_starts_a_section_at_each_marker() {
+        let marker = |label: &str| Step {
+            text: Some(label.to_string()),
+            ..step("marker", None, None)
+        };
+        let recording = RecordingWithSteps {
+            recording: Recording {
+                id: "rec-1".to_string(),
+                name: "Month end".to_string(),
+                ..Default::default()
+            },
+            steps: vec![
+                marker("Reconcile"),
+                step("click", None, Some("Click **Reconcile**.")),
+                Step {
+                    title: Some("Close the books".to_string()),
+                    ..marker("Chapter 2")
+                },
+                step("click", None, Some("Click **Close**.")),
+            ],
+        };
+
+        let markdown = render_markdown(&recording, &[], Locale::En);
+
+        assert!(markdown.starts_with("# Month end\n\n## Reconcile\n\n### Step 1\n\n"));
+        assert!(markdown.contains("## Close the books\n\n### Step 2\n\n"));
+        assert!(!markdown.contains("Chapter 2"));
+    }
+
+    #[test]
+    fn render_markdown_adds_link_cross_references() {
+        let recording = RecordingWithSteps {
+            recording: Recording {
+                id: "rec-1".to_string(),
+                name: "Submit expenses".to_string(),
+                ..Default::default()
+            },
+            steps: vec![step("click", None, Some("Click **Submit**."))],
+        };
+        let link = |to_name: &str, kind: &str| RecordingLink {
+            from_id: "rec-1".to_string(),
+            to_id: format!("{}-id", to_name),
+            to_name: to_name.to_string(),
+            kind: kind.to_string(),
+            created_at: 1,
+        };
+
+        let markdown = render_markdown(
+            &recording,
+            &[
+                link("Approve expenses", LINK_KIND_SEE_ALSO),
+                link("Sign in", LINK_KIND_PREREQUISITE),
+            ],
+            Locale::En,
+        );
+
+        assert!(markdown
+            .starts_with("# Submit expenses\n\n> **Before you begin:** Complete *Sign in* first."));
+        assert!(markdown.ends_with("## See also\n\n- *Approve expenses*\n"));
+    }
+
+    #[test]
+    fn render_markdown_names_page_when_browser_url_changes() {
+        let on = |url: &str| Step {
+            page_url: Some(url.to_string()),
+            element_name: Some("Save".to_string()),
+            ..step("click", None, None)
+        };
+        let recording = RecordingWithSteps {
+            recording: Recording::default(),
+            steps: vec![
+                on("https://admin.example.com/users"),
+                on("https://admin.example.com/users"),
+                on("https://admin.example.com/roles"),
+            ],
+        };
+
+        let markdown = render_markdown(&recording, &[], Locale::En);
+
+        assert!(markdown
+            .contains("### Step 1\n\nOn <https://admin.example.com/users>, click \"Save\"."));
+        assert!(markdown.contains("### Step 2\n\nClick \"Save\"."));
+        assert!(markdown.contains("On <https://admin.example.com/roles>, click \"Save\"."));
+    }
+
+    #[test]
+    fn render_markdown_adds_element_inset_after_screenshot() {
+        let recording = RecordingWithSteps {
+            recording: Recording::default(),
+            steps: vec![Step {
+                screenshot_path: Some("/shots/full.jpg".to_string()),
+                element_screenshot_path: Some("/shots/full_element.jpg".to_string()),
+                ..step("click", None, Some("Click **Save**."))
+            }],
+        };
+
+        let markdown = render_markdown(&recording, &[], Locale::En);
+
+        assert!(markdown.contains(
+            "![Step 1](</shots/full.jpg>)\n\n![Step 1 detail](</shots/full_element.jpg>)"
+        ));
+    }
+
+    #[test]
+    fn render_markdown_uses_the_locale_for_generated_text() {
+        let recording = RecordingWithSteps {
+            recording: Recording::default(),
+            steps: vec![
+                Step {
+                    element_name: Some("Speichern".to_string()),
+                    screenshot_path: Some("/shots/full.jpg".to_string()),
+                    ..step("click", None, None)
+                },
+                step("note", None, Some("Änderungen werden sofort wirksam.")),
+            ],
+        };
+
+        let markdown = render_markdown(&recording, &[], Locale::De);
+
+        assert!(markdown.contains(
+            "### Schritt 1\n\nKlicken Sie auf „Speichern“.\n\n![Schritt 1](</shots/full.jpg>)"
+        ));
+        assert!(markdown.contains("> **Hinweis:** Änderungen werden sofort wirksam."));
+    }
+
+    #[test]
+    fn fallback_description_mentions_field_and_toggle_state() {
+        let mut toggle = step("click", None, None);
+        toggle.element_name = Some("Remember me".to_string());
+        toggle.element_value = Some("checked".to_string());
+        let mut typed = step("type", None, None);
+        typed.element_name = Some("Email".to_string());
+        typed.text = Some("ada@example.com".to_string());
+
+        assert_eq!(
+            fallback_description(&toggle, Locale::En.catalog()),
+            "Click \"Remember me\" (now checked)."
+        );
+        assert_eq!(
+            fallback_description(&typed, Locale::En.catalog()),
+            "Type \"ada@example.com\" into \"Email\"."
+        );
+
+        toggle.action = Some("Uncheck \"Remember me\"".to_string());
+        assert_eq!(
+            fallback_description(&toggle, Locale::En.catalog()),
+            "Uncheck \"Remember me\"."
+        );
+    }
+
+    #[test]
+    fn rewrite_image_links_replaces_only_image_targets() {
+        let markdown = "Open [the docs](<https://example.com>).\n\n![Step 1](</shots/a.jpg>)\n\n![Step 1 detail](</shots/b.jpg>)\n";
+        let rewritten = rewrite_image_links(markdown, |path| {
+            (path == "/shots/a.jpg").then(|| "images/01.jpg".to_string())
+        });
+        assert_eq!(
+            rewritten,
+            "Open [the docs](<https://example.com>).\n\n![Step 1](<images/01.jpg>)\n\n![Step 1 detail](</shots/b.jpg>)\n"
+        );
+    }
+}
@@ -0,0 +1,318 @@
+//! Third-party exporters loaded from dynamic libraries.
+//!
+//! Dropping a library (`.dll`, `.dylib` or `.so`) into
+//! `<app_data_dir>/plugins/exporters` adds an output format, listed in the
+//! export menu from the next start. The contract is plain C and JSON, so a
+//! plugin can be written in any language that builds a C library:
+//!
+//! - `const char *stepsnap_exporter_manifest(void)` returns a static JSON
+//!   object: `{"api_version": 1, "id": "acme-wiki", "name": "Acme Wiki",
+//!   "extension": "xml", "description": "..."}`.
+//! - `char *stepsnap_exporter_export(const char *request)` takes a JSON
+//!   object with `api_version`, `recording` (the recording and its steps, as
+//!   `get_recording` returns them), `markdown` (the rendered document, with
+//!   annotated screenshots) and `output_path`, writes the output there, and
+//!   returns `{"error": null}` or `{"error": "message"}`.
+//! - `void stepsnap_exporter_free(char *response)` frees what the export
+//!   returned.
+//!
+//! Plugins run in-process with the app's permissions, so only trusted ones
+//! should be installed. Libraries that don't export the three functions or
+//! were built for another `API_VERSION` are skipped with a warning.
+
+use crate::database::RecordingWithSteps;
+use crate::db_worker::DatabaseWorker;
+use crate::settings::SettingsState;
+use crate::{analytics, render_markdown_source, ExportStyles, MarkdownSource};
+use libloading::{Library, Symbol};
+use serde::{Deserialize, Serialize};
+use std::ffi::{c_char, CStr, CString};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use tauri::{AppHandle, Manager};
+
+/// Version of the JSON contract this build speaks
+pub const API_VERSION: u32 = 1;
+const MANIFEST_SYMBOL: &[u8] = b"stepsnap_exporter_manifest\0";
+const EXPORT_SYMBOL: &[u8] = b"stepsnap_exporter_export\0";
+const FREE_SYMBOL: &[u8] = b"stepsnap_exporter_free\0";
+
+type ManifestFn = unsafe extern "C" fn() -> *const c_char;
+type ExportFn = unsafe extern "C" fn(*const c_char) -> *mut c_char;
+type FreeFn = unsafe extern "C" fn(*mut c_char);
+
+/// What a plugin says about itself
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ExporterManifest {
+    pub api_version: u32,
+    pub id: String,
+    pub name: String,
+    /// File extension of the output, without the dot
+    pub extension: String,
+    #[serde(default)]
+    pub description: String,
+}
+
+/// A loaded plugin as the export menu lists it
+#[derive(Clone, Debug, Serialize)]
+pub struct ExporterPluginInfo {
+    #[serde(flatten)]
+    pub manifest: ExporterManifest,
+    pub path: PathBuf,
+}
+
+#[derive(Serialize)]
+struct ExportRequest<'a> {
+    api_version: u32,
+    recording: &'a RecordingWithSteps,
+    markdown: &'a str,
+    output_path: &'a str,
+}
+
+#[derive(Deserialize)]
+struct ExportResponse {
+    error: Option<String>,
+}
+
+struct ExporterPlugin {
+    manifest: ExporterManifest,
+    path: PathBuf,
+    library: Library,
+}
+
+impl ExporterPlugin {
+    fn info(&self) -> ExporterPluginInfo {
+        ExporterPluginInfo {
+            manifest: self.manifest.clone(),
+            path: self.path.clone(),
+        }
+    }
+}
+
+static PLUGINS: Mutex<Vec<ExporterPlugin>> = Mutex::new(Vec::new());
+
+fn plugins_dir(app: &AppHandle) -> Result<PathBuf, String> {
+    let app_data_dir = app.path().app_data_dir().map_err(|e| e.to_string())?;
+    Ok(app_data_dir.join("plugins").join("exporters"))
+}
+
+fn is_library(path: &Path) -> bool {
+    matches!(
+        path.extension().and_then(|extension| extension.to_str()),
+        Some("dll" | "dylib" | "so")
+    )
+}
+
+/// A manifest the app can use: this contract version, and an id and
+/// extension that are safe in menus and file names
+fn parse_manifest(json: &str) -> Result<ExporterManifest, String> {
+    let manifest: ExporterManifest =
+        serde_json::from_str(json).map_err(|e| format!("Invalid manifest: {}", e))?;
+    if manifest.api_version != API_VERSION {
+        return Err(format!(
+            "Built for exporter API {}, this version of StepSnap speaks {}",
+            manifest.api_version, API_VERSION
+        ));
+    }
+    let identifier = |value: &str| {
+        !value.is_empty()
+            && value
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_'))
+    };
+    if !identifier(&manifest.id) || !identifier(&manifest.extension) {
+        return Err("The manifest's id and extension must be letters, digits, - or _".to_string());
+    }
+    if manifest.name.trim().is_empty() {
+        return Err("The manifest has no name".to_string());
+    }
+    Ok(manifest)
+}
+
+fn load(path: &Path) -> Result<ExporterPlugin, String> {
+    // Loading runs the library's initializers; the plugins directory is
+    // trusted the same way an installed app is
+    let library = unsafe { Library::new(path) }.map_err(|e| e.to_string())?;
+    let manifest = unsafe {
+        let manifest: Symbol<ManifestFn> =
+            library.get(MANIFEST_SYMBOL).map_err(|e| e.to_string())?;
+        library
+            .get::<ExportFn>(EXPORT_SYMBOL)
+            .map_err(|e| e.to_string())?;
+        library
+            .get::<FreeFn>(FREE_SYMBOL)
+            .map_err(|e| e.to_string())?;
+        let json = manifest();
+        if json.is_null() {
+            return Err("The manifest is missing".to_string());
+        }
+        CStr::from_ptr(json).to_string_lossy().into_owned()
+    };
+    Ok(ExporterPlugin {
+        manifest: parse_manifest(&manifest)?,
+        path: path.to_path_buf(),
+        library,
+    })
+}
+
+/// Load every plugin in the plugins directory, replacing those loaded before
+fn discover(app: &AppHandle) -> Result<Vec<ExporterPluginInfo>, String> {
+    let dir = plugins_dir(app)?;
+    let mut loaded: Vec<ExporterPlugin> = Vec::new();
+    if let Ok(entries) = std::fs::read_dir(&dir) {
+        let mut paths: Vec<PathBuf> = entries
+            .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+            .filter(|path| is_library(path))
+            .collect();
+        paths.sort();
+        for path in paths {
+            match load(&path) {
+                Ok(plugin) if loaded.iter().any(|p| p.manifest.id == plugin.manifest.id) => {
+                    tracing::warn!(path = %path.display(), id = %plugin.manifest.id, "Skipped exporter plugin with a duplicate id");
+                }
+                Ok(plugin) => {
+                    tracing::info!(path = %path.display(), id = %plugin.manifest.id, "Loaded exporter plugin");
+                    loaded.push(plugin);
+                }
+                Err(e) => {
+                    tracing::warn!(path = %path.display(), "Skipped exporter plugin: {}", e)
+                }
+            }
+        }
+    }
+    let infos = loaded.iter().map(ExporterPlugin::info).collect();
+    *PLUGINS.lock().unwrap() = loaded;
+    Ok(infos)
+}
+
+/// Load the installed plugins
+pub fn init(app: &AppHandle) {
+    match discover(app) {
+        Ok(plugins) if !plugins.is_empty() => {
+            tracing::info!(count = plugins.len(), "Exporter plugins ready")
+        }
+        Ok(_) => {}
+        Err(e) => tracing::warn!("Failed to load exporter plugins: {}", e),
+    }
+}
+
+fn run(plugin: &ExporterPlugin, request: &str) -> Result<(), String> {
+    let request = CString::new(request).map_err(|e| e.to_string())?;
+    let response = unsafe {
+        let export: Symbol<ExportFn> = plugin
+            .library
+            .get(EXPORT_SYMBOL)
+            .map_err(|e| e.to_string())?;
+        let free: Symbol<FreeFn> = plugin.library.get(FREE_SYMBOL).map_err(|e| e.to_string())?;
+        let response = export(request.as_ptr());
+        if response.is_null() {
+            return Err(format!("{} returned nothing", plugin.manifest.name));
+        }
+        let text = CStr::from_ptr(response).to_string_lossy().into_owned();
+        free(response);
+        text
+    };
+    let response: ExportResponse = serde_json::from_str(&response).map_err(|e| {
+        format!(
+            "{} returned an invalid response: {}",
+            plugin.manifest.name, e
+        )
+    })?;
+    match response.error {
+        Some(error) => Err(format!("{}: {}", plugin.manifest.name, error)),
+        None => Ok(()),
+    }
+}
+
+/// The exporter plugins loaded at startup
+#[tauri::command]
+pub fn list_exporter_plugins() -> Vec<ExporterPluginInfo> {
+    PLUGINS
+        .lock()
+        .unwrap()
+        .iter()
+        .map(ExporterPlugin::info)
+        .collect()
+}
+
+/// Look for plugins again, for ones installed since startup
+#[tauri::command]
+pub async fn reload_exporter_plugins(app: AppHandle) -> Result<Vec<ExporterPluginInfo>, String> {
+    tauri::async_runtime::spawn_blocking(move || discover(&app))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+/// Export `recording_id` to `output_path` with the plugin `plugin_id`
+#[tauri::command]
+pub async fn export_with_plugin(
+    app: AppHandle,
+    plugin_id: String,
+    recording_id: String,
+    output_path: String,
+) -> Result<(), String> {
+    let (task_plugin, task_recording) = (plugin_id.clone(), recording_id.clone());
+    tauri::async_runtime::spawn_blocking(move || {
+        let (locale, styles) = {
+            let settings = app.state::<SettingsState>();
+            let settings = settings.0.lock().unwrap();
+            (settings.locale, ExportStyles::from_settings(&settings))
+        };
+        // Plugins get the recording as stored, and the rendered screenshots
+        // through the Markdown
+        let (recording, source) = app.state::<DatabaseWorker>().run_blocking(move |db| {
+            let source = MarkdownSource::load(db, &task_recording)?;
+            let recording = db
+                .get_recording(&task_recording)
+                .map_err(|e| e.to_string())?
+                .ok_or_else(|| format!("Recording not found: {}", task_recording))?;
+            Ok((recording, source))
+        })?;
+        let markdown = render_markdown_source(source, locale, styles);
+        let request = serde_json::to_string(&ExportRequest {
+            api_version: API_VERSION,
+            recording: &recording,
+            markdown: &markdown,
+            output_path: &output_path,
+        })
+        .map_err(|e| e.to_string())?;
+
+        let plugins = PLUGINS.lock().unwrap();
+        let plugin = plugins
+            .iter()
+            .find(|plugin| plugin.manifest.id == task_plugin)
+            .ok_or_else(|| format!("Exporter plugin not found: {}", task_plugin))?;
+        run(plugin, &request)
+    })
+    .await
+    .map_err(|e| e.to_string())??;
+
+    analytics::track("plugin_export");
+    tracing::info!(recording_id = %recording_id, plugin = %plugin_id, "Exported with plugin");
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn validates_manifests() {
+        let manifest = parse_manifest(
+            r#"{"api_version": 1, "id": "acme-wiki", "name": "Acme Wiki", "extension": "xml"}"#,
+        )
+        .unwrap();
+        assert_eq!(manifest.id, "acme-wiki");
+        assert_eq!(manifest.description, "");
+
+        assert!(parse_manifest(
+            r#"{"api_version": 2, "id": "acme", "name": "Acme", "extension": "xml"}"#
+        )
+        .is_err());
+        assert!(parse_manifest(
+            r#"{"api_version": 1, "id": "../acme", "name": "Acme", "extension": "xml"}"#
+        )
+        .is_err());
+        assert!(parse_manifest(r#"{"api_version": 1}"#).is_err());
+    }
+}
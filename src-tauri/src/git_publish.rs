@@ -0,0 +1,249 @@
+//! Publishing recordings into a Git repository, for teams that keep their
+//! docs next to their code.
+//!
+//! A recording is written to the configured folder of a local clone as
+//! Markdown with its screenshots (see `write_markdown_folder`), committed
+//! with a message filled in from a template, and optionally pushed. Git is
+//! run as a command, so the user's own configuration, credential helpers and
+//! SSH keys apply; it never prompts, so a push that needs credentials it
+//! doesn't have fails instead of hanging.
+
+use crate::database::Database;
+use crate::db_worker::DatabaseWorker;
+use crate::i18n::fill;
+use crate::settings::SettingsState;
+use crate::{
+    analytics, render_markdown_source, write_markdown_folder, ExportStyles, MarkdownSource,
+};
+use serde::{Deserialize, Serialize};
+use std::path::{Component, Path, PathBuf};
+use std::process::{Command, Output};
+use tauri::{AppHandle, Manager};
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GitPublishSettings {
+    /// Local clone to publish into; empty until set up
+    pub repo_path: String,
+    /// Folder within the repository the guides go in
+    pub directory: String,
+    /// `{name}`, `{id}`, `{steps}` and `{date}` are filled in
+    pub commit_message: String,
+    pub push: bool,
+    /// Remote to push to; the branch's upstream when empty
+    pub remote: String,
+}
+
+impl Default for GitPublishSettings {
+    fn default() -> Self {
+        Self {
+            repo_path: String::new(),
+            directory: "docs".to_string(),
+            commit_message: "Update \"{name}\" guide".to_string(),
+            push: false,
+            remote: String::new(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct GitPublishResult {
+    /// Hash of the new commit; `None` when the guide was already up to date
+    commit: Option<String>,
+    pushed: bool,
+}
+
+/// `directory` as a path inside the repository, refusing ones that leave it
+fn relative_directory(directory: &str) -> Result<PathBuf, String> {
+    let path = Path::new(directory.trim());
+    if path
+        .components()
+        .any(|component| !matches!(component, Component::Normal(_) | Component::CurDir))
+    {
+        return Err(format!(
+            "The folder must be inside the repository: {}",
+            directory
+        ));
+    }
+    Ok(path.to_path_buf())
+}
+
+fn commit_message(template: &str, name: &str, id: &str, steps: usize, date: &str) -> String {
+    let message = fill(
+        template,
+        &[
+            ("name", name),
+            ("id", id),
+            ("steps", &steps.to_string()),
+            ("date", date),
+        ],
+    );
+    if message.trim().is_empty() {
+        format!("Update \"{}\" guide", name)
+    } else {
+        message
+    }
+}
+
+fn git(repo: &Path, args: &[&str]) -> Result<Output, String> {
+    let mut command = Command::new("git");
+    command
+        .arg("-C")
+        .arg(repo)
+        .args(args)
+        .env("GIT_TERMINAL_PROMPT", "0");
+    #[cfg(target_os = "windows")]
+    {
+        use std::os::windows::process::CommandExt;
+        const CREATE_NO_WINDOW: u32 = 0x0800_0000;
+        command.creation_flags(CREATE_NO_WINDOW);
+    }
+    let output = command.output().map_err(|e| match e.kind() {
+        std::io::ErrorKind::NotFound => "Git isn't installed or isn't on the PATH".to_string(),
+        _ => format!("Failed to run git: {}", e),
+    })?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(format!("git {} failed: {}", args[0], stderr.trim()));
+    }
+    Ok(output)
+}
+
+fn with_paths<'a>(args: &[&'a str], paths: &[&'a str]) -> Vec<&'a str> {
+    args.iter().chain(paths).copied().collect()
+}
+
+fn stdout(output: &Output) -> String {
+    String::from_utf8_lossy(&output.stdout).trim().to_string()
+}
+
+fn publish(app: &AppHandle, recording_id: &str) -> Result<GitPublishResult, String> {
+    let (config, locale, styles) = {
+        let settings = app.state::<SettingsState>();
+        let settings = settings.0.lock().unwrap();
+        (
+            settings.git.clone(),
+            settings.locale,
+            ExportStyles::from_settings(&settings),
+        )
+    };
+    if config.repo_path.trim().is_empty() {
+        return Err("Choose a Git repository to publish to in Settings".to_string());
+    }
+    let repo = PathBuf::from(
+        git(
+            Path::new(config.repo_path.trim()),
+            &["rev-parse", "--show-toplevel"],
+        )
+        .map(|output| stdout(&output))
+        .map_err(|_| format!("Not a Git repository: {}", config.repo_path))?,
+    );
+    let directory = relative_directory(&config.directory)?;
+
+    let id = recording_id.to_string();
+    let source = app
+        .state::<DatabaseWorker>()
+        .run_blocking(move |db| MarkdownSource::load(db, &id))?;
+    let name = source.recording.recording.name.clone();
+    let step_count = source.recording.steps.len();
+    let markdown = render_markdown_source(source, locale, styles);
+    let stem = Database::sanitize_dirname_public(&name);
+    write_markdown_folder(&markdown, &repo.join(&directory), &stem)?;
+
+    // Only this guide's files, so anything else the user has staged stays out
+    let git_path = |path: PathBuf| path.to_string_lossy().replace('\\', "/");
+    let mut paths = vec![git_path(directory.join(format!("{}.md", stem)))];
+    // Naming a folder that neither exists nor was committed is an error
+    let images = git_path(directory.join("images").join(&stem));
+    let tracked_images = git(&repo, &["ls-files", "--", &images])?;
+    if repo.join(&images).exists() || !stdout(&tracked_images).is_empty() {
+        paths.push(images);
+    }
+    let pathspec: Vec<&str> = paths.iter().map(String::as_str).collect();
+
+    git(&repo, &with_paths(&["add", "--all", "--"], &pathspec))?;
+    let changes = git(
+        &repo,
+        &with_paths(&["status", "--porcelain", "--"], &pathspec),
+    )?;
+    if stdout(&changes).is_empty() {
+        tracing::info!(recording_id, "Git guide already up to date");
+        return Ok(GitPublishResult {
+            commit: None,
+            pushed: false,
+        });
+    }
+
+    let date = chrono::Local::now().format("%Y-%m-%d").to_string();
+    let message = commit_message(
+        &config.commit_message,
+        &name,
+        recording_id,
+        step_count,
+        &date,
+    );
+    git(
+        &repo,
+        &with_paths(&["commit", "--message", &message, "--"], &pathspec),
+    )?;
+    let commit = stdout(&git(&repo, &["rev-parse", "HEAD"])?);
+    analytics::track("git_publish");
+    tracing::info!(recording_id, commit = %commit, "Recording committed to Git");
+
+    if config.push {
+        let remote = config.remote.trim();
+        if remote.is_empty() {
+            git(&repo, &["push"])?;
+        } else {
+            git(&repo, &["push", remote, "HEAD"])?;
+        }
+        tracing::info!(recording_id, "Pushed the guide");
+    }
+    Ok(GitPublishResult {
+        commit: Some(commit),
+        pushed: config.push,
+    })
+}
+
+/// Write `recording_id` into the configured repository, commit it and push
+/// if enabled
+#[tauri::command]
+pub async fn publish_recording_to_git(
+    app: AppHandle,
+    recording_id: String,
+) -> Result<GitPublishResult, String> {
+    tauri::async_runtime::spawn_blocking(move || publish(&app, &recording_id))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keeps_guides_inside_the_repository_with_filled_in_messages() {
+        assert_eq!(
+            relative_directory("docs/guides").unwrap(),
+            PathBuf::from("docs/guides")
+        );
+        assert_eq!(relative_directory("").unwrap(), PathBuf::new());
+        assert!(relative_directory("../elsewhere").is_err());
+        assert!(relative_directory("/etc").is_err());
+
+        assert_eq!(
+            commit_message(
+                "docs: {name} ({steps} steps, {date})",
+                "Reset",
+                "r1",
+                4,
+                "2026-10-16"
+            ),
+            "docs: Reset (4 steps, 2026-10-16)"
+        );
+        assert_eq!(
+            commit_message("  ", "Reset", "r1", 4, "2026-10-16"),
+            "Update \"Reset\" guide"
+        );
+    }
+}
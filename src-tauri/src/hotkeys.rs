@@ -0,0 +1,347 @@
+//! Global hotkeys: the actions they trigger and the keys they can use.
+//!
+//! Each `HotkeyAction` has at most one binding. Pressing it emits the
+//! action's event to the frontend, which decides what it means in the
+//! current state, e.g. undo is ignored while not recording.
+//!
+//! Bindings store the browser's `KeyboardEvent.code` for the key, e.g.
+//! `KeyR` or `NumpadAdd`. `SUPPORTED_KEYS` maps each one the global shortcut
+//! plugin understands to its `Code`, along with a label and group for the
+//! settings UI, so the frontend never offers a key the backend would reject.
+//!
+//! Spare mouse buttons can be bound too. The global shortcut plugin only
+//! knows keyboards, so those bindings are never registered with it; the
+//! recorder's input listener matches them instead and emits the same events.
+
+use crate::recorder::HotkeyBinding;
+use rdev::Button;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use tauri_plugin_global_shortcut::Code;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HotkeyAction {
+    Start,
+    Stop,
+    /// Open the capture picker for a manual screenshot
+    Capture,
+    /// Pause recording, or resume it when paused
+    Pause,
+    /// Remove the last recorded step
+    Undo,
+    /// Add a marker step
+    Marker,
+}
+
+impl HotkeyAction {
+    pub const ALL: [HotkeyAction; 6] = [
+        HotkeyAction::Start,
+        HotkeyAction::Stop,
+        HotkeyAction::Capture,
+        HotkeyAction::Pause,
+        HotkeyAction::Undo,
+        HotkeyAction::Marker,
+    ];
+
+    /// Event emitted to the frontend when the hotkey is pressed
+    pub fn event(self) -> &'static str {
+        match self {
+            HotkeyAction::Start => "hotkey-start",
+            HotkeyAction::Stop => "hotkey-stop",
+            HotkeyAction::Capture => "hotkey-capture",
+            HotkeyAction::Pause => "hotkey-pause",
+            HotkeyAction::Undo => "hotkey-undo",
+            HotkeyAction::Marker => "hotkey-marker",
+        }
+    }
+
+    /// Ctrl+Alt plus a letter, until the settings are synced
+    fn default_key(self) -> &'static str {
+        match self {
+            HotkeyAction::Start => "KeyR",
+            HotkeyAction::Stop => "KeyS",
+            HotkeyAction::Capture => "KeyC",
+            HotkeyAction::Pause => "KeyP",
+            HotkeyAction::Undo => "KeyZ",
+            HotkeyAction::Marker => "KeyM",
+        }
+    }
+}
+
+/// Why a binding can't be registered
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum HotkeyConflict {
+    /// The key isn't one of `supported_keys`
+    UnsupportedKey { key: String },
+    /// Another OpenScribe action already uses the binding
+    Action { action: HotkeyAction },
+    /// The OS or another application holds the shortcut. Only detected
+    /// where registering it fails; some platforms allow sharing.
+    System { message: String },
+}
+
+/// An action whose hotkey couldn't be registered
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct HotkeyFailure {
+    pub action: HotkeyAction,
+    pub conflict: HotkeyConflict,
+}
+
+/// The first action other than `except` bound to the same keys as
+/// `binding`, even if it's disabled
+pub fn action_with_binding(
+    bindings: &HashMap<HotkeyAction, HotkeyBinding>,
+    binding: &HotkeyBinding,
+    except: Option<HotkeyAction>,
+) -> Option<HotkeyAction> {
+    HotkeyAction::ALL
+        .into_iter()
+        .filter(|&action| Some(action) != except)
+        .find(|action| bindings.get(action).is_some_and(|b| b.same_keys(binding)))
+}
+
+/// The enabled action `pressed` triggers
+pub fn action_for_press(
+    bindings: &HashMap<HotkeyAction, HotkeyBinding>,
+    pressed: &HotkeyBinding,
+) -> Option<HotkeyAction> {
+    HotkeyAction::ALL.into_iter().find(|action| {
+        bindings
+            .get(action)
+            .is_some_and(|b| !b.disabled && b.same_keys(pressed))
+    })
+}
+
+/// The binding of every action before the frontend syncs its settings
+pub fn default_bindings() -> HashMap<HotkeyAction, HotkeyBinding> {
+    HotkeyAction::ALL
+        .into_iter()
+        .map(|action| {
+            let binding = HotkeyBinding {
+                ctrl: true,
+                shift: false,
+                alt: true,
+                meta: false,
+                key: action.default_key().to_string(),
+                disabled: false,
+            };
+            (action, binding)
+        })
+        .collect()
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum KeyGroup {
+    Letter,
+    Digit,
+    Function,
+    Navigation,
+    Editing,
+    Punctuation,
+    Numpad,
+    Media,
+    Mouse,
+}
+
+/// A bindable key as listed by `list_supported_hotkeys`
+#[derive(Clone, Copy, Debug, Serialize)]
+pub struct SupportedKey {
+    /// `KeyboardEvent.code`, as stored in `HotkeyBinding::key`
+    pub code: &'static str,
+    pub label: &'static str,
+    pub group: KeyGroup,
+}
+
+macro_rules! keys {
+    ($($group:ident: $($code:ident => $label:literal),+ $(,)?;)+) => {
+        &[$($((stringify!($code), $label, KeyGroup::$group, Code::$code),)+)+]
+    };
+}
+
+/// `(KeyboardEvent.code, label, group, Code)` for every bindable key
+const SUPPORTED_KEYS: &[(&str, &str, KeyGroup, Code)] = keys! {
+    Letter:
+        KeyA => "A", KeyB => "B", KeyC => "C", KeyD => "D", KeyE => "E", KeyF => "F",
+        KeyG => "G", KeyH => "H", KeyI => "I", KeyJ => "J", KeyK => "K", KeyL => "L",
+        KeyM => "M", KeyN => "N", KeyO => "O", KeyP => "P", KeyQ => "Q", KeyR => "R",
+        KeyS => "S", KeyT => "T", KeyU => "U", KeyV => "V", KeyW => "W", KeyX => "X",
+        KeyY => "Y", KeyZ => "Z";
+    Digit:
+        Digit0 => "0", Digit1 => "1", Digit2 => "2", Digit3 => "3", Digit4 => "4",
+        Digit5 => "5", Digit6 => "6", Digit7 => "7", Digit8 => "8", Digit9 => "9";
+    Function:
+        F1 => "F1", F2 => "F2", F3 => "F3", F4 => "F4", F5 => "F5", F6 => "F6",
+        F7 => "F7", F8 => "F8", F9 => "F9", F10 => "F10", F11 => "F11", F12 => "F12",
+        F13 => "F13", F14 => "F14", F15 => "F15", F16 => "F16", F17 => "F17", F18 => "F18",
+        F19 => "F19", F20 => "F20", F21 => "F21", F22 => "F22", F23 => "F23", F24 => "F24";
+    Navigation:
+        ArrowUp => "Up", ArrowDown => "Down", ArrowLeft => "Left", ArrowRight => "Right",
+        Home => "Home", End => "End", PageUp => "Page Up", PageDown => "Page Down";
+    Editing:
+        Space => "Space", Enter => "Enter", Escape => "Esc", Backspace => "Backspace",
+        Tab => "Tab", Insert => "Insert", Delete => "Delete", PrintScreen => "Print Screen",
+        ScrollLock => "Scroll Lock", Pause => "Pause";
+    Punctuation:
+        Minus => "-", Equal => "=", BracketLeft => "[", BracketRight => "]",
+        Backslash => "\\", Semicolon => ";", Quote => "'", Backquote => "`",
+        Comma => ",", Period => ".", Slash => "/";
+    Numpad:
+        Numpad0 => "Num 0", Numpad1 => "Num 1", Numpad2 => "Num 2", Numpad3 => "Num 3",
+        Numpad4 => "Num 4", Numpad5 => "Num 5", Numpad6 => "Num 6", Numpad7 => "Num 7",
+        Numpad8 => "Num 8", Numpad9 => "Num 9", NumpadAdd => "Num +",
+        NumpadSubtract => "Num -", NumpadMultiply => "Num *", NumpadDivide => "Num /",
+        NumpadDecimal => "Num .", NumpadEnter => "Num Enter", NumpadEqual => "Num =";
+    Media:
+        MediaPlayPause => "Play/Pause", MediaStop => "Stop", MediaTrackNext => "Next Track",
+        MediaTrackPrevious => "Previous Track", AudioVolumeUp => "Volume Up",
+        AudioVolumeDown => "Volume Down", AudioVolumeMute => "Mute";
+};
+
+/// `(HotkeyBinding::key, label)` for every bindable mouse button
+const MOUSE_BUTTONS: &[(&str, &str)] = &[
+    ("MouseMiddle", "Middle Click"),
+    ("Mouse4", "Mouse 4"),
+    ("Mouse5", "Mouse 5"),
+];
+
+/// Whether `key` is a mouse button, matched by the input listener rather
+/// than registered as a global shortcut
+pub fn is_mouse_button(key: &str) -> bool {
+    MOUSE_BUTTONS.iter().any(|&(code, _)| code == key)
+}
+
+/// The `HotkeyBinding::key` for a mouse button rdev reports, None for the
+/// left and right buttons, which can't be bound
+pub fn mouse_button_key(button: Button) -> Option<&'static str> {
+    // rdev passes the side buttons through with the platform's numbering
+    const SIDE_BUTTONS: (u8, u8) = if cfg!(target_os = "windows") {
+        (1, 2)
+    } else if cfg!(target_os = "macos") {
+        (3, 4)
+    } else {
+        (8, 9)
+    };
+    match button {
+        Button::Middle => Some("MouseMiddle"),
+        Button::Unknown(n) if n == SIDE_BUTTONS.0 => Some("Mouse4"),
+        Button::Unknown(n) if n == SIDE_BUTTONS.1 => Some("Mouse5"),
+        _ => None,
+    }
+}
+
+/// Whether `key` can be bound, to the keyboard or the mouse
+pub fn is_supported_key(key: &str) -> bool {
+    code_for(key).is_some() || is_mouse_button(key)
+}
+
+/// The `Code` for a `KeyboardEvent.code`, None for keys that can't be bound
+pub fn code_for(key: &str) -> Option<Code> {
+    SUPPORTED_KEYS
+        .iter()
+        .find(|(code, ..)| *code == key)
+        .map(|&(.., code)| code)
+}
+
+/// Every bindable key, grouped in the order the UI lists them
+pub fn supported_keys() -> Vec<SupportedKey> {
+    SUPPORTED_KEYS
+        .iter()
+        .map(|&(code, label, group, _)| SupportedKey { code, label, group })
+        .chain(MOUSE_BUTTONS.iter().map(|&(code, label)| SupportedKey {
+            code,
+            label,
+            group: KeyGroup::Mouse,
+        }))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn browser_codes_map_to_shortcut_codes() {
+        assert_eq!(code_for("KeyR"), Some(Code::KeyR));
+        assert_eq!(code_for("NumpadAdd"), Some(Code::NumpadAdd));
+        assert_eq!(code_for("PageDown"), Some(Code::PageDown));
+        assert_eq!(code_for("MediaPlayPause"), Some(Code::MediaPlayPause));
+        assert_eq!(code_for("Backquote"), Some(Code::Backquote));
+        assert_eq!(code_for("Fn"), None);
+        assert_eq!(code_for("keyr"), None);
+    }
+
+    #[test]
+    fn spare_mouse_buttons_are_bindable() {
+        assert_eq!(mouse_button_key(Button::Middle), Some("MouseMiddle"));
+        assert_eq!(mouse_button_key(Button::Left), None);
+        assert_eq!(mouse_button_key(Button::Right), None);
+        assert!(is_mouse_button("Mouse4"));
+        assert!(!is_mouse_button("KeyM"));
+        assert_eq!(code_for("Mouse5"), None);
+    }
+
+    #[test]
+    fn every_action_has_a_distinct_default() {
+        let defaults = default_bindings();
+        assert_eq!(defaults.len(), HotkeyAction::ALL.len());
+        let mut keys: Vec<_> = defaults.values().map(|b| b.key.as_str()).collect();
+        keys.sort_unstable();
+        keys.dedup();
+        assert_eq!(keys.len(), HotkeyAction::ALL.len());
+        assert!(keys.iter().all(|key| code_for(key).is_some()));
+    }
+
+    #[test]
+    fn other_actions_with_the_same_binding_conflict() {
+        let bindings = default_bindings();
+        let capture = bindings[&HotkeyAction::Capture].clone();
+        assert_eq!(
+            action_with_binding(&bindings, &capture, None),
+            Some(HotkeyAction::Capture)
+        );
+        // An action never conflicts with its own binding
+        assert_eq!(
+            action_with_binding(&bindings, &capture, Some(HotkeyAction::Capture)),
+            None
+        );
+        let with_shift = HotkeyBinding {
+            shift: true,
+            ..capture
+        };
+        assert_eq!(action_with_binding(&bindings, &with_shift, None), None);
+    }
+
+    #[test]
+    fn disabled_bindings_conflict_but_never_fire() {
+        let mut bindings = default_bindings();
+        let undo = bindings.get_mut(&HotkeyAction::Undo).unwrap();
+        undo.disabled = true;
+        let pressed = HotkeyBinding {
+            disabled: false,
+            ..undo.clone()
+        };
+        assert_eq!(action_for_press(&bindings, &pressed), None);
+        assert_eq!(
+            action_with_binding(&bindings, &pressed, None),
+            Some(HotkeyAction::Undo)
+        );
+        let capture = bindings[&HotkeyAction::Capture].clone();
+        assert_eq!(
+            action_for_press(&bindings, &capture),
+            Some(HotkeyAction::Capture)
+        );
+    }
+
+    #[test]
+    fn every_supported_key_is_listed_once() {
+        let keys = supported_keys();
+        let mut codes: Vec<_> = keys.iter().map(|key| key.code).collect();
+        codes.sort_unstable();
+        codes.dedup();
+        assert_eq!(codes.len(), keys.len());
+        assert!(keys.iter().all(|key| is_supported_key(key.code)));
+    }
+}
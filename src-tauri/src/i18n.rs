@@ -0,0 +1,499 @@
+//! Localised text the backend writes into recordings and documents.
+//!
+//! Default step descriptions, inferred click actions, the Markdown export's
+//! headings and callouts, and the dates in generated names come from the
+//! `Catalog` of the `locale` setting, as does the notification about a
+//! recording recovered after a crash. Templates name their placeholders
+//! (`{name}`) so each language can order them, and carry their own quote
+//! marks. Text the user or the AI wrote is never translated.
+
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicU8, Ordering};
+
+/// Language of generated text
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Locale {
+    #[default]
+    En,
+    De,
+    Fr,
+    Es,
+}
+
+/// The locale generated text uses unless a caller passes one; see `set_locale`
+static CURRENT: AtomicU8 = AtomicU8::new(0);
+
+const LOCALES: [Locale; 4] = [Locale::En, Locale::De, Locale::Fr, Locale::Es];
+
+pub fn set_locale(locale: Locale) {
+    let index = LOCALES.iter().position(|l| *l == locale).unwrap_or(0);
+    CURRENT.store(index as u8, Ordering::Relaxed);
+}
+
+pub fn current() -> Locale {
+    LOCALES
+        .get(CURRENT.load(Ordering::Relaxed) as usize)
+        .copied()
+        .unwrap_or_default()
+}
+
+/// Every generated string, as templates with named placeholders
+pub struct Catalog {
+    // Markdown export
+    pub step: &'static str,
+    pub step_titled: &'static str,
+    pub step_detail: &'static str,
+    pub note: &'static str,
+    pub warning: &'static str,
+    pub before_you_begin: &'static str,
+    pub complete_first: &'static str,
+    pub see_also: &'static str,
+    /// Prefixes a sentence with the page it happens on
+    pub on_page: &'static str,
+    /// Whether the sentence after `on_page` starts in lowercase
+    pub on_page_lowercase: bool,
+    pub go_to: &'static str,
+
+    // Descriptions of steps without one
+    pub click_named: &'static str,
+    pub click_named_state: &'static str,
+    pub click_highlighted: &'static str,
+    pub type_into: &'static str,
+    pub type_text: &'static str,
+    pub enter_recorded_text: &'static str,
+    pub verify_screen: &'static str,
+    pub state_checked: &'static str,
+    pub state_unchecked: &'static str,
+    pub state_mixed: &'static str,
+    pub state_selected: &'static str,
+    pub state_not_selected: &'static str,
+
+    // Default step descriptions (without the closing period)
+    pub describe_click_role: &'static str,
+    pub describe_click: &'static str,
+    pub describe_type_role: &'static str,
+    pub describe_type: &'static str,
+    pub describe_type_text: &'static str,
+    /// A section with a role label, e.g. "Billing group"
+    pub in_section_role: &'static str,
+    pub in_section: &'static str,
+    pub role_button: &'static str,
+    pub role_link: &'static str,
+    pub role_field: &'static str,
+    pub role_checkbox: &'static str,
+    pub role_option: &'static str,
+    pub role_tab: &'static str,
+    pub role_menu_item: &'static str,
+    pub role_drop_down: &'static str,
+    pub role_list_item: &'static str,
+    pub role_item: &'static str,
+    pub role_icon: &'static str,
+
+    // Inferred click actions (without the closing period)
+    pub action_check: &'static str,
+    pub action_uncheck: &'static str,
+    pub action_select_option: &'static str,
+    pub action_switch_tab: &'static str,
+    pub action_open_menu: &'static str,
+    pub action_close_menu: &'static str,
+    pub action_choose: &'static str,
+    pub action_open_drop_down: &'static str,
+    pub action_expand: &'static str,
+    pub action_collapse: &'static str,
+    pub action_select: &'static str,
+    pub action_follow_link: &'static str,
+
+    // Names and dates
+    pub recovered_recording: &'static str,
+    pub recovered_notification_title: &'static str,
+    pub recovered_notification: &'static str,
+    /// `chrono` format of a date with the time
+    pub date_time_format: &'static str,
+}
+
+const EN: Catalog = Catalog {
+    step: "Step {n}",
+    step_titled: "Step {n}: {title}",
+    step_detail: "Step {n} detail",
+    note: "Note",
+    warning: "Warning",
+    before_you_begin: "Before you begin",
+    complete_first: "Complete {recordings} first.",
+    see_also: "See also",
+    on_page: "On <{url}>, {sentence}",
+    on_page_lowercase: true,
+    go_to: "Go to <{url}>.",
+
+    click_named: "Click \"{name}\".",
+    click_named_state: "Click \"{name}\" (now {state}).",
+    click_highlighted: "Click the highlighted element.",
+    type_into: "Type \"{text}\" into \"{name}\".",
+    type_text: "Type \"{text}\".",
+    enter_recorded_text: "Enter the recorded text.",
+    verify_screen: "Verify the screen state shown below.",
+    state_checked: "checked",
+    state_unchecked: "unchecked",
+    state_mixed: "mixed",
+    state_selected: "selected",
+    state_not_selected: "not selected",
+
+    describe_click_role: "Click the \"{label}\" {role}{section}",
+    describe_click: "Click \"{label}\"{section}",
+    describe_type_role: "Type \"{text}\" into the \"{label}\" {role}{section}",
+    describe_type: "Type \"{text}\" into \"{label}\"{section}",
+    describe_type_text: "Type \"{text}\"",
+    in_section_role: " in the {section}",
+    in_section: " in \"{section}\"",
+    role_button: "button",
+    role_link: "link",
+    role_field: "field",
+    role_checkbox: "checkbox",
+    role_option: "option",
+    role_tab: "tab",
+    role_menu_item: "menu item",
+    role_drop_down: "drop-down",
+    role_list_item: "list item",
+    role_item: "item",
+    role_icon: "icon",
+
+    action_check: "Check \"{name}\"",
+    action_uncheck: "Uncheck \"{name}\"",
+    action_select_option: "Select the \"{name}\" option",
+    action_switch_tab: "Switch to the \"{name}\" tab",
+    action_open_menu: "Open the \"{name}\" menu",
+    action_close_menu: "Close the \"{name}\" menu",
+    action_choose: "Choose \"{name}\"",
+    action_open_drop_down: "Open the \"{name}\" drop-down",
+    action_expand: "Expand \"{name}\"",
+    action_collapse: "Collapse \"{name}\"",
+    action_select: "Select \"{name}\"",
+    action_follow_link: "Follow the \"{name}\" link",
+
+    recovered_recording: "Recovered recording {date}",
+    recovered_notification_title: "Recording recovered",
+    recovered_notification:
+        "{count} unsaved step(s) from an interrupted session were saved as \"{name}\".",
+    date_time_format: "%Y-%m-%d %H:%M",
+};
+
+// Role nouns carry the accusative article the templates need
+const DE: Catalog = Catalog {
+    step: "Schritt {n}",
+    step_titled: "Schritt {n}: {title}",
+    step_detail: "Schritt {n} Detail",
+    note: "Hinweis",
+    warning: "Warnung",
+    before_you_begin: "Bevor Sie beginnen",
+    complete_first: "Schließen Sie zuerst {recordings} ab.",
+    see_also: "Siehe auch",
+    on_page: "Auf <{url}>: {sentence}",
+    on_page_lowercase: false,
+    go_to: "Öffnen Sie <{url}>.",
+
+    click_named: "Klicken Sie auf „{name}“.",
+    click_named_state: "Klicken Sie auf „{name}“ (jetzt {state}).",
+    click_highlighted: "Klicken Sie auf das markierte Element.",
+    type_into: "Geben Sie „{text}“ in „{name}“ ein.",
+    type_text: "Geben Sie „{text}“ ein.",
+    enter_recorded_text: "Geben Sie den aufgezeichneten Text ein.",
+    verify_screen: "Prüfen Sie den unten gezeigten Bildschirmzustand.",
+    state_checked: "aktiviert",
+    state_unchecked: "deaktiviert",
+    state_mixed: "teilweise aktiviert",
+    state_selected: "ausgewählt",
+    state_not_selected: "nicht ausgewählt",
+
+    describe_click_role: "Klicken Sie auf {role} „{label}“{section}",
+    describe_click: "Klicken Sie auf „{label}“{section}",
+    describe_type_role: "Geben Sie „{text}“ in {role} „{label}“{section} ein",
+    describe_type: "Geben Sie „{text}“ in „{label}“{section} ein",
+    describe_type_text: "Geben Sie „{text}“ ein",
+    in_section_role: " im Bereich „{section}“",
+    in_section: " im Bereich „{section}“",
+    role_button: "die Schaltfläche",
+    role_link: "den Link",
+    role_field: "das Feld",
+    role_checkbox: "das Kontrollkästchen",
+    role_option: "die Option",
+    role_tab: "die Registerkarte",
+    role_menu_item: "den Menüeintrag",
+    role_drop_down: "die Auswahlliste",
+    role_list_item: "den Listeneintrag",
+    role_item: "das Element",
+    role_icon: "das Symbol",
+
+    action_check: "Aktivieren Sie „{name}“",
+    action_uncheck: "Deaktivieren Sie „{name}“",
+    action_select_option: "Wählen Sie die Option „{name}“",
+    action_switch_tab: "Wechseln Sie zur Registerkarte „{name}“",
+    action_open_menu: "Öffnen Sie das Menü „{name}“",
+    action_close_menu: "Schließen Sie das Menü „{name}“",
+    action_choose: "Wählen Sie „{name}“",
+    action_open_drop_down: "Öffnen Sie die Auswahlliste „{name}“",
+    action_expand: "Erweitern Sie „{name}“",
+    action_collapse: "Reduzieren Sie „{name}“",
+    action_select: "Wählen Sie „{name}“ aus",
+    action_follow_link: "Folgen Sie dem Link „{name}“",
+
+    recovered_recording: "Wiederhergestellte Aufnahme {date}",
+    recovered_notification_title: "Aufnahme wiederhergestellt",
+    recovered_notification: "{count} nicht gespeicherte(r) Schritt(e) aus einer unterbrochenen Sitzung wurde(n) als „{name}“ gespeichert.",
+    date_time_format: "%d.%m.%Y %H:%M",
+};
+
+const FR: Catalog = Catalog {
+    step: "Étape {n}",
+    step_titled: "Étape {n} : {title}",
+    step_detail: "Étape {n} (détail)",
+    note: "Remarque",
+    warning: "Avertissement",
+    before_you_begin: "Avant de commencer",
+    complete_first: "Effectuez d’abord {recordings}.",
+    see_also: "Voir aussi",
+    on_page: "Sur <{url}> : {sentence}",
+    on_page_lowercase: false,
+    go_to: "Accédez à <{url}>.",
+
+    click_named: "Cliquez sur « {name} ».",
+    click_named_state: "Cliquez sur « {name} » (désormais {state}).",
+    click_highlighted: "Cliquez sur l’élément mis en évidence.",
+    type_into: "Saisissez « {text} » dans « {name} ».",
+    type_text: "Saisissez « {text} ».",
+    enter_recorded_text: "Saisissez le texte enregistré.",
+    verify_screen: "Vérifiez l’état de l’écran ci-dessous.",
+    state_checked: "cochée",
+    state_unchecked: "décochée",
+    state_mixed: "partiellement cochée",
+    state_selected: "sélectionné",
+    state_not_selected: "non sélectionné",
+
+    describe_click_role: "Cliquez sur {role} « {label} »{section}",
+    describe_click: "Cliquez sur « {label} »{section}",
+    describe_type_role: "Saisissez « {text} » dans {role} « {label} »{section}",
+    describe_type: "Saisissez « {text} » dans « {label} »{section}",
+    describe_type_text: "Saisissez « {text} »",
+    in_section_role: " dans la section « {section} »",
+    in_section: " dans la section « {section} »",
+    role_button: "le bouton",
+    role_link: "le lien",
+    role_field: "le champ",
+    role_checkbox: "la case à cocher",
+    role_option: "l’option",
+    role_tab: "l’onglet",
+    role_menu_item: "l’élément de menu",
+    role_drop_down: "la liste déroulante",
+    role_list_item: "l’élément de liste",
+    role_item: "l’élément",
+    role_icon: "l’icône",
+
+    action_check: "Cochez « {name} »",
+    action_uncheck: "Décochez « {name} »",
+    action_select_option: "Sélectionnez l’option « {name} »",
+    action_switch_tab: "Passez à l’onglet « {name} »",
+    action_open_menu: "Ouvrez le menu « {name} »",
+    action_close_menu: "Fermez le menu « {name} »",
+    action_choose: "Choisissez « {name} »",
+    action_open_drop_down: "Ouvrez la liste déroulante « {name} »",
+    action_expand: "Développez « {name} »",
+    action_collapse: "Réduisez « {name} »",
+    action_select: "Sélectionnez « {name} »",
+    action_follow_link: "Suivez le lien « {name} »",
+
+    recovered_recording: "Enregistrement récupéré {date}",
+    recovered_notification_title: "Enregistrement récupéré",
+    recovered_notification: "{count} étape(s) non enregistrée(s) d’une session interrompue ont été enregistrées sous « {name} ».",
+    date_time_format: "%d/%m/%Y %H:%M",
+};
+
+const ES: Catalog = Catalog {
+    step: "Paso {n}",
+    step_titled: "Paso {n}: {title}",
+    step_detail: "Paso {n} (detalle)",
+    note: "Nota",
+    warning: "Advertencia",
+    before_you_begin: "Antes de empezar",
+    complete_first: "Complete primero {recordings}.",
+    see_also: "Véase también",
+    on_page: "En <{url}>: {sentence}",
+    on_page_lowercase: false,
+    go_to: "Vaya a <{url}>.",
+
+    click_named: "Haga clic en «{name}».",
+    click_named_state: "Haga clic en «{name}» (ahora {state}).",
+    click_highlighted: "Haga clic en el elemento resaltado.",
+    type_into: "Escriba «{text}» en «{name}».",
+    type_text: "Escriba «{text}».",
+    enter_recorded_text: "Escriba el texto grabado.",
+    verify_screen: "Compruebe el estado de la pantalla que se muestra abajo.",
+    state_checked: "marcada",
+    state_unchecked: "desmarcada",
+    state_mixed: "parcialmente marcada",
+    state_selected: "seleccionado",
+    state_not_selected: "no seleccionado",
+
+    describe_click_role: "Haga clic en {role} «{label}»{section}",
+    describe_click: "Haga clic en «{label}»{section}",
+    describe_type_role: "Escriba «{text}» en {role} «{label}»{section}",
+    describe_type: "Escriba «{text}» en «{label}»{section}",
+    describe_type_text: "Escriba «{text}»",
+    in_section_role: " en la sección «{section}»",
+    in_section: " en la sección «{section}»",
+    role_button: "el botón",
+    role_link: "el enlace",
+    role_field: "el campo",
+    role_checkbox: "la casilla",
+    role_option: "la opción",
+    role_tab: "la pestaña",
+    role_menu_item: "el elemento de menú",
+    role_drop_down: "la lista desplegable",
+    role_list_item: "el elemento de lista",
+    role_item: "el elemento",
+    role_icon: "el icono",
+
+    action_check: "Marque «{name}»",
+    action_uncheck: "Desmarque «{name}»",
+    action_select_option: "Seleccione la opción «{name}»",
+    action_switch_tab: "Cambie a la pestaña «{name}»",
+    action_open_menu: "Abra el menú «{name}»",
+    action_close_menu: "Cierre el menú «{name}»",
+    action_choose: "Elija «{name}»",
+    action_open_drop_down: "Abra la lista desplegable «{name}»",
+    action_expand: "Expanda «{name}»",
+    action_collapse: "Contraiga «{name}»",
+    action_select: "Seleccione «{name}»",
+    action_follow_link: "Siga el enlace «{name}»",
+
+    recovered_recording: "Grabación recuperada {date}",
+    recovered_notification_title: "Grabación recuperada",
+    recovered_notification:
+        "{count} paso(s) sin guardar de una sesión interrumpida se guardaron como «{name}».",
+    date_time_format: "%d/%m/%Y %H:%M",
+};
+
+impl Locale {
+    pub fn catalog(self) -> &'static Catalog {
+        match self {
+            Locale::En => &EN,
+            Locale::De => &DE,
+            Locale::Fr => &FR,
+            Locale::Es => &ES,
+        }
+    }
+
+    /// `timestamp_ms` as a local date and time in this locale's format
+    pub fn format_date_time(self, timestamp_ms: i64) -> String {
+        chrono::DateTime::from_timestamp_millis(timestamp_ms)
+            .map(|time| {
+                time.with_timezone(&chrono::Local)
+                    .format(self.catalog().date_time_format)
+                    .to_string()
+            })
+            .unwrap_or_default()
+    }
+
+    /// The language's English name, for prompts asking a model to write in it
+    pub fn english_name(self) -> &'static str {
+        match self {
+            Locale::En => "English",
+            Locale::De => "German",
+            Locale::Fr => "French",
+            Locale::Es => "Spanish",
+        }
+    }
+}
+
+impl Catalog {
+    /// The word for an element state the accessibility APIs report
+    /// ("checked", "not selected", ...), or the state itself if unknown
+    pub fn state<'a>(&'a self, state: &'a str) -> &'a str {
+        match state {
+            "checked" => self.state_checked,
+            "unchecked" => self.state_unchecked,
+            "mixed" => self.state_mixed,
+            "selected" => self.state_selected,
+            "not selected" => self.state_not_selected,
+            other => other,
+        }
+    }
+
+    /// `sentence` prefixed with the page it happens on
+    pub fn on_page(&self, url: &str, sentence: &str) -> String {
+        if sentence.is_empty() {
+            return fill(self.go_to, &[("url", url)]);
+        }
+        let sentence = if self.on_page_lowercase {
+            let mut chars = sentence.chars();
+            chars
+                .next()
+                .map(|first| first.to_lowercase().chain(chars).collect())
+                .unwrap_or_default()
+        } else {
+            sentence.to_string()
+        };
+        fill(self.on_page, &[("url", url), ("sentence", &sentence)])
+    }
+}
+
+/// `template` with each `{name}` replaced by its value. Values are inserted
+/// as is, so a value containing a placeholder isn't expanded again.
+pub fn fill(template: &str, values: &[(&str, &str)]) -> String {
+    let mut out = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        out.push_str(&rest[..start]);
+        let after = &rest[start + 1..];
+        let value = after.find('}').and_then(|end| {
+            let name = &after[..end];
+            values
+                .iter()
+                .find(|(key, _)| *key == name)
+                .map(|(_, value)| (*value, end))
+        });
+        match value {
+            Some((value, end)) => {
+                out.push_str(value);
+                rest = &after[end + 1..];
+            }
+            None => {
+                out.push('{');
+                rest = after;
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fill_replaces_named_placeholders_once() {
+        assert_eq!(
+            fill(
+                "Type \"{text}\" into \"{label}\"{section}",
+                &[("text", "{label}"), ("label", "Email"), ("section", "")]
+            ),
+            "Type \"{label}\" into \"Email\""
+        );
+        assert_eq!(fill("Keep {unknown} and {", &[]), "Keep {unknown} and {");
+    }
+
+    #[test]
+    fn on_page_follows_the_locale() {
+        assert_eq!(
+            Locale::En
+                .catalog()
+                .on_page("https://example.com", "Click \"Save\"."),
+            "On <https://example.com>, click \"Save\"."
+        );
+        assert_eq!(
+            Locale::De
+                .catalog()
+                .on_page("https://example.com", "Klicken Sie auf „Speichern“."),
+            "Auf <https://example.com>: Klicken Sie auf „Speichern“."
+        );
+    }
+}
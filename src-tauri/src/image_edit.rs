@@ -0,0 +1,127 @@
+//! Rotate, flip and resize edits of step screenshots.
+//!
+//! `ImageEdit::apply` produces the edited image and `ImageEdit::map_point`
+//! says where a pixel of the original ends up, so click positions,
+//! annotations and redactions can follow the edit (see
+//! `Database::replace_step_image_edited`).
+
+use crate::annotations::Point;
+use image::imageops::FilterType;
+use image::DynamicImage;
+use serde::Deserialize;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum ImageEdit {
+    RotateClockwise,
+    RotateCounterclockwise,
+    Rotate180,
+    /// Mirror left to right
+    FlipHorizontal,
+    /// Mirror top to bottom
+    FlipVertical,
+    /// Scale to `width`, keeping the aspect ratio
+    Resize {
+        width: u32,
+    },
+}
+
+impl ImageEdit {
+    pub fn apply(self, image: &DynamicImage) -> DynamicImage {
+        match self {
+            ImageEdit::RotateClockwise => image.rotate90(),
+            ImageEdit::RotateCounterclockwise => image.rotate270(),
+            ImageEdit::Rotate180 => image.rotate180(),
+            ImageEdit::FlipHorizontal => image.fliph(),
+            ImageEdit::FlipVertical => image.flipv(),
+            ImageEdit::Resize { width } => {
+                let height = (image.height() as f64 * self.scale(image.width())).round() as u32;
+                image.resize_exact(width.max(1), height.max(1), FilterType::Lanczos3)
+            }
+        }
+    }
+
+    /// How much larger the edited image is than a `width`-wide original;
+    /// 1.0 unless it's resized
+    pub fn scale(self, width: u32) -> f64 {
+        match self {
+            ImageEdit::Resize { width: new_width } => new_width.max(1) as f64 / width.max(1) as f64,
+            _ => 1.0,
+        }
+    }
+
+    /// Where `point` on a `width` x `height` original lands after the edit
+    pub fn map_point(self, point: Point, width: u32, height: u32) -> Point {
+        let (width, height) = (width as f32, height as f32);
+        let Point { x, y } = point;
+        match self {
+            ImageEdit::RotateClockwise => Point {
+                x: height - y,
+                y: x,
+            },
+            ImageEdit::RotateCounterclockwise => Point { x: y, y: width - x },
+            ImageEdit::Rotate180 => Point {
+                x: width - x,
+                y: height - y,
+            },
+            ImageEdit::FlipHorizontal => Point { x: width - x, y },
+            ImageEdit::FlipVertical => Point { x, y: height - y },
+            ImageEdit::Resize { .. } => point.scaled(self.scale(width as u32) as f32),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::{Rgba, RgbaImage};
+
+    /// A 4x2 image with one red pixel at `at`
+    fn marked(at: (u32, u32)) -> DynamicImage {
+        let mut image = RgbaImage::from_pixel(4, 2, Rgba([0, 0, 0, 255]));
+        image.put_pixel(at.0, at.1, Rgba([255, 0, 0, 255]));
+        DynamicImage::ImageRgba8(image)
+    }
+
+    fn red_pixel(image: &DynamicImage) -> (u32, u32) {
+        let image = image.to_rgba8();
+        let (x, y, _) = image
+            .enumerate_pixels()
+            .find(|(_, _, pixel)| pixel[0] == 255)
+            .expect("the marked pixel survives the edit");
+        (x, y)
+    }
+
+    #[test]
+    fn points_follow_the_pixels_they_were_on() {
+        for edit in [
+            ImageEdit::RotateClockwise,
+            ImageEdit::RotateCounterclockwise,
+            ImageEdit::Rotate180,
+            ImageEdit::FlipHorizontal,
+            ImageEdit::FlipVertical,
+        ] {
+            let edited = edit.apply(&marked((1, 0)));
+            // The centre of pixel (1, 0)
+            let moved = edit.map_point(Point { x: 1.5, y: 0.5 }, 4, 2);
+            let (x, y) = red_pixel(&edited);
+            assert_eq!(
+                (moved.x, moved.y),
+                (x as f32 + 0.5, y as f32 + 0.5),
+                "{:?}",
+                edit
+            );
+        }
+    }
+
+    #[test]
+    fn resize_keeps_the_aspect_ratio() {
+        let edit = ImageEdit::Resize { width: 2 };
+        let edited = edit.apply(&marked((0, 0)));
+        assert_eq!((edited.width(), edited.height()), (2, 1));
+        assert_eq!(
+            edit.map_point(Point { x: 4.0, y: 2.0 }, 4, 2),
+            Point { x: 2.0, y: 1.0 }
+        );
+    }
+}
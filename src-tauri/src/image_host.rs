@@ -0,0 +1,348 @@
+//! Uploading screenshots to an image host, for exports that link their
+//! images instead of bundling them.
+//!
+//! Screenshots go to an S3 bucket (through presigned URLs, so the same
+//! bucket settings as sync work), to Imgur, or to any endpoint that takes a
+//! multipart upload and answers with the image's URL. Uploads are keyed by
+//! the file's SHA-256 and remembered in the database, so exporting the same
+//! recording twice uploads nothing new until expiring links run out.
+
+use crate::database::HostedImage;
+use crate::db_worker::DatabaseWorker;
+use crate::settings::SettingsState;
+use crate::sync::S3Settings;
+use crate::sync_provider::S3;
+use crate::{analytics, credentials, network};
+use reqwest::multipart::{Form, Part};
+use reqwest::{Client, Method};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::path::Path;
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
+
+pub const S3_SECRET_CREDENTIAL: &str = "image_host_s3_secret";
+pub const IMGUR_CLIENT_ID_CREDENTIAL: &str = "imgur_client_id";
+pub const CUSTOM_TOKEN_CREDENTIAL: &str = "image_host_token";
+const IMGUR_UPLOAD_URL: &str = "https://api.imgur.com/3/image";
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(60);
+/// How long the presigned upload URL stays valid
+const UPLOAD_URL_SECS: u64 = 60 * 60;
+/// Longest presigned link S3 accepts
+const MAX_LINK_HOURS: u32 = 24 * 7;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ImageHostProvider {
+    #[default]
+    None,
+    S3,
+    Imgur,
+    Custom,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ImageHostSettings {
+    pub provider: ImageHostProvider,
+    /// Link screenshots from Markdown and HTML exports instead of bundling
+    pub hosted_images: bool,
+    pub s3: S3Settings,
+    /// Lifetime of presigned links; 0 for plain links to a public bucket
+    pub s3_link_expiry_hours: u32,
+    /// Where a public bucket is served, e.g. a CDN; the bucket URL when empty
+    pub s3_public_url: String,
+    /// Endpoint that takes a multipart `POST` of the image
+    pub custom_url: String,
+    /// Form field the image goes in
+    pub custom_field: String,
+    /// JSON pointer to the URL in the response, e.g. `/data/url`; empty when
+    /// the response body is the URL
+    pub custom_url_pointer: String,
+}
+
+impl Default for ImageHostSettings {
+    fn default() -> Self {
+        Self {
+            provider: ImageHostProvider::None,
+            hosted_images: false,
+            s3: S3Settings::default(),
+            s3_link_expiry_hours: 0,
+            s3_public_url: String::new(),
+            custom_url: String::new(),
+            custom_field: "file".to_string(),
+            custom_url_pointer: String::new(),
+        }
+    }
+}
+
+impl ImageHostSettings {
+    /// Identifies where uploads go, so changing the host uploads again
+    fn destination(&self) -> String {
+        match self.provider {
+            ImageHostProvider::None => String::new(),
+            ImageHostProvider::S3 => format!(
+                "s3:{}/{}/{}",
+                self.s3.endpoint.trim(),
+                self.s3.bucket.trim(),
+                self.s3.prefix.trim()
+            ),
+            ImageHostProvider::Imgur => "imgur".to_string(),
+            ImageHostProvider::Custom => format!("custom:{}", self.custom_url.trim()),
+        }
+    }
+}
+
+/// Name of the uploaded file: its hash, so re-uploads overwrite in place
+fn object_name(sha256: &str, path: &Path) -> String {
+    let extension = path
+        .extension()
+        .and_then(|extension| extension.to_str())
+        .unwrap_or("png")
+        .to_ascii_lowercase();
+    format!("images/{}.{}", sha256, extension)
+}
+
+fn content_type(path: &Path) -> &'static str {
+    match path
+        .extension()
+        .and_then(|extension| extension.to_str())
+        .map(str::to_ascii_lowercase)
+        .as_deref()
+    {
+        Some("jpg" | "jpeg") => "image/jpeg",
+        Some("webp") => "image/webp",
+        Some("gif") => "image/gif",
+        _ => "image/png",
+    }
+}
+
+/// The image URL in a custom endpoint's response body
+fn custom_response_url(body: &str, pointer: &str) -> Result<String, String> {
+    let pointer = pointer.trim();
+    let url = if pointer.is_empty() {
+        body.trim().to_string()
+    } else {
+        let json: serde_json::Value = serde_json::from_str(body)
+            .map_err(|e| format!("The image host didn't answer with JSON: {}", e))?;
+        json.pointer(pointer)
+            .and_then(|value| value.as_str())
+            .ok_or_else(|| format!("The image host's response has no URL at {}", pointer))?
+            .to_string()
+    };
+    if !url.starts_with("http://") && !url.starts_with("https://") {
+        return Err(format!("The image host didn't return a URL: {}", url));
+    }
+    Ok(url)
+}
+
+fn file_part(path: &Path, bytes: Vec<u8>) -> Result<Part, String> {
+    let name = path
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_else(|| "screenshot.png".to_string());
+    Part::bytes(bytes)
+        .file_name(name)
+        .mime_str(content_type(path))
+        .map_err(|e| e.to_string())
+}
+
+async fn check(response: Result<reqwest::Response, reqwest::Error>) -> Result<String, String> {
+    let response = response.map_err(|e| format!("Couldn't reach the image host: {}", e))?;
+    let status = response.status();
+    let body = response.text().await.map_err(|e| e.to_string())?;
+    if !status.is_success() {
+        return Err(format!(
+            "The image host refused the upload: {} {}",
+            status,
+            body.trim()
+        ));
+    }
+    Ok(body)
+}
+
+/// Upload `bytes` and return the image's URL and when it stops working
+async fn upload(
+    client: &Client,
+    config: &ImageHostSettings,
+    path: &Path,
+    sha256: &str,
+    bytes: Vec<u8>,
+) -> Result<(String, Option<i64>), String> {
+    match config.provider {
+        ImageHostProvider::None => Err("Choose an image host in Settings".to_string()),
+        ImageHostProvider::S3 => {
+            let secret = credentials::get(S3_SECRET_CREDENTIAL)?.unwrap_or_default();
+            let s3 = S3::new(client.clone(), &config.s3, secret)?;
+            let name = object_name(sha256, path);
+            check(
+                client
+                    .put(s3.presign(&Method::PUT, &name, UPLOAD_URL_SECS))
+                    .header(reqwest::header::CONTENT_TYPE, content_type(path))
+                    .body(bytes)
+                    .send()
+                    .await,
+            )
+            .await?;
+            if config.s3_link_expiry_hours > 0 {
+                let hours = config.s3_link_expiry_hours.min(MAX_LINK_HOURS);
+                let expires_at =
+                    chrono::Utc::now().timestamp_millis() + i64::from(hours) * 60 * 60 * 1000;
+                let url = s3.presign(&Method::GET, &name, u64::from(hours) * 60 * 60);
+                return Ok((url.to_string(), Some(expires_at)));
+            }
+            let url = match config.s3_public_url.trim().trim_end_matches('/') {
+                "" => s3.file_url(&name).to_string(),
+                public => format!("{}/{}", public, name),
+            };
+            Ok((url, None))
+        }
+        ImageHostProvider::Imgur => {
+            let client_id = credentials::get(IMGUR_CLIENT_ID_CREDENTIAL)?
+                .filter(|id| !id.trim().is_empty())
+                .ok_or_else(|| "Set the Imgur client ID in Settings".to_string())?;
+            let body = check(
+                client
+                    .post(IMGUR_UPLOAD_URL)
+                    .header(
+                        reqwest::header::AUTHORIZATION,
+                        format!("Client-ID {}", client_id.trim()),
+                    )
+                    .multipart(Form::new().part("image", file_part(path, bytes)?))
+                    .send()
+                    .await,
+            )
+            .await?;
+            Ok((custom_response_url(&body, "/data/link")?, None))
+        }
+        ImageHostProvider::Custom => {
+            let url = config.custom_url.trim();
+            if url.is_empty() {
+                return Err("Set the image upload URL in Settings".to_string());
+            }
+            let field = match config.custom_field.trim() {
+                "" => "file",
+                field => field,
+            };
+            let mut request = client
+                .post(url)
+                .multipart(Form::new().part(field.to_string(), file_part(path, bytes)?));
+            if let Some(token) =
+                credentials::get(CUSTOM_TOKEN_CREDENTIAL)?.filter(|token| !token.trim().is_empty())
+            {
+                request = request.bearer_auth(token.trim());
+            }
+            let body = check(request.send().await).await?;
+            Ok((
+                custom_response_url(&body, &config.custom_url_pointer)?,
+                None,
+            ))
+        }
+    }
+}
+
+/// Upload the screenshots at `paths` to the configured image host and return
+/// each path's public URL. Screenshots uploaded before are not sent again.
+#[tauri::command]
+pub async fn upload_screenshots(
+    app: AppHandle,
+    paths: Vec<String>,
+) -> Result<HashMap<String, String>, String> {
+    let config = app
+        .state::<SettingsState>()
+        .0
+        .lock()
+        .unwrap()
+        .image_host
+        .clone();
+    if config.provider == ImageHostProvider::None {
+        return Err("Choose an image host in Settings".to_string());
+    }
+    let destination = config.destination();
+    let client = network::client_builder()?
+        .timeout(REQUEST_TIMEOUT)
+        .build()
+        .map_err(|e| format!("Failed to create HTTP client: {}", e))?;
+
+    let mut urls = HashMap::new();
+    let mut uploaded = 0;
+    for path in paths {
+        if urls.contains_key(&path) {
+            continue;
+        }
+        let bytes = std::fs::read(&path).map_err(|e| format!("Failed to read {}: {}", path, e))?;
+        let sha256 = format!("{:x}", Sha256::digest(&bytes));
+        let now = chrono::Utc::now().timestamp_millis();
+        let cached = {
+            let (sha256, destination) = (sha256.clone(), destination.clone());
+            app.state::<DatabaseWorker>()
+                .run(move |db| {
+                    db.get_hosted_image(&sha256, &destination, now)
+                        .map_err(|e| e.to_string())
+                })
+                .await?
+        };
+        let url = match cached {
+            Some(image) => image.url,
+            None => {
+                let (url, expires_at) =
+                    upload(&client, &config, Path::new(&path), &sha256, bytes).await?;
+                let image = HostedImage {
+                    sha256,
+                    destination: destination.clone(),
+                    url: url.clone(),
+                    uploaded_at: now,
+                    expires_at,
+                };
+                app.state::<DatabaseWorker>()
+                    .run(move |db| db.save_hosted_image(&image).map_err(|e| e.to_string()))
+                    .await?;
+                uploaded += 1;
+                url
+            }
+        };
+        urls.insert(path, url);
+    }
+
+    analytics::track("screenshots_hosted");
+    tracing::info!(
+        images = urls.len(),
+        uploaded = uploaded,
+        "Hosted screenshots"
+    );
+    Ok(urls)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_the_url_in_upload_responses() {
+        assert_eq!(
+            custom_response_url(
+                r#"{"data":{"link":"https://i.imgur.com/a.png"}}"#,
+                "/data/link"
+            )
+            .unwrap(),
+            "https://i.imgur.com/a.png"
+        );
+        assert_eq!(
+            custom_response_url("https://img.example.com/a.png\n", "").unwrap(),
+            "https://img.example.com/a.png"
+        );
+        assert!(custom_response_url(r#"{"ok":true}"#, "/url").is_err());
+        assert!(custom_response_url("uploaded", "").is_err());
+    }
+
+    #[test]
+    fn names_uploads_by_hash() {
+        assert_eq!(
+            object_name("abc", Path::new("/shots/Step 1.JPG")),
+            "images/abc.jpg"
+        );
+        assert_eq!(content_type(Path::new("/shots/a.jpeg")), "image/jpeg");
+        assert_eq!(content_type(Path::new("/shots/a")), "image/png");
+    }
+}
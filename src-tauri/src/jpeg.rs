@@ -0,0 +1,80 @@
+//! JPEG encoding for captured screenshots.
+//!
+//! The `image` crate's encoder is pure Rust and was the slowest stage of a
+//! capture: a 4K frame took long enough that the step toast trailed the
+//! click by seconds. Screenshots from the recorder and the manual-capture
+//! commands are encoded with libjpeg-turbo instead, whose SIMD encoder is
+//! several times faster. The output is the same baseline JPEG with 4:2:0
+//! chroma subsampling. Edits and exports still go through `image`.
+
+use image::{RgbImage, RgbaImage};
+use std::path::Path;
+use turbojpeg::{Image, PixelFormat, Subsamp};
+
+/// An image buffer libjpeg-turbo can read without converting it first
+pub trait JpegSource {
+    fn as_turbojpeg(&self) -> Image<&[u8]>;
+}
+
+impl JpegSource for RgbImage {
+    fn as_turbojpeg(&self) -> Image<&[u8]> {
+        Image {
+            pixels: self.as_raw().as_slice(),
+            width: self.width() as usize,
+            pitch: self.width() as usize * 3,
+            height: self.height() as usize,
+            format: PixelFormat::RGB,
+        }
+    }
+}
+
+impl JpegSource for RgbaImage {
+    // Alpha is dropped; captures are opaque
+    fn as_turbojpeg(&self) -> Image<&[u8]> {
+        Image {
+            pixels: self.as_raw().as_slice(),
+            width: self.width() as usize,
+            pitch: self.width() as usize * 4,
+            height: self.height() as usize,
+            format: PixelFormat::RGBA,
+        }
+    }
+}
+
+/// Encode `image` at `quality` (1-100)
+pub fn encode(image: &impl JpegSource, quality: u8) -> Result<Vec<u8>, String> {
+    let jpeg = turbojpeg::compress(
+        image.as_turbojpeg(),
+        quality.clamp(1, 100) as i32,
+        Subsamp::Sub2x2,
+    )
+    .map_err(|e| format!("Failed to encode JPEG: {}", e))?;
+    Ok(jpeg.to_vec())
+}
+
+/// Encode `image` and write it to `path`. The file is complete when this
+/// returns, so it can be handed to the frontend straight away.
+pub fn save(image: &impl JpegSource, path: &Path, quality: u8) -> Result<(), String> {
+    let jpeg = encode(image, quality)?;
+    std::fs::write(path, jpeg).map_err(|e| format!("Failed to write {}: {}", path.display(), e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn encodes_rgb_and_rgba_captures() {
+        let rgb = RgbImage::from_pixel(64, 48, image::Rgb([200, 40, 40]));
+        let decoded = image::load_from_memory(&encode(&rgb, 85).unwrap())
+            .unwrap()
+            .to_rgb8();
+        assert_eq!(decoded.dimensions(), (64, 48));
+        let [r, g, b] = decoded.get_pixel(32, 24).0;
+        assert!(r > 180 && g < 70 && b < 70);
+
+        let rgba = RgbaImage::from_pixel(33, 17, image::Rgba([10, 20, 250, 255]));
+        let decoded = image::load_from_memory(&encode(&rgba, 85).unwrap()).unwrap();
+        assert_eq!((decoded.width(), decoded.height()), (33, 17));
+    }
+}
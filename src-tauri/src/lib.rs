@@ -1,21 +1,75 @@
 // Learn more about Tauri commands at https://tauri.app/develop/calling-rust/
 mod accessibility;
+mod actions;
+mod ai;
+mod analytics;
+mod annotations;
+mod api_server;
+mod bundle;
+mod capture_backend;
+mod capture_benchmark;
+mod chat;
+pub mod cli;
+mod credentials;
+mod data_location;
 mod database;
+mod db_worker;
+mod deep_link;
+mod descriptions;
+mod diagnostics;
+mod email;
+mod environment;
+mod event_stream;
+mod export;
+mod exporter_plugins;
+mod git_publish;
+mod hotkeys;
+mod i18n;
+mod image_edit;
+mod image_host;
+mod jpeg;
 mod logging;
+mod mcp;
+mod monitor_cache;
+mod narrated_video;
+mod network;
 mod ocr;
+mod ocr_models;
+mod ocr_queue;
 mod overlay;
+mod phash;
+mod pii;
 mod recorder;
+mod replay;
+mod screenshot_protocol;
+mod scroll_capture;
+mod settings;
+mod share;
+mod smart_crop;
+mod sync;
+mod sync_provider;
+mod tickets;
+mod transcription;
+mod tts;
+mod watermark;
+mod window_list;
+mod zoom_inset;
 
 #[cfg(target_os = "linux")]
 mod display;
 
 use base64::{engine::general_purpose, Engine as _};
 use database::{
-    Database, DeleteRecordingCleanup, Notification, PaginatedRecordings, Recording,
-    RecordingWithSteps, StepInput,
+    AuditEntry, Database, DeleteRecordingCleanup, HotkeyProfile, Notification, PaginatedRecordings,
+    Recording, RecordingImageSettings, RecordingLink, RecordingStatistics, RecordingWithSteps,
+    RenamedRecording, ScreenshotFormat, Step, StepInput, StorageUsage,
 };
+use db_worker::DatabaseWorker;
+use hotkeys::{HotkeyAction, HotkeyConflict, HotkeyFailure};
 use recorder::{HotkeyBinding, RecordingState};
 use serde::{Deserialize, Serialize};
+use settings::{Settings, SettingsState};
+use std::collections::{HashMap, HashSet};
 use std::io::Write;
 use std::net::{IpAddr, ToSocketAddrs};
 use std::panic::{catch_unwind, AssertUnwindSafe};
@@ -24,7 +78,7 @@ use std::sync::{Arc, Mutex};
 use std::time::Duration;
 use tauri::{AppHandle, Emitter, Manager, State};
 use tauri_plugin_dialog::DialogExt;
-use tauri_plugin_global_shortcut::{Code, GlobalShortcutExt, Modifiers, Shortcut, ShortcutState};
+use tauri_plugin_global_shortcut::{GlobalShortcutExt, Modifiers, Shortcut, ShortcutState};
 
 pub struct DatabaseState(pub Mutex<Database>);
 
@@ -106,21 +160,188 @@ fn get_startup_status(startup: State<'_, StartupState>) -> StartupStatus {
 }
 
 #[tauri::command]
-fn start_recording(state: State<'_, RecordingState>, _app: AppHandle) {
+fn start_recording(state: State<'_, RecordingState>, app: AppHandle) {
     let mut is_recording = state.is_recording.lock().unwrap();
     if !*is_recording {
         *is_recording = true;
+        recorder::begin_session();
+        let _ = app.emit("recording-started", ());
+        analytics::track("recording_started");
         logging::log(logging::CATEGORY_RECORDER, "info", "Recording started", None);
     }
 }
 
+/// Stop capturing steps without ending the session; see `resume_recording`
 #[tauri::command]
-fn stop_recording(state: State<'_, RecordingState>) {
+fn pause_recording(state: State<'_, RecordingState>, app: AppHandle) {
     let mut is_recording = state.is_recording.lock().unwrap();
     if *is_recording {
-        logging::log(logging::CATEGORY_RECORDER, "info", "Recording stopped", None);
+        *is_recording = false;
+        let _ = app.emit("recording-paused", ());
+        logging::log(logging::CATEGORY_RECORDER, "info", "Recording paused", None);
     }
-    *is_recording = false;
+}
+
+/// Capture steps again in the session `pause_recording` paused
+#[tauri::command]
+fn resume_recording(state: State<'_, RecordingState>, app: AppHandle) {
+    let mut is_recording = state.is_recording.lock().unwrap();
+    if !*is_recording {
+        *is_recording = true;
+        let _ = app.emit("recording-resumed", ());
+        logging::log(
+            logging::CATEGORY_RECORDER,
+            "info",
+            "Recording resumed",
+            None,
+        );
+    }
+}
+
+/// Start a new chapter of the recording with a marker step labelled `label`.
+/// Emits `marker-added` with the step id so the frontend can ask for a
+/// better label once the user is back in the app.
+#[tauri::command]
+fn add_marker(app: AppHandle, label: String) -> String {
+    let label = label.trim();
+    let step_id = recorder::emit_marker_step(&app, label);
+    let _ = app.emit(
+        "marker-added",
+        serde_json::json!({ "step_id": step_id, "label": label }),
+    );
+    let _ = overlay::show_toast(&format!("Marker added: {}", label), 2000);
+    analytics::track("marker_added");
+    logging::log(logging::CATEGORY_RECORDER, "info", "Marker added", None);
+    step_id
+}
+
+#[tauri::command]
+async fn stop_recording(
+    state: State<'_, RecordingState>,
+    db: State<'_, DatabaseWorker>,
+    app: AppHandle,
+) -> Result<(), String> {
+    {
+        let mut is_recording = state.is_recording.lock().unwrap();
+        if *is_recording {
+            let _ = app.emit("recording-stopped", ());
+            logging::log(logging::CATEGORY_RECORDER, "info", "Recording stopped", None);
+        }
+        *is_recording = false;
+    }
+    capture_backend::release();
+
+    // Write the session's usage counts and capture timings
+    let flushed = db
+        .run(|db| analytics::flush(db).map_err(|e| e.to_string()))
+        .await;
+    if let Err(e) = flushed {
+        tracing::warn!("Failed to save usage analytics: {}", e);
+    }
+    Ok(())
+}
+
+/// Folder holding the database and screenshots
+#[tauri::command]
+async fn get_data_directory(db: State<'_, DatabaseWorker>) -> Result<String, String> {
+    db.run(|db| Ok(db.data_dir().to_string_lossy().to_string()))
+        .await
+}
+
+/// Move the database and screenshots to `target`, an empty folder, and
+/// switch to them there. The old copy is deleted if `remove_old` is set.
+/// Screenshots outside the data directory (a custom storage location) stay
+/// where they are.
+#[tauri::command]
+async fn relocate_data_directory(
+    app: AppHandle,
+    db: State<'_, DatabaseWorker>,
+    state: State<'_, RecordingState>,
+    settings: State<'_, SettingsState>,
+    target: String,
+    remove_old: bool,
+) -> Result<data_location::DataRelocation, String> {
+    if *state.is_recording.lock().unwrap() {
+        return Err("Stop recording before moving the data directory".to_string());
+    }
+    analytics::track("data_relocated");
+    let app_data_dir = app
+        .path()
+        .app_data_dir()
+        .map_err(|e| format!("Failed to get app data directory: {}", e))?;
+    let quota = settings.0.lock().unwrap().lossless_original_quota;
+
+    let scope_app = app.clone();
+    let relocation = db
+        .run(move |db| {
+            let (moved, relocation) =
+                data_location::relocate(db, &app_data_dir, std::path::Path::new(&target))?;
+            scope_app
+                .asset_protocol_scope()
+                .allow_directory(&relocation.new_dir, true)
+                .map_err(|e| format!("Failed to register asset scope: {}", e))?;
+            drop(std::mem::replace(db, moved));
+            db.set_original_quota(quota);
+            Ok(relocation)
+        })
+        .await?;
+
+    if remove_old {
+        data_location::remove_data(std::path::Path::new(&relocation.old_dir));
+    }
+    logging::log(
+        logging::CATEGORY_DATABASE,
+        "info",
+        "Data directory moved",
+        Some(&serde_json::json!(relocation)),
+    );
+    Ok(relocation)
+}
+
+/// Zip logs, sanitized settings and system details into `destination` for a
+/// bug report. Returns the names of the files in the bundle.
+#[tauri::command]
+async fn create_diagnostics_bundle(
+    app: AppHandle,
+    db: State<'_, DatabaseWorker>,
+    settings: State<'_, SettingsState>,
+    destination: String,
+) -> Result<Vec<String>, String> {
+    analytics::track("diagnostics_bundle");
+    let destination = normalize_file_path(std::path::Path::new(&destination))?;
+    let app_data_dir = app
+        .path()
+        .app_data_dir()
+        .map_err(|e| format!("Failed to get app data directory: {}", e))?;
+    let (schema_version, data_dir) = db
+        .run(|db| Ok((db.schema_version().ok(), db.data_dir().clone())))
+        .await?;
+    let settings = settings.0.lock().unwrap().clone();
+    let logs_dir = logging::logs_dir();
+    let home_dir = app.path().home_dir().ok();
+
+    let entries = diagnostics::write_bundle(
+        &destination,
+        &diagnostics::BundleSources {
+            settings: &settings,
+            schema_version,
+            app_data_dir: &app_data_dir,
+            data_dir: &data_dir,
+            logs_dir: logs_dir.as_deref(),
+            home_dir: home_dir.as_deref(),
+            monitors: diagnostics::monitor_topology(),
+        },
+    )?;
+    logging::log(
+        logging::CATEGORY_APP,
+        "info",
+        "Diagnostics bundle created",
+        Some(&serde_json::json!({
+            "path": destination.to_string_lossy(),
+            "files": entries.len(),
+        })),
+    );
+    Ok(entries)
 }
 
 /// Normalize an absolute file path into a stable canonical path.
@@ -291,13 +512,19 @@ fn build_ai_endpoint(base_url: &reqwest::Url, path: &str) -> Result<reqwest::Url
 }
 
 fn ai_http_client(timeout: Duration) -> Result<reqwest::Client, String> {
-    reqwest::Client::builder()
+    network::client_builder()?
         .timeout(timeout)
         .redirect(reqwest::redirect::Policy::none())
         .build()
         .map_err(|e| format!("Failed to create AI HTTP client: {}", e))
 }
 
+/// The proxy the "System" proxy mode currently resolves to, if any
+#[tauri::command]
+fn get_system_proxy() -> Option<network::SystemProxy> {
+    network::detect_system_proxy()
+}
+
 fn map_ai_transport_error(error: &reqwest::Error, base_url: &reqwest::Url) -> String {
     if error.is_timeout() {
         return "Connection timed out. Check the server URL.".to_string();
@@ -622,9 +849,8 @@ async fn post_ai_chat_completion(
 }
 
 #[tauri::command]
-fn delete_screenshot(path: String, db: State<'_, DatabaseState>) -> Result<(), String> {
+fn delete_screenshot(path: String) -> Result<(), String> {
     let path = PathBuf::from(&path);
-    drop(safe_db_lock(&db)?);
     let validated_path = normalize_file_path(&path)?;
     std::fs::remove_file(&validated_path).map_err(|e| e.to_string())
 }
@@ -641,191 +867,487 @@ fn binding_to_shortcut(binding: &HotkeyBinding) -> Option<Shortcut> {
     if binding.alt {
         modifiers |= Modifiers::ALT;
     }
+    if binding.meta {
+        modifiers |= Modifiers::SUPER;
+    }
 
-    let code = match binding.key.as_str() {
-        "KeyA" => Code::KeyA,
-        "KeyB" => Code::KeyB,
-        "KeyC" => Code::KeyC,
-        "KeyD" => Code::KeyD,
-        "KeyE" => Code::KeyE,
-        "KeyF" => Code::KeyF,
-        "KeyG" => Code::KeyG,
-        "KeyH" => Code::KeyH,
-        "KeyI" => Code::KeyI,
-        "KeyJ" => Code::KeyJ,
-        "KeyK" => Code::KeyK,
-        "KeyL" => Code::KeyL,
-        "KeyM" => Code::KeyM,
-        "KeyN" => Code::KeyN,
-        "KeyO" => Code::KeyO,
-        "KeyP" => Code::KeyP,
-        "KeyQ" => Code::KeyQ,
-        "KeyR" => Code::KeyR,
-        "KeyS" => Code::KeyS,
-        "KeyT" => Code::KeyT,
-        "KeyU" => Code::KeyU,
-        "KeyV" => Code::KeyV,
-        "KeyW" => Code::KeyW,
-        "KeyX" => Code::KeyX,
-        "KeyY" => Code::KeyY,
-        "KeyZ" => Code::KeyZ,
-        "Digit0" => Code::Digit0,
-        "Digit1" => Code::Digit1,
-        "Digit2" => Code::Digit2,
-        "Digit3" => Code::Digit3,
-        "Digit4" => Code::Digit4,
-        "Digit5" => Code::Digit5,
-        "Digit6" => Code::Digit6,
-        "Digit7" => Code::Digit7,
-        "Digit8" => Code::Digit8,
-        "Digit9" => Code::Digit9,
-        "F1" => Code::F1,
-        "F2" => Code::F2,
-        "F3" => Code::F3,
-        "F4" => Code::F4,
-        "F5" => Code::F5,
-        "F6" => Code::F6,
-        "F7" => Code::F7,
-        "F8" => Code::F8,
-        "F9" => Code::F9,
-        "F10" => Code::F10,
-        "F11" => Code::F11,
-        "F12" => Code::F12,
-        "Space" => Code::Space,
-        "Enter" => Code::Enter,
-        "Escape" => Code::Escape,
-        "Backspace" => Code::Backspace,
-        "Tab" => Code::Tab,
-        _ => return None,
-    };
+    let code = hotkeys::code_for(&binding.key)?;
 
     Some(Shortcut::new(Some(modifiers), code))
 }
 
+#[tauri::command]
+fn list_supported_hotkeys() -> Vec<hotkeys::SupportedKey> {
+    hotkeys::supported_keys()
+}
+
+/// Register every hotkey in `bindings` to emit its action's event when
+/// pressed, returning the ones that couldn't be
+fn register_hotkeys(
+    app: &AppHandle,
+    bindings: &HashMap<HotkeyAction, HotkeyBinding>,
+) -> Vec<HotkeyFailure> {
+    let global_shortcut = app.global_shortcut();
+    let mut failures = Vec::new();
+    let mut registered: Vec<(HotkeyAction, Shortcut)> = Vec::new();
+    for action in HotkeyAction::ALL {
+        let Some(binding) = bindings.get(&action).filter(|binding| !binding.disabled) else {
+            continue;
+        };
+        // Mouse buttons are matched by the recorder's input listener
+        if hotkeys::is_mouse_button(&binding.key) {
+            continue;
+        }
+        let Some(shortcut) = binding_to_shortcut(binding) else {
+            failures.push(HotkeyFailure {
+                action,
+                conflict: HotkeyConflict::UnsupportedKey {
+                    key: binding.key.clone(),
+                },
+            });
+            continue;
+        };
+        if let Some(&(other, _)) = registered.iter().find(|(_, s)| *s == shortcut) {
+            failures.push(HotkeyFailure {
+                action,
+                conflict: HotkeyConflict::Action { action: other },
+            });
+            continue;
+        }
+        let result = global_shortcut.on_shortcut(shortcut, move |app, _shortcut, event| {
+            if event.state == ShortcutState::Pressed {
+                let _ = app.emit(action.event(), ());
+            }
+        });
+        match result {
+            Ok(()) => registered.push((action, shortcut)),
+            Err(e) => failures.push(HotkeyFailure {
+                action,
+                conflict: HotkeyConflict::System {
+                    message: e.to_string(),
+                },
+            }),
+        }
+    }
+
+    for failure in &failures {
+        logging::log(
+            logging::CATEGORY_APP,
+            "warn",
+            "Failed to register hotkey",
+            Some(&serde_json::json!({
+                "action": failure.action,
+                "conflict": failure.conflict,
+            })),
+        );
+    }
+    failures
+}
+
+/// Rebind the hotkeys in `bindings`. Actions missing from it keep their
+/// current binding; a null binding unbinds the action. Returns the hotkeys
+/// that couldn't be registered, which stay bound but won't fire.
 #[tauri::command]
 fn set_hotkeys(
     app: AppHandle,
     state: State<'_, RecordingState>,
-    start: HotkeyBinding,
-    stop: HotkeyBinding,
-    capture: Option<HotkeyBinding>,
-) -> Result<(), String> {
+    bindings: HashMap<HotkeyAction, Option<HotkeyBinding>>,
+) -> Vec<HotkeyFailure> {
     let global_shortcut = app.global_shortcut();
+    let mut hotkeys = state.hotkeys.lock().unwrap();
 
-    // Get old shortcuts to unregister
-    let old_start = state.start_hotkey.lock().unwrap().clone();
-    let old_stop = state.stop_hotkey.lock().unwrap().clone();
-    let old_capture = state.capture_hotkey.lock().unwrap().clone();
+    // Unregister everything first so two actions can swap keys
+    for binding in hotkeys.values() {
+        if let Some(shortcut) = binding_to_shortcut(binding) {
+            let _ = global_shortcut.unregister(shortcut);
+        }
+    }
 
-    // Unregister old shortcuts
-    if let Some(shortcut) = binding_to_shortcut(&old_start) {
-        let _ = global_shortcut.unregister(shortcut);
+    for (action, binding) in bindings {
+        match binding {
+            Some(binding) => {
+                hotkeys.insert(action, binding);
+            }
+            None => {
+                hotkeys.remove(&action);
+            }
+        }
     }
-    if let Some(shortcut) = binding_to_shortcut(&old_stop) {
-        let _ = global_shortcut.unregister(shortcut);
+
+    if *state.hotkeys_suspended.lock().unwrap() {
+        return Vec::new();
     }
-    if let Some(shortcut) = binding_to_shortcut(&old_capture) {
-        let _ = global_shortcut.unregister(shortcut);
+    register_hotkeys(&app, &hotkeys)
+}
+
+/// Suspend every hotkey until called again with `suspended` false, which
+/// registers them again and returns the ones that failed to
+#[tauri::command]
+fn set_hotkeys_suspended(
+    app: AppHandle,
+    state: State<'_, RecordingState>,
+    suspended: bool,
+) -> Vec<HotkeyFailure> {
+    let hotkeys = state.hotkeys.lock().unwrap();
+    let mut is_suspended = state.hotkeys_suspended.lock().unwrap();
+    if *is_suspended == suspended {
+        return Vec::new();
     }
+    *is_suspended = suspended;
 
-    // Register new shortcuts
-    if let Some(shortcut) = binding_to_shortcut(&start) {
-        global_shortcut
-            .on_shortcut(shortcut, move |_app, _shortcut, event| {
-                if event.state == ShortcutState::Pressed {
-                    let _ = _app.emit("hotkey-start", ());
-                }
-            })
-            .map_err(|e| e.to_string())?;
+    if !suspended {
+        return register_hotkeys(&app, &hotkeys);
+    }
+    let global_shortcut = app.global_shortcut();
+    for binding in hotkeys.values() {
+        if let Some(shortcut) = binding_to_shortcut(binding) {
+            let _ = global_shortcut.unregister(shortcut);
+        }
     }
+    Vec::new()
+}
 
-    if let Some(shortcut) = binding_to_shortcut(&stop) {
-        global_shortcut
-            .on_shortcut(shortcut, move |_app, _shortcut, event| {
-                if event.state == ShortcutState::Pressed {
-                    let _ = _app.emit("hotkey-stop", ());
-                }
-            })
-            .map_err(|e| e.to_string())?;
+/// Why `binding` can't be used for `action`, or None when it's free. Pass
+/// no action to check against every current hotkey.
+#[tauri::command]
+fn validate_hotkey(
+    app: AppHandle,
+    state: State<'_, RecordingState>,
+    binding: HotkeyBinding,
+    action: Option<HotkeyAction>,
+) -> Option<HotkeyConflict> {
+    let hotkeys = state.hotkeys.lock().unwrap();
+    if let Some(other) = hotkeys::action_with_binding(&hotkeys, &binding, action) {
+        return Some(HotkeyConflict::Action { action: other });
     }
+    // Nothing else can claim a mouse button; the listener only observes it
+    if hotkeys::is_mouse_button(&binding.key) {
+        return None;
+    }
+    let Some(shortcut) = binding_to_shortcut(&binding) else {
+        return Some(HotkeyConflict::UnsupportedKey { key: binding.key });
+    };
 
-    // Register capture hotkey if provided
-    let capture_binding = capture.unwrap_or_else(|| old_capture.clone());
-    if let Some(shortcut) = binding_to_shortcut(&capture_binding) {
-        global_shortcut
-            .on_shortcut(shortcut, move |_app, _shortcut, event| {
-                if event.state == ShortcutState::Pressed {
-                    let _ = _app.emit("hotkey-capture", ());
-                }
-            })
-            .map_err(|e| e.to_string())?;
+    // Registered by us, as this action's current binding
+    let global_shortcut = app.global_shortcut();
+    if global_shortcut.is_registered(shortcut) {
+        return None;
+    }
+    // Otherwise the only way to find out if something else holds it is to
+    // try to take it
+    match global_shortcut.register(shortcut) {
+        Ok(()) => {
+            let _ = global_shortcut.unregister(shortcut);
+            None
+        }
+        Err(e) => Some(HotkeyConflict::System {
+            message: e.to_string(),
+        }),
+    }
+}
+
+/// `name` trimmed, if it's usable for a profile with `bindings`
+fn validate_hotkey_profile(
+    name: &str,
+    bindings: &HashMap<HotkeyAction, HotkeyBinding>,
+) -> Result<String, String> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err("Profile name cannot be empty".to_string());
     }
+    if let Some(binding) = bindings
+        .values()
+        .find(|binding| !hotkeys::is_supported_key(&binding.key))
+    {
+        return Err(format!("{} can't be used in a hotkey", binding.key));
+    }
+    Ok(name.to_string())
+}
 
-    // Update state
-    *state.start_hotkey.lock().unwrap() = start;
-    *state.stop_hotkey.lock().unwrap() = stop;
-    *state.capture_hotkey.lock().unwrap() = capture_binding;
+#[tauri::command]
+async fn list_hotkey_profiles(db: State<'_, DatabaseWorker>) -> Result<Vec<HotkeyProfile>, String> {
+    db.run(|db| db.list_hotkey_profiles().map_err(|e| e.to_string()))
+        .await
+}
 
-    Ok(())
+/// Create a hotkey profile, or replace the bindings of the one named `name`
+#[tauri::command]
+async fn save_hotkey_profile(
+    db: State<'_, DatabaseWorker>,
+    name: String,
+    bindings: HashMap<HotkeyAction, HotkeyBinding>,
+) -> Result<HotkeyProfile, String> {
+    let name = validate_hotkey_profile(&name, &bindings)?;
+    db.run(move |db| {
+        db.save_hotkey_profile(&name, &bindings)
+            .map_err(|e| e.to_string())
+    })
+    .await
+}
+
+#[tauri::command]
+async fn delete_hotkey_profile(db: State<'_, DatabaseWorker>, name: String) -> Result<(), String> {
+    db.run(move |db| db.delete_hotkey_profile(&name).map_err(|e| e.to_string()))
+        .await
+}
+
+/// A hotkey profile switched to, with the hotkeys that couldn't be registered
+#[derive(Serialize)]
+struct AppliedHotkeyProfile {
+    profile: HotkeyProfile,
+    failures: Vec<HotkeyFailure>,
+}
+
+/// Switch every hotkey to profile `name`'s bindings, unbinding the actions
+/// it leaves out
+#[tauri::command]
+async fn apply_hotkey_profile(
+    app: AppHandle,
+    db: State<'_, DatabaseWorker>,
+    state: State<'_, RecordingState>,
+    name: String,
+) -> Result<AppliedHotkeyProfile, String> {
+    let profile = db
+        .run(move |db| {
+            db.get_hotkey_profile(&name)
+                .map_err(|e| e.to_string())?
+                .ok_or_else(|| format!("Hotkey profile not found: {}", name))
+        })
+        .await?;
+    let bindings = HotkeyAction::ALL
+        .into_iter()
+        .map(|action| (action, profile.bindings.get(&action).cloned()))
+        .collect();
+    let failures = set_hotkeys(app, state, bindings);
+    Ok(AppliedHotkeyProfile { profile, failures })
+}
+
+/// Save profile `name` to a JSON file the user picks, for sharing. Returns
+/// false when the dialog is cancelled.
+#[tauri::command]
+async fn export_hotkey_profile(
+    app: AppHandle,
+    db: State<'_, DatabaseWorker>,
+    name: String,
+) -> Result<bool, String> {
+    let mut profile = db
+        .run(move |db| {
+            db.get_hotkey_profile(&name)
+                .map_err(|e| e.to_string())?
+                .ok_or_else(|| format!("Hotkey profile not found: {}", name))
+        })
+        .await?;
+    profile.updated_at = 0;
+    let json = serde_json::to_vec_pretty(&profile).map_err(|e| e.to_string())?;
+
+    let Some(path) = app
+        .dialog()
+        .file()
+        .set_file_name(format!("{}.json", profile.name))
+        .add_filter("Hotkey profile", &["json"])
+        .blocking_save_file()
+    else {
+        return Ok(false);
+    };
+    let path = path
+        .into_path()
+        .map_err(|e| format!("Invalid save path: {}", e))?;
+    write_bytes_to_file(&path, &json)?;
+    Ok(true)
+}
+
+/// Add a profile from a file written by `export_hotkey_profile`, replacing
+/// any with the same name. None when the dialog is cancelled.
+#[tauri::command]
+async fn import_hotkey_profile(
+    app: AppHandle,
+    db: State<'_, DatabaseWorker>,
+) -> Result<Option<HotkeyProfile>, String> {
+    let Some(path) = app
+        .dialog()
+        .file()
+        .add_filter("Hotkey profile", &["json"])
+        .blocking_pick_file()
+    else {
+        return Ok(None);
+    };
+    let path = path
+        .into_path()
+        .map_err(|e| format!("Invalid file path: {}", e))?;
+    let json = std::fs::read(&path).map_err(|e| e.to_string())?;
+    let profile: HotkeyProfile =
+        serde_json::from_slice(&json).map_err(|e| format!("Not a hotkey profile: {}", e))?;
+    let name = validate_hotkey_profile(&profile.name, &profile.bindings)?;
+    db.run(move |db| {
+        db.save_hotkey_profile(&name, &profile.bindings)
+            .map(Some)
+            .map_err(|e| e.to_string())
+    })
+    .await
+}
+
+/// Run `work` on the blocking pool, for commands that process images
+/// between short database queries and so would hold up the database worker
+async fn on_blocking_pool<T: Send + 'static>(
+    app: &AppHandle,
+    work: impl FnOnce(&State<'_, DatabaseState>) -> Result<T, String> + Send + 'static,
+) -> Result<T, String> {
+    let app = app.clone();
+    tauri::async_runtime::spawn_blocking(move || work(&app.state::<DatabaseState>()))
+        .await
+        .map_err(|e| e.to_string())?
 }
 
 // Database commands
 #[tauri::command]
-fn create_recording(db: State<'_, DatabaseState>, name: String) -> Result<String, String> {
-    safe_db_lock(&db)?
-        .create_recording(name)
-        .map_err(|e| e.to_string())
+async fn create_recording(db: State<'_, DatabaseWorker>, name: String) -> Result<String, String> {
+    db.run(move |db| db.create_recording(name).map_err(|e| e.to_string()))
+        .await
 }
 
 #[tauri::command]
-fn save_steps(
-    db: State<'_, DatabaseState>,
+async fn save_steps(
+    app: AppHandle,
     recording_id: String,
     steps: Vec<StepInput>,
 ) -> Result<(), String> {
-    safe_db_lock(&db)?
-        .save_steps(&recording_id, steps)
-        .map_err(|e| e.to_string())
+    let id = recording_id.clone();
+    with_recording_image_settings(&app, recording_id, move |db| db.save_steps(&id, steps)).await
+}
+
+/// Run `save` on the database worker, then re-encode the images of the steps
+/// it added to `recording_id` when the recording has its own format, quality
+/// or size. Steps that fail to re-encode keep their images as captured.
+async fn with_recording_image_settings<T: Send + 'static>(
+    app: &AppHandle,
+    recording_id: String,
+    save: impl FnOnce(&Database) -> rusqlite::Result<T> + Send + 'static,
+) -> Result<T, String> {
+    let id = recording_id.clone();
+    let (saved, reencode) = app
+        .state::<DatabaseWorker>()
+        .run(move |db| {
+            let settings = db
+                .get_recording_image_settings(&id)
+                .map_err(|e| e.to_string())?;
+            // Steps already there were re-encoded when they were added
+            let reencode = if settings.overrides_encoding() {
+                let existing: HashSet<String> = db
+                    .step_images(&id)
+                    .map_err(|e| e.to_string())?
+                    .into_iter()
+                    .map(|(step_id, ..)| step_id)
+                    .collect();
+                Some((ReencodeSettings::for_recording(&settings), existing))
+            } else {
+                None
+            };
+            Ok((save(db).map_err(|e| e.to_string())?, reencode))
+        })
+        .await?;
+
+    if let Some((settings, existing)) = reencode {
+        let id = recording_id.clone();
+        let reencoded = on_blocking_pool(app, move |db| {
+            let mut report = ReencodeReport::default();
+            reencode_recording_screenshots(db, &id, &settings, &existing, &mut report)
+        })
+        .await;
+        if let Err(e) = reencoded {
+            logging::log(
+                logging::CATEGORY_DATABASE,
+                "error",
+                "Failed to apply recording image settings",
+                Some(&serde_json::json!({ "recording_id": recording_id, "error": e })),
+            );
+        }
+    }
+    Ok(saved)
+}
+
+/// The image settings `recording_id` overrides the global defaults with
+#[tauri::command]
+async fn get_recording_image_settings(
+    db: State<'_, DatabaseWorker>,
+    recording_id: String,
+) -> Result<RecordingImageSettings, String> {
+    db.run(move |db| {
+        db.get_recording_image_settings(&recording_id)
+            .map_err(|e| e.to_string())
+    })
+    .await
 }
 
+/// Give `recording_id` its own image settings. They apply to steps added
+/// from now on; `reencode_screenshots` brings existing ones in line.
 #[tauri::command]
-fn save_documentation(
-    db: State<'_, DatabaseState>,
+async fn set_recording_image_settings(
+    db: State<'_, DatabaseWorker>,
+    recording_id: String,
+    settings: RecordingImageSettings,
+) -> Result<(), String> {
+    db.run(move |db| {
+        db.set_recording_image_settings(&recording_id, &settings)
+            .map_err(|e| e.to_string())
+    })
+    .await
+}
+
+#[tauri::command]
+async fn save_documentation(
+    db: State<'_, DatabaseWorker>,
     recording_id: String,
     documentation: String,
 ) -> Result<(), String> {
-    safe_db_lock(&db)?
-        .save_documentation(&recording_id, &documentation)
-        .map_err(|e| e.to_string())
+    db.run(move |db| {
+        db.save_documentation(&recording_id, &documentation)
+            .map_err(|e| e.to_string())
+    })
+    .await
 }
 
 #[tauri::command]
-fn list_recordings(db: State<'_, DatabaseState>) -> Result<Vec<Recording>, String> {
-    safe_db_lock(&db)?
-        .list_recordings()
-        .map_err(|e| e.to_string())
+async fn list_recordings(db: State<'_, DatabaseWorker>) -> Result<Vec<Recording>, String> {
+    db.run(|db| db.list_recordings().map_err(|e| e.to_string()))
+        .await
 }
 
 #[tauri::command]
-fn list_recordings_paginated(
-    db: State<'_, DatabaseState>,
+async fn list_recordings_paginated(
+    db: State<'_, DatabaseWorker>,
     page: i32,
     per_page: i32,
     search: Option<String>,
 ) -> Result<PaginatedRecordings, String> {
-    safe_db_lock(&db)?
-        .list_recordings_paginated(page, per_page, search.as_deref())
-        .map_err(|e| e.to_string())
+    db.run(move |db| {
+        db.list_recordings_paginated(page, per_page, search.as_deref())
+            .map_err(|e| e.to_string())
+    })
+    .await
+}
+
+/// Steps whose screenshot text matches `query`, for jumping straight to the
+/// step that shows a label
+#[tauri::command]
+async fn search_step_text(
+    db: State<'_, DatabaseWorker>,
+    query: String,
+    limit: Option<i64>,
+) -> Result<Vec<database::StepTextMatch>, String> {
+    db.run(move |db| {
+        db.search_step_text(&query, limit.unwrap_or(50))
+            .map_err(|e| e.to_string())
+    })
+    .await
 }
 
 #[tauri::command]
-fn get_recording(
-    db: State<'_, DatabaseState>,
+async fn get_recording(
+    db: State<'_, DatabaseWorker>,
     id: String,
 ) -> Result<Option<RecordingWithSteps>, String> {
-    safe_db_lock(&db)?
-        .get_recording(&id)
-        .map_err(|e| e.to_string())
+    db.run(move |db| db.get_recording(&id).map_err(|e| e.to_string()))
+        .await
 }
 
 /// Progress event payload for delete operations
@@ -838,8 +1360,8 @@ struct DeleteProgress {
 }
 
 #[tauri::command]
-fn delete_recording(
-    db: State<'_, DatabaseState>,
+async fn delete_recording(
+    db: State<'_, DatabaseWorker>,
     id: String,
     app: AppHandle,
 ) -> Result<(), String> {
@@ -859,8 +1381,9 @@ fn delete_recording(
 
     // Get cleanup info from database (this also deletes DB records)
     let cleanup: DeleteRecordingCleanup = {
-        let db = safe_db_lock(&db)?;
-        db.delete_recording(&id).map_err(|e| e.to_string())?
+        let id = id.clone();
+        db.run(move |db| db.delete_recording(&id).map_err(|e| e.to_string()))
+            .await?
     };
 
     // Emit database deletion complete
@@ -963,65 +1486,218 @@ fn delete_recording(
         },
     );
 
-    // Log any warnings to stderr for debugging
     for warning in &warnings {
-        eprintln!("Delete warning: {}", warning);
+        tracing::warn!(recording_id = %id, "Delete warning: {}", warning);
     }
 
     Ok(())
 }
 
 #[tauri::command]
-fn update_recording_name(
-    db: State<'_, DatabaseState>,
+async fn update_recording_name(
+    db: State<'_, DatabaseWorker>,
     id: String,
     name: String,
 ) -> Result<(), String> {
-    safe_db_lock(&db)?
-        .update_recording_name(&id, &name)
-        .map_err(|e| e.to_string())
+    db.run(move |db| {
+        db.update_recording_name(&id, &name)
+            .map_err(|e| e.to_string())
+    })
+    .await
 }
 
+/// Rename several recordings at once from a pattern such as
+/// `"Onboarding {n} – {name}"`. `{n}` counts up from `start` (default 1) in the
+/// order of `ids`, zero-padded to `pad_width` digits; `{date}` is the creation
+/// date. Either every recording is renamed or none is.
 #[tauri::command]
-fn get_default_screenshot_path(db: State<'_, DatabaseState>) -> Result<String, String> {
-    let path = safe_db_lock(&db)?.get_default_screenshot_path();
-    Ok(path.to_string_lossy().to_string())
+async fn rename_recordings(
+    db: State<'_, DatabaseWorker>,
+    ids: Vec<String>,
+    pattern: String,
+    start: Option<i64>,
+    pad_width: Option<usize>,
+) -> Result<Vec<RenamedRecording>, String> {
+    if pattern.trim().is_empty() {
+        return Err("Rename pattern cannot be empty".to_string());
+    }
+    if ids.len() > 1 && !pattern.contains("{n}") && !pattern.contains("{name}") {
+        return Err(
+            "Pattern must contain {n} or {name} to give each recording a distinct name".to_string(),
+        );
+    }
+    db.run(move |db| {
+        db.rename_recordings(
+            &ids,
+            &pattern,
+            start.unwrap_or(1),
+            pad_width.unwrap_or(0).min(6),
+        )
+        .map_err(|e| e.to_string())
+    })
+    .await
 }
 
+/// Drop the draft journal once the in-flight steps have been saved or
+/// discarded by the frontend.
 #[tauri::command]
-fn validate_screenshot_path(path: String) -> Result<bool, String> {
-    let path = PathBuf::from(&path);
-
-    // Check if path exists and is a directory
-    if !path.exists() {
-        // Try to create it
-        if let Err(e) = std::fs::create_dir_all(&path) {
-            return Err(format!("Cannot create directory: {}", e));
-        }
-    } else if !path.is_dir() {
-        return Err("Path is not a directory".to_string());
-    }
+async fn clear_drafts(db: State<'_, DatabaseWorker>) -> Result<(), String> {
+    db.run(|db| db.clear_drafts().map_err(|e| e.to_string()))
+        .await
+}
 
-    // Check if writable by creating a temp file
-    let test_file = path.join(".stepsnap_write_test");
-    match std::fs::write(&test_file, "test") {
-        Ok(_) => {
-            let _ = std::fs::remove_file(&test_file);
-            Ok(true)
-        }
-        Err(e) => Err(format!("Directory is not writable: {}", e)),
-    }
+#[tauri::command]
+async fn toggle_recording_favorite(
+    db: State<'_, DatabaseWorker>,
+    id: String,
+) -> Result<bool, String> {
+    db.run(move |db| db.toggle_recording_favorite(&id).map_err(|e| e.to_string()))
+        .await
 }
 
+/// Audit trail of recording modifications, newest first. Pass a recording ID
+/// to limit it to one recording.
 #[tauri::command]
-fn read_file_base64(path: String) -> Result<String, String> {
-    let bytes = read_validated_file_bytes(std::path::Path::new(&path))?;
-    Ok(general_purpose::STANDARD.encode(bytes))
+async fn list_audit_log(
+    db: State<'_, DatabaseWorker>,
+    recording_id: Option<String>,
+    limit: i32,
+    offset: i32,
+) -> Result<Vec<AuditEntry>, String> {
+    db.run(move |db| {
+        db.list_audit_log(recording_id.as_deref(), limit, offset)
+            .map_err(|e| e.to_string())
+    })
+    .await
 }
 
 #[tauri::command]
-fn read_file_bytes(path: String) -> Result<Vec<u8>, String> {
-    read_validated_file_bytes(std::path::Path::new(&path))
+async fn set_recording_locked(
+    db: State<'_, DatabaseWorker>,
+    id: String,
+    locked: bool,
+) -> Result<(), String> {
+    db.run(move |db| {
+        db.set_recording_locked(&id, locked)
+            .map_err(|e| e.to_string())
+    })
+    .await
+}
+
+/// Disk usage per recording plus the library total. Figures are cached and
+/// only recomputed for recordings changed since, unless `refresh` is set.
+#[tauri::command]
+async fn get_storage_usage(
+    db: State<'_, DatabaseWorker>,
+    refresh: Option<bool>,
+) -> Result<StorageUsage, String> {
+    db.run(move |db| {
+        db.get_storage_usage(refresh.unwrap_or(false))
+            .map_err(|e| e.to_string())
+    })
+    .await
+}
+
+#[tauri::command]
+async fn get_statistics(
+    db: State<'_, DatabaseWorker>,
+    recent_limit: Option<i32>,
+) -> Result<RecordingStatistics, String> {
+    db.run(move |db| {
+        db.get_statistics(recent_limit.unwrap_or(5))
+            .map_err(|e| e.to_string())
+    })
+    .await
+}
+
+/// Local feature usage and capture timings of the last `days` days (7 by
+/// default) for the insights view. Empty unless analytics are turned on.
+#[tauri::command]
+async fn get_usage_report(
+    db: State<'_, DatabaseWorker>,
+    days: Option<i64>,
+) -> Result<analytics::UsageReport, String> {
+    db.run(move |db| analytics::report(db, days.unwrap_or(7)).map_err(|e| e.to_string()))
+        .await
+}
+
+#[tauri::command]
+async fn clear_usage_data(db: State<'_, DatabaseWorker>) -> Result<(), String> {
+    db.run(|db| analytics::clear(db).map_err(|e| e.to_string()))
+        .await
+}
+
+#[tauri::command]
+async fn get_default_screenshot_path(db: State<'_, DatabaseWorker>) -> Result<String, String> {
+    db.run(|db| {
+        Ok(db
+            .get_default_screenshot_path()
+            .to_string_lossy()
+            .to_string())
+    })
+    .await
+}
+
+#[tauri::command]
+fn validate_screenshot_path(path: String) -> Result<bool, String> {
+    let path = PathBuf::from(&path);
+
+    // Check if path exists and is a directory
+    if !path.exists() {
+        // Try to create it
+        if let Err(e) = std::fs::create_dir_all(&path) {
+            return Err(format!("Cannot create directory: {}", e));
+        }
+    } else if !path.is_dir() {
+        return Err("Path is not a directory".to_string());
+    }
+
+    // Check if writable by creating a temp file
+    let test_file = path.join(".stepsnap_write_test");
+    match std::fs::write(&test_file, "test") {
+        Ok(_) => {
+            let _ = std::fs::remove_file(&test_file);
+            Ok(true)
+        }
+        Err(e) => Err(format!("Directory is not writable: {}", e)),
+    }
+}
+
+#[tauri::command]
+fn read_file_base64(path: String) -> Result<String, String> {
+    let bytes = read_validated_file_bytes(std::path::Path::new(&path))?;
+    Ok(general_purpose::STANDARD.encode(bytes))
+}
+
+/// A screenshot as base64 JPEG with the click highlight drawn at `marker`
+/// in the default style, which is what the AI prompts describe. The file
+/// itself is left untouched.
+#[tauri::command]
+fn read_highlighted_screenshot_base64(
+    path: String,
+    marker: annotations::Point,
+) -> Result<String, String> {
+    use image::codecs::jpeg::JpegEncoder;
+
+    let bytes = read_validated_file_bytes(std::path::Path::new(&path))?;
+    let mut image = image::load_from_memory(&bytes)
+        .map_err(|e| format!("Failed to read image: {}", e))?
+        .to_rgba8();
+    annotations::draw_click_highlight(
+        &mut image,
+        marker,
+        &annotations::ClickHighlightStyle::default(),
+    );
+    let mut encoded = Vec::new();
+    JpegEncoder::new_with_quality(&mut encoded, 85)
+        .encode_image(&image::DynamicImage::ImageRgba8(image).to_rgb8())
+        .map_err(|e| format!("Failed to encode image: {}", e))?;
+    Ok(general_purpose::STANDARD.encode(encoded))
+}
+
+#[tauri::command]
+fn read_file_bytes(path: String) -> Result<Vec<u8>, String> {
+    read_validated_file_bytes(std::path::Path::new(&path))
 }
 
 #[tauri::command]
@@ -1228,24 +1904,20 @@ async fn ai_chat_completion(
     body: serde_json::Value,
     retry_config: Option<AiRetryConfig>,
 ) -> Result<String, String> {
+    analytics::track("ai_generation");
     let validated_base_url = validate_ai_base_url(&base_url)?;
     let retry_config = retry_config.unwrap_or_default();
     post_ai_chat_completion(&validated_base_url, api_key.trim(), &body, &retry_config).await
 }
 
 #[tauri::command]
-fn register_asset_scope(
-    app: AppHandle,
-    path: String,
-    db: State<'_, DatabaseState>,
-) -> Result<(), String> {
+fn register_asset_scope(app: AppHandle, path: String) -> Result<(), String> {
     let path = PathBuf::from(&path);
 
     if path.as_os_str().is_empty() {
         return Ok(());
     }
 
-    drop(safe_db_lock(&db)?);
     let validated_path = normalize_directory_path(&path)?;
 
     // Ensure directory exists
@@ -1261,20 +1933,39 @@ fn register_asset_scope(
 }
 
 #[tauri::command]
-fn save_cropped_image(
+async fn save_cropped_image(
+    app: AppHandle,
     path: String,
     base64_data: String,
-    db: State<'_, DatabaseState>,
 ) -> Result<String, String> {
-    let path_buf = PathBuf::from(&path);
-    drop(safe_db_lock(&db)?);
+    on_blocking_pool(&app, move |db| write_cropped_image(db, &path, &base64_data)).await
+}
+
+fn write_cropped_image(
+    db: &State<'_, DatabaseState>,
+    path: &str,
+    base64_data: &str,
+) -> Result<String, String> {
+    let path_buf = PathBuf::from(path);
+    let is_object = safe_db_lock(db)?
+        .is_screenshot_object(path)
+        .map_err(|e| e.to_string())?;
     let validated_path = normalize_file_path(&path_buf)?;
 
     // Decode base64 to bytes
     let image_data = general_purpose::STANDARD
-        .decode(&base64_data)
+        .decode(base64_data)
         .map_err(|e| format!("Failed to decode base64: {}", e))?;
 
+    // Managed objects can be shared by several steps, so the crop becomes a
+    // new object; the caller points the step at the returned path.
+    if is_object {
+        let object_path = safe_db_lock(db)?
+            .store_screenshot_object(&image_data, "jpg")
+            .map_err(|e| format!("Failed to store screenshot: {}", e))?;
+        return Ok(object_path.to_string_lossy().to_string());
+    }
+
     if let Some(parent) = validated_path.parent() {
         std::fs::create_dir_all(parent)
             .map_err(|e| format!("Failed to create parent directory: {}", e))?;
@@ -1292,8 +1983,8 @@ fn save_cropped_image(
 /// Copy a screenshot from temp location to permanent storage immediately.
 /// Used when recording additional steps for an existing recording so images display immediately.
 #[tauri::command]
-fn copy_screenshot_to_permanent(
-    db: State<'_, DatabaseState>,
+async fn copy_screenshot_to_permanent(
+    db: State<'_, DatabaseWorker>,
     temp_path: String,
     recording_id: String,
     recording_name: String,
@@ -1309,7 +2000,7 @@ fn copy_screenshot_to_permanent(
     // Get the base directory (custom path or default)
     let base_dir = match normalize_optional_directory_path(custom_screenshot_path)? {
         Some(path) => path,
-        None => safe_db_lock(&db)?.screenshots_dir(),
+        None => db.run(|db| Ok(db.screenshots_dir())).await?,
     };
 
     // Create recording-specific subfolder with sanitized name
@@ -1320,7 +2011,11 @@ fn copy_screenshot_to_permanent(
 
     // Generate unique filename
     let step_id = Uuid::new_v4().to_string();
-    let filename = format!("{}_{}.jpg", recording_id, step_id);
+    let extension = temp_path_buf
+        .extension()
+        .and_then(|extension| extension.to_str())
+        .unwrap_or("jpg");
+    let filename = format!("{}_{}.{}", recording_id, step_id, extension);
     let dest_path = screenshots_dir.join(&filename);
 
     // Copy the file
@@ -1334,78 +2029,1478 @@ fn copy_screenshot_to_permanent(
 }
 
 #[tauri::command]
-fn update_step_screenshot(
-    db: State<'_, DatabaseState>,
+async fn update_step_screenshot(
+    db: State<'_, DatabaseWorker>,
     step_id: String,
     screenshot_path: String,
     is_cropped: bool,
 ) -> Result<(), String> {
-    safe_db_lock(&db)?
-        .update_step_screenshot(&step_id, &screenshot_path, is_cropped)
-        .map_err(|e| e.to_string())
+    db.run(move |db| {
+        db.update_step_screenshot(&step_id, &screenshot_path, is_cropped)
+            .map_err(|e| e.to_string())
+    })
+    .await
 }
 
+/// Rotate, flip or resize one of a step's images (its screenshot unless
+/// `image` says otherwise). The result is written to a new file and the step
+/// is pointed at it in one transaction, with its click position, marker,
+/// annotations and redactions moved along. Returns the new path.
 #[tauri::command]
-fn reorder_steps(
-    db: State<'_, DatabaseState>,
+async fn edit_step_image(
+    app: AppHandle,
+    step_id: String,
+    image: Option<database::StepImage>,
+    edit: image_edit::ImageEdit,
+) -> Result<String, String> {
+    analytics::track("image_edit");
+    let image = image.unwrap_or(database::StepImage::Screenshot);
+    on_blocking_pool(&app, move |db| {
+        write_step_image_edit(db, &step_id, image, edit)
+    })
+    .await
+}
+
+fn write_step_image_edit(
+    db: &State<'_, DatabaseState>,
+    step_id: &str,
+    image: database::StepImage,
+    edit: image_edit::ImageEdit,
+) -> Result<String, String> {
+    use image::codecs::jpeg::JpegEncoder;
+
+    let (source, is_object, original) = {
+        let db = safe_db_lock(db)?;
+        let step = db
+            .get_step(step_id)
+            .map_err(|e| e.to_string())?
+            .ok_or_else(|| format!("Step not found: {}", step_id))?;
+        let (source, original) = match image {
+            database::StepImage::Screenshot => (step.screenshot_path, step.original_path),
+            database::StepImage::After => (step.screenshot_after_path, None),
+            database::StepImage::Element => (step.element_screenshot_path, None),
+        };
+        let source = source.ok_or_else(|| "Step has no such image".to_string())?;
+        let is_object = db
+            .is_screenshot_object(&source)
+            .map_err(|e| e.to_string())?;
+        let original = match original {
+            Some(original) => {
+                let is_object = db
+                    .is_screenshot_object(&original)
+                    .map_err(|e| e.to_string())?;
+                Some((PathBuf::from(original), is_object))
+            }
+            None => None,
+        };
+        (PathBuf::from(source), is_object, original)
+    };
+
+    // Edit the lossless original when there is one, so the JPEG is only
+    // encoded once
+    let pixels = original.as_ref().map_or(&source, |(path, _)| path);
+    let unedited = image::open(pixels).map_err(|e| format!("Failed to read image: {}", e))?;
+    let edited = edit.apply(&unedited);
+    let mut encoded = Vec::new();
+    JpegEncoder::new_with_quality(&mut encoded, 90)
+        .encode_image(&edited.to_rgb8())
+        .map_err(|e| format!("Failed to encode image: {}", e))?;
+
+    let mut written: Vec<PathBuf> = Vec::new();
+    let result = store_replacement_screenshot(
+        db,
+        &source,
+        is_object,
+        "edited",
+        "jpg",
+        &encoded,
+        &mut written,
+    )
+    .and_then(|dest| {
+        let edited_original = match &original {
+            Some((path, is_object)) => Some(store_replacement_screenshot(
+                db,
+                path,
+                *is_object,
+                "edited",
+                "png",
+                &encode_png(&edited)?,
+                &mut written,
+            )?),
+            None => None,
+        };
+        let dest = dest.to_string_lossy().to_string();
+        let edited_original = edited_original.map(|path| path.to_string_lossy().to_string());
+        let orphaned = safe_db_lock(db)?
+            .replace_step_image_edited(
+                step_id,
+                image,
+                &dest,
+                edited_original.as_deref(),
+                edit,
+                (unedited.width(), unedited.height()),
+            )
+            .map_err(|e| e.to_string())?;
+        Ok((dest, orphaned))
+    });
+    match result {
+        Ok((dest, orphaned)) => {
+            for path in orphaned {
+                let _ = std::fs::remove_file(path);
+            }
+            Ok(dest)
+        }
+        Err(e) => {
+            for path in written {
+                let _ = std::fs::remove_file(path);
+            }
+            Err(e)
+        }
+    }
+}
+
+/// `image` as PNG bytes, for lossless originals
+fn encode_png(image: &image::DynamicImage) -> Result<Vec<u8>, String> {
+    let mut encoded = std::io::Cursor::new(Vec::new());
+    image
+        .write_to(&mut encoded, image::ImageFormat::Png)
+        .map_err(|e| format!("Failed to encode image: {}", e))?;
+    Ok(encoded.into_inner())
+}
+
+#[tauri::command]
+async fn reorder_steps(
+    db: State<'_, DatabaseWorker>,
     recording_id: String,
     step_ids: Vec<String>,
 ) -> Result<(), String> {
-    safe_db_lock(&db)?
-        .reorder_steps(&recording_id, step_ids)
-        .map_err(|e| e.to_string())
+    db.run(move |db| {
+        db.reorder_steps(&recording_id, step_ids)
+            .map_err(|e| e.to_string())
+    })
+    .await
 }
 
 #[tauri::command]
-fn update_step_description(
-    db: State<'_, DatabaseState>,
+async fn update_step_description(
+    db: State<'_, DatabaseWorker>,
     step_id: String,
     description: String,
 ) -> Result<(), String> {
-    safe_db_lock(&db)?
-        .update_step_description(&step_id, &description)
-        .map_err(|e| e.to_string())
+    db.run(move |db| {
+        db.update_step_description(&step_id, &description)
+            .map_err(|e| e.to_string())
+    })
+    .await
 }
 
+/// Replace the arrows, boxes and labels drawn over a step's screenshot
 #[tauri::command]
-fn update_step_title(
-    db: State<'_, DatabaseState>,
+async fn update_step_annotations(
+    db: State<'_, DatabaseWorker>,
+    step_id: String,
+    annotations: Vec<annotations::Annotation>,
+) -> Result<(), String> {
+    db.run(move |db| {
+        db.update_step_annotations(&step_id, &annotations)
+            .map_err(|e| e.to_string())
+    })
+    .await
+}
+
+/// Move a step's click highlight, or remove it with a null `marker`
+#[tauri::command]
+async fn update_step_click_marker(
+    db: State<'_, DatabaseWorker>,
+    step_id: String,
+    marker: Option<annotations::Point>,
+) -> Result<(), String> {
+    db.run(move |db| {
+        db.update_step_click_marker(&step_id, marker)
+            .map_err(|e| e.to_string())
+    })
+    .await
+}
+
+/// Set the areas of a step's screenshot to blur or pixelate. With
+/// `keep_original` they're stored and applied at export like annotations.
+/// Otherwise they're burned into new copies of the screenshot, its after
+/// frame and element crop straight away, and the unredacted files are
+/// released. Returns the updated step.
+#[tauri::command]
+async fn set_step_redactions(
+    app: AppHandle,
+    step_id: String,
+    redactions: Vec<annotations::Redaction>,
+    keep_original: bool,
+) -> Result<Step, String> {
+    analytics::track("redaction");
+    on_blocking_pool(&app, move |db| {
+        apply_step_redactions(db, &step_id, &redactions, keep_original)
+    })
+    .await
+}
+
+fn apply_step_redactions(
+    db: &State<'_, DatabaseState>,
+    step_id: &str,
+    redactions: &[annotations::Redaction],
+    keep_original: bool,
+) -> Result<Step, String> {
+    let step = safe_db_lock(db)?
+        .get_step(step_id)
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| format!("Step not found: {}", step_id))?;
+
+    if keep_original || redactions.is_empty() {
+        let db = safe_db_lock(db)?;
+        db.update_step_redactions(step_id, redactions)
+            .map_err(|e| e.to_string())?;
+        return db
+            .get_step(step_id)
+            .map_err(|e| e.to_string())?
+            .ok_or_else(|| format!("Step not found: {}", step_id));
+    }
+
+    let mut written: Vec<PathBuf> = Vec::new();
+    let result = write_redacted_screenshots(db, &step, redactions, &mut written).and_then(
+        |(screenshot, after, element, original)| {
+            safe_db_lock(db)?
+                .replace_step_images_redacted(
+                    step_id,
+                    screenshot.as_deref(),
+                    after.as_deref(),
+                    element.as_deref(),
+                    original.as_deref(),
+                )
+                .map_err(|e| e.to_string())
+        },
+    );
+
+    match result {
+        Ok(orphaned) => {
+            for path in orphaned {
+                let _ = std::fs::remove_file(path);
+            }
+            safe_db_lock(db)?
+                .get_step(step_id)
+                .map_err(|e| e.to_string())?
+                .ok_or_else(|| format!("Step not found: {}", step_id))
+        }
+        Err(e) => {
+            for path in written {
+                let _ = std::fs::remove_file(path);
+            }
+            Err(e)
+        }
+    }
+}
+
+/// Proposed redactions for a recording: the areas of password fields and of
+/// fields showing personal data, on steps the user hasn't reviewed yet.
+/// Accepting goes through `set_step_redactions`.
+#[tauri::command]
+async fn get_redaction_suggestions(
+    db: State<'_, DatabaseWorker>,
+    recording_id: String,
+) -> Result<Vec<pii::RedactionSuggestion>, String> {
+    let recording = db
+        .run(move |db| {
+            db.get_recording(&recording_id)
+                .map_err(|e| e.to_string())?
+                .ok_or_else(|| format!("Recording not found: {}", recording_id))
+        })
+        .await?;
+    Ok(recording
+        .steps
+        .iter()
+        .filter(|step| !step.redactions_reviewed && step.screenshot_path.is_some())
+        .filter_map(pii::suggest_redaction)
+        .collect())
+}
+
+/// Stop suggesting redactions for these steps
+#[tauri::command]
+async fn dismiss_redaction_suggestions(
+    db: State<'_, DatabaseWorker>,
+    step_ids: Vec<String>,
+) -> Result<(), String> {
+    db.run(move |db| {
+        db.dismiss_redaction_suggestions(&step_ids)
+            .map_err(|e| e.to_string())
+    })
+    .await
+}
+
+/// Redacted copies of a step's screenshot, after frame, element crop and
+/// lossless original, or None for each one no redaction reaches.
+fn write_redacted_screenshots(
+    db: &State<'_, DatabaseState>,
+    step: &Step,
+    redactions: &[annotations::Redaction],
+    written: &mut Vec<PathBuf>,
+) -> Result<RedactedScreenshots, String> {
+    // A screenshot with an original is redacted from it, and the redacted
+    // original replaces the unredacted one
+    let (screenshot, original) = match (&step.screenshot_path, &step.original_path) {
+        (Some(path), Some(original)) => match redacted_image(original, redactions)? {
+            Some(image) => (
+                Some(store_redacted_image(db, path, &image, "jpg", written)?),
+                Some(store_redacted_image(db, original, &image, "png", written)?),
+            ),
+            None => (None, None),
+        },
+        (path, _) => (redact_file(db, path, redactions, written)?, None),
+    };
+    let after = redact_file(db, &step.screenshot_after_path, redactions, written)?;
+    // After a crop the screenshot no longer lines up with the element bounds
+    let element = match &step.element_bounds {
+        Some(bounds) if !step.is_cropped.unwrap_or(false) => {
+            let origin = annotations::element_crop_origin(bounds);
+            let shifted: Vec<_> = redactions
+                .iter()
+                .map(|redaction| redaction.relative_to(origin))
+                .collect();
+            redact_file(db, &step.element_screenshot_path, &shifted, written)?
+        }
+        _ => None,
+    };
+    Ok((screenshot, after, element, original))
+}
+
+/// Paths of a step's redacted screenshot, after frame, element crop and
+/// original; see `write_redacted_screenshots`
+type RedactedScreenshots = (
+    Option<String>,
+    Option<String>,
+    Option<String>,
+    Option<String>,
+);
+
+fn redact_file(
+    db: &State<'_, DatabaseState>,
+    path: &Option<String>,
+    redactions: &[annotations::Redaction],
+    written: &mut Vec<PathBuf>,
+) -> Result<Option<String>, String> {
+    let Some(path) = path else {
+        return Ok(None);
+    };
+    match redacted_image(path, redactions)? {
+        Some(image) => store_redacted_image(db, path, &image, "jpg", written).map(Some),
+        None => Ok(None),
+    }
+}
+
+/// The image at `path` with `redactions` applied, or None when none of them
+/// reach it
+fn redacted_image(
+    path: &str,
+    redactions: &[annotations::Redaction],
+) -> Result<Option<image::DynamicImage>, String> {
+    let source = normalize_file_path(std::path::Path::new(path))?;
+    let mut image = image::open(&source)
+        .map_err(|e| format!("Failed to read image: {}", e))?
+        .to_rgba8();
+    if !annotations::redact(&mut image, redactions) {
+        return Ok(None);
+    }
+    Ok(Some(image::DynamicImage::ImageRgba8(image)))
+}
+
+/// Store a redacted `image` that will replace `path`, as a JPEG or, for
+/// lossless originals, a PNG
+fn store_redacted_image(
+    db: &State<'_, DatabaseState>,
+    path: &str,
+    image: &image::DynamicImage,
+    extension: &str,
+    written: &mut Vec<PathBuf>,
+) -> Result<String, String> {
+    use image::codecs::jpeg::JpegEncoder;
+
+    let is_object = safe_db_lock(db)?
+        .is_screenshot_object(path)
+        .map_err(|e| e.to_string())?;
+    let source = normalize_file_path(std::path::Path::new(path))?;
+    let encoded = if extension == "png" {
+        encode_png(image)?
+    } else {
+        let mut encoded = Vec::new();
+        JpegEncoder::new_with_quality(&mut encoded, 85)
+            .encode_image(&image.to_rgb8())
+            .map_err(|e| format!("Failed to encode image: {}", e))?;
+        encoded
+    };
+
+    let dest = store_replacement_screenshot(
+        db, &source, is_object, "redacted", extension, &encoded, written,
+    )?;
+    Ok(dest.to_string_lossy().to_string())
+}
+
+/// Render a recording's annotated and redacted screenshots for export in the
+/// saved export styles (see `ExportStyles`). Returns original path ->
+/// rendered copy; untouched images aren't listed.
+#[tauri::command]
+async fn render_annotated_screenshots(
+    db: State<'_, DatabaseWorker>,
+    settings: State<'_, SettingsState>,
+    recording_id: String,
+) -> Result<std::collections::HashMap<String, String>, String> {
+    let styles = ExportStyles::from_settings(&settings.0.lock().unwrap());
+    let id = recording_id.clone();
+    let (recording, image_settings) = db
+        .run(move |db| {
+            let recording = db
+                .get_recording(&id)
+                .map_err(|e| e.to_string())?
+                .ok_or_else(|| format!("Recording not found: {}", id))?;
+            let image_settings = db
+                .get_recording_image_settings(&id)
+                .map_err(|e| e.to_string())?;
+            Ok((recording, image_settings))
+        })
+        .await?;
+    tauri::async_runtime::spawn_blocking(move || {
+        styles.render(
+            &recording.steps,
+            &annotated_export_dir(&recording_id),
+            image_settings.highlight,
+        )
+    })
+    .await
+    .map_err(|e| e.to_string())
+}
+
+/// Where annotated screenshots are rendered for export
+fn annotated_export_dir(recording_id: &str) -> PathBuf {
+    std::env::temp_dir()
+        .join("stepsnap_annotated")
+        .join(recording_id)
+}
+
+#[tauri::command]
+async fn update_step_title(
+    db: State<'_, DatabaseWorker>,
     step_id: String,
     title: String,
 ) -> Result<(), String> {
-    safe_db_lock(&db)?
-        .update_step_title(&step_id, &title)
+    db.run(move |db| {
+        db.update_step_title(&step_id, &title)
+            .map_err(|e| e.to_string())
+    })
+    .await
+}
+
+#[tauri::command]
+async fn delete_step(db: State<'_, DatabaseWorker>, step_id: String) -> Result<(), String> {
+    db.run(move |db| db.delete_step(&step_id).map_err(|e| e.to_string()))
+        .await
+}
+
+#[tauri::command]
+async fn save_steps_with_path(
+    app: AppHandle,
+    recording_id: String,
+    recording_name: String,
+    steps: Vec<StepInput>,
+    screenshot_path: Option<String>,
+) -> Result<(), String> {
+    let normalized_screenshot_path =
+        normalize_optional_directory_path(screenshot_path)?.map(|path| {
+            path.to_string_lossy().to_string()
+        });
+
+    let id = recording_id.clone();
+    with_recording_image_settings(&app, recording_id, move |db| {
+        db.save_steps_with_path(
+            &id,
+            &recording_name,
+            steps,
+            normalized_screenshot_path.as_deref(),
+        )
+    })
+    .await
+}
+
+/// Insert a single step at `position` in an existing recording. The step can
+/// carry a temp capture, a permanent path from `import_step_image`, or no
+/// screenshot at all (text-only note). Later steps are renumbered.
+#[tauri::command]
+async fn insert_step(
+    app: AppHandle,
+    recording_id: String,
+    recording_name: String,
+    position: i32,
+    step: StepInput,
+    screenshot_path: Option<String>,
+) -> Result<Step, String> {
+    let normalized_screenshot_path = normalize_optional_directory_path(screenshot_path)?
+        .map(|path| path.to_string_lossy().to_string());
+
+    let id = recording_id.clone();
+    let step = with_recording_image_settings(&app, recording_id, move |db| {
+        db.insert_step(
+            &id,
+            &recording_name,
+            position,
+            step,
+            normalized_screenshot_path.as_deref(),
+        )
+    })
+    .await?;
+    // Re-read in case its images were re-encoded
+    app.state::<DatabaseWorker>()
+        .run(move |db| {
+            Ok(db
+                .get_step(&step.id)
+                .map_err(|e| e.to_string())?
+                .unwrap_or(step))
+        })
+        .await
+}
+
+/// Import an image file from disk as a step screenshot. The source is decoded
+/// and re-encoded as JPEG into the recording's screenshot folder (the original
+/// is left untouched), and the permanent path is returned so the frontend can
+/// pass it to `insert_step` with `screenshot_is_permanent`.
+#[tauri::command]
+async fn import_step_image(
+    app: AppHandle,
+    source_path: String,
+    recording_id: String,
+    recording_name: String,
+    custom_screenshot_path: Option<String>,
+) -> Result<String, String> {
+    on_blocking_pool(&app, move |db| {
+        write_imported_step_image(
+            db,
+            &source_path,
+            &recording_id,
+            &recording_name,
+            custom_screenshot_path,
+        )
+    })
+    .await
+}
+
+fn write_imported_step_image(
+    db: &State<'_, DatabaseState>,
+    source_path: &str,
+    recording_id: &str,
+    recording_name: &str,
+    custom_screenshot_path: Option<String>,
+) -> Result<String, String> {
+    use image::codecs::jpeg::JpegEncoder;
+    use std::io::BufWriter;
+    use uuid::Uuid;
+
+    let source = normalize_file_path(std::path::Path::new(source_path))?;
+    let image = image::open(&source).map_err(|e| format!("Failed to read image: {}", e))?;
+
+    let normalized_screenshot_path = normalize_optional_directory_path(custom_screenshot_path)?
+        .map(|path| path.to_string_lossy().to_string());
+    let screenshots_dir = safe_db_lock(db)?
+        .recording_screenshots_dir(recording_name, normalized_screenshot_path.as_deref());
+
+    let filename = format!("{}_{}.jpg", recording_id, Uuid::new_v4());
+    let dest_path = screenshots_dir.join(&filename);
+
+    let file =
+        std::fs::File::create(&dest_path).map_err(|e| format!("Failed to create file: {}", e))?;
+    let mut writer = BufWriter::new(file);
+    let mut encoder = JpegEncoder::new_with_quality(&mut writer, 85);
+    encoder
+        .encode_image(&image.to_rgb8())
+        .map_err(|e| format!("Failed to encode image: {}", e))?;
+
+    Ok(dest_path.to_string_lossy().to_string())
+}
+
+/// Render a recording to Markdown from its stored steps. Text-only steps
+/// (`heading`, `note`, `warning`) are rendered as sections and callouts.
+#[tauri::command]
+async fn export_recording_markdown(
+    app: AppHandle,
+    settings: State<'_, SettingsState>,
+    recording_id: String,
+) -> Result<String, String> {
+    analytics::track("markdown_export");
+    let (locale, styles) = {
+        let settings = settings.0.lock().unwrap();
+        (settings.locale, ExportStyles::from_settings(&settings))
+    };
+    recording_markdown(&app, recording_id, locale, styles).await
+}
+
+/// How screenshots are drawn for an export: step number badges, the smart
+/// crop, click highlights, zoom insets and the watermark
+struct ExportStyles {
+    badge_style: annotations::StepBadgeStyle,
+    crop: smart_crop::SmartCropSettings,
+    /// Overridden by the recording's own highlight style, if it has one
+    highlight: annotations::ClickHighlightStyle,
+    inset: zoom_inset::ZoomInsetStyle,
+    watermark: watermark::WatermarkStyle,
+}
+
+impl ExportStyles {
+    /// The styles saved in `settings`
+    fn from_settings(settings: &Settings) -> Self {
+        Self {
+            badge_style: settings.step_badge_style.clone(),
+            crop: settings.smart_crop,
+            highlight: settings.click_highlight_style.clone(),
+            inset: settings.zoom_inset.clone(),
+            watermark: settings.watermark.clone(),
+        }
+    }
+
+    /// Render `steps` for export into `dir`, with `highlight`, the
+    /// recording's own highlight style, in place of ours when it has one.
+    /// Returns original path -> rendered copy, like
+    /// `annotations::render_for_export`.
+    fn render(
+        &self,
+        steps: &[Step],
+        dir: &std::path::Path,
+        highlight: Option<annotations::ClickHighlightStyle>,
+    ) -> HashMap<String, String> {
+        annotations::render_for_export(
+            steps,
+            dir,
+            &self.badge_style,
+            &self.crop,
+            highlight.as_ref().unwrap_or(&self.highlight),
+            &self.inset,
+            &self.watermark,
+        )
+    }
+}
+
+/// What a recording's Markdown export reads from the database
+struct MarkdownSource {
+    recording: RecordingWithSteps,
+    links: Vec<RecordingLink>,
+    image_settings: RecordingImageSettings,
+}
+
+impl MarkdownSource {
+    fn load(db: &Database, recording_id: &str) -> Result<Self, String> {
+        let recording = db
+            .get_recording(recording_id)
+            .map_err(|e| e.to_string())?
+            .ok_or_else(|| format!("Recording not found: {}", recording_id))?;
+        let links = db
+            .list_recording_links(recording_id)
+            .map_err(|e| e.to_string())?;
+        let image_settings = db
+            .get_recording_image_settings(recording_id)
+            .map_err(|e| e.to_string())?;
+        Ok(Self {
+            recording,
+            links,
+            image_settings,
+        })
+    }
+}
+
+/// `recording_id` as Markdown, with its screenshots rendered in `styles`.
+/// Reads the recording on the database worker and renders on the blocking
+/// pool, so the worker isn't held up by the images.
+async fn recording_markdown(
+    app: &AppHandle,
+    recording_id: String,
+    locale: i18n::Locale,
+    styles: ExportStyles,
+) -> Result<String, String> {
+    let source = app
+        .state::<DatabaseWorker>()
+        .run(move |db| MarkdownSource::load(db, &recording_id))
+        .await?;
+    tauri::async_runtime::spawn_blocking(move || render_markdown_source(source, locale, styles))
+        .await
         .map_err(|e| e.to_string())
 }
 
+/// Like `recording_markdown`, for the CLI, which opens the database itself
+fn render_recording_markdown(
+    db: &Database,
+    recording_id: &str,
+    locale: i18n::Locale,
+    styles: ExportStyles,
+) -> Result<String, String> {
+    let source = MarkdownSource::load(db, recording_id)?;
+    Ok(render_markdown_source(source, locale, styles))
+}
+
+fn render_markdown_source(
+    source: MarkdownSource,
+    locale: i18n::Locale,
+    styles: ExportStyles,
+) -> String {
+    let MarkdownSource {
+        mut recording,
+        links,
+        image_settings,
+    } = source;
+    let annotated = styles.render(
+        &recording.steps,
+        &annotated_export_dir(&recording.recording.id),
+        image_settings.highlight,
+    );
+    for step in &mut recording.steps {
+        for path in [&mut step.screenshot_path, &mut step.element_screenshot_path] {
+            if let Some(rendered) = path.as_ref().and_then(|path| annotated.get(path)) {
+                *path = Some(rendered.clone());
+            }
+        }
+    }
+    export::render_markdown(&recording, &links, locale)
+}
+
+/// Write `markdown`, a rendered recording, to `dir` as `<stem>.md`, with its
+/// screenshots copied to `images/<stem>/` beside it and linked relatively,
+/// for exports that leave this computer. Images from an earlier write are
+/// replaced. Returns the files written, the document first.
+fn write_markdown_folder(
+    markdown: &str,
+    dir: &std::path::Path,
+    stem: &str,
+) -> Result<Vec<PathBuf>, String> {
+    let images_dir = dir.join("images").join(stem);
+    if images_dir.exists() {
+        std::fs::remove_dir_all(&images_dir).map_err(|e| e.to_string())?;
+    }
+    std::fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+
+    let mut files = vec![dir.join(format!("{}.md", stem))];
+    let mut copy_error = None;
+    let markdown = export::rewrite_image_links(markdown, |path| {
+        let source = std::path::Path::new(path);
+        let extension = source.extension()?.to_str()?;
+        let name = format!("{:02}.{}", files.len(), extension);
+        let copied = std::fs::create_dir_all(&images_dir)
+            .and_then(|()| std::fs::copy(source, images_dir.join(&name)));
+        if let Err(e) = copied {
+            copy_error.get_or_insert_with(|| format!("Failed to copy {}: {}", path, e));
+            return None;
+        }
+        files.push(images_dir.join(&name));
+        Some(format!("images/{}/{}", stem, name))
+    });
+    if let Some(e) = copy_error {
+        return Err(e);
+    }
+    std::fs::write(&files[0], markdown).map_err(|e| e.to_string())?;
+    Ok(files)
+}
+
+/// Link one recording to another. `kind` is `prerequisite` or `see_also`.
+#[tauri::command]
+async fn add_recording_link(
+    db: State<'_, DatabaseWorker>,
+    from_id: String,
+    to_id: String,
+    kind: String,
+) -> Result<(), String> {
+    if kind != database::LINK_KIND_PREREQUISITE && kind != database::LINK_KIND_SEE_ALSO {
+        return Err(format!("Unknown link kind: {}", kind));
+    }
+    if from_id == to_id {
+        return Err("A recording cannot link to itself".to_string());
+    }
+
+    db.run(move |db| {
+        db.add_recording_link(&from_id, &to_id, &kind)
+            .map_err(|e| e.to_string())
+    })
+    .await
+}
+
+#[tauri::command]
+async fn remove_recording_link(
+    db: State<'_, DatabaseWorker>,
+    from_id: String,
+    to_id: String,
+    kind: String,
+) -> Result<(), String> {
+    db.run(move |db| {
+        db.remove_recording_link(&from_id, &to_id, &kind)
+            .map_err(|e| e.to_string())
+    })
+    .await
+}
+
+#[tauri::command]
+async fn list_recording_links(
+    db: State<'_, DatabaseWorker>,
+    recording_id: String,
+) -> Result<Vec<RecordingLink>, String> {
+    db.run(move |db| {
+        db.list_recording_links(&recording_id)
+            .map_err(|e| e.to_string())
+    })
+    .await
+}
+
+/// Delete several steps at once. Runs in a single transaction and renumbers
+/// the remaining steps; returns how many were deleted.
 #[tauri::command]
-fn delete_step(db: State<'_, DatabaseState>, step_id: String) -> Result<(), String> {
-    safe_db_lock(&db)?
-        .delete_step(&step_id)
+async fn delete_steps(
+    db: State<'_, DatabaseWorker>,
+    recording_id: String,
+    step_ids: Vec<String>,
+) -> Result<usize, String> {
+    db.run(move |db| {
+        db.delete_steps(&recording_id, &step_ids)
+            .map_err(|e| e.to_string())
+    })
+    .await
+}
+
+/// Move (or copy, with `copy`) steps into another recording at `position`.
+/// Returns the step IDs in the target recording.
+#[tauri::command]
+async fn transfer_steps(
+    db: State<'_, DatabaseWorker>,
+    source_recording_id: String,
+    target_recording_id: String,
+    step_ids: Vec<String>,
+    position: i32,
+    copy: bool,
+) -> Result<Vec<String>, String> {
+    if !copy && source_recording_id == target_recording_id {
+        return Err("Steps can only be moved to a different recording".to_string());
+    }
+
+    db.run(move |db| {
+        db.transfer_steps(
+            &source_recording_id,
+            &target_recording_id,
+            &step_ids,
+            position,
+            copy,
+        )
         .map_err(|e| e.to_string())
+    })
+    .await
 }
 
-#[tauri::command]
-fn save_steps_with_path(
-    db: State<'_, DatabaseState>,
-    recording_id: String,
-    recording_name: String,
-    steps: Vec<StepInput>,
-    screenshot_path: Option<String>,
-) -> Result<(), String> {
-    let normalized_screenshot_path =
-        normalize_optional_directory_path(screenshot_path)?.map(|path| {
-            path.to_string_lossy().to_string()
-        });
+/// Find-and-replace across all step descriptions of a recording. Returns the
+/// number of steps changed.
+#[tauri::command]
+async fn replace_in_step_descriptions(
+    db: State<'_, DatabaseWorker>,
+    recording_id: String,
+    find: String,
+    replace: String,
+    case_sensitive: bool,
+) -> Result<usize, String> {
+    db.run(move |db| {
+        db.replace_in_step_descriptions(&recording_id, &find, &replace, case_sensitive)
+            .map_err(|e| e.to_string())
+    })
+    .await
+}
+
+/// Crop rectangle for one step, in screenshot pixel coordinates.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct StepCropInput {
+    step_id: String,
+    x: u32,
+    y: u32,
+    width: u32,
+    height: u32,
+}
+
+/// Re-crop the screenshots of several steps. Every crop is encoded to a new
+/// file first and the step rows are updated in one transaction; if anything
+/// fails the new files are removed and the originals are left untouched.
+/// Crops of managed (shared) objects are stored as new objects. Steps with a
+/// lossless original are cropped from it and keep a cropped original.
+/// Returns the new screenshot paths in input order.
+#[tauri::command]
+async fn crop_step_screenshots(
+    app: AppHandle,
+    recording_id: String,
+    crops: Vec<StepCropInput>,
+) -> Result<Vec<String>, String> {
+    on_blocking_pool(&app, move |db| write_step_crops(db, &recording_id, &crops)).await
+}
+
+fn write_step_crops(
+    db: &State<'_, DatabaseState>,
+    recording_id: &str,
+    crops: &[StepCropInput],
+) -> Result<Vec<String>, String> {
+    let mut sources: Vec<(StoredImage, Option<StoredImage>)> = Vec::with_capacity(crops.len());
+    {
+        let db = safe_db_lock(db)?;
+        for crop in crops {
+            let step = db
+                .get_step(&crop.step_id)
+                .map_err(|e| e.to_string())?
+                .filter(|step| step.recording_id == recording_id)
+                .ok_or_else(|| format!("Step not found: {}", crop.step_id))?;
+            let path = step
+                .screenshot_path
+                .ok_or_else(|| format!("Step has no screenshot: {}", crop.step_id))?;
+            let stored = |path: &str| -> Result<StoredImage, String> {
+                let is_object = db.is_screenshot_object(path).map_err(|e| e.to_string())?;
+                Ok((normalize_file_path(std::path::Path::new(path))?, is_object))
+            };
+            let screenshot = stored(&path)?;
+            let original = step.original_path.as_deref().map(stored).transpose()?;
+            sources.push((screenshot, original));
+        }
+    }
+
+    // Unreferenced objects from a failed batch are pruned on the next start,
+    // so only plain files need removing here.
+    let mut written: Vec<PathBuf> = Vec::with_capacity(crops.len());
+    let result = write_cropped_screenshots(db, crops, &sources, &mut written).and_then(
+        |(updates, originals)| {
+            let orphaned = safe_db_lock(db)?
+                .update_step_screenshots_cropped(recording_id, &updates, &originals)
+                .map_err(|e| e.to_string())?;
+            Ok((updates, orphaned))
+        },
+    );
+
+    match result {
+        Ok((updates, orphaned)) => {
+            for path in orphaned {
+                let _ = std::fs::remove_file(path);
+            }
+            Ok(updates.into_iter().map(|(_, path)| path).collect())
+        }
+        Err(e) => {
+            for path in written {
+                let _ = std::fs::remove_file(path);
+            }
+            Err(e)
+        }
+    }
+}
+
+/// A stored image and whether it's a managed object
+type StoredImage = (PathBuf, bool);
+
+/// `(step_id, path)` of the cropped screenshots and of the cropped lossless
+/// originals
+type CroppedScreenshots = (Vec<(String, String)>, Vec<(String, String)>);
+
+fn write_cropped_screenshots(
+    db: &State<'_, DatabaseState>,
+    crops: &[StepCropInput],
+    sources: &[(StoredImage, Option<StoredImage>)],
+    written: &mut Vec<PathBuf>,
+) -> Result<CroppedScreenshots, String> {
+    use image::codecs::jpeg::JpegEncoder;
+
+    let mut updates = Vec::with_capacity(crops.len());
+    let mut originals = Vec::new();
+    for (crop, ((source, is_object), original)) in crops.iter().zip(sources) {
+        let pixels = original.as_ref().map_or(source, |(path, _)| path);
+        let image = image::open(pixels).map_err(|e| format!("Failed to read image: {}", e))?;
+        if crop.width == 0
+            || crop.height == 0
+            || crop.x.saturating_add(crop.width) > image.width()
+            || crop.y.saturating_add(crop.height) > image.height()
+        {
+            return Err(format!(
+                "Crop is outside the screenshot for step {}",
+                crop.step_id
+            ));
+        }
+        let cropped = image.crop_imm(crop.x, crop.y, crop.width, crop.height);
+
+        let mut encoded = Vec::new();
+        JpegEncoder::new_with_quality(&mut encoded, 85)
+            .encode_image(&cropped.to_rgb8())
+            .map_err(|e| format!("Failed to encode image: {}", e))?;
+
+        let dest =
+            store_replacement_screenshot(db, source, *is_object, "crop", "jpg", &encoded, written)?;
+        updates.push((crop.step_id.clone(), dest.to_string_lossy().to_string()));
+        if let Some((path, is_object)) = original {
+            let encoded = encode_png(&cropped)?;
+            let dest = store_replacement_screenshot(
+                db, path, *is_object, "crop", "png", &encoded, written,
+            )?;
+            originals.push((crop.step_id.clone(), dest.to_string_lossy().to_string()));
+        }
+    }
+    Ok((updates, originals))
+}
+
+/// Store re-encoded image bytes (a `extension` file) that will replace
+/// `source` on a step. Objects get a new object (the old one may be shared);
+/// plain files get a sibling `{stem}_{suffix}_{uuid}.{extension}`, recorded
+/// in `written` for cleanup on failure.
+fn store_replacement_screenshot(
+    db: &State<'_, DatabaseState>,
+    source: &std::path::Path,
+    is_object: bool,
+    suffix: &str,
+    extension: &str,
+    encoded: &[u8],
+    written: &mut Vec<PathBuf>,
+) -> Result<PathBuf, String> {
+    if is_object {
+        return safe_db_lock(db)?
+            .store_screenshot_object(encoded, extension)
+            .map_err(|e| format!("Failed to store screenshot: {}", e));
+    }
+
+    let stem = source
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().to_string())
+        .unwrap_or_else(|| "screenshot".to_string());
+    let dest = source.with_file_name(format!(
+        "{}_{}_{}.{}",
+        stem,
+        suffix,
+        uuid::Uuid::new_v4(),
+        extension
+    ));
+    std::fs::write(&dest, encoded).map_err(|e| format!("Failed to write file: {}", e))?;
+    written.push(dest.clone());
+    Ok(dest)
+}
+
+/// Storage savings reported by `archive_recording`.
+#[derive(Debug, Serialize)]
+struct ArchiveReport {
+    recording_id: String,
+    screenshots_reencoded: usize,
+    bytes_before: u64,
+    bytes_after: u64,
+}
+
+/// Archive a recording: re-encode its screenshots as lower-quality JPEGs
+/// (default quality 60) and mark it archived. Screenshots that would not get
+/// smaller are left alone. The quality reduction is permanent;
+/// `unarchive_recording` only clears the mark.
+#[tauri::command]
+async fn archive_recording(
+    app: AppHandle,
+    recording_id: String,
+    quality: Option<u8>,
+) -> Result<ArchiveReport, String> {
+    analytics::track("recording_archived");
+    let quality = quality.unwrap_or(60).clamp(10, 95);
+    on_blocking_pool(&app, move |db| {
+        let mut sources: Vec<(String, PathBuf, bool)> = Vec::new();
+        {
+            let db = safe_db_lock(db)?;
+            let recording = db
+                .get_recording(&recording_id)
+                .map_err(|e| e.to_string())?
+                .ok_or_else(|| format!("Recording not found: {}", recording_id))?;
+            for step in recording.steps {
+                let Some(path) = step.screenshot_path else {
+                    continue;
+                };
+                let is_object = db.is_screenshot_object(&path).map_err(|e| e.to_string())?;
+                sources.push((step.id, PathBuf::from(path), is_object));
+            }
+        }
+
+        let mut report = ArchiveReport {
+            recording_id: recording_id.clone(),
+            screenshots_reencoded: 0,
+            bytes_before: 0,
+            bytes_after: 0,
+        };
+        let mut written: Vec<PathBuf> = Vec::new();
+        let result = write_archived_screenshots(db, &sources, quality, &mut report, &mut written)
+            .and_then(|updates| {
+                safe_db_lock(db)?
+                    .archive_recording(&recording_id, &updates)
+                    .map_err(|e| e.to_string())
+            });
+
+        match result {
+            Ok(orphaned) => {
+                for path in orphaned {
+                    let _ = std::fs::remove_file(path);
+                }
+                logging::log(
+                    logging::CATEGORY_DATABASE,
+                    "info",
+                    "Recording archived",
+                    Some(&serde_json::json!({
+                        "recording_id": recording_id,
+                        "screenshots_reencoded": report.screenshots_reencoded,
+                        "bytes_saved": report.bytes_before - report.bytes_after,
+                    })),
+                );
+                Ok(report)
+            }
+            Err(e) => {
+                for path in written {
+                    let _ = std::fs::remove_file(path);
+                }
+                Err(e)
+            }
+        }
+    })
+    .await
+}
+
+fn write_archived_screenshots(
+    db: &State<'_, DatabaseState>,
+    sources: &[(String, PathBuf, bool)],
+    quality: u8,
+    report: &mut ArchiveReport,
+    written: &mut Vec<PathBuf>,
+) -> Result<Vec<(String, String)>, String> {
+    use image::codecs::jpeg::JpegEncoder;
+
+    // Steps sharing one object or file are re-encoded once.
+    let mut reencoded: HashMap<&PathBuf, PathBuf> = HashMap::new();
+    let mut updates: Vec<(String, String)> = Vec::new();
+    for (step_id, source, is_object) in sources {
+        if let Some(dest) = reencoded.get(source) {
+            updates.push((step_id.clone(), dest.to_string_lossy().to_string()));
+            continue;
+        }
+        let Ok(original) = std::fs::read(source) else {
+            continue;
+        };
+        let image = image::load_from_memory(&original)
+            .map_err(|e| format!("Failed to read image: {}", e))?;
+        let mut encoded = Vec::new();
+        JpegEncoder::new_with_quality(&mut encoded, quality)
+            .encode_image(&image.to_rgb8())
+            .map_err(|e| format!("Failed to encode image: {}", e))?;
+        if encoded.len() >= original.len() {
+            continue;
+        }
+
+        let dest = store_replacement_screenshot(
+            db, source, *is_object, "archived", "jpg", &encoded, written,
+        )?;
+        report.screenshots_reencoded += 1;
+        report.bytes_before += original.len() as u64;
+        report.bytes_after += encoded.len() as u64;
+        updates.push((step_id.clone(), dest.to_string_lossy().to_string()));
+        reencoded.insert(source, dest);
+    }
+    Ok(updates)
+}
+
+#[tauri::command]
+async fn unarchive_recording(
+    db: State<'_, DatabaseWorker>,
+    recording_id: String,
+) -> Result<(), String> {
+    db.run(move |db| {
+        db.unarchive_recording(&recording_id)
+            .map_err(|e| e.to_string())
+    })
+    .await
+}
+
+/// Target of `reencode_screenshots`.
+#[derive(Debug, Deserialize)]
+#[serde(default)]
+struct ReencodeSettings {
+    format: ScreenshotFormat,
+    /// JPEG quality, clamped to 10-95; ignored for PNG
+    quality: u8,
+    /// Wider images are scaled down to this width
+    max_width: Option<u32>,
+}
+
+impl Default for ReencodeSettings {
+    fn default() -> Self {
+        Self {
+            format: ScreenshotFormat::Jpeg,
+            quality: 75,
+            max_width: None,
+        }
+    }
+}
+
+impl ReencodeSettings {
+    /// A recording's own image settings, with what they leave unset as
+    /// captured
+    fn for_recording(settings: &RecordingImageSettings) -> Self {
+        Self {
+            format: settings.format.unwrap_or_default(),
+            quality: settings.quality.unwrap_or(CAPTURE_JPEG_QUALITY),
+            max_width: settings.max_width,
+        }
+    }
+}
+
+/// JPEG quality the recorder captures screenshots at
+const CAPTURE_JPEG_QUALITY: u8 = 85;
+
+/// Totals reported by `reencode_screenshots`.
+#[derive(Debug, Default, Serialize)]
+struct ReencodeReport {
+    recordings: usize,
+    /// Locked recordings, which are left as they are
+    locked_skipped: usize,
+    screenshots_reencoded: usize,
+    bytes_before: u64,
+    bytes_after: u64,
+}
+
+/// Emitted as `reencode-progress` before each recording is re-encoded.
+#[derive(Clone, Serialize)]
+struct ReencodeProgress {
+    recording_id: String,
+    current: usize,
+    total: usize,
+}
+
+/// Re-encode the screenshots and element crops of `recording_ids` to
+/// `settings`, pointing their steps at the new files. Images that would
+/// neither shrink, change format nor be resized are left alone. Resized
+/// screenshots have their click positions, annotations and redactions scaled
+/// to match. A recording that fails keeps its old images; the others are
+/// still processed and the first error is returned after them.
+#[tauri::command]
+async fn reencode_screenshots(
+    app: AppHandle,
+    recording_ids: Vec<String>,
+    settings: Option<ReencodeSettings>,
+) -> Result<ReencodeReport, String> {
+    let emitter = app.clone();
+    on_blocking_pool(&app, move |db| {
+        let settings = settings.unwrap_or_default();
+        let mut report = ReencodeReport::default();
+        let mut first_error: Option<String> = None;
+        let total = recording_ids.len();
+        for (index, recording_id) in recording_ids.iter().enumerate() {
+            let _ = emitter.emit(
+                "reencode-progress",
+                ReencodeProgress {
+                    recording_id: recording_id.clone(),
+                    current: index + 1,
+                    total,
+                },
+            );
+
+            match reencode_recording_screenshots(
+                db,
+                recording_id,
+                &settings,
+                &HashSet::new(),
+                &mut report,
+            ) {
+                Ok(()) => {}
+                Err(e) => {
+                    logging::log(
+                        logging::CATEGORY_DATABASE,
+                        "error",
+                        "Failed to re-encode screenshots",
+                        Some(&serde_json::json!({ "recording_id": recording_id, "error": e })),
+                    );
+                    first_error.get_or_insert(e);
+                }
+            }
+        }
+
+        logging::log(
+            logging::CATEGORY_DATABASE,
+            "info",
+            "Screenshots re-encoded",
+            Some(&serde_json::json!({
+                "recordings": report.recordings,
+                "locked_skipped": report.locked_skipped,
+                "screenshots_reencoded": report.screenshots_reencoded,
+                "bytes_before": report.bytes_before,
+                "bytes_after": report.bytes_after,
+            })),
+        );
+        match first_error {
+            Some(e) => Err(e),
+            None => Ok(report),
+        }
+    })
+    .await
+}
+
+/// Re-encode the images of `recording_id`'s steps, except those in `skip`.
+/// Locked recordings are skipped and counted in `report`.
+fn reencode_recording_screenshots(
+    db: &State<'_, DatabaseState>,
+    recording_id: &str,
+    settings: &ReencodeSettings,
+    skip: &HashSet<String>,
+    report: &mut ReencodeReport,
+) -> Result<(), String> {
+    let images = {
+        let db = safe_db_lock(db)?;
+        if db
+            .recording_locked(recording_id)
+            .map_err(|e| e.to_string())?
+        {
+            report.locked_skipped += 1;
+            return Ok(());
+        }
+        let mut images = Vec::new();
+        for (step_id, image, path) in db.step_images(recording_id).map_err(|e| e.to_string())? {
+            if skip.contains(&step_id) {
+                continue;
+            }
+            let is_object = db.is_screenshot_object(&path).map_err(|e| e.to_string())?;
+            // Screenshots are re-encoded from their lossless original if kept
+            let lossless = match image {
+                database::StepImage::Screenshot => db
+                    .get_step(&step_id)
+                    .map_err(|e| e.to_string())?
+                    .and_then(|step| step.original_path)
+                    .map(PathBuf::from),
+                _ => None,
+            };
+            images.push(ReencodeSource {
+                step_id,
+                image,
+                path: PathBuf::from(path),
+                is_object,
+                lossless,
+            });
+        }
+        images
+    };
+
+    let mut written: Vec<PathBuf> = Vec::new();
+    let mut totals = ReencodeReport::default();
+    let result = write_reencoded_screenshots(db, &images, settings, &mut totals, &mut written)
+        .and_then(|updates| {
+            safe_db_lock(db)?
+                .replace_step_images(recording_id, &updates)
+                .map_err(|e| e.to_string())
+        });
+
+    match result {
+        Ok(orphaned) => {
+            for path in orphaned {
+                let _ = std::fs::remove_file(path);
+            }
+            report.recordings += 1;
+            report.screenshots_reencoded += totals.screenshots_reencoded;
+            report.bytes_before += totals.bytes_before;
+            report.bytes_after += totals.bytes_after;
+            Ok(())
+        }
+        Err(e) => {
+            for path in written {
+                let _ = std::fs::remove_file(path);
+            }
+            Err(e)
+        }
+    }
+}
+
+/// A stored image of a step to re-encode
+struct ReencodeSource {
+    step_id: String,
+    image: database::StepImage,
+    path: PathBuf,
+    is_object: bool,
+    /// Lossless original to decode instead of `path`
+    lossless: Option<PathBuf>,
+}
+
+fn write_reencoded_screenshots(
+    db: &State<'_, DatabaseState>,
+    images: &[ReencodeSource],
+    settings: &ReencodeSettings,
+    report: &mut ReencodeReport,
+    written: &mut Vec<PathBuf>,
+) -> Result<Vec<database::ImageReplacement>, String> {
+    use image::codecs::jpeg::JpegEncoder;
+
+    let quality = settings.quality.clamp(10, 95);
+    let extension = settings.format.extension();
+
+    // Images shared by several steps are re-encoded once.
+    let mut reencoded: HashMap<&PathBuf, (PathBuf, f64)> = HashMap::new();
+    let mut updates = Vec::new();
+    for ReencodeSource {
+        step_id,
+        image,
+        path: source,
+        is_object,
+        lossless,
+    } in images
+    {
+        if let Some((dest, scale)) = reencoded.get(source) {
+            updates.push(database::ImageReplacement {
+                step_id: step_id.clone(),
+                image: *image,
+                path: dest.to_string_lossy().to_string(),
+                scale: *scale,
+            });
+            continue;
+        }
+        let Ok(original) = std::fs::read(source) else {
+            continue;
+        };
+        let mut decoded = match lossless {
+            Some(lossless) => image::open(lossless),
+            None => image::load_from_memory(&original),
+        }
+        .map_err(|e| format!("Failed to read image: {}", e))?;
+        let old_width = decoded.width();
+        let resized = settings
+            .max_width
+            .is_some_and(|max_width| max_width > 0 && old_width > max_width);
+        if let Some(max_width) = settings.max_width.filter(|_| resized) {
+            decoded = decoded.resize(max_width, u32::MAX, image::imageops::FilterType::Lanczos3);
+        }
+        let scale = decoded.width() as f64 / old_width.max(1) as f64;
+
+        let mut encoded = Vec::new();
+        match settings.format {
+            ScreenshotFormat::Jpeg => JpegEncoder::new_with_quality(&mut encoded, quality)
+                .encode_image(&decoded.to_rgb8())
+                .map_err(|e| format!("Failed to encode image: {}", e))?,
+            ScreenshotFormat::Png => decoded
+                .write_to(
+                    &mut std::io::Cursor::new(&mut encoded),
+                    image::ImageFormat::Png,
+                )
+                .map_err(|e| format!("Failed to encode image: {}", e))?,
+        }
+        let same_format = source
+            .extension()
+            .is_some_and(|ext| ext.eq_ignore_ascii_case(extension));
+        if same_format && !resized && encoded.len() >= original.len() {
+            continue;
+        }
 
-    safe_db_lock(&db)?
-        .save_steps_with_path(
-            &recording_id,
-            &recording_name,
-            steps,
-            normalized_screenshot_path.as_deref(),
-        )
-        .map_err(|e| e.to_string())
+        let dest = store_replacement_screenshot(
+            db,
+            source,
+            *is_object,
+            "reencoded",
+            extension,
+            &encoded,
+            written,
+        )?;
+        report.screenshots_reencoded += 1;
+        report.bytes_before += original.len() as u64;
+        report.bytes_after += encoded.len() as u64;
+        updates.push(database::ImageReplacement {
+            step_id: step_id.clone(),
+            image: *image,
+            path: dest.to_string_lossy().to_string(),
+            scale,
+        });
+        reencoded.insert(source, (dest, scale));
+    }
+    Ok(updates)
 }
 
 // Monitor info structure for frontend
@@ -1424,6 +3519,8 @@ pub struct MonitorInfo {
 #[derive(Clone, serde::Serialize)]
 pub struct WindowInfo {
     pub id: u32,
+    /// Owning process, 0 when unknown
+    pub pid: u32,
     pub title: String,
     pub app_name: String,
     pub x: i32,
@@ -1431,6 +3528,8 @@ pub struct WindowInfo {
     pub width: u32,
     pub height: u32,
     pub is_minimized: bool,
+    /// App icon as a PNG data URL; see `window_list::icon`
+    pub icon: Option<String>,
 }
 
 // Bounds for highlight overlay (passed from frontend)
@@ -1551,8 +3650,9 @@ fn is_capturable_window(title: &str, app_name: &str) -> bool {
     true
 }
 
+/// Capturable windows matching `query`, one page at a time
 #[tauri::command]
-fn get_windows() -> Result<Vec<WindowInfo>, String> {
+fn get_windows(query: Option<window_list::WindowQuery>) -> Result<window_list::WindowPage, String> {
     use xcap::Window;
 
     let windows = Window::all().map_err(|e| e.to_string())?;
@@ -1575,6 +3675,7 @@ fn get_windows() -> Result<Vec<WindowInfo>, String> {
 
         result.push(WindowInfo {
             id: window.id().ok().unwrap_or(0),
+            pid: window.pid().unwrap_or(0),
             title,
             app_name,
             x: window.x().unwrap_or(0),
@@ -1582,13 +3683,16 @@ fn get_windows() -> Result<Vec<WindowInfo>, String> {
             width,
             height,
             is_minimized: window.is_minimized().unwrap_or(false),
+            icon: None,
         });
     }
 
-    // Limit to prevent UI issues
-    result.truncate(30);
-
-    Ok(result)
+    // Icons are read only for the windows shown
+    let mut page = window_list::page(result, &query.unwrap_or_default());
+    for window in &mut page.windows {
+        window.icon = window_list::icon(window.id, window.pid);
+    }
+    Ok(page)
 }
 
 #[tauri::command]
@@ -1636,8 +3740,6 @@ async fn save_and_emit_capture(
     image: image::RgbaImage,
     prefix: &str,
 ) -> Result<String, String> {
-    use image::codecs::jpeg::JpegEncoder;
-    use std::io::BufWriter;
     use tokio::time::{sleep, Duration};
 
     let temp_dir = std::env::temp_dir().join("stepsnap_screenshots");
@@ -1651,10 +3753,7 @@ async fn save_and_emit_capture(
     let filename = format!("manual_capture_{}_{}.jpg", prefix, timestamp);
     let file_path = temp_dir.join(&filename);
 
-    let file = std::fs::File::create(&file_path).map_err(|e| e.to_string())?;
-    let mut writer = BufWriter::new(file);
-    let mut encoder = JpegEncoder::new_with_quality(&mut writer, 85);
-    encoder.encode_image(&image).map_err(|e| e.to_string())?;
+    jpeg::save(&image, &file_path, 85)?;
 
     let _ = app.emit(
         "manual-capture-complete",
@@ -1771,7 +3870,7 @@ fn restore_macos_window(app_name: &str) -> Result<(), String> {
 
     if !output.status.success() {
         let stderr = String::from_utf8_lossy(&output.stderr);
-        eprintln!("AppleScript warning (may be ignorable): {}", stderr);
+        tracing::warn!("AppleScript warning (may be ignorable): {}", stderr);
         // Don't fail on AppleScript errors - the window might still be usable
     }
 
@@ -1786,7 +3885,7 @@ where
     match mutex.lock() {
         Ok(mut guard) => *guard = value,
         Err(poisoned) => {
-            eprintln!("Mutex poisoned, recovering");
+            tracing::warn!("Mutex poisoned, recovering");
             *poisoned.into_inner() = value;
         }
     }
@@ -1799,7 +3898,7 @@ fn safe_db_lock(db: &DatabaseState) -> Result<std::sync::MutexGuard<'_, Database
     match db.0.lock() {
         Ok(guard) => Ok(guard),
         Err(poisoned) => {
-            eprintln!("Database mutex poisoned, recovering");
+            tracing::warn!("Database mutex poisoned, recovering");
             Ok(poisoned.into_inner())
         }
     }
@@ -1811,10 +3910,13 @@ async fn capture_window_and_close_picker(
     state: State<'_, RecordingState>,
     window_id: u32,
     is_minimized: bool,
+    full_page: bool,
 ) -> Result<String, String> {
     use tokio::time::{sleep, Duration};
     use xcap::Window;
 
+    analytics::track("manual_capture");
+
     // IMPORTANT: Hide highlight overlay FIRST and ensure it's destroyed
     let _ = overlay::hide_monitor_border();
 
@@ -1866,13 +3968,13 @@ async fn capture_window_and_close_picker(
         // Get the app name for this window so we can target it with AppleScript
         if let Some(app_name) = get_app_name_for_window(window_id) {
             if let Err(e) = restore_macos_window(&app_name) {
-                eprintln!("Warning: Failed to restore macOS window: {}", e);
+                tracing::warn!("Failed to restore macOS window: {}", e);
                 // Continue anyway - the window might still be capturable
             }
             // Wait for window to fully restore before capturing
             sleep(Duration::from_millis(500)).await;
         } else {
-            eprintln!("Warning: Could not find app name for window {}", window_id);
+            tracing::warn!("Could not find app name for window {}", window_id);
         }
     }
 
@@ -1890,6 +3992,12 @@ async fn capture_window_and_close_picker(
         return Err("Window has invalid dimensions".to_string());
     }
 
+    // Full page: scroll the window through and stitch the frames
+    if full_page {
+        let image = scroll_capture::capture_full_page(target).await?;
+        return save_and_emit_capture(app, image, "page").await;
+    }
+
     // Safely attempt capture with panic recovery
     let capture_result = catch_unwind(AssertUnwindSafe(|| target.capture_image()));
 
@@ -1904,14 +4012,13 @@ async fn capture_window_and_close_picker(
 
 #[tauri::command]
 async fn capture_monitor(app: AppHandle, index: usize) -> Result<String, String> {
-    use image::codecs::jpeg::JpegEncoder;
-    use std::io::BufWriter;
     use xcap::Monitor;
 
+    analytics::track("manual_capture");
     let monitors = Monitor::all().map_err(|e| e.to_string())?;
     let monitor = monitors.get(index).ok_or("Invalid monitor index")?;
 
-    let image = monitor.capture_image().map_err(|e| e.to_string())?;
+    let image = capture_backend::capture(monitor)?;
 
     // Save to temp file
     let temp_dir = std::env::temp_dir().join("stepsnap_screenshots");
@@ -1925,11 +4032,7 @@ async fn capture_monitor(app: AppHandle, index: usize) -> Result<String, String>
     let filename = format!("manual_capture_{}.jpg", timestamp);
     let file_path = temp_dir.join(&filename);
 
-    let file = std::fs::File::create(&file_path).map_err(|e| e.to_string())?;
-    let mut writer = BufWriter::new(file);
-    let mut encoder = JpegEncoder::new_with_quality(&mut writer, 85);
-
-    encoder.encode_image(&image).map_err(|e| e.to_string())?;
+    jpeg::save(&image, &file_path, 85)?;
 
     // Emit capture event to recorder
     let _ = app.emit(
@@ -1949,14 +4052,12 @@ async fn capture_monitor_and_close_picker(
     state: State<'_, RecordingState>,
     index: usize,
 ) -> Result<String, String> {
-    use image::codecs::jpeg::JpegEncoder;
-    use std::io::BufWriter;
     use tokio::time::{sleep, Duration};
     use xcap::Monitor;
 
     // Hide highlight overlay first - this is synchronous with message flush
     if let Err(e) = overlay::hide_monitor_border() {
-        eprintln!("Warning: Failed to hide overlay: {}", e);
+        tracing::warn!("Failed to hide overlay: {}", e);
     }
 
     // Close the picker window entirely to ensure it's not captured in the screenshot
@@ -1973,7 +4074,7 @@ async fn capture_monitor_and_close_picker(
     let monitors = Monitor::all().map_err(|e| e.to_string())?;
     let monitor = monitors.get(index).ok_or("Invalid monitor index")?;
 
-    let image = monitor.capture_image().map_err(|e| e.to_string())?;
+    let image = capture_backend::capture(monitor)?;
 
     // Save to temp file
     let temp_dir = std::env::temp_dir().join("stepsnap_screenshots");
@@ -1987,11 +4088,7 @@ async fn capture_monitor_and_close_picker(
     let filename = format!("manual_capture_{}.jpg", timestamp);
     let file_path = temp_dir.join(&filename);
 
-    let file = std::fs::File::create(&file_path).map_err(|e| e.to_string())?;
-    let mut writer = BufWriter::new(file);
-    let mut encoder = JpegEncoder::new_with_quality(&mut writer, 85);
-
-    encoder.encode_image(&image).map_err(|e| e.to_string())?;
+    jpeg::save(&image, &file_path, 85)?;
 
     // Emit capture event to recorder
     let _ = app.emit(
@@ -2007,10 +4104,10 @@ async fn capture_monitor_and_close_picker(
 
 #[tauri::command]
 async fn capture_all_monitors(app: AppHandle) -> Result<String, String> {
-    use image::{codecs::jpeg::JpegEncoder, RgbaImage};
-    use std::io::BufWriter;
+    use image::RgbaImage;
     use xcap::Monitor;
 
+    analytics::track("manual_capture");
     let monitors = Monitor::all().map_err(|e| e.to_string())?;
 
     if monitors.is_empty() {
@@ -2042,7 +4139,7 @@ async fn capture_all_monitors(app: AppHandle) -> Result<String, String> {
     let mut composite = RgbaImage::new(total_width, total_height);
 
     for mon in monitors {
-        if let Ok(img) = mon.capture_image() {
+        if let Ok(img) = capture_backend::capture(&mon) {
             let offset_x = (mon.x().unwrap_or(0) - min_x) as i64;
             let offset_y = (mon.y().unwrap_or(0) - min_y) as i64;
             image::imageops::overlay(&mut composite, &img, offset_x, offset_y);
@@ -2061,14 +4158,7 @@ async fn capture_all_monitors(app: AppHandle) -> Result<String, String> {
     let filename = format!("manual_capture_all_{}.jpg", timestamp);
     let file_path = temp_dir.join(&filename);
 
-    let file = std::fs::File::create(&file_path).map_err(|e| e.to_string())?;
-    let mut writer = BufWriter::new(file);
-    let mut encoder = JpegEncoder::new_with_quality(&mut writer, 85);
-
-    let rgb_image = image::DynamicImage::ImageRgba8(composite).to_rgb8();
-    encoder
-        .encode_image(&rgb_image)
-        .map_err(|e| e.to_string())?;
+    jpeg::save(&composite, &file_path, 85)?;
 
     // Emit capture event
     let _ = app.emit(
@@ -2144,7 +4234,7 @@ async fn show_monitor_highlight(_app: AppHandle, index: usize) -> Result<(), Str
     let width = monitor.width().unwrap_or(0);
     let height = monitor.height().unwrap_or(0);
 
-    println!(
+    tracing::debug!(
         "Monitor {}: pos=({}, {}), size={}x{}",
         index, x, y, width, height
     );
@@ -2159,44 +4249,239 @@ async fn hide_monitor_highlight(_app: AppHandle) -> Result<(), String> {
     overlay::hide_monitor_border()
 }
 
+/// The backend settings; see `settings::Settings`
+#[tauri::command]
+fn get_settings(settings: State<'_, SettingsState>) -> Settings {
+    settings.0.lock().unwrap().clone()
+}
+
+/// Replace the settings named in `patch`, a JSON object of `Settings`
+/// fields, then save and apply them and emit `settings-changed`. Nothing
+/// changes if a field is unknown or invalid.
+#[tauri::command]
+async fn update_settings(
+    app: AppHandle,
+    db: State<'_, DatabaseWorker>,
+    patch: serde_json::Map<String, serde_json::Value>,
+) -> Result<Settings, String> {
+    db.run(move |db| {
+        let settings = app.state::<SettingsState>();
+        let mut current = settings.0.lock().unwrap();
+        let updated = current.merge(&patch)?;
+        updated.save(db).map_err(|e| e.to_string())?;
+        updated
+            .apply(&app.state::<RecordingState>(), db)
+            .map_err(|e| e.to_string())?;
+        if *current != updated {
+            let _ = app.emit("settings-changed", &updated);
+        }
+        *current = updated.clone();
+        Ok(updated)
+    })
+    .await
+}
+
+/// Change how much detail goes into the log files, e.g. "debug" while
+/// support chases a capture failure. Kept across restarts like any setting.
+#[tauri::command]
+async fn set_log_level(
+    app: AppHandle,
+    db: State<'_, DatabaseWorker>,
+    level: String,
+) -> Result<Settings, String> {
+    if !logging::is_valid_level(&level) {
+        return Err(format!("Unknown log level: {}", level));
+    }
+    let patch = serde_json::Map::from_iter([(
+        "log_level".to_string(),
+        serde_json::Value::String(level.trim().to_ascii_lowercase()),
+    )]);
+    let updated = update_settings(app, db, patch).await?;
+    logging::log(
+        logging::CATEGORY_APP,
+        "info",
+        "Log level changed",
+        Some(&serde_json::json!({ "level": updated.log_level })),
+    );
+    Ok(updated)
+}
+
 // OCR commands
 #[tauri::command]
-fn set_ocr_enabled(state: State<'_, RecordingState>, enabled: bool) {
-    *state.ocr_enabled.lock().unwrap() = enabled;
+fn get_ocr_languages(app: AppHandle) -> Vec<ocr::OcrLanguageInfo> {
+    ocr::list_languages(&ocr::get_models_dirs(&app))
+}
+
+/// OCR progress of one saved recording, so exports can wait for the text
+#[tauri::command]
+async fn get_recording_ocr_status(
+    db: State<'_, DatabaseWorker>,
+    recording_id: String,
+) -> Result<database::RecordingOcrStatus, String> {
+    db.run(move |db| {
+        db.recording_ocr_status(&recording_id)
+            .map_err(|e| e.to_string())
+    })
+    .await
+}
+
+#[tauri::command]
+fn get_ocr_queue_status(state: State<'_, RecordingState>) -> ocr_queue::OcrQueueStatus {
+    state.ocr_queue.status()
+}
+
+/// Move queued OCR for these steps ahead of everything else, e.g. because
+/// they just scrolled into view
+#[tauri::command]
+fn prioritize_ocr_steps(state: State<'_, RecordingState>, step_ids: Vec<String>) {
+    state.ocr_queue.prioritize(&step_ids);
+}
+
+/// A saved step to OCR in the background
+#[derive(Debug, Deserialize)]
+struct OcrBackfillStep {
+    step_id: String,
+    screenshot_path: String,
+    x: Option<i32>,
+    y: Option<i32>,
+    step_type: String,
+}
+
+/// Queue saved steps that have no OCR text yet at backfill priority. Results
+/// arrive as `ocr-step-complete` events keyed by the saved step id.
+#[tauri::command]
+fn queue_ocr_backfill(
+    app: AppHandle,
+    state: State<'_, RecordingState>,
+    steps: Vec<OcrBackfillStep>,
+) -> Result<(), String> {
+    for step in steps {
+        let path = normalize_file_path(std::path::Path::new(&step.screenshot_path))?;
+        let evicted = state.ocr_queue.push(ocr_queue::QueuedOcrJob {
+            step_id: step.step_id,
+            image: ocr_queue::OcrImage::File(path),
+            x: step.x,
+            y: step.y,
+            step_type: step.step_type,
+            priority: ocr_queue::OcrPriority::Backfill,
+        });
+        if let Some(evicted) = evicted {
+            let _ = app.emit("ocr-step-complete", recorder::skipped_ocr_result(evicted));
+        }
+    }
+    Ok(())
+}
+
+/// Region of a screenshot in image pixels
+#[derive(Debug, Deserialize)]
+struct OcrRegion {
+    x: u32,
+    y: u32,
+    width: u32,
+    height: u32,
+}
+
+/// OCR a region of an existing screenshot, e.g. one the user selected in the
+/// step editor. Uses the recorder's OCR language unless `language` is given.
+#[tauri::command]
+async fn run_ocr_on_region(
+    app: AppHandle,
+    recording_state: State<'_, RecordingState>,
+    path: String,
+    rect: OcrRegion,
+    language: Option<String>,
+) -> Result<Option<String>, String> {
+    analytics::track("ocr_region");
+    let source = normalize_file_path(std::path::Path::new(&path))?;
+    let image = image::open(&source).map_err(|e| format!("Failed to read image: {}", e))?;
+
+    let x = rect.x.min(image.width());
+    let y = rect.y.min(image.height());
+    let width = rect.width.min(image.width() - x);
+    let height = rect.height.min(image.height() - y);
+    if width == 0 || height == 0 {
+        return Err("Selected region is empty".to_string());
+    }
+    let region = image.crop_imm(x, y, width, height);
+
+    let language = language.unwrap_or_else(|| recording_state.ocr_language.lock().unwrap().clone());
+
+    // The engine the recorder's OCR workers use, loaded here if they
+    // haven't yet
+    let config = recording_state.ocr_config.lock().unwrap().clone();
+    let manager = ocr::shared_manager(&app, &config, &language);
+    manager.extract_text(&region, &language)
+}
+
+/// Providers this build can run, for the settings UI
+#[tauri::command]
+fn get_ocr_providers() -> Vec<ocr::OcrProvider> {
+    ocr::OcrProvider::available()
+}
+
+/// Time each available OCR provider on the most recent step screenshot, so
+/// the frontend can pick the fastest on first run.
+#[tauri::command]
+async fn benchmark_ocr_providers(
+    app: AppHandle,
+    db: State<'_, DatabaseWorker>,
+    state: State<'_, RecordingState>,
+) -> Result<ocr::OcrBenchmark, String> {
+    let path = db
+        .run(|db| db.latest_step_screenshot().map_err(|e| e.to_string()))
+        .await?
+        .ok_or("No screenshot to benchmark OCR on yet")?;
+    let image = image::open(&path)
+        .map_err(|e| format!("Failed to read image: {}", e))?
+        .into_rgba8();
+    let config = state.ocr_config.lock().unwrap().clone();
+    let language = state.ocr_language.lock().unwrap().clone();
+    Ok(ocr::OcrManager::benchmark(
+        &ocr::get_models_dirs(&app),
+        &config,
+        &image,
+        &language,
+    ))
 }
 
 #[tauri::command]
-fn get_ocr_enabled(state: State<'_, RecordingState>) -> bool {
-    *state.ocr_enabled.lock().unwrap()
+fn list_ocr_models(app: AppHandle) -> Result<Vec<ocr_models::ModelFileStatus>, String> {
+    ocr_models::list_installed(&app)
 }
 
 #[tauri::command]
-fn set_state_diff_enabled(state: State<'_, RecordingState>, enabled: bool) {
-    *state.state_diff_enabled.lock().unwrap() = enabled;
+async fn download_ocr_model(app: AppHandle, language: String) -> Result<(), String> {
+    ocr_models::download_language(&app, &language).await
 }
 
 #[tauri::command]
-fn set_after_frame_max_wait_ms(state: State<'_, RecordingState>, ms: u64) {
-    // Clamp to a sensible range to match the frontend slider bounds.
-    let clamped = ms.clamp(500, 5000);
-    *state.after_frame_max_wait_ms.lock().unwrap() = clamped;
+async fn update_ocr_models(app: AppHandle) -> Result<Vec<String>, String> {
+    ocr_models::update_all(&app).await
 }
 
 #[tauri::command]
-fn set_video_clips_enabled(state: State<'_, RecordingState>, enabled: bool) {
-    *state.video_clips_enabled.lock().unwrap() = enabled;
+async fn verify_ocr_models(app: AppHandle) -> Result<Vec<ocr_models::ModelVerification>, String> {
+    ocr_models::verify(&app)
 }
 
 #[tauri::command]
-fn update_step_ocr(
-    db: State<'_, DatabaseState>,
+async fn update_step_ocr(
+    db: State<'_, DatabaseWorker>,
     step_id: String,
     ocr_text: Option<String>,
+    ocr_lines: Option<Vec<ocr::OcrLine>>,
     ocr_status: String,
-) -> Result<(), String> {
-    safe_db_lock(&db)?
-        .update_step_ocr(&step_id, ocr_text.as_deref(), &ocr_status)
+) -> Result<Option<String>, String> {
+    db.run(move |db| {
+        db.update_step_ocr(
+            &step_id,
+            ocr_text.as_deref(),
+            ocr_lines.as_deref(),
+            &ocr_status,
+        )
         .map_err(|e| e.to_string())
+    })
+    .await
 }
 
 /// Persist the after-frame screenshot path for a step (used by the state-diff
@@ -2204,105 +4489,118 @@ fn update_step_ocr(
 /// recorder, copies the temp file to permanent storage, and then calls this
 /// to record the path against the existing step row.
 #[tauri::command]
-fn update_step_after_screenshot(
-    db: State<'_, DatabaseState>,
+async fn update_step_after_screenshot(
+    db: State<'_, DatabaseWorker>,
     step_id: String,
     screenshot_after_path: Option<String>,
 ) -> Result<(), String> {
-    safe_db_lock(&db)?
-        .update_step_after_screenshot(&step_id, screenshot_after_path.as_deref())
-        .map_err(|e| e.to_string())
+    db.run(move |db| {
+        db.update_step_after_screenshot(&step_id, screenshot_after_path.as_deref())
+            .map_err(|e| e.to_string())
+    })
+    .await
 }
 
 /// Persist the cached Stage A "element identification" JSON for a step. The
 /// frontend writes this after a successful multi-stage call so subsequent
 /// regenerations can skip the vision pass.
 #[tauri::command]
-fn update_step_identified_element(
-    db: State<'_, DatabaseState>,
+async fn update_step_identified_element(
+    db: State<'_, DatabaseWorker>,
     step_id: String,
     identified_element_json: Option<String>,
 ) -> Result<(), String> {
-    safe_db_lock(&db)?
-        .update_step_identified_element(&step_id, identified_element_json.as_deref())
-        .map_err(|e| e.to_string())
+    db.run(move |db| {
+        db.update_step_identified_element(&step_id, identified_element_json.as_deref())
+            .map_err(|e| e.to_string())
+    })
+    .await
 }
 
 /// Persist the path to a step's short video/animated clip (Phase 8a).
 #[tauri::command]
-fn update_step_clip_path(
-    db: State<'_, DatabaseState>,
+async fn update_step_clip_path(
+    db: State<'_, DatabaseWorker>,
     step_id: String,
     clip_path: Option<String>,
 ) -> Result<(), String> {
-    safe_db_lock(&db)?
-        .update_step_clip_path(&step_id, clip_path.as_deref())
-        .map_err(|e| e.to_string())
+    db.run(move |db| {
+        db.update_step_clip_path(&step_id, clip_path.as_deref())
+            .map_err(|e| e.to_string())
+    })
+    .await
 }
 
 // ── Notification commands ──────────────────────────────────────────────
 
 #[tauri::command]
-fn create_notification(
-    db: State<'_, DatabaseState>,
+async fn create_notification(
+    db: State<'_, DatabaseWorker>,
     title: Option<String>,
     message: String,
     variant: String,
     log_category: Option<String>,
 ) -> Result<Notification, String> {
-    safe_db_lock(&db)?
-        .create_notification(title.as_deref(), &message, &variant, log_category.as_deref())
+    db.run(move |db| {
+        db.create_notification(
+            title.as_deref(),
+            &message,
+            &variant,
+            log_category.as_deref(),
+        )
         .map_err(|e| e.to_string())
+    })
+    .await
 }
 
 #[tauri::command]
-fn list_notifications(
-    db: State<'_, DatabaseState>,
+async fn list_notifications(
+    db: State<'_, DatabaseWorker>,
     limit: i32,
     offset: i32,
 ) -> Result<Vec<Notification>, String> {
-    safe_db_lock(&db)?
-        .list_notifications(limit, offset)
-        .map_err(|e| e.to_string())
+    db.run(move |db| {
+        db.list_notifications(limit, offset)
+            .map_err(|e| e.to_string())
+    })
+    .await
 }
 
 #[tauri::command]
-fn get_unread_notification_count(db: State<'_, DatabaseState>) -> Result<i64, String> {
-    safe_db_lock(&db)?
-        .get_unread_notification_count()
-        .map_err(|e| e.to_string())
+async fn get_unread_notification_count(db: State<'_, DatabaseWorker>) -> Result<i64, String> {
+    db.run(|db| {
+        db.get_unread_notification_count()
+            .map_err(|e| e.to_string())
+    })
+    .await
 }
 
 #[tauri::command]
-fn mark_notification_read(db: State<'_, DatabaseState>, id: String) -> Result<(), String> {
-    safe_db_lock(&db)?
-        .mark_notification_read(&id)
-        .map_err(|e| e.to_string())
+async fn mark_notification_read(db: State<'_, DatabaseWorker>, id: String) -> Result<(), String> {
+    db.run(move |db| db.mark_notification_read(&id).map_err(|e| e.to_string()))
+        .await
 }
 
 #[tauri::command]
-fn mark_all_notifications_read(db: State<'_, DatabaseState>) -> Result<(), String> {
-    safe_db_lock(&db)?
-        .mark_all_notifications_read()
-        .map_err(|e| e.to_string())
+async fn mark_all_notifications_read(db: State<'_, DatabaseWorker>) -> Result<(), String> {
+    db.run(|db| db.mark_all_notifications_read().map_err(|e| e.to_string()))
+        .await
 }
 
 #[tauri::command]
-fn delete_notification(db: State<'_, DatabaseState>, id: String) -> Result<(), String> {
-    safe_db_lock(&db)?
-        .delete_notification(&id)
-        .map_err(|e| e.to_string())
+async fn delete_notification(db: State<'_, DatabaseWorker>, id: String) -> Result<(), String> {
+    db.run(move |db| db.delete_notification(&id).map_err(|e| e.to_string()))
+        .await
 }
 
 #[tauri::command]
-fn clear_all_notifications(db: State<'_, DatabaseState>) -> Result<(), String> {
-    safe_db_lock(&db)?
-        .delete_all_notifications()
-        .map_err(|e| e.to_string())
+async fn clear_all_notifications(db: State<'_, DatabaseWorker>) -> Result<(), String> {
+    db.run(|db| db.delete_all_notifications().map_err(|e| e.to_string()))
+        .await
 }
 
 // Permission status response
-#[derive(Clone, serde::Serialize)]
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize)]
 pub struct PermissionStatus {
     pub screen_recording: bool,
     pub accessibility: bool,
@@ -2333,7 +4631,7 @@ fn check_screen_recording_permission() -> bool {
 /// This will show the system permission dialog if not already granted
 /// Returns true if permission was granted, false otherwise
 #[tauri::command]
-fn request_screen_recording_permission() -> bool {
+fn request_screen_recording_permission(app: AppHandle) -> bool {
     #[cfg(target_os = "macos")]
     {
         #[link(name = "CoreGraphics", kind = "framework")]
@@ -2342,11 +4640,14 @@ fn request_screen_recording_permission() -> bool {
             fn CGRequestScreenCaptureAccess() -> bool;
         }
 
-        unsafe { CGRequestScreenCaptureAccess() }
+        let granted = unsafe { CGRequestScreenCaptureAccess() };
+        let _ = app.emit("permission-status", get_permission_status());
+        granted
     }
 
     #[cfg(not(target_os = "macos"))]
     {
+        let _ = app;
         true // No permission needed on other platforms
     }
 }
@@ -2372,25 +4673,69 @@ fn check_accessibility_permission() -> bool {
 }
 
 /// Request accessibility permission on macOS
-/// This opens System Preferences to the Accessibility pane
+/// Shows the system prompt, which adds the app to the Accessibility list and
+/// offers to open System Settings. Returns true if already trusted.
+#[tauri::command]
+fn request_accessibility_permission(app: AppHandle) -> bool {
+    #[cfg(target_os = "macos")]
+    {
+        use core_foundation::base::TCFType;
+        use core_foundation::boolean::CFBoolean;
+        use core_foundation::dictionary::{CFDictionary, CFDictionaryRef};
+        use core_foundation::string::{CFString, CFStringRef};
+
+        #[link(name = "ApplicationServices", kind = "framework")]
+        extern "C" {
+            static kAXTrustedCheckOptionPrompt: CFStringRef;
+            fn AXIsProcessTrustedWithOptions(options: CFDictionaryRef) -> bool;
+        }
+
+        let trusted = unsafe {
+            let options = CFDictionary::from_CFType_pairs(&[(
+                CFString::wrap_under_get_rule(kAXTrustedCheckOptionPrompt),
+                CFBoolean::true_value(),
+            )]);
+            AXIsProcessTrustedWithOptions(options.as_concrete_TypeRef())
+        };
+        let _ = app.emit("permission-status", get_permission_status());
+        trusted
+    }
+
+    #[cfg(not(target_os = "macos"))]
+    {
+        let _ = app;
+        true // No permission needed on other platforms
+    }
+}
+
+/// Open the System Settings pane where `permission` ("accessibility" or
+/// "screen_recording") is granted.
 #[tauri::command]
-fn request_accessibility_permission() -> Result<(), String> {
+fn open_permission_settings(permission: String) -> Result<(), String> {
+    let pane = match permission.as_str() {
+        "accessibility" => "Privacy_Accessibility",
+        "screen_recording" => "Privacy_ScreenCapture",
+        other => return Err(format!("Unknown permission: {}", other)),
+    };
+
     #[cfg(target_os = "macos")]
     {
         use std::process::Command;
 
-        // Open System Preferences to the Accessibility pane
-        // Using the Privacy & Security > Accessibility path
         Command::new("open")
-            .args(["x-apple.systempreferences:com.apple.preference.security?Privacy_Accessibility"])
+            .arg(format!(
+                "x-apple.systempreferences:com.apple.preference.security?{}",
+                pane
+            ))
             .spawn()
-            .map_err(|e| format!("Failed to open System Preferences: {}", e))?;
+            .map_err(|e| format!("Failed to open System Settings: {}", e))?;
 
         Ok(())
     }
 
     #[cfg(not(target_os = "macos"))]
     {
+        let _ = pane;
         Ok(()) // No permission needed on other platforms
     }
 }
@@ -2404,6 +4749,79 @@ fn get_permission_status() -> PermissionStatus {
     }
 }
 
+/// Check everything recording depends on, so onboarding can point at what
+/// to fix before the first recording instead of it failing silently
+#[tauri::command]
+async fn run_environment_check(
+    app: AppHandle,
+    db: State<'_, DatabaseWorker>,
+    settings: State<'_, SettingsState>,
+) -> Result<environment::EnvironmentReport, String> {
+    let data_dir = db.run(|db| Ok(db.data_dir().clone())).await?;
+    let permissions = get_permission_status();
+    let settings = settings.0.lock().unwrap().clone();
+
+    let mut checks = vec![
+        environment::screen_capture_check(permissions.screen_recording),
+        environment::accessibility_check(permissions.accessibility),
+        environment::input_check(recorder::input_listener_error()),
+        environment::writable_dir_check("data_dir", &data_dir),
+    ];
+    if let Some(path) = &settings.screenshot_path {
+        checks.push(environment::writable_dir_check(
+            "screenshot_dir",
+            std::path::Path::new(path),
+        ));
+    }
+    checks.push(environment::ocr_check(
+        settings.ocr_enabled,
+        &ocr::get_models_dirs(&app),
+    ));
+
+    let report = environment::EnvironmentReport::new(checks);
+    if !report.ready {
+        logging::log(
+            logging::CATEGORY_APP,
+            "warn",
+            "Environment check failed",
+            Some(&serde_json::json!(report)),
+        );
+    }
+    Ok(report)
+}
+
+/// Emit `permission-status` whenever a permission is granted or revoked in
+/// System Settings, so the frontend can update without polling. Missing
+/// permissions otherwise fail silently: captures come back black and steps
+/// lose their element names.
+#[cfg(target_os = "macos")]
+fn spawn_permission_watcher(app: AppHandle) {
+    const POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+    std::thread::spawn(move || {
+        let mut last: Option<PermissionStatus> = None;
+        loop {
+            let status = get_permission_status();
+            if last.as_ref() != Some(&status) {
+                if !status.screen_recording || !status.accessibility {
+                    logging::log(
+                        logging::CATEGORY_APP,
+                        "warn",
+                        "macOS permissions missing",
+                        Some(&serde_json::json!({
+                            "screen_recording": status.screen_recording,
+                            "accessibility": status.accessibility,
+                        })),
+                    );
+                }
+                let _ = app.emit("permission-status", status.clone());
+                last = Some(status);
+            }
+            std::thread::sleep(POLL_INTERVAL);
+        }
+    });
+}
+
 /// Update paths in settings.json that reference the old identifier.
 /// This is called after a successful folder migration.
 fn update_settings_paths(
@@ -2427,9 +4845,9 @@ fn update_settings_paths(
 
     // Write back the updated settings
     if let Err(e) = std::fs::write(settings_path, updated_content) {
-        eprintln!("Warning: Could not update paths in settings.json: {}", e);
+        tracing::warn!("Could not update paths in settings.json: {}", e);
     } else {
-        println!(
+        tracing::info!(
             "Updated paths in settings.json: {} -> {}",
             old_identifier, new_identifier
         );
@@ -2444,7 +4862,7 @@ fn update_database_paths(db_path: &std::path::Path, old_identifier: &str, new_id
     let conn = match Connection::open(db_path) {
         Ok(c) => c,
         Err(e) => {
-            eprintln!("Warning: Could not open database for path migration: {}", e);
+            tracing::warn!("Could not open database for path migration: {}", e);
             return;
         }
     };
@@ -2459,11 +4877,11 @@ fn update_database_paths(db_path: &std::path::Path, old_identifier: &str, new_id
     ) {
         Ok(count) => {
             if count > 0 {
-                println!("Updated {} screenshot paths in database: {} -> {}", count, old_identifier, new_identifier);
+                tracing::info!("Updated {} screenshot paths in database: {} -> {}", count, old_identifier, new_identifier);
             }
         }
         Err(e) => {
-            eprintln!("Warning: Could not update screenshot paths in database: {}", e);
+            tracing::warn!("Could not update screenshot paths in database: {}", e);
         }
     }
 
@@ -2474,11 +4892,11 @@ fn update_database_paths(db_path: &std::path::Path, old_identifier: &str, new_id
     ) {
         Ok(count) => {
             if count > 0 {
-                println!("Updated {} documentation entries in database: {} -> {}", count, old_identifier, new_identifier);
+                tracing::info!("Updated {} documentation entries in database: {} -> {}", count, old_identifier, new_identifier);
             }
         }
         Err(e) => {
-            eprintln!("Warning: Could not update documentation paths in database: {}", e);
+            tracing::warn!("Could not update documentation paths in database: {}", e);
         }
     }
 }
@@ -2518,7 +4936,7 @@ fn migrate_from_openscribe(new_data_dir: &std::path::Path) -> Result<Option<Stri
     // Attempt to rename old folder to new location
     match std::fs::rename(&old_data_dir, new_data_dir) {
         Ok(_) => {
-            println!(
+            tracing::info!(
                 "Successfully migrated data from {} to {}",
                 old_data_dir.display(),
                 new_data_dir.display()
@@ -2529,9 +4947,9 @@ fn migrate_from_openscribe(new_data_dir: &std::path::Path) -> Result<Option<Stri
             let new_db_path = new_data_dir.join("stepsnap.db");
             if old_db_in_new_dir.exists() {
                 if let Err(e) = std::fs::rename(&old_db_in_new_dir, &new_db_path) {
-                    eprintln!("Warning: Could not rename database file: {}", e);
+                    tracing::warn!("Could not rename database file: {}", e);
                 } else {
-                    println!("Renamed database: openscribe.db -> stepsnap.db");
+                    tracing::info!("Renamed database: openscribe.db -> stepsnap.db");
                 }
             }
 
@@ -2592,7 +5010,7 @@ fn migrate_from_old_identifier(new_data_dir: &std::path::Path) -> Result<Option<
     // Attempt to rename old folder to new location
     match std::fs::rename(&old_data_dir, new_data_dir) {
         Ok(_) => {
-            println!(
+            tracing::info!(
                 "Successfully migrated data from {} to {}",
                 old_data_dir.display(),
                 new_data_dir.display()
@@ -2603,9 +5021,9 @@ fn migrate_from_old_identifier(new_data_dir: &std::path::Path) -> Result<Option<
             let new_db_path = new_data_dir.join("stepsnap.db");
             if old_db_in_new_dir.exists() {
                 if let Err(e) = std::fs::rename(&old_db_in_new_dir, &new_db_path) {
-                    eprintln!("Warning: Could not rename database file: {}", e);
+                    tracing::warn!("Could not rename database file: {}", e);
                 } else {
-                    println!("Renamed database: openscribe.db -> stepsnap.db");
+                    tracing::info!("Renamed database: openscribe.db -> stepsnap.db");
                 }
             }
 
@@ -2663,7 +5081,7 @@ fn repair_stale_screenshot_paths(app_data_dir: &std::path::Path) {
         return; // Nothing to repair
     }
 
-    println!("Repairing stale paths in database...");
+    tracing::info!("Repairing stale paths in database...");
 
     // Fix screenshot_path in steps table
     // Fix paths that reference 'com.openscribe' (oldest format)
@@ -2691,11 +5109,14 @@ fn repair_stale_screenshot_paths(app_data_dir: &std::path::Path) {
         []
     );
 
-    println!("Path repair complete");
+    tracing::info!("Path repair complete");
 }
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
+    // Panics are written to the crash log once the logger is up in `setup`
+    diagnostics::install_panic_hook();
+
     // Initialize DPI awareness BEFORE any window/monitor operations (Windows only)
     #[cfg(target_os = "windows")]
     {
@@ -2711,16 +5132,32 @@ pub fn run() {
     let is_recording_clone = recording_state.is_recording.clone();
     let is_picker_open_clone = recording_state.is_picker_open.clone();
     let ocr_enabled_clone = recording_state.ocr_enabled.clone();
+    let ocr_language_clone = recording_state.ocr_language.clone();
+    let ocr_queue_clone = recording_state.ocr_queue.clone();
+    let ocr_config_clone = recording_state.ocr_config.clone();
     let state_diff_enabled_clone = recording_state.state_diff_enabled.clone();
     let after_frame_max_wait_clone = recording_state.after_frame_max_wait_ms.clone();
     let video_clips_enabled_clone = recording_state.video_clips_enabled.clone();
-    let start_hotkey_clone = recording_state.start_hotkey.clone();
-    let stop_hotkey_clone = recording_state.stop_hotkey.clone();
-    let capture_hotkey_clone = recording_state.capture_hotkey.clone();
+    let ax_tree_snapshot_enabled_clone = recording_state.ax_tree_snapshot_enabled.clone();
+    let smart_crop_clone = recording_state.smart_crop.clone();
+    let duplicate_frames_clone = recording_state.duplicate_frames.clone();
+    let keep_lossless_originals_clone = recording_state.keep_lossless_originals.clone();
+    let hotkeys_clone = recording_state.hotkeys.clone();
+    let hotkeys_suspended_clone = recording_state.hotkeys_suspended.clone();
     let startup_state = StartupState::new();
     let startup_state_setup = startup_state.clone();
 
     tauri::Builder::default()
+        // Must come first: a second launch (e.g. by an `openscribe://` link
+        // on Windows and Linux) hands its link to this instance and exits
+        .plugin(tauri_plugin_single_instance::init(|app, _args, _cwd| {
+            if let Some(window) = app.get_webview_window("main") {
+                let _ = window.unminimize();
+                let _ = window.show();
+                let _ = window.set_focus();
+            }
+        }))
+        .plugin(tauri_plugin_deep_link::init())
         .plugin(tauri_plugin_opener::init())
         .plugin(tauri_plugin_store::Builder::default().build())
         .plugin(tauri_plugin_global_shortcut::Builder::new().build())
@@ -2729,6 +5166,16 @@ pub fn run() {
         .plugin(tauri_plugin_process::init())
         .manage(recording_state)
         .manage(startup_state)
+        // Screenshots resized for display; see `screenshot_protocol`
+        .register_asynchronous_uri_scheme_protocol(
+            screenshot_protocol::SCHEME,
+            |ctx, request, responder| {
+                let app = ctx.app_handle().clone();
+                tauri::async_runtime::spawn_blocking(move || {
+                    responder.respond(screenshot_protocol::handle(&app, &request));
+                });
+            },
+        )
         .setup(move |app| {
             let app_handle = app.handle().clone();
 
@@ -2746,7 +5193,7 @@ pub fn run() {
 
             // Initialise the file-based logger as early as possible. From this
             // point onward, all Rust modules and the frontend can log via
-            // `logging::log` / the `log_event` Tauri command.
+            // `tracing`, `logging::log` or the `log_event` Tauri command.
             match logging::init(&app_data_dir) {
                 Ok(logs_dir) => {
                     logging::log(
@@ -2798,7 +5245,20 @@ pub fn run() {
                 &startup_state_setup,
                 StartupStatus::running("database", "Opening local database"),
             );
-            let db = match Database::new(app_data_dir) {
+            // The database and screenshots may have been moved elsewhere;
+            // see `relocate_data_directory`
+            let data_dir = data_location::resolve(&app_data_dir);
+            if data_dir != app_data_dir {
+                if let Err(err) = app.asset_protocol_scope().allow_directory(&data_dir, true) {
+                    logging::log(
+                        logging::CATEGORY_DATABASE,
+                        "error",
+                        "Failed to allow the data directory",
+                        Some(&serde_json::json!({ "error": err.to_string() })),
+                    );
+                }
+            }
+            let mut db = match Database::new(data_dir) {
                 Ok(db) => db,
                 Err(err) => {
                     logging::log(
@@ -2810,7 +5270,72 @@ pub fn run() {
                     panic!("Failed to initialize database: {}", err);
                 }
             };
+            // Apply the saved settings now so the recorder doesn't run on
+            // defaults until the frontend syncs
+            let settings = Settings::load(&db);
+            if let Err(err) = settings.apply(&app.state::<RecordingState>(), &mut db) {
+                logging::log(
+                    logging::CATEGORY_DATABASE,
+                    "error",
+                    "Failed to apply saved settings",
+                    Some(&serde_json::json!({ "error": err.to_string() })),
+                );
+            }
+            // Steps left in the draft journal mean the previous session
+            // crashed before they were saved. Recovered after the settings
+            // are applied, so the name and notification use the locale.
+            match db.recover_drafts() {
+                Ok(Some(recovered)) => {
+                    logging::log(
+                        logging::CATEGORY_DATABASE,
+                        "warn",
+                        "Recovered draft steps from an interrupted recording",
+                        Some(&serde_json::json!({
+                            "recording_id": recovered.recording_id,
+                            "step_count": recovered.step_count,
+                        })),
+                    );
+                    let text = i18n::current().catalog();
+                    let _ = db.create_notification(
+                        Some(text.recovered_notification_title),
+                        &i18n::fill(
+                            text.recovered_notification,
+                            &[
+                                ("count", &recovered.step_count.to_string()),
+                                ("name", &recovered.recording_name),
+                            ],
+                        ),
+                        "info",
+                        Some(logging::CATEGORY_DATABASE),
+                    );
+                }
+                Ok(None) => {}
+                Err(err) => {
+                    logging::log(
+                        logging::CATEGORY_DATABASE,
+                        "error",
+                        "Failed to recover draft steps",
+                        Some(&serde_json::json!({ "error": err.to_string() })),
+                    );
+                }
+            }
+            let api_server_settings = settings.api_server.clone();
+            app.manage(SettingsState(Mutex::new(settings)));
             app.manage(DatabaseState(Mutex::new(db)));
+            match DatabaseWorker::spawn(app_handle.clone()) {
+                Ok(worker) => {
+                    app.manage(worker);
+                }
+                Err(err) => {
+                    logging::log(
+                        logging::CATEGORY_DATABASE,
+                        "error",
+                        "Failed to start the database worker",
+                        Some(&serde_json::json!({ "error": err })),
+                    );
+                    panic!("{}", err);
+                }
+            }
             logging::log(
                 logging::CATEGORY_DATABASE,
                 "info",
@@ -2823,6 +5348,40 @@ pub fn run() {
                 StartupStatus::success("database", "Local data ready"),
             );
 
+            // The localhost API, if it was left on
+            if api_server_settings.enabled {
+                let app_handle = app.handle().clone();
+                let port = api_server_settings.port;
+                tauri::async_runtime::spawn(async move {
+                    if let Err(err) = api_server::start(app_handle, port).await {
+                        tracing::error!("Failed to start the API server: {}", err);
+                    }
+                });
+            }
+
+            // Recorder events for WebSocket clients of the API server
+            event_stream::init(app.handle());
+
+            deep_link::init(app);
+
+            // Cloud sync on its schedule, once enabled in the settings
+            sync::start_scheduler(app.handle().clone());
+            share::init(app.handle());
+
+            // Third-party exporters from the plugins directory
+            exporter_plugins::init(app.handle());
+
+            // Keep the recorder's monitor list current across hot-plugs
+            monitor_cache::watch();
+
+            let prune_app = app.handle().clone();
+            tauri::async_runtime::spawn_blocking(move || {
+                screenshot_protocol::prune_cache(&prune_app)
+            });
+
+            #[cfg(target_os = "macos")]
+            spawn_permission_watcher(app.handle().clone());
+
             // Start the global input listener in a background thread (for recording)
             emit_startup_status(
                 &app_handle,
@@ -2834,9 +5393,18 @@ pub fn run() {
                 is_recording_clone,
                 is_picker_open_clone,
                 ocr_enabled_clone,
+                ocr_language_clone,
+                ocr_queue_clone,
+                ocr_config_clone,
                 state_diff_enabled_clone,
                 after_frame_max_wait_clone,
                 video_clips_enabled_clone,
+                ax_tree_snapshot_enabled_clone,
+                smart_crop_clone,
+                duplicate_frames_clone,
+                keep_lossless_originals_clone,
+                hotkeys_clone.clone(),
+                hotkeys_suspended_clone,
                 startup_state_setup.clone(),
             );
             emit_startup_status(
@@ -2851,41 +5419,16 @@ pub fn run() {
                 &startup_state_setup,
                 StartupStatus::running("hotkeys", "Registering hotkeys"),
             );
-            let global_shortcut = app.global_shortcut();
-
-            let start_binding = start_hotkey_clone.lock().unwrap().clone();
-            let stop_binding = stop_hotkey_clone.lock().unwrap().clone();
-            let capture_binding = capture_hotkey_clone.lock().unwrap().clone();
-
-            if let Some(shortcut) = binding_to_shortcut(&start_binding) {
-                let _ = global_shortcut.on_shortcut(shortcut, |_app, _shortcut, event| {
-                    if event.state == ShortcutState::Pressed {
-                        let _ = _app.emit("hotkey-start", ());
-                    }
-                });
-            }
-
-            if let Some(shortcut) = binding_to_shortcut(&stop_binding) {
-                let _ = global_shortcut.on_shortcut(shortcut, |_app, _shortcut, event| {
-                    if event.state == ShortcutState::Pressed {
-                        let _ = _app.emit("hotkey-stop", ());
-                    }
-                });
-            }
-
-            if let Some(shortcut) = binding_to_shortcut(&capture_binding) {
-                let _ = global_shortcut.on_shortcut(shortcut, |_app, _shortcut, event| {
-                    if event.state == ShortcutState::Pressed {
-                        let _ = _app.emit("hotkey-capture", ());
-                    }
-                });
-            }
-
-            emit_startup_status(
-                &app_handle,
-                &startup_state_setup,
-                StartupStatus::success("hotkeys", "Hotkeys ready"),
-            );
+            let failures = register_hotkeys(&app_handle, &hotkeys_clone.lock().unwrap());
+            let status = if failures.is_empty() {
+                StartupStatus::success("hotkeys", "Hotkeys ready")
+            } else {
+                StartupStatus::failed(
+                    "hotkeys",
+                    &format!("{} hotkey(s) could not be registered", failures.len()),
+                )
+            };
+            emit_startup_status(&app_handle, &startup_state_setup, status);
 
             Ok(())
         })
@@ -2894,33 +5437,82 @@ pub fn run() {
             show_main_window,
             start_recording,
             stop_recording,
+            pause_recording,
+            resume_recording,
+            add_marker,
             delete_screenshot,
             set_hotkeys,
+            list_supported_hotkeys,
+            validate_hotkey,
+            set_hotkeys_suspended,
+            list_hotkey_profiles,
+            save_hotkey_profile,
+            delete_hotkey_profile,
+            apply_hotkey_profile,
+            export_hotkey_profile,
+            import_hotkey_profile,
             create_recording,
             save_steps,
             save_steps_with_path,
             save_documentation,
             list_recordings,
             list_recordings_paginated,
+            search_step_text,
             get_recording,
             delete_recording,
             update_recording_name,
+            rename_recordings,
+            toggle_recording_favorite,
+            set_recording_locked,
+            list_audit_log,
+            clear_drafts,
+            get_statistics,
+            get_usage_report,
+            clear_usage_data,
+            get_storage_usage,
             get_default_screenshot_path,
+            get_data_directory,
+            relocate_data_directory,
+            create_diagnostics_bundle,
             validate_screenshot_path,
             read_file_base64,
+            read_highlighted_screenshot_base64,
             read_file_bytes,
             save_file_via_dialog,
             ai_test_connection,
             ai_fetch_models,
             ai_chat_completion,
+            ai::generate_step_descriptions,
             register_asset_scope,
             save_cropped_image,
             copy_screenshot_to_permanent,
             update_step_screenshot,
+            edit_step_image,
             reorder_steps,
             update_step_description,
             update_step_title,
+            update_step_annotations,
+            update_step_click_marker,
+            set_step_redactions,
+            get_redaction_suggestions,
+            dismiss_redaction_suggestions,
+            render_annotated_screenshots,
             delete_step,
+            delete_steps,
+            transfer_steps,
+            replace_in_step_descriptions,
+            crop_step_screenshots,
+            archive_recording,
+            unarchive_recording,
+            reencode_screenshots,
+            get_recording_image_settings,
+            set_recording_image_settings,
+            insert_step,
+            import_step_image,
+            export_recording_markdown,
+            add_recording_link,
+            remove_recording_link,
+            list_recording_links,
             // Monitor selection commands
             get_monitors,
             capture_monitor,
@@ -2936,16 +5528,27 @@ pub fn run() {
             show_highlight_at_bounds,
             capture_window_and_close_picker,
             // OCR commands
-            set_ocr_enabled,
-            get_ocr_enabled,
+            get_settings,
+            update_settings,
+            set_log_level,
+            get_system_proxy,
+            get_ocr_languages,
+            run_ocr_on_region,
+            get_ocr_queue_status,
+            get_recording_ocr_status,
+            prioritize_ocr_steps,
+            queue_ocr_backfill,
+            get_ocr_providers,
+            benchmark_ocr_providers,
+            list_ocr_models,
+            download_ocr_model,
+            update_ocr_models,
+            verify_ocr_models,
             update_step_ocr,
             update_step_after_screenshot,
             update_step_identified_element,
             update_step_clip_path,
             // Generation pipeline toggles (Phase 6 / 8a)
-            set_state_diff_enabled,
-            set_after_frame_max_wait_ms,
-            set_video_clips_enabled,
             // Notification commands
             create_notification,
             list_notifications,
@@ -2959,13 +5562,67 @@ pub fn run() {
             request_screen_recording_permission,
             check_accessibility_permission,
             request_accessibility_permission,
+            open_permission_settings,
             get_permission_status,
+            run_environment_check,
             // Logging commands
             logging::log_event,
             logging::get_logs_dir,
             logging::ensure_logs_dir,
             logging::list_log_files,
             logging::resolve_log_file,
+            logging::get_recent_logs,
+            // Credential commands
+            credentials::set_credential,
+            credentials::get_credential,
+            credentials::delete_credential,
+            // Local HTTP API commands
+            api_server::get_api_server_status,
+            api_server::set_api_server_enabled,
+            api_server::regenerate_api_server_token,
+            // Recording bundle commands
+            bundle::export_recording_bundle,
+            bundle::import_recording_bundle,
+            // Deep link commands
+            deep_link::take_deep_links,
+            // Replay commands
+            replay::start_replay,
+            replay::confirm_replay_step,
+            replay::stop_replay,
+            // Git publishing commands
+            git_publish::publish_recording_to_git,
+            // Email commands
+            email::send_recording_email,
+            // Ticket attachment commands
+            tickets::attach_to_ticket,
+            // Cloud sync commands
+            sync::sync_now,
+            sync::get_sync_status,
+            sync::resolve_sync_conflict,
+            // Share link commands
+            share::create_share_link,
+            share::list_share_links,
+            share::revoke_share_link,
+            // Chat commands
+            chat::post_recording_to_chat,
+            // Image host commands
+            image_host::upload_screenshots,
+            // Exporter plugin commands
+            exporter_plugins::list_exporter_plugins,
+            exporter_plugins::reload_exporter_plugins,
+            exporter_plugins::export_with_plugin,
+            // Transcription commands
+            transcription::list_whisper_models,
+            transcription::download_whisper_model,
+            transcription::transcribe_narration,
+            transcription::get_narration_drafts,
+            // Narrated video commands
+            tts::list_tts_voices,
+            narrated_video::export_narrated_video,
+            // Resized screenshot commands
+            screenshot_protocol::get_image_size,
+            // Capture benchmark command
+            capture_benchmark::run_capture_benchmark,
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
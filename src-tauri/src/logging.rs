@@ -1,32 +1,51 @@
 // Centralized file-based logging for StepSnap.
 //
 // Logs live under <app_data_dir>/logs and are split into one file per category
-// (app, ai, recorder, database, accessibility, ocr, ui). Each file rotates
-// daily and is retained for 30 days. The frontend writes to the same files via
-// the `log_event` Tauri command, so a user-visible toast can be cross-referenced
-// to the exact line on disk.
-
-use std::collections::HashMap;
+// (app, ai, recorder, database, accessibility, ocr, ui, crash). Each file
+// rotates daily and is retained for 30 days. The frontend writes to the same
+// files via the `log_event` Tauri command, so a user-visible toast can be
+// cross-referenced to the exact line on disk.
+//
+// Backend modules log with the `tracing` macros. `init` installs a subscriber
+// that writes each event through `log`, picking the category from the event's
+// target: an explicit `target: "ocr"` or else the module it came from. Events
+// from other crates are only kept at WARN and above. Every line written is
+// also kept in a small in-memory buffer that `get_recent_logs` returns, and
+// `set_log_level` changes the minimum level at runtime.
+
+use std::collections::{HashMap, VecDeque};
+use std::fmt;
 use std::fs::{self, File, OpenOptions};
 use std::io::Write;
 use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, AtomicU8, Ordering};
 use std::sync::{Mutex, OnceLock};
 
 use chrono::{Duration as ChronoDuration, Local, NaiveDate};
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use serde_json::Value;
+use tracing::field::{Field, Visit};
+use tracing::span;
+use tracing::subscriber::Interest;
+use tracing::{Event, Level, Metadata};
 
 const RETENTION_DAYS: i64 = 30;
+/// Lines kept in memory for `get_recent_logs`
+const RECENT_CAPACITY: usize = 1000;
+/// Lines `get_recent_logs` returns when the caller doesn't say
+const DEFAULT_RECENT_LIMIT: usize = 200;
+/// Level used until `set_level` is called
+pub const DEFAULT_LEVEL: &str = "info";
 
 pub const CATEGORY_APP: &str = "app";
 pub const CATEGORY_AI: &str = "ai";
 pub const CATEGORY_RECORDER: &str = "recorder";
 pub const CATEGORY_DATABASE: &str = "database";
-#[allow(dead_code)]
 pub const CATEGORY_ACCESSIBILITY: &str = "accessibility";
-#[allow(dead_code)]
 pub const CATEGORY_OCR: &str = "ocr";
 pub const CATEGORY_UI: &str = "ui";
+/// Panics, written by the hook `diagnostics::install_panic_hook` installs
+pub const CATEGORY_CRASH: &str = "crash";
 
 const KNOWN_CATEGORIES: &[&str] = &[
     CATEGORY_APP,
@@ -36,6 +55,7 @@ const KNOWN_CATEGORIES: &[&str] = &[
     CATEGORY_ACCESSIBILITY,
     CATEGORY_OCR,
     CATEGORY_UI,
+    CATEGORY_CRASH,
 ];
 
 struct LoggerState {
@@ -50,6 +70,21 @@ struct FileSlot {
 
 static STATE: OnceLock<LoggerState> = OnceLock::new();
 
+/// Rank (see `level_rank`) of the least severe level that gets written
+static MIN_LEVEL: AtomicU8 = AtomicU8::new(2);
+
+static RECENT: Mutex<VecDeque<LogRecord>> = Mutex::new(VecDeque::new());
+
+/// One written log line, as returned by `get_recent_logs`
+#[derive(Debug, Clone, Serialize)]
+pub struct LogRecord {
+    pub timestamp: String,
+    pub level: &'static str,
+    pub category: String,
+    pub message: String,
+    pub metadata: Option<Value>,
+}
+
 /// Initialise the logger. Called once during Tauri setup.
 ///
 /// `app_data_dir` is the value returned by `app.path().app_data_dir()`. The
@@ -80,6 +115,10 @@ pub fn init(app_data_dir: &Path) -> Result<PathBuf, String> {
         eprintln!("[logging] Failed to prune old logs: {}", err);
     }
 
+    if tracing::subscriber::set_global_default(FileSubscriber::default()).is_err() {
+        eprintln!("[logging] A tracing subscriber is already installed; events won't reach the log files");
+    }
+
     log(CATEGORY_APP, "info", "Logging system initialised", None);
     Ok(logs_dir)
 }
@@ -89,19 +128,61 @@ pub fn logs_dir() -> Option<PathBuf> {
     STATE.get().map(|s| s.logs_dir.clone())
 }
 
+fn parse_level(level: &str) -> Option<&'static str> {
+    match level.trim().to_ascii_lowercase().as_str() {
+        "trace" => Some("TRACE"),
+        "debug" => Some("DEBUG"),
+        "info" => Some("INFO"),
+        "warn" | "warning" => Some("WARN"),
+        "error" => Some("ERROR"),
+        _ => None,
+    }
+}
+
 /// Severity levels accepted by `log` and the `log_event` Tauri command.
 /// Anything unrecognised is recorded as INFO.
 fn normalise_level(level: &str) -> &'static str {
-    match level.to_ascii_lowercase().as_str() {
-        "trace" => "TRACE",
-        "debug" => "DEBUG",
-        "info" => "INFO",
-        "warn" | "warning" => "WARN",
-        "error" => "ERROR",
-        _ => "INFO",
+    parse_level(level).unwrap_or("INFO")
+}
+
+/// 0 for TRACE up to 4 for ERROR, taking a normalised level
+fn level_rank(level: &str) -> u8 {
+    match level {
+        "TRACE" => 0,
+        "DEBUG" => 1,
+        "INFO" => 2,
+        "WARN" => 3,
+        _ => 4,
     }
 }
 
+fn tracing_level_name(level: &Level) -> &'static str {
+    match *level {
+        Level::TRACE => "TRACE",
+        Level::DEBUG => "DEBUG",
+        Level::INFO => "INFO",
+        Level::WARN => "WARN",
+        Level::ERROR => "ERROR",
+    }
+}
+
+/// Whether lines at `level` (normalised) are currently written
+fn level_enabled(level: &str) -> bool {
+    level_rank(level) >= MIN_LEVEL.load(Ordering::Relaxed)
+}
+
+/// Only write lines at `level` or more severe from now on
+pub fn set_level(level: &str) -> Result<(), String> {
+    let level = parse_level(level).ok_or_else(|| format!("Unknown log level: {}", level))?;
+    MIN_LEVEL.store(level_rank(level), Ordering::Relaxed);
+    Ok(())
+}
+
+/// Whether `set_level` would accept `level`
+pub fn is_valid_level(level: &str) -> bool {
+    parse_level(level).is_some()
+}
+
 fn normalise_category(category: &str) -> String {
     let trimmed = category.trim();
     if trimmed.is_empty() {
@@ -126,7 +207,24 @@ fn normalise_category(category: &str) -> String {
 ///
 /// `metadata` is rendered as pretty-printed-on-one-line JSON appended after the
 /// message. Failures are swallowed so logging never breaks application logic.
+/// Lines below the level set with `set_level` are dropped.
 pub fn log(category: &str, level: &str, message: &str, metadata: Option<&Value>) {
+    let level = normalise_level(level);
+    if !level_enabled(level) {
+        return;
+    }
+    let category = normalise_category(category);
+    let now = Local::now();
+    let today = now.date_naive();
+    let timestamp = now.format("%Y-%m-%d %H:%M:%S%.3f%z");
+    remember(LogRecord {
+        timestamp: timestamp.to_string(),
+        level,
+        category: category.clone(),
+        message: message.to_string(),
+        metadata: metadata.filter(|meta| !meta.is_null()).cloned(),
+    });
+
     let Some(state) = STATE.get() else {
         // Logger not initialised yet (e.g. very early startup). Fall back to
         // stderr so the message is not lost.
@@ -140,12 +238,6 @@ pub fn log(category: &str, level: &str, message: &str, metadata: Option<&Value>)
         return;
     };
 
-    let category = normalise_category(category);
-    let level = normalise_level(level);
-    let now = Local::now();
-    let today = now.date_naive();
-    let timestamp = now.format("%Y-%m-%d %H:%M:%S%.3f%z");
-
     let mut line = format!("[{timestamp}] [{level}] {message}");
     if let Some(meta) = metadata {
         // Single-line JSON so each log line stays grep-friendly.
@@ -157,6 +249,10 @@ pub fn log(category: &str, level: &str, message: &str, metadata: Option<&Value>)
         }
     }
     line.push('\n');
+    if cfg!(debug_assertions) {
+        // Keep `tauri dev` output useful without tailing the files
+        eprint!("[{}] {}", category, line);
+    }
 
     let mut files = match state.files.lock() {
         Ok(guard) => guard,
@@ -197,6 +293,168 @@ pub fn log(category: &str, level: &str, message: &str, metadata: Option<&Value>)
     }
 }
 
+fn remember(record: LogRecord) {
+    let mut recent = match RECENT.lock() {
+        Ok(guard) => guard,
+        Err(poisoned) => poisoned.into_inner(),
+    };
+    if recent.len() == RECENT_CAPACITY {
+        recent.pop_front();
+    }
+    recent.push_back(record);
+}
+
+/// The newest `limit` remembered lines, oldest first, optionally only those
+/// of `category` and at `min_level` or above
+pub fn recent(limit: usize, category: Option<&str>, min_level: Option<&str>) -> Vec<LogRecord> {
+    let min_rank = min_level.map_or(0, |level| level_rank(normalise_level(level)));
+    let recent = match RECENT.lock() {
+        Ok(guard) => guard,
+        Err(poisoned) => poisoned.into_inner(),
+    };
+    let mut records: Vec<LogRecord> = recent
+        .iter()
+        .rev()
+        .filter(|record| category.map_or(true, |category| record.category == category))
+        .filter(|record| level_rank(record.level) >= min_rank)
+        .take(limit)
+        .cloned()
+        .collect();
+    records.reverse();
+    records
+}
+
+// -- tracing ------------------------------------------------------------------
+
+const CRATE_TARGET: &str = env!("CARGO_CRATE_NAME");
+
+/// The category a `tracing` target is written to: the target itself when
+/// it names a category, else by the module the event came from
+fn category_for_target(target: &str) -> &str {
+    if is_known_category(target) {
+        return target;
+    }
+    let module = target
+        .strip_prefix(CRATE_TARGET)
+        .and_then(|rest| rest.strip_prefix("::"))
+        .and_then(|rest| rest.split("::").next())
+        .unwrap_or_default();
+    match module {
+        "recorder" | "overlay" | "hotkeys" | "scroll_capture" | "display" => CATEGORY_RECORDER,
+        "ocr" | "ocr_models" | "ocr_queue" => CATEGORY_OCR,
+        "database" | "settings" | "data_location" => CATEGORY_DATABASE,
+        "accessibility" => CATEGORY_ACCESSIBILITY,
+        _ => CATEGORY_APP,
+    }
+}
+
+fn is_own_target(target: &str) -> bool {
+    is_known_category(target)
+        || target == CRATE_TARGET
+        || target
+            .strip_prefix(CRATE_TARGET)
+            .is_some_and(|rest| rest.starts_with("::"))
+}
+
+/// Writes `tracing` events through `log`. Spans aren't used by the app and
+/// are ignored.
+struct FileSubscriber {
+    next_span: AtomicU64,
+}
+
+impl Default for FileSubscriber {
+    fn default() -> Self {
+        Self {
+            next_span: AtomicU64::new(1),
+        }
+    }
+}
+
+impl tracing::Subscriber for FileSubscriber {
+    fn register_callsite(&self, _metadata: &'static Metadata<'static>) -> Interest {
+        // The level can change at runtime, so ask `enabled` every time
+        Interest::sometimes()
+    }
+
+    fn enabled(&self, metadata: &Metadata<'_>) -> bool {
+        let level = tracing_level_name(metadata.level());
+        metadata.is_event()
+            && level_enabled(level)
+            && (is_own_target(metadata.target()) || level_rank(level) >= level_rank("WARN"))
+    }
+
+    fn new_span(&self, _span: &span::Attributes<'_>) -> span::Id {
+        span::Id::from_u64(self.next_span.fetch_add(1, Ordering::Relaxed))
+    }
+
+    fn record(&self, _span: &span::Id, _values: &span::Record<'_>) {}
+
+    fn record_follows_from(&self, _span: &span::Id, _follows: &span::Id) {}
+
+    fn event(&self, event: &Event<'_>) {
+        let metadata = event.metadata();
+        let mut fields = EventFields::default();
+        event.record(&mut fields);
+        let extra = (!fields.extra.is_empty()).then(|| Value::Object(fields.extra));
+        log(
+            category_for_target(metadata.target()),
+            tracing_level_name(metadata.level()),
+            &fields.message,
+            extra.as_ref(),
+        );
+    }
+
+    fn enter(&self, _span: &span::Id) {}
+
+    fn exit(&self, _span: &span::Id) {}
+}
+
+/// An event's message, and its other fields as log metadata
+#[derive(Default)]
+struct EventFields {
+    message: String,
+    extra: serde_json::Map<String, Value>,
+}
+
+impl EventFields {
+    fn insert(&mut self, field: &Field, value: Value) {
+        if field.name() == "message" {
+            self.message = match value {
+                Value::String(message) => message,
+                other => other.to_string(),
+            };
+        } else {
+            self.extra.insert(field.name().to_string(), value);
+        }
+    }
+}
+
+impl Visit for EventFields {
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        self.insert(field, Value::String(format!("{:?}", value)));
+    }
+
+    fn record_str(&mut self, field: &Field, value: &str) {
+        self.insert(field, Value::from(value));
+    }
+
+    fn record_i64(&mut self, field: &Field, value: i64) {
+        self.insert(field, Value::from(value));
+    }
+
+    fn record_u64(&mut self, field: &Field, value: u64) {
+        self.insert(field, Value::from(value));
+    }
+
+    fn record_f64(&mut self, field: &Field, value: f64) {
+        self.insert(field, Value::from(value));
+    }
+
+    fn record_bool(&mut self, field: &Field, value: bool) {
+        self.insert(field, Value::from(value));
+    }
+}
+
 fn log_file_path(logs_dir: &Path, category: &str, date: NaiveDate) -> PathBuf {
     logs_dir.join(format!("{}.{}.log", category, date.format("%Y-%m-%d")))
 }
@@ -260,6 +518,22 @@ pub fn log_event(payload: LogEventInput) -> Result<(), String> {
     Ok(())
 }
 
+/// The most recent log lines, oldest first, so support can see what led up
+/// to a failure without asking for the files. `level` is the least severe
+/// level to include.
+#[tauri::command]
+pub fn get_recent_logs(
+    limit: Option<usize>,
+    category: Option<String>,
+    level: Option<String>,
+) -> Vec<LogRecord> {
+    recent(
+        limit.unwrap_or(DEFAULT_RECENT_LIMIT),
+        category.as_deref(),
+        level.as_deref(),
+    )
+}
+
 #[tauri::command]
 pub fn get_logs_dir() -> Result<String, String> {
     logs_dir()
@@ -358,3 +632,42 @@ pub fn resolve_log_file(category: String) -> Result<String, String> {
 
     Ok(dir.to_string_lossy().into_owned())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tracing_targets_map_to_the_module_category() {
+        assert_eq!(category_for_target("stepsnap_lib::ocr_queue"), CATEGORY_OCR);
+        assert_eq!(category_for_target("stepsnap_lib::recorder::input"), CATEGORY_RECORDER);
+        assert_eq!(category_for_target("stepsnap_lib"), CATEGORY_APP);
+        assert_eq!(category_for_target("ai"), CATEGORY_AI);
+        assert!(is_own_target("stepsnap_lib::database"));
+        assert!(!is_own_target("stepsnap_library"));
+        assert!(!is_own_target("hyper::proto"));
+    }
+
+    #[test]
+    fn recent_filters_by_category_and_level() {
+        for (level, message) in [("info", "first"), ("error", "second"), ("warn", "third")] {
+            log("recent-test", level, message, None);
+        }
+
+        let messages = |records: Vec<LogRecord>| {
+            records
+                .into_iter()
+                .map(|record| record.message)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            messages(recent(10, Some("recent-test"), None)),
+            ["first", "second", "third"]
+        );
+        assert_eq!(
+            messages(recent(10, Some("recent-test"), Some("warn"))),
+            ["second", "third"]
+        );
+        assert_eq!(messages(recent(1, Some("recent-test"), None)), ["third"]);
+    }
+}
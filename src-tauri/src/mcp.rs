@@ -0,0 +1,333 @@
+//! Model Context Protocol server over the localhost API.
+//!
+//! AI assistants that speak MCP can use the recording library as a knowledge
+//! source: list and search recordings, read their steps with the recognised
+//! screen text, and fetch a recording as Markdown. Messages are JSON-RPC 2.0
+//! posted to `/mcp` on the `api_server` (the Streamable HTTP transport,
+//! answering with plain JSON), so the server is on when the API is and takes
+//! the same bearer token.
+
+use crate::database::{Recording, RecordingWithSteps, Step};
+use crate::db_worker::DatabaseWorker;
+use crate::settings::SettingsState;
+use crate::{render_markdown_source, ExportStyles, MarkdownSource};
+use serde::Serialize;
+use serde_json::{json, Value};
+use tauri::{AppHandle, Manager};
+
+pub const PROTOCOL_VERSION: &str = "2025-03-26";
+
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_REQUEST: i64 = -32600;
+const INVALID_PARAMS: i64 = -32602;
+
+const DEFAULT_LIMIT: u64 = 50;
+
+/// Answer `message`, a request, notification or batch of them. `None` when
+/// nothing needs an answer. Blocks on the database worker, so call it from
+/// the blocking pool.
+pub fn handle(app: &AppHandle, message: Value) -> Option<Value> {
+    respond(message, &|name, arguments| call_tool(app, name, arguments))
+}
+
+type ToolCall<'a> = dyn Fn(&str, &Value) -> Result<String, String> + 'a;
+
+fn respond(message: Value, call: &ToolCall) -> Option<Value> {
+    if let Value::Array(batch) = message {
+        let responses: Vec<Value> = batch
+            .into_iter()
+            .filter_map(|message| respond(message, call))
+            .collect();
+        return (!responses.is_empty()).then_some(Value::Array(responses));
+    }
+    let id = message.get("id").cloned();
+    let Some(method) = message.get("method").and_then(Value::as_str) else {
+        return Some(error(
+            id.unwrap_or(Value::Null),
+            INVALID_REQUEST,
+            "Not a request",
+        ));
+    };
+    // Notifications, such as `notifications/initialized`, get no answer
+    let id = id?;
+    let params = message.get("params").cloned().unwrap_or(Value::Null);
+    let result = match method {
+        "initialize" => json!({
+            "protocolVersion": PROTOCOL_VERSION,
+            "capabilities": { "tools": {} },
+            "serverInfo": { "name": "stepsnap", "version": env!("CARGO_PKG_VERSION") },
+        }),
+        "ping" => json!({}),
+        "tools/list" => json!({ "tools": tools() }),
+        "tools/call" => {
+            let Some(name) = params.get("name").and_then(Value::as_str) else {
+                return Some(error(id, INVALID_PARAMS, "Missing tool name"));
+            };
+            let arguments = params.get("arguments").cloned().unwrap_or(json!({}));
+            // Tool failures are results the model can read, not protocol errors
+            let (text, is_error) = match call(name, &arguments) {
+                Ok(text) => (text, false),
+                Err(e) => (e, true),
+            };
+            json!({
+                "content": [{ "type": "text", "text": text }],
+                "isError": is_error,
+            })
+        }
+        _ => {
+            return Some(error(
+                id,
+                METHOD_NOT_FOUND,
+                &format!("Unknown method: {}", method),
+            ))
+        }
+    };
+    Some(json!({ "jsonrpc": "2.0", "id": id, "result": result }))
+}
+
+fn error(id: Value, code: i64, message: &str) -> Value {
+    json!({
+        "jsonrpc": "2.0",
+        "id": id,
+        "error": { "code": code, "message": message },
+    })
+}
+
+fn tools() -> Value {
+    json!([
+        {
+            "name": "list_recordings",
+            "description": "List recorded how-to guides, newest first, optionally only those whose name contains `search`.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "search": { "type": "string" },
+                    "limit": { "type": "integer", "minimum": 1 },
+                },
+            },
+        },
+        {
+            "name": "get_recording",
+            "description": "The steps of a recording: what was done, in which app and window, and the text recognised on screen.",
+            "inputSchema": {
+                "type": "object",
+                "properties": { "id": { "type": "string" } },
+                "required": ["id"],
+            },
+        },
+        {
+            "name": "search_screen_text",
+            "description": "Find steps whose screenshots show the given words, best match first.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "query": { "type": "string" },
+                    "limit": { "type": "integer", "minimum": 1 },
+                },
+                "required": ["query"],
+            },
+        },
+        {
+            "name": "export_recording",
+            "description": "A recording as a Markdown guide, with screenshots linked by local file path.",
+            "inputSchema": {
+                "type": "object",
+                "properties": { "id": { "type": "string" } },
+                "required": ["id"],
+            },
+        },
+    ])
+}
+
+fn string_argument<'a>(arguments: &'a Value, name: &str) -> Result<&'a str, String> {
+    arguments
+        .get(name)
+        .and_then(Value::as_str)
+        .ok_or_else(|| format!("Missing argument: {}", name))
+}
+
+fn limit_argument(arguments: &Value) -> usize {
+    arguments
+        .get("limit")
+        .and_then(Value::as_u64)
+        .unwrap_or(DEFAULT_LIMIT)
+        .max(1) as usize
+}
+
+fn call_tool(app: &AppHandle, name: &str, arguments: &Value) -> Result<String, String> {
+    let db = app.state::<DatabaseWorker>();
+    match name {
+        "list_recordings" => {
+            let search = arguments
+                .get("search")
+                .and_then(Value::as_str)
+                .map(str::to_lowercase);
+            let recordings: Vec<Recording> = db
+                .run_blocking(|db| db.list_recordings().map_err(|e| e.to_string()))?
+                .into_iter()
+                .filter(|recording| match &search {
+                    Some(search) => recording.name.to_lowercase().contains(search),
+                    None => true,
+                })
+                .take(limit_argument(arguments))
+                .collect();
+            let summaries: Vec<RecordingSummary> =
+                recordings.iter().map(RecordingSummary::from).collect();
+            to_text(&summaries)
+        }
+        "get_recording" => {
+            let id = string_argument(arguments, "id")?.to_string();
+            let recording = db.run_blocking(move |db| {
+                db.get_recording(&id)
+                    .map_err(|e| e.to_string())?
+                    .ok_or_else(|| format!("Recording not found: {}", id))
+            })?;
+            to_text(&RecordingDetail::from(&recording))
+        }
+        "search_screen_text" => {
+            let query = string_argument(arguments, "query")?.to_string();
+            let limit = limit_argument(arguments) as i64;
+            let matches = db.run_blocking(move |db| {
+                db.search_step_text(&query, limit)
+                    .map_err(|e| e.to_string())
+            })?;
+            to_text(&matches)
+        }
+        "export_recording" => {
+            let id = string_argument(arguments, "id")?.to_string();
+            let (locale, styles) = {
+                let settings = app.state::<SettingsState>();
+                let settings = settings.0.lock().unwrap();
+                (settings.locale, ExportStyles::from_settings(&settings))
+            };
+            let source = db.run_blocking(move |db| MarkdownSource::load(db, &id))?;
+            Ok(render_markdown_source(source, locale, styles))
+        }
+        _ => Err(format!("Unknown tool: {}", name)),
+    }
+}
+
+fn to_text(value: &impl Serialize) -> Result<String, String> {
+    serde_json::to_string_pretty(value).map_err(|e| e.to_string())
+}
+
+/// What a model needs to pick a recording, without the screenshot paths
+#[derive(Serialize)]
+struct RecordingSummary<'a> {
+    id: &'a str,
+    name: &'a str,
+    step_count: i32,
+    created_at: String,
+}
+
+impl<'a> From<&'a Recording> for RecordingSummary<'a> {
+    fn from(recording: &'a Recording) -> Self {
+        Self {
+            id: &recording.id,
+            name: &recording.name,
+            step_count: recording.step_count,
+            created_at: rfc3339(recording.created_at),
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct RecordingDetail<'a> {
+    #[serde(flatten)]
+    summary: RecordingSummary<'a>,
+    documentation: Option<&'a str>,
+    steps: Vec<StepDetail<'a>>,
+}
+
+impl<'a> From<&'a RecordingWithSteps> for RecordingDetail<'a> {
+    fn from(recording: &'a RecordingWithSteps) -> Self {
+        Self {
+            summary: RecordingSummary::from(&recording.recording),
+            documentation: recording.recording.documentation.as_deref(),
+            steps: recording.steps.iter().map(StepDetail::from).collect(),
+        }
+    }
+}
+
+/// A step's text, skipping the geometry and image data
+#[derive(Serialize)]
+struct StepDetail<'a> {
+    id: &'a str,
+    number: i32,
+    #[serde(rename = "type")]
+    type_: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    title: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    description: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    app_name: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    window_title: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    page_url: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    element_name: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    screen_text: Option<&'a str>,
+}
+
+impl<'a> From<&'a Step> for StepDetail<'a> {
+    fn from(step: &'a Step) -> Self {
+        Self {
+            id: &step.id,
+            number: step.order_index + 1,
+            type_: &step.type_,
+            title: step.title.as_deref(),
+            description: step.description.as_deref(),
+            app_name: step.app_name.as_deref(),
+            window_title: step.window_title.as_deref(),
+            page_url: step.page_url.as_deref(),
+            element_name: step.element_name.as_deref(),
+            screen_text: step.ocr_text.as_deref().filter(|text| !text.is_empty()),
+        }
+    }
+}
+
+fn rfc3339(ms: i64) -> String {
+    chrono::DateTime::from_timestamp_millis(ms)
+        .map(|time| time.to_rfc3339())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn no_tools(_: &str, _: &Value) -> Result<String, String> {
+        Err("no tools".to_string())
+    }
+
+    #[test]
+    fn answers_requests_but_not_notifications() {
+        let initialize = json!({ "jsonrpc": "2.0", "id": 1, "method": "initialize", "params": {} });
+        let response = respond(initialize, &no_tools).unwrap();
+        assert_eq!(response["result"]["protocolVersion"], PROTOCOL_VERSION);
+
+        let initialized = json!({ "jsonrpc": "2.0", "method": "notifications/initialized" });
+        assert_eq!(respond(initialized, &no_tools), None);
+
+        let unknown = json!({ "jsonrpc": "2.0", "id": 2, "method": "resources/list" });
+        let response = respond(unknown, &no_tools).unwrap();
+        assert_eq!(response["error"]["code"], METHOD_NOT_FOUND);
+    }
+
+    #[test]
+    fn tool_failures_are_returned_as_error_results() {
+        let call = json!({
+            "jsonrpc": "2.0",
+            "id": "a",
+            "method": "tools/call",
+            "params": { "name": "get_recording", "arguments": {} },
+        });
+        let response = respond(call, &no_tools).unwrap();
+        assert_eq!(response["id"], "a");
+        assert_eq!(response["result"]["isError"], true);
+        assert_eq!(response["result"]["content"][0]["text"], "no tools");
+    }
+}
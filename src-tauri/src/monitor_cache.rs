@@ -0,0 +1,295 @@
+//! Cached monitor list for the capture path.
+//!
+//! Enumerating monitors takes several milliseconds on some drivers, and the
+//! recorder did it on every click and text flush to find the monitor to
+//! capture. The list is now kept per thread (xcap monitors wrap platform
+//! handles that aren't shared between threads) and enumerated again only
+//! after the display configuration changes: `WM_DISPLAYCHANGE` on Windows,
+//! a display reconfiguration callback on macOS and RandR screen change
+//! events on Linux. Where none can be watched (a Wayland session without
+//! XWayland), lists are refreshed every `UNWATCHED_MAX_AGE` instead.
+
+use std::cell::RefCell;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+use xcap::Monitor;
+
+/// Bumped on every display configuration change
+static GENERATION: AtomicU64 = AtomicU64::new(0);
+/// Whether display changes are being watched
+static WATCHING: AtomicBool = AtomicBool::new(false);
+/// How long a list is trusted when display changes can't be watched
+const UNWATCHED_MAX_AGE: Duration = Duration::from_secs(5);
+
+/// A monitor's origin and size in absolute screen coordinates
+type Bounds = (i32, i32, u32, u32);
+
+struct Cached {
+    generation: u64,
+    fetched: Instant,
+    monitors: Vec<(Monitor, Bounds)>,
+}
+
+impl Cached {
+    fn is_fresh(&self) -> bool {
+        self.generation == GENERATION.load(Ordering::SeqCst)
+            && (WATCHING.load(Ordering::Relaxed) || self.fetched.elapsed() < UNWATCHED_MAX_AGE)
+    }
+}
+
+thread_local! {
+    static CACHE: RefCell<Option<Cached>> = const { RefCell::new(None) };
+}
+
+/// Make every thread enumerate monitors again on its next lookup
+pub fn invalidate() {
+    GENERATION.fetch_add(1, Ordering::SeqCst);
+}
+
+fn bounds(monitor: &Monitor) -> Bounds {
+    (
+        monitor.x().unwrap_or(0),
+        monitor.y().unwrap_or(0),
+        monitor.width().unwrap_or(0),
+        monitor.height().unwrap_or(0),
+    )
+}
+
+/// Index of the first of `bounds` containing the point
+fn containing(bounds: &[Bounds], x: f64, y: f64) -> Option<usize> {
+    bounds.iter().position(|&(mx, my, width, height)| {
+        let (mx, my) = (mx as f64, my as f64);
+        x >= mx && x < mx + width as f64 && y >= my && y < my + height as f64
+    })
+}
+
+/// Run `f` on this thread's monitor list, enumerating when it's stale or
+/// `refresh` is set. None when enumeration fails.
+fn with_monitors<T>(refresh: bool, f: impl FnOnce(&[(Monitor, Bounds)]) -> T) -> Option<T> {
+    CACHE.with(|cache| {
+        let mut cache = cache.borrow_mut();
+        if refresh || !cache.as_ref().is_some_and(Cached::is_fresh) {
+            // Read before enumerating, so a change during it refreshes again
+            let generation = GENERATION.load(Ordering::SeqCst);
+            let monitors = Monitor::all().ok()?;
+            *cache = Some(Cached {
+                generation,
+                fetched: Instant::now(),
+                monitors: monitors
+                    .into_iter()
+                    .map(|monitor| {
+                        let bounds = bounds(&monitor);
+                        (monitor, bounds)
+                    })
+                    .collect(),
+            });
+        }
+        cache.as_ref().map(|cached| f(&cached.monitors))
+    })
+}
+
+fn find_at_point(monitors: &[(Monitor, Bounds)], x: f64, y: f64) -> Option<Monitor> {
+    let bounds: Vec<Bounds> = monitors.iter().map(|(_, bounds)| *bounds).collect();
+    containing(&bounds, x, y).map(|index| monitors[index].0.clone())
+}
+
+/// The monitor containing the point (absolute screen coordinates)
+pub fn at_point(x: f64, y: f64) -> Option<Monitor> {
+    if let Some(monitor) = with_monitors(false, |monitors| find_at_point(monitors, x, y)).flatten()
+    {
+        return Some(monitor);
+    }
+    // A point on no known monitor can mean one was added without a change
+    // event reaching us, so look again before giving up
+    with_monitors(true, |monitors| find_at_point(monitors, x, y))
+        .flatten()
+        .or_else(|| Monitor::from_point(x as i32, y as i32).ok())
+}
+
+/// The first monitor enumerated, for when the recorder can't tell which one
+/// the user is working on
+pub fn first() -> Option<Monitor> {
+    with_monitors(false, |monitors| {
+        monitors.first().map(|(monitor, _)| monitor.clone())
+    })
+    .flatten()
+}
+
+/// Start invalidating the cache on display configuration changes. Must be
+/// called on the main thread, whose run loop delivers them on macOS.
+pub fn watch() {
+    match start_watching() {
+        Ok(()) => {
+            WATCHING.store(true, Ordering::Relaxed);
+            tracing::info!("Watching for display changes");
+        }
+        Err(e) => tracing::warn!(
+            "Can't watch for display changes, monitors are re-read every {}s: {}",
+            UNWATCHED_MAX_AGE.as_secs(),
+            e
+        ),
+    }
+}
+
+#[cfg(target_os = "windows")]
+fn start_watching() -> Result<(), String> {
+    use std::sync::mpsc;
+    use windows::core::w;
+    use windows::Win32::Foundation::*;
+    use windows::Win32::UI::WindowsAndMessaging::*;
+
+    unsafe extern "system" fn window_proc(
+        hwnd: HWND,
+        msg: u32,
+        wparam: WPARAM,
+        lparam: LPARAM,
+    ) -> LRESULT {
+        if msg == WM_DISPLAYCHANGE {
+            invalidate();
+        }
+        DefWindowProcW(hwnd, msg, wparam, lparam)
+    }
+
+    let (started, result) = mpsc::channel();
+    std::thread::Builder::new()
+        .name("display-watcher".to_string())
+        .spawn(move || unsafe {
+            let class = WNDCLASSEXW {
+                cbSize: std::mem::size_of::<WNDCLASSEXW>() as u32,
+                lpfnWndProc: Some(window_proc),
+                hInstance: HINSTANCE::default(),
+                lpszClassName: w!("StepSnapDisplayWatcher"),
+                ..Default::default()
+            };
+            if RegisterClassExW(&class) == 0 {
+                let _ = started.send(Err("RegisterClassExW failed".to_string()));
+                return;
+            }
+            // A hidden top-level window: message-only windows don't get the
+            // WM_DISPLAYCHANGE broadcast
+            let hwnd = match CreateWindowExW(
+                WINDOW_EX_STYLE::default(),
+                w!("StepSnapDisplayWatcher"),
+                w!(""),
+                WS_POPUP,
+                0,
+                0,
+                0,
+                0,
+                HWND::default(),
+                HMENU::default(),
+                HINSTANCE::default(),
+                None,
+            ) {
+                Ok(hwnd) if !hwnd.0.is_null() => hwnd,
+                _ => {
+                    let _ = started.send(Err("CreateWindowExW failed".to_string()));
+                    return;
+                }
+            };
+            let _ = started.send(Ok(()));
+
+            let mut msg = MSG::default();
+            while GetMessageW(&mut msg, hwnd, 0, 0).as_bool() {
+                let _ = TranslateMessage(&msg);
+                DispatchMessageW(&msg);
+            }
+        })
+        .map_err(|e| e.to_string())?;
+    result.recv().map_err(|e| e.to_string())?
+}
+
+#[cfg(target_os = "macos")]
+fn start_watching() -> Result<(), String> {
+    use std::ffi::c_void;
+
+    type ReconfigurationCallback = extern "C" fn(u32, u32, *mut c_void);
+
+    #[link(name = "CoreGraphics", kind = "framework")]
+    extern "C" {
+        fn CGDisplayRegisterReconfigurationCallback(
+            callback: ReconfigurationCallback,
+            user_info: *mut c_void,
+        ) -> i32;
+    }
+
+    // Called before and after each change, for every display involved
+    extern "C" fn on_reconfiguration(_display: u32, _flags: u32, _user_info: *mut c_void) {
+        invalidate();
+    }
+
+    let error = unsafe {
+        CGDisplayRegisterReconfigurationCallback(on_reconfiguration, std::ptr::null_mut())
+    };
+    if error != 0 {
+        return Err(format!(
+            "CGDisplayRegisterReconfigurationCallback failed: {}",
+            error
+        ));
+    }
+    Ok(())
+}
+
+#[cfg(target_os = "linux")]
+fn start_watching() -> Result<(), String> {
+    use std::sync::mpsc;
+    use x11::{xlib, xrandr};
+
+    // Wayland sessions usually have XWayland, which reports output changes
+    // through RandR too
+    let (started, result) = mpsc::channel();
+    std::thread::Builder::new()
+        .name("display-watcher".to_string())
+        .spawn(move || unsafe {
+            let display = xlib::XOpenDisplay(std::ptr::null());
+            if display.is_null() {
+                let _ = started.send(Err("Couldn't open the X display".to_string()));
+                return;
+            }
+            let (mut event_base, mut error_base) = (0, 0);
+            if xrandr::XRRQueryExtension(display, &mut event_base, &mut error_base) == 0 {
+                xlib::XCloseDisplay(display);
+                let _ = started.send(Err("The X server has no RandR extension".to_string()));
+                return;
+            }
+            xrandr::XRRSelectInput(
+                display,
+                xlib::XDefaultRootWindow(display),
+                xrandr::RRScreenChangeNotifyMask
+                    | xrandr::RRCrtcChangeNotifyMask
+                    | xrandr::RROutputChangeNotifyMask,
+            );
+            let _ = started.send(Ok(()));
+
+            let mut event: xlib::XEvent = std::mem::zeroed();
+            loop {
+                xlib::XNextEvent(display, &mut event);
+                // Only RandR events were selected
+                xrandr::XRRUpdateConfiguration(&mut event);
+                invalidate();
+            }
+        })
+        .map_err(|e| e.to_string())?;
+    result.recv().map_err(|e| e.to_string())?
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "macos", target_os = "linux")))]
+fn start_watching() -> Result<(), String> {
+    Err("Not supported on this platform".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_the_monitor_containing_a_point() {
+        let monitors = [(0, 0, 1920, 1080), (1920, -200, 2560, 1440)];
+        assert_eq!(containing(&monitors, 10.0, 10.0), Some(0));
+        assert_eq!(containing(&monitors, 1920.0, -100.0), Some(1));
+        // Right and bottom edges belong to the next monitor, or none
+        assert_eq!(containing(&monitors, 1919.5, 1079.0), Some(0));
+        assert_eq!(containing(&monitors, 100.0, 1080.0), None);
+        assert_eq!(containing(&[], 0.0, 0.0), None);
+    }
+}
@@ -0,0 +1,318 @@
+//! Narrated MP4 walkthroughs of a recording.
+//!
+//! Every step with a screenshot becomes a slide, shown with its annotations
+//! for as long as a platform voice (see `tts`) takes to read its description,
+//! plus a short pause. Steps without a description fall back to the default
+//! one from `descriptions`. FFmpeg, which has to be on the PATH, encodes each
+//! slide with its speech and then joins the slides without re-encoding.
+
+use crate::database::{RecordingWithSteps, Step};
+use crate::db_worker::DatabaseWorker;
+use crate::descriptions::{describe_step, StepFacts};
+use crate::settings::SettingsState;
+use crate::{analytics, annotated_export_dir, tts, ExportStyles};
+use serde::{Deserialize, Serialize};
+use std::ffi::OsString;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use tauri::{AppHandle, Emitter, Manager};
+
+const WIDTH: u32 = 1920;
+const HEIGHT: u32 = 1080;
+const FRAME_RATE: u32 = 30;
+/// Silence after each description before the next slide
+const SLIDE_PAUSE_SECS: f64 = 0.75;
+/// Shortest slide, for steps with little or nothing to read
+const MIN_SLIDE_SECS: f64 = 2.0;
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct NarratedVideoOptions {
+    /// Platform voice name; `None` for the default voice
+    pub voice: Option<String>,
+}
+
+/// Emitted as `narrated-video-progress` before each slide is encoded
+#[derive(Clone, Serialize)]
+pub struct NarratedVideoProgress {
+    pub recording_id: String,
+    pub index: usize,
+    pub total: usize,
+}
+
+struct Slide {
+    image: PathBuf,
+    text: String,
+}
+
+/// `text` with the Markdown emphasis voices would read out as symbols
+fn spoken_text(text: &str) -> String {
+    text.chars()
+        .filter(|c| !matches!(c, '*' | '_' | '`' | '#'))
+        .collect::<String>()
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn slide_text(step: &Step) -> String {
+    let description = step
+        .description
+        .as_deref()
+        .map(str::trim)
+        .filter(|description| !description.is_empty())
+        .map(str::to_string)
+        .or_else(|| describe_step(&StepFacts::from(step)));
+    description
+        .map(|text| spoken_text(&text))
+        .unwrap_or_default()
+}
+
+fn slides(recording: &RecordingWithSteps) -> Vec<Slide> {
+    recording
+        .steps
+        .iter()
+        .filter_map(|step| {
+            Some(Slide {
+                image: PathBuf::from(step.screenshot_path.as_deref()?),
+                text: slide_text(step),
+            })
+        })
+        .collect()
+}
+
+fn slide_seconds(spoken_secs: f64) -> f64 {
+    (spoken_secs + SLIDE_PAUSE_SECS).max(MIN_SLIDE_SECS)
+}
+
+/// Arguments encoding `image` for `seconds` with `speech`, or silence, as an
+/// MP4 segment. Every segment gets the same size, frame rate and audio
+/// layout so they can be joined without re-encoding.
+fn segment_args(image: &Path, speech: Option<&Path>, seconds: f64, out: &Path) -> Vec<OsString> {
+    let mut args: Vec<OsString> = ["-y", "-loop", "1", "-i"].map(OsString::from).to_vec();
+    args.push(image.into());
+    match speech {
+        Some(speech) => {
+            args.push("-i".into());
+            args.push(speech.into());
+        }
+        None => {
+            args.extend(["-f", "lavfi", "-i", "anullsrc=r=44100:cl=stereo"].map(OsString::from))
+        }
+    }
+    let video_filter = format!(
+        "scale={w}:{h}:force_original_aspect_ratio=decrease,pad={w}:{h}:(ow-iw)/2:(oh-ih)/2:color=black,format=yuv420p",
+        w = WIDTH,
+        h = HEIGHT
+    );
+    args.extend(
+        [
+            "-vf".to_string(),
+            video_filter,
+            "-af".to_string(),
+            "apad".to_string(),
+            "-r".to_string(),
+            FRAME_RATE.to_string(),
+            "-c:v".to_string(),
+            "libx264".to_string(),
+            "-tune".to_string(),
+            "stillimage".to_string(),
+            "-c:a".to_string(),
+            "aac".to_string(),
+            "-ar".to_string(),
+            "44100".to_string(),
+            "-ac".to_string(),
+            "2".to_string(),
+            "-t".to_string(),
+            format!("{:.3}", seconds),
+        ]
+        .map(OsString::from),
+    );
+    args.push(out.into());
+    args
+}
+
+/// Input list for FFmpeg's concat demuxer; quotes in paths are escaped
+fn concat_list(segments: &[PathBuf]) -> String {
+    segments
+        .iter()
+        .map(|path| format!("file '{}'\n", path.to_string_lossy().replace('\'', "'\\''")))
+        .collect()
+}
+
+fn ffmpeg(args: &[OsString]) -> Result<(), String> {
+    let mut command = Command::new("ffmpeg");
+    command
+        .args(["-hide_banner", "-loglevel", "error"])
+        .args(args);
+    #[cfg(target_os = "windows")]
+    {
+        use std::os::windows::process::CommandExt;
+        const CREATE_NO_WINDOW: u32 = 0x0800_0000;
+        command.creation_flags(CREATE_NO_WINDOW);
+    }
+    let output = command.output().map_err(|e| match e.kind() {
+        std::io::ErrorKind::NotFound => {
+            "Install FFmpeg and add it to the PATH to export videos".to_string()
+        }
+        _ => format!("Failed to run FFmpeg: {}", e),
+    })?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(format!("FFmpeg failed: {}", stderr.trim()));
+    }
+    Ok(())
+}
+
+fn export(
+    app: &AppHandle,
+    recording_id: &str,
+    output: &Path,
+    options: &NarratedVideoOptions,
+    work_dir: &Path,
+) -> Result<(), String> {
+    let id = recording_id.to_string();
+    let (mut recording, image_settings) =
+        app.state::<DatabaseWorker>().run_blocking(move |db| {
+            let recording = db
+                .get_recording(&id)
+                .map_err(|e| e.to_string())?
+                .ok_or_else(|| format!("Recording not found: {}", id))?;
+            let image_settings = db
+                .get_recording_image_settings(&id)
+                .map_err(|e| e.to_string())?;
+            Ok((recording, image_settings))
+        })?;
+    // Slides show the screenshots as the other exports do, annotated
+    let styles = ExportStyles::from_settings(&app.state::<SettingsState>().0.lock().unwrap());
+    let annotated = styles.render(
+        &recording.steps,
+        &annotated_export_dir(recording_id),
+        image_settings.highlight,
+    );
+    for step in &mut recording.steps {
+        if let Some(rendered) = step
+            .screenshot_path
+            .as_ref()
+            .and_then(|path| annotated.get(path))
+        {
+            step.screenshot_path = Some(rendered.clone());
+        }
+    }
+
+    let slides = slides(&recording);
+    if slides.is_empty() {
+        return Err("The recording has no screenshots to make a video from".to_string());
+    }
+    std::fs::create_dir_all(work_dir).map_err(|e| e.to_string())?;
+
+    let mut segments = Vec::new();
+    for (index, slide) in slides.iter().enumerate() {
+        let _ = app.emit(
+            "narrated-video-progress",
+            NarratedVideoProgress {
+                recording_id: recording_id.to_string(),
+                index,
+                total: slides.len(),
+            },
+        );
+        let speech = if slide.text.is_empty() {
+            None
+        } else {
+            let speech = work_dir.join(format!("speech_{}.wav", index));
+            tts::synthesize(&slide.text, options.voice.as_deref(), &speech)?;
+            Some(speech)
+        };
+        let spoken_secs = match &speech {
+            Some(speech) => tts::wav_seconds(speech)?,
+            None => 0.0,
+        };
+        let segment = work_dir.join(format!("slide_{}.mp4", index));
+        ffmpeg(&segment_args(
+            &slide.image,
+            speech.as_deref(),
+            slide_seconds(spoken_secs),
+            &segment,
+        ))?;
+        segments.push(segment);
+    }
+
+    let list = work_dir.join("slides.txt");
+    std::fs::write(&list, concat_list(&segments)).map_err(|e| e.to_string())?;
+    let mut args: Vec<OsString> = ["-y", "-f", "concat", "-safe", "0", "-i"]
+        .map(OsString::from)
+        .to_vec();
+    args.push(list.into());
+    args.extend(["-c", "copy", "-movflags", "+faststart"].map(OsString::from));
+    args.push(output.into());
+    ffmpeg(&args)
+}
+
+/// Export `recording_id` as a narrated MP4 at `output_path`
+#[tauri::command]
+pub async fn export_narrated_video(
+    app: AppHandle,
+    recording_id: String,
+    output_path: String,
+    options: Option<NarratedVideoOptions>,
+) -> Result<(), String> {
+    let options = options.unwrap_or_default();
+    let work_dir = std::env::temp_dir().join(format!("stepsnap-video-{}", uuid::Uuid::new_v4()));
+    let (task_app, task_recording, task_dir) =
+        (app.clone(), recording_id.clone(), work_dir.clone());
+    let result = tauri::async_runtime::spawn_blocking(move || {
+        export(
+            &task_app,
+            &task_recording,
+            Path::new(&output_path),
+            &options,
+            &task_dir,
+        )
+    })
+    .await
+    .map_err(|e| e.to_string())?;
+    let _ = std::fs::remove_dir_all(&work_dir);
+    result?;
+
+    analytics::track("narrated_video_export");
+    tracing::info!(recording_id = %recording_id, "Exported narrated video");
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn builds_slides_from_screenshot_steps() {
+        let step = |type_: &str, screenshot: Option<&str>, description: Option<&str>| Step {
+            type_: type_.to_string(),
+            screenshot_path: screenshot.map(str::to_string),
+            description: description.map(str::to_string),
+            ..Default::default()
+        };
+        let recording = RecordingWithSteps {
+            recording: Default::default(),
+            steps: vec![
+                step("note", None, Some("Read this first")),
+                step("click", Some("/shots/1.jpg"), Some("Click **Save**\n now.")),
+                step("capture", Some("/shots/2.jpg"), None),
+            ],
+        };
+
+        let slides = slides(&recording);
+        assert_eq!(slides.len(), 2);
+        assert_eq!(slides[0].image, PathBuf::from("/shots/1.jpg"));
+        assert_eq!(slides[0].text, "Click Save now.");
+        assert_eq!(slides[1].text, "");
+
+        assert_eq!(slide_seconds(0.0), MIN_SLIDE_SECS);
+        assert_eq!(slide_seconds(4.0), 4.75);
+    }
+
+    #[test]
+    fn quotes_paths_in_the_concat_list() {
+        let list = concat_list(&[PathBuf::from("/tmp/a.mp4"), PathBuf::from("/tmp/it's.mp4")]);
+        assert_eq!(list, "file '/tmp/a.mp4'\nfile '/tmp/it'\\''s.mp4'\n");
+    }
+}
@@ -0,0 +1,346 @@
+//! Proxy and TLS settings shared by every outbound request.
+//!
+//! Corporate networks usually only let traffic out through a proxy, often
+//! one that re-signs TLS with its own CA. Every integration (AI providers,
+//! OCR model downloads) builds its client from `client_builder`, which applies
+//! the proxy chosen in `NetworkSettings` and any extra root certificates on
+//! top of the OS trust store.
+//!
+//! `ProxyMode::System` follows the `HTTPS_PROXY`/`HTTP_PROXY`/`NO_PROXY`
+//! variables, then the Windows Internet Settings or the macOS network
+//! configuration. Proxy auto-config (PAC) scripts aren't evaluated; use a
+//! manual proxy on such networks.
+
+use serde::{Deserialize, Serialize};
+use std::sync::{Mutex, RwLock};
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ProxyMode {
+    /// The proxy the OS or environment is configured with, if any
+    #[default]
+    System,
+    /// Connect directly, ignoring any system proxy
+    Direct,
+    /// `NetworkSettings::proxy_url`
+    Manual,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct NetworkSettings {
+    pub proxy_mode: ProxyMode,
+    /// `http://[user:password@]host:port`, used with `ProxyMode::Manual`
+    pub proxy_url: String,
+    /// Comma-separated hosts, domains (`.corp.example`) and CIDR ranges that
+    /// bypass the manual proxy
+    pub no_proxy: String,
+    /// PEM file of extra root certificates, e.g. a TLS-inspecting proxy's CA
+    pub ca_certificates_path: Option<String>,
+}
+
+impl NetworkSettings {
+    /// Why these settings can't be used, if they can't
+    pub fn validate(&self) -> Result<(), String> {
+        if self.proxy_mode == ProxyMode::Manual {
+            let url = self.proxy_url.trim();
+            if url.is_empty() {
+                return Err("Enter a proxy URL or pick another proxy mode".to_string());
+            }
+            reqwest::Proxy::all(with_scheme(url))
+                .map_err(|e| format!("Invalid proxy URL: {}", e))?;
+        }
+        if let Some(path) = &self.ca_certificates_path {
+            if !std::path::Path::new(path).is_file() {
+                return Err(format!("Certificate file not found: {}", path));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// A proxy found in the environment or OS settings
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct SystemProxy {
+    pub url: String,
+    /// In `NO_PROXY` format
+    pub no_proxy: String,
+    /// "environment", "windows" or "macos"
+    pub source: &'static str,
+}
+
+const LOOPBACK_HOSTS: &[&str] = &["localhost", "127.0.0.1", "::1"];
+
+static NETWORK: RwLock<NetworkSettings> = RwLock::new(NetworkSettings {
+    proxy_mode: ProxyMode::System,
+    proxy_url: String::new(),
+    no_proxy: String::new(),
+    ca_certificates_path: None,
+});
+
+/// `detect_system_proxy`, remembered until `configure` or the next detection
+static DETECTED: Mutex<Option<Option<SystemProxy>>> = Mutex::new(None);
+
+/// Use `settings` for clients built from now on
+pub fn configure(settings: &NetworkSettings) {
+    *NETWORK.write().unwrap() = settings.clone();
+    *DETECTED.lock().unwrap() = None;
+}
+
+/// A client builder with the configured proxy and root certificates
+pub fn client_builder() -> Result<reqwest::ClientBuilder, String> {
+    let network = NETWORK.read().unwrap().clone();
+    // Proxies come from the settings only, not reqwest's own env lookup
+    let mut builder = reqwest::Client::builder().no_proxy();
+
+    let proxy = match network.proxy_mode {
+        ProxyMode::Direct => None,
+        ProxyMode::Manual => Some((network.proxy_url.trim().to_string(), network.no_proxy)),
+        ProxyMode::System => cached_system_proxy().map(|proxy| (proxy.url, proxy.no_proxy)),
+    };
+    if let Some((url, no_proxy)) = proxy.filter(|(url, _)| !url.is_empty()) {
+        // Local AI servers are never behind the proxy
+        let no_proxy = no_proxy
+            .split(',')
+            .chain(LOOPBACK_HOSTS.iter().copied())
+            .map(str::trim)
+            .filter(|host| !host.is_empty())
+            .collect::<Vec<_>>()
+            .join(",");
+        let proxy = reqwest::Proxy::all(with_scheme(&url))
+            .map_err(|e| format!("Invalid proxy URL: {}", e))?
+            .no_proxy(reqwest::NoProxy::from_string(&no_proxy));
+        builder = builder.proxy(proxy);
+    }
+
+    if let Some(path) = &network.ca_certificates_path {
+        let pem = std::fs::read(path)
+            .map_err(|e| format!("Failed to read certificates {}: {}", path, e))?;
+        let certificates = reqwest::Certificate::from_pem_bundle(&pem)
+            .map_err(|e| format!("Invalid certificates in {}: {}", path, e))?;
+        for certificate in certificates {
+            builder = builder.add_root_certificate(certificate);
+        }
+    }
+    Ok(builder)
+}
+
+fn cached_system_proxy() -> Option<SystemProxy> {
+    DETECTED
+        .lock()
+        .unwrap()
+        .get_or_insert_with(|| environment_proxy().or_else(platform_proxy))
+        .clone()
+}
+
+/// The proxy `ProxyMode::System` uses, looked up afresh
+pub fn detect_system_proxy() -> Option<SystemProxy> {
+    let detected = environment_proxy().or_else(platform_proxy);
+    *DETECTED.lock().unwrap() = Some(detected.clone());
+    detected
+}
+
+/// "host:port" as "http://host:port"; proxies are HTTP unless they say so
+fn with_scheme(url: &str) -> String {
+    if url.contains("://") {
+        url.to_string()
+    } else {
+        format!("http://{}", url)
+    }
+}
+
+fn first_env(names: &[&str]) -> Option<String> {
+    names
+        .iter()
+        .filter_map(|name| std::env::var(name).ok())
+        .map(|value| value.trim().to_string())
+        .find(|value| !value.is_empty())
+}
+
+fn environment_proxy() -> Option<SystemProxy> {
+    let url = first_env(&[
+        "HTTPS_PROXY",
+        "https_proxy",
+        "ALL_PROXY",
+        "all_proxy",
+        "HTTP_PROXY",
+        "http_proxy",
+    ])?;
+    Some(SystemProxy {
+        url: with_scheme(&url),
+        no_proxy: first_env(&["NO_PROXY", "no_proxy"]).unwrap_or_default(),
+        source: "environment",
+    })
+}
+
+#[cfg(target_os = "windows")]
+fn platform_proxy() -> Option<SystemProxy> {
+    use std::os::windows::process::CommandExt;
+    const CREATE_NO_WINDOW: u32 = 0x0800_0000;
+
+    let output = std::process::Command::new("reg")
+        .args([
+            "query",
+            r"HKCU\Software\Microsoft\Windows\CurrentVersion\Internet Settings",
+        ])
+        .creation_flags(CREATE_NO_WINDOW)
+        .output()
+        .ok()?;
+    parse_windows_internet_settings(&String::from_utf8_lossy(&output.stdout))
+}
+
+#[cfg(target_os = "macos")]
+fn platform_proxy() -> Option<SystemProxy> {
+    let output = std::process::Command::new("scutil")
+        .arg("--proxy")
+        .output()
+        .ok()?;
+    parse_scutil_proxy(&String::from_utf8_lossy(&output.stdout))
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+fn platform_proxy() -> Option<SystemProxy> {
+    None
+}
+
+/// The proxy in `reg query` output of the Internet Settings key, when
+/// `ProxyEnable` is set. `ProxyServer` is either `host:port` or per scheme,
+/// `http=host:port;https=host:port`.
+#[cfg_attr(not(any(target_os = "windows", test)), allow(dead_code))]
+fn parse_windows_internet_settings(output: &str) -> Option<SystemProxy> {
+    let value = |name: &str| {
+        output.lines().find_map(|line| {
+            let mut parts = line.split_whitespace();
+            (parts.next() == Some(name))
+                .then(|| parts.skip(1).collect::<Vec<_>>().join(" "))
+        })
+    };
+    if value("ProxyEnable")?.trim() != "0x1" {
+        return None;
+    }
+    let server = value("ProxyServer")?;
+    let url = if server.contains('=') {
+        let for_scheme = |scheme: &str| {
+            server.split(';').find_map(|entry| {
+                entry
+                    .trim()
+                    .strip_prefix(scheme)
+                    .and_then(|rest| rest.strip_prefix('='))
+                    .map(str::to_string)
+            })
+        };
+        for_scheme("https").or_else(|| for_scheme("http"))?
+    } else {
+        server
+    };
+    let no_proxy = value("ProxyOverride")
+        .unwrap_or_default()
+        .split(';')
+        .filter_map(bypass_entry)
+        .collect::<Vec<_>>()
+        .join(",");
+    Some(SystemProxy {
+        url: with_scheme(url.trim()),
+        no_proxy,
+        source: "windows",
+    })
+}
+
+/// An OS proxy exception in `NO_PROXY` form: `*.corp.example` becomes
+/// `.corp.example`. Other wildcards, and Windows' `<local>` ("hosts without a
+/// dot"), can't be expressed and are dropped.
+fn bypass_entry(host: &str) -> Option<String> {
+    let host = host.trim().trim_start_matches('*');
+    (!host.is_empty() && !host.contains(['*', '<'])).then(|| host.to_string())
+}
+
+/// The HTTPS (else HTTP) proxy in `scutil --proxy` output
+#[cfg_attr(not(any(target_os = "macos", test)), allow(dead_code))]
+fn parse_scutil_proxy(output: &str) -> Option<SystemProxy> {
+    let value = |name: &str| {
+        output.lines().find_map(|line| {
+            let (key, value) = line.split_once(" : ")?;
+            (key.trim() == name).then(|| value.trim().to_string())
+        })
+    };
+    let proxy = |scheme: &str| {
+        if value(&format!("{}Enable", scheme))? != "1" {
+            return None;
+        }
+        let host = value(&format!("{}Proxy", scheme))?;
+        let port = value(&format!("{}Port", scheme))?;
+        Some(format!("http://{}:{}", host, port))
+    };
+    let url = proxy("HTTPS").or_else(|| proxy("HTTP"))?;
+
+    // Exceptions are the numbered entries of the ExceptionsList array
+    let mut exceptions = Vec::new();
+    let mut in_exceptions = false;
+    for line in output.lines().map(str::trim) {
+        if line.starts_with("ExceptionsList") {
+            in_exceptions = true;
+        } else if in_exceptions && line == "}" {
+            break;
+        } else if in_exceptions {
+            exceptions.extend(line.split_once(" : ").and_then(|(_, host)| bypass_entry(host)));
+        }
+    }
+    Some(SystemProxy {
+        url,
+        no_proxy: exceptions.join(","),
+        source: "macos",
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_windows_internet_settings_prefers_the_https_proxy() {
+        let output = r"
+HKEY_CURRENT_USER\Software\Microsoft\Windows\CurrentVersion\Internet Settings
+    ProxyEnable    REG_DWORD    0x1
+    ProxyServer    REG_SZ    http=proxy.corp:8080;https=secure.corp:8443
+    ProxyOverride    REG_SZ    *.corp.example;10.*;<local>
+";
+        let proxy = parse_windows_internet_settings(output).unwrap();
+
+        assert_eq!(proxy.url, "http://secure.corp:8443");
+        assert_eq!(proxy.no_proxy, ".corp.example");
+        assert!(parse_windows_internet_settings(&output.replace("0x1", "0x0")).is_none());
+    }
+
+    #[test]
+    fn parse_scutil_proxy_reads_the_enabled_proxy_and_exceptions() {
+        let output = "<dictionary> {
+  ExceptionsList : <array> {
+    0 : *.local
+    1 : 169.254/16
+  }
+  HTTPEnable : 1
+  HTTPPort : 3128
+  HTTPProxy : proxy.corp
+  HTTPSEnable : 0
+}";
+        let proxy = parse_scutil_proxy(output).unwrap();
+
+        assert_eq!(proxy.url, "http://proxy.corp:3128");
+        assert_eq!(proxy.no_proxy, ".local,169.254/16");
+        let disabled = output.replace("HTTPEnable : 1", "HTTPEnable : 0");
+        assert!(parse_scutil_proxy(&disabled).is_none());
+    }
+
+    #[test]
+    fn validate_requires_a_usable_manual_proxy() {
+        let manual = |url: &str| NetworkSettings {
+            proxy_mode: ProxyMode::Manual,
+            proxy_url: url.to_string(),
+            ..Default::default()
+        };
+
+        assert!(manual("").validate().is_err());
+        assert!(manual("proxy.corp:8080").validate().is_ok());
+        assert!(NetworkSettings::default().validate().is_ok());
+    }
+}
@@ -2,21 +2,266 @@
 //!
 //! This module provides:
 //! - OcrEngine initialization and management
+//! - A registry of per-language recognition models
 //! - Image cropping around click points
 //! - Result aggregation
+//! - One engine shared by the OCR workers and the editor, loaded and warmed
+//!   up in the background
 
-use image::DynamicImage;
+use image::{DynamicImage, RgbaImage};
 use pure_onnx_ocr::{OcrEngine, OcrEngineBuilder};
+use std::collections::HashMap;
 use std::path::PathBuf;
-use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+use tauri::Emitter;
+
+/// Language setting that picks a recognition model per job
+pub const AUTO_LANGUAGE: &str = "auto";
+
+/// Mean confidence above which auto-detection keeps the first model's result
+const AUTO_DETECT_CONFIDENCE: f32 = 0.8;
+
+/// Lines read at least this confidently need no review
+const HIGH_CONFIDENCE: f32 = 0.9;
+
+/// Lines read less confidently than this are likely misread
+const MEDIUM_CONFIDENCE: f32 = 0.75;
+
+/// Bumped whenever model files are installed or replaced, or the OCR
+/// configuration changes, so the OCR threads know to rebuild their engines.
+static MODELS_GENERATION: AtomicU64 = AtomicU64::new(0);
+
+/// Signal that the installed model files or the OCR configuration changed
+pub fn models_changed() {
+    MODELS_GENERATION.fetch_add(1, Ordering::Relaxed);
+}
+
+/// Emitted when a newly loaded engine has been warmed up
+pub const READY_EVENT: &str = "ocr-ready";
+
+/// The manager the OCR workers and the editor share, so models are loaded
+/// and warmed up once rather than by each of them on its first job
+static SHARED: Mutex<Option<Arc<OcrManager>>> = Mutex::new(None);
+
+/// Payload of `READY_EVENT`
+#[derive(Clone, Debug, serde::Serialize)]
+pub struct OcrReady {
+    /// The backend that loaded
+    pub provider: &'static str,
+    pub warm_up_ms: u64,
+}
+
+/// A model or dictionary file and where the model manager can fetch it
+#[derive(Debug)]
+pub struct ModelFile {
+    pub name: &'static str,
+    /// Download source. Files without one must be installed by hand.
+    pub url: Option<&'static str>,
+    /// Expected SHA-256 (lowercase hex). When unset, downloads are recorded
+    /// with their computed hash and later verified against that.
+    pub sha256: Option<&'static str>,
+}
+
+/// Text detection model shared by every recognition model
+pub const DETECTION_MODEL: ModelFile = ModelFile {
+    name: "det.onnx",
+    url: Some(
+        "https://github.com/MeKo-Christian/paddleocr-onnx/releases/download/v1.0.0/PP-OCRv5_mobile_det.onnx",
+    ),
+    sha256: None,
+};
+
+/// A recognition model and its dictionary. All models share `DETECTION_MODEL`.
+#[derive(Debug, serde::Serialize)]
+pub struct RecognitionModel {
+    /// Identifier used by the `ocr_language` setting
+    pub id: &'static str,
+    pub label: &'static str,
+    /// BCP-47 tags handed to the platform OCR engines for this language
+    #[serde(skip)]
+    pub locales: &'static [&'static str],
+    #[serde(skip)]
+    pub model: ModelFile,
+    #[serde(skip)]
+    pub dictionary: ModelFile,
+}
+
+/// Known recognition models. The first entry is the default and is tried
+/// first when auto-detecting.
+pub const RECOGNITION_MODELS: &[RecognitionModel] = &[
+    RecognitionModel {
+        id: "chinese",
+        label: "Chinese & English",
+        locales: &["zh-Hans", "en-US"],
+        model: ModelFile {
+            name: "rec.onnx",
+            url: Some(
+                "https://github.com/MeKo-Christian/paddleocr-onnx/releases/download/v1.0.0/PP-OCRv5_mobile_rec.onnx",
+            ),
+            sha256: None,
+        },
+        dictionary: ModelFile {
+            name: "ppocr_keys_v1.txt",
+            url: Some(
+                "https://raw.githubusercontent.com/PaddlePaddle/PaddleOCR/main/ppocr/utils/ppocr_keys_v1.txt",
+            ),
+            sha256: None,
+        },
+    },
+    RecognitionModel {
+        id: "latin",
+        label: "Latin",
+        locales: &["en-US", "fr-FR", "de-DE", "es-ES", "it-IT", "pt-BR"],
+        model: ModelFile {
+            name: "rec_latin.onnx",
+            url: None,
+            sha256: None,
+        },
+        dictionary: ModelFile {
+            name: "latin_dict.txt",
+            url: Some(
+                "https://raw.githubusercontent.com/PaddlePaddle/PaddleOCR/main/ppocr/utils/dict/latin_dict.txt",
+            ),
+            sha256: None,
+        },
+    },
+    RecognitionModel {
+        id: "japanese",
+        label: "Japanese",
+        locales: &["ja-JP"],
+        model: ModelFile {
+            name: "rec_japan.onnx",
+            url: None,
+            sha256: None,
+        },
+        dictionary: ModelFile {
+            name: "japan_dict.txt",
+            url: Some(
+                "https://raw.githubusercontent.com/PaddlePaddle/PaddleOCR/main/ppocr/utils/dict/japan_dict.txt",
+            ),
+            sha256: None,
+        },
+    },
+    RecognitionModel {
+        id: "cyrillic",
+        label: "Cyrillic",
+        locales: &["ru-RU", "uk-UA"],
+        model: ModelFile {
+            name: "rec_cyrillic.onnx",
+            url: None,
+            sha256: None,
+        },
+        dictionary: ModelFile {
+            name: "cyrillic_dict.txt",
+            url: Some(
+                "https://raw.githubusercontent.com/PaddlePaddle/PaddleOCR/main/ppocr/utils/dict/cyrillic_dict.txt",
+            ),
+            sha256: None,
+        },
+    },
+];
+
+impl RecognitionModel {
+    /// Every file this model needs, including the shared detection model
+    pub fn files(&self) -> [&ModelFile; 3] {
+        [&DETECTION_MODEL, &self.model, &self.dictionary]
+    }
+
+    /// Whether both the model and its dictionary are present in `models_dirs`
+    pub fn is_installed(&self, models_dirs: &[PathBuf]) -> bool {
+        find_model_file(models_dirs, self.model.name).is_some()
+            && find_model_file(models_dirs, self.dictionary.name).is_some()
+    }
+
+    /// Whether the model manager can fetch every file this model needs
+    pub fn is_downloadable(&self) -> bool {
+        self.files().iter().all(|file| file.url.is_some())
+    }
+}
+
+/// First directory in `models_dirs` that contains `name`
+pub fn find_model_file(models_dirs: &[PathBuf], name: &str) -> Option<PathBuf> {
+    models_dirs
+        .iter()
+        .map(|dir| dir.join(name))
+        .find(|path| path.exists())
+}
+
+/// Registry entry as reported to the settings UI
+#[derive(Debug, serde::Serialize)]
+pub struct OcrLanguageInfo {
+    #[serde(flatten)]
+    pub model: &'static RecognitionModel,
+    pub installed: bool,
+    pub downloadable: bool,
+}
+
+/// List every known recognition model with its install state
+pub fn list_languages(models_dirs: &[PathBuf]) -> Vec<OcrLanguageInfo> {
+    RECOGNITION_MODELS
+        .iter()
+        .map(|model| OcrLanguageInfo {
+            model,
+            installed: model.is_installed(models_dirs),
+            downloadable: model.is_downloadable(),
+        })
+        .collect()
+}
+
+/// Recognition models the ONNX provider runs at once by default
+pub const DEFAULT_PARALLEL_MODELS: usize = 2;
+
+/// Timed runs per provider in `OcrManager::benchmark`, after one warm-up
+const BENCHMARK_RUNS: usize = 3;
+
+/// Engines `OcrManager` can run text recognition on
+#[derive(Clone, Copy, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum OcrProvider {
+    /// Bundled or downloaded PaddleOCR models on the CPU
+    Onnx,
+    /// Windows.Media.Ocr (Windows 10+)
+    Windows,
+    /// Apple's Vision framework (macOS)
+    Vision,
+}
+
+impl OcrProvider {
+    /// Providers this build can run, in the order auto-selection tries them
+    pub fn available() -> Vec<Self> {
+        let mut providers = vec![Self::Onnx];
+        if cfg!(target_os = "windows") {
+            providers.push(Self::Windows);
+        }
+        if cfg!(target_os = "macos") {
+            providers.push(Self::Vision);
+        }
+        providers
+    }
+}
 
 /// OCR configuration
-#[derive(Clone)]
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
 pub struct OcrConfig {
     /// Radius around click point for cropping (default: 300)
     pub crop_radius: u32,
     /// Minimum confidence threshold (default: 0.5)
     pub min_confidence: f32,
+    /// Engine to use. `None` uses the ONNX models when they're installed and
+    /// the platform engine otherwise.
+    #[serde(default)]
+    pub provider: Option<OcrProvider>,
+    /// Recognition models the ONNX provider runs at once while
+    /// auto-detecting the language, each on its own thread (default: 2).
+    /// A single-language job runs one model, whatever this is.
+    #[serde(default = "default_parallel_models")]
+    pub parallel_models: usize,
+}
+
+fn default_parallel_models() -> usize {
+    DEFAULT_PARALLEL_MODELS
 }
 
 impl Default for OcrConfig {
@@ -24,18 +269,91 @@ impl Default for OcrConfig {
         Self {
             crop_radius: 300,
             min_confidence: 0.5,
+            provider: None,
+            parallel_models: DEFAULT_PARALLEL_MODELS,
         }
     }
 }
 
+/// Timing of one provider in an `OcrBenchmark`
+#[derive(Debug, serde::Serialize)]
+pub struct ProviderBenchmark {
+    pub provider: OcrProvider,
+    /// Best time for one job, or `None` when the provider failed
+    pub millis: Option<u64>,
+    pub error: Option<String>,
+}
+
+/// Result of `OcrManager::benchmark`
+#[derive(Debug, serde::Serialize)]
+pub struct OcrBenchmark {
+    pub results: Vec<ProviderBenchmark>,
+    /// Provider with the lowest time, if any ran
+    pub fastest: Option<OcrProvider>,
+}
+
+/// The provider with the lowest time in `results`
+fn fastest_provider(results: &[ProviderBenchmark]) -> Option<OcrProvider> {
+    results
+        .iter()
+        .filter_map(|result| Some((result.millis?, result.provider)))
+        .min_by_key(|(millis, _)| *millis)
+        .map(|(_, provider)| provider)
+}
+
 /// Data sent to OCR thread for processing
 #[derive(Clone)]
 pub struct OcrJob {
     pub step_id: String,
-    pub image: Arc<DynamicImage>,
+    /// The captured frame, shared with the recorder's encoder
+    pub image: Arc<RgbaImage>,
     pub x: Option<i32>,
     pub y: Option<i32>,
     pub step_type: String,
+    /// Recognition model id, or `AUTO_LANGUAGE`
+    pub language: String,
+}
+
+/// How sure the engine was of a line, coarse enough to style text by
+#[derive(Clone, Copy, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ConfidenceBucket {
+    High,
+    Medium,
+    Low,
+}
+
+impl ConfidenceBucket {
+    pub fn from_confidence(confidence: f32) -> Self {
+        if confidence >= HIGH_CONFIDENCE {
+            Self::High
+        } else if confidence >= MEDIUM_CONFIDENCE {
+            Self::Medium
+        } else {
+            Self::Low
+        }
+    }
+}
+
+/// One recognised line of text
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct OcrLine {
+    pub text: String,
+    pub confidence: ConfidenceBucket,
+}
+
+/// The lines' text, one per line, or `None` when nothing was read
+pub fn join_lines(lines: &[OcrLine]) -> Option<String> {
+    let text = lines
+        .iter()
+        .map(|line| line.text.as_str())
+        .collect::<Vec<_>>()
+        .join("\n");
+    if text.is_empty() {
+        None
+    } else {
+        Some(text)
+    }
 }
 
 /// Result from OCR processing
@@ -43,62 +361,502 @@ pub struct OcrJob {
 pub struct OcrJobResult {
     pub step_id: String,
     pub ocr_text: Option<String>,
+    /// `ocr_text` line by line with confidence, when it was read by OCR
+    pub ocr_lines: Option<Vec<OcrLine>>,
     pub status: String,
 }
 
-/// Manages OCR engine lifecycle and provides processing functions
-pub struct OcrManager {
-    engine: Option<Arc<OcrEngine>>,
-    config: OcrConfig,
+/// A text recognizer. `OcrManager` uses the bundled ONNX models when they're
+/// installed and falls back to the OS engine otherwise.
+pub trait OcrBackend: Send + Sync {
+    /// Short name for logs
+    fn name(&self) -> &'static str;
+
+    /// Read `image`, returning (text, confidence) per line. `language` is a
+    /// registry id or `AUTO_LANGUAGE`.
+    fn recognize(&self, image: &DynamicImage, language: &str)
+        -> Result<Vec<(String, f32)>, String>;
 }
 
-impl OcrManager {
-    /// Create a new OCR manager, loading models from bundled resources
-    pub fn new(models_dir: PathBuf, config: OcrConfig) -> Result<Self, String> {
-        let det_model = models_dir.join("det.onnx");
-        let rec_model = models_dir.join("rec.onnx");
-        let dictionary = models_dir.join("ppocr_keys_v1.txt");
+/// PaddleOCR models run in-process by pure-onnx-ocr
+struct OnnxBackend {
+    /// Directories searched for model files, in priority order
+    models_dirs: Vec<PathBuf>,
+    /// Installed recognition models, in registry order
+    models: Vec<&'static RecognitionModel>,
+    /// Engines built so far, keyed by model id. Built lazily on first use.
+    engines: Mutex<HashMap<&'static str, Arc<OcrEngine>>>,
+    /// Recognition models run at once by `recognize_auto`
+    parallel_models: usize,
+}
 
+impl OnnxBackend {
+    /// Load models from the first of `models_dirs` that has each file
+    fn new(models_dirs: Vec<PathBuf>, parallel_models: usize) -> Result<Self, String> {
         // Validate model files exist
-        if !det_model.exists() {
-            return Err(format!("Detection model not found: {:?}", det_model));
+        if find_model_file(&models_dirs, DETECTION_MODEL.name).is_none() {
+            return Err(format!("Detection model not found in {:?}", models_dirs));
         }
-        if !rec_model.exists() {
-            return Err(format!("Recognition model not found: {:?}", rec_model));
+        let models: Vec<&'static RecognitionModel> = RECOGNITION_MODELS
+            .iter()
+            .filter(|model| model.is_installed(&models_dirs))
+            .collect();
+        if models.is_empty() {
+            return Err(format!("No recognition model found in {:?}", models_dirs));
         }
-        if !dictionary.exists() {
-            return Err(format!("Dictionary not found: {:?}", dictionary));
+
+        let backend = Self {
+            models_dirs,
+            models,
+            engines: Mutex::new(HashMap::new()),
+            parallel_models: parallel_models.max(1),
+        };
+
+        // Build the default engine up front so load failures surface at startup
+        backend.engine(backend.models[0])?;
+
+        Ok(backend)
+    }
+
+    /// Get the engine for a recognition model, building it on first use
+    fn engine(&self, model: &'static RecognitionModel) -> Result<Arc<OcrEngine>, String> {
+        let mut engines = self.engines.lock().unwrap();
+        if let Some(engine) = engines.get(model.id) {
+            return Ok(engine.clone());
         }
 
-        // Initialize the OCR engine
+        let locate = |file: &ModelFile| {
+            find_model_file(&self.models_dirs, file.name)
+                .ok_or_else(|| format!("OCR model file not found: {}", file.name))
+        };
+        let det_model = locate(&DETECTION_MODEL)?;
+        let rec_model = locate(&model.model)?;
+        let dictionary = locate(&model.dictionary)?;
         let engine = OcrEngineBuilder::new()
             .det_model_path(det_model.to_string_lossy().to_string())
             .rec_model_path(rec_model.to_string_lossy().to_string())
             .dictionary_path(dictionary.to_string_lossy().to_string())
             .build()
-            .map_err(|e| format!("Failed to build OCR engine: {:?}", e))?;
+            .map_err(|e| format!("Failed to build {} OCR engine: {:?}", model.id, e))?;
+        let engine = Arc::new(engine);
+        engines.insert(model.id, engine.clone());
+        Ok(engine)
+    }
+
+    /// Run one recognition model, returning (text, confidence) per detected line
+    fn recognize_with(
+        &self,
+        model: &'static RecognitionModel,
+        image: &DynamicImage,
+    ) -> Result<Vec<(String, f32)>, String> {
+        let engine = self.engine(model)?;
+        let results = engine
+            .run_from_image(image)
+            .map_err(|e| format!("{:?}", e))?;
+        Ok(results
+            .into_iter()
+            .map(|r| (r.text, r.confidence))
+            .collect())
+    }
+
+    /// Try installed models in registry order and keep the most confident
+    /// result. Stops at the first model that reads the text confidently.
+    /// Up to `parallel_models` models run at once; the result is the same as running
+    /// them one by one.
+    fn recognize_auto(&self, image: &DynamicImage) -> Result<Vec<(String, f32)>, String> {
+        let mut best: Option<(f32, Vec<(String, f32)>)> = None;
+        for batch in self.models.chunks(self.parallel_models) {
+            let results: Vec<Result<Vec<(String, f32)>, String>> = match batch {
+                &[model] => vec![self.recognize_with(model, image)],
+                _ => std::thread::scope(|scope| {
+                    let handles: Vec<_> = batch
+                        .iter()
+                        .map(|&model| scope.spawn(move || self.recognize_with(model, image)))
+                        .collect();
+                    handles
+                        .into_iter()
+                        .map(|handle| {
+                            handle
+                                .join()
+                                .unwrap_or_else(|_| Err("OCR thread panicked".to_string()))
+                        })
+                        .collect()
+                }),
+            };
+
+            for lines in results {
+                let lines = lines?;
+                // Detection is shared, so no lines means no model will find any
+                if lines.is_empty() {
+                    return Ok(lines);
+                }
+                let score = lines.iter().map(|(_, c)| c).sum::<f32>() / lines.len() as f32;
+                if score >= AUTO_DETECT_CONFIDENCE {
+                    return Ok(lines);
+                }
+                let better = match &best {
+                    Some((best_score, _)) => score > *best_score,
+                    None => true,
+                };
+                if better {
+                    best = Some((score, lines));
+                }
+            }
+        }
+        Ok(best.map(|(_, lines)| lines).unwrap_or_default())
+    }
+}
+
+impl OcrBackend for OnnxBackend {
+    fn name(&self) -> &'static str {
+        "onnx"
+    }
+
+    /// Use the model for `language`, falling back to auto-detection when the
+    /// language is "auto" or its model isn't installed
+    fn recognize(
+        &self,
+        image: &DynamicImage,
+        language: &str,
+    ) -> Result<Vec<(String, f32)>, String> {
+        let model = self
+            .models
+            .iter()
+            .copied()
+            .find(|model| model.id == language);
+        match model {
+            Some(model) => self.recognize_with(model, image),
+            None => self.recognize_auto(image),
+        }
+    }
+}
+
+/// Platform tags for a registry language, or none to let the engine decide
+fn language_locales(language: &str) -> &'static [&'static str] {
+    RECOGNITION_MODELS
+        .iter()
+        .find(|model| model.id == language)
+        .map_or(&[], |model| model.locales)
+}
+
+/// Windows.Media.Ocr, available on Windows 10 and later for every language
+/// pack the user has installed
+#[cfg(target_os = "windows")]
+mod windows_backend {
+    use super::{language_locales, OcrBackend};
+    use image::DynamicImage;
+    use windows::core::HSTRING;
+    use windows::Globalization::Language;
+    use windows::Graphics::Imaging::{BitmapPixelFormat, SoftwareBitmap};
+    use windows::Media::Ocr::OcrEngine;
+    use windows::Storage::Streams::DataWriter;
+
+    pub struct WindowsOcrBackend;
+
+    impl WindowsOcrBackend {
+        pub fn new() -> Result<Self, String> {
+            // Fails when the user has no OCR-capable language installed
+            OcrEngine::TryCreateFromUserProfileLanguages().map_err(|e| e.to_string())?;
+            Ok(Self)
+        }
+
+        /// Engine for the first installed locale of `language`, else the
+        /// user's profile languages
+        fn engine(language: &str) -> windows::core::Result<OcrEngine> {
+            for tag in language_locales(language) {
+                let language = Language::CreateLanguage(&HSTRING::from(*tag))?;
+                if OcrEngine::IsLanguageSupported(&language)? {
+                    return OcrEngine::TryCreateFromLanguage(&language);
+                }
+            }
+            OcrEngine::TryCreateFromUserProfileLanguages()
+        }
+    }
+
+    impl OcrBackend for WindowsOcrBackend {
+        fn name(&self) -> &'static str {
+            "windows"
+        }
+
+        fn recognize(
+            &self,
+            image: &DynamicImage,
+            language: &str,
+        ) -> Result<Vec<(String, f32)>, String> {
+            let read = || -> windows::core::Result<Vec<(String, f32)>> {
+                let engine = Self::engine(language)?;
+
+                // OcrEngine rejects images larger than MaxImageDimension
+                let max = OcrEngine::MaxImageDimension()?;
+                let image = if image.width() > max || image.height() > max {
+                    image.resize(max, max, image::imageops::FilterType::Triangle)
+                } else {
+                    image.clone()
+                };
+
+                let mut bgra = image.to_rgba8();
+                for pixel in bgra.pixels_mut() {
+                    pixel.0.swap(0, 2);
+                }
+                let writer = DataWriter::new()?;
+                writer.WriteBytes(bgra.as_raw())?;
+                let bitmap = SoftwareBitmap::CreateCopyFromBuffer(
+                    &writer.DetachBuffer()?,
+                    BitmapPixelFormat::Bgra8,
+                    bgra.width() as i32,
+                    bgra.height() as i32,
+                )?;
+
+                let result = engine.RecognizeAsync(&bitmap)?.get()?;
+                let mut lines = Vec::new();
+                for line in result.Lines()? {
+                    // Windows OCR doesn't report confidence, so its lines
+                    // are never flagged for review
+                    lines.push((line.Text()?.to_string(), 1.0));
+                }
+                Ok(lines)
+            };
+            read().map_err(|e| e.to_string())
+        }
+    }
+}
+
+/// Apple's Vision framework (VNRecognizeTextRequest)
+#[cfg(target_os = "macos")]
+mod vision_backend {
+    use super::{language_locales, OcrBackend, AUTO_LANGUAGE};
+    use image::DynamicImage;
+    use objc2::rc::Retained;
+    use objc2::runtime::AnyObject;
+    use objc2::AllocAnyThread;
+    use objc2_foundation::{NSArray, NSData, NSDictionary, NSString};
+    use objc2_vision::{
+        VNImageOption, VNImageRequestHandler, VNRecognizeTextRequest, VNRequest,
+        VNRequestTextRecognitionLevel,
+    };
+    use std::io::Cursor;
+
+    pub struct VisionOcrBackend;
+
+    impl OcrBackend for VisionOcrBackend {
+        fn name(&self) -> &'static str {
+            "vision"
+        }
+
+        fn recognize(
+            &self,
+            image: &DynamicImage,
+            language: &str,
+        ) -> Result<Vec<(String, f32)>, String> {
+            // Hand Vision an encoded PNG so no CGImage plumbing is needed
+            let mut png = Vec::new();
+            image
+                .write_to(&mut Cursor::new(&mut png), image::ImageFormat::Png)
+                .map_err(|e| e.to_string())?;
+
+            unsafe {
+                let data = NSData::with_bytes(&png);
+                let options = NSDictionary::<VNImageOption, AnyObject>::new();
+                let handler = VNImageRequestHandler::initWithData_options(
+                    VNImageRequestHandler::alloc(),
+                    &data,
+                    &options,
+                );
+
+                let request = VNRecognizeTextRequest::new();
+                request.setRecognitionLevel(VNRequestTextRecognitionLevel::Accurate);
+                request.setUsesLanguageCorrection(true);
+                if language == AUTO_LANGUAGE {
+                    request.setAutomaticallyDetectsLanguage(true);
+                } else {
+                    let locales: Vec<Retained<NSString>> = language_locales(language)
+                        .iter()
+                        .map(|tag| NSString::from_str(tag))
+                        .collect();
+                    request.setRecognitionLanguages(&NSArray::from_retained_slice(&locales));
+                }
+
+                let generic: Retained<VNRequest> =
+                    Retained::into_super(Retained::into_super(request.clone()));
+                handler
+                    .performRequests_error(&NSArray::from_retained_slice(&[generic]))
+                    .map_err(|e| e.localizedDescription().to_string())?;
+
+                let mut lines = Vec::new();
+                if let Some(observations) = request.results() {
+                    for observation in observations.iter() {
+                        if let Some(candidate) = observation.topCandidates(1).firstObject() {
+                            lines.push((candidate.string().to_string(), candidate.confidence()));
+                        }
+                    }
+                }
+                Ok(lines)
+            }
+        }
+    }
+}
+
+/// Build the backend for `provider`
+fn build_backend(
+    provider: OcrProvider,
+    models_dirs: &[PathBuf],
+    config: &OcrConfig,
+) -> Result<Box<dyn OcrBackend>, String> {
+    match provider {
+        OcrProvider::Onnx => Ok(Box::new(OnnxBackend::new(
+            models_dirs.to_vec(),
+            config.parallel_models,
+        )?)),
+        #[cfg(target_os = "windows")]
+        OcrProvider::Windows => Ok(Box::new(windows_backend::WindowsOcrBackend::new()?)),
+        #[cfg(target_os = "macos")]
+        OcrProvider::Vision => Ok(Box::new(vision_backend::VisionOcrBackend)),
+        other => Err(format!(
+            "The {:?} OCR provider isn't available on this platform",
+            other
+        )),
+    }
+}
+
+/// The first available provider that loads: the ONNX models when they're
+/// installed, otherwise the OS text recognizer. Fails with the ONNX error
+/// when nothing loads.
+fn auto_backend(
+    models_dirs: &[PathBuf],
+    config: &OcrConfig,
+) -> Result<Box<dyn OcrBackend>, String> {
+    let mut first_error = None;
+    for provider in OcrProvider::available() {
+        match build_backend(provider, models_dirs, config) {
+            Ok(backend) => return Ok(backend),
+            Err(e) => {
+                if first_error.is_some() {
+                    tracing::warn!("{:?} OCR unavailable: {}", provider, e);
+                }
+                first_error.get_or_insert(e);
+            }
+        }
+    }
+    Err(first_error.unwrap_or_else(|| "No OCR provider available".to_string()))
+}
+
+/// Manages OCR engine lifecycle and provides processing functions
+pub struct OcrManager {
+    backend: Option<Box<dyn OcrBackend>>,
+    config: OcrConfig,
+    /// `MODELS_GENERATION` when this manager scanned for models
+    generation: u64,
+}
+
+impl OcrManager {
+    /// Create a new OCR manager on `config.provider`. Without one, ONNX
+    /// models are loaded from the first of `models_dirs` that has each file,
+    /// falling back to the platform engine when they aren't installed.
+    pub fn new(models_dirs: Vec<PathBuf>, config: OcrConfig) -> Result<Self, String> {
+        let generation = MODELS_GENERATION.load(Ordering::Relaxed);
+
+        let backend = match config.provider {
+            Some(provider) => build_backend(provider, &models_dirs, &config)?,
+            None => auto_backend(&models_dirs, &config)?,
+        };
+        tracing::info!("Using {} OCR backend", backend.name());
 
         Ok(Self {
-            engine: Some(Arc::new(engine)),
+            backend: Some(backend),
             config,
+            generation,
         })
     }
 
+    /// Name of the backend in use, or None when disabled
+    pub fn provider(&self) -> Option<&'static str> {
+        self.backend.as_ref().map(|backend| backend.name())
+    }
+
     /// Create a disabled OCR manager (when models not available)
     pub fn disabled() -> Self {
         Self {
-            engine: None,
+            backend: None,
             config: OcrConfig::default(),
+            generation: MODELS_GENERATION.load(Ordering::Relaxed),
         }
     }
 
     /// Check if OCR is enabled and ready
     pub fn is_enabled(&self) -> bool {
-        self.engine.is_some()
+        self.backend.is_some()
+    }
+
+    /// Whether model files were installed or replaced since this manager
+    /// was created
+    pub fn is_stale(&self) -> bool {
+        self.generation != MODELS_GENERATION.load(Ordering::Relaxed)
+    }
+
+    /// Time every available provider on `image`, cropped like a click in its
+    /// centre. Loading the engine and one warm-up run aren't counted.
+    pub fn benchmark(
+        models_dirs: &[PathBuf],
+        config: &OcrConfig,
+        image: &RgbaImage,
+        language: &str,
+    ) -> OcrBenchmark {
+        let results: Vec<ProviderBenchmark> = OcrProvider::available()
+            .into_iter()
+            .map(|provider| {
+                let config = OcrConfig {
+                    provider: Some(provider),
+                    ..config.clone()
+                };
+                let timed = OcrManager::new(models_dirs.to_vec(), config).and_then(|manager| {
+                    let crop = manager.crop_around_point(
+                        image,
+                        image.width() as i32 / 2,
+                        image.height() as i32 / 2,
+                    );
+                    manager.extract_text(&crop, language)?;
+                    let mut best = u64::MAX;
+                    for _ in 0..BENCHMARK_RUNS {
+                        let started = std::time::Instant::now();
+                        manager.extract_text(&crop, language)?;
+                        best = best.min(started.elapsed().as_millis() as u64);
+                    }
+                    Ok(best)
+                });
+                match timed {
+                    Ok(millis) => ProviderBenchmark {
+                        provider,
+                        millis: Some(millis),
+                        error: None,
+                    },
+                    Err(e) => ProviderBenchmark {
+                        provider,
+                        millis: None,
+                        error: Some(e),
+                    },
+                }
+            })
+            .collect();
+        OcrBenchmark {
+            fastest: fastest_provider(&results),
+            results,
+        }
+    }
+
+    /// Read a synthetic line of text once, so the engine for `language` is
+    /// built and its sessions have allocated their buffers before the first
+    /// real job
+    pub fn warm_up(&self, language: &str) {
+        let Some(backend) = &self.backend else {
+            return;
+        };
+        if let Err(e) = backend.recognize(&warm_up_image(), language) {
+            tracing::warn!(target: "ocr", "OCR warm-up failed: {}", e);
+        }
     }
 
     /// Crop image around click point
-    pub fn crop_around_point(&self, image: &DynamicImage, x: i32, y: i32) -> DynamicImage {
+    pub fn crop_around_point(&self, image: &RgbaImage, x: i32, y: i32) -> DynamicImage {
         let radius = self.config.crop_radius as i32;
         let (width, height) = (image.width() as i32, image.height() as i32);
 
@@ -110,68 +868,140 @@ impl OcrManager {
         let crop_width = end_x - start_x;
         let crop_height = end_y - start_y;
 
-        image.crop_imm(start_x, start_y, crop_width, crop_height)
+        DynamicImage::ImageRgba8(
+            image::imageops::crop_imm(image, start_x, start_y, crop_width, crop_height).to_image(),
+        )
     }
 
     /// Process a single OCR job
-    pub fn process_job(&self, job: &OcrJob) -> OcrJobResult {
-        let Some(engine) = &self.engine else {
+    pub fn process_job(&self, job: OcrJob) -> OcrJobResult {
+        if !self.is_enabled() {
             return OcrJobResult {
                 step_id: job.step_id.clone(),
                 ocr_text: None,
+                ocr_lines: None,
                 status: "failed".to_string(),
             };
-        };
+        }
 
         // Crop image for click steps
-        // Use Cow to avoid cloning the full image when not cropping
-        let image_binding = job.image.clone();
-        let image_to_process: std::borrow::Cow<DynamicImage> = if job.step_type == "click" {
-            if let (Some(x), Some(y)) = (job.x, job.y) {
-                std::borrow::Cow::Owned(self.crop_around_point(&image_binding, x, y))
-            } else {
-                std::borrow::Cow::Borrowed(&image_binding)
-            }
-        } else {
-            // For type/capture steps, use full image
-            std::borrow::Cow::Borrowed(&image_binding)
+        let image_to_process = match (job.step_type.as_str(), job.x, job.y) {
+            ("click", Some(x), Some(y)) => self.crop_around_point(&job.image, x, y),
+            // For type/capture steps, use the full image. The encoder has
+            // usually dropped its share by now, so this moves the frame
+            // rather than copying it.
+            _ => DynamicImage::ImageRgba8(Arc::unwrap_or_clone(job.image)),
         };
 
-        // Run OCR - pass the DynamicImage directly
-        match engine.run_from_image(&image_to_process) {
-            Ok(results) => {
-                if results.is_empty() {
-                    OcrJobResult {
-                        step_id: job.step_id.clone(),
-                        ocr_text: None,
-                        status: "completed".to_string(),
-                    }
-                } else {
-                    // Aggregate all detected text, filtering by confidence
-                    let text: String = results
-                        .iter()
-                        .filter(|r| r.confidence >= self.config.min_confidence)
-                        .map(|r| r.text.as_str())
-                        .collect::<Vec<_>>()
-                        .join("\n");
-
-                    OcrJobResult {
-                        step_id: job.step_id.clone(),
-                        ocr_text: if text.is_empty() { None } else { Some(text) },
-                        status: "completed".to_string(),
-                    }
-                }
-            }
+        match self.extract_lines(&image_to_process, &job.language) {
+            Ok(lines) => OcrJobResult {
+                step_id: job.step_id.clone(),
+                ocr_text: join_lines(&lines),
+                ocr_lines: Some(lines),
+                status: "completed".to_string(),
+            },
             Err(e) => {
-                eprintln!("OCR error for step {}: {:?}", job.step_id, e);
+                tracing::warn!(step_id = %job.step_id, "OCR failed: {}", e);
                 OcrJobResult {
                     step_id: job.step_id.clone(),
                     ocr_text: None,
+                    ocr_lines: None,
                     status: "failed".to_string(),
                 }
             }
         }
     }
+
+    /// Read the lines of text in `image` for `language` ("auto" lets the
+    /// backend decide). Lines below `min_confidence` are dropped.
+    pub fn extract_lines(
+        &self,
+        image: &DynamicImage,
+        language: &str,
+    ) -> Result<Vec<OcrLine>, String> {
+        let Some(backend) = &self.backend else {
+            return Err("OCR is unavailable".to_string());
+        };
+        let results = backend.recognize(image, language)?;
+
+        Ok(results
+            .into_iter()
+            .filter(|(text, confidence)| {
+                !text.is_empty() && *confidence >= self.config.min_confidence
+            })
+            .map(|(text, confidence)| OcrLine {
+                text,
+                confidence: ConfidenceBucket::from_confidence(confidence),
+            })
+            .collect())
+    }
+
+    /// `extract_lines` joined into one string, or `None` when nothing was read
+    pub fn extract_text(
+        &self,
+        image: &DynamicImage,
+        language: &str,
+    ) -> Result<Option<String>, String> {
+        self.extract_lines(image, language)
+            .map(|lines| join_lines(&lines))
+    }
+}
+
+/// Dark blocks on white, spaced like a line of words so detection hands
+/// something to recognition too
+fn warm_up_image() -> DynamicImage {
+    let mut image = image::GrayImage::from_pixel(320, 48, image::Luma([255]));
+    for word in 0..5 {
+        let left = 12 + word * 62;
+        for x in (left..left + 46).filter(|x| (x - left) % 7 < 5) {
+            for y in 16..32 {
+                image.put_pixel(x, y, image::Luma([20]));
+            }
+        }
+    }
+    DynamicImage::ImageLuma8(image)
+}
+
+/// The shared OCR manager, first loading and warming up a new one when none
+/// is loaded or the models or configuration changed since. When nothing
+/// loads, a disabled manager is kept until the next change. Blocks while
+/// loading, so callers arriving meanwhile wait for the same engine.
+pub fn shared_manager(
+    app: &tauri::AppHandle,
+    config: &OcrConfig,
+    language: &str,
+) -> Arc<OcrManager> {
+    let mut shared = SHARED.lock().unwrap();
+    if let Some(manager) = shared.as_ref().filter(|manager| !manager.is_stale()) {
+        return manager.clone();
+    }
+
+    let models_dirs = get_models_dirs(app);
+    let manager = match OcrManager::new(models_dirs.clone(), config.clone()) {
+        Ok(manager) => {
+            let started = Instant::now();
+            manager.warm_up(language);
+            let ready = OcrReady {
+                provider: manager.provider().unwrap_or_default(),
+                warm_up_ms: started.elapsed().as_millis() as u64,
+            };
+            tracing::info!(
+                target: "ocr",
+                "OCR engine loaded from {:?}, warmed up in {}ms",
+                models_dirs,
+                ready.warm_up_ms
+            );
+            let _ = app.emit(READY_EVENT, ready);
+            manager
+        }
+        Err(e) => {
+            tracing::error!(target: "ocr", "Failed to load OCR engine: {}", e);
+            OcrManager::disabled()
+        }
+    };
+    let manager = Arc::new(manager);
+    *shared = Some(manager.clone());
+    manager
 }
 
 /// Get the OCR models directory path
@@ -187,7 +1017,7 @@ pub fn get_models_dir(app_handle: &tauri::AppHandle) -> PathBuf {
         let dev_path = PathBuf::from(manifest_dir)
             .join("resources")
             .join("ocr_models");
-        println!(
+        tracing::debug!(
             "OCR dev path: {:?} (exists: {})",
             dev_path,
             dev_path.exists()
@@ -207,7 +1037,7 @@ pub fn get_models_dir(app_handle: &tauri::AppHandle) -> PathBuf {
 
         for candidate in &candidates {
             if candidate.exists() {
-                println!("Found OCR models at: {:?}", candidate);
+                tracing::info!("Found OCR models at: {:?}", candidate);
                 return candidate.clone();
             }
         }
@@ -237,7 +1067,7 @@ pub fn get_models_dir(app_handle: &tauri::AppHandle) -> PathBuf {
 
                     if let Some(path) = resolved {
                         if path.exists() {
-                            println!("Found OCR models at: {:?}", path);
+                            tracing::info!("Found OCR models at: {:?}", path);
                             return path;
                         }
                     }
@@ -247,6 +1077,17 @@ pub fn get_models_dir(app_handle: &tauri::AppHandle) -> PathBuf {
     }
 
     // Last resort
-    println!("OCR models not found in any expected location");
+    tracing::warn!("OCR models not found in any expected location");
     PathBuf::from("resources").join("ocr_models")
 }
+
+/// Directories searched for OCR models: downloaded models first, so updates
+/// take precedence over the copies bundled with the app
+pub fn get_models_dirs(app_handle: &tauri::AppHandle) -> Vec<PathBuf> {
+    let mut dirs = Vec::new();
+    if let Ok(downloads) = crate::ocr_models::downloads_dir(app_handle) {
+        dirs.push(downloads);
+    }
+    dirs.push(get_models_dir(app_handle));
+    dirs
+}
@@ -0,0 +1,387 @@
+//! OCR model manager: downloads, verifies and updates model files on demand.
+//!
+//! Downloaded files live in `<app_data_dir>/ocr_models`, which is searched
+//! before the bundled resources directory. A `manifest.json` next to them
+//! records the source URL and SHA-256 of every downloaded file, so updates
+//! can be detected when the registry in `ocr.rs` changes and corrupted files
+//! can be found by re-hashing.
+
+use crate::network;
+use crate::ocr::{get_models_dir, models_changed, ModelFile, DETECTION_MODEL, RECOGNITION_MODELS};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, Emitter, Manager};
+
+const MANIFEST_FILE: &str = "manifest.json";
+
+/// Bytes downloaded between `ocr-model-progress` events
+const PROGRESS_INTERVAL_BYTES: u64 = 512 * 1024;
+
+/// Manifest record for a downloaded file
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct InstalledFile {
+    pub url: String,
+    pub sha256: String,
+    pub size: u64,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct Manifest {
+    files: BTreeMap<String, InstalledFile>,
+}
+
+/// Download progress, emitted as `ocr-model-progress`
+#[derive(Clone, Serialize)]
+pub struct ModelDownloadProgress {
+    pub language: String,
+    pub file: String,
+    pub downloaded: u64,
+    pub total: Option<u64>,
+}
+
+/// An installed model file as listed in settings
+#[derive(Serialize)]
+pub struct ModelFileStatus {
+    pub name: String,
+    pub path: String,
+    pub size: u64,
+    /// "downloaded" or "bundled"
+    pub source: String,
+    pub update_available: bool,
+}
+
+/// Result of re-hashing a downloaded file against the manifest
+#[derive(Serialize)]
+pub struct ModelVerification {
+    pub name: String,
+    pub valid: bool,
+}
+
+/// Directory that downloaded models are written to
+pub fn downloads_dir(app: &AppHandle) -> Result<PathBuf, String> {
+    let app_data_dir = app.path().app_data_dir().map_err(|e| e.to_string())?;
+    Ok(app_data_dir.join("ocr_models"))
+}
+
+fn load_manifest(dir: &Path) -> Manifest {
+    fs::read_to_string(dir.join(MANIFEST_FILE))
+        .ok()
+        .and_then(|json| serde_json::from_str(&json).ok())
+        .unwrap_or_default()
+}
+
+fn save_manifest(dir: &Path, manifest: &Manifest) -> Result<(), String> {
+    let json = serde_json::to_string_pretty(manifest).map_err(|e| e.to_string())?;
+    fs::write(dir.join(MANIFEST_FILE), json).map_err(|e| e.to_string())
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+fn sha256_file(path: &Path) -> Result<String, String> {
+    let mut file = fs::File::open(path).map_err(|e| e.to_string())?;
+    let mut hasher = Sha256::new();
+    std::io::copy(&mut file, &mut hasher).map_err(|e| e.to_string())?;
+    Ok(to_hex(&hasher.finalize()))
+}
+
+/// Whether a downloaded copy of `file` no longer matches the registry, either
+/// because its source moved to a new release or its pinned checksum changed
+fn is_outdated(file: &ModelFile, installed: Option<&InstalledFile>) -> bool {
+    let (Some(url), Some(installed)) = (file.url, installed) else {
+        return false;
+    };
+    installed.url != url
+        || file
+            .sha256
+            .is_some_and(|sha256| !sha256.eq_ignore_ascii_case(&installed.sha256))
+}
+
+/// Every file in the registry, with the shared detection model listed once
+fn registry_files() -> impl Iterator<Item = &'static ModelFile> {
+    std::iter::once(&DETECTION_MODEL).chain(
+        RECOGNITION_MODELS
+            .iter()
+            .flat_map(|model| [&model.model, &model.dictionary]),
+    )
+}
+
+/// List installed model files, preferring downloaded copies over bundled ones
+pub fn list_installed(app: &AppHandle) -> Result<Vec<ModelFileStatus>, String> {
+    let downloads = downloads_dir(app)?;
+    let bundled = get_models_dir(app);
+    let manifest = load_manifest(&downloads);
+
+    let mut files = Vec::new();
+    for file in registry_files() {
+        let downloaded = downloads.join(file.name);
+        let (path, source) = if downloaded.exists() {
+            (downloaded, "downloaded")
+        } else if bundled.join(file.name).exists() {
+            (bundled.join(file.name), "bundled")
+        } else {
+            continue;
+        };
+        let size = fs::metadata(&path).map(|m| m.len()).unwrap_or(0);
+        files.push(ModelFileStatus {
+            name: file.name.to_string(),
+            path: path.to_string_lossy().to_string(),
+            size,
+            update_available: source == "downloaded"
+                && is_outdated(file, manifest.files.get(file.name)),
+            source: source.to_string(),
+        });
+    }
+    Ok(files)
+}
+
+/// Download whatever `language` is missing, plus any outdated downloaded
+/// files, into the downloads directory
+pub async fn download_language(app: &AppHandle, language: &str) -> Result<(), String> {
+    let model = RECOGNITION_MODELS
+        .iter()
+        .find(|model| model.id == language)
+        .ok_or_else(|| format!("Unknown OCR language: {}", language))?;
+
+    let dir = downloads_dir(app)?;
+    fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+    let bundled = get_models_dir(app);
+    let mut manifest = load_manifest(&dir);
+
+    let client = network::client_builder()?
+        .build()
+        .map_err(|e| format!("Failed to create download client: {}", e))?;
+
+    let mut changed = false;
+    for file in model.files() {
+        let needed = if dir.join(file.name).exists() {
+            is_outdated(file, manifest.files.get(file.name))
+        } else {
+            !bundled.join(file.name).exists()
+        };
+        if !needed {
+            continue;
+        }
+
+        let url = file.url.ok_or_else(|| {
+            format!(
+                "{} has no download source. Copy it into {:?} manually.",
+                file.name, dir
+            )
+        })?;
+        let installed = download_file(app, &client, language, file, url, &dir).await?;
+        manifest.files.insert(file.name.to_string(), installed);
+        save_manifest(&dir, &manifest)?;
+        changed = true;
+    }
+
+    if changed {
+        models_changed();
+    }
+    Ok(())
+}
+
+/// Download one file to `<name>.part`, check its hash, then move it into place
+async fn download_file(
+    app: &AppHandle,
+    client: &reqwest::Client,
+    language: &str,
+    file: &ModelFile,
+    url: &str,
+    dir: &Path,
+) -> Result<InstalledFile, String> {
+    let mut response = client
+        .get(url)
+        .send()
+        .await
+        .and_then(|response| response.error_for_status())
+        .map_err(|e| format!("Failed to download {}: {}", file.name, e))?;
+
+    let total = response.content_length();
+    let partial = dir.join(format!("{}.part", file.name));
+    let mut out = fs::File::create(&partial).map_err(|e| e.to_string())?;
+    let mut hasher = Sha256::new();
+    let mut downloaded = 0u64;
+    let mut last_reported = 0u64;
+
+    let emit_progress = |downloaded: u64| {
+        let _ = app.emit(
+            "ocr-model-progress",
+            ModelDownloadProgress {
+                language: language.to_string(),
+                file: file.name.to_string(),
+                downloaded,
+                total,
+            },
+        );
+    };
+    emit_progress(0);
+
+    while let Some(chunk) = response
+        .chunk()
+        .await
+        .map_err(|e| format!("Failed to download {}: {}", file.name, e))?
+    {
+        out.write_all(&chunk).map_err(|e| e.to_string())?;
+        hasher.update(&chunk);
+        downloaded += chunk.len() as u64;
+        if downloaded - last_reported >= PROGRESS_INTERVAL_BYTES {
+            emit_progress(downloaded);
+            last_reported = downloaded;
+        }
+    }
+    out.flush().map_err(|e| e.to_string())?;
+    drop(out);
+    emit_progress(downloaded);
+
+    let sha256 = to_hex(&hasher.finalize());
+    if let Some(expected) = file.sha256 {
+        if !expected.eq_ignore_ascii_case(&sha256) {
+            let _ = fs::remove_file(&partial);
+            return Err(format!(
+                "Checksum mismatch for {}: expected {}, got {}",
+                file.name, expected, sha256
+            ));
+        }
+    }
+
+    fs::rename(&partial, dir.join(file.name)).map_err(|e| e.to_string())?;
+    Ok(InstalledFile {
+        url: url.to_string(),
+        sha256,
+        size: downloaded,
+    })
+}
+
+/// Re-download every outdated file. Returns the languages that were updated.
+pub async fn update_all(app: &AppHandle) -> Result<Vec<String>, String> {
+    let dir = downloads_dir(app)?;
+    let manifest = load_manifest(&dir);
+
+    let mut updated = Vec::new();
+    for model in RECOGNITION_MODELS {
+        let outdated = model.files().iter().any(|file| {
+            dir.join(file.name).exists() && is_outdated(file, manifest.files.get(file.name))
+        });
+        if outdated {
+            download_language(app, model.id).await?;
+            updated.push(model.id.to_string());
+        }
+    }
+    Ok(updated)
+}
+
+/// Re-hash every downloaded file and compare it with the manifest
+pub fn verify(app: &AppHandle) -> Result<Vec<ModelVerification>, String> {
+    let dir = downloads_dir(app)?;
+    let manifest = load_manifest(&dir);
+
+    Ok(manifest
+        .files
+        .iter()
+        .map(|(name, installed)| ModelVerification {
+            name: name.clone(),
+            valid: sha256_file(&dir.join(name))
+                .map(|sha256| sha256 == installed.sha256)
+                .unwrap_or(false),
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn installed(url: &str, sha256: &str) -> InstalledFile {
+        InstalledFile {
+            url: url.to_string(),
+            sha256: sha256.to_string(),
+            size: 1,
+        }
+    }
+
+    #[test]
+    fn is_outdated_when_source_url_changes() {
+        let file = ModelFile {
+            name: "rec.onnx",
+            url: Some("https://example.com/v2/rec.onnx"),
+            sha256: None,
+        };
+
+        assert!(is_outdated(
+            &file,
+            Some(&installed("https://example.com/v1/rec.onnx", "aa"))
+        ));
+        assert!(!is_outdated(
+            &file,
+            Some(&installed("https://example.com/v2/rec.onnx", "aa"))
+        ));
+    }
+
+    #[test]
+    fn is_outdated_when_pinned_checksum_differs() {
+        let file = ModelFile {
+            name: "rec.onnx",
+            url: Some("https://example.com/rec.onnx"),
+            sha256: Some("BB"),
+        };
+
+        assert!(is_outdated(
+            &file,
+            Some(&installed("https://example.com/rec.onnx", "aa"))
+        ));
+        assert!(!is_outdated(
+            &file,
+            Some(&installed("https://example.com/rec.onnx", "bb"))
+        ));
+    }
+
+    #[test]
+    fn files_without_a_manifest_entry_or_source_are_never_outdated() {
+        let manual = ModelFile {
+            name: "rec_latin.onnx",
+            url: None,
+            sha256: None,
+        };
+        let downloadable = ModelFile {
+            name: "rec.onnx",
+            url: Some("https://example.com/rec.onnx"),
+            sha256: None,
+        };
+
+        assert!(!is_outdated(
+            &manual,
+            Some(&installed("https://example.com/rec.onnx", "aa"))
+        ));
+        assert!(!is_outdated(&downloadable, None));
+    }
+
+    #[test]
+    fn manifest_round_trips_through_disk() {
+        let dir = std::env::temp_dir().join(format!(
+            "stepsnap_ocr_manifest_test_{}",
+            uuid::Uuid::new_v4()
+        ));
+        fs::create_dir_all(&dir).unwrap();
+
+        let mut manifest = Manifest::default();
+        manifest.files.insert(
+            "det.onnx".to_string(),
+            installed("https://example.com/det.onnx", "cc"),
+        );
+        save_manifest(&dir, &manifest).unwrap();
+
+        let loaded = load_manifest(&dir);
+        assert_eq!(
+            loaded.files.get("det.onnx"),
+            Some(&installed("https://example.com/det.onnx", "cc"))
+        );
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,393 @@
+//! Bounded priority queue feeding the OCR workers.
+//!
+//! Steps the user is looking at are read first, then steps from the live
+//! recording, then backfill of saved steps. While a recording is running only
+//! one worker runs at a time so OCR doesn't compete with capture; once it
+//! stops, up to the configured parallelism run at once.
+//!
+//! Jobs queued while the queue is busy join the current batch; the batch's
+//! progress is reported as `ocr-started` / `ocr-progress` events until the
+//! queue drains.
+
+use image::RgbaImage;
+use std::cmp::Ordering;
+use std::collections::{BinaryHeap, HashSet};
+use std::path::PathBuf;
+use std::sync::{Arc, Condvar, Mutex};
+use std::time::Duration;
+
+/// Jobs queued beyond this evict the lowest-priority job
+pub const OCR_QUEUE_CAPACITY: usize = 256;
+
+/// Number of OCR worker threads. `set_parallelism` picks how many of them
+/// may run at once.
+pub const MAX_OCR_WORKERS: usize = 4;
+
+pub const DEFAULT_PARALLELISM: usize = 2;
+
+/// How often idle workers re-check whether recording has stopped
+const RECHECK_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Declared from most to least urgent
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum OcrPriority {
+    /// Steps currently on screen in the editor
+    Visible,
+    /// Steps from the recording in progress
+    Live,
+    /// Saved steps that never got OCR text
+    Backfill,
+}
+
+/// Screenshot to read, either still in memory from the recorder or on disk
+pub enum OcrImage {
+    Loaded(Arc<RgbaImage>),
+    File(PathBuf),
+}
+
+pub struct QueuedOcrJob {
+    pub step_id: String,
+    pub image: OcrImage,
+    pub x: Option<i32>,
+    pub y: Option<i32>,
+    pub step_type: String,
+    pub priority: OcrPriority,
+}
+
+struct Entry {
+    /// Insertion order, so equal priorities run first-in first-out
+    seq: u64,
+    job: QueuedOcrJob,
+}
+
+impl Ord for Entry {
+    // `BinaryHeap` pops the greatest entry: the most urgent, then the oldest
+    fn cmp(&self, other: &Self) -> Ordering {
+        other
+            .job
+            .priority
+            .cmp(&self.job.priority)
+            .then_with(|| other.seq.cmp(&self.seq))
+    }
+}
+
+impl PartialOrd for Entry {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl PartialEq for Entry {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Entry {}
+
+struct QueueState {
+    entries: BinaryHeap<Entry>,
+    next_seq: u64,
+    in_flight: usize,
+    completed: u64,
+    dropped: u64,
+    parallelism: usize,
+    /// Jobs finished since the queue was last idle
+    batch_done: usize,
+    /// Whether the current batch has been reported by `take_batch_start`
+    batch_announced: bool,
+}
+
+impl QueueState {
+    fn batch_progress(&self) -> OcrProgress {
+        OcrProgress {
+            done: self.batch_done,
+            total: self.batch_done + self.in_flight + self.entries.len(),
+        }
+    }
+}
+
+/// Payload of the `ocr-started` and `ocr-progress` events. `total` grows as
+/// jobs join the batch; the batch is over once `done == total`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, serde::Serialize)]
+pub struct OcrProgress {
+    pub done: usize,
+    pub total: usize,
+}
+
+/// Snapshot returned by `get_ocr_queue_status`
+#[derive(Clone, Debug, serde::Serialize)]
+pub struct OcrQueueStatus {
+    pub queued_visible: usize,
+    pub queued_live: usize,
+    pub queued_backfill: usize,
+    pub in_flight: usize,
+    pub completed: u64,
+    pub dropped: u64,
+    pub parallelism: usize,
+}
+
+pub struct OcrQueue {
+    state: Mutex<QueueState>,
+    available: Condvar,
+}
+
+impl OcrQueue {
+    pub fn new() -> Self {
+        Self {
+            state: Mutex::new(QueueState {
+                entries: BinaryHeap::new(),
+                next_seq: 0,
+                in_flight: 0,
+                completed: 0,
+                dropped: 0,
+                parallelism: DEFAULT_PARALLELISM,
+                batch_done: 0,
+                batch_announced: false,
+            }),
+            available: Condvar::new(),
+        }
+    }
+
+    /// Queue a job. When the queue is full the least urgent, newest job is
+    /// dropped to make room (possibly `job` itself) and returned so the caller
+    /// can report it.
+    pub fn push(&self, job: QueuedOcrJob) -> Option<QueuedOcrJob> {
+        let mut state = self.state.lock().unwrap();
+        if state.entries.is_empty() && state.in_flight == 0 {
+            state.batch_done = 0;
+            state.batch_announced = false;
+        }
+        let seq = state.next_seq;
+        state.next_seq += 1;
+        let entry = Entry { seq, job };
+
+        let dropped = if state.entries.len() >= OCR_QUEUE_CAPACITY {
+            state.dropped += 1;
+            let mut entries = std::mem::take(&mut state.entries).into_vec();
+            // The least urgent entry is the smallest by `Entry`'s ordering
+            let (worst, _) = entries
+                .iter()
+                .enumerate()
+                .min_by(|(_, a), (_, b)| a.cmp(b))
+                .expect("queue is full");
+            let dropped = if entries[worst] < entry {
+                let evicted = entries.swap_remove(worst);
+                entries.push(entry);
+                evicted.job
+            } else {
+                entry.job
+            };
+            state.entries = BinaryHeap::from(entries);
+            Some(dropped)
+        } else {
+            state.entries.push(entry);
+            None
+        };
+
+        self.available.notify_one();
+        dropped
+    }
+
+    /// Move queued jobs for `step_ids` to the front
+    pub fn prioritize(&self, step_ids: &[String]) {
+        let step_ids: HashSet<&str> = step_ids.iter().map(String::as_str).collect();
+        let mut state = self.state.lock().unwrap();
+        let mut entries = std::mem::take(&mut state.entries).into_vec();
+        for entry in &mut entries {
+            if step_ids.contains(entry.job.step_id.as_str()) {
+                entry.job.priority = OcrPriority::Visible;
+            }
+        }
+        state.entries = BinaryHeap::from(entries);
+    }
+
+    /// Set how many workers may run at once, clamped to `1..=MAX_OCR_WORKERS`
+    pub fn set_parallelism(&self, parallelism: usize) {
+        self.state.lock().unwrap().parallelism = parallelism.clamp(1, MAX_OCR_WORKERS);
+        self.available.notify_all();
+    }
+
+    /// Block until a job may run. Only one job runs at a time while
+    /// `is_recording` is set. Call `finish` once the job is done.
+    pub fn next(&self, is_recording: &Mutex<bool>) -> QueuedOcrJob {
+        let mut state = self.state.lock().unwrap();
+        loop {
+            let limit = if *is_recording.lock().unwrap() {
+                1
+            } else {
+                state.parallelism
+            };
+            if state.in_flight < limit {
+                if let Some(entry) = state.entries.pop() {
+                    state.in_flight += 1;
+                    return entry.job;
+                }
+            }
+            state = self
+                .available
+                .wait_timeout(state, RECHECK_INTERVAL)
+                .unwrap()
+                .0;
+        }
+    }
+
+    /// The current batch's progress the first time this is called for it,
+    /// so exactly one worker reports the batch as started
+    pub fn take_batch_start(&self) -> Option<OcrProgress> {
+        let mut state = self.state.lock().unwrap();
+        if state.batch_announced {
+            return None;
+        }
+        state.batch_announced = true;
+        Some(state.batch_progress())
+    }
+
+    /// Mark a job returned by `next` as done and return the batch's progress
+    pub fn finish(&self) -> OcrProgress {
+        let mut state = self.state.lock().unwrap();
+        state.in_flight = state.in_flight.saturating_sub(1);
+        state.completed += 1;
+        state.batch_done += 1;
+        self.available.notify_one();
+        state.batch_progress()
+    }
+
+    pub fn status(&self) -> OcrQueueStatus {
+        let state = self.state.lock().unwrap();
+        let queued = |priority| {
+            state
+                .entries
+                .iter()
+                .filter(|entry| entry.job.priority == priority)
+                .count()
+        };
+        OcrQueueStatus {
+            queued_visible: queued(OcrPriority::Visible),
+            queued_live: queued(OcrPriority::Live),
+            queued_backfill: queued(OcrPriority::Backfill),
+            in_flight: state.in_flight,
+            completed: state.completed,
+            dropped: state.dropped,
+            parallelism: state.parallelism,
+        }
+    }
+}
+
+impl Default for OcrQueue {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn job(step_id: &str, priority: OcrPriority) -> QueuedOcrJob {
+        QueuedOcrJob {
+            step_id: step_id.to_string(),
+            image: OcrImage::File(PathBuf::new()),
+            x: None,
+            y: None,
+            step_type: "capture".to_string(),
+            priority,
+        }
+    }
+
+    fn drain(queue: &OcrQueue) -> Vec<String> {
+        let not_recording = Mutex::new(false);
+        queue.set_parallelism(MAX_OCR_WORKERS);
+        let mut order = Vec::new();
+        loop {
+            let status = queue.status();
+            if status.queued_visible + status.queued_live + status.queued_backfill == 0 {
+                return order;
+            }
+            order.push(queue.next(&not_recording).step_id);
+            queue.finish();
+        }
+    }
+
+    #[test]
+    fn jobs_run_by_priority_then_insertion_order() {
+        let queue = OcrQueue::new();
+        queue.push(job("backfill", OcrPriority::Backfill));
+        queue.push(job("live-1", OcrPriority::Live));
+        queue.push(job("visible", OcrPriority::Visible));
+        queue.push(job("live-2", OcrPriority::Live));
+
+        assert_eq!(drain(&queue), ["visible", "live-1", "live-2", "backfill"]);
+    }
+
+    #[test]
+    fn prioritize_moves_queued_steps_to_the_front() {
+        let queue = OcrQueue::new();
+        queue.push(job("live-1", OcrPriority::Live));
+        queue.push(job("backfill", OcrPriority::Backfill));
+        queue.prioritize(&["backfill".to_string()]);
+
+        assert_eq!(drain(&queue), ["backfill", "live-1"]);
+    }
+
+    #[test]
+    fn full_queue_evicts_the_least_urgent_newest_job() {
+        let queue = OcrQueue::new();
+        for i in 0..OCR_QUEUE_CAPACITY - 2 {
+            queue.push(job(&format!("live-{}", i), OcrPriority::Live));
+        }
+        queue.push(job("backfill-1", OcrPriority::Backfill));
+        queue.push(job("backfill-2", OcrPriority::Backfill));
+
+        let evicted = queue.push(job("live-new", OcrPriority::Live)).unwrap();
+        assert_eq!(evicted.step_id, "backfill-2");
+
+        // A backfill job can't displace anything more urgent than itself
+        let rejected = queue
+            .push(job("backfill-3", OcrPriority::Backfill))
+            .unwrap();
+        assert_eq!(rejected.step_id, "backfill-3");
+
+        let status = queue.status();
+        assert_eq!(status.queued_live, OCR_QUEUE_CAPACITY - 1);
+        assert_eq!(status.queued_backfill, 1);
+        assert_eq!(status.dropped, 2);
+    }
+
+    #[test]
+    fn batch_progress_counts_jobs_until_the_queue_drains() {
+        let queue = OcrQueue::new();
+        let not_recording = Mutex::new(false);
+        queue.push(job("a", OcrPriority::Live));
+        queue.push(job("b", OcrPriority::Live));
+
+        queue.next(&not_recording);
+        assert_eq!(
+            queue.take_batch_start(),
+            Some(OcrProgress { done: 0, total: 2 })
+        );
+        assert_eq!(queue.take_batch_start(), None);
+        // Joins the running batch
+        queue.push(job("c", OcrPriority::Backfill));
+        assert_eq!(queue.finish(), OcrProgress { done: 1, total: 3 });
+        drain(&queue);
+
+        // The next job after the queue drained starts a fresh batch
+        queue.push(job("d", OcrPriority::Live));
+        queue.next(&not_recording);
+        assert_eq!(
+            queue.take_batch_start(),
+            Some(OcrProgress { done: 0, total: 1 })
+        );
+        assert_eq!(queue.finish(), OcrProgress { done: 1, total: 1 });
+    }
+
+    #[test]
+    fn parallelism_is_clamped() {
+        let queue = OcrQueue::new();
+        queue.set_parallelism(0);
+        assert_eq!(queue.status().parallelism, 1);
+        queue.set_parallelism(64);
+        assert_eq!(queue.status().parallelism, MAX_OCR_WORKERS);
+    }
+}
@@ -259,7 +259,7 @@ mod windows_impl {
                 // Register toast window class if not already done
                 if !TOAST_CLASS_REGISTERED.swap(true, Ordering::SeqCst) {
                     if let Err(e) = register_toast_class() {
-                        eprintln!("Failed to register toast class: {}", e);
+                        tracing::error!("Failed to register toast class: {}", e);
                         return;
                     }
                 }
@@ -299,7 +299,7 @@ mod windows_impl {
                 ) {
                     Ok(h) if !h.0.is_null() => h,
                     _ => {
-                        eprintln!("Failed to create toast window");
+                        tracing::error!("Failed to create toast window");
                         return;
                     }
                 };
@@ -1325,7 +1325,7 @@ mod linux_x11_impl {
         // Create toast in a new thread to avoid blocking
         std::thread::spawn(move || {
             if let Err(e) = show_toast_internal(&message_owned, duration) {
-                eprintln!("Failed to show toast: {}", e);
+                tracing::warn!("Failed to show toast: {}", e);
             }
         });
 
@@ -1529,8 +1529,8 @@ mod linux_wayland_impl {
 
         // Log warning once about limited Wayland overlay support
         if !LAYER_SHELL_WARNED.swap(true, Ordering::SeqCst) {
-            eprintln!(
-                "[StepSnap] Wayland detected: Border overlays using layer-shell are not yet fully implemented. \
+            tracing::warn!(
+                "Wayland detected: Border overlays using layer-shell are not yet fully implemented. \
                  Overlay may not appear. Toast notifications will work via D-Bus."
             );
         }
@@ -1542,8 +1542,8 @@ mod linux_wayland_impl {
         }
 
         // No XWayland available - overlay won't show but app continues
-        eprintln!(
-            "[StepSnap] Cannot show overlay: no XWayland available. \
+        tracing::warn!(
+            "Cannot show overlay: no XWayland available. \
              Overlay position would be: ({}, {}) size: {}x{}",
             x, y, width, height
         );
@@ -0,0 +1,94 @@
+//! Perceptual hashes for spotting repeated frames.
+//!
+//! `dhash` shrinks a screenshot to 9x8 grey pixels and keeps one bit per
+//! pair of neighbours, set when the left one is brighter. Frames that look
+//! the same get the same or nearly the same hash, whatever their size, JPEG
+//! noise or a blinking caret. The recorder compares each frame with the
+//! previous step's and, depending on `DuplicateFrames`, reuses its screenshot
+//! or drops the step.
+
+use image::imageops::{self, FilterType};
+use image::{ImageBuffer, Pixel};
+use serde::{Deserialize, Serialize};
+
+/// Hashes at most this many bits apart are treated as the same frame
+pub const DUPLICATE_DISTANCE: u32 = 4;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DuplicateFrames {
+    /// Write every frame
+    Off,
+    /// Keep the step but reuse the previous screenshot and mark it a duplicate.
+    #[default]
+    Flag,
+    /// Drop click steps whose frame repeats the previous one. Other steps
+    /// are flagged, since their text would be lost.
+    Skip,
+}
+
+/// Difference hash of `image`, an RGB or RGBA frame
+pub fn dhash<P: Pixel<Subpixel = u8>>(image: &ImageBuffer<P, Vec<u8>>) -> u64 {
+    let grey = imageops::grayscale(image);
+    let small = imageops::resize(&grey, 9, 8, FilterType::Triangle);
+    let mut hash = 0u64;
+    for y in 0..8 {
+        for x in 0..8 {
+            let brighter = small.get_pixel(x, y)[0] > small.get_pixel(x + 1, y)[0];
+            hash = hash << 1 | brighter as u64;
+        }
+    }
+    hash
+}
+
+/// Number of bits that differ between two hashes
+pub fn distance(a: u64, b: u64) -> u32 {
+    (a ^ b).count_ones()
+}
+
+/// Whether two hashes are close enough to be the same frame
+pub fn is_duplicate(a: u64, b: u64) -> bool {
+    distance(a, b) <= DUPLICATE_DISTANCE
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::{Rgb, RgbImage};
+
+    fn gradient(width: u32, height: u32) -> RgbImage {
+        RgbImage::from_fn(width, height, |x, y| {
+            let v = (x * 200 / width + y * 50 / height) as u8;
+            Rgb([v, v, v])
+        })
+    }
+
+    #[test]
+    fn same_picture_at_another_size_hashes_alike() {
+        let large = gradient(1920, 1080);
+        let small = imageops::resize(&large, 960, 540, FilterType::Triangle);
+        assert!(is_duplicate(dhash(&large), dhash(&small)));
+    }
+
+    #[test]
+    fn small_changes_are_duplicates_but_new_screens_are_not() {
+        let before = gradient(800, 600);
+
+        let mut caret = before.clone();
+        for y in 300..316 {
+            caret.put_pixel(400, y, Rgb([0, 0, 0]));
+        }
+        assert!(is_duplicate(dhash(&before), dhash(&caret)));
+
+        let mut mirrored = before.clone();
+        imageops::flip_horizontal_in_place(&mut mirrored);
+        assert!(!is_duplicate(dhash(&before), dhash(&mirrored)));
+    }
+
+    #[test]
+    fn distance_counts_differing_bits() {
+        assert_eq!(distance(0, 0), 0);
+        assert_eq!(distance(0b1011, 0b0001), 2);
+        assert_eq!(distance(u64::MAX, 0), 64);
+    }
+}
@@ -0,0 +1,316 @@
+//! Personal data detection and redaction suggestions.
+//!
+//! `detect` finds e-mail addresses, phone numbers, payment card numbers, US
+//! social security numbers and IBANs in free text. `suggest_redaction` runs
+//! it over what a step knows about its element (typed text, value, name) and
+//! proposes hiding the element's area when anything turns up, or when the
+//! element is a password field. OCR text has no positions, so matches there
+//! can't be placed and aren't suggested.
+
+use crate::annotations::{Rect, Redaction, RedactionStyle};
+use crate::database::Step;
+use serde::Serialize;
+
+/// Margin added around the element so its border and focus ring are covered
+const SUGGESTION_PADDING: f32 = 4.0;
+
+/// Roles and class names of secure text fields: AT-SPI, AX, WPF and WinUI
+const PASSWORD_ROLES: &[&str] = &["password text", "axsecuretextfield", "passwordbox"];
+
+/// Field names that mark a secret even when the role doesn't
+const PASSWORD_NAMES: &[&str] = &["password", "passcode", "passwort", "mot de passe"];
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PiiKind {
+    Email,
+    Phone,
+    CardNumber,
+    NationalId,
+    Iban,
+    Password,
+}
+
+/// A redaction the user may accept for a step
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct RedactionSuggestion {
+    pub step_id: String,
+    /// Why the area was flagged, password first when it's a password field
+    pub kinds: Vec<PiiKind>,
+    pub redaction: Redaction,
+}
+
+fn is_email(token: &str) -> bool {
+    let Some((local, domain)) = token.split_once('@') else {
+        return false;
+    };
+    let valid_domain = domain.contains('.')
+        && !domain.starts_with('.')
+        && !domain.ends_with('.')
+        && !domain.contains("..")
+        && domain
+            .chars()
+            .all(|c| c.is_alphanumeric() || c == '.' || c == '-');
+    let valid_local = !local.is_empty()
+        && local
+            .chars()
+            .all(|c| c.is_alphanumeric() || "._%+-".contains(c));
+    valid_local && valid_domain
+}
+
+fn passes_luhn(digits: &[u32]) -> bool {
+    let sum: u32 = digits
+        .iter()
+        .rev()
+        .enumerate()
+        .map(|(i, &digit)| match (i % 2, digit * 2) {
+            (0, _) => digit,
+            (_, doubled) if doubled > 9 => doubled - 9,
+            (_, doubled) => doubled,
+        })
+        .sum();
+    sum % 10 == 0
+}
+
+/// Compact IBAN: country code, check digits, then 11 to 30 letters or digits
+/// that pass the mod-97 check.
+fn is_iban(token: &str) -> bool {
+    let bytes = token.as_bytes();
+    if !(15..=34).contains(&bytes.len())
+        || !bytes[..2].iter().all(u8::is_ascii_uppercase)
+        || !bytes[2..4].iter().all(u8::is_ascii_digit)
+        || !bytes[4..]
+            .iter()
+            .all(|b| b.is_ascii_uppercase() || b.is_ascii_digit())
+    {
+        return false;
+    }
+    let rearranged = bytes[4..].iter().chain(&bytes[..4]);
+    let remainder = rearranged.fold(0u32, |acc, &b| {
+        let value = if b.is_ascii_digit() {
+            (b - b'0') as u32
+        } else {
+            (b - b'A') as u32 + 10
+        };
+        let shift = if value >= 10 { 100 } else { 10 };
+        (acc * shift + value) % 97
+    });
+    remainder == 1
+}
+
+/// `ddd-dd-dddd`
+fn is_ssn(run: &str) -> bool {
+    let groups: Vec<&str> = run.split('-').collect();
+    groups.len() == 3
+        && groups
+            .iter()
+            .zip([3, 2, 4])
+            .all(|(group, len)| group.len() == len && group.bytes().all(|b| b.is_ascii_digit()))
+        && groups[0] != "000"
+        && groups[1] != "00"
+        && groups[2] != "0000"
+}
+
+/// Classify a run of digits and separators
+fn classify_number(run: &str) -> Option<PiiKind> {
+    let digits: Vec<u32> = run.chars().filter_map(|c| c.to_digit(10)).collect();
+    if is_ssn(run) {
+        Some(PiiKind::NationalId)
+    } else if (13..=19).contains(&digits.len()) && !run.contains(['(', '+']) && passes_luhn(&digits)
+    {
+        Some(PiiKind::CardNumber)
+    } else if (10..=15).contains(&digits.len()) {
+        Some(PiiKind::Phone)
+    } else {
+        None
+    }
+}
+
+/// Kinds of personal data found in `text`: words first, then numbers
+pub fn detect(text: &str) -> Vec<PiiKind> {
+    let mut matches = Vec::new();
+
+    for token in text.split_whitespace() {
+        let token = token.trim_matches(|c: char| !c.is_alphanumeric());
+        if is_email(token) {
+            matches.push(PiiKind::Email);
+        } else if is_iban(token) {
+            matches.push(PiiKind::Iban);
+        }
+    }
+
+    // Numbers may be split by spaces, dashes, dots and brackets
+    let is_number_char = |c: char| c.is_ascii_digit() || " -.()+".contains(c);
+    let mut rest = text;
+    while let Some(start) = rest.find(|c: char| c.is_ascii_digit() || c == '+' || c == '(') {
+        // Digits glued to letters are part of a word (ids, IBANs), not a number
+        let glued = rest[..start]
+            .chars()
+            .next_back()
+            .is_some_and(char::is_alphanumeric);
+        let tail = &rest[start..];
+        let end = tail
+            .find(|c: char| !is_number_char(c))
+            .unwrap_or(tail.len());
+        let run = tail[..end].trim_end_matches(|c: char| !c.is_ascii_digit());
+        let followed_by_word = tail[run.len()..]
+            .chars()
+            .next()
+            .is_some_and(char::is_alphanumeric);
+        if !glued && !followed_by_word {
+            matches.extend(classify_number(run));
+        }
+        rest = &tail[end.max(1)..];
+    }
+
+    matches
+}
+
+/// Whether the step's element is a password or other secret field
+pub fn is_password_field(step: &Step) -> bool {
+    let role = [step.element_type.as_deref(), step.class_name.as_deref()]
+        .into_iter()
+        .flatten()
+        .map(str::to_lowercase)
+        .any(|role| PASSWORD_ROLES.contains(&role.as_str()));
+    let name = step
+        .element_name
+        .as_deref()
+        .unwrap_or_default()
+        .to_lowercase();
+    role || PASSWORD_NAMES.iter().any(|secret| name.contains(secret))
+}
+
+/// Redaction worth proposing for `step`: its element's area when the
+/// element is a password field or shows personal data. Steps without
+/// element bounds, or with a cropped screenshot the bounds no longer line up
+/// with, get none.
+pub fn suggest_redaction(step: &Step) -> Option<RedactionSuggestion> {
+    let bounds = step.element_bounds?;
+    if step.is_cropped.unwrap_or(false) || bounds.width <= 0 || bounds.height <= 0 {
+        return None;
+    }
+
+    let mut kinds = Vec::new();
+    if is_password_field(step) {
+        kinds.push(PiiKind::Password);
+    }
+    let texts = [
+        step.text.as_deref(),
+        step.element_value.as_deref(),
+        step.element_name.as_deref(),
+    ];
+    for kind in texts.into_iter().flatten().flat_map(detect) {
+        if !kinds.contains(&kind) {
+            kinds.push(kind);
+        }
+    }
+    if kinds.is_empty() {
+        return None;
+    }
+
+    let rect = Rect {
+        x: (bounds.x as f32 - SUGGESTION_PADDING).max(0.0),
+        y: (bounds.y as f32 - SUGGESTION_PADDING).max(0.0),
+        width: bounds.width as f32 + SUGGESTION_PADDING * 2.0,
+        height: bounds.height as f32 + SUGGESTION_PADDING * 2.0,
+    };
+    let already_covered = step.redactions.iter().flatten().any(|redaction| {
+        let existing = &redaction.rect;
+        existing.x <= rect.x
+            && existing.y <= rect.y
+            && existing.x + existing.width >= rect.x + rect.width
+            && existing.y + existing.height >= rect.y + rect.height
+    });
+    if already_covered {
+        return None;
+    }
+
+    Some(RedactionSuggestion {
+        step_id: step.id.clone(),
+        kinds,
+        redaction: Redaction {
+            rect,
+            style: RedactionStyle::default(),
+        },
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::accessibility::ElementBounds;
+
+    #[test]
+    fn detects_common_personal_data() {
+        assert_eq!(
+            detect("Contact jane.doe+ops@example.co.uk."),
+            [PiiKind::Email]
+        );
+        assert_eq!(detect("Call +44 20 7946 0958 today"), [PiiKind::Phone]);
+        assert_eq!(detect("(555) 010-4477"), [PiiKind::Phone]);
+        assert_eq!(detect("Card 4111 1111 1111 1111"), [PiiKind::CardNumber]);
+        assert_eq!(detect("SSN 123-45-6789"), [PiiKind::NationalId]);
+        assert_eq!(detect("IBAN GB82WEST12345698765432"), [PiiKind::Iban]);
+    }
+
+    #[test]
+    fn ignores_ordinary_numbers_and_words() {
+        assert!(detect("Version 2.10.3 released on 2024-06-01").is_empty());
+        assert!(detect("Order 4111 1111 1111 1112 shipped").is_empty());
+        assert!(detect("Click @mention or user@localhost").is_empty());
+        assert!(detect("Build ABC1234567890 passed").is_empty());
+    }
+
+    fn field_step(element_type: &str, text: Option<&str>) -> Step {
+        Step {
+            id: "step".to_string(),
+            type_: "type".to_string(),
+            text: text.map(str::to_string),
+            element_type: Some(element_type.to_string()),
+            element_name: Some("Email".to_string()),
+            element_bounds: Some(ElementBounds {
+                x: 100,
+                y: 50,
+                width: 200,
+                height: 30,
+            }),
+            ..Step::default()
+        }
+    }
+
+    #[test]
+    fn suggests_the_field_area_for_personal_data_and_passwords() {
+        let suggestion = suggest_redaction(&field_step("edit", Some("jane@example.com"))).unwrap();
+        assert_eq!(suggestion.kinds, [PiiKind::Email]);
+        assert_eq!(
+            suggestion.redaction.rect,
+            Rect {
+                x: 96.0,
+                y: 46.0,
+                width: 208.0,
+                height: 38.0,
+            }
+        );
+
+        let password = suggest_redaction(&field_step("password text", None)).unwrap();
+        assert_eq!(password.kinds, [PiiKind::Password]);
+
+        assert_eq!(suggest_redaction(&field_step("edit", Some("hello"))), None);
+    }
+
+    #[test]
+    fn skips_areas_already_redacted() {
+        let mut step = field_step("edit", Some("jane@example.com"));
+        step.redactions = Some(vec![Redaction {
+            rect: Rect {
+                x: 0.0,
+                y: 0.0,
+                width: 400.0,
+                height: 200.0,
+            },
+            style: RedactionStyle::Blur,
+        }]);
+        assert_eq!(suggest_redaction(&step), None);
+    }
+}
@@ -1,19 +1,33 @@
-use crate::accessibility::{get_element_at_point, get_focused_field_value, ElementInfo};
-use crate::ocr::{get_models_dir, OcrConfig, OcrJob, OcrManager};
-use crate::{emit_startup_status, StartupState, StartupStatus};
+use crate::accessibility::{
+    get_focused_field_value, spawn_element_lookup, spawn_focused_element_lookup,
+    spawn_subtree_lookup, AxNode, ElementBounds, ElementInfo, ElementPathSegment,
+};
+use crate::actions::{infer_click_action, reads_state_after_click};
+use crate::analytics::{self, METRIC_SCREENSHOT_CAPTURE, METRIC_SCREENSHOT_ENCODE};
+use crate::annotations::Point;
+use crate::capture_backend;
+use crate::database::{Database, STEP_TYPE_MARKER};
+use crate::hotkeys::{action_for_press, default_bindings, mouse_button_key, HotkeyAction};
+use crate::jpeg;
+use crate::monitor_cache;
+use crate::ocr::{self, OcrConfig, OcrJob, OcrJobResult, AUTO_LANGUAGE};
+use crate::ocr_queue::{OcrImage, OcrPriority, OcrQueue, QueuedOcrJob, MAX_OCR_WORKERS};
+use crate::phash::{dhash, is_duplicate, DuplicateFrames};
+use crate::smart_crop::{focus_rect, CropRect, SmartCropMode, SmartCropSettings};
+use crate::{emit_startup_status, DatabaseState, StartupState, StartupStatus};
+use image::buffer::ConvertBuffer;
 use image::codecs::gif::{GifEncoder, Repeat};
-use image::codecs::jpeg::JpegEncoder;
-use image::{Delay, Frame, Rgb};
-use imageproc::drawing::{draw_filled_circle_mut, draw_hollow_circle_mut};
+use image::{Delay, Frame};
 use rdev::{listen, Button, EventType};
+use std::borrow::Cow;
 use std::fs;
 use std::io::BufWriter;
-use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 use std::sync::mpsc;
-use std::sync::Arc;
+use std::sync::{Arc, Weak};
 use std::thread;
 use std::time::{Duration, Instant, SystemTime};
-use tauri::{AppHandle, Emitter};
+use tauri::{AppHandle, Emitter, Manager};
 use uuid::Uuid;
 use xcap::Monitor;
 
@@ -29,6 +43,47 @@ fn is_stepsnap_app(app_name: &Option<String>) -> bool {
 
 static SCREENSHOT_COUNTER: AtomicU64 = AtomicU64::new(0);
 
+/// Bumped when recording starts, so the encoder never marks a step as
+/// repeating the last frame of an earlier recording.
+static RECORDING_SESSION: AtomicU64 = AtomicU64::new(0);
+
+/// Why the global input listener stopped. `rdev::listen` only returns when
+/// it can't hook input, after which no clicks or keys are recorded.
+static INPUT_LISTENER_ERROR: std::sync::Mutex<Option<String>> = std::sync::Mutex::new(None);
+
+pub fn input_listener_error() -> Option<String> {
+    INPUT_LISTENER_ERROR.lock().unwrap().clone()
+}
+
+/// Forget the frames of earlier recordings; see `RECORDING_SESSION`.
+pub fn begin_session() {
+    RECORDING_SESSION.fetch_add(1, Ordering::SeqCst);
+}
+
+/// Write to the draft journal so captured steps survive a crash before the
+/// frontend saves them. Best effort: a failure only costs crash recovery.
+fn journal_draft(app: &AppHandle, write: impl FnOnce(&Database) -> rusqlite::Result<()>) {
+    if let Some(db) = app.try_state::<DatabaseState>() {
+        if let Ok(db) = db.0.lock() {
+            let _ = write(&db);
+        }
+    }
+}
+
+/// How long a click waits for its accessibility lookup before the step is
+/// emitted without element info.
+const ELEMENT_LOOKUP_TIMEOUT: Duration = Duration::from_millis(200);
+/// How long a late lookup may still attach its result to the step.
+const LATE_ELEMENT_LOOKUP_LIMIT: Duration = Duration::from_secs(10);
+/// Delay before re-reading a clicked control whose state the click changes
+/// (checkboxes, menus), so the inferred action sees the new state.
+const ACTION_SETTLE_DELAY: Duration = Duration::from_millis(300);
+/// How long after startup the OCR engine is loaded, unless a job needs it
+/// sooner
+const OCR_WARM_UP_DELAY: Duration = Duration::from_secs(3);
+/// Margin kept around the clicked control in the element crop, in pixels.
+pub(crate) const ELEMENT_CROP_PADDING: i32 = 12;
+
 #[derive(Clone, serde::Serialize)]
 struct Step {
     id: String, // Unique ID for tracking OCR results
@@ -38,30 +93,242 @@ struct Step {
     text: Option<String>,
     timestamp: u64,
     screenshot: Option<String>, // File path to screenshot
-    element_name: Option<String>,
-    element_type: Option<String>,
-    element_value: Option<String>,
-    app_name: Option<String>,
+    /// Crop of just the clicked control; see `save_element_crop`.
+    element_screenshot: Option<String>,
+    /// Lossless PNG of the same frame, when
+    /// `RecordingState::keep_lossless_originals` is on.
+    screenshot_original: Option<String>,
+    #[serde(flatten)]
+    element: StepElement,
+    #[serde(flatten)]
+    window: ForegroundWindow,
+    /// "timeout" when the accessibility lookup had not answered in time; the
+    /// element fields then follow in a `new-step-element` event if the lookup
+    /// finishes later.
+    element_lookup: Option<&'static str>,
     /// Where the `text` field came from for type steps: "keystrokes" (raw
     /// rdev event stream), "ax_value" / "ax_text" / "ax_legacy" (read from
     /// the focused element via the accessibility API), or "password" (the
     /// field was secure; content was redacted before reaching this point).
     /// `None` for click / capture steps.
     input_source: Option<String>,
+    /// Set when smart crop trimmed the screenshot at capture; `x`/`y` and the
+    /// element bounds stay in full-frame coordinates.
+    is_cropped: bool,
+    /// Where the click highlight goes, in screenshot pixels (after any smart
+    /// crop). The ring itself isn't burned in; see `annotations`.
+    click_marker: Option<Point>,
+    /// Display scale of the captured monitor. `x`/`y`, the element bounds and
+    /// the marker are in screenshot (physical) pixels; divide by this for
+    /// logical ones.
+    scale_factor: f32,
+    /// Set when the frame repeated the previous step's and its screenshot was
+    /// reused instead of writing another.
+    is_duplicate: bool,
+}
+
+/// Element fields of a step. Flattened into the `new-step` payload, and sent
+/// on their own in `new-step-element` when a slow lookup finishes late.
+#[derive(Clone, Default, serde::Serialize)]
+struct StepElement {
+    element_name: Option<String>,
+    element_type: Option<String>,
+    element_value: Option<String>,
+    app_name: Option<String>,
+    /// Bounds of the clicked element, relative to the captured monitor (same
+    /// space as `x`/`y`).
+    element_bounds: Option<ElementBounds>,
+    /// Browser address and tab title when the click landed in a web page.
+    page_url: Option<String>,
+    page_title: Option<String>,
+    /// Selector material for test-script export: the clicked element's
+    /// automation id and class name plus its ancestor path.
+    automation_id: Option<String>,
+    class_name: Option<String>,
+    element_path: Option<Vec<ElementPathSegment>>,
+    /// Named ancestors down to the element; see `ElementInfo::breadcrumb`.
+    breadcrumb: Option<String>,
+    /// Inferred instruction for clicks, e.g. `Open the "File" menu`.
+    action: Option<String>,
+}
+
+impl From<&ElementInfo> for StepElement {
+    fn from(info: &ElementInfo) -> Self {
+        Self {
+            element_name: Some(info.name.clone()),
+            element_type: Some(info.element_type.clone()),
+            element_value: info.value.clone(),
+            app_name: info.app_name.clone(),
+            element_bounds: info.bounds,
+            page_url: info.page_url.clone(),
+            page_title: info.page_title.clone(),
+            automation_id: info.automation_id.clone(),
+            class_name: info.class_name.clone(),
+            element_path: Some(info.ancestor_path.clone()).filter(|path| !path.is_empty()),
+            breadcrumb: info.breadcrumb(),
+            action: None,
+        }
+    }
 }
 
-#[derive(Clone, serde::Deserialize)]
+/// Foreground process and window title when a step is captured, read from
+/// the OS. More reliable than the accessibility app name, which is missing
+/// or localized for many apps.
+#[derive(Clone, Default, serde::Serialize)]
+struct ForegroundWindow {
+    /// Executable file name, e.g. `EXCEL.EXE` or `firefox`.
+    process_name: Option<String>,
+    exe_path: Option<String>,
+    window_title: Option<String>,
+}
+
+impl ForegroundWindow {
+    fn new(exe_path: Option<String>, window_title: Option<String>) -> Self {
+        let process_name = exe_path.as_deref().and_then(|path| {
+            std::path::Path::new(path)
+                .file_name()
+                .map(|name| name.to_string_lossy().to_string())
+        });
+        Self {
+            process_name,
+            exe_path,
+            window_title: window_title.filter(|title| !title.is_empty()),
+        }
+    }
+}
+
+/// Element fields for a click step, with the action inferred from the state
+/// read when the click happened.
+fn click_element(info: &ElementInfo) -> StepElement {
+    StepElement {
+        action: infer_click_action(Some(info), None),
+        ..StepElement::from(info)
+    }
+}
+
+/// How screen coordinates map onto a monitor's capture. Input events,
+/// monitor geometry and accessibility bounds share one space per platform:
+/// physical pixels on Windows and X11, but logical points on macOS, where
+/// captures are still physical. Without scaling, clicks on a HiDPI monitor
+/// land off the clicked control in the screenshot.
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct MonitorSpace {
+    /// Monitor origin in screen coordinates
+    origin: (i32, i32),
+    /// Capture pixels per screen coordinate
+    scale: f64,
+    /// The monitor's display scale (1.0 at 96 DPI), recorded with the step
+    /// so its pixels can be shown at logical size
+    scale_factor: f32,
+}
+
+impl MonitorSpace {
+    /// The space of `mon`, captured as an image `capture_width` pixels wide
+    fn of(mon: &Monitor, capture_width: u32) -> Self {
+        let width = mon.width().unwrap_or(0);
+        let scale = if width > 0 && capture_width > 0 {
+            capture_width as f64 / width as f64
+        } else {
+            1.0
+        };
+        Self {
+            origin: monitor_origin(mon),
+            scale,
+            scale_factor: mon.scale_factor().unwrap_or(1.0),
+        }
+    }
+
+    /// A screen point in capture pixels
+    fn point(&self, x: f64, y: f64) -> (i32, i32) {
+        (
+            ((x - self.origin.0 as f64) * self.scale).round() as i32,
+            ((y - self.origin.1 as f64) * self.scale).round() as i32,
+        )
+    }
+
+    /// Screen-space bounds in capture pixels
+    fn bounds(&self, bounds: ElementBounds) -> ElementBounds {
+        bounds
+            .offset(-self.origin.0, -self.origin.1)
+            .scale(self.scale)
+    }
+}
+
+/// Convert an element's screen-space bounds to the captured monitor's pixels.
+fn to_monitor_space(mut info: ElementInfo, space: MonitorSpace) -> ElementInfo {
+    info.bounds = info.bounds.map(|b| space.bounds(b));
+    info
+}
+
+/// The screenshot the encoder wrote for the last step, to spot repeats
+struct WrittenFrame {
+    /// Captured frame it was encoded from, to spot a type step and its click
+    /// sharing one capture
+    image: Weak<image::RgbaImage>,
+    /// `None` when duplicate detection was off
+    hash: Option<u64>,
+    /// Smart crop applied before hashing
+    focus: Option<CropRect>,
+    path: String,
+    original: Option<String>,
+}
+
+/// Element lookup still running when its click step was emitted, with the
+/// space of the captured monitor.
+struct PendingElement {
+    rx: mpsc::Receiver<Option<ElementInfo>>,
+    space: MonitorSpace,
+}
+
+/// Subtree snapshot started at a click, with the space of the captured
+/// monitor.
+struct PendingSubtree {
+    rx: mpsc::Receiver<Option<AxNode>>,
+    space: MonitorSpace,
+    /// Store the snapshot with the step; see
+    /// `RecordingState::ax_tree_snapshot_enabled`.
+    store: bool,
+    /// Send its labels as the step's OCR text, because OCR will not run.
+    context_text: bool,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
 pub struct HotkeyBinding {
     pub ctrl: bool,
     pub shift: bool,
     pub alt: bool,
+    /// Cmd on macOS, the Windows key on Windows. Missing from bindings saved
+    /// before it existed.
+    #[serde(default)]
+    pub meta: bool,
     pub key: String,
+    /// Kept in the settings but not registered, so it does nothing
+    #[serde(default)]
+    pub disabled: bool,
+}
+
+impl HotkeyBinding {
+    /// Whether both are the same key and modifiers, enabled or not
+    pub fn same_keys(&self, other: &HotkeyBinding) -> bool {
+        self.ctrl == other.ctrl
+            && self.shift == other.shift
+            && self.alt == other.alt
+            && self.meta == other.meta
+            && self.key == other.key
+    }
 }
 
 pub struct RecordingState {
     pub is_recording: std::sync::Arc<std::sync::Mutex<bool>>,
     pub is_picker_open: std::sync::Arc<std::sync::Mutex<bool>>,
     pub ocr_enabled: std::sync::Arc<std::sync::Mutex<bool>>,
+    /// Recognition model id used for OCR, or "auto" to pick per step.
+    pub ocr_language: std::sync::Arc<std::sync::Mutex<String>>,
+    /// Pending OCR jobs, shared by the recorder and the OCR workers.
+    pub ocr_queue: std::sync::Arc<OcrQueue>,
+    /// Provider and thread settings the OCR workers build engines with.
+    /// Call `ocr::models_changed` after changing it.
+    pub ocr_config: std::sync::Arc<std::sync::Mutex<OcrConfig>>,
     /// Whether to capture an after-frame ~700ms-2s after each event.
     /// When false the encoder thread skips spawning the after-frame worker
     /// entirely (no extra screen capture, no extra disk write).
@@ -72,9 +339,24 @@ pub struct RecordingState {
     /// Whether to maintain a continuous frame buffer and emit a short clip
     /// per event (8a). Off by default — opt-in due to memory cost.
     pub video_clips_enabled: std::sync::Arc<std::sync::Mutex<bool>>,
-    pub start_hotkey: std::sync::Arc<std::sync::Mutex<HotkeyBinding>>,
-    pub stop_hotkey: std::sync::Arc<std::sync::Mutex<HotkeyBinding>>,
-    pub capture_hotkey: std::sync::Arc<std::sync::Mutex<HotkeyBinding>>,
+    /// Whether to store a shallow accessibility subtree around each clicked
+    /// element, for rebuilding selectors later. Off by default.
+    pub ax_tree_snapshot_enabled: std::sync::Arc<std::sync::Mutex<bool>>,
+    /// Whether screenshots are cropped around the clicked element, and when.
+    /// Only `SmartCropMode::Capture` concerns the recorder.
+    pub smart_crop: std::sync::Arc<std::sync::Mutex<SmartCropSettings>>,
+    /// What to do with steps whose frame nearly matches the previous step's.
+    pub duplicate_frames: std::sync::Arc<std::sync::Mutex<DuplicateFrames>>,
+    /// Whether to also write each new frame as a lossless PNG, which edits
+    /// and exports then start from instead of the JPEG. Off by default.
+    pub keep_lossless_originals: std::sync::Arc<std::sync::Mutex<bool>>,
+    /// The registered binding of each hotkey action; unbound actions are
+    /// missing.
+    pub hotkeys:
+        std::sync::Arc<std::sync::Mutex<std::collections::HashMap<HotkeyAction, HotkeyBinding>>>,
+    /// While true no hotkey is registered or matched, e.g. during a game
+    /// that needs the F-keys. Not saved; the app always starts with hotkeys.
+    pub hotkeys_suspended: std::sync::Arc<std::sync::Mutex<bool>>,
 }
 
 impl RecordingState {
@@ -83,27 +365,53 @@ impl RecordingState {
             is_recording: std::sync::Arc::new(std::sync::Mutex::new(false)),
             is_picker_open: std::sync::Arc::new(std::sync::Mutex::new(false)),
             ocr_enabled: std::sync::Arc::new(std::sync::Mutex::new(true)), // Enabled by default
+            ocr_language: std::sync::Arc::new(std::sync::Mutex::new(AUTO_LANGUAGE.to_string())),
+            ocr_queue: std::sync::Arc::new(OcrQueue::new()),
+            ocr_config: std::sync::Arc::new(std::sync::Mutex::new(OcrConfig::default())),
             state_diff_enabled: std::sync::Arc::new(std::sync::Mutex::new(true)),
             after_frame_max_wait_ms: std::sync::Arc::new(std::sync::Mutex::new(2000)),
             video_clips_enabled: std::sync::Arc::new(std::sync::Mutex::new(false)),
-            start_hotkey: std::sync::Arc::new(std::sync::Mutex::new(HotkeyBinding {
-                ctrl: true,
-                shift: false,
-                alt: true,
-                key: "KeyR".to_string(),
-            })),
-            stop_hotkey: std::sync::Arc::new(std::sync::Mutex::new(HotkeyBinding {
-                ctrl: true,
-                shift: false,
-                alt: true,
-                key: "KeyS".to_string(),
-            })),
-            capture_hotkey: std::sync::Arc::new(std::sync::Mutex::new(HotkeyBinding {
-                ctrl: true,
-                shift: false,
-                alt: true,
-                key: "KeyC".to_string(),
-            })),
+            ax_tree_snapshot_enabled: std::sync::Arc::new(std::sync::Mutex::new(false)),
+            smart_crop: std::sync::Arc::new(std::sync::Mutex::new(SmartCropSettings::default())),
+            duplicate_frames: std::sync::Arc::new(
+                std::sync::Mutex::new(DuplicateFrames::default()),
+            ),
+            keep_lossless_originals: std::sync::Arc::new(std::sync::Mutex::new(false)),
+            hotkeys: std::sync::Arc::new(std::sync::Mutex::new(default_bindings())),
+            hotkeys_suspended: std::sync::Arc::new(std::sync::Mutex::new(false)),
+        }
+    }
+}
+
+/// Modifier keys the input listener has seen pressed and not yet released
+#[derive(Default)]
+struct HeldModifiers {
+    ctrl: bool,
+    shift: bool,
+    alt: bool,
+    meta: bool,
+}
+
+impl HeldModifiers {
+    fn update(&mut self, key: rdev::Key, pressed: bool) {
+        match key {
+            rdev::Key::ControlLeft | rdev::Key::ControlRight => self.ctrl = pressed,
+            rdev::Key::ShiftLeft | rdev::Key::ShiftRight => self.shift = pressed,
+            rdev::Key::Alt | rdev::Key::AltGr => self.alt = pressed,
+            rdev::Key::MetaLeft | rdev::Key::MetaRight => self.meta = pressed,
+            _ => {}
+        }
+    }
+
+    /// `key` pressed with the held modifiers
+    fn binding(&self, key: &str) -> HotkeyBinding {
+        HotkeyBinding {
+            ctrl: self.ctrl,
+            shift: self.shift,
+            alt: self.alt,
+            meta: self.meta,
+            key: key.to_string(),
+            disabled: false,
         }
     }
 }
@@ -123,16 +431,24 @@ enum RecorderEvent {
 struct CaptureData {
     x: Option<i32>,
     y: Option<i32>,
-    image: Arc<image::DynamicImage>,
+    /// The captured frame, shared with the OCR queue and with a click that
+    /// flushes typed text rather than copied
+    image: Arc<image::RgbaImage>,
     timestamp: u64,
     step_type: String,
     text: Option<String>,
     element_info: Option<ElementInfo>,
+    /// Set when the element lookup timed out; see `Step::element_lookup`.
+    pending_element: Option<PendingElement>,
+    pending_subtree: Option<PendingSubtree>,
     /// Origin of `text` for type steps. See `Step::input_source`.
     input_source: Option<String>,
+    window: ForegroundWindow,
+    /// Display scale of the captured monitor; see `MonitorSpace`.
+    scale_factor: f32,
     /// Absolute screen coordinates of an anchor inside the captured monitor.
     /// Used by the after-frame thread to identify the same monitor 700ms
-    /// later via `Monitor::from_point`. For clicks this is the click position;
+    /// later via `get_monitor_at_point`. For clicks this is the click position;
     /// for type steps it's the foreground window's centre.
     anchor: Option<(f64, f64)>,
 }
@@ -140,22 +456,57 @@ struct CaptureData {
 /// Decide what `text` to record for a type-step flush. Prefers the focused
 /// field's AX value over the raw keystroke buffer — that handles autocomplete,
 /// paste, IME, and edit-then-retype patterns the keystroke stream gets wrong.
+/// Also returns the focused element itself (in screen space) when its lookup
+/// answers within `ELEMENT_LOOKUP_TIMEOUT`, so the step can name the field.
 /// Returns `None` if the field is a password (caller should drop the step).
-fn resolve_type_step_text(key_buffer_text: &str) -> Option<(String, &'static str)> {
-    match get_focused_field_value() {
-        Some(ffv) if ffv.is_password => None,
-        Some(ffv) if !ffv.value.is_empty() => Some((ffv.value, ffv.source)),
-        _ => Some((key_buffer_text.to_string(), "keystrokes")),
-    }
+fn resolve_type_step_text(
+    key_buffer_text: &str,
+) -> Option<(String, &'static str, Option<ElementInfo>)> {
+    let focused = spawn_focused_element_lookup();
+    let (text, source) = match get_focused_field_value() {
+        Some(ffv) if ffv.is_password => return None,
+        Some(ffv) if !ffv.value.is_empty() => (ffv.value, ffv.source),
+        _ => (key_buffer_text.to_string(), "keystrokes"),
+    };
+    let element = focused.recv_timeout(ELEMENT_LOOKUP_TIMEOUT).ok().flatten();
+    Some((text, source, element))
 }
 
-/// Data sent to OCR processing thread
-struct OcrData {
-    step_id: String,
-    image: Arc<image::DynamicImage>,
-    x: Option<i32>,
-    y: Option<i32>,
-    step_type: String,
+/// Origin of a monitor in absolute screen coordinates.
+fn monitor_origin(mon: &Monitor) -> (i32, i32) {
+    (mon.x().unwrap_or(0), mon.y().unwrap_or(0))
+}
+
+/// Write a JPEG of just the clicked control (`bounds`, monitor space) with a
+/// small margin, for exports to show as a zoomed inset. Skipped when the
+/// bounds fall outside the frame or cover most of it (a whole window or
+/// pane), where the crop would add nothing to the full screenshot.
+fn save_element_crop(
+    image: &image::RgbaImage,
+    bounds: ElementBounds,
+    path: &std::path::Path,
+) -> Option<String> {
+    let (width, height) = (image.width() as i32, image.height() as i32);
+    let (left, top) = crate::annotations::element_crop_origin(&bounds);
+    let right = (bounds.x + bounds.width + ELEMENT_CROP_PADDING).min(width);
+    let bottom = (bounds.y + bounds.height + ELEMENT_CROP_PADDING).min(height);
+    if bounds.width <= 0 || bounds.height <= 0 || right <= left || bottom <= top {
+        return None;
+    }
+    if (right - left) as i64 * (bottom - top) as i64 > width as i64 * height as i64 / 4 {
+        return None;
+    }
+
+    let crop = image::imageops::crop_imm(
+        image,
+        left as u32,
+        top as u32,
+        (right - left) as u32,
+        (bottom - top) as u32,
+    )
+    .to_image();
+    jpeg::save(&crop, path, 90).ok()?;
+    Some(path.to_string_lossy().to_string())
 }
 
 /// Centre of a monitor in absolute screen coordinates. Used as an anchor
@@ -217,7 +568,7 @@ fn capture_clip_gif(
     let mut frames: Vec<image::RgbaImage> = Vec::with_capacity(frame_count as usize);
     for _ in 0..frame_count {
         thread::sleep(Duration::from_millis(interval_ms));
-        if let Ok(img) = mon.capture_image() {
+        if let Ok(img) = capture_backend::capture(&mon) {
             // Downsample to half resolution before storing — GIF palette
             // encoding gets exponentially larger with dimensions.
             let (w, h) = (img.width() / 2, img.height() / 2);
@@ -271,7 +622,7 @@ fn capture_settled_frame(
     thread::sleep(Duration::from_millis(initial_wait_ms));
 
     let mon = get_monitor_at_point(anchor_x, anchor_y)?;
-    let mut prev = mon.capture_image().ok()?;
+    let mut prev = capture_backend::capture(&mon).ok()?;
     let mut total_waited = initial_wait_ms;
 
     loop {
@@ -281,7 +632,7 @@ fn capture_settled_frame(
         thread::sleep(Duration::from_millis(poll_interval_ms));
         total_waited += poll_interval_ms;
 
-        let next = match mon.capture_image() {
+        let next = match capture_backend::capture(&mon) {
             Ok(img) => img,
             // Capture failure on a later poll — return what we have rather than fail outright.
             Err(_) => return Some(prev),
@@ -297,19 +648,7 @@ fn capture_settled_frame(
 
 // Find the monitor that contains the given point
 fn get_monitor_at_point(x: f64, y: f64) -> Option<Monitor> {
-    // Primary: Use xcap's built-in method (handles DPI correctly on all platforms)
-    if let Ok(monitor) = Monitor::from_point(x as i32, y as i32) {
-        return Some(monitor);
-    }
-
-    // Fallback: Manual iteration (in case primary fails)
-    Monitor::all().ok()?.into_iter().find(|m| {
-        let mx = m.x().unwrap_or(0) as f64;
-        let my = m.y().unwrap_or(0) as f64;
-        let mw = m.width().unwrap_or(0) as f64;
-        let mh = m.height().unwrap_or(0) as f64;
-        x >= mx && x < mx + mw && y >= my && y < my + mh
-    })
+    monitor_cache::at_point(x, y)
 }
 
 // Get the monitor containing the currently focused/foreground window
@@ -378,7 +717,7 @@ fn get_monitor_for_foreground_window() -> Option<Monitor> {
     }
 
     // Fallback to primary monitor
-    Monitor::all().ok()?.into_iter().next()
+    monitor_cache::first()
 }
 
 #[cfg(target_os = "linux")]
@@ -443,7 +782,143 @@ fn get_monitor_for_foreground_window() -> Option<Monitor> {
     }
 
     // Fallback to primary monitor
-    Monitor::all().ok()?.into_iter().next()
+    monitor_cache::first()
+}
+
+// Get the process and title of the foreground window (recorded on each step)
+#[cfg(target_os = "windows")]
+fn get_foreground_window() -> Option<ForegroundWindow> {
+    use windows::core::PWSTR;
+    use windows::Win32::Foundation::CloseHandle;
+    use windows::Win32::System::Threading::{
+        OpenProcess, QueryFullProcessImageNameW, PROCESS_NAME_WIN32,
+        PROCESS_QUERY_LIMITED_INFORMATION,
+    };
+    use windows::Win32::UI::WindowsAndMessaging::{
+        GetForegroundWindow, GetWindowTextW, GetWindowThreadProcessId,
+    };
+
+    unsafe {
+        let hwnd = GetForegroundWindow();
+        if hwnd.0.is_null() {
+            return None;
+        }
+
+        let mut title = vec![0u16; 512];
+        let len = GetWindowTextW(hwnd, &mut title).max(0) as usize;
+        let window_title = String::from_utf16_lossy(&title[..len]);
+
+        let mut pid = 0u32;
+        GetWindowThreadProcessId(hwnd, Some(&mut pid));
+        // Limited rights are enough for the image name, including for
+        // elevated processes.
+        let exe_path = OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, false, pid)
+            .ok()
+            .and_then(|process| {
+                let mut buffer = vec![0u16; 1024];
+                let mut size = buffer.len() as u32;
+                let result = QueryFullProcessImageNameW(
+                    process,
+                    PROCESS_NAME_WIN32,
+                    PWSTR(buffer.as_mut_ptr()),
+                    &mut size,
+                );
+                let _ = CloseHandle(process);
+                result
+                    .ok()
+                    .map(|_| String::from_utf16_lossy(&buffer[..size as usize]))
+            });
+
+        Some(ForegroundWindow::new(exe_path, Some(window_title)))
+    }
+}
+
+#[cfg(target_os = "macos")]
+fn get_foreground_window() -> Option<ForegroundWindow> {
+    use core_foundation::base::{CFType, TCFType};
+    use core_foundation::dictionary::{CFDictionary, CFDictionaryRef};
+    use core_foundation::number::CFNumber;
+    use core_foundation::string::{CFString, CFStringRef};
+    use core_graphics::window::{
+        copy_window_info, kCGNullWindowID, kCGWindowLayer, kCGWindowListExcludeDesktopElements,
+        kCGWindowListOptionOnScreenOnly, kCGWindowName, kCGWindowOwnerPID,
+    };
+
+    const PROC_PIDPATHINFO_MAXSIZE: usize = 4096;
+
+    extern "C" {
+        fn proc_pidpath(pid: i32, buffer: *mut std::ffi::c_void, buffersize: u32) -> i32;
+    }
+
+    let windows = copy_window_info(
+        kCGWindowListOptionOnScreenOnly | kCGWindowListExcludeDesktopElements,
+        kCGNullWindowID,
+    )?;
+    let key = |key: CFStringRef| unsafe { CFString::wrap_under_get_rule(key) };
+
+    // On-screen windows are listed front to back, so the first one in the
+    // normal window layer belongs to the frontmost application. The title
+    // needs the Screen Recording permission, which capture already requires.
+    let (pid, window_title) = windows.iter().find_map(|item| {
+        let info = unsafe {
+            CFDictionary::<CFString, CFType>::wrap_under_get_rule(*item as CFDictionaryRef)
+        };
+        let number = |name: CFStringRef| {
+            info.find(&key(name))
+                .and_then(|value| value.downcast::<CFNumber>())
+                .and_then(|number| number.to_i64())
+        };
+        if number(unsafe { kCGWindowLayer }) != Some(0) {
+            return None;
+        }
+        let pid = number(unsafe { kCGWindowOwnerPID })?;
+        let title = info
+            .find(&key(unsafe { kCGWindowName }))
+            .and_then(|value| value.downcast::<CFString>())
+            .map(|title| title.to_string());
+        Some((pid as i32, title))
+    })?;
+
+    let mut buffer = vec![0u8; PROC_PIDPATHINFO_MAXSIZE];
+    let len = unsafe { proc_pidpath(pid, buffer.as_mut_ptr().cast(), buffer.len() as u32) };
+    let exe_path = (len > 0).then(|| String::from_utf8_lossy(&buffer[..len as usize]).to_string());
+
+    Some(ForegroundWindow::new(exe_path, window_title))
+}
+
+#[cfg(target_os = "linux")]
+fn get_foreground_window() -> Option<ForegroundWindow> {
+    use crate::display::{detect_display_server, DisplayServer};
+    use std::process::Command;
+
+    // Wayland has no standardized way to query the active window.
+    if detect_display_server() != DisplayServer::X11 {
+        return None;
+    }
+
+    // Chained: prints the active window's PID, then its title.
+    let output = Command::new("xdotool")
+        .args(["getactivewindow", "getwindowpid", "getwindowname"])
+        .output()
+        .ok()
+        .filter(|output| output.status.success())?;
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let mut lines = stdout.lines();
+    let pid: u32 = lines.next()?.trim().parse().ok()?;
+    let window_title = lines.next().map(|title| title.trim().to_string());
+
+    let exe_path = fs::read_link(format!("/proc/{}/exe", pid))
+        .ok()
+        .map(|path| path.to_string_lossy().to_string());
+    let mut window = ForegroundWindow::new(exe_path, window_title);
+    // The exe link is unreadable for other users' processes; comm is not.
+    if window.process_name.is_none() {
+        window.process_name = fs::read_to_string(format!("/proc/{}/comm", pid))
+            .ok()
+            .map(|comm| comm.trim().to_string())
+            .filter(|comm| !comm.is_empty());
+    }
+    Some(window)
 }
 
 // Get the app name/title of the foreground window (for filtering self-interactions)
@@ -533,14 +1008,227 @@ fn get_foreground_window_app_name() -> Option<String> {
     None
 }
 
+/// Whether a click at (`x`, `y`) lands on one of StepSnap's own windows,
+/// judged by the owning process rather than the element lookup, which may
+/// not have answered yet
+fn is_own_window_at(x: f64, y: f64) -> bool {
+    window_pid_at(x, y) == Some(std::process::id())
+}
+
+// Process owning the top-level window at a point
+#[cfg(target_os = "windows")]
+fn window_pid_at(x: f64, y: f64) -> Option<u32> {
+    use windows::Win32::Foundation::POINT;
+    use windows::Win32::UI::WindowsAndMessaging::{
+        GetAncestor, GetWindowThreadProcessId, WindowFromPoint, GA_ROOT,
+    };
+
+    unsafe {
+        let hwnd = WindowFromPoint(POINT {
+            x: x as i32,
+            y: y as i32,
+        });
+        if hwnd.0.is_null() {
+            return None;
+        }
+        let mut pid = 0u32;
+        GetWindowThreadProcessId(GetAncestor(hwnd, GA_ROOT), Some(&mut pid));
+        (pid != 0).then_some(pid)
+    }
+}
+
+#[cfg(target_os = "macos")]
+fn window_pid_at(x: f64, y: f64) -> Option<u32> {
+    use core_foundation::base::{CFType, TCFType};
+    use core_foundation::dictionary::{CFDictionary, CFDictionaryRef};
+    use core_foundation::number::CFNumber;
+    use core_foundation::string::{CFString, CFStringRef};
+    use core_graphics::window::{
+        copy_window_info, kCGNullWindowID, kCGWindowBounds, kCGWindowListExcludeDesktopElements,
+        kCGWindowListOptionOnScreenOnly, kCGWindowOwnerPID,
+    };
+
+    let windows = copy_window_info(
+        kCGWindowListOptionOnScreenOnly | kCGWindowListExcludeDesktopElements,
+        kCGNullWindowID,
+    )?;
+    let key = |key: CFStringRef| unsafe { CFString::wrap_under_get_rule(key) };
+    let number = |info: &CFDictionary<CFString, CFType>, name: CFString| {
+        info.find(&name)
+            .and_then(|value| value.downcast::<CFNumber>())
+            .and_then(|number| number.to_f64())
+    };
+
+    // Listed front to back, floating panels such as the recorder toolbar
+    // included, so the first window containing the point is the one clicked
+    windows.iter().find_map(|item| {
+        let info = unsafe {
+            CFDictionary::<CFString, CFType>::wrap_under_get_rule(*item as CFDictionaryRef)
+        };
+        let bounds = info
+            .find(&key(unsafe { kCGWindowBounds }))
+            .and_then(|value| value.downcast::<CFDictionary>())?;
+        let bounds = unsafe {
+            CFDictionary::<CFString, CFType>::wrap_under_get_rule(bounds.as_concrete_TypeRef())
+        };
+        let field = |name: &str| number(&bounds, CFString::new(name));
+        let (left, top) = (field("X")?, field("Y")?);
+        let (width, height) = (field("Width")?, field("Height")?);
+        if x < left || y < top || x >= left + width || y >= top + height {
+            return None;
+        }
+        number(&info, key(unsafe { kCGWindowOwnerPID })).map(|pid| pid as u32)
+    })
+}
+
+#[cfg(target_os = "linux")]
+fn window_pid_at(_x: f64, _y: f64) -> Option<u32> {
+    use crate::display::{detect_display_server, DisplayServer};
+    use std::process::Command;
+
+    // X11 only; the pointer is still where the press happened
+    if detect_display_server() != DisplayServer::X11 {
+        return None;
+    }
+    let xdotool = |args: &[&str]| {
+        Command::new("xdotool")
+            .args(args)
+            .output()
+            .ok()
+            .filter(|output| output.status.success())
+            .map(|output| String::from_utf8_lossy(&output.stdout).to_string())
+    };
+    let window = xdotool(&["getmouselocation", "--shell"])?
+        .lines()
+        .find_map(|line| line.strip_prefix("WINDOW=").map(str::to_string))?;
+    // Windows without _NET_WM_PID fall back to the active window
+    xdotool(&["getwindowpid", &window])
+        .or_else(|| xdotool(&["getactivewindow", "getwindowpid"]))?
+        .trim()
+        .parse()
+        .ok()
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "macos", target_os = "linux")))]
+fn window_pid_at(_x: f64, _y: f64) -> Option<u32> {
+    None
+}
+
+/// Result reported for a job dropped from a full OCR queue
+pub fn skipped_ocr_result(job: QueuedOcrJob) -> OcrJobResult {
+    OcrJobResult {
+        step_id: job.step_id,
+        ocr_text: None,
+        ocr_lines: None,
+        status: "skipped".to_string(),
+    }
+}
+
+/// Add a marker step labelled `label` to the recording in progress, the
+/// same way captured steps arrive, and return its id
+pub fn emit_marker_step(app: &AppHandle, label: &str) -> String {
+    let step = Step {
+        id: Uuid::new_v4().to_string(),
+        type_: STEP_TYPE_MARKER.to_string(),
+        x: None,
+        y: None,
+        text: Some(label.to_string()),
+        timestamp: SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_millis() as u64,
+        screenshot: None,
+        element_screenshot: None,
+        screenshot_original: None,
+        element: StepElement::default(),
+        window: get_foreground_window().unwrap_or_default(),
+        element_lookup: None,
+        input_source: None,
+        is_cropped: false,
+        click_marker: None,
+        scale_factor: 1.0,
+        is_duplicate: false,
+    };
+
+    if let Ok(step_json) = serde_json::to_string(&step) {
+        journal_draft(app, |db| db.save_draft_step(&step.id, &step_json));
+    }
+    let id = step.id.clone();
+    let _ = app.emit("new-step", step);
+    id
+}
+
+/// Run one queued OCR job on a worker with the shared engine, loading it
+/// first when needed. Returns `None` when OCR is turned off or unavailable.
+fn run_queued_ocr(
+    app: &AppHandle,
+    queued: QueuedOcrJob,
+    ocr_enabled: &std::sync::Mutex<bool>,
+    ocr_language: &std::sync::Mutex<String>,
+    ocr_config: &std::sync::Mutex<OcrConfig>,
+    ocr_unavailable: &AtomicBool,
+) -> Option<OcrJobResult> {
+    // Check if OCR is enabled
+    if !*ocr_enabled.lock().unwrap() {
+        return None;
+    }
+
+    // Loads models when the startup warm-up hasn't yet, and picks up models
+    // downloaded or updated and settings changed since the engine was built
+    let language = ocr_language.lock().unwrap().clone();
+    let config = ocr_config.lock().unwrap().clone();
+    let manager = ocr::shared_manager(app, &config, &language);
+    ocr_unavailable.store(!manager.is_enabled(), Ordering::Relaxed);
+    if !manager.is_enabled() {
+        return None;
+    }
+
+    let image = match queued.image {
+        OcrImage::Loaded(image) => image,
+        OcrImage::File(path) => match image::open(&path) {
+            Ok(image) => Arc::new(image.into_rgba8()),
+            Err(e) => {
+                tracing::warn!(target: "ocr", step_id = %queued.step_id, "Failed to read {:?} for OCR: {}", path, e);
+                return Some(OcrJobResult {
+                    step_id: queued.step_id,
+                    ocr_text: None,
+                    ocr_lines: None,
+                    status: "failed".to_string(),
+                });
+            }
+        },
+    };
+
+    let job = OcrJob {
+        step_id: queued.step_id,
+        image,
+        x: queued.x,
+        y: queued.y,
+        step_type: queued.step_type,
+        language,
+    };
+    Some(manager.process_job(job))
+}
+
 pub fn start_listener(
     app: AppHandle,
     is_recording: std::sync::Arc<std::sync::Mutex<bool>>,
     is_picker_open: std::sync::Arc<std::sync::Mutex<bool>>,
     ocr_enabled: std::sync::Arc<std::sync::Mutex<bool>>,
+    ocr_language: std::sync::Arc<std::sync::Mutex<String>>,
+    ocr_queue: std::sync::Arc<OcrQueue>,
+    ocr_config: std::sync::Arc<std::sync::Mutex<OcrConfig>>,
     state_diff_enabled: std::sync::Arc<std::sync::Mutex<bool>>,
     after_frame_max_wait_ms: std::sync::Arc<std::sync::Mutex<u64>>,
     video_clips_enabled: std::sync::Arc<std::sync::Mutex<bool>>,
+    ax_tree_snapshot_enabled: std::sync::Arc<std::sync::Mutex<bool>>,
+    smart_crop: std::sync::Arc<std::sync::Mutex<SmartCropSettings>>,
+    duplicate_frames: std::sync::Arc<std::sync::Mutex<DuplicateFrames>>,
+    keep_lossless_originals: std::sync::Arc<std::sync::Mutex<bool>>,
+    hotkeys: std::sync::Arc<
+        std::sync::Mutex<std::collections::HashMap<HotkeyAction, HotkeyBinding>>,
+    >,
+    hotkeys_suspended: std::sync::Arc<std::sync::Mutex<bool>>,
     startup_state: StartupState,
 ) {
     // Channel 1: Listener -> Capture Logic
@@ -549,16 +1237,13 @@ pub fn start_listener(
     // Channel 2: Capture Logic -> Encoder
     let (tx_encode, rx_encode) = mpsc::channel::<CaptureData>();
 
-    // Channel 3: Encoder -> OCR Processor
-    let (tx_ocr, rx_ocr) = mpsc::channel::<OcrData>();
-
     let app_clone = app.clone();
-    let app_clone_ocr = app.clone();
-    let ocr_enabled_clone = ocr_enabled.clone();
     let state_diff_enabled_clone = state_diff_enabled.clone();
     let after_frame_max_wait_clone = after_frame_max_wait_ms.clone();
     let video_clips_enabled_clone = video_clips_enabled.clone();
-    let startup_state_ocr = startup_state.clone();
+    // Set when the OCR models fail to load; clicks then fall back to
+    // accessibility labels for their context text.
+    let ocr_unavailable = Arc::new(AtomicBool::new(false));
 
     emit_startup_status(
         &app,
@@ -569,59 +1254,80 @@ pub fn start_listener(
     // Note: Capture hotkey is now handled by the frontend (monitor picker UI)
     // The old capture event listener has been removed
 
-    // Thread 4: OCR Processor (processes screenshots asynchronously)
-    thread::spawn(move || {
-        // Get models directory and initialize OCR engine
-        emit_startup_status(
-            &app_clone_ocr,
-            &startup_state_ocr,
-            StartupStatus::running("ocr", "Loading OCR models"),
-        );
-        let models_dir = get_models_dir(&app_clone_ocr);
-        let ocr_manager = match OcrManager::new(models_dir.clone(), OcrConfig::default()) {
-            Ok(m) => {
-                println!("OCR engine initialized successfully from {:?}", models_dir);
-                emit_startup_status(
-                    &app_clone_ocr,
-                    &startup_state_ocr,
-                    StartupStatus::success("ocr", "OCR ready"),
-                );
-                m
-            }
-            Err(e) => {
-                eprintln!(
-                    "Failed to initialize OCR engine: {}. OCR will be disabled.",
-                    e
-                );
+    // Thread 5: OCR warm-up. Loads the shared engine and runs it once after
+    // startup, so neither launch nor the first OCR job waits on it.
+    {
+        let app = app.clone();
+        let ocr_enabled = ocr_enabled.clone();
+        let ocr_language = ocr_language.clone();
+        let ocr_config = ocr_config.clone();
+        let ocr_unavailable = ocr_unavailable.clone();
+        let startup_state = startup_state.clone();
+        thread::spawn(move || {
+            // Let the rest of startup have the CPU first
+            thread::sleep(OCR_WARM_UP_DELAY);
+            if !*ocr_enabled.lock().unwrap() {
                 emit_startup_status(
-                    &app_clone_ocr,
-                    &startup_state_ocr,
-                    StartupStatus::failed("ocr", "OCR unavailable"),
+                    &app,
+                    &startup_state,
+                    StartupStatus::success("ocr", "OCR disabled in settings"),
                 );
-                OcrManager::disabled()
+                return;
             }
-        };
-
-        for ocr_data in rx_ocr {
-            // Check if OCR is enabled
-            if !*ocr_enabled_clone.lock().unwrap() || !ocr_manager.is_enabled() {
-                continue;
-            }
-
-            let job = OcrJob {
-                step_id: ocr_data.step_id.clone(),
-                image: ocr_data.image,
-                x: ocr_data.x,
-                y: ocr_data.y,
-                step_type: ocr_data.step_type,
+            emit_startup_status(
+                &app,
+                &startup_state,
+                StartupStatus::running("ocr", "Loading OCR models"),
+            );
+            let language = ocr_language.lock().unwrap().clone();
+            let config = ocr_config.lock().unwrap().clone();
+            let manager = ocr::shared_manager(&app, &config, &language);
+            ocr_unavailable.store(!manager.is_enabled(), Ordering::Relaxed);
+            let status = if manager.is_enabled() {
+                StartupStatus::success("ocr", "OCR ready")
+            } else {
+                StartupStatus::failed("ocr", "OCR unavailable")
             };
+            emit_startup_status(&app, &startup_state, status);
+        });
+    }
 
-            let result = ocr_manager.process_job(&job);
+    // Thread 4: OCR workers (process queued screenshots asynchronously).
+    // The queue decides how many of them run at once.
+    for _ in 0..MAX_OCR_WORKERS {
+        let app = app.clone();
+        let queue = ocr_queue.clone();
+        let is_recording = is_recording.clone();
+        let ocr_enabled = ocr_enabled.clone();
+        let ocr_language = ocr_language.clone();
+        let ocr_config = ocr_config.clone();
+        let ocr_unavailable = ocr_unavailable.clone();
+        thread::spawn(move || {
+            loop {
+                let queued = queue.next(&is_recording);
+                if *ocr_enabled.lock().unwrap() {
+                    if let Some(progress) = queue.take_batch_start() {
+                        let _ = app.emit("ocr-started", progress);
+                    }
+                }
+                let result = run_queued_ocr(
+                    &app,
+                    queued,
+                    &ocr_enabled,
+                    &ocr_language,
+                    &ocr_config,
+                    &ocr_unavailable,
+                );
+                let progress = queue.finish();
 
-            // Emit OCR result to frontend
-            let _ = app_clone_ocr.emit("ocr-result", &result);
-        }
-    });
+                // Emit OCR result to frontend
+                if let Some(result) = result {
+                    let _ = app.emit("ocr-step-complete", &result);
+                    let _ = app.emit("ocr-progress", progress);
+                }
+            }
+        });
+    }
 
     // Thread 3: Encoder/Emitter (Write to temp files - much faster than base64)
     thread::spawn(move || {
@@ -629,28 +1335,103 @@ pub fn start_listener(
         let temp_dir = std::env::temp_dir().join("stepsnap_screenshots");
         let _ = fs::create_dir_all(&temp_dir);
 
-        for data in rx_encode {
-            let mut rgb_image = data.image.to_rgb8();
-
-            // Draw click highlight if this is a click step
-            if data.step_type == "click" {
-                if let (Some(x), Some(y)) = (data.x, data.y) {
-                    let cx = x;
-                    let cy = y;
+        let mut session = RECORDING_SESSION.load(Ordering::SeqCst);
+        let mut previous_frame: Option<WrittenFrame> = None;
 
-                    // Colors for highlight
-                    let outer_color = Rgb([255u8, 69u8, 0u8]); // Orange-red
-                    let inner_color = Rgb([255u8, 0u8, 0u8]); // Red
+        for data in rx_encode {
+            // Smart crop around the element, or the click when it's unknown.
+            // The element crop and OCR still read the full frame.
+            let crop = *smart_crop.lock().unwrap();
+            let focus = match (crop.mode, data.step_type.as_str()) {
+                (SmartCropMode::Capture, "click" | "type") => focus_rect(
+                    data.image.width(),
+                    data.image.height(),
+                    data.element_info.as_ref().and_then(|info| info.bounds),
+                    data.x.zip(data.y),
+                    (crop.padding as f32 * data.scale_factor).round() as u32,
+                ),
+                _ => None,
+            };
+            // Only a crop is copied; an uncropped frame is hashed and
+            // encoded where it is
+            let frame: Cow<image::RgbaImage> = match focus {
+                Some(focus) => Cow::Owned(
+                    image::imageops::crop_imm(
+                        &*data.image,
+                        focus.x,
+                        focus.y,
+                        focus.width,
+                        focus.height,
+                    )
+                    .to_image(),
+                ),
+                None => Cow::Borrowed(&*data.image),
+            };
 
-                    // Draw outer ring (multiple circles for thickness)
-                    for r in 30..=35 {
-                        draw_hollow_circle_mut(&mut rgb_image, (cx, cy), r, outer_color);
-                    }
+            // The click highlight is drawn when the step is shown or exported,
+            // so only its position in the saved image is kept
+            let click_marker = match (data.step_type.as_str(), data.x, data.y) {
+                ("click", Some(x), Some(y)) => {
+                    let (left, top) = focus.map_or((0, 0), |focus| (focus.x, focus.y));
+                    let (x, y) = (x - left as i32, y - top as i32);
+                    let inside = (0..frame.width() as i32).contains(&x)
+                        && (0..frame.height() as i32).contains(&y);
+                    inside.then_some(Point {
+                        x: x as f32,
+                        y: y as f32,
+                    })
+                }
+                _ => None,
+            };
 
-                    // Draw inner filled dot
-                    draw_filled_circle_mut(&mut rgb_image, (cx, cy), 5, inner_color);
+            // A frame nearly identical to the previous step's reuses its
+            // screenshot, or drops the click when the user asked to skip them.
+            // Both must be cropped alike for the click marker to line up. The
+            // JPEG is copied rather than shared so deleting or moving one
+            // step's file never breaks another; the object store keeps the
+            // identical bytes once.
+            let current_session = RECORDING_SESSION.load(Ordering::SeqCst);
+            if current_session != session {
+                session = current_session;
+                previous_frame = None;
+            }
+            // A click flushing typed text shares the type step's capture.
+            // Its files are reused the same way, without encoding the frame
+            // again, but it's no repeat: the user did click.
+            let shared = previous_frame.as_ref().filter(|previous| {
+                previous.focus == focus
+                    && previous
+                        .image
+                        .upgrade()
+                        .is_some_and(|image| Arc::ptr_eq(&image, &data.image))
+            });
+            let duplicates = *duplicate_frames.lock().unwrap();
+            let hash =
+                (duplicates != DuplicateFrames::Off && shared.is_none()).then(|| dhash(&*frame));
+            let repeated = match (hash, &previous_frame) {
+                (Some(hash), Some(previous))
+                    if previous.focus == focus
+                        && previous
+                            .hash
+                            .is_some_and(|previous| is_duplicate(hash, previous)) =>
+                {
+                    Some(previous.path.clone())
                 }
+                _ => None,
+            };
+            if repeated.is_some()
+                && duplicates == DuplicateFrames::Skip
+                && data.step_type == "click"
+            {
+                continue;
             }
+            let shared = shared.map(|previous| {
+                (
+                    previous.hash,
+                    previous.path.clone(),
+                    previous.original.clone(),
+                )
+            });
 
             // Generate unique step ID for tracking OCR results
             let step_id = Uuid::new_v4().to_string();
@@ -661,28 +1442,86 @@ pub fn start_listener(
             let file_path = temp_dir.join(&filename);
 
             // Write directly to file (faster than base64 encoding + memory)
-            let screenshot_path = if let Ok(file) = fs::File::create(&file_path) {
-                let mut writer = BufWriter::new(file);
-                let mut encoder = JpegEncoder::new_with_quality(&mut writer, 85);
-
-                if encoder.encode_image(&rgb_image).is_ok() {
-                    Some(file_path.to_string_lossy().to_string())
-                } else {
-                    None
-                }
+            let reused = repeated.is_some_and(|previous| fs::copy(previous, &file_path).is_ok());
+            let shared = shared
+                .filter(|(_, previous, _)| fs::copy(previous, &file_path).is_ok())
+                .map(|(hash, _, original)| (hash, original));
+            let screenshot_path = if reused || shared.is_some() {
+                Some(file_path.to_string_lossy().to_string())
             } else {
-                None
+                let encode_started = Instant::now();
+                match jpeg::save(&*frame, &file_path, 85) {
+                    Ok(()) => {
+                        analytics::measure(METRIC_SCREENSHOT_ENCODE, encode_started.elapsed());
+                        Some(file_path.to_string_lossy().to_string())
+                    }
+                    Err(e) => {
+                        tracing::warn!("Failed to save screenshot: {}", e);
+                        None
+                    }
+                }
             };
 
-            // Send to OCR thread for async processing (non-blocking)
-            let _ = tx_ocr.send(OcrData {
-                step_id: step_id.clone(),
-                image: data.image.clone(),
-                x: data.x,
-                y: data.y,
-                step_type: data.step_type.clone(),
-            });
+            // Repeated frames reuse the previous JPEG and have no original;
+            // shared ones reuse the previous original too
+            let screenshot_original = match (&screenshot_path, &shared) {
+                (Some(_), Some((_, Some(previous)))) => {
+                    let original_path = temp_dir.join(format!(
+                        "screenshot_{}_{}_original.png",
+                        data.timestamp, counter
+                    ));
+                    fs::copy(previous, &original_path)
+                        .ok()
+                        .map(|_| original_path.to_string_lossy().to_string())
+                }
+                (Some(_), None) if !reused && *keep_lossless_originals.lock().unwrap() => {
+                    let original_path = temp_dir.join(format!(
+                        "screenshot_{}_{}_original.png",
+                        data.timestamp, counter
+                    ));
+                    // Saved without the capture's opaque alpha channel
+                    let rgb: image::RgbImage = frame.convert();
+                    rgb.save_with_format(&original_path, image::ImageFormat::Png)
+                        .ok()
+                        .map(|_| original_path.to_string_lossy().to_string())
+                }
+                _ => None,
+            };
+
+            if let Some(path) = &screenshot_path {
+                previous_frame = Some(WrittenFrame {
+                    image: Arc::downgrade(&data.image),
+                    hash: shared.as_ref().map_or(hash, |(hash, _)| *hash),
+                    focus,
+                    path: path.clone(),
+                    original: screenshot_original.clone(),
+                });
+            }
 
+            let element_screenshot = match (&data.element_info, data.step_type.as_str()) {
+                (
+                    Some(ElementInfo {
+                        bounds: Some(bounds),
+                        ..
+                    }),
+                    "click",
+                ) => save_element_crop(
+                    &data.image,
+                    *bounds,
+                    &temp_dir.join(format!(
+                        "screenshot_{}_{}_element.jpg",
+                        data.timestamp, counter
+                    )),
+                ),
+                _ => None,
+            };
+
+            let is_click = data.step_type == "click";
+            let element = match data.element_info.as_ref() {
+                Some(info) if is_click => click_element(info),
+                Some(info) => StepElement::from(info),
+                None => StepElement::default(),
+            };
             let step = Step {
                 id: step_id.clone(),
                 type_: data.step_type.clone(),
@@ -691,15 +1530,121 @@ pub fn start_listener(
                 text: data.text,
                 timestamp: data.timestamp,
                 screenshot: screenshot_path,
-                element_name: data.element_info.as_ref().map(|e| e.name.clone()),
-                element_type: data.element_info.as_ref().map(|e| e.element_type.clone()),
-                element_value: data.element_info.as_ref().and_then(|e| e.value.clone()),
-                app_name: data.element_info.as_ref().and_then(|e| e.app_name.clone()),
+                element_screenshot,
+                screenshot_original,
+                element: element.clone(),
+                window: data.window,
+                element_lookup: data.pending_element.as_ref().map(|_| "timeout"),
                 input_source: data.input_source,
+                is_cropped: focus.is_some(),
+                click_marker,
+                scale_factor: data.scale_factor,
+                is_duplicate: reused,
             };
 
+            if let Ok(step_json) = serde_json::to_string(&step) {
+                journal_draft(&app_clone, |db| db.save_draft_step(&step.id, &step_json));
+            }
+            analytics::track("step_captured");
             let _ = app_clone.emit("new-step", step);
 
+            // Queue for the OCR workers after the step exists on the frontend, so a
+            // skipped result for this step has somewhere to land (non-blocking)
+            let evicted = ocr_queue.push(QueuedOcrJob {
+                step_id: step_id.clone(),
+                image: OcrImage::Loaded(data.image.clone()),
+                x: data.x,
+                y: data.y,
+                step_type: data.step_type.clone(),
+                priority: OcrPriority::Live,
+            });
+            if let Some(evicted) = evicted {
+                let _ = app_clone.emit("ocr-step-complete", skipped_ocr_result(evicted));
+            }
+
+            if let Some(pending) = data.pending_element {
+                let app_element = app_clone.clone();
+                let element_step_id = step_id.clone();
+                thread::spawn(move || {
+                    if let Ok(Some(info)) = pending.rx.recv_timeout(LATE_ELEMENT_LOOKUP_LIMIT) {
+                        let element = click_element(&to_monitor_space(info, pending.space));
+                        let _ = app_element.emit(
+                            "new-step-element",
+                            serde_json::json!({
+                                "step_id": element_step_id,
+                                "element": element,
+                            }),
+                        );
+                    }
+                });
+            }
+
+            // The subtree snapshot follows as a partial `new-step-element`,
+            // and its labels as an `ocr-step-complete` when OCR is not running.
+            if let Some(pending) = data.pending_subtree {
+                let app_subtree = app_clone.clone();
+                let subtree_step_id = step_id.clone();
+                thread::spawn(move || {
+                    let Ok(Some(mut node)) = pending.rx.recv_timeout(LATE_ELEMENT_LOOKUP_LIMIT)
+                    else {
+                        return;
+                    };
+                    node.map_bounds(&|bounds| pending.space.bounds(bounds));
+                    if pending.context_text {
+                        let _ = app_subtree.emit(
+                            "ocr-step-complete",
+                            OcrJobResult {
+                                step_id: subtree_step_id.clone(),
+                                ocr_text: node.context_text(),
+                                ocr_lines: None,
+                                status: "accessibility".to_string(),
+                            },
+                        );
+                    }
+                    if pending.store {
+                        if let Ok(json) = serde_json::to_string(&node) {
+                            let _ = app_subtree.emit(
+                                "new-step-element",
+                                serde_json::json!({
+                                    "step_id": subtree_step_id,
+                                    "element": { "ax_tree_json": json },
+                                }),
+                            );
+                        }
+                    }
+                });
+            }
+
+            // Re-read controls whose state the click changes once the UI has
+            // settled, and send the refined action as a `new-step-element`.
+            if let (true, Some(before), Some((x, y))) =
+                (is_click, data.element_info.clone(), data.anchor)
+            {
+                if reads_state_after_click(&before) {
+                    let app_action = app_clone.clone();
+                    let action_step_id = step_id.clone();
+                    let mut element = element;
+                    thread::spawn(move || {
+                        thread::sleep(ACTION_SETTLE_DELAY);
+                        let after = spawn_element_lookup(x, y)
+                            .recv_timeout(ELEMENT_LOOKUP_TIMEOUT)
+                            .ok()
+                            .flatten();
+                        let action = infer_click_action(Some(&before), after.as_ref());
+                        if action.is_some() && action != element.action {
+                            element.action = action;
+                            let _ = app_action.emit(
+                                "new-step-element",
+                                serde_json::json!({
+                                    "step_id": action_step_id,
+                                    "element": element,
+                                }),
+                            );
+                        }
+                    });
+                }
+            }
+
             // Schedule a one-shot after-frame capture, so the AI prompt can see
             // what changed on screen after the action. Skipped for `capture`
             // (manual verification) steps and when the user has disabled
@@ -728,7 +1673,6 @@ pub fn start_listener(
                             None => return,
                         };
 
-                        let rgb_image = image::DynamicImage::ImageRgba8(image).to_rgb8();
                         let after_counter = SCREENSHOT_COUNTER.fetch_add(1, Ordering::SeqCst);
                         let after_filename = format!(
                             "screenshot_{}_{}_after.jpg",
@@ -739,23 +1683,18 @@ pub fn start_listener(
                             after_counter,
                         );
                         let after_path = temp_dir_after.join(&after_filename);
-                        let after_file = match fs::File::create(&after_path) {
-                            Ok(f) => f,
-                            Err(_) => return,
-                        };
-                        let mut after_writer = BufWriter::new(after_file);
-                        let mut after_encoder =
-                            JpegEncoder::new_with_quality(&mut after_writer, 85);
-                        if after_encoder.encode_image(&rgb_image).is_err() {
-                            return;
-                        }
-                        // Important: flush+close the writer before emitting so
-                        // the frontend can read the file immediately.
-                        drop(after_encoder);
-                        if after_writer.into_inner().is_err() {
+                        // Written in full before emitting so the frontend
+                        // can read the file immediately
+                        if jpeg::save(&image, &after_path, 85).is_err() {
                             return;
                         }
 
+                        journal_draft(&app_after, |db| {
+                            db.update_draft_step_after(
+                                &after_step_id,
+                                &after_path.to_string_lossy(),
+                            )
+                        });
                         let _ = app_after.emit(
                             "new-step-after",
                             serde_json::json!({
@@ -788,6 +1727,9 @@ pub fn start_listener(
                         ) {
                             return;
                         }
+                        journal_draft(&app_clip, |db| {
+                            db.update_draft_step_clip(&clip_step_id, &clip_path.to_string_lossy())
+                        });
                         let _ = app_clip.emit(
                             "new-step-clip",
                             serde_json::json!({
@@ -846,22 +1788,28 @@ pub fn start_listener(
                             key_buffer.clear();
                             last_key_time = None;
                         }
-                        Some((final_text, source)) => {
+                        Some((final_text, source, focused)) => {
                             if let Some(mon) = get_monitor_for_foreground_window() {
-                                if let Ok(image) = mon.capture_image() {
+                                if let Ok(image) = capture_backend::capture(&mon) {
                                     let anchor = monitor_center(&mon);
+                                    let space = MonitorSpace::of(&mon, image.width());
                                     let _ = tx_encode.send(CaptureData {
                                         x: None,
                                         y: None,
-                                        image: Arc::new(image::DynamicImage::ImageRgba8(image)),
+                                        image: Arc::new(image),
                                         timestamp: SystemTime::now()
                                             .duration_since(SystemTime::UNIX_EPOCH)
                                             .unwrap_or_default()
                                             .as_millis() as u64,
                                         step_type: "type".to_string(),
                                         text: Some(final_text),
-                                        element_info: None,
+                                        element_info: focused
+                                            .map(|info| to_monitor_space(info, space)),
+                                        pending_element: None,
+                                        pending_subtree: None,
                                         input_source: Some(source.to_string()),
+                                        window: get_foreground_window().unwrap_or_default(),
+                                        scale_factor: space.scale_factor,
                                         anchor,
                                     });
                                     key_buffer.clear();
@@ -924,14 +1872,15 @@ pub fn start_listener(
                                 key_buffer.clear();
                                 last_key_time = None;
                             }
-                            Some((final_text, source)) => {
+                            Some((final_text, source, focused)) => {
                                 if let Some(mon) = get_monitor_for_foreground_window() {
-                                    if let Ok(image) = mon.capture_image() {
+                                    if let Ok(image) = capture_backend::capture(&mon) {
                                         let anchor = monitor_center(&mon);
+                                        let space = MonitorSpace::of(&mon, image.width());
                                         let _ = tx_encode.send(CaptureData {
                                             x: None,
                                             y: None,
-                                            image: Arc::new(image::DynamicImage::ImageRgba8(image)),
+                                            image: Arc::new(image),
                                             timestamp: SystemTime::now()
                                                 .duration_since(SystemTime::UNIX_EPOCH)
                                                 .unwrap_or_default()
@@ -939,8 +1888,13 @@ pub fn start_listener(
                                                 as u64,
                                             step_type: "type".to_string(),
                                             text: Some(final_text),
-                                            element_info: None,
+                                            element_info: focused
+                                                .map(|info| to_monitor_space(info, space)),
+                                            pending_element: None,
+                                            pending_subtree: None,
                                             input_source: Some(source.to_string()),
+                                            window: get_foreground_window().unwrap_or_default(),
+                                            scale_factor: space.scale_factor,
                                             anchor,
                                         });
                                         key_buffer.clear();
@@ -967,11 +1921,36 @@ pub fn start_listener(
                     last_click_time = Some(now);
                     last_click_pos = (x, y);
 
-                    // Get element info at click point using accessibility APIs
-                    let element_info = get_element_at_point(x, y);
+                    // Start the accessibility lookup, then grab the frame while
+                    // it runs so a slow provider can't delay the screenshot.
+                    // The lookup gets a bounded wait; if it misses it the step
+                    // goes out without element info and the result follows in
+                    // a `new-step-element` event.
+                    let lookup = spawn_element_lookup(x, y);
+                    let store_subtree = *ax_tree_snapshot_enabled.lock().unwrap();
+                    let context_text =
+                        !*ocr_enabled.lock().unwrap() || ocr_unavailable.load(Ordering::Relaxed);
+                    let subtree =
+                        (store_subtree || context_text).then(|| spawn_subtree_lookup(x, y));
+                    let monitor = get_monitor_at_point(x, y);
+                    let capture_started = Instant::now();
+                    let frame = monitor
+                        .as_ref()
+                        .and_then(|mon| capture_backend::capture(mon).ok());
+                    if frame.is_some() {
+                        analytics::measure(METRIC_SCREENSHOT_CAPTURE, capture_started.elapsed());
+                    }
+                    let (element_info, late_lookup) =
+                        match lookup.recv_timeout(ELEMENT_LOOKUP_TIMEOUT) {
+                            Ok(info) => (info, None),
+                            Err(mpsc::RecvTimeoutError::Timeout) => (None, Some(lookup)),
+                            Err(mpsc::RecvTimeoutError::Disconnected) => (None, None),
+                        };
 
                     // Skip clicks within StepSnap windows (but flush pending text first)
-                    if is_stepsnap_app(&element_info.as_ref().and_then(|e| e.app_name.clone())) {
+                    if is_own_window_at(x, y)
+                        || is_stepsnap_app(&element_info.as_ref().and_then(|e| e.app_name.clone()))
+                    {
                         // Still flush any pending text buffer - it was typed in another app
                         if !key_buffer.trim().is_empty() {
                             let key_buf_trim = key_buffer.trim().to_string();
@@ -980,10 +1959,11 @@ pub fn start_listener(
                                     key_buffer.clear();
                                     last_key_time = None;
                                 }
-                                Some((final_text, source)) => {
+                                Some((final_text, source, focused)) => {
                                     if let Some(mon) = get_monitor_for_foreground_window() {
-                                        if let Ok(image) = mon.capture_image() {
+                                        if let Ok(image) = capture_backend::capture(&mon) {
                                             let anchor = monitor_center(&mon);
+                                            let space = MonitorSpace::of(&mon, image.width());
                                             let timestamp = SystemTime::now()
                                                 .duration_since(SystemTime::UNIX_EPOCH)
                                                 .unwrap_or_default()
@@ -992,12 +1972,17 @@ pub fn start_listener(
                                             let _ = tx_encode.send(CaptureData {
                                                 x: None,
                                                 y: None,
-                                                image: Arc::new(image::DynamicImage::ImageRgba8(image)),
+                                                image: Arc::new(image),
                                                 timestamp,
                                                 step_type: "type".to_string(),
                                                 text: Some(final_text),
-                                                element_info: None,
+                                                element_info: focused
+                                                    .map(|info| to_monitor_space(info, space)),
+                                                pending_element: None,
+                                                pending_subtree: None,
                                                 input_source: Some(source.to_string()),
+                                                window: get_foreground_window().unwrap_or_default(),
+                                                scale_factor: space.scale_factor,
                                                 anchor,
                                             });
                                             key_buffer.clear();
@@ -1010,16 +1995,20 @@ pub fn start_listener(
                         continue; // Skip the click itself - it's within StepSnap
                     }
 
-                    // Capture Screenshot from the correct monitor
-                    if let Some(mon) = get_monitor_at_point(x, y) {
-                        if let Ok(image) = mon.capture_image() {
+                    // Screenshot from the correct monitor, captured above
+                    if let Some(mon) = monitor {
+                        if let Some(image) = frame {
                             let timestamp = SystemTime::now()
                                 .duration_since(SystemTime::UNIX_EPOCH)
                                 .unwrap_or_default()
                                 .as_millis() as u64;
 
-                            // 1. Flush text if any (using the same screenshot)
+                            // 1. Flush text if any (using the same screenshot,
+                            // so the encoder writes it once for both steps)
                             let click_anchor = monitor_center(&mon);
+                            let space = MonitorSpace::of(&mon, image.width());
+                            let image = Arc::new(image);
+                            let window = get_foreground_window().unwrap_or_default();
                             if !key_buffer.trim().is_empty() {
                                 let key_buf_trim = key_buffer.trim().to_string();
                                 match resolve_type_step_text(&key_buf_trim) {
@@ -1028,18 +2017,21 @@ pub fn start_listener(
                                         key_buffer.clear();
                                         last_key_time = None;
                                     }
-                                    Some((final_text, source)) => {
+                                    Some((final_text, source, focused)) => {
                                         let _ = tx_encode.send(CaptureData {
                                             x: None,
                                             y: None,
-                                            image: Arc::new(image::DynamicImage::ImageRgba8(
-                                                image.clone(),
-                                            )),
+                                            image: image.clone(),
                                             timestamp,
                                             step_type: "type".to_string(),
                                             text: Some(final_text),
-                                            element_info: None,
+                                            element_info: focused
+                                                .map(|info| to_monitor_space(info, space)),
+                                            pending_element: None,
+                                            pending_subtree: None,
                                             input_source: Some(source.to_string()),
+                                            window: window.clone(),
+                                            scale_factor: space.scale_factor,
                                             anchor: click_anchor,
                                         });
                                         key_buffer.clear();
@@ -1049,20 +2041,33 @@ pub fn start_listener(
                             }
 
                             // 2. Emit Click Step with element info
-                            // Convert absolute screen coordinates to monitor-relative coordinates
-                            // This ensures the click highlight is drawn at the correct position on the captured image
-                            let rel_x = (x - mon.x().unwrap_or(0) as f64).round() as i32;
-                            let rel_y = (y - mon.y().unwrap_or(0) as f64).round() as i32;
+                            // Convert absolute screen coordinates to pixels of the captured
+                            // image, so the click marker, crops and OCR line up with it
+                            let (rel_x, rel_y) = space.point(x, y);
+                            let element_info =
+                                element_info.map(|info| to_monitor_space(info, space));
+                            let pending_element =
+                                late_lookup.map(|rx| PendingElement { rx, space });
+                            let pending_subtree = subtree.map(|rx| PendingSubtree {
+                                rx,
+                                space,
+                                store: store_subtree,
+                                context_text,
+                            });
 
                             let _ = tx_encode.send(CaptureData {
                                 x: Some(rel_x),
                                 y: Some(rel_y),
-                                image: Arc::new(image::DynamicImage::ImageRgba8(image)), // Move for click step
+                                image, // Move for click step
                                 timestamp,
                                 step_type: "click".to_string(),
                                 text: None,
                                 element_info,
+                                pending_element,
+                                pending_subtree,
                                 input_source: None,
+                                window,
+                                scale_factor: space.scale_factor,
                                 // Use the click position itself as the anchor — it's
                                 // guaranteed to be on the right monitor.
                                 anchor: Some((x, y)),
@@ -1078,6 +2083,7 @@ pub fn start_listener(
     thread::spawn(move || {
         let mut current_x = 0.0;
         let mut current_y = 0.0;
+        let mut modifiers = HeldModifiers::default();
 
         if let Err(error) = listen(move |event| match event.event_type {
             EventType::MouseMove { x, y } => {
@@ -1090,15 +2096,32 @@ pub fn start_listener(
                     y: current_y,
                 });
             }
+            EventType::ButtonPress(button) => {
+                // Mouse-button hotkeys; see `hotkeys`
+                let Some(key) = mouse_button_key(button) else {
+                    return;
+                };
+                if *hotkeys_suspended.lock().unwrap() {
+                    return;
+                }
+                let pressed = modifiers.binding(key);
+                let action = action_for_press(&hotkeys.lock().unwrap(), &pressed);
+                if let Some(action) = action {
+                    let _ = app.emit(action.event(), ());
+                }
+            }
             EventType::KeyPress(key) => {
+                modifiers.update(key, true);
                 let _ = tx_event.send(RecorderEvent::Key {
                     key,
                     text: event.name,
                 });
             }
+            EventType::KeyRelease(key) => modifiers.update(key, false),
             _ => {}
         }) {
-            eprintln!("Input listener error: {:?}", error);
+            tracing::error!("Input listener error: {:?}", error);
+            *INPUT_LISTENER_ERROR.lock().unwrap() = Some(format!("{:?}", error));
         }
     });
 }
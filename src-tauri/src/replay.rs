@@ -0,0 +1,382 @@
+//! Replaying a recording's clicks and typing, so a guide can be re-run as a
+//! lightweight automation.
+//!
+//! Steps play in order on a background thread: click steps move the pointer
+//! to the recorded point and click, type steps type their text, and the rest
+//! (captures, headings, notes) are passed over. Recorded points are pixels of
+//! the step's screenshot, so each is mapped back to the screen through the
+//! monitor whose size matches the screenshot; with several identical monitors
+//! the primary one wins. With `confirm_each_step` the replay waits for
+//! `confirm_replay_step` before every step, with the window brought back so
+//! the user can answer.
+//!
+//! Progress is emitted as `replay-progress` and the outcome as
+//! `replay-finished`. Only one replay runs at a time.
+
+use crate::database::Step;
+use crate::recorder::RecordingState;
+use crate::db_worker::DatabaseWorker;
+use enigo::{Button, Coordinate, Direction, Enigo, Keyboard, Mouse};
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter, Manager, State};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ReplayOptions {
+    /// Pause before each step, so pages and dialogs can catch up
+    pub delay_ms: u64,
+    pub confirm_each_step: bool,
+}
+
+impl Default for ReplayOptions {
+    fn default() -> Self {
+        Self {
+            delay_ms: 1000,
+            confirm_each_step: false,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum StepDecision {
+    Run,
+    Skip,
+}
+
+struct Control {
+    cancel: Arc<AtomicBool>,
+    decisions: Sender<StepDecision>,
+}
+
+static ACTIVE: Mutex<Option<Control>> = Mutex::new(None);
+
+#[derive(Clone, Serialize)]
+struct ReplayProgress<'a> {
+    recording_id: &'a str,
+    step_id: &'a str,
+    index: usize,
+    total: usize,
+    /// `waiting` (for confirmation), `running` or `skipped`
+    state: &'static str,
+}
+
+#[derive(Clone, Serialize)]
+struct ReplayFinished {
+    recording_id: String,
+    /// Steps whose input was played
+    played: usize,
+    cancelled: bool,
+    error: Option<String>,
+}
+
+#[derive(Debug, PartialEq)]
+enum Action {
+    Click { x: i32, y: i32 },
+    Type(String),
+}
+
+/// A monitor in screen coordinates, as xcap reports it
+#[derive(Debug, Clone, Copy)]
+struct Screen {
+    x: i32,
+    y: i32,
+    width: u32,
+    height: u32,
+    scale_factor: f32,
+    is_primary: bool,
+}
+
+fn screens() -> Vec<Screen> {
+    let mut screens: Vec<Screen> = xcap::Monitor::all()
+        .unwrap_or_default()
+        .iter()
+        .map(|mon| Screen {
+            x: mon.x().unwrap_or(0),
+            y: mon.y().unwrap_or(0),
+            width: mon.width().unwrap_or(0),
+            height: mon.height().unwrap_or(0),
+            scale_factor: mon.scale_factor().unwrap_or(1.0),
+            is_primary: mon.is_primary().unwrap_or(false),
+        })
+        .collect();
+    // Primary first, so it wins ties
+    screens.sort_by_key(|screen| !screen.is_primary);
+    screens
+}
+
+/// `point` in a `capture`-sized screenshot, in screen coordinates. The
+/// recorder captures either at the monitor's size or scaled by its display
+/// scale, like `recorder::MonitorSpace`.
+fn to_screen(point: (i32, i32), capture: (u32, u32), screens: &[Screen]) -> Option<(i32, i32)> {
+    let matches = |screen: &&Screen| {
+        [1.0, screen.scale_factor as f64].iter().any(|scale| {
+            let width = (screen.width as f64 * scale).round() as i64;
+            let height = (screen.height as f64 * scale).round() as i64;
+            (width - capture.0 as i64).abs() <= 1 && (height - capture.1 as i64).abs() <= 1
+        })
+    };
+    let screen = screens.iter().find(matches).or_else(|| screens.first())?;
+    let scale = if screen.width > 0 && capture.0 > 0 {
+        capture.0 as f64 / screen.width as f64
+    } else {
+        1.0
+    };
+    Some((
+        screen.x + (point.0 as f64 / scale).round() as i32,
+        screen.y + (point.1 as f64 / scale).round() as i32,
+    ))
+}
+
+/// What replaying `step` does, if anything
+fn action(step: &Step, screens: &[Screen]) -> Option<Action> {
+    match step.type_.as_str() {
+        "click" => {
+            let point = (step.x?, step.y?);
+            let capture = step
+                .screenshot_path
+                .as_deref()
+                .and_then(|path| image::image_dimensions(path).ok())?;
+            let (x, y) = to_screen(point, capture, screens)?;
+            Some(Action::Click { x, y })
+        }
+        "type" => step
+            .text
+            .clone()
+            .filter(|text| !text.is_empty())
+            .map(Action::Type),
+        _ => None,
+    }
+}
+
+fn perform(enigo: &mut Enigo, action: &Action) -> Result<(), String> {
+    match action {
+        Action::Click { x, y } => {
+            enigo
+                .move_mouse(*x, *y, Coordinate::Abs)
+                .map_err(|e| e.to_string())?;
+            enigo
+                .button(Button::Left, Direction::Click)
+                .map_err(|e| e.to_string())
+        }
+        Action::Type(text) => enigo.text(text).map_err(|e| e.to_string()),
+    }
+}
+
+/// Sleep for `duration` unless cancelled first. Returns whether cancelled.
+fn wait(duration: Duration, cancel: &AtomicBool) -> bool {
+    let until = Instant::now() + duration;
+    while Instant::now() < until {
+        if cancel.load(Ordering::SeqCst) {
+            return true;
+        }
+        std::thread::sleep(Duration::from_millis(50).min(until - Instant::now()));
+    }
+    cancel.load(Ordering::SeqCst)
+}
+
+fn set_window_visible(app: &AppHandle, visible: bool) {
+    if let Some(window) = app.get_webview_window("main") {
+        if visible {
+            let _ = window.unminimize();
+            let _ = window.set_focus();
+        } else {
+            let _ = window.minimize();
+        }
+    }
+}
+
+fn play(
+    app: &AppHandle,
+    recording_id: &str,
+    steps: &[Step],
+    options: &ReplayOptions,
+    cancel: &AtomicBool,
+    decisions: Receiver<StepDecision>,
+) -> Result<usize, String> {
+    let screens = screens();
+    let mut enigo = Enigo::new(&enigo::Settings::default())
+        .map_err(|e| format!("Can't send input on this system: {}", e))?;
+    let mut played = 0;
+    for (index, step) in steps.iter().enumerate() {
+        let progress = |state| ReplayProgress {
+            recording_id,
+            step_id: &step.id,
+            index,
+            total: steps.len(),
+            state,
+        };
+        let Some(input) = action(step, &screens) else {
+            let _ = app.emit("replay-progress", progress("skipped"));
+            continue;
+        };
+        if options.confirm_each_step {
+            set_window_visible(app, true);
+            let _ = app.emit("replay-progress", progress("waiting"));
+            // Closed by `stop_replay`
+            match decisions.recv() {
+                Ok(StepDecision::Run) => set_window_visible(app, false),
+                Ok(StepDecision::Skip) => {
+                    let _ = app.emit("replay-progress", progress("skipped"));
+                    continue;
+                }
+                Err(_) => break,
+            }
+        }
+        if wait(Duration::from_millis(options.delay_ms), cancel) {
+            break;
+        }
+        let _ = app.emit("replay-progress", progress("running"));
+        perform(&mut enigo, &input)
+            .map_err(|e| format!("Step {} failed: {}", step.order_index + 1, e))?;
+        played += 1;
+    }
+    Ok(played)
+}
+
+/// Start replaying `recording_id`. Returns once the replay has started;
+/// `replay-finished` reports how it ended.
+#[tauri::command]
+pub async fn start_replay(
+    app: AppHandle,
+    db: State<'_, DatabaseWorker>,
+    recording: State<'_, RecordingState>,
+    recording_id: String,
+    options: Option<ReplayOptions>,
+) -> Result<(), String> {
+    if *recording.is_recording.lock().unwrap() {
+        return Err("Stop recording before replaying".to_string());
+    }
+    let id = recording_id.clone();
+    let steps = db
+        .run(move |db| {
+            db.get_recording(&id)
+                .map_err(|e| e.to_string())?
+                .ok_or_else(|| format!("Recording not found: {}", id))
+        })
+        .await?
+        .steps;
+    let options = options.unwrap_or_default();
+
+    let cancel = Arc::new(AtomicBool::new(false));
+    let (decisions, receiver) = mpsc::channel();
+    {
+        let mut active = ACTIVE.lock().unwrap();
+        if active.is_some() {
+            return Err("A replay is already running".to_string());
+        }
+        *active = Some(Control {
+            cancel: cancel.clone(),
+            decisions,
+        });
+    }
+    crate::analytics::track("replay");
+    tracing::info!(recording_id = %recording_id, steps = steps.len(), ?options, "Replay started");
+
+    std::thread::spawn(move || {
+        if !options.confirm_each_step {
+            set_window_visible(&app, false);
+        }
+        let result = play(&app, &recording_id, &steps, &options, &cancel, receiver);
+        {
+            // Unless stopped, and maybe replaced by a newer replay already
+            let mut active = ACTIVE.lock().unwrap();
+            if active
+                .as_ref()
+                .is_some_and(|control| Arc::ptr_eq(&control.cancel, &cancel))
+            {
+                *active = None;
+            }
+        }
+        set_window_visible(&app, true);
+        let cancelled = cancel.load(Ordering::SeqCst);
+        let finished = match result {
+            Ok(played) => ReplayFinished {
+                recording_id,
+                played,
+                cancelled,
+                error: None,
+            },
+            Err(e) => {
+                tracing::warn!("Replay stopped: {}", e);
+                ReplayFinished {
+                    recording_id,
+                    played: 0,
+                    cancelled,
+                    error: Some(e),
+                }
+            }
+        };
+        let _ = app.emit("replay-finished", finished);
+    });
+    Ok(())
+}
+
+/// Answer a replay waiting to play a step
+#[tauri::command]
+pub fn confirm_replay_step(decision: StepDecision) -> Result<(), String> {
+    let active = ACTIVE.lock().unwrap();
+    let control = active.as_ref().ok_or("No replay is running")?;
+    control
+        .decisions
+        .send(decision)
+        .map_err(|_| "The replay has finished".to_string())
+}
+
+#[tauri::command]
+pub fn stop_replay() {
+    if let Some(control) = ACTIVE.lock().unwrap().take() {
+        control.cancel.store(true, Ordering::SeqCst);
+        // Dropping the sender wakes a replay waiting for confirmation
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn screen(x: i32, width: u32, height: u32, scale_factor: f32, is_primary: bool) -> Screen {
+        Screen {
+            x,
+            y: 0,
+            width,
+            height,
+            scale_factor,
+            is_primary,
+        }
+    }
+
+    #[test]
+    fn maps_screenshot_pixels_to_the_matching_monitor() {
+        let screens = [
+            screen(0, 1920, 1080, 1.0, true),
+            // A 4K monitor at 200% reported in logical points
+            screen(1920, 1920, 1080, 2.0, false),
+            screen(-1280, 1280, 1024, 1.0, false),
+        ];
+        // Same size as the primary: the primary wins the tie
+        assert_eq!(
+            to_screen((100, 50), (1920, 1080), &screens),
+            Some((100, 50))
+        );
+        // Captured at physical size: halve back to points
+        assert_eq!(
+            to_screen((200, 100), (3840, 2160), &screens),
+            Some((2020, 50))
+        );
+        assert_eq!(
+            to_screen((10, 10), (1280, 1024), &screens),
+            Some((-1270, 10))
+        );
+        // Unknown size: fall back to the primary at the screenshot's scale
+        assert_eq!(
+            to_screen((400, 300), (800, 600), &screens),
+            Some((960, 720))
+        );
+        assert_eq!(to_screen((1, 1), (10, 10), &[]), None);
+    }
+}
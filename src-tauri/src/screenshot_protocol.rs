@@ -0,0 +1,229 @@
+//! The `screenshot:` protocol, serving screenshots resized for display.
+//!
+//! The step editor used to load every screenshot at full resolution through
+//! the asset protocol, decoding a dozen 4K JPEGs to show cards a few hundred
+//! pixels wide. `screenshot://localhost/<path>?width=<px>` (the frontend
+//! builds it with `convertFileSrc(path, "screenshot")`) serves a copy no
+//! wider than `width`, resized once and then read from a disk cache keyed by
+//! the file's path, size and modification time, so an edited screenshot gets
+//! a fresh copy. Without `width`, or for a clip, the file is served as is.
+//! Access follows the asset protocol's scope.
+
+use crate::jpeg;
+use sha2::{Digest, Sha256};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+use tauri::http::{header, Request, Response, StatusCode};
+use tauri::{AppHandle, Manager};
+
+pub const SCHEME: &str = "screenshot";
+/// Requested widths are rounded up to a multiple of this, so windows of
+/// slightly different sizes share cached copies
+const WIDTH_STEP: u32 = 160;
+const MAX_WIDTH: u32 = 7680;
+const QUALITY: u8 = 85;
+/// Cached copies not written for this long are deleted at startup
+const CACHE_MAX_AGE: Duration = Duration::from_secs(30 * 24 * 60 * 60);
+
+fn cache_dir(app: &AppHandle) -> Option<PathBuf> {
+    app.path()
+        .app_cache_dir()
+        .ok()
+        .map(|dir| dir.join("screenshots"))
+}
+
+/// Decode `%XX` escapes; None for malformed ones or invalid UTF-8
+fn percent_decode(text: &str) -> Option<String> {
+    let bytes = text.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%' {
+            let hex = std::str::from_utf8(bytes.get(i + 1..i + 3)?).ok()?;
+            decoded.push(u8::from_str_radix(hex, 16).ok()?);
+            i += 3;
+        } else {
+            decoded.push(bytes[i]);
+            i += 1;
+        }
+    }
+    String::from_utf8(decoded).ok()
+}
+
+/// The `width` query parameter, rounded up to `WIDTH_STEP`
+fn requested_width(query: Option<&str>) -> Option<u32> {
+    let width: u32 = query?
+        .split('&')
+        .find_map(|pair| pair.strip_prefix("width="))?
+        .parse()
+        .ok()?;
+    (width > 0).then(|| (width.div_ceil(WIDTH_STEP) * WIDTH_STEP).min(MAX_WIDTH))
+}
+
+fn content_type(path: &Path) -> Option<&'static str> {
+    let extension = path.extension()?.to_str()?.to_ascii_lowercase();
+    match extension.as_str() {
+        "jpg" | "jpeg" => Some("image/jpeg"),
+        "png" => Some("image/png"),
+        "gif" => Some("image/gif"),
+        "webp" => Some("image/webp"),
+        _ => None,
+    }
+}
+
+fn respond(status: StatusCode, content_type: &str, body: Vec<u8>) -> Response<Vec<u8>> {
+    Response::builder()
+        .status(status)
+        .header(header::CONTENT_TYPE, content_type)
+        // The cache below makes a repeat request cheap, and the webview
+        // shouldn't keep a copy of a screenshot that's since been edited
+        .header(header::CACHE_CONTROL, "no-cache")
+        .body(body)
+        .unwrap_or_default()
+}
+
+fn error(status: StatusCode, message: &str) -> Response<Vec<u8>> {
+    respond(status, "text/plain", message.as_bytes().to_vec())
+}
+
+/// `path` no wider than `width`, from the cache or resized into it; None
+/// when it's that narrow already
+fn resized(app: &AppHandle, path: &Path, width: u32) -> Result<Option<Vec<u8>>, String> {
+    // Reads only the header
+    let (original_width, original_height) =
+        image::image_dimensions(path).map_err(|e| e.to_string())?;
+    if original_width <= width {
+        return Ok(None);
+    }
+    let metadata = std::fs::metadata(path).map_err(|e| e.to_string())?;
+    let modified = metadata
+        .modified()
+        .ok()
+        .and_then(|time| time.duration_since(SystemTime::UNIX_EPOCH).ok())
+        .unwrap_or_default();
+    let key = Sha256::digest(format!(
+        "{}|{}|{}|{}",
+        path.display(),
+        metadata.len(),
+        modified.as_millis(),
+        width
+    ));
+    let cached = cache_dir(app).map(|dir| dir.join(format!("{:x}.jpg", key)));
+    if let Some(bytes) = cached.as_ref().and_then(|file| std::fs::read(file).ok()) {
+        return Ok(Some(bytes));
+    }
+
+    let height = (original_height as u64 * width as u64 / original_width as u64).max(1) as u32;
+    let small = image::open(path)
+        .map_err(|e| e.to_string())?
+        .resize_exact(width, height, image::imageops::FilterType::Triangle)
+        .to_rgb8();
+    let bytes = jpeg::encode(&small, QUALITY)?;
+    if let Some(file) = cached {
+        // Written beside and renamed, so a concurrent request never reads
+        // half a file
+        let partial = file.with_extension("part");
+        let written = file
+            .parent()
+            .map_or(Ok(()), std::fs::create_dir_all)
+            .and_then(|_| std::fs::write(&partial, &bytes))
+            .and_then(|_| std::fs::rename(&partial, &file));
+        if let Err(e) = written {
+            tracing::debug!("Failed to cache a resized screenshot: {}", e);
+        }
+    }
+    Ok(Some(bytes))
+}
+
+/// Answer a `screenshot:` request. Blocking; run off the main thread.
+pub fn handle(app: &AppHandle, request: &Request<Vec<u8>>) -> Response<Vec<u8>> {
+    let uri = request.uri();
+    let Some(path) = percent_decode(uri.path().trim_start_matches('/')) else {
+        return error(StatusCode::BAD_REQUEST, "Invalid path");
+    };
+    let path = PathBuf::from(path);
+    if !app.asset_protocol_scope().is_allowed(&path) {
+        return error(StatusCode::FORBIDDEN, "Outside the asset scope");
+    }
+    let Some(content_type) = content_type(&path) else {
+        return error(StatusCode::FORBIDDEN, "Not an image");
+    };
+
+    // Resizing would drop a clip's animation
+    let width = requested_width(uri.query()).filter(|_| content_type != "image/gif");
+    if let Some(width) = width {
+        match resized(app, &path, width) {
+            Ok(Some(bytes)) => return respond(StatusCode::OK, "image/jpeg", bytes),
+            // Already small enough
+            Ok(None) => {}
+            Err(e) => {
+                tracing::debug!(path = %path.display(), "Failed to resize a screenshot: {}", e);
+            }
+        }
+    }
+    match std::fs::read(&path) {
+        Ok(bytes) => respond(StatusCode::OK, content_type, bytes),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+            error(StatusCode::NOT_FOUND, "Not found")
+        }
+        Err(e) => error(StatusCode::INTERNAL_SERVER_ERROR, &e.to_string()),
+    }
+}
+
+/// Width and height of the image at `path`, for mapping positions on a
+/// resized copy back to the original
+#[tauri::command]
+pub async fn get_image_size(path: String) -> Result<(u32, u32), String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        image::image_dimensions(&path).map_err(|e| format!("Failed to read {}: {}", path, e))
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// Delete cached copies older than `CACHE_MAX_AGE`
+pub fn prune_cache(app: &AppHandle) {
+    let Some(entries) = cache_dir(app).and_then(|dir| std::fs::read_dir(dir).ok()) else {
+        return;
+    };
+    let mut removed = 0;
+    for entry in entries.flatten() {
+        let stale = entry
+            .metadata()
+            .and_then(|metadata| metadata.modified())
+            .ok()
+            .and_then(|modified| modified.elapsed().ok())
+            .is_some_and(|age| age > CACHE_MAX_AGE);
+        if stale && std::fs::remove_file(entry.path()).is_ok() {
+            removed += 1;
+        }
+    }
+    if removed > 0 {
+        tracing::info!(removed, "Pruned resized screenshot cache");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_paths_and_widths() {
+        assert_eq!(
+            percent_decode("C%3A%5CUsers%5Cme%5Cshot%201.jpg").as_deref(),
+            Some("C:\\Users\\me\\shot 1.jpg")
+        );
+        assert_eq!(
+            percent_decode("%2Ftmp%2Fa.png").as_deref(),
+            Some("/tmp/a.png")
+        );
+        assert_eq!(percent_decode("bad%2"), None);
+
+        assert_eq!(requested_width(Some("width=300")), Some(320));
+        assert_eq!(requested_width(Some("t=5&width=320")), Some(320));
+        assert_eq!(requested_width(Some("width=100000")), Some(MAX_WIDTH));
+        assert_eq!(requested_width(Some("width=0")), None);
+        assert_eq!(requested_width(Some("t=5")), None);
+        assert_eq!(requested_width(None), None);
+    }
+}
@@ -0,0 +1,302 @@
+//! Full-page captures of scrollable windows.
+//!
+//! `capture_full_page` scrolls the window with synthetic mouse wheel events,
+//! capturing a frame after each scroll, and `stitch` joins the frames into
+//! one tall image. Each frame is matched against the previous one to find how
+//! far the content moved; rows that didn't move (toolbars, sticky headers,
+//! status bars) are kept once, at the top and bottom of the result.
+
+use image::RgbaImage;
+use rdev::{simulate, EventType};
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use tokio::time::{sleep, Duration};
+use xcap::Window;
+
+/// Stop after this many frames, so endless feeds still finish
+const MAX_FRAMES: usize = 24;
+
+/// Wheel notches sent to reach the top before capturing, per burst
+const TOP_BURST_NOTCHES: u32 = 25;
+
+/// Time for smooth scrolling to finish before a frame is captured
+const SCROLL_SETTLE: Duration = Duration::from_millis(350);
+
+/// Columns compared per row are this far apart
+const SAMPLE_STEP: usize = 4;
+
+/// Mean channel difference under which two rows are treated as the same
+const ROW_TOLERANCE: u32 = 8;
+
+/// Frames must share at least this many rows to be matched reliably
+const MIN_OVERLAP: u32 = 32;
+
+/// Where two consecutive frames line up
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ScrollOverlap {
+    /// Rows at the top that stayed put
+    pub header: u32,
+    /// Rows at the bottom that stayed put
+    pub footer: u32,
+    /// How far the content between them moved up
+    pub shift: u32,
+}
+
+/// Mean channel difference between row `a_row` of `a` and `b_row` of `b`
+fn row_difference(a: &RgbaImage, a_row: u32, b: &RgbaImage, b_row: u32) -> u32 {
+    let row_len = a.width() as usize * 4;
+    let a_start = a_row as usize * row_len;
+    let b_start = b_row as usize * row_len;
+    let a_row = &a.as_raw()[a_start..a_start + row_len];
+    let b_row = &b.as_raw()[b_start..b_start + row_len];
+
+    let mut total = 0u32;
+    let mut samples = 0u32;
+    for (a_px, b_px) in a_row
+        .chunks_exact(4)
+        .zip(b_row.chunks_exact(4))
+        .step_by(SAMPLE_STEP)
+    {
+        total += (0..3)
+            .map(|c| a_px[c].abs_diff(b_px[c]) as u32)
+            .sum::<u32>();
+        samples += 3;
+    }
+    total / samples.max(1)
+}
+
+/// Whether two frames show the same thing, give or take a blinking caret
+fn same_frame(a: &RgbaImage, b: &RgbaImage) -> bool {
+    a.dimensions() == b.dimensions()
+        && (0..a.height()).all(|row| row_difference(a, row, b, row) <= ROW_TOLERANCE)
+}
+
+/// Rows at the top (`from_top`) or bottom of two frames that are the same,
+/// capped at a third of the height.
+fn fixed_rows(prev: &RgbaImage, next: &RgbaImage, from_top: bool) -> u32 {
+    let height = prev.height();
+    (0..height / 3)
+        .take_while(|&i| {
+            let row = if from_top { i } else { height - 1 - i };
+            row_difference(prev, row, next, row) <= ROW_TOLERANCE
+        })
+        .count() as u32
+}
+
+/// How far the content of `next` moved up from `prev`, with `header` and
+/// `footer` rows excluded. None when it didn't move or no shift fits.
+fn find_shift(prev: &RgbaImage, next: &RgbaImage, header: u32, footer: u32) -> Option<u32> {
+    let region = prev.height().checked_sub(header + footer)?;
+    let mut best: Option<(u32, u32)> = None;
+    for shift in 1..region.saturating_sub(MIN_OVERLAP) {
+        let rows = region - shift;
+        let limit = best.map_or(u32::MAX, |(_, cost)| cost);
+        let mut total = 0u32;
+        let mut compared = 0u32;
+        for i in (0..rows).step_by(2) {
+            total += row_difference(next, header + i, prev, header + shift + i);
+            compared += 1;
+            // Already worse than the best shift; stop comparing
+            if total / compared > limit.saturating_add(ROW_TOLERANCE) {
+                break;
+            }
+        }
+        let cost = total / compared.max(1);
+        match best {
+            Some((_, best_cost)) if best_cost <= cost => {}
+            _ => best = Some((shift, cost)),
+        }
+    }
+    best.filter(|&(_, cost)| cost <= ROW_TOLERANCE)
+        .map(|(shift, _)| shift)
+}
+
+/// Where `next` continues `prev`. None when the frames differ in size, the
+/// content didn't move (the end of the page) or they don't line up.
+pub fn find_overlap(prev: &RgbaImage, next: &RgbaImage) -> Option<ScrollOverlap> {
+    if prev.dimensions() != next.dimensions() || prev.height() <= MIN_OVERLAP {
+        return None;
+    }
+    let header = fixed_rows(prev, next, true);
+    let footer = fixed_rows(prev, next, false);
+    let shift = find_shift(prev, next, header, footer)?;
+    Some(ScrollOverlap {
+        header,
+        footer,
+        shift,
+    })
+}
+
+/// Join frames captured while scrolling down into one image. The fixed
+/// header and footer are found on the first pair of frames; stitching stops
+/// at the first frame that doesn't continue the one before it.
+pub fn stitch(frames: &[RgbaImage]) -> Option<RgbaImage> {
+    let first = frames.first()?;
+    let (width, height) = first.dimensions();
+
+    // Row ranges of each frame, top to bottom
+    let mut parts: Vec<(&RgbaImage, u32, u32)> = Vec::new();
+    let mut fixed: Option<(u32, u32)> = None;
+    let mut last = first;
+    for pair in frames.windows(2) {
+        let (prev, next) = (&pair[0], &pair[1]);
+        let shift = match fixed {
+            None => find_overlap(prev, next).map(|overlap| {
+                fixed = Some((overlap.header, overlap.footer));
+                overlap.shift
+            }),
+            Some(_) if prev.dimensions() != next.dimensions() => None,
+            Some((header, footer)) => find_shift(prev, next, header, footer),
+        };
+        let (Some(shift), Some((_, footer))) = (shift, fixed) else {
+            break;
+        };
+        if parts.is_empty() {
+            parts.push((first, 0, height - footer));
+        }
+        parts.push((next, height - footer - shift, height - footer));
+        last = next;
+    }
+
+    let Some((_, footer)) = fixed else {
+        return Some(first.clone());
+    };
+    parts.push((last, height - footer, height));
+
+    let total: u32 = parts.iter().map(|(_, start, end)| end - start).sum();
+    let mut page = RgbaImage::new(width, total);
+    let mut y = 0;
+    for (frame, start, end) in parts {
+        let strip = image::imageops::crop_imm(frame, 0, start, width, end - start);
+        image::imageops::replace(&mut page, &*strip, 0, y as i64);
+        y += end - start;
+    }
+    Some(page)
+}
+
+fn capture(window: &Window) -> Result<RgbaImage, String> {
+    match catch_unwind(AssertUnwindSafe(|| window.capture_image())) {
+        Ok(Ok(image)) => Ok(image),
+        Ok(Err(e)) => Err(format!("Capture failed: {}", e)),
+        Err(_) => Err("Window capture crashed - window may be invalid".to_string()),
+    }
+}
+
+/// Send `notches` wheel clicks, down when `down`
+async fn scroll(notches: u32, down: bool) {
+    let delta_y = if down { -1 } else { 1 };
+    for _ in 0..notches {
+        let _ = simulate(&EventType::Wheel {
+            delta_x: 0,
+            delta_y,
+        });
+        sleep(Duration::from_millis(10)).await;
+    }
+}
+
+/// Scroll `window` to the top, then down a page at a time, and stitch what
+/// it showed into one image. The pointer is moved over the window so the
+/// wheel events reach it.
+pub async fn capture_full_page(window: Window) -> Result<RgbaImage, String> {
+    let x = window.x().map_err(|e| e.to_string())?;
+    let y = window.y().map_err(|e| e.to_string())?;
+    let width = window.width().map_err(|e| e.to_string())?;
+    let height = window.height().map_err(|e| e.to_string())?;
+    simulate(&EventType::MouseMove {
+        x: x as f64 + width as f64 / 2.0,
+        y: y as f64 + height as f64 / 2.0,
+    })
+    .map_err(|_| "Could not move the pointer over the window".to_string())?;
+    sleep(Duration::from_millis(50)).await;
+
+    // Back to the top: scroll up until a burst changes nothing
+    let mut frame = capture(&window)?;
+    for _ in 0..MAX_FRAMES {
+        scroll(TOP_BURST_NOTCHES, false).await;
+        sleep(SCROLL_SETTLE).await;
+        let next = capture(&window)?;
+        let unchanged = same_frame(&frame, &next);
+        frame = next;
+        if unchanged {
+            break;
+        }
+    }
+
+    // Down about half a window per frame (a notch is ~100px in most apps),
+    // leaving plenty of overlap to line the frames up
+    let notches = (frame.height() / 200).clamp(1, 10);
+    let mut frames = vec![frame];
+    while frames.len() < MAX_FRAMES {
+        scroll(notches, true).await;
+        sleep(SCROLL_SETTLE).await;
+        let next = capture(&window)?;
+        let prev = frames.last().expect("frames start with the top");
+        // A blank stretch of an unchanged frame would fit any shift, so
+        // check for the end of the page first
+        if same_frame(prev, &next) || find_overlap(prev, &next).is_none() {
+            break;
+        }
+        frames.push(next);
+    }
+
+    stitch(&frames).ok_or_else(|| "Nothing was captured".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::Rgba;
+
+    const HEADER: u32 = 20;
+
+    /// A page whose rows all look different, with a fixed toolbar on top
+    fn page(height: u32) -> RgbaImage {
+        RgbaImage::from_fn(64, height, |x, y| {
+            let v = (y.wrapping_mul(2654435761) >> 8) as u8 ^ (x as u8 / 16);
+            Rgba([v, v.wrapping_mul(3), 255 - v, 255])
+        })
+    }
+
+    /// The window showing `page` scrolled to `offset`, under the toolbar
+    fn frame(page: &RgbaImage, offset: u32, height: u32) -> RgbaImage {
+        let mut frame = RgbaImage::from_pixel(64, height, Rgba([40, 40, 40, 255]));
+        let body = image::imageops::crop_imm(page, 0, offset, 64, height - HEADER);
+        image::imageops::replace(&mut frame, &*body, 0, HEADER as i64);
+        frame
+    }
+
+    #[test]
+    fn finds_how_far_the_content_scrolled() {
+        let page = page(600);
+        let overlap = find_overlap(&frame(&page, 0, 200), &frame(&page, 70, 200)).unwrap();
+        assert_eq!(overlap.header, HEADER);
+        assert_eq!(overlap.shift, 70);
+    }
+
+    #[test]
+    fn nothing_moved_means_the_end_of_the_page() {
+        let page = page(600);
+        let top = frame(&page, 0, 200);
+        assert_eq!(find_overlap(&top, &top), None);
+    }
+
+    #[test]
+    fn stitches_frames_into_the_whole_page() {
+        let page = page(500);
+        // The last scroll hits the bottom early and moves less
+        let frames: Vec<_> = [0, 120, 240, 320]
+            .iter()
+            .map(|&offset| frame(&page, offset, 200))
+            .collect();
+
+        let stitched = stitch(&frames).unwrap();
+        assert_eq!(stitched.dimensions(), (64, HEADER + 500));
+        assert_eq!(stitched, frame(&page, 0, HEADER + 500));
+    }
+
+    #[test]
+    fn a_single_frame_is_returned_as_is() {
+        let page = page(300);
+        let only = frame(&page, 0, 200);
+        assert_eq!(stitch(std::slice::from_ref(&only)), Some(only));
+    }
+}
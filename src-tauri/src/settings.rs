@@ -0,0 +1,365 @@
+//! Typed configuration the backend reads without asking the webview.
+//!
+//! `Settings` holds every setting the recorder, OCR workers and exports
+//! consume. It's persisted in the `settings` table, one JSON value per field,
+//! so a field added later falls back to its default and one unreadable value
+//! doesn't reset the rest. The copy in `SettingsState` is loaded at startup,
+//! before the frontend syncs, and replaced by `update_settings`, which
+//! emits `settings-changed` with the new settings.
+//!
+//! Hotkeys are configured separately through `set_hotkeys`, since their
+//! registration can fail per action.
+
+use crate::ai::AiSettings;
+use crate::analytics;
+use crate::annotations::{ClickHighlightStyle, StepBadgeStyle};
+use crate::api_server::{self, ApiServerSettings};
+use crate::capture_backend::{self, CaptureBackendKind};
+use crate::chat::ChatSettings;
+use crate::database::{Database, DEFAULT_ORIGINAL_QUOTA};
+use crate::email::{self, EmailSettings};
+use crate::git_publish::GitPublishSettings;
+use crate::i18n::{self, Locale};
+use crate::image_host::ImageHostSettings;
+use crate::logging;
+use crate::network::{self, NetworkSettings};
+use crate::ocr::{self, OcrProvider, AUTO_LANGUAGE, DEFAULT_PARALLEL_MODELS, RECOGNITION_MODELS};
+use crate::ocr_queue::{DEFAULT_PARALLELISM, MAX_OCR_WORKERS};
+use crate::phash::DuplicateFrames;
+use crate::recorder::RecordingState;
+use crate::share::{self, ShareSettings};
+use crate::smart_crop::SmartCropSettings;
+use crate::sync::SyncSettings;
+use crate::tickets::TicketSettings;
+use crate::watermark::WatermarkStyle;
+use crate::zoom_inset::ZoomInsetStyle;
+use serde::{Deserialize, Serialize};
+use std::sync::Mutex;
+
+/// Bounds of the after-frame wait, matching the settings slider
+const AFTER_FRAME_WAIT_RANGE_MS: (u64, u64) = (500, 5000);
+
+/// Fields saved under an older name, (old, new)
+const RENAMED_FIELDS: &[(&str, &str)] = &[("ocr_threads", "ocr_parallel_models")];
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    /// Where screenshots are stored; `None` for the app data directory
+    pub screenshot_path: Option<String>,
+    pub ocr_enabled: bool,
+    /// Recognition model id, or "auto" to pick per step
+    pub ocr_language: String,
+    /// OCR jobs that may run at once, `1..=MAX_OCR_WORKERS`
+    pub ocr_parallelism: usize,
+    /// `None` picks the provider automatically
+    pub ocr_provider: Option<OcrProvider>,
+    /// Recognition models auto-detection runs at once; see `OcrConfig`
+    pub ocr_parallel_models: usize,
+    pub state_diff_enabled: bool,
+    pub after_frame_max_wait_ms: u64,
+    pub video_clips_enabled: bool,
+    pub ax_tree_snapshot_enabled: bool,
+    pub smart_crop: SmartCropSettings,
+    /// Step number badges burned into exported screenshots
+    pub step_badge_style: StepBadgeStyle,
+    /// Highlight drawn at each click, unless a recording has its own
+    pub click_highlight_style: ClickHighlightStyle,
+    /// Magnified inset of the area around the click in exports
+    pub zoom_inset: ZoomInsetStyle,
+    /// Text or image stamped onto exported screenshots
+    pub watermark: WatermarkStyle,
+    pub duplicate_frames: DuplicateFrames,
+    pub keep_lossless_originals: bool,
+    /// Disk space lossless originals may take, in bytes
+    pub lossless_original_quota: u64,
+    /// What takes screenshots; see `capture_backend`
+    pub capture_backend: CaptureBackendKind,
+    /// Least severe level written to the log files
+    pub log_level: String,
+    /// Proxy and extra root certificates for outbound requests
+    pub network: NetworkSettings,
+    /// Count feature use and time captures locally; see `analytics`
+    pub analytics_enabled: bool,
+    /// Language of generated descriptions, export headings and dates
+    pub locale: Locale,
+    /// The localhost HTTP API; see `api_server`
+    pub api_server: ApiServerSettings,
+    /// Provider, models and privacy of backend AI generation; see `ai`
+    pub ai: AiSettings,
+    /// Repository, folder and commit template of Git publishing; see
+    /// `git_publish`
+    pub git: GitPublishSettings,
+    /// SMTP server and sender of emailed recordings; see `email`
+    pub email: EmailSettings,
+    /// Jira and Azure DevOps sites exports are attached to; see `tickets`
+    pub tickets: TicketSettings,
+    /// Provider, folder and schedule of cloud sync; see `sync`
+    pub sync: SyncSettings,
+    /// Port and upload host of shared links; see `share`
+    pub share: ShareSettings,
+    /// Slack channel of bot posts; see `chat`
+    pub chat: ChatSettings,
+    /// Where exported screenshots are uploaded; see `image_host`
+    pub image_host: ImageHostSettings,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            screenshot_path: None,
+            ocr_enabled: true,
+            ocr_language: AUTO_LANGUAGE.to_string(),
+            ocr_parallelism: DEFAULT_PARALLELISM,
+            ocr_provider: None,
+            ocr_parallel_models: DEFAULT_PARALLEL_MODELS,
+            state_diff_enabled: true,
+            after_frame_max_wait_ms: 2000,
+            video_clips_enabled: false,
+            ax_tree_snapshot_enabled: false,
+            smart_crop: SmartCropSettings::default(),
+            step_badge_style: StepBadgeStyle::default(),
+            click_highlight_style: ClickHighlightStyle::default(),
+            zoom_inset: ZoomInsetStyle::default(),
+            watermark: WatermarkStyle::default(),
+            duplicate_frames: DuplicateFrames::default(),
+            keep_lossless_originals: false,
+            lossless_original_quota: DEFAULT_ORIGINAL_QUOTA,
+            capture_backend: CaptureBackendKind::default(),
+            log_level: logging::DEFAULT_LEVEL.to_string(),
+            network: NetworkSettings::default(),
+            analytics_enabled: false,
+            locale: Locale::default(),
+            api_server: ApiServerSettings::default(),
+            ai: AiSettings::default(),
+            git: GitPublishSettings::default(),
+            email: EmailSettings::default(),
+            tickets: TicketSettings::default(),
+            sync: SyncSettings::default(),
+            share: ShareSettings::default(),
+            chat: ChatSettings::default(),
+            image_host: ImageHostSettings::default(),
+        }
+    }
+}
+
+/// The current settings, managed by the app once the database is open
+pub struct SettingsState(pub Mutex<Settings>);
+
+impl Settings {
+    /// The saved settings, with defaults for missing or unreadable fields
+    pub fn load(db: &Database) -> Settings {
+        let rows = match db.load_settings() {
+            Ok(rows) => rows,
+            Err(e) => {
+                tracing::error!("Failed to load settings: {}", e);
+                return Settings::default();
+            }
+        };
+        let saved: Vec<String> = rows.iter().map(|(key, _)| key.clone()).collect();
+        rows.into_iter()
+            .fold(Settings::default(), |settings, (key, json)| {
+                let key = match RENAMED_FIELDS.iter().find(|(old, _)| *old == key) {
+                    // Saved under the new name as well, which is newer
+                    Some((_, new)) if saved.iter().any(|key| key == new) => return settings,
+                    Some((_, new)) => new.to_string(),
+                    None => key,
+                };
+                let Ok(value) = serde_json::from_str(&json) else {
+                    return settings;
+                };
+                let patch = serde_json::Map::from_iter([(key, value)]);
+                settings.merge(&patch).unwrap_or(settings)
+            })
+    }
+
+    pub fn save(&self, db: &Database) -> rusqlite::Result<()> {
+        let values: Vec<(String, String)> = match serde_json::to_value(self) {
+            Ok(serde_json::Value::Object(fields)) => fields
+                .into_iter()
+                .map(|(key, value)| (key, value.to_string()))
+                .collect(),
+            _ => Vec::new(),
+        };
+        db.save_settings(&values)
+    }
+
+    /// These settings with the fields in `patch` replaced. Fails on a field
+    /// that doesn't exist or a value of the wrong type, leaving `self` as is.
+    pub fn merge(
+        &self,
+        patch: &serde_json::Map<String, serde_json::Value>,
+    ) -> Result<Settings, String> {
+        let mut value = serde_json::to_value(self).map_err(|e| e.to_string())?;
+        let fields = value
+            .as_object_mut()
+            .ok_or("Settings are not an object")?;
+        for (key, field) in patch {
+            if !fields.contains_key(key) {
+                return Err(format!("Unknown setting: {}", key));
+            }
+            fields.insert(key.clone(), field.clone());
+        }
+        let settings: Settings = serde_json::from_value(value)
+            .map_err(|e| format!("Invalid settings: {}", e))?;
+        settings.network.validate()?;
+        Ok(settings.clamped())
+    }
+
+    /// Numeric settings pulled into the ranges the backend supports
+    fn clamped(mut self) -> Settings {
+        self.ocr_parallelism = self.ocr_parallelism.clamp(1, MAX_OCR_WORKERS);
+        self.ocr_parallel_models = self.ocr_parallel_models.clamp(1, RECOGNITION_MODELS.len());
+        let (min_wait, max_wait) = AFTER_FRAME_WAIT_RANGE_MS;
+        self.after_frame_max_wait_ms = self.after_frame_max_wait_ms.clamp(min_wait, max_wait);
+        self.screenshot_path = self.screenshot_path.filter(|path| !path.trim().is_empty());
+        if !logging::is_valid_level(&self.log_level) {
+            self.log_level = logging::DEFAULT_LEVEL.to_string();
+        }
+        if self.api_server.port == 0 {
+            self.api_server.port = api_server::DEFAULT_PORT;
+        }
+        if self.email.port == 0 {
+            self.email.port = email::DEFAULT_PORT;
+        }
+        if self.share.port == 0 {
+            self.share.port = share::DEFAULT_PORT;
+        }
+        self
+    }
+
+    /// Push these settings to the recorder, the capture backend, the OCR
+    /// workers, the logger, the HTTP clients, analytics, generated text and
+    /// the lossless original quota, dropping originals over a lowered quota
+    pub fn apply(&self, state: &RecordingState, db: &mut Database) -> rusqlite::Result<()> {
+        let _ = logging::set_level(&self.log_level);
+        network::configure(&self.network);
+        analytics::set_enabled(self.analytics_enabled);
+        i18n::set_locale(self.locale);
+        capture_backend::configure(self.capture_backend);
+        *state.ocr_enabled.lock().unwrap() = self.ocr_enabled;
+        *state.ocr_language.lock().unwrap() = self.ocr_language.clone();
+        state.ocr_queue.set_parallelism(self.ocr_parallelism);
+        {
+            let mut config = state.ocr_config.lock().unwrap();
+            // Only rebuild engines when the provider or parallel models change
+            if config.provider != self.ocr_provider
+                || config.parallel_models != self.ocr_parallel_models
+            {
+                config.provider = self.ocr_provider;
+                config.parallel_models = self.ocr_parallel_models;
+                ocr::models_changed();
+            }
+        }
+        *state.state_diff_enabled.lock().unwrap() = self.state_diff_enabled;
+        *state.after_frame_max_wait_ms.lock().unwrap() = self.after_frame_max_wait_ms;
+        *state.video_clips_enabled.lock().unwrap() = self.video_clips_enabled;
+        *state.ax_tree_snapshot_enabled.lock().unwrap() = self.ax_tree_snapshot_enabled;
+        *state.smart_crop.lock().unwrap() = self.smart_crop;
+        *state.duplicate_frames.lock().unwrap() = self.duplicate_frames;
+        *state.keep_lossless_originals.lock().unwrap() = self.keep_lossless_originals;
+
+        db.set_original_quota(self.lossless_original_quota);
+        db.enforce_original_quota()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::smart_crop::SmartCropMode;
+    use serde_json::json;
+
+    fn patch(value: serde_json::Value) -> serde_json::Map<String, serde_json::Value> {
+        value.as_object().unwrap().clone()
+    }
+
+    #[test]
+    fn merge_replaces_only_the_given_fields() {
+        let settings = Settings::default()
+            .merge(&patch(json!({
+                "ocr_enabled": false,
+                "smart_crop": { "mode": "capture" },
+                "step_badge_style": { "enabled": true },
+                "duplicate_frames": "skip",
+                "locale": "de",
+            })))
+            .unwrap();
+
+        assert!(!settings.ocr_enabled);
+        assert_eq!(settings.smart_crop.mode, SmartCropMode::Capture);
+        assert_eq!(settings.smart_crop.padding, 160);
+        assert!(settings.step_badge_style.enabled);
+        assert_eq!(settings.step_badge_style.size, 18.0);
+        assert_eq!(settings.duplicate_frames, DuplicateFrames::Skip);
+        assert_eq!(settings.locale, Locale::De);
+        assert_eq!(settings.ocr_language, AUTO_LANGUAGE);
+    }
+
+    #[test]
+    fn merge_rejects_unknown_fields_and_wrong_types() {
+        let settings = Settings::default();
+        assert!(settings.merge(&patch(json!({ "ocr_enabeld": false }))).is_err());
+        assert!(settings
+            .merge(&patch(json!({ "ocr_parallelism": "lots" })))
+            .is_err());
+    }
+
+    #[test]
+    fn merge_clamps_numbers_to_supported_ranges() {
+        let settings = Settings::default()
+            .merge(&patch(json!({
+                "ocr_parallelism": 0,
+                "after_frame_max_wait_ms": 60_000,
+                "screenshot_path": "  ",
+                "log_level": "verbose",
+            })))
+            .unwrap();
+
+        assert_eq!(settings.ocr_parallelism, 1);
+        assert_eq!(settings.after_frame_max_wait_ms, 5000);
+        assert_eq!(settings.screenshot_path, None);
+        assert_eq!(settings.log_level, logging::DEFAULT_LEVEL);
+    }
+
+    #[test]
+    fn settings_survive_a_save_and_skip_unreadable_values() {
+        let dir = std::env::temp_dir().join(format!("stepsnap_settings_{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let db = Database::new(dir.clone()).unwrap();
+        assert_eq!(Settings::load(&db), Settings::default());
+
+        let settings = Settings::default()
+            .merge(&patch(json!({
+                "ocr_language": "latin",
+                "keep_lossless_originals": true,
+            })))
+            .unwrap();
+        settings.save(&db).unwrap();
+        db.save_settings(&[("video_clips_enabled".to_string(), "\"yes\"".to_string())])
+            .unwrap();
+
+        assert_eq!(Settings::load(&db), settings);
+
+        drop(db);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn renamed_fields_load_until_saved_under_their_new_name() {
+        let dir = std::env::temp_dir().join(format!("stepsnap_settings_{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let db = Database::new(dir.clone()).unwrap();
+
+        db.save_settings(&[("ocr_threads".to_string(), "3".to_string())])
+            .unwrap();
+        assert_eq!(Settings::load(&db).ocr_parallel_models, 3);
+
+        db.save_settings(&[("ocr_parallel_models".to_string(), "1".to_string())])
+            .unwrap();
+        assert_eq!(Settings::load(&db).ocr_parallel_models, 1);
+
+        drop(db);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,582 @@
+//! Shareable links, so people without the app can read a recording in a
+//! browser.
+//!
+//! A link points at a snapshot: the recording is rendered once, when the
+//! link is made, to a single HTML page with its screenshots inlined, and
+//! later edits don't change it. The page is either served by the app itself,
+//! on `0.0.0.0:<port>` under an unguessable token while the app runs, or
+//! uploaded with HTTP `PUT` to a static host (any WebDAV folder, or a server
+//! accepting uploads) that serves it from then on.
+//!
+//! Links expire: served ones stop answering and are deleted, uploaded ones
+//! are deleted from the host by the hourly cleanup. A password can only
+//! protect served links, since a static host has no way to check it. The
+//! server stops once no served links are left.
+
+use crate::database::{Database, ShareLink};
+use crate::db_worker::DatabaseWorker;
+use crate::settings::SettingsState;
+use crate::{
+    analytics, credentials, export, network, render_markdown_source, ExportStyles, MarkdownSource,
+};
+use axum::extract::{Form, Path, State};
+use axum::http::{header, StatusCode};
+use axum::response::{IntoResponse, Response};
+use axum::routing::get;
+use axum::Router;
+use base64::Engine;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::net::{IpAddr, Ipv4Addr, SocketAddr, UdpSocket};
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
+use tokio::sync::oneshot;
+
+pub const DEFAULT_PORT: u16 = 47822;
+pub const UPLOAD_PASSWORD_CREDENTIAL: &str = "share_upload_password";
+const CLEANUP_INTERVAL: Duration = Duration::from_secs(60 * 60);
+/// Slows password guessing on served links
+const WRONG_PASSWORD_DELAY: Duration = Duration::from_secs(1);
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ShareSettings {
+    /// Port served links listen on, on every interface
+    pub port: u16,
+    /// Address others reach this computer at, e.g. `http://docs-pc:47822`;
+    /// empty to use its network address
+    pub public_url: String,
+    /// Folder uploaded links are `PUT` into; empty when not set up
+    pub upload_url: String,
+    pub upload_username: String,
+    /// Where the static host publishes `upload_url`; the same when empty
+    pub upload_public_url: String,
+    pub default_expiry_hours: u32,
+}
+
+impl Default for ShareSettings {
+    fn default() -> Self {
+        Self {
+            port: DEFAULT_PORT,
+            public_url: String::new(),
+            upload_url: String::new(),
+            upload_username: String::new(),
+            upload_public_url: String::new(),
+            default_expiry_hours: 24 * 7,
+        }
+    }
+}
+
+struct RunningServer {
+    address: SocketAddr,
+    shutdown: oneshot::Sender<()>,
+}
+
+static SERVER: Mutex<Option<RunningServer>> = Mutex::new(None);
+
+fn shares_dir(db: &Database) -> PathBuf {
+    db.data_dir().join("shares")
+}
+
+fn page_path(db: &Database, token: &str) -> PathBuf {
+    shares_dir(db).join(format!("{}.html", token))
+}
+
+fn hash_password(password: &str, salt: &str) -> String {
+    let digest = Sha256::digest(format!("{}:{}", salt, password).as_bytes());
+    format!("{}:{:x}", salt, digest)
+}
+
+/// Compares in time independent of where the hashes differ
+fn password_matches(password: &str, stored: &str) -> bool {
+    let Some((salt, _)) = stored.split_once(':') else {
+        return false;
+    };
+    let computed = hash_password(password, salt);
+    computed.len() == stored.len()
+        && computed
+            .bytes()
+            .zip(stored.bytes())
+            .fold(0, |diff, (a, b)| diff | (a ^ b))
+            == 0
+}
+
+fn now_ms() -> i64 {
+    chrono::Utc::now().timestamp_millis()
+}
+
+/// This computer's address on its network, which the OS picks for outgoing
+/// traffic. Connecting a UDP socket sends nothing.
+fn network_address() -> Option<IpAddr> {
+    let socket = UdpSocket::bind((Ipv4Addr::UNSPECIFIED, 0)).ok()?;
+    socket.connect((Ipv4Addr::new(192, 0, 2, 1), 80)).ok()?;
+    let ip = socket.local_addr().ok()?.ip();
+    (!ip.is_loopback() && !ip.is_unspecified()).then_some(ip)
+}
+
+/// `markdown` as a standalone page, its images already inlined
+fn render_page(title: &str, markdown: &str, expires_at: i64) -> String {
+    let expires = chrono::DateTime::from_timestamp_millis(expires_at)
+        .map(|date| date.format("%Y-%m-%d %H:%M UTC").to_string())
+        .unwrap_or_default();
+    format!(
+        "<!DOCTYPE html><html><head><meta charset=\"utf-8\">\
+         <meta name=\"viewport\" content=\"width=device-width, initial-scale=1\">\
+         <meta name=\"robots\" content=\"noindex\"><title>{title}</title></head>\
+         <body style=\"font-family: system-ui, sans-serif; max-width: 800px; margin: 0 auto; \
+         padding: 2rem; line-height: 1.6; color: #1f1f1f;\">{body}\
+         <footer style=\"margin-top: 3rem; font-size: 0.8rem; color: #777;\">\
+         Shared from StepSnap. This link expires {expires}.</footer></body></html>",
+        title = export::escape_html(title),
+        body = export::markdown_to_html(markdown),
+        expires = expires,
+    )
+}
+
+fn password_form(wrong: bool) -> String {
+    format!(
+        "<!DOCTYPE html><html><head><meta charset=\"utf-8\">\
+         <meta name=\"robots\" content=\"noindex\"><title>Password required</title></head>\
+         <body style=\"font-family: system-ui, sans-serif; max-width: 360px; margin: 4rem auto;\">\
+         <form method=\"post\"><p>This guide is protected by a password.</p>{}\
+         <input type=\"password\" name=\"password\" autofocus style=\"width: 100%; padding: 0.5rem;\">\
+         <button type=\"submit\" style=\"margin-top: 0.75rem; padding: 0.5rem 1rem;\">View</button>\
+         </form></body></html>",
+        if wrong {
+            "<p style=\"color: #c00;\">That password isn't right.</p>"
+        } else {
+            ""
+        }
+    )
+}
+
+/// The recording as a page with inlined screenshots
+fn render_recording(
+    app: &AppHandle,
+    recording_id: &str,
+    expires_at: i64,
+) -> Result<String, String> {
+    let (locale, styles) = {
+        let settings = app.state::<SettingsState>();
+        let settings = settings.0.lock().unwrap();
+        (settings.locale, ExportStyles::from_settings(&settings))
+    };
+    let id = recording_id.to_string();
+    let source = app
+        .state::<DatabaseWorker>()
+        .run_blocking(move |db| MarkdownSource::load(db, &id))?;
+    let name = source.recording.recording.name.clone();
+    let markdown = render_markdown_source(source, locale, styles);
+    let mut failed = None;
+    let markdown = export::rewrite_image_links(&markdown, |path| {
+        let mime = match std::path::Path::new(path)
+            .extension()
+            .and_then(|ext| ext.to_str())
+        {
+            Some("png") => "image/png",
+            Some("webp") => "image/webp",
+            _ => "image/jpeg",
+        };
+        match std::fs::read(path) {
+            Ok(bytes) => Some(format!(
+                "data:{};base64,{}",
+                mime,
+                base64::engine::general_purpose::STANDARD.encode(bytes)
+            )),
+            Err(e) => {
+                failed.get_or_insert(format!("Failed to read {}: {}", path, e));
+                None
+            }
+        }
+    });
+    if let Some(e) = failed {
+        return Err(e);
+    }
+    Ok(render_page(&name, &markdown, expires_at))
+}
+
+// ── Serving ────────────────────────────────────────────────────────────
+
+/// Listen on `port` on every interface, unless already running there
+async fn start(app: AppHandle, port: u16) -> Result<SocketAddr, String> {
+    if let Some(address) = address() {
+        if address.port() == port {
+            return Ok(address);
+        }
+        stop();
+    }
+    let listener = tokio::net::TcpListener::bind((Ipv4Addr::UNSPECIFIED, port))
+        .await
+        .map_err(|e| format!("Failed to listen on port {}: {}", port, e))?;
+    let address = listener.local_addr().map_err(|e| e.to_string())?;
+    let (shutdown, shutdown_rx) = oneshot::channel();
+    *SERVER.lock().unwrap() = Some(RunningServer { address, shutdown });
+    let router = Router::new()
+        .route("/s/{token}", get(view).post(unlock))
+        .with_state(app);
+    tauri::async_runtime::spawn(async move {
+        let served = axum::serve(listener, router)
+            .with_graceful_shutdown(async {
+                let _ = shutdown_rx.await;
+            })
+            .await;
+        if let Err(e) = served {
+            tracing::error!("Share server stopped: {}", e);
+        }
+    });
+    tracing::info!(%address, "Share server listening");
+    Ok(address)
+}
+
+fn stop() {
+    if let Some(server) = SERVER.lock().unwrap().take() {
+        let _ = server.shutdown.send(());
+        tracing::info!(address = %server.address, "Share server stopped");
+    }
+}
+
+fn address() -> Option<SocketAddr> {
+    SERVER.lock().unwrap().as_ref().map(|server| server.address)
+}
+
+fn html(status: StatusCode, body: String) -> Response {
+    (
+        status,
+        [
+            (header::CONTENT_TYPE, "text/html; charset=utf-8"),
+            (header::CACHE_CONTROL, "no-store"),
+            (header::REFERRER_POLICY, "no-referrer"),
+            (
+                header::CONTENT_SECURITY_POLICY,
+                "default-src 'none'; img-src data:; style-src 'unsafe-inline'; form-action 'self'",
+            ),
+        ],
+        body,
+    )
+        .into_response()
+}
+
+fn not_found() -> Response {
+    html(
+        StatusCode::NOT_FOUND,
+        "<!DOCTYPE html><p>This link doesn't exist or has expired.</p>".to_string(),
+    )
+}
+
+/// The served link `token`, if it is still valid
+async fn served_link(app: &AppHandle, token: &str) -> Option<(ShareLink, PathBuf)> {
+    let token = token.to_string();
+    let link = app
+        .state::<DatabaseWorker>()
+        .run(move |db| {
+            let link = db.get_share_link(&token).map_err(|e| e.to_string())?;
+            Ok(link.map(|link| (link, page_path(db, &token))))
+        })
+        .await
+        .ok()??;
+    (!link.0.hosted && link.0.expires_at > now_ms()).then_some(link)
+}
+
+async fn page(path: PathBuf) -> Response {
+    match tokio::fs::read_to_string(&path).await {
+        Ok(body) => html(StatusCode::OK, body),
+        Err(_) => not_found(),
+    }
+}
+
+async fn view(State(app): State<AppHandle>, Path(token): Path<String>) -> Response {
+    match served_link(&app, &token).await {
+        Some((link, _)) if link.has_password => html(StatusCode::OK, password_form(false)),
+        Some((_, path)) => page(path).await,
+        None => not_found(),
+    }
+}
+
+#[derive(Deserialize)]
+struct Unlock {
+    password: String,
+}
+
+async fn unlock(
+    State(app): State<AppHandle>,
+    Path(token): Path<String>,
+    Form(form): Form<Unlock>,
+) -> Response {
+    let Some((link, path)) = served_link(&app, &token).await else {
+        return not_found();
+    };
+    let unlocked = match link.password_hash.as_deref() {
+        Some(hash) => password_matches(&form.password, hash),
+        None => true,
+    };
+    if !unlocked {
+        tokio::time::sleep(WRONG_PASSWORD_DELAY).await;
+        return html(StatusCode::UNAUTHORIZED, password_form(true));
+    }
+    page(path).await
+}
+
+// ── Uploading ──────────────────────────────────────────────────────────
+
+fn upload_client() -> Result<reqwest::Client, String> {
+    network::client_builder()?
+        .timeout(Duration::from_secs(120))
+        .build()
+        .map_err(|e| format!("Failed to create HTTP client: {}", e))
+}
+
+fn folder_url(url: &str, setting: &str) -> Result<reqwest::Url, String> {
+    let url = url.trim().trim_end_matches('/');
+    if url.is_empty() {
+        return Err(format!("Set the {} in Settings", setting));
+    }
+    reqwest::Url::parse(&format!("{}/", url)).map_err(|e| format!("Invalid {}: {}", setting, e))
+}
+
+fn upload_request(
+    config: &ShareSettings,
+    method: reqwest::Method,
+    token: &str,
+) -> Result<reqwest::RequestBuilder, String> {
+    let url = folder_url(&config.upload_url, "upload URL")?
+        .join(&format!("{}.html", token))
+        .map_err(|e| e.to_string())?;
+    let mut request = upload_client()?.request(method, url);
+    if !config.upload_username.trim().is_empty() {
+        let password = credentials::get(UPLOAD_PASSWORD_CREDENTIAL)?;
+        request = request.basic_auth(config.upload_username.trim(), password);
+    }
+    Ok(request)
+}
+
+async fn upload(config: &ShareSettings, token: &str, page: String) -> Result<String, String> {
+    let response = upload_request(config, reqwest::Method::PUT, token)?
+        .header(reqwest::header::CONTENT_TYPE, "text/html; charset=utf-8")
+        .body(page)
+        .send()
+        .await
+        .map_err(|e| format!("Couldn't reach the upload host: {}", e))?;
+    if !response.status().is_success() {
+        return Err(format!(
+            "The upload host refused the page: {}",
+            response.status()
+        ));
+    }
+    let public = match config.upload_public_url.trim() {
+        "" => &config.upload_url,
+        url => url,
+    };
+    Ok(folder_url(public, "public URL")?
+        .join(&format!("{}.html", token))
+        .map_err(|e| e.to_string())?
+        .to_string())
+}
+
+/// Take down `link`'s page and forget it
+async fn remove(app: &AppHandle, link: &ShareLink) -> Result<(), String> {
+    if link.hosted {
+        let config = app.state::<SettingsState>().0.lock().unwrap().share.clone();
+        let response = upload_request(&config, reqwest::Method::DELETE, &link.token)?
+            .send()
+            .await
+            .map_err(|e| format!("Couldn't reach the upload host: {}", e))?;
+        let status = response.status();
+        if !status.is_success() && status != reqwest::StatusCode::NOT_FOUND {
+            return Err(format!("The upload host refused the delete: {}", status));
+        }
+    }
+    let token = link.token.clone();
+    let serving = app
+        .state::<DatabaseWorker>()
+        .run(move |db| {
+            let _ = std::fs::remove_file(page_path(db, &token));
+            db.delete_share_link(&token).map_err(|e| e.to_string())?;
+            Ok(db
+                .list_share_links(None)
+                .map_err(|e| e.to_string())?
+                .iter()
+                .any(|link| !link.hosted))
+        })
+        .await?;
+    if !serving {
+        stop();
+    }
+    Ok(())
+}
+
+/// Remove expired links; uploaded ones that can't be deleted yet are retried
+/// next time
+async fn remove_expired(app: &AppHandle) {
+    let links = app
+        .state::<DatabaseWorker>()
+        .run(|db| db.list_share_links(None).map_err(|e| e.to_string()))
+        .await
+        .unwrap_or_default();
+    for link in links.iter().filter(|link| link.expires_at <= now_ms()) {
+        match remove(app, link).await {
+            Ok(()) => tracing::info!(token = %link.token, "Expired share link removed"),
+            Err(e) => {
+                tracing::warn!(token = %link.token, "Failed to remove expired share link: {}", e)
+            }
+        }
+    }
+}
+
+/// Serve the links left from earlier runs and remove expired ones hourly
+pub fn init(app: &AppHandle) {
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        remove_expired(&app).await;
+        let serving = app
+            .state::<DatabaseWorker>()
+            .run(|db| db.list_share_links(None).map_err(|e| e.to_string()))
+            .await
+            .is_ok_and(|links| links.iter().any(|link| !link.hosted));
+        if serving {
+            let port = app.state::<SettingsState>().0.lock().unwrap().share.port;
+            if let Err(e) = start(app.clone(), port).await {
+                tracing::error!("Failed to start the share server: {}", e);
+            }
+        }
+        loop {
+            tokio::time::sleep(CLEANUP_INTERVAL).await;
+            remove_expired(&app).await;
+        }
+    });
+}
+
+// ── Commands ───────────────────────────────────────────────────────────
+
+/// Share a snapshot of `recording_id` for `expires_in_hours` (the settings'
+/// default when not given), served by the app or, with `upload`, uploaded to
+/// the static host
+#[tauri::command]
+pub async fn create_share_link(
+    app: AppHandle,
+    recording_id: String,
+    expires_in_hours: Option<u32>,
+    password: Option<String>,
+    upload: bool,
+) -> Result<ShareLink, String> {
+    let config = app.state::<SettingsState>().0.lock().unwrap().share.clone();
+    let password = password.filter(|password| !password.is_empty());
+    if upload && password.is_some() {
+        return Err(
+            "Passwords only work for links served by the app; a static host can't check them"
+                .to_string(),
+        );
+    }
+    let hours = expires_in_hours
+        .unwrap_or(config.default_expiry_hours)
+        .max(1);
+    let created_at = now_ms();
+    let expires_at = created_at + i64::from(hours) * 60 * 60 * 1000;
+    let token = format!(
+        "{}{}",
+        uuid::Uuid::new_v4().simple(),
+        uuid::Uuid::new_v4().simple()
+    );
+    let page = {
+        let app = app.clone();
+        let recording_id = recording_id.clone();
+        tauri::async_runtime::spawn_blocking(move || {
+            render_recording(&app, &recording_id, expires_at)
+        })
+        .await
+        .map_err(|e| e.to_string())??
+    };
+
+    let url = if upload {
+        upload(&config, &token, page).await?
+    } else {
+        let page_token = token.clone();
+        app.state::<DatabaseWorker>()
+            .run(move |db| {
+                std::fs::create_dir_all(shares_dir(db)).map_err(|e| e.to_string())?;
+                std::fs::write(page_path(db, &page_token), page).map_err(|e| e.to_string())
+            })
+            .await?;
+        let address = start(app.clone(), config.port).await?;
+        let base = match config.public_url.trim().trim_end_matches('/') {
+            "" => {
+                let host = network_address().unwrap_or(IpAddr::V4(Ipv4Addr::LOCALHOST));
+                format!("http://{}", SocketAddr::new(host, address.port()))
+            }
+            url => url.to_string(),
+        };
+        format!("{}/s/{}", base, token)
+    };
+
+    let link = ShareLink {
+        token: token.clone(),
+        recording_id,
+        url,
+        created_at,
+        expires_at,
+        hosted: upload,
+        password_hash: password
+            .as_deref()
+            .map(|password| hash_password(password, &uuid::Uuid::new_v4().simple().to_string())),
+        has_password: password.is_some(),
+    };
+    let saved = link.clone();
+    app.state::<DatabaseWorker>()
+        .run(move |db| db.add_share_link(&saved).map_err(|e| e.to_string()))
+        .await?;
+    analytics::track("share_link");
+    tracing::info!(recording_id = %link.recording_id, hosted = upload, hours, "Share link created");
+    Ok(link)
+}
+
+#[tauri::command]
+pub async fn list_share_links(
+    db: tauri::State<'_, DatabaseWorker>,
+    recording_id: Option<String>,
+) -> Result<Vec<ShareLink>, String> {
+    db.run(move |db| {
+        db.list_share_links(recording_id.as_deref())
+            .map_err(|e| e.to_string())
+    })
+    .await
+}
+
+/// Take the link down before it expires
+#[tauri::command]
+pub async fn revoke_share_link(app: AppHandle, token: String) -> Result<(), String> {
+    let id = token.clone();
+    let link = app
+        .state::<DatabaseWorker>()
+        .run(move |db| {
+            db.get_share_link(&id)
+                .map_err(|e| e.to_string())?
+                .ok_or_else(|| "Share link not found".to_string())
+        })
+        .await?;
+    remove(&app, &link).await?;
+    tracing::info!(token = %token, "Share link revoked");
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn checks_passwords_and_renders_standalone_pages() {
+        let stored = hash_password("hunter2", "salt");
+        assert!(password_matches("hunter2", &stored));
+        assert!(!password_matches("hunter3", &stored));
+        assert!(!password_matches("hunter2", "no-salt"));
+
+        let page = render_page(
+            "Reset <password>",
+            "### Step 1\n\n![Step 1](<data:image/png;base64,AAAA>)\n",
+            0,
+        );
+        assert!(page.contains("<title>Reset &lt;password&gt;</title>"));
+        assert!(page.contains("src=\"data:image/png;base64,AAAA\""));
+        assert!(page.contains("expires 1970-01-01 00:00 UTC"));
+    }
+}
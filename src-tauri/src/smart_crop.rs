@@ -0,0 +1,181 @@
+//! Focused screenshots around the clicked element.
+//!
+//! `focus_rect` picks the part of a screenshot worth showing for a step: the
+//! clicked element's bounds plus some context, or an area around the click
+//! when the element is unknown or too large to focus on. The result keeps
+//! the screenshot's aspect ratio so cropped steps line up in documents.
+//! Depending on `SmartCropMode` it is applied by the recorder before the
+//! screenshot is written, or only to the copies rendered for export.
+
+use crate::accessibility::ElementBounds;
+use serde::{Deserialize, Serialize};
+
+/// Crops smaller than this fraction of the screenshot (per side) lose too
+/// much context to follow.
+const MIN_SIDE_FRACTION: f32 = 1.0 / 3.0;
+
+/// Crops covering more than this fraction of the screenshot aren't worth it.
+const MAX_AREA_FRACTION: f32 = 0.8;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SmartCropMode {
+    #[default]
+    Off,
+    /// Crop when the screenshot is captured. The step is marked cropped.
+    Capture,
+    /// Keep full screenshots and crop the copies rendered for export.
+    Export,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SmartCropSettings {
+    pub mode: SmartCropMode,
+    /// Context kept around the element or click, in logical pixels
+    pub padding: u32,
+}
+
+impl Default for SmartCropSettings {
+    fn default() -> Self {
+        Self {
+            mode: SmartCropMode::Off,
+            padding: 160,
+        }
+    }
+}
+
+/// An area of a screenshot in whole pixels
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CropRect {
+    pub x: u32,
+    pub y: u32,
+    pub width: u32,
+    pub height: u32,
+}
+
+/// Move `start..start + len` inside `0..limit`, shortening it only when it's
+/// longer than `limit`.
+fn fit_span(start: f32, len: f32, limit: f32) -> (f32, f32) {
+    let len = len.min(limit);
+    (start.min(limit - len).max(0.0), len)
+}
+
+/// The focused area of a `width` x `height` screenshot for a step clicked
+/// at `click` on an element with `bounds` (both in screenshot pixels). None
+/// when there's nothing to focus on or the crop would keep most of the
+/// screenshot anyway.
+pub fn focus_rect(
+    width: u32,
+    height: u32,
+    bounds: Option<ElementBounds>,
+    click: Option<(i32, i32)>,
+    padding: u32,
+) -> Option<CropRect> {
+    if width == 0 || height == 0 {
+        return None;
+    }
+    let (image_w, image_h) = (width as f32, height as f32);
+
+    // Elements covering a quarter of the screen or more are windows or panes
+    let element = bounds.filter(|bounds| {
+        bounds.width > 0
+            && bounds.height > 0
+            && (bounds.width as f32 * bounds.height as f32) < image_w * image_h / 4.0
+    });
+    let (x, y, w, h) = match (element, click) {
+        (Some(bounds), _) => (
+            bounds.x as f32,
+            bounds.y as f32,
+            bounds.width as f32,
+            bounds.height as f32,
+        ),
+        (None, Some((x, y))) => (x as f32, y as f32, 0.0, 0.0),
+        (None, None) => return None,
+    };
+    if x + w < 0.0 || y + h < 0.0 || x > image_w || y > image_h {
+        return None;
+    }
+
+    // Padded focus, grown to the minimum size and the screenshot's aspect
+    let padding = padding as f32;
+    let (x, y, w, h) = (
+        x - padding,
+        y - padding,
+        w + padding * 2.0,
+        h + padding * 2.0,
+    );
+    let aspect = image_w / image_h;
+    let mut target_w = w.max(image_w * MIN_SIDE_FRACTION);
+    let mut target_h = h.max(image_h * MIN_SIDE_FRACTION);
+    if target_w / target_h > aspect {
+        target_h = target_w / aspect;
+    } else {
+        target_w = target_h * aspect;
+    }
+
+    let centre = |start: f32, len: f32, target: f32| start - (target - len) / 2.0;
+    let (left, crop_w) = fit_span(centre(x, w, target_w), target_w, image_w);
+    let (top, crop_h) = fit_span(centre(y, h, target_h), target_h, image_h);
+    if crop_w * crop_h > image_w * image_h * MAX_AREA_FRACTION {
+        return None;
+    }
+
+    let rect = CropRect {
+        x: left.round() as u32,
+        y: top.round() as u32,
+        width: crop_w.round() as u32,
+        height: crop_h.round() as u32,
+    };
+    Some(CropRect {
+        width: rect.width.min(width - rect.x),
+        height: rect.height.min(height - rect.y),
+        ..rect
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn centres_on_a_small_element_with_padding() {
+        let bounds = ElementBounds {
+            x: 900,
+            y: 500,
+            width: 120,
+            height: 40,
+        };
+        let rect = focus_rect(1920, 1080, Some(bounds), Some((950, 520)), 100).unwrap();
+
+        assert_eq!((rect.width, rect.height), (640, 360));
+        assert_eq!((rect.x, rect.y), (640, 340));
+    }
+
+    #[test]
+    fn falls_back_to_the_click_and_stays_inside_the_screenshot() {
+        let rect = focus_rect(1920, 1080, None, Some((10, 1070)), 160).unwrap();
+
+        assert_eq!((rect.x, rect.width), (0, 640));
+        assert_eq!(rect.y + rect.height, 1080);
+    }
+
+    #[test]
+    fn large_elements_use_the_click_instead() {
+        let window = ElementBounds {
+            x: 0,
+            y: 0,
+            width: 1600,
+            height: 900,
+        };
+        let rect = focus_rect(1920, 1080, Some(window), Some((1500, 200)), 100).unwrap();
+        assert!(rect.x <= 1500 && rect.x + rect.width >= 1500);
+        assert_eq!(rect.width, 640);
+    }
+
+    #[test]
+    fn skips_crops_that_keep_most_of_the_screenshot() {
+        assert_eq!(focus_rect(1920, 1080, None, None, 100), None);
+        assert_eq!(focus_rect(800, 600, None, Some((400, 300)), 400), None);
+    }
+}
@@ -0,0 +1,711 @@
+//! Cloud sync of the library through a shared folder of recording bundles,
+//! the first step toward the same library on several devices.
+//!
+//! Each recording is pushed as `<key>.stepsnap` (see `bundle`) to the
+//! folder of a storage provider (`sync_provider`), keyed by the id it had
+//! where it was first pushed. `sync_state` remembers, per recording, its key,
+//! the bundle's ETag and the recording's `updated_at` as of the last sync, so
+//! each run can tell which side changed:
+//!
+//! - changed here only: push; changed there only: pull, replacing the local
+//!   copy (which gets a new id, as imports do)
+//! - changed on both sides, or deleted elsewhere but still here: flagged as
+//!   a conflict until the user keeps one side with `resolve_sync_conflict`,
+//!   so local recordings are never deleted without asking
+//! - deleted here: deleted there too, unless it changed there, in which case
+//!   it is pulled back
+//!
+//! Runs are started by `sync_now` or every `interval_minutes` when enabled,
+//! one at a time, and reported as `sync-finished`.
+
+use crate::database::{Database, Recording, SyncState};
+use crate::db_worker::DatabaseWorker;
+use crate::settings::SettingsState;
+use crate::sync_provider::{StorageProvider, WebDav, S3};
+use crate::{analytics, bundle, credentials, network, safe_db_lock, DatabaseState};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter, Manager};
+
+pub const S3_SECRET_CREDENTIAL: &str = "sync_s3_secret_key";
+pub const WEBDAV_PASSWORD_CREDENTIAL: &str = "sync_webdav_password";
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(300);
+/// How often the scheduler checks whether a sync is due
+const SCHEDULER_TICK: Duration = Duration::from_secs(60);
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SyncProvider {
+    #[default]
+    S3,
+    WebDav,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct S3Settings {
+    /// Empty for AWS; the service's URL for S3-compatible storage
+    pub endpoint: String,
+    pub region: String,
+    pub bucket: String,
+    /// Folder within the bucket
+    pub prefix: String,
+    pub access_key_id: String,
+    /// Bucket in the path rather than the host name, which most
+    /// S3-compatible services need
+    pub path_style: bool,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WebDavSettings {
+    /// Folder URL, e.g. `https://cloud.example.com/remote.php/dav/files/me/StepSnap`
+    pub url: String,
+    pub username: String,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SyncSettings {
+    /// Sync on a schedule; `sync_now` works either way
+    pub enabled: bool,
+    pub provider: SyncProvider,
+    pub interval_minutes: u32,
+    pub s3: S3Settings,
+    pub webdav: WebDavSettings,
+}
+
+impl Default for SyncSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            provider: SyncProvider::default(),
+            interval_minutes: 30,
+            s3: S3Settings::default(),
+            webdav: WebDavSettings::default(),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SyncSide {
+    Local,
+    Remote,
+}
+
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct SyncReport {
+    pushed: usize,
+    pulled: usize,
+    deleted: usize,
+    /// Recordings newly flagged as conflicts
+    conflicts: usize,
+    /// Recordings that failed, which the next run retries
+    errors: Vec<String>,
+    finished_at: i64,
+}
+
+#[derive(Serialize)]
+pub struct SyncStatus {
+    running: bool,
+    last_report: Option<SyncReport>,
+    /// Every synced recording, conflicts included
+    recordings: Vec<SyncState>,
+}
+
+static RUNNING: AtomicBool = AtomicBool::new(false);
+static LAST_REPORT: Mutex<Option<SyncReport>> = Mutex::new(None);
+
+const CHANGED_ON_BOTH: &str = "Changed on this device and another one";
+const DELETED_REMOTELY: &str = "Deleted on another device but kept here";
+const ALREADY_REMOTE: &str = "A different copy is already in the sync folder";
+
+#[derive(Debug, PartialEq)]
+enum Action {
+    Push {
+        recording_id: String,
+        key: String,
+    },
+    Pull {
+        key: String,
+        etag: String,
+        /// Local copy the pulled bundle replaces
+        replaces: Option<String>,
+    },
+    DeleteRemote {
+        recording_id: String,
+        key: String,
+    },
+    /// Delete the local copy of a bundle deleted elsewhere
+    DeleteLocal {
+        recording_id: String,
+    },
+    Conflict {
+        recording_id: String,
+        key: String,
+        reason: &'static str,
+    },
+    /// Drop the state of a recording gone from both sides
+    Forget {
+        recording_id: String,
+    },
+}
+
+/// What a run does, given each local recording's `updated_at`, each bundle's
+/// ETag and what the last run knew
+fn plan(
+    local: &HashMap<String, i64>,
+    remote: &HashMap<String, String>,
+    states: &[SyncState],
+) -> Vec<Action> {
+    let mut actions = Vec::new();
+    for state in states {
+        // Waits for `resolve_sync_conflict`
+        if state.conflict.is_some() {
+            continue;
+        }
+        let recording_id = state.recording_id.clone();
+        let key = state.remote_key.clone();
+        let local_changed = local
+            .get(&state.recording_id)
+            .map(|updated_at| *updated_at > state.local_updated_at);
+        let remote_etag = remote.get(&state.remote_key);
+        let remote_changed = remote_etag.map(|etag| Some(etag) != state.remote_etag.as_ref());
+        actions.push(match (local_changed, remote_changed) {
+            (None, None) => Action::Forget { recording_id },
+            (None, Some(false)) => Action::DeleteRemote { recording_id, key },
+            // Edits elsewhere win over a delete here
+            (None, Some(true)) => Action::Pull {
+                key,
+                etag: remote_etag.cloned().unwrap_or_default(),
+                replaces: None,
+            },
+            (Some(true), None) => Action::Push { recording_id, key },
+            (Some(false), None) => Action::Conflict {
+                recording_id,
+                key,
+                reason: DELETED_REMOTELY,
+            },
+            (Some(false), Some(false)) => continue,
+            (Some(true), Some(false)) => Action::Push { recording_id, key },
+            (Some(false), Some(true)) => Action::Pull {
+                key,
+                etag: remote_etag.cloned().unwrap_or_default(),
+                replaces: Some(recording_id),
+            },
+            (Some(true), Some(true)) => Action::Conflict {
+                recording_id,
+                key,
+                reason: CHANGED_ON_BOTH,
+            },
+        });
+    }
+
+    let known_ids: HashSet<&str> = states.iter().map(|s| s.recording_id.as_str()).collect();
+    let known_keys: HashSet<&str> = states.iter().map(|s| s.remote_key.as_str()).collect();
+    let mut new_local: Vec<&String> = local
+        .keys()
+        .filter(|id| !known_ids.contains(id.as_str()))
+        .collect();
+    new_local.sort();
+    for recording_id in new_local {
+        actions.push(if remote.contains_key(recording_id) {
+            Action::Conflict {
+                recording_id: recording_id.clone(),
+                key: recording_id.clone(),
+                reason: ALREADY_REMOTE,
+            }
+        } else {
+            Action::Push {
+                recording_id: recording_id.clone(),
+                key: recording_id.clone(),
+            }
+        });
+    }
+    let mut new_remote: Vec<(&String, &String)> = remote
+        .iter()
+        .filter(|(key, _)| !known_keys.contains(key.as_str()) && !local.contains_key(*key))
+        .collect();
+    new_remote.sort();
+    for (key, etag) in new_remote {
+        actions.push(Action::Pull {
+            key: key.clone(),
+            etag: etag.clone(),
+            replaces: None,
+        });
+    }
+    actions
+}
+
+fn temp_bundle() -> std::path::PathBuf {
+    std::env::temp_dir().join(format!(
+        "stepsnap_sync_{}.{}",
+        uuid::Uuid::new_v4(),
+        bundle::EXTENSION
+    ))
+}
+
+fn delete_local(db: &Database, recording_id: &str) -> Result<(), String> {
+    let cleanup = db
+        .delete_recording(recording_id)
+        .map_err(|e| e.to_string())?;
+    for file in cleanup.files {
+        let _ = std::fs::remove_file(file);
+    }
+    Ok(())
+}
+
+fn updated_at(db: &Database, recording_id: &str) -> Result<i64, String> {
+    db.get_recording(recording_id)
+        .map_err(|e| e.to_string())?
+        .map(|recording| recording.recording.updated_at)
+        .ok_or_else(|| format!("Recording not found: {}", recording_id))
+}
+
+/// Run `work` on the database off the async runtime
+async fn with_db<T: Send + 'static>(
+    app: &AppHandle,
+    work: impl FnOnce(&Database) -> Result<T, String> + Send + 'static,
+) -> Result<T, String> {
+    let app = app.clone();
+    tauri::async_runtime::spawn_blocking(move || {
+        let db = app.state::<DatabaseState>();
+        let db = safe_db_lock(&db)?;
+        work(&db)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+async fn push(
+    app: &AppHandle,
+    provider: &impl StorageProvider,
+    recording_id: String,
+    key: String,
+) -> Result<(), String> {
+    let (data, local_updated_at) = {
+        let recording_id = recording_id.clone();
+        with_db(app, move |db| {
+            let path = temp_bundle();
+            // Read together with the bundle, so edits made during the upload
+            // count as changes for the next run
+            let local_updated_at = updated_at(db, &recording_id)?;
+            let exported = bundle::export(db, &recording_id, &path)
+                .and_then(|_| std::fs::read(&path).map_err(|e| e.to_string()));
+            let _ = std::fs::remove_file(&path);
+            Ok((exported?, local_updated_at))
+        })
+        .await?
+    };
+    let etag = provider.put(&key, data).await?;
+    with_db(app, move |db| {
+        db.save_sync_state(&SyncState {
+            recording_id,
+            remote_key: key,
+            remote_etag: Some(etag),
+            local_updated_at,
+            synced_at: chrono::Utc::now().timestamp_millis(),
+            conflict: None,
+        })
+        .map_err(|e| e.to_string())
+    })
+    .await
+}
+
+async fn pull(
+    app: &AppHandle,
+    provider: &impl StorageProvider,
+    key: String,
+    etag: String,
+    replaces: Option<String>,
+) -> Result<(), String> {
+    let data = provider.get(&key).await?;
+    let path = temp_bundle();
+    std::fs::write(&path, data).map_err(|e| e.to_string())?;
+    let result = {
+        let path = path.clone();
+        with_db(app, move |db| {
+            let recording_id = bundle::import(db, &path)?;
+            if let Some(old) = replaces {
+                delete_local(db, &old)?;
+                db.delete_sync_state(&old).map_err(|e| e.to_string())?;
+            }
+            db.save_sync_state(&SyncState {
+                local_updated_at: updated_at(db, &recording_id)?,
+                recording_id,
+                remote_key: key,
+                remote_etag: Some(etag),
+                synced_at: chrono::Utc::now().timestamp_millis(),
+                conflict: None,
+            })
+            .map_err(|e| e.to_string())
+        })
+        .await
+    };
+    let _ = std::fs::remove_file(&path);
+    result
+}
+
+async fn perform(
+    app: &AppHandle,
+    provider: &impl StorageProvider,
+    action: Action,
+    report: &mut SyncReport,
+) -> Result<(), String> {
+    match action {
+        Action::Push { recording_id, key } => {
+            push(app, provider, recording_id, key).await?;
+            report.pushed += 1;
+        }
+        Action::Pull {
+            key,
+            etag,
+            replaces,
+        } => {
+            pull(app, provider, key, etag, replaces).await?;
+            report.pulled += 1;
+        }
+        Action::DeleteRemote { recording_id, key } => {
+            provider.delete(&key).await?;
+            with_db(app, move |db| {
+                db.delete_sync_state(&recording_id)
+                    .map_err(|e| e.to_string())
+            })
+            .await?;
+            report.deleted += 1;
+        }
+        Action::DeleteLocal { recording_id } => {
+            with_db(app, move |db| {
+                delete_local(db, &recording_id)?;
+                db.delete_sync_state(&recording_id)
+                    .map_err(|e| e.to_string())
+            })
+            .await?;
+            report.deleted += 1;
+        }
+        Action::Conflict {
+            recording_id,
+            key,
+            reason,
+        } => {
+            with_db(app, move |db| {
+                db.set_sync_conflict(&recording_id, &key, reason)
+                    .map_err(|e| e.to_string())
+            })
+            .await?;
+            report.conflicts += 1;
+        }
+        Action::Forget { recording_id } => {
+            with_db(app, move |db| {
+                db.delete_sync_state(&recording_id)
+                    .map_err(|e| e.to_string())
+            })
+            .await?;
+        }
+    }
+    Ok(())
+}
+
+/// Build the configured provider and hand it to `$body`, which is generic
+/// over providers
+macro_rules! with_provider {
+    ($app:expr, |$provider:ident| $body:expr) => {{
+        let config = $app.state::<SettingsState>().0.lock().unwrap().sync.clone();
+        let client = network::client_builder()?
+            .timeout(REQUEST_TIMEOUT)
+            .build()
+            .map_err(|e| format!("Failed to create HTTP client: {}", e))?;
+        match config.provider {
+            SyncProvider::S3 => {
+                let secret = credentials::get(S3_SECRET_CREDENTIAL)?.unwrap_or_default();
+                let $provider = S3::new(client, &config.s3, secret)?;
+                $body
+            }
+            SyncProvider::WebDav => {
+                let password = credentials::get(WEBDAV_PASSWORD_CREDENTIAL)?.unwrap_or_default();
+                let $provider = WebDav::new(client, &config.webdav, password)?;
+                $body
+            }
+        }
+    }};
+}
+
+async fn run(app: &AppHandle, provider: &impl StorageProvider) -> Result<SyncReport, String> {
+    let remote: HashMap<String, String> = provider
+        .list()
+        .await?
+        .into_iter()
+        .map(|object| (object.key, object.etag))
+        .collect();
+    let (recordings, states) = with_db(app, |db| {
+        let recordings = db.list_recordings().map_err(|e| e.to_string())?;
+        let states = db.list_sync_states().map_err(|e| e.to_string())?;
+        Ok((recordings, states))
+    })
+    .await?;
+    let local: HashMap<String, i64> = recordings
+        .into_iter()
+        .map(|Recording { id, updated_at, .. }| (id, updated_at))
+        .collect();
+
+    let mut report = SyncReport::default();
+    for action in plan(&local, &remote, &states) {
+        let description = format!("{:?}", action);
+        if let Err(e) = perform(app, provider, action, &mut report).await {
+            tracing::warn!(action = %description, "Sync step failed: {}", e);
+            report.errors.push(e);
+        }
+    }
+    Ok(report)
+}
+
+/// Clears `RUNNING` however the run ends
+struct RunGuard;
+
+impl Drop for RunGuard {
+    fn drop(&mut self) {
+        RUNNING.store(false, Ordering::SeqCst);
+    }
+}
+
+async fn sync(app: &AppHandle) -> Result<SyncReport, String> {
+    if RUNNING.swap(true, Ordering::SeqCst) {
+        return Err("A sync is already running".to_string());
+    }
+    let _guard = RunGuard;
+    let started = Instant::now();
+    let result: Result<SyncReport, String> =
+        async { with_provider!(app, |provider| run(app, &provider).await) }.await;
+    let report = match result {
+        Ok(mut report) => {
+            report.finished_at = chrono::Utc::now().timestamp_millis();
+            report
+        }
+        Err(e) => SyncReport {
+            errors: vec![e],
+            finished_at: chrono::Utc::now().timestamp_millis(),
+            ..SyncReport::default()
+        },
+    };
+    analytics::track("sync");
+    tracing::info!(
+        pushed = report.pushed,
+        pulled = report.pulled,
+        deleted = report.deleted,
+        conflicts = report.conflicts,
+        errors = report.errors.len(),
+        elapsed_ms = started.elapsed().as_millis() as u64,
+        "Sync finished"
+    );
+    *LAST_REPORT.lock().unwrap() = Some(report.clone());
+    let _ = app.emit("sync-finished", &report);
+    Ok(report)
+}
+
+/// Sync every `interval_minutes` while enabled, checking the settings each
+/// minute so changes apply without a restart
+pub fn start_scheduler(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let mut last_run: Option<Instant> = None;
+        loop {
+            tokio::time::sleep(SCHEDULER_TICK).await;
+            let (enabled, interval_minutes) = {
+                let settings = app.state::<SettingsState>();
+                let settings = settings.0.lock().unwrap();
+                (settings.sync.enabled, settings.sync.interval_minutes)
+            };
+            let interval = Duration::from_secs(u64::from(interval_minutes.max(1)) * 60);
+            if !enabled || last_run.is_some_and(|last| last.elapsed() < interval) {
+                continue;
+            }
+            last_run = Some(Instant::now());
+            if let Err(e) = sync(&app).await {
+                tracing::debug!("Scheduled sync skipped: {}", e);
+            }
+        }
+    });
+}
+
+#[tauri::command]
+pub async fn sync_now(app: AppHandle) -> Result<SyncReport, String> {
+    sync(&app).await
+}
+
+#[tauri::command]
+pub async fn get_sync_status(db: tauri::State<'_, DatabaseWorker>) -> Result<SyncStatus, String> {
+    let recordings = db
+        .run(|db| db.list_sync_states().map_err(|e| e.to_string()))
+        .await?;
+    Ok(SyncStatus {
+        running: RUNNING.load(Ordering::SeqCst),
+        last_report: LAST_REPORT.lock().unwrap().clone(),
+        recordings,
+    })
+}
+
+/// Settle the conflict on `recording_id` by keeping `keep`'s copy and
+/// overwriting (or deleting) the other
+#[tauri::command]
+pub async fn resolve_sync_conflict(
+    app: AppHandle,
+    recording_id: String,
+    keep: SyncSide,
+) -> Result<(), String> {
+    if RUNNING.swap(true, Ordering::SeqCst) {
+        return Err("Wait for the running sync to finish".to_string());
+    }
+    let _guard = RunGuard;
+    let (state, local_exists) = {
+        let recording_id = recording_id.clone();
+        with_db(&app, move |db| {
+            let state = db
+                .list_sync_states()
+                .map_err(|e| e.to_string())?
+                .into_iter()
+                .find(|state| state.recording_id == recording_id && state.conflict.is_some())
+                .ok_or("This recording has no sync conflict")?;
+            let local_exists = db
+                .get_recording(&recording_id)
+                .map_err(|e| e.to_string())?
+                .is_some();
+            Ok((state, local_exists))
+        })
+        .await?
+    };
+
+    with_provider!(&app, |provider| {
+        let remote_etag = provider
+            .list()
+            .await?
+            .into_iter()
+            .find(|object| object.key == state.remote_key)
+            .map(|object| object.etag);
+        let action = match (keep, remote_etag) {
+            (SyncSide::Local, _) if local_exists => Action::Push {
+                recording_id: state.recording_id.clone(),
+                key: state.remote_key.clone(),
+            },
+            (SyncSide::Local, Some(_)) => Action::DeleteRemote {
+                recording_id: state.recording_id.clone(),
+                key: state.remote_key.clone(),
+            },
+            (SyncSide::Remote, Some(etag)) => Action::Pull {
+                key: state.remote_key.clone(),
+                etag,
+                replaces: Some(state.recording_id.clone()).filter(|_| local_exists),
+            },
+            (SyncSide::Remote, None) if local_exists => Action::DeleteLocal {
+                recording_id: state.recording_id.clone(),
+            },
+            _ => Action::Forget {
+                recording_id: state.recording_id.clone(),
+            },
+        };
+        tracing::info!(recording_id = %recording_id, ?keep, ?action, "Resolving sync conflict");
+        perform(&app, &provider, action, &mut SyncReport::default()).await
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn state(recording_id: &str, key: &str, etag: &str, updated_at: i64) -> SyncState {
+        SyncState {
+            recording_id: recording_id.to_string(),
+            remote_key: key.to_string(),
+            remote_etag: Some(etag.to_string()),
+            local_updated_at: updated_at,
+            synced_at: 0,
+            conflict: None,
+        }
+    }
+
+    #[test]
+    fn plans_by_which_side_changed_since_the_last_sync() {
+        let local: HashMap<String, i64> = [
+            ("unchanged", 10),
+            ("edited", 20),
+            ("stale", 10),
+            ("both", 20),
+            ("dropped-there", 10),
+            ("new-here", 5),
+        ]
+        .into_iter()
+        .map(|(id, updated_at)| (id.to_string(), updated_at))
+        .collect();
+        let remote: HashMap<String, String> = [
+            ("k-unchanged", "e1"),
+            ("k-edited", "e1"),
+            ("k-stale", "e2"),
+            ("k-both", "e2"),
+            ("k-dropped-here", "e1"),
+            ("k-new-there", "e9"),
+        ]
+        .into_iter()
+        .map(|(key, etag)| (key.to_string(), etag.to_string()))
+        .collect();
+        let states = vec![
+            state("unchanged", "k-unchanged", "e1", 10),
+            state("edited", "k-edited", "e1", 10),
+            state("stale", "k-stale", "e1", 10),
+            state("both", "k-both", "e1", 10),
+            state("dropped-there", "k-dropped-there", "e1", 10),
+            state("dropped-here", "k-dropped-here", "e1", 10),
+            state("gone", "k-gone", "e1", 10),
+        ];
+
+        assert_eq!(
+            plan(&local, &remote, &states),
+            vec![
+                Action::Push {
+                    recording_id: "edited".to_string(),
+                    key: "k-edited".to_string(),
+                },
+                Action::Pull {
+                    key: "k-stale".to_string(),
+                    etag: "e2".to_string(),
+                    replaces: Some("stale".to_string()),
+                },
+                Action::Conflict {
+                    recording_id: "both".to_string(),
+                    key: "k-both".to_string(),
+                    reason: CHANGED_ON_BOTH,
+                },
+                Action::Conflict {
+                    recording_id: "dropped-there".to_string(),
+                    key: "k-dropped-there".to_string(),
+                    reason: DELETED_REMOTELY,
+                },
+                Action::DeleteRemote {
+                    recording_id: "dropped-here".to_string(),
+                    key: "k-dropped-here".to_string(),
+                },
+                Action::Forget {
+                    recording_id: "gone".to_string(),
+                },
+                Action::Push {
+                    recording_id: "new-here".to_string(),
+                    key: "new-here".to_string(),
+                },
+                Action::Pull {
+                    key: "k-new-there".to_string(),
+                    etag: "e9".to_string(),
+                    replaces: None,
+                },
+            ]
+        );
+
+        // Flagged conflicts wait for the user
+        let mut flagged = state("both", "k-both", "e1", 10);
+        flagged.conflict = Some(CHANGED_ON_BOTH.to_string());
+        let only_both: HashMap<String, i64> = [("both".to_string(), 20)].into();
+        let remote_both: HashMap<String, String> =
+            [("k-both".to_string(), "e2".to_string())].into();
+        assert!(plan(&only_both, &remote_both, &[flagged]).is_empty());
+    }
+}
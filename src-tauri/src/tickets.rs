@@ -0,0 +1,363 @@
+//! Attaching an exported recording to a Jira issue or an Azure DevOps work
+//! item, so reproduction steps land on the ticket they belong to.
+//!
+//! The frontend renders the export (PDF or HTML) and hands over its bytes;
+//! this module uploads them through the tracker's REST API. Jira takes the
+//! file as a multipart upload on the issue. Azure DevOps takes it in two
+//! calls: the file is uploaded to the project, then linked to the work item
+//! as an `AttachedFile` relation. Tokens are kept in the keychain as
+//! `jira_api_token` and `azure_devops_token`.
+
+use crate::settings::SettingsState;
+use crate::{analytics, credentials, network};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
+
+pub const JIRA_TOKEN_CREDENTIAL: &str = "jira_api_token";
+pub const AZURE_DEVOPS_TOKEN_CREDENTIAL: &str = "azure_devops_token";
+const AZURE_DEVOPS_API_VERSION: &str = "7.1";
+const UPLOAD_TIMEOUT: Duration = Duration::from_secs(120);
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct JiraSettings {
+    /// Site URL, e.g. `https://example.atlassian.net`; empty until set up
+    pub base_url: String,
+    /// Account email for Jira Cloud API tokens; empty to send the token as a
+    /// Data Center personal access token
+    pub email: String,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AzureDevOpsSettings {
+    /// e.g. `https://dev.azure.com/example`; empty until set up
+    pub organization_url: String,
+    pub project: String,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TicketSettings {
+    pub jira: JiraSettings,
+    pub azure_devops: AzureDevOpsSettings,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Tracker {
+    Jira,
+    AzureDevops,
+}
+
+impl Tracker {
+    fn label(self) -> &'static str {
+        match self {
+            Tracker::Jira => "Jira",
+            Tracker::AzureDevops => "Azure DevOps",
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct TicketAttachment {
+    /// Ticket the file was attached to, as entered or parsed from a link
+    ticket: String,
+    /// Page of the ticket in the tracker
+    url: String,
+}
+
+/// The ticket in `input`, which may be a key (`ABC-12`, `42`) or a link to
+/// the ticket copied from the browser
+fn parse_ticket(tracker: Tracker, input: &str) -> Result<String, String> {
+    let input = input.trim();
+    let candidate = input
+        .split(['?', '#'])
+        .next()
+        .unwrap_or_default()
+        .trim_end_matches('/')
+        .rsplit('/')
+        .next()
+        .unwrap_or_default();
+    let valid = match tracker {
+        Tracker::Jira => candidate.split_once('-').is_some_and(|(project, number)| {
+            project.starts_with(|c: char| c.is_ascii_alphabetic())
+                && project
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || c == '_')
+                && !number.is_empty()
+                && number.chars().all(|c| c.is_ascii_digit())
+        }),
+        Tracker::AzureDevops => {
+            !candidate.is_empty() && candidate.chars().all(|c| c.is_ascii_digit())
+        }
+    };
+    if !valid {
+        let example = match tracker {
+            Tracker::Jira => "an issue key like ABC-123",
+            Tracker::AzureDevops => "a work item number like 42",
+        };
+        return Err(format!("Enter {} or a link to it", example));
+    }
+    Ok(candidate.to_ascii_uppercase())
+}
+
+fn base_url(url: &str, setting: &str) -> Result<reqwest::Url, String> {
+    let url = url.trim().trim_end_matches('/');
+    if url.is_empty() {
+        return Err(format!("Set the {} in Settings", setting));
+    }
+    reqwest::Url::parse(&format!("{}/", url)).map_err(|e| format!("Invalid {}: {}", setting, e))
+}
+
+fn token(tracker: Tracker, name: &str) -> Result<String, String> {
+    credentials::get(name)?
+        .filter(|token| !token.trim().is_empty())
+        .ok_or_else(|| format!("Add a {} token in Settings", tracker.label()))
+}
+
+/// A readable reason for a failed request
+fn failure(tracker: Tracker, ticket: &str, status: reqwest::StatusCode, body: &str) -> String {
+    match status.as_u16() {
+        401 => format!(
+            "{} rejected the token. Check it in Settings.",
+            tracker.label()
+        ),
+        403 => format!(
+            "The {} token isn't allowed to add attachments to {}",
+            tracker.label(),
+            ticket
+        ),
+        404 => format!("{} not found in {}", ticket, tracker.label()),
+        413 => format!("The file is larger than {} accepts", tracker.label()),
+        _ => {
+            // Both APIs describe the problem in a JSON error body
+            let message = serde_json::from_str::<Value>(body)
+                .ok()
+                .and_then(|body| {
+                    body["errorMessages"][0]
+                        .as_str()
+                        .or_else(|| body["message"].as_str())
+                        .map(str::to_string)
+                })
+                .unwrap_or_else(|| body.chars().take(300).collect());
+            format!("{} request failed: {} {}", tracker.label(), status, message)
+                .trim()
+                .to_string()
+        }
+    }
+}
+
+async fn check(
+    tracker: Tracker,
+    ticket: &str,
+    response: Result<reqwest::Response, reqwest::Error>,
+) -> Result<reqwest::Response, String> {
+    let response = response.map_err(|e| {
+        if e.is_timeout() {
+            format!("{} didn't respond in time", tracker.label())
+        } else {
+            format!("Couldn't reach {}: {}", tracker.label(), e)
+        }
+    })?;
+    let status = response.status();
+    if status.is_success() {
+        return Ok(response);
+    }
+    let body = response.text().await.unwrap_or_default();
+    tracing::warn!(tracker = tracker.label(), ticket, status = status.as_u16(), body = %body, "Ticket attachment failed");
+    Err(failure(tracker, ticket, status, &body))
+}
+
+async fn attach_jira(
+    client: &reqwest::Client,
+    config: &JiraSettings,
+    issue: &str,
+    file_name: String,
+    data: Vec<u8>,
+) -> Result<String, String> {
+    let base = base_url(&config.base_url, "Jira site URL")?;
+    let token = token(Tracker::Jira, JIRA_TOKEN_CREDENTIAL)?;
+    let endpoint = base
+        // Version 2, which Data Center has as well as Cloud
+        .join(&format!("rest/api/2/issue/{}/attachments", issue))
+        .map_err(|e| e.to_string())?;
+    let form = reqwest::multipart::Form::new().part(
+        "file",
+        reqwest::multipart::Part::bytes(data).file_name(file_name),
+    );
+    let request = client
+        .post(endpoint)
+        // Required by Jira for uploads, as CSRF protection
+        .header("X-Atlassian-Token", "no-check")
+        .multipart(form);
+    let request = if config.email.trim().is_empty() {
+        request.bearer_auth(token)
+    } else {
+        request.basic_auth(config.email.trim(), Some(token))
+    };
+    check(Tracker::Jira, issue, request.send().await).await?;
+    Ok(base
+        .join(&format!("browse/{}", issue))
+        .map_err(|e| e.to_string())?
+        .to_string())
+}
+
+async fn attach_azure_devops(
+    client: &reqwest::Client,
+    config: &AzureDevOpsSettings,
+    work_item: &str,
+    file_name: String,
+    data: Vec<u8>,
+) -> Result<String, String> {
+    let base = base_url(&config.organization_url, "Azure DevOps organization URL")?;
+    let project = config.project.trim();
+    if project.is_empty() {
+        return Err("Set the Azure DevOps project in Settings".to_string());
+    }
+    let token = token(Tracker::AzureDevops, AZURE_DEVOPS_TOKEN_CREDENTIAL)?;
+    let project_url = |path: &str| {
+        let mut url = base.clone();
+        url.path_segments_mut()
+            .map_err(|_| "Invalid Azure DevOps organization URL".to_string())?
+            .pop_if_empty()
+            .push(project)
+            .extend(path.split('/'));
+        Ok::<_, String>(url)
+    };
+
+    let mut upload = project_url("_apis/wit/attachments")?;
+    upload
+        .query_pairs_mut()
+        .append_pair("fileName", &file_name)
+        .append_pair("api-version", AZURE_DEVOPS_API_VERSION);
+    let uploaded: Value = check(
+        Tracker::AzureDevops,
+        work_item,
+        client
+            .post(upload)
+            .basic_auth("", Some(&token))
+            .header(reqwest::header::CONTENT_TYPE, "application/octet-stream")
+            .body(data)
+            .send()
+            .await,
+    )
+    .await?
+    .json()
+    .await
+    .map_err(|e| format!("Unexpected Azure DevOps response: {}", e))?;
+    let attachment_url = uploaded["url"]
+        .as_str()
+        .ok_or("Azure DevOps didn't return the uploaded file's URL")?;
+
+    let mut update = project_url(&format!("_apis/wit/workitems/{}", work_item))?;
+    update
+        .query_pairs_mut()
+        .append_pair("api-version", AZURE_DEVOPS_API_VERSION);
+    let patch = json!([{
+        "op": "add",
+        "path": "/relations/-",
+        "value": {
+            "rel": "AttachedFile",
+            "url": attachment_url,
+            "attributes": { "comment": "Steps recorded with StepSnap" },
+        },
+    }]);
+    check(
+        Tracker::AzureDevops,
+        work_item,
+        client
+            .patch(update)
+            .basic_auth("", Some(&token))
+            .header(reqwest::header::CONTENT_TYPE, "application/json-patch+json")
+            .body(patch.to_string())
+            .send()
+            .await,
+    )
+    .await?;
+    Ok(project_url(&format!("_workitems/edit/{}", work_item))?.to_string())
+}
+
+/// Attach `data`, an export named `file_name`, to `ticket` in `tracker`
+#[tauri::command]
+pub async fn attach_to_ticket(
+    app: AppHandle,
+    tracker: Tracker,
+    ticket: String,
+    file_name: String,
+    data: Vec<u8>,
+) -> Result<TicketAttachment, String> {
+    let ticket = parse_ticket(tracker, &ticket)?;
+    // Only the name, in case a path was passed
+    let file_name = file_name
+        .rsplit(['/', '\\'])
+        .next()
+        .filter(|name| !name.trim().is_empty())
+        .ok_or("The export needs a file name")?
+        .to_string();
+    let config = app
+        .state::<SettingsState>()
+        .0
+        .lock()
+        .unwrap()
+        .tickets
+        .clone();
+    let client = network::client_builder()?
+        .timeout(UPLOAD_TIMEOUT)
+        .build()
+        .map_err(|e| format!("Failed to create HTTP client: {}", e))?;
+
+    let size = data.len();
+    let url = match tracker {
+        Tracker::Jira => attach_jira(&client, &config.jira, &ticket, file_name, data).await?,
+        Tracker::AzureDevops => {
+            attach_azure_devops(&client, &config.azure_devops, &ticket, file_name, data).await?
+        }
+    };
+    analytics::track("ticket_attachment");
+    tracing::info!(tracker = tracker.label(), ticket = %ticket, bytes = size, "Attached export to ticket");
+    Ok(TicketAttachment { ticket, url })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_ticket_keys_and_links() {
+        assert_eq!(parse_ticket(Tracker::Jira, " abc-12 ").unwrap(), "ABC-12");
+        assert_eq!(
+            parse_ticket(
+                Tracker::Jira,
+                "https://example.atlassian.net/browse/OPS_2-7?focusedCommentId=1"
+            )
+            .unwrap(),
+            "OPS_2-7"
+        );
+        assert!(parse_ticket(Tracker::Jira, "12-ABC").is_err());
+        assert!(parse_ticket(Tracker::Jira, "ABC-").is_err());
+
+        assert_eq!(parse_ticket(Tracker::AzureDevops, "42").unwrap(), "42");
+        assert_eq!(
+            parse_ticket(
+                Tracker::AzureDevops,
+                "https://dev.azure.com/org/Project/_workitems/edit/42/"
+            )
+            .unwrap(),
+            "42"
+        );
+        assert!(parse_ticket(Tracker::AzureDevops, "#42x").is_err());
+
+        assert_eq!(
+            failure(
+                Tracker::Jira,
+                "ABC-12",
+                reqwest::StatusCode::BAD_REQUEST,
+                r#"{"errorMessages":["Attachments are disabled."]}"#
+            ),
+            "Jira request failed: 400 Bad Request Attachments are disabled."
+        );
+    }
+}
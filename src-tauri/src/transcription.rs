@@ -0,0 +1,440 @@
+//! Transcribing a recording's narration into draft step descriptions.
+//!
+//! The narration is a WAV file recorded alongside the steps. It's copied
+//! into `<data_dir>/narration`, decoded to 16 kHz mono and run through
+//! whisper.cpp with a ggml model downloaded on demand into
+//! `<app_data_dir>/whisper_models`. The transcript is kept in the
+//! `narrations` table as timed segments together with the moment the audio
+//! starts on the steps' clock, and aligned to the steps whenever drafts are
+//! asked for, so reordered or deleted steps are taken into account.
+//!
+//! A segment belongs to the first step that happens after it (people say
+//! what they're about to do, then do it), with a little slack for finishing
+//! the sentence while clicking. Drafts are only offered to the editor; the
+//! user applies them as descriptions.
+
+use crate::database::{Narration, NarrationSegment, Step};
+use crate::db_worker::DatabaseWorker;
+use crate::{analytics, network};
+use serde::Serialize;
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, Emitter, Manager};
+use whisper_rs::{FullParams, SamplingStrategy, WhisperContext, WhisperContextParameters};
+
+/// Sample rate whisper models expect
+const WHISPER_SAMPLE_RATE: u32 = 16_000;
+/// Speech this long after a step still describes it
+const TRAILING_SPEECH_MS: i64 = 1500;
+/// Bytes downloaded between `whisper-model-progress` events
+const PROGRESS_INTERVAL_BYTES: u64 = 512 * 1024;
+const MODEL_BASE_URL: &str = "https://huggingface.co/ggerganov/whisper.cpp/resolve/main";
+/// Models offered for transcription: id and approximate download size in MB
+const WHISPER_MODELS: &[(&str, u64)] = &[("tiny", 75), ("base", 142), ("small", 466)];
+
+/// A whisper model as listed in the transcription dialog
+#[derive(Serialize)]
+pub struct WhisperModelStatus {
+    pub id: String,
+    pub size_mb: u64,
+    pub installed: bool,
+}
+
+/// Download progress, emitted as `whisper-model-progress`
+#[derive(Clone, Serialize)]
+pub struct WhisperModelProgress {
+    pub model: String,
+    pub downloaded: u64,
+    pub total: Option<u64>,
+}
+
+/// Narration spoken around a step, offered as its description
+#[derive(Debug, PartialEq, Serialize)]
+pub struct NarrationDraft {
+    pub step_id: String,
+    pub text: String,
+}
+
+fn models_dir(app: &AppHandle) -> Result<PathBuf, String> {
+    let app_data_dir = app.path().app_data_dir().map_err(|e| e.to_string())?;
+    Ok(app_data_dir.join("whisper_models"))
+}
+
+fn model_file(model: &str) -> Result<String, String> {
+    WHISPER_MODELS
+        .iter()
+        .find(|(id, _)| *id == model)
+        .map(|(id, _)| format!("ggml-{}.bin", id))
+        .ok_or_else(|| format!("Unknown transcription model: {}", model))
+}
+
+/// Mono samples at `WHISPER_SAMPLE_RATE`, from a PCM or float WAV file
+fn load_wav(path: &Path) -> Result<Vec<f32>, String> {
+    let mut reader = hound::WavReader::open(path)
+        .map_err(|e| format!("Couldn't read the narration as WAV audio: {}", e))?;
+    let spec = reader.spec();
+    let samples: Vec<f32> = match spec.sample_format {
+        hound::SampleFormat::Float => reader
+            .samples::<f32>()
+            .collect::<Result<_, _>>()
+            .map_err(|e| e.to_string())?,
+        hound::SampleFormat::Int => {
+            let scale = 1.0 / (1i64 << (spec.bits_per_sample - 1)) as f32;
+            reader
+                .samples::<i32>()
+                .map(|sample| sample.map(|sample| sample as f32 * scale))
+                .collect::<Result<_, _>>()
+                .map_err(|e| e.to_string())?
+        }
+    };
+    let channels = usize::from(spec.channels.max(1));
+    let mono: Vec<f32> = samples
+        .chunks(channels)
+        .map(|frame| frame.iter().sum::<f32>() / frame.len() as f32)
+        .collect();
+    Ok(resample(&mono, spec.sample_rate, WHISPER_SAMPLE_RATE))
+}
+
+/// Linear resampling; plenty for speech recognition
+fn resample(samples: &[f32], from: u32, to: u32) -> Vec<f32> {
+    if from == to || samples.is_empty() {
+        return samples.to_vec();
+    }
+    let step = f64::from(from) / f64::from(to);
+    let len = (samples.len() as f64 / step) as usize;
+    (0..len)
+        .map(|i| {
+            let position = i as f64 * step;
+            let index = position as usize;
+            let next = samples.get(index + 1).unwrap_or(&samples[index]);
+            let fraction = (position - index as f64) as f32;
+            samples[index] + (next - samples[index]) * fraction
+        })
+        .collect()
+}
+
+/// Whisper's markers for stretches without speech, e.g. `[BLANK_AUDIO]` or `(music)`
+fn is_non_speech(text: &str) -> bool {
+    text.is_empty()
+        || (text.starts_with('[') && text.ends_with(']'))
+        || (text.starts_with('(') && text.ends_with(')'))
+}
+
+fn transcribe(
+    model_path: &Path,
+    samples: &[f32],
+    language: &str,
+) -> Result<Vec<NarrationSegment>, String> {
+    let model_path = model_path.to_string_lossy();
+    let context = WhisperContext::new_with_params(&model_path, WhisperContextParameters::default())
+        .map_err(|e| format!("Failed to load the transcription model: {}", e))?;
+    let mut state = context.create_state().map_err(|e| e.to_string())?;
+
+    let mut params = FullParams::new(SamplingStrategy::Greedy { best_of: 1 });
+    let threads = std::thread::available_parallelism().map_or(4, |n| n.get().min(8));
+    params.set_n_threads(threads as i32);
+    params.set_language(Some(language));
+    params.set_print_special(false);
+    params.set_print_progress(false);
+    params.set_print_realtime(false);
+    params.set_print_timestamps(false);
+    state
+        .full(params, samples)
+        .map_err(|e| format!("Transcription failed: {}", e))?;
+
+    let count = state.full_n_segments().map_err(|e| e.to_string())?;
+    let mut segments = Vec::new();
+    for i in 0..count {
+        let text = state
+            .full_get_segment_text(i)
+            .map_err(|e| e.to_string())?
+            .trim()
+            .to_string();
+        if is_non_speech(&text) {
+            continue;
+        }
+        // Segment times are in 10 ms units
+        let start_ms = state.full_get_segment_t0(i).map_err(|e| e.to_string())? * 10;
+        let end_ms = state.full_get_segment_t1(i).map_err(|e| e.to_string())? * 10;
+        segments.push(NarrationSegment {
+            start_ms,
+            end_ms,
+            text,
+        });
+    }
+    Ok(segments)
+}
+
+/// Group the transcript by the step each segment leads up to. Steps without
+/// narration get no draft.
+fn align_to_steps(narration: &Narration, steps: &[Step]) -> Vec<NarrationDraft> {
+    let mut by_time: Vec<&Step> = steps.iter().collect();
+    by_time.sort_by_key(|step| step.timestamp);
+    if by_time.is_empty() {
+        return Vec::new();
+    }
+
+    let mut texts: Vec<Vec<&str>> = vec![Vec::new(); by_time.len()];
+    for segment in &narration.segments {
+        let middle = narration.started_at + (segment.start_ms + segment.end_ms) / 2;
+        let index = by_time
+            .iter()
+            .position(|step| middle <= step.timestamp + TRAILING_SPEECH_MS)
+            .unwrap_or(by_time.len() - 1);
+        texts[index].push(&segment.text);
+    }
+
+    let mut drafts: Vec<NarrationDraft> = by_time
+        .iter()
+        .zip(texts)
+        .filter(|(_, texts)| !texts.is_empty())
+        .map(|(step, texts)| NarrationDraft {
+            step_id: step.id.clone(),
+            text: texts.join(" "),
+        })
+        .collect();
+    // Back in the editor's order
+    drafts.sort_by_key(|draft| {
+        steps
+            .iter()
+            .position(|step| step.id == draft.step_id)
+            .unwrap_or(usize::MAX)
+    });
+    drafts
+}
+
+/// Whisper models and whether they're downloaded
+#[tauri::command]
+pub fn list_whisper_models(app: AppHandle) -> Result<Vec<WhisperModelStatus>, String> {
+    let dir = models_dir(&app)?;
+    WHISPER_MODELS
+        .iter()
+        .map(|(id, size_mb)| {
+            Ok(WhisperModelStatus {
+                id: id.to_string(),
+                size_mb: *size_mb,
+                installed: dir.join(model_file(id)?).exists(),
+            })
+        })
+        .collect()
+}
+
+/// Download a whisper model to `<name>.part`, then move it into place
+#[tauri::command]
+pub async fn download_whisper_model(app: AppHandle, model: String) -> Result<(), String> {
+    let name = model_file(&model)?;
+    let dir = models_dir(&app)?;
+    fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+
+    let client = network::client_builder()?
+        .build()
+        .map_err(|e| format!("Failed to create download client: {}", e))?;
+    let mut response = client
+        .get(format!("{}/{}", MODEL_BASE_URL, name))
+        .send()
+        .await
+        .and_then(|response| response.error_for_status())
+        .map_err(|e| format!("Failed to download {}: {}", name, e))?;
+
+    let total = response.content_length();
+    let partial = dir.join(format!("{}.part", name));
+    let mut out = fs::File::create(&partial).map_err(|e| e.to_string())?;
+    let mut downloaded = 0u64;
+    let mut last_reported = 0u64;
+    let emit_progress = |downloaded: u64| {
+        let _ = app.emit(
+            "whisper-model-progress",
+            WhisperModelProgress {
+                model: model.clone(),
+                downloaded,
+                total,
+            },
+        );
+    };
+    emit_progress(0);
+
+    while let Some(chunk) = response
+        .chunk()
+        .await
+        .map_err(|e| format!("Failed to download {}: {}", name, e))?
+    {
+        out.write_all(&chunk).map_err(|e| e.to_string())?;
+        downloaded += chunk.len() as u64;
+        if downloaded - last_reported >= PROGRESS_INTERVAL_BYTES {
+            emit_progress(downloaded);
+            last_reported = downloaded;
+        }
+    }
+    out.flush().map_err(|e| e.to_string())?;
+    drop(out);
+    emit_progress(downloaded);
+
+    fs::rename(&partial, dir.join(&name)).map_err(|e| e.to_string())?;
+    tracing::info!(model = %model, bytes = downloaded, "Downloaded transcription model");
+    Ok(())
+}
+
+/// Transcribe `audio_path` as the narration of `recording_id` and return the
+/// drafts. `first_step_at_ms` is how far into the audio the first step
+/// happens; `language` is an ISO 639-1 code or "auto".
+#[tauri::command]
+pub async fn transcribe_narration(
+    app: AppHandle,
+    recording_id: String,
+    audio_path: String,
+    model: String,
+    language: Option<String>,
+    first_step_at_ms: i64,
+) -> Result<Vec<NarrationDraft>, String> {
+    let model_path = models_dir(&app)?.join(model_file(&model)?);
+    if !model_path.exists() {
+        return Err(format!("Download the {} transcription model first", model));
+    }
+    let id = recording_id.clone();
+    let (steps, narration_dir) = app
+        .state::<DatabaseWorker>()
+        .run(move |db| {
+            let recording = db
+                .get_recording(&id)
+                .map_err(|e| e.to_string())?
+                .ok_or_else(|| format!("Recording not found: {}", id))?;
+            Ok((recording.steps, db.data_dir().join("narration")))
+        })
+        .await?;
+    let first_step = steps
+        .iter()
+        .map(|step| step.timestamp)
+        .min()
+        .ok_or("The recording has no steps to align the narration to")?;
+
+    // Keep a copy so the transcript outlives the original file
+    fs::create_dir_all(&narration_dir).map_err(|e| e.to_string())?;
+    let copy = narration_dir.join(format!("{}.wav", recording_id));
+    if Path::new(&audio_path) != copy {
+        fs::copy(&audio_path, &copy).map_err(|e| format!("Couldn't copy the narration: {}", e))?;
+    }
+
+    let language = language
+        .map(|language| language.trim().to_lowercase())
+        .filter(|language| !language.is_empty())
+        .unwrap_or_else(|| "auto".to_string());
+    let (audio, whisper_language) = (copy.clone(), language.clone());
+    let segments = tauri::async_runtime::spawn_blocking(move || {
+        let samples = load_wav(&audio)?;
+        transcribe(&model_path, &samples, &whisper_language)
+    })
+    .await
+    .map_err(|e| e.to_string())??;
+
+    let narration = Narration {
+        recording_id: recording_id.clone(),
+        audio_path: copy.to_string_lossy().to_string(),
+        started_at: first_step - first_step_at_ms,
+        language: Some(language),
+        segments,
+        transcribed_at: chrono::Utc::now().timestamp_millis(),
+    };
+    let saved = narration.clone();
+    app.state::<DatabaseWorker>()
+        .run(move |db| db.save_narration(&saved).map_err(|e| e.to_string()))
+        .await?;
+
+    analytics::track("narration_transcribed");
+    tracing::info!(
+        recording_id = %recording_id,
+        segments = narration.segments.len(),
+        "Transcribed narration"
+    );
+    Ok(align_to_steps(&narration, &steps))
+}
+
+/// Drafts from the stored transcript of `recording_id`, aligned to its
+/// current steps; empty when it hasn't been transcribed
+#[tauri::command]
+pub async fn get_narration_drafts(
+    db: tauri::State<'_, DatabaseWorker>,
+    recording_id: String,
+) -> Result<Vec<NarrationDraft>, String> {
+    db.run(move |db| {
+        let Some(narration) = db.get_narration(&recording_id).map_err(|e| e.to_string())? else {
+            return Ok(Vec::new());
+        };
+        let recording = db
+            .get_recording(&recording_id)
+            .map_err(|e| e.to_string())?
+            .ok_or_else(|| format!("Recording not found: {}", recording_id))?;
+        Ok(align_to_steps(&narration, &recording.steps))
+    })
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn step(id: &str, timestamp: i64) -> Step {
+        Step {
+            id: id.to_string(),
+            timestamp,
+            ..Default::default()
+        }
+    }
+
+    fn segment(start_ms: i64, end_ms: i64, text: &str) -> NarrationSegment {
+        NarrationSegment {
+            start_ms,
+            end_ms,
+            text: text.to_string(),
+        }
+    }
+
+    #[test]
+    fn narration_goes_to_the_step_it_leads_up_to() {
+        let narration = Narration {
+            recording_id: "r".to_string(),
+            audio_path: String::new(),
+            started_at: 10_000,
+            language: None,
+            segments: vec![
+                segment(0, 2_000, "Open the settings."),
+                segment(4_000, 6_000, "Now switch to the Billing tab"),
+                segment(6_200, 7_400, "and save."),
+                segment(20_000, 22_000, "That's it."),
+            ],
+            transcribed_at: 0,
+        };
+        // Listed in editor order, which differs from capture order
+        let steps = vec![
+            step("save", 18_000),
+            step("open", 12_000),
+            step("quiet", 13_000),
+        ];
+
+        assert_eq!(
+            align_to_steps(&narration, &steps),
+            vec![
+                NarrationDraft {
+                    step_id: "save".to_string(),
+                    text: "Now switch to the Billing tab and save. That's it.".to_string(),
+                },
+                NarrationDraft {
+                    step_id: "open".to_string(),
+                    text: "Open the settings.".to_string(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn resamples_and_skips_non_speech() {
+        let samples = [0.0, 1.0, 0.0, -1.0];
+        assert_eq!(resample(&samples, 32_000, 16_000), vec![0.0, 0.0]);
+        assert_eq!(resample(&samples, 8_000, 16_000).len(), 8);
+        assert_eq!(resample(&samples, 8_000, 16_000)[1], 0.5);
+
+        assert!(is_non_speech("[BLANK_AUDIO]"));
+        assert!(is_non_speech("(keyboard clicking)"));
+        assert!(!is_non_speech("Click Save."));
+    }
+}
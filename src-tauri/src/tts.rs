@@ -0,0 +1,191 @@
+//! Speaking text into WAV files with the voices the platform ships.
+//!
+//! Windows goes through System.Speech from PowerShell, macOS through `say`
+//! and Linux through espeak-ng (or the older espeak). Text and paths are
+//! handed over in a file and environment variables rather than on the
+//! command line, so descriptions need no quoting.
+
+use std::path::Path;
+use std::process::{Command, Output};
+
+#[cfg(target_os = "windows")]
+const SPEAK_SCRIPT: &str = "Add-Type -AssemblyName System.Speech; \
+    $s = New-Object System.Speech.Synthesis.SpeechSynthesizer; \
+    if ($env:STEPSNAP_TTS_VOICE) { $s.SelectVoice($env:STEPSNAP_TTS_VOICE) }; \
+    $s.SetOutputToWaveFile($env:STEPSNAP_TTS_OUT); \
+    $s.Speak([IO.File]::ReadAllText($env:STEPSNAP_TTS_TEXT)); \
+    $s.Dispose()";
+#[cfg(target_os = "windows")]
+const VOICES_SCRIPT: &str = "Add-Type -AssemblyName System.Speech; \
+    (New-Object System.Speech.Synthesis.SpeechSynthesizer).GetInstalledVoices() | \
+    ForEach-Object { $_.VoiceInfo.Name }";
+
+fn run(command: &mut Command, program: &str) -> Result<Output, String> {
+    #[cfg(target_os = "windows")]
+    {
+        use std::os::windows::process::CommandExt;
+        const CREATE_NO_WINDOW: u32 = 0x0800_0000;
+        command.creation_flags(CREATE_NO_WINDOW);
+    }
+    let output = command.output().map_err(|e| match e.kind() {
+        std::io::ErrorKind::NotFound => format!("{} isn't installed or isn't on the PATH", program),
+        _ => format!("Failed to run {}: {}", program, e),
+    })?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(format!("{} failed: {}", program, stderr.trim()));
+    }
+    Ok(output)
+}
+
+/// espeak-ng when it's installed, else espeak
+#[cfg(target_os = "linux")]
+fn espeak() -> &'static str {
+    let installed = Command::new("espeak-ng")
+        .arg("--version")
+        .output()
+        .is_ok_and(|output| output.status.success());
+    if installed {
+        "espeak-ng"
+    } else {
+        "espeak"
+    }
+}
+
+/// Names from `say -v ?`, whose lines read `Name  en_US  # Sample sentence`;
+/// names may contain spaces
+#[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+fn parse_say_voices(listing: &str) -> Vec<String> {
+    listing
+        .lines()
+        .filter_map(|line| {
+            let (voice, _) = line.split_once('#')?;
+            let mut words: Vec<&str> = voice.split_whitespace().collect();
+            words.pop()?;
+            (!words.is_empty()).then(|| words.join(" "))
+        })
+        .collect()
+}
+
+/// Languages from `espeak-ng --voices`, a table headed
+/// `Pty Language Age/Gender VoiceName File Other Languages`
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn parse_espeak_voices(listing: &str) -> Vec<String> {
+    let mut voices: Vec<String> = listing
+        .lines()
+        .skip(1)
+        .filter_map(|line| line.split_whitespace().nth(1).map(str::to_string))
+        .collect();
+    voices.dedup();
+    voices
+}
+
+/// Voices `synthesize` accepts, for the export dialog to pick from
+pub fn voices() -> Result<Vec<String>, String> {
+    #[cfg(target_os = "windows")]
+    {
+        let output = run(
+            Command::new("powershell").args([
+                "-NoProfile",
+                "-NonInteractive",
+                "-Command",
+                VOICES_SCRIPT,
+            ]),
+            "PowerShell",
+        )?;
+        Ok(String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+            .map(str::to_string)
+            .collect())
+    }
+    #[cfg(target_os = "macos")]
+    {
+        let output = run(Command::new("say").args(["-v", "?"]), "say")?;
+        Ok(parse_say_voices(&String::from_utf8_lossy(&output.stdout)))
+    }
+    #[cfg(target_os = "linux")]
+    {
+        let program = espeak();
+        let output = run(Command::new(program).arg("--voices"), program)?;
+        Ok(parse_espeak_voices(&String::from_utf8_lossy(
+            &output.stdout,
+        )))
+    }
+}
+
+/// Speak `text` into the WAV file `out` with `voice`, or the default voice
+pub fn synthesize(text: &str, voice: Option<&str>, out: &Path) -> Result<(), String> {
+    let text_file = out.with_extension("txt");
+    std::fs::write(&text_file, text).map_err(|e| e.to_string())?;
+    let voice = voice.map(str::trim).filter(|voice| !voice.is_empty());
+
+    #[cfg(target_os = "windows")]
+    let result = run(
+        Command::new("powershell")
+            .args(["-NoProfile", "-NonInteractive", "-Command", SPEAK_SCRIPT])
+            .env("STEPSNAP_TTS_VOICE", voice.unwrap_or_default())
+            .env("STEPSNAP_TTS_OUT", out)
+            .env("STEPSNAP_TTS_TEXT", &text_file),
+        "PowerShell",
+    );
+    #[cfg(target_os = "macos")]
+    let result = {
+        let mut command = Command::new("say");
+        command
+            .args(["--file-format=WAVE", "--data-format=LEI16@22050", "-o"])
+            .arg(out)
+            .arg("-f")
+            .arg(&text_file);
+        if let Some(voice) = voice {
+            command.args(["-v", voice]);
+        }
+        run(&mut command, "say")
+    };
+    #[cfg(target_os = "linux")]
+    let result = {
+        let program = espeak();
+        let mut command = Command::new(program);
+        command.arg("-w").arg(out).arg("-f").arg(&text_file);
+        if let Some(voice) = voice {
+            command.args(["-v", voice]);
+        }
+        run(&mut command, program)
+    };
+
+    let _ = std::fs::remove_file(&text_file);
+    result.map(|_| ())
+}
+
+/// Length of a WAV file in seconds
+pub fn wav_seconds(path: &Path) -> Result<f64, String> {
+    let reader = hound::WavReader::open(path)
+        .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    Ok(f64::from(reader.duration()) / f64::from(reader.spec().sample_rate))
+}
+
+/// Voices the platform can narrate with
+#[tauri::command]
+pub async fn list_tts_voices() -> Result<Vec<String>, String> {
+    tauri::async_runtime::spawn_blocking(voices)
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_voice_listings() {
+        let say = "Alex                en_US    # Most people recognize me by my voice.\n\
+                   Bad News            en_US    # The light you see at the end of the tunnel.\n";
+        assert_eq!(parse_say_voices(say), vec!["Alex", "Bad News"]);
+
+        let espeak = "Pty Language       Age/Gender VoiceName          File                 Other Languages\n \
+                      5  de              --/M      German             gmw/de\n \
+                      2  en-us           --/M      English_(America)  gmw/en-US            (en 3)\n";
+        assert_eq!(parse_espeak_voices(espeak), vec!["de", "en-us"]);
+    }
+}
@@ -0,0 +1,270 @@
+//! Watermarks stamped onto exported screenshots.
+//!
+//! Some teams must mark internal documentation, e.g. "CONFIDENTIAL". A
+//! `WatermarkStyle` is either a line of text or an image such as a logo,
+//! placed in a corner, the centre or tiled across the screenshot at a given
+//! opacity. Like the other export styles it's only applied to the copies
+//! rendered for export; captures on disk never carry it.
+
+use crate::annotations::{label_font, parse_color};
+use ab_glyph::PxScale;
+use image::{imageops, Rgba, RgbaImage};
+use imageproc::drawing::{draw_text_mut, text_size};
+use serde::{Deserialize, Serialize};
+
+/// Watermarks never get wider than this fraction of the screenshot
+const MAX_WIDTH_FRACTION: f32 = 0.8;
+
+/// Gap between tiled watermarks, as a fraction of the watermark's size
+const TILE_GAP: f32 = 1.0;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WatermarkKind {
+    #[default]
+    Text,
+    /// The image at `WatermarkStyle::image_path`
+    Image,
+}
+
+/// Where a watermark goes on the screenshot
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WatermarkPosition {
+    #[default]
+    Center,
+    TopLeft,
+    TopRight,
+    BottomLeft,
+    BottomRight,
+    /// Repeated across the whole screenshot
+    Tiled,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WatermarkStyle {
+    pub enabled: bool,
+    pub kind: WatermarkKind,
+    pub text: String,
+    /// PNG or JPEG stamped for `WatermarkKind::Image`
+    pub image_path: Option<String>,
+    /// `#RRGGBB` text colour
+    pub color: String,
+    pub position: WatermarkPosition,
+    /// 0 (invisible) to 1 (opaque)
+    pub opacity: f32,
+    /// Text height, or image width, in logical pixels
+    pub size: f32,
+}
+
+impl Default for WatermarkStyle {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            kind: WatermarkKind::Text,
+            text: "CONFIDENTIAL".to_string(),
+            image_path: None,
+            color: "#FF3B30".to_string(),
+            position: WatermarkPosition::Center,
+            opacity: 0.3,
+            size: 64.0,
+        }
+    }
+}
+
+/// A watermark ready to stamp, with its image loaded once for a whole export
+pub struct Watermark {
+    style: WatermarkStyle,
+    image: Option<RgbaImage>,
+}
+
+impl Watermark {
+    /// None when `style` is disabled or has nothing to draw: no text, or an
+    /// image that can't be read.
+    pub fn load(style: &WatermarkStyle) -> Option<Self> {
+        if !style.enabled || style.opacity <= 0.0 {
+            return None;
+        }
+        let image = match style.kind {
+            WatermarkKind::Text if style.text.trim().is_empty() => return None,
+            WatermarkKind::Text => None,
+            WatermarkKind::Image => {
+                let path = style.image_path.as_deref()?;
+                match image::open(path) {
+                    Ok(image) => Some(image.to_rgba8()),
+                    Err(e) => {
+                        tracing::warn!("Failed to read watermark image {}: {}", path, e);
+                        return None;
+                    }
+                }
+            }
+        };
+        Some(Self {
+            style: style.clone(),
+            image,
+        })
+    }
+
+    /// Stamp the watermark onto `image`, a screenshot captured at
+    /// `scale_factor`
+    pub fn draw(&self, image: &mut RgbaImage, scale_factor: f32) {
+        let Some(mark) = self.render(image.width(), scale_factor) else {
+            return;
+        };
+        let layer = faded(mark, self.style.opacity);
+        for (x, y) in placements(
+            image.width(),
+            image.height(),
+            layer.dimensions(),
+            self.style.position,
+        ) {
+            imageops::overlay(image, &layer, x, y);
+        }
+    }
+
+    /// The watermark at full opacity, sized for a screenshot `width` pixels
+    /// wide
+    fn render(&self, width: u32, scale_factor: f32) -> Option<RgbaImage> {
+        let max_width = (width as f32 * MAX_WIDTH_FRACTION).max(1.0);
+        let size = (self.style.size * scale_factor).max(1.0);
+        match &self.image {
+            Some(logo) => {
+                let target = size.min(max_width).round().max(1.0) as u32;
+                let height = (logo.height() as f32 * target as f32 / logo.width().max(1) as f32)
+                    .round()
+                    .max(1.0) as u32;
+                Some(imageops::resize(
+                    logo,
+                    target,
+                    height,
+                    imageops::FilterType::CatmullRom,
+                ))
+            }
+            None => {
+                let font = label_font()?;
+                let text = self.style.text.trim();
+                let color = parse_color(&self.style.color).unwrap_or(Rgba([255, 59, 48, 255]));
+                let mut scale = PxScale::from(size);
+                let (text_width, _) = text_size(scale, font, text);
+                // Long text shrinks to fit rather than running off the image
+                if text_width as f32 > max_width {
+                    scale = PxScale::from(size * max_width / text_width as f32);
+                }
+                let (text_width, text_height) = text_size(scale, font, text);
+                let mut layer = RgbaImage::new(text_width.max(1), text_height.max(1));
+                draw_text_mut(&mut layer, color, 0, 0, scale, font, text);
+                Some(layer)
+            }
+        }
+    }
+}
+
+/// `mark` with its alpha scaled by `opacity`
+fn faded(mut mark: RgbaImage, opacity: f32) -> RgbaImage {
+    let opacity = opacity.clamp(0.0, 1.0);
+    for pixel in mark.pixels_mut() {
+        pixel[3] = (pixel[3] as f32 * opacity).round() as u8;
+    }
+    mark
+}
+
+/// Top-left corners a `mark`-sized watermark is stamped at on a `width` x
+/// `height` screenshot
+fn placements(
+    width: u32,
+    height: u32,
+    (mark_width, mark_height): (u32, u32),
+    position: WatermarkPosition,
+) -> Vec<(i64, i64)> {
+    let (width, height) = (width as i64, height as i64);
+    let (mark_width, mark_height) = (mark_width as i64, mark_height as i64);
+    let margin = (mark_height / 2).max(8);
+    let right = width - mark_width - margin;
+    let bottom = height - mark_height - margin;
+    match position {
+        WatermarkPosition::Center => vec![((width - mark_width) / 2, (height - mark_height) / 2)],
+        WatermarkPosition::TopLeft => vec![(margin, margin)],
+        WatermarkPosition::TopRight => vec![(right, margin)],
+        WatermarkPosition::BottomLeft => vec![(margin, bottom)],
+        WatermarkPosition::BottomRight => vec![(right, bottom)],
+        WatermarkPosition::Tiled => {
+            let step_x = (mark_width as f32 * (1.0 + TILE_GAP)).round().max(1.0) as usize;
+            let step_y = (mark_height as f32 * (1.0 + TILE_GAP)).round().max(1.0) as usize;
+            (0..height.max(0))
+                .step_by(step_y)
+                .enumerate()
+                .flat_map(|(row, y)| {
+                    // Every other row is shifted by half a tile
+                    let offset = if row % 2 == 1 { step_x as i64 / 2 } else { 0 };
+                    (-offset..width)
+                        .step_by(step_x)
+                        .map(move |x| (x, y))
+                        .collect::<Vec<_>>()
+                })
+                .collect()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn watermark_goes_where_it_is_placed() {
+        let at = |position| placements(1000, 800, (200, 40), position);
+        assert_eq!(at(WatermarkPosition::Center), vec![(400, 380)]);
+        assert_eq!(at(WatermarkPosition::TopLeft), vec![(20, 20)]);
+        assert_eq!(at(WatermarkPosition::BottomRight), vec![(780, 740)]);
+    }
+
+    #[test]
+    fn tiled_watermark_covers_the_screenshot() {
+        let tiles = placements(1000, 800, (200, 40), WatermarkPosition::Tiled);
+        assert!(tiles.contains(&(0, 0)));
+        assert!(tiles.contains(&(-200, 80)));
+        assert!(tiles.iter().all(|&(x, y)| x < 1000 && y < 800));
+        assert_eq!(tiles.len(), 30);
+    }
+
+    #[test]
+    fn image_watermark_is_blended_at_its_opacity() {
+        let dir = std::env::temp_dir().join(format!("stepsnap_watermark_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let logo = dir.join("logo.png");
+        RgbaImage::from_pixel(20, 10, Rgba([255, 255, 255, 255]))
+            .save(&logo)
+            .unwrap();
+
+        let style = WatermarkStyle {
+            enabled: true,
+            kind: WatermarkKind::Image,
+            image_path: Some(logo.to_string_lossy().to_string()),
+            position: WatermarkPosition::TopLeft,
+            opacity: 0.5,
+            size: 40.0,
+            ..WatermarkStyle::default()
+        };
+        let mut image = RgbaImage::from_pixel(200, 100, Rgba([0, 0, 0, 255]));
+        Watermark::load(&style).unwrap().draw(&mut image, 1.0);
+        let _ = std::fs::remove_dir_all(&dir);
+
+        // A 40x20 logo with a 10px margin, half see-through
+        let stamped = image.get_pixel(20, 15);
+        assert!((126..=129).contains(&stamped[0]), "{:?}", stamped);
+        assert_eq!(*image.get_pixel(5, 5), Rgba([0, 0, 0, 255]));
+        assert_eq!(*image.get_pixel(55, 15), Rgba([0, 0, 0, 255]));
+    }
+
+    #[test]
+    fn disabled_or_empty_watermarks_are_not_loaded() {
+        assert!(Watermark::load(&WatermarkStyle::default()).is_none());
+        let blank = WatermarkStyle {
+            enabled: true,
+            text: "  ".to_string(),
+            ..WatermarkStyle::default()
+        };
+        assert!(Watermark::load(&blank).is_none());
+    }
+}
@@ -0,0 +1,439 @@
+//! Window list for the capture picker.
+//!
+//! `get_windows` used to return the first 30 capturable windows and drop the
+//! rest, so with many windows open the one the user wanted could be missing
+//! from the picker. It now takes a `WindowQuery` (a search over titles and
+//! app names, whether to list minimized windows, and a page) and reports how
+//! many windows matched, so the picker can search and page through all of
+//! them. Windows on the page carry their app's icon as a PNG data URL, read
+//! from the process's executable on Windows, `NSRunningApplication` on macOS
+//! and the window's `_NET_WM_ICON` on X11.
+
+use crate::WindowInfo;
+use base64::Engine;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::io::Cursor;
+use std::sync::{Mutex, OnceLock};
+
+/// Windows per page when the query doesn't say
+const DEFAULT_PAGE_SIZE: usize = 50;
+const MAX_PAGE_SIZE: usize = 200;
+/// Icons are scaled down to this many pixels square
+const ICON_SIZE: u32 = 32;
+
+#[derive(Debug, Deserialize)]
+#[serde(default)]
+pub struct WindowQuery {
+    /// Words that must each appear in the title or app name, ignoring case
+    pub search: String,
+    pub include_minimized: bool,
+    pub offset: usize,
+    pub limit: usize,
+}
+
+impl Default for WindowQuery {
+    fn default() -> Self {
+        Self {
+            search: String::new(),
+            include_minimized: true,
+            offset: 0,
+            limit: DEFAULT_PAGE_SIZE,
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct WindowPage {
+    pub windows: Vec<WindowInfo>,
+    /// Windows matching the query, on every page
+    pub total: usize,
+    /// Capturable windows before the search and minimized filters
+    pub total_windows: usize,
+}
+
+fn matches(window: &WindowInfo, query: &WindowQuery, words: &[String]) -> bool {
+    if window.is_minimized && !query.include_minimized {
+        return false;
+    }
+    let title = window.title.to_lowercase();
+    let app_name = window.app_name.to_lowercase();
+    words
+        .iter()
+        .all(|word| title.contains(word.as_str()) || app_name.contains(word.as_str()))
+}
+
+/// The page of `windows` the query asks for. Icons aren't loaded yet.
+pub fn page(windows: Vec<WindowInfo>, query: &WindowQuery) -> WindowPage {
+    let total_windows = windows.len();
+    let words: Vec<String> = query
+        .search
+        .split_whitespace()
+        .map(str::to_lowercase)
+        .collect();
+    let matching: Vec<WindowInfo> = windows
+        .into_iter()
+        .filter(|window| matches(window, query, &words))
+        .collect();
+    let total = matching.len();
+    let windows = matching
+        .into_iter()
+        .skip(query.offset)
+        .take(query.limit.clamp(1, MAX_PAGE_SIZE))
+        .collect();
+    WindowPage {
+        windows,
+        total,
+        total_windows,
+    }
+}
+
+/// Icons already read, by process id. None when an app has none.
+fn icon_cache() -> &'static Mutex<HashMap<u32, Option<String>>> {
+    static CACHE: OnceLock<Mutex<HashMap<u32, Option<String>>>> = OnceLock::new();
+    CACHE.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+/// Icon of the app owning the window, as a PNG data URL
+pub fn icon(window_id: u32, pid: u32) -> Option<String> {
+    if pid == 0 {
+        return None;
+    }
+    if let Some(icon) = icon_cache().lock().unwrap().get(&pid) {
+        return icon.clone();
+    }
+
+    let icon = read_icon(window_id, pid).and_then(|icon| {
+        let icon = if icon.width() > ICON_SIZE || icon.height() > ICON_SIZE {
+            image::imageops::resize(
+                &icon,
+                ICON_SIZE,
+                ICON_SIZE,
+                image::imageops::FilterType::Lanczos3,
+            )
+        } else {
+            icon
+        };
+        let mut png = Vec::new();
+        icon.write_to(&mut Cursor::new(&mut png), image::ImageFormat::Png)
+            .ok()?;
+        Some(format!(
+            "data:image/png;base64,{}",
+            base64::engine::general_purpose::STANDARD.encode(png)
+        ))
+    });
+    icon_cache().lock().unwrap().insert(pid, icon.clone());
+    icon
+}
+
+#[cfg(target_os = "windows")]
+use windows_impl::read_icon;
+
+#[cfg(target_os = "windows")]
+mod windows_impl {
+    use image::RgbaImage;
+    use std::ffi::c_void;
+    use windows::core::{PCWSTR, PWSTR};
+    use windows::Win32::Foundation::{CloseHandle, HWND, MAX_PATH};
+    use windows::Win32::Graphics::Gdi::{
+        DeleteObject, GetDC, GetDIBits, GetObjectW, ReleaseDC, BITMAP, BITMAPINFO,
+        BITMAPINFOHEADER, BI_RGB, DIB_RGB_COLORS, HBITMAP,
+    };
+    use windows::Win32::Storage::FileSystem::FILE_FLAGS_AND_ATTRIBUTES;
+    use windows::Win32::System::Threading::{
+        OpenProcess, QueryFullProcessImageNameW, PROCESS_NAME_WIN32,
+        PROCESS_QUERY_LIMITED_INFORMATION,
+    };
+    use windows::Win32::UI::Shell::{SHGetFileInfoW, SHFILEINFOW, SHGFI_ICON, SHGFI_LARGEICON};
+    use windows::Win32::UI::WindowsAndMessaging::{DestroyIcon, GetIconInfo, HICON, ICONINFO};
+
+    /// The large shell icon of the process's executable
+    pub fn read_icon(_window_id: u32, pid: u32) -> Option<RgbaImage> {
+        unsafe {
+            let process = OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, false, pid).ok()?;
+            let mut path = [0u16; MAX_PATH as usize];
+            let mut len = path.len() as u32;
+            let queried = QueryFullProcessImageNameW(
+                process,
+                PROCESS_NAME_WIN32,
+                PWSTR(path.as_mut_ptr()),
+                &mut len,
+            );
+            let _ = CloseHandle(process);
+            queried.ok()?;
+
+            let mut info = SHFILEINFOW::default();
+            let found = SHGetFileInfoW(
+                PCWSTR(path.as_ptr()),
+                FILE_FLAGS_AND_ATTRIBUTES(0),
+                Some(&mut info as *mut SHFILEINFOW),
+                std::mem::size_of::<SHFILEINFOW>() as u32,
+                SHGFI_ICON | SHGFI_LARGEICON,
+            );
+            if found == 0 || info.hIcon.is_invalid() {
+                return None;
+            }
+            let icon = icon_pixels(info.hIcon);
+            let _ = DestroyIcon(info.hIcon);
+            icon
+        }
+    }
+
+    unsafe fn icon_pixels(icon: HICON) -> Option<RgbaImage> {
+        let mut info = ICONINFO::default();
+        GetIconInfo(icon, &mut info).ok()?;
+        let pixels = bitmap_pixels(info.hbmColor);
+        let _ = DeleteObject(info.hbmColor);
+        let _ = DeleteObject(info.hbmMask);
+        pixels
+    }
+
+    unsafe fn bitmap_pixels(bitmap: HBITMAP) -> Option<RgbaImage> {
+        if bitmap.is_invalid() {
+            return None;
+        }
+        let mut header = BITMAP::default();
+        let size = std::mem::size_of::<BITMAP>() as i32;
+        let header_ptr = &mut header as *mut BITMAP as *mut c_void;
+        if GetObjectW(bitmap, size, Some(header_ptr)) == 0 {
+            return None;
+        }
+        let (width, height) = (header.bmWidth, header.bmHeight);
+        if width <= 0 || height <= 0 {
+            return None;
+        }
+
+        let mut info = BITMAPINFO {
+            bmiHeader: BITMAPINFOHEADER {
+                biSize: std::mem::size_of::<BITMAPINFOHEADER>() as u32,
+                biWidth: width,
+                // Negative for rows top to bottom
+                biHeight: -height,
+                biPlanes: 1,
+                biBitCount: 32,
+                biCompression: BI_RGB.0,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let mut pixels = vec![0u8; width as usize * height as usize * 4];
+        let dc = GetDC(HWND::default());
+        let lines = GetDIBits(
+            dc,
+            bitmap,
+            0,
+            height as u32,
+            Some(pixels.as_mut_ptr() as *mut c_void),
+            &mut info,
+            DIB_RGB_COLORS,
+        );
+        ReleaseDC(HWND::default(), dc);
+        if lines == 0 {
+            return None;
+        }
+
+        // BGRA; icons without an alpha channel leave it all zero
+        let opaque = pixels.chunks_exact(4).all(|pixel| pixel[3] == 0);
+        for pixel in pixels.chunks_exact_mut(4) {
+            pixel.swap(0, 2);
+            if opaque {
+                pixel[3] = 255;
+            }
+        }
+        RgbaImage::from_raw(width as u32, height as u32, pixels)
+    }
+}
+
+#[cfg(target_os = "macos")]
+use macos_impl::read_icon;
+
+#[cfg(target_os = "macos")]
+mod macos_impl {
+    use image::RgbaImage;
+    use objc2_app_kit::NSRunningApplication;
+
+    /// The icon AppKit shows for the running app
+    pub fn read_icon(_window_id: u32, pid: u32) -> Option<RgbaImage> {
+        let tiff = unsafe {
+            let app = NSRunningApplication::runningApplicationWithProcessIdentifier(pid as i32)?;
+            app.icon()?.TIFFRepresentation()?
+        };
+        image::load_from_memory_with_format(&tiff.to_vec(), image::ImageFormat::Tiff)
+            .ok()
+            .map(|icon| icon.into_rgba8())
+    }
+}
+
+#[cfg(target_os = "linux")]
+use linux_impl::read_icon;
+
+#[cfg(target_os = "linux")]
+mod linux_impl {
+    use image::RgbaImage;
+    use std::os::raw::{c_int, c_long, c_uchar, c_ulong};
+    use x11::xlib;
+
+    /// Items read from `_NET_WM_ICON` at most; a 256px icon is 64K of them
+    const MAX_ICON_ITEMS: c_long = 1 << 20;
+
+    /// The window's `_NET_WM_ICON`. Not set by Wayland-native windows, which
+    /// xcap doesn't list anyway.
+    pub fn read_icon(window_id: u32, _pid: u32) -> Option<RgbaImage> {
+        unsafe {
+            let display = xlib::XOpenDisplay(std::ptr::null());
+            if display.is_null() {
+                return None;
+            }
+            let values = net_wm_icon(display, window_id);
+            xlib::XCloseDisplay(display);
+            pick_icon(&values?, super::ICON_SIZE)
+        }
+    }
+
+    unsafe fn net_wm_icon(display: *mut xlib::Display, window_id: u32) -> Option<Vec<u32>> {
+        let atom = xlib::XInternAtom(display, b"_NET_WM_ICON\0".as_ptr() as *const _, xlib::True);
+        if atom == 0 {
+            return None;
+        }
+        let mut actual_type: xlib::Atom = 0;
+        let mut actual_format: c_int = 0;
+        let mut items: c_ulong = 0;
+        let mut remaining: c_ulong = 0;
+        let mut data: *mut c_uchar = std::ptr::null_mut();
+        let status = xlib::XGetWindowProperty(
+            display,
+            window_id as xlib::Window,
+            atom,
+            0,
+            MAX_ICON_ITEMS,
+            xlib::False,
+            xlib::XA_CARDINAL,
+            &mut actual_type,
+            &mut actual_format,
+            &mut items,
+            &mut remaining,
+            &mut data,
+        );
+        if status != xlib::Success as c_int || data.is_null() {
+            return None;
+        }
+        // Format 32 items come back as C longs
+        let values = (actual_format == 32).then(|| {
+            std::slice::from_raw_parts(data as *const c_ulong, items as usize)
+                .iter()
+                .map(|&value| value as u32)
+                .collect()
+        });
+        xlib::XFree(data as *mut _);
+        values
+    }
+
+    /// The smallest icon in a `_NET_WM_ICON` value at least `size` wide, or
+    /// the largest when all are smaller. Each is a width, a height and then
+    /// ARGB pixels.
+    pub(super) fn pick_icon(values: &[u32], size: u32) -> Option<RgbaImage> {
+        let mut best: Option<(u32, u32, &[u32])> = None;
+        let mut rest = values;
+        while let [width, height, tail @ ..] = rest {
+            let len = (*width as usize).checked_mul(*height as usize)?;
+            if len == 0 || tail.len() < len {
+                break;
+            }
+            let (pixels, next) = tail.split_at(len);
+            let better = match best {
+                None => true,
+                Some((best_width, ..)) if best_width >= size => {
+                    *width >= size && *width < best_width
+                }
+                Some((best_width, ..)) => *width > best_width,
+            };
+            if better {
+                best = Some((*width, *height, pixels));
+            }
+            rest = next;
+        }
+
+        let (width, height, pixels) = best?;
+        let rgba = pixels
+            .iter()
+            .flat_map(|argb| {
+                let [b, g, r, a] = argb.to_le_bytes();
+                [r, g, b, a]
+            })
+            .collect();
+        RgbaImage::from_raw(width, height, rgba)
+    }
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "macos", target_os = "linux")))]
+fn read_icon(_window_id: u32, _pid: u32) -> Option<image::RgbaImage> {
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn window(id: u32, title: &str, app_name: &str, is_minimized: bool) -> WindowInfo {
+        WindowInfo {
+            id,
+            pid: 0,
+            title: title.to_string(),
+            app_name: app_name.to_string(),
+            x: 0,
+            y: 0,
+            width: 800,
+            height: 600,
+            is_minimized,
+            icon: None,
+        }
+    }
+
+    fn ids(page: &WindowPage) -> Vec<u32> {
+        page.windows.iter().map(|window| window.id).collect()
+    }
+
+    #[test]
+    fn searches_filters_and_pages_windows() {
+        let windows: Vec<WindowInfo> = (0..40)
+            .map(|id| window(id, &format!("Report {}", id), "Word", id % 2 == 1))
+            .chain([window(40, "Inbox - Outlook", "Outlook", false)])
+            .collect();
+
+        let all = page(windows.clone(), &WindowQuery::default());
+        assert_eq!((all.total, all.total_windows), (41, 41));
+        assert_eq!(all.windows.len(), 41);
+
+        let search = WindowQuery {
+            search: "  INBOX outlook ".to_string(),
+            ..WindowQuery::default()
+        };
+        assert_eq!(ids(&page(windows.clone(), &search)), [40]);
+
+        let restored = WindowQuery {
+            search: "word".to_string(),
+            include_minimized: false,
+            offset: 10,
+            limit: 5,
+        };
+        let second = page(windows, &restored);
+        assert_eq!(second.total, 20);
+        assert_eq!(ids(&second), [20, 22, 24, 26, 28]);
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn picks_the_closest_net_wm_icon() {
+        let mut values = vec![16, 16];
+        values.extend(std::iter::repeat(0xff00_00ff).take(256));
+        values.extend([48, 48]);
+        values.extend(std::iter::repeat(0x80ff_0000).take(48 * 48));
+        let icon = linux_impl::pick_icon(&values, ICON_SIZE).unwrap();
+        assert_eq!(icon.dimensions(), (48, 48));
+        assert_eq!(icon.get_pixel(0, 0).0, [255, 0, 0, 128]);
+
+        let icon = linux_impl::pick_icon(&values[..258], ICON_SIZE).unwrap();
+        assert_eq!(icon.get_pixel(0, 0).0, [0, 0, 255, 255]);
+        assert!(linux_impl::pick_icon(&[4, 4, 1], ICON_SIZE).is_none());
+    }
+}
@@ -0,0 +1,212 @@
+//! Magnified insets of the area around a click.
+//!
+//! On a 4K screenshot shrunk onto a page, the clicked control is a few
+//! pixels wide. `draw_zoom_inset` copies the area around the click, enlarges
+//! it by `ZoomInsetStyle::zoom` and pastes it picture-in-picture style into
+//! a corner away from the click, so readers see both the whole screen and
+//! what was clicked. Like the other export styles it's only applied to the
+//! copies rendered for export.
+
+use crate::annotations::parse_color;
+use crate::smart_crop::CropRect;
+use image::{imageops, Rgba, RgbaImage};
+use serde::{Deserialize, Serialize};
+
+/// Insets never take more than this fraction of the screenshot's shorter side
+const MAX_SIDE_FRACTION: f32 = 0.4;
+
+/// Insets smaller than this many pixels aren't worth drawing
+const MIN_SIDE: u32 = 48;
+
+/// Corner an inset is pasted into
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum InsetPlacement {
+    /// The corner furthest from the click
+    #[default]
+    Auto,
+    TopLeft,
+    TopRight,
+    BottomLeft,
+    BottomRight,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ZoomInsetStyle {
+    pub enabled: bool,
+    /// Magnification of the area around the click
+    pub zoom: f32,
+    /// Side of the square inset in logical pixels
+    pub size: f32,
+    /// `#RRGGBB` or `#RRGGBBAA` frame around the inset
+    pub border_color: String,
+    pub placement: InsetPlacement,
+}
+
+impl Default for ZoomInsetStyle {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            zoom: 2.5,
+            size: 240.0,
+            border_color: "#FFFFFF".to_string(),
+            placement: InsetPlacement::Auto,
+        }
+    }
+}
+
+impl ZoomInsetStyle {
+    /// The style in physical pixels of a screenshot at `scale_factor`
+    pub fn scaled(&self, scale_factor: f32) -> Self {
+        Self {
+            size: self.size * scale_factor,
+            ..self.clone()
+        }
+    }
+}
+
+/// Where an inset goes on a `width` x `height` screenshot clicked at
+/// `click`: the area it magnifies and the square it's pasted into, as
+/// (area, top-left corner, side). None when the screenshot is too small for
+/// one or the zoom doesn't magnify.
+fn inset_layout(
+    width: u32,
+    height: u32,
+    click: (i32, i32),
+    style: &ZoomInsetStyle,
+) -> Option<(CropRect, (u32, u32), u32)> {
+    if style.zoom <= 1.0 || width == 0 || height == 0 {
+        return None;
+    }
+    let side = style
+        .size
+        .min(width.min(height) as f32 * MAX_SIDE_FRACTION)
+        .round() as u32;
+    if side < MIN_SIDE {
+        return None;
+    }
+
+    // The magnified area, centred on the click but kept inside the image
+    let source = ((side as f32 / style.zoom).round() as u32).max(1);
+    let start = |center: i32, limit: u32| {
+        (center - source as i32 / 2).clamp(0, limit.saturating_sub(source) as i32) as u32
+    };
+    let area = CropRect {
+        x: start(click.0, width),
+        y: start(click.1, height),
+        width: source.min(width),
+        height: source.min(height),
+    };
+
+    let margin = (side / 16).max(8);
+    let left = margin;
+    let right = width.saturating_sub(side + margin);
+    let top = margin;
+    let bottom = height.saturating_sub(side + margin);
+    let corner = match style.placement {
+        InsetPlacement::TopLeft => (left, top),
+        InsetPlacement::TopRight => (right, top),
+        InsetPlacement::BottomLeft => (left, bottom),
+        InsetPlacement::BottomRight => (right, bottom),
+        InsetPlacement::Auto => (
+            if click.0 < width as i32 / 2 {
+                right
+            } else {
+                left
+            },
+            if click.1 < height as i32 / 2 {
+                bottom
+            } else {
+                top
+            },
+        ),
+    };
+    Some((area, corner, side))
+}
+
+/// Paste a magnified copy of the area around `click` (in image pixels) into
+/// a corner of `image`, framed in `style.border_color`.
+pub fn draw_zoom_inset(image: &mut RgbaImage, click: (i32, i32), style: &ZoomInsetStyle) {
+    let Some((area, (x, y), side)) = inset_layout(image.width(), image.height(), click, style)
+    else {
+        return;
+    };
+    let source = imageops::crop_imm(image, area.x, area.y, area.width, area.height).to_image();
+    let magnified = imageops::resize(&source, side, side, imageops::FilterType::CatmullRom);
+
+    let border = (side / 60).max(2);
+    let color = parse_color(&style.border_color).unwrap_or(Rgba([255, 255, 255, 255]));
+    let frame = RgbaImage::from_pixel(side + border * 2, side + border * 2, color);
+    imageops::replace(
+        image,
+        &frame,
+        x as i64 - border as i64,
+        y as i64 - border as i64,
+    );
+    imageops::replace(image, &magnified, x as i64, y as i64);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn style(size: f32, zoom: f32) -> ZoomInsetStyle {
+        ZoomInsetStyle {
+            enabled: true,
+            zoom,
+            size,
+            ..ZoomInsetStyle::default()
+        }
+    }
+
+    #[test]
+    fn inset_goes_in_the_corner_away_from_the_click() {
+        let (area, corner, side) = inset_layout(1000, 800, (100, 100), &style(200.0, 2.0)).unwrap();
+        assert_eq!(side, 200);
+        assert_eq!(
+            area,
+            CropRect {
+                x: 50,
+                y: 50,
+                width: 100,
+                height: 100
+            }
+        );
+        assert_eq!(corner, (1000 - 200 - 12, 800 - 200 - 12));
+
+        let (_, corner, _) = inset_layout(1000, 800, (900, 700), &style(200.0, 2.0)).unwrap();
+        assert_eq!(corner, (12, 12));
+    }
+
+    #[test]
+    fn magnified_area_stays_inside_the_image() {
+        let (area, ..) = inset_layout(1000, 800, (995, 2), &style(200.0, 2.0)).unwrap();
+        assert_eq!((area.x, area.y), (900, 0));
+    }
+
+    #[test]
+    fn small_screenshots_and_no_zoom_get_no_inset() {
+        assert!(inset_layout(100, 100, (50, 50), &style(240.0, 2.0)).is_none());
+        assert!(inset_layout(1000, 800, (50, 50), &style(240.0, 1.0)).is_none());
+    }
+
+    #[test]
+    fn inset_shows_the_clicked_pixels_enlarged() {
+        let mut image = RgbaImage::from_pixel(400, 400, Rgba([0, 0, 0, 255]));
+        for y in 95..105 {
+            for x in 95..105 {
+                image.put_pixel(x, y, Rgba([255, 0, 0, 255]));
+            }
+        }
+        draw_zoom_inset(&mut image, (100, 100), &style(120.0, 4.0));
+
+        // A 120px inset of the 30px around the click, bottom right
+        let (x, y) = (400 - 120 - 8, 400 - 120 - 8);
+        let center = image.get_pixel(x + 60, y + 60);
+        assert!(center[0] > 240 && center[1] < 16, "{:?}", center);
+        let edge = image.get_pixel(x + 5, y + 5);
+        assert!(edge[0] < 16, "{:?}", edge);
+        assert_eq!(*image.get_pixel(x - 1, y + 60), Rgba([255, 255, 255, 255]));
+    }
+}
@@ -3,6 +3,7 @@ import { Routes, Route, useNavigate } from "react-router-dom";
 import { invoke } from "@tauri-apps/api/core";
 import { listen } from "@tauri-apps/api/event";
 import { getCurrentWindow } from "@tauri-apps/api/window";
+import { ask } from "@tauri-apps/plugin-dialog";
 
 import ToastHost from "./components/ToastHost";
 import TitleBar from "./components/TitleBar";
@@ -23,6 +24,8 @@ import { useToastStore } from "./store/toastStore";
 import { useUpdateStore } from "./store/updateStore";
 import { useNotificationStore } from "./store/notificationStore";
 import UpdateNotification from "./components/UpdateNotification";
+import PermissionNotice from "./components/PermissionNotice";
+import { usePermissionsStore, type PermissionStatus } from "./store/permissionsStore";
 
 // Loading fallback component
 const PageLoader = () => (
@@ -34,12 +37,18 @@ const PageLoader = () => (
   </div>
 );
 
+/** Mirrors `DeepLink` in the backend. */
+type DeepLink =
+  | { action: "open_recording"; recording_id: string }
+  | { action: "start_recording"; name: string | null }
+  | { action: "import_bundle"; path: string };
+
 let settingsHydrationPromise: Promise<void> | null = null;
 let backgroundStartupPromise: Promise<void> | null = null;
 
 function App() {
   const navigate = useNavigate();
-  const { isRecording, setIsRecording } = useRecorderStore();
+  const { isRecording, isPaused, setIsRecording, setIsPaused } = useRecorderStore();
   const { isLoaded, sendScreenshotsToAi } = useSettingsStore();
   const isShellReady = useStartupStore((state) => state.isShellReady);
 
@@ -163,6 +172,13 @@ function App() {
           state: "running",
           message: "Checking for updates",
         });
+        // First run: time the OCR providers and keep the fastest. Runs in
+        // the background and retries on later launches until a screenshot
+        // exists to benchmark on.
+        if (sendScreenshotsToAi && !settingsStore.ocrProviderBenchmarked) {
+          void settingsStore.benchmarkOcrProviders();
+        }
+
         await useUpdateStore.getState().checkForUpdates();
         const updateError = useUpdateStore.getState().error;
         startup.applyStatus({
@@ -195,6 +211,78 @@ function App() {
     };
   }, []);
 
+  // Track macOS permissions; the backend emits changes made in System Settings.
+  // The environment check covers what else recording needs (input, storage, OCR).
+  useEffect(() => {
+    void usePermissionsStore.getState().refresh();
+    void usePermissionsStore.getState().checkEnvironment();
+    const unlistenPermissions = listen<PermissionStatus>("permission-status", (event) => {
+      usePermissionsStore.getState().setStatus(event.payload);
+      void usePermissionsStore.getState().checkEnvironment();
+    });
+
+    return () => {
+      unlistenPermissions.then((f) => f());
+    };
+  }, []);
+
+  // openscribe:// links, queued by the backend until we take them. Any web
+  // page can open one, so ask before recording or importing.
+  useEffect(() => {
+    const handleLink = async (link: DeepLink) => {
+      switch (link.action) {
+        case "open_recording":
+          navigate(`/recordings/${link.recording_id}`);
+          break;
+        case "start_recording": {
+          if (useRecorderStore.getState().isRecording) {
+            useToastStore.getState().showToast({ message: "A recording is already in progress", variant: "info" });
+            return;
+          }
+          const prompt = link.name ? `Start recording "${link.name}"?` : "Start a new recording?";
+          if (!(await ask(prompt, { title: "Open Link", kind: "info" }))) {
+            return;
+          }
+          await invoke("start_recording");
+          setIsRecording(true);
+          navigate("/new-recording", { state: { name: link.name } });
+          await getCurrentWindow().minimize();
+          break;
+        }
+        case "import_bundle": {
+          if (!(await ask(`Import the recording in ${link.path}?`, { title: "Open Link", kind: "info" }))) {
+            return;
+          }
+          const recordingId = await invoke<string>("import_recording_bundle", { path: link.path });
+          navigate(`/recordings/${recordingId}`);
+          break;
+        }
+      }
+    };
+
+    const takeLinks = async () => {
+      const links = await invoke<DeepLink[]>("take_deep_links");
+      for (const link of links) {
+        try {
+          await handleLink(link);
+        } catch (error) {
+          useToastStore.getState().showToast({
+            message: `Failed to open link: ${error}`,
+            variant: "error",
+            logCategory: "app",
+          });
+        }
+      }
+    };
+
+    void takeLinks();
+    const unlistenDeepLink = listen("deep-link", () => void takeLinks());
+
+    return () => {
+      unlistenDeepLink.then((f) => f());
+    };
+  }, [navigate, setIsRecording]);
+
   // Listen for hotkey events
   useEffect(() => {
     const unlistenStart = listen("hotkey-start", async () => {
@@ -237,12 +325,24 @@ function App() {
       }
     });
 
+    const unlistenPause = listen("hotkey-pause", async () => {
+      if (isRecording) {
+        try {
+          await invoke(isPaused ? "resume_recording" : "pause_recording");
+          setIsPaused(!isPaused);
+        } catch (error) {
+          console.error("Failed to pause or resume recording:", error);
+        }
+      }
+    });
+
     return () => {
       unlistenStart.then((f) => f());
       unlistenStop.then((f) => f());
       unlistenCapture.then((f) => f());
+      unlistenPause.then((f) => f());
     };
-  }, [isRecording, setIsRecording, navigate]);
+  }, [isRecording, isPaused, setIsRecording, setIsPaused, navigate]);
 
   const isMonitorPicker =
     typeof window !== "undefined" &&
@@ -263,6 +363,7 @@ function App() {
       ) : (
         <div className="flex h-screen flex-col">
           <TitleBar />
+          <PermissionNotice />
           <div className="flex min-h-0 flex-1">
             <Suspense fallback={<PageLoader />}>
               <Routes>
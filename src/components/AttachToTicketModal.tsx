@@ -0,0 +1,132 @@
+import { useState } from "react";
+import { invoke } from "@tauri-apps/api/core";
+import { X } from "lucide-react";
+
+import { useToastStore } from "../store/toastStore";
+
+type Tracker = "jira" | "azure_devops";
+type Format = "pdf" | "html";
+
+interface AttachToTicketModalProps {
+    markdown: string;
+    fileName: string;
+    recordingId?: string;
+    onClose: () => void;
+}
+
+const inputClass =
+    "w-full px-4 py-2 bg-[#161316]/70 border border-white/10 rounded-md text-white placeholder-white/50 focus:outline-none focus:border-[#2721E8]";
+
+const TRACKERS: Record<Tracker, { label: string; placeholder: string }> = {
+    jira: { label: "Jira", placeholder: "ABC-123 or a link to the issue" },
+    azure_devops: { label: "Azure DevOps", placeholder: "42 or a link to the work item" },
+};
+
+/** Renders the export here and has the backend upload it to the ticket. */
+export default function AttachToTicketModal({ markdown, fileName, recordingId, onClose }: AttachToTicketModalProps) {
+    const [tracker, setTracker] = useState<Tracker>("jira");
+    const [ticket, setTicket] = useState("");
+    const [format, setFormat] = useState<Format>("pdf");
+    const [attaching, setAttaching] = useState(false);
+    const [error, setError] = useState<string | null>(null);
+
+    const render = async (): Promise<Uint8Array> => {
+        const { withAnnotatedScreenshots } = await import("../lib/export/utils");
+        const exportMarkdown = await withAnnotatedScreenshots(markdown, recordingId);
+        if (format === "pdf") {
+            const { renderPdf } = await import("../lib/export/pdfExporter");
+            return renderPdf(exportMarkdown);
+        }
+        const { renderHtml } = await import("../lib/export/htmlExporter");
+        return renderHtml(exportMarkdown, fileName);
+    };
+
+    const handleAttach = async () => {
+        setAttaching(true);
+        setError(null);
+        try {
+            const data = await render();
+            const result = await invoke<{ ticket: string; url: string }>("attach_to_ticket", {
+                tracker,
+                ticket,
+                fileName: `${fileName}.${format}`,
+                data: Array.from(data),
+            });
+            useToastStore.getState().showToast({
+                message: `Attached to ${result.ticket}`,
+                variant: "success",
+            });
+            onClose();
+        } catch (attachError) {
+            setError(String(attachError));
+        } finally {
+            setAttaching(false);
+        }
+    };
+
+    return (
+        <div className="fixed inset-0 z-50 flex items-center justify-center bg-black/80 p-8">
+            <div className="glass-surface-1 w-full max-w-md space-y-4 rounded-xl p-6">
+                <div className="flex items-start justify-between">
+                    <div>
+                        <h3 className="text-lg font-medium text-white">Attach to Ticket</h3>
+                        <p className="mt-1 text-xs text-white/50">
+                            Uploads the export to the issue or work item, using the sites and tokens in Settings.
+                        </p>
+                    </div>
+                    <button
+                        aria-label="Close"
+                        onClick={onClose}
+                        className="rounded-md p-1 text-white/60 transition-colors hover:bg-white/10 hover:text-white"
+                    >
+                        <X size={18} />
+                    </button>
+                </div>
+
+                <div className="flex gap-2">
+                    <div className="flex-1">
+                        <label className="mb-2 block text-sm font-medium text-white/60">Tracker</label>
+                        <select value={tracker} onChange={(e) => setTracker(e.target.value as Tracker)} className={inputClass}>
+                            {(Object.keys(TRACKERS) as Tracker[]).map((key) => (
+                                <option key={key} value={key}>
+                                    {TRACKERS[key].label}
+                                </option>
+                            ))}
+                        </select>
+                    </div>
+                    <div className="w-28">
+                        <label className="mb-2 block text-sm font-medium text-white/60">Format</label>
+                        <select value={format} onChange={(e) => setFormat(e.target.value as Format)} className={inputClass}>
+                            <option value="pdf">PDF</option>
+                            <option value="html">HTML</option>
+                        </select>
+                    </div>
+                </div>
+
+                <div>
+                    <label className="mb-2 block text-sm font-medium text-white/60">Ticket</label>
+                    <input
+                        type="text"
+                        value={ticket}
+                        onChange={(e) => setTicket(e.target.value)}
+                        placeholder={TRACKERS[tracker].placeholder}
+                        className={inputClass}
+                        autoFocus
+                    />
+                </div>
+
+                <button
+                    onClick={() => {
+                        void handleAttach();
+                    }}
+                    disabled={attaching || !ticket.trim()}
+                    className="w-full rounded-md bg-[#2721E8] px-4 py-2 text-sm text-white transition-colors hover:bg-[#2721E8]/90 disabled:opacity-50"
+                >
+                    {attaching ? "Attaching..." : "Attach"}
+                </button>
+
+                {error && <p className="text-xs text-red-400">{error}</p>}
+            </div>
+        </div>
+    );
+}
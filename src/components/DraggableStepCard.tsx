@@ -1,11 +1,27 @@
-import { useState, useMemo, memo } from "react";
-import { convertFileSrc } from "@tauri-apps/api/core";
+import { useEffect, useState, useMemo, useRef, memo } from "react";
+import type { MouseEvent as ReactMouseEvent } from "react";
+import { convertFileSrc, invoke } from "@tauri-apps/api/core";
 import { useSortable } from "@dnd-kit/sortable";
 import { CSS } from "@dnd-kit/utilities";
-import { Trash2, Pencil, GripVertical, ImageOff } from "lucide-react";
+import {
+    Trash2,
+    Pencil,
+    GripVertical,
+    ImageOff,
+    ScanText,
+    Crosshair,
+    X,
+    RotateCcw,
+    RotateCw,
+    FlipHorizontal2,
+    FlipVertical2,
+} from "lucide-react";
 import Tooltip from "./Tooltip";
 import Spinner from "./Spinner";
 import ImageViewer from "./ImageViewer";
+import type { AnnotationPoint, ImageEdit, OcrLine } from "../store/recordingsStore";
+import { useSettingsStore, type ClickHighlightStyle } from "../store/settingsStore";
+import { screenshotSrc as resizedScreenshotSrc } from "../lib/localAssets";
 
 interface Step {
     type_: string;
@@ -26,6 +42,10 @@ interface Step {
     input_source?: string;
     clip_path?: string;
     title?: string;
+    ocr_lines?: OcrLine[];
+    click_marker?: AnnotationPoint | null;
+    scale_factor?: number | null;
+    is_duplicate?: boolean;
 }
 
 interface DraggableStepCardProps {
@@ -36,12 +56,73 @@ interface DraggableStepCardProps {
     /** Receives "after" when the user is currently viewing the after-frame,
      *  so the image editor edits the correct image. */
     onCrop?: (target: "before" | "after") => void;
+    /** Rotate or flip the image being viewed; annotations follow the screenshot. */
+    onEditImage?: (edit: ImageEdit, target: "before" | "after") => void;
     onUpdateDescription: (description: string) => void;
     onUpdateTitle?: (title: string) => void;
+    /** Move the click highlight to a point on the screenshot, or remove it. */
+    onUpdateClickMarker?: (marker: AnnotationPoint | null) => void;
     isDeleting?: boolean;
     cropTimestamp?: number;
+    /** Called when the card scrolls into view, e.g. to OCR it first. */
+    onVisible?: () => void;
+    /** The recording's own click highlight, in place of the global one. */
+    highlightStyle?: ClickHighlightStyle | null;
 }
 
+/** Text styling per OCR confidence; doubtful lines stand out for review. */
+const OCR_CONFIDENCE_CLASSES: Record<OcrLine["confidence"], string> = {
+    high: "text-white/70",
+    medium: "text-amber-200/90",
+    low: "text-red-300 underline decoration-dotted",
+};
+
+/** Drag selection over the screenshot, in pixels relative to the <img> box. */
+/** Widest the screenshot is drawn in a card, in CSS pixels. */
+const CARD_IMAGE_WIDTH = 960;
+
+interface Selection {
+    startX: number;
+    startY: number;
+    endX: number;
+    endY: number;
+}
+
+interface ImageSize {
+    width: number;
+    height: number;
+}
+
+/**
+ * Map a selection on an `object-contain` image to pixels of the original
+ * screenshot, `source`, which the displayed copy may be a resized one of.
+ */
+const selectionToImageRect = (img: HTMLImageElement, selection: Selection, source: ImageSize) => {
+    const scale = Math.min(img.clientWidth / img.naturalWidth, img.clientHeight / img.naturalHeight);
+    const offsetX = (img.clientWidth - img.naturalWidth * scale) / 2;
+    const offsetY = (img.clientHeight - img.naturalHeight * scale) / 2;
+    const toSource = source.width / img.naturalWidth;
+    const left = Math.min(selection.startX, selection.endX);
+    const top = Math.min(selection.startY, selection.endY);
+    const x = Math.max(0, Math.round(((left - offsetX) / scale) * toSource));
+    const y = Math.max(0, Math.round(((top - offsetY) / scale) * toSource));
+    return {
+        x,
+        y,
+        width: Math.round((Math.abs(selection.endX - selection.startX) / scale) * toSource),
+        height: Math.round((Math.abs(selection.endY - selection.startY) / scale) * toSource),
+    };
+};
+
+/** Map a point on an `object-contain` image to pixels of the original screenshot. */
+const pointToImagePixel = (img: HTMLImageElement, x: number, y: number, source: ImageSize): AnnotationPoint => {
+    const rect = selectionToImageRect(img, { startX: x, startY: y, endX: x, endY: y }, source);
+    return {
+        x: Math.min(rect.x, source.width - 1),
+        y: Math.min(rect.y, source.height - 1),
+    };
+};
+
 const defaultTitleForStep = (step: Step, index: number): string => {
     if (step.title && step.title.trim().length > 0) {
         return step.title;
@@ -55,19 +136,99 @@ const defaultTitleForStep = (step: Step, index: number): string => {
     if (step.type_ === "capture") {
         return `Manual capture`;
     }
+    if (step.type_ === "marker") {
+        return step.text ? step.text : `Marker`;
+    }
     return `Step ${index + 1}`;
 };
 
+const IMAGE_EDIT_BUTTONS: { edit: ImageEdit; label: string; Icon: typeof RotateCw }[] = [
+    { edit: { kind: "rotate_counterclockwise" }, label: "Rotate left", Icon: RotateCcw },
+    { edit: { kind: "rotate_clockwise" }, label: "Rotate right", Icon: RotateCw },
+    { edit: { kind: "flip_horizontal" }, label: "Flip horizontally", Icon: FlipHorizontal2 },
+    { edit: { kind: "flip_vertical" }, label: "Flip vertically", Icon: FlipVertical2 },
+];
+
+/** Arrow highlights are this many times the highlight radius long. Matches the exporter. */
+const ARROW_LENGTH = 2.5;
+
+interface ClickHighlightMarkerProps {
+    at: AnnotationPoint;
+    highlight: ClickHighlightStyle;
+    scaleFactor: number;
+    imageSize: { width: number; height: number };
+    maskId: string;
+}
+
+/** The click highlight in image pixels, drawn like `annotations::draw_click_highlight`. */
+const ClickHighlightMarker = ({ at, highlight, scaleFactor, imageSize, maskId }: ClickHighlightMarkerProps) => {
+    const radius = highlight.radius * scaleFactor;
+    const width = highlight.width * scaleFactor;
+
+    if (highlight.shape === "arrow") {
+        // From below and to the right, unless that side runs off the image
+        const offset = (radius * ARROW_LENGTH) / Math.SQRT2;
+        const dx = at.x + offset > imageSize.width ? -offset : offset;
+        const dy = at.y + offset > imageSize.height ? -offset : offset;
+        const length = Math.hypot(dx, dy);
+        const [ux, uy] = [dx / length, dy / length];
+        const head = Math.min(Math.max(width * 4, 14), length);
+        const base = { x: at.x + ux * head, y: at.y + uy * head };
+        const half = head / 2;
+        return (
+            <>
+                <line
+                    x1={at.x + dx}
+                    y1={at.y + dy}
+                    x2={base.x}
+                    y2={base.y}
+                    stroke={highlight.color}
+                    strokeWidth={width}
+                    strokeLinecap="round"
+                />
+                <polygon
+                    points={`${at.x},${at.y} ${base.x - uy * half},${base.y + ux * half} ${base.x + uy * half},${base.y - ux * half}`}
+                    fill={highlight.color}
+                />
+            </>
+        );
+    }
+
+    if (highlight.shape === "spotlight") {
+        return (
+            <>
+                <mask id={maskId}>
+                    <rect width={imageSize.width} height={imageSize.height} fill="white" />
+                    <circle cx={at.x} cy={at.y} r={radius} fill="black" />
+                </mask>
+                <rect width={imageSize.width} height={imageSize.height} fill="black" opacity={0.55} mask={`url(#${maskId})`} />
+                <circle cx={at.x} cy={at.y} r={radius} fill="none" stroke={highlight.color} strokeWidth={width / 2} />
+            </>
+        );
+    }
+
+    return (
+        <>
+            <circle cx={at.x} cy={at.y} r={radius} fill="none" stroke={highlight.color} strokeWidth={width} />
+            <circle cx={at.x} cy={at.y} r={Math.max(2, radius / 6)} fill={highlight.color} />
+        </>
+    );
+};
+
 const DraggableStepCard = memo(function DraggableStepCard({
     step,
     index,
     id,
     onDelete,
     onCrop,
+    onEditImage,
     onUpdateDescription,
     onUpdateTitle,
+    onUpdateClickMarker,
     isDeleting,
     cropTimestamp,
+    onVisible,
+    highlightStyle,
 }: DraggableStepCardProps) {
     const {
         attributes,
@@ -78,6 +239,24 @@ const DraggableStepCard = memo(function DraggableStepCard({
         isDragging,
     } = useSortable({ id });
 
+    // Callers pass inline callbacks, so read the latest one through a ref
+    // instead of re-creating the observer on every render.
+    const cardRef = useRef<HTMLDivElement | null>(null);
+    const onVisibleRef = useRef(onVisible);
+    onVisibleRef.current = onVisible;
+    const observesVisibility = Boolean(onVisible);
+    useEffect(() => {
+        const node = cardRef.current;
+        if (!node || !observesVisibility) return;
+        const observer = new IntersectionObserver((entries) => {
+            if (entries.some((entry) => entry.isIntersecting)) {
+                onVisibleRef.current?.();
+            }
+        });
+        observer.observe(node);
+        return () => observer.disconnect();
+    }, [observesVisibility]);
+
     const style = {
         transform: CSS.Transform.toString(transform),
         transition,
@@ -95,12 +274,100 @@ const DraggableStepCard = memo(function DraggableStepCard({
 
     const showingAfter = frameMode === "after" && hasAfter;
     const activePath = showingAfter ? afterPath : beforePath;
+    // Cards show a copy resized to their width; the viewer shows the original
     const screenshotSrc = useMemo(
+        () => activePath
+            ? resizedScreenshotSrc(activePath, CARD_IMAGE_WIDTH) + (cropTimestamp ? `&t=${cropTimestamp}` : '')
+            : '',
+        [activePath, cropTimestamp]
+    );
+    const fullScreenshotSrc = useMemo(
         () => activePath
             ? convertFileSrc(activePath) + (cropTimestamp ? `?t=${cropTimestamp}` : '')
             : '',
         [activePath, cropTimestamp]
     );
+    // Size of the original, which markers and selections are measured in
+    const [sourceSize, setSourceSize] = useState<ImageSize | null>(null);
+    useEffect(() => {
+        setSourceSize(null);
+        if (!activePath) return;
+        let cancelled = false;
+        invoke<[number, number]>("get_image_size", { path: activePath })
+            .then(([width, height]) => {
+                if (!cancelled) setSourceSize({ width, height });
+            })
+            .catch((error) => console.error("Failed to read the screenshot size:", error));
+        return () => {
+            cancelled = true;
+        };
+    }, [activePath, cropTimestamp]);
+
+    // Region OCR: drag over the screenshot to read its text into the description.
+    const imgRef = useRef<HTMLImageElement>(null);
+    const [isSelectingText, setIsSelectingText] = useState(false);
+    const [selection, setSelection] = useState<Selection | null>(null);
+    const [isReadingText, setIsReadingText] = useState(false);
+    const [ocrError, setOcrError] = useState<string | null>(null);
+
+    const pointInImage = (event: ReactMouseEvent<HTMLDivElement>) => {
+        const bounds = event.currentTarget.getBoundingClientRect();
+        return { x: event.clientX - bounds.left, y: event.clientY - bounds.top };
+    };
+
+    const handleSelectionStart = (event: ReactMouseEvent<HTMLDivElement>) => {
+        const { x, y } = pointInImage(event);
+        setSelection({ startX: x, startY: y, endX: x, endY: y });
+    };
+
+    const handleSelectionMove = (event: ReactMouseEvent<HTMLDivElement>) => {
+        if (!selection) return;
+        const { x, y } = pointInImage(event);
+        setSelection({ ...selection, endX: x, endY: y });
+    };
+
+    const handleSelectionEnd = async () => {
+        const img = imgRef.current;
+        if (!selection || !img || !activePath || !sourceSize) return;
+        setSelection(null);
+        const rect = selectionToImageRect(img, selection, sourceSize);
+        if (rect.width < 4 || rect.height < 4) return;
+
+        setIsSelectingText(false);
+        setIsReadingText(true);
+        setOcrError(null);
+        try {
+            const text = await invoke<string | null>("run_ocr_on_region", { path: activePath, rect });
+            if (text) {
+                const current = step.description?.trimEnd() ?? "";
+                onUpdateDescription(current ? `${current}\n${text}` : text);
+            } else {
+                setOcrError("No text found in the selected area");
+            }
+        } catch (error) {
+            setOcrError(String(error));
+        } finally {
+            setIsReadingText(false);
+        }
+    };
+
+    // The click highlight is drawn over the screenshot rather than burned in,
+    // so it can be moved or removed. It belongs to the before-frame only.
+    const globalHighlight = useSettingsStore((state) => state.clickHighlightStyle);
+    const highlight = highlightStyle ?? globalHighlight;
+    const [isPlacingMarker, setIsPlacingMarker] = useState(false);
+    const marker = !showingAfter && highlight.enabled ? step.click_marker : null;
+    // Screenshot coordinates are physical pixels; the ring size and the click
+    // position shown to the user are logical, as on the recorded monitor
+    const scaleFactor = step.scale_factor || 1;
+
+    const handlePlaceMarker = (event: ReactMouseEvent<HTMLDivElement>) => {
+        const img = imgRef.current;
+        if (!img || !onUpdateClickMarker || !sourceSize) return;
+        const { x, y } = pointInImage(event);
+        onUpdateClickMarker(pointToImagePixel(img, x, y, sourceSize));
+        setIsPlacingMarker(false);
+    };
 
     const titleValue = step.title ?? "";
     const titlePlaceholder = defaultTitleForStep(step, index);
@@ -109,13 +376,16 @@ const DraggableStepCard = memo(function DraggableStepCard({
         <>
             {isViewerOpen && hasScreenshot && (
                 <ImageViewer
-                    imageSrc={screenshotSrc}
+                    imageSrc={fullScreenshotSrc}
                     title={`Step ${index + 1} ${showingAfter ? "(After)" : "Screenshot"}`}
                     onClose={() => setIsViewerOpen(false)}
                 />
             )}
             <div
-                ref={setNodeRef}
+                ref={(node) => {
+                    setNodeRef(node);
+                    cardRef.current = node;
+                }}
                 style={style}
                 className="glass-surface-2 rounded-2xl overflow-hidden relative flex flex-col"
             >
@@ -163,7 +433,7 @@ const DraggableStepCard = memo(function DraggableStepCard({
                     <div className="px-5 pt-3">
                         {step.type_ === "click" && (
                             <p className="text-xs text-white/45">
-                                Click at ({Math.round(step.x || 0)}, {Math.round(step.y || 0)})
+                                Click at ({Math.round((step.x || 0) / scaleFactor)}, {Math.round((step.y || 0) / scaleFactor)})
                                 {step.element_name ? ` · ${step.element_name}` : ''}
                             </p>
                         )}
@@ -196,27 +466,102 @@ const DraggableStepCard = memo(function DraggableStepCard({
                     </div>
                 )}
 
+                {/* Recognised text, shown when OCR wasn't sure of some of it */}
+                {step.ocr_lines?.some((line) => line.confidence !== "high") && (
+                    <div className="px-5 pt-3">
+                        <p className="mb-1 text-xs text-white/45">
+                            Recognised text · check highlighted lines against the screenshot
+                        </p>
+                        <div className="rounded-md bg-[#161316] border border-white/8 px-3 py-2 font-mono text-xs leading-relaxed break-words">
+                            {step.ocr_lines.map((line, lineIndex) => (
+                                <div key={lineIndex} className={OCR_CONFIDENCE_CLASSES[line.confidence]}>
+                                    {line.text}
+                                </div>
+                            ))}
+                        </div>
+                    </div>
+                )}
+
                 {/* Screenshot */}
                 <div className="px-5 pt-4">
                     <div className="relative overflow-hidden rounded-xl border border-white/10 bg-[#161316]">
                         {hasScreenshot ? (
                             <>
                                 <img
+                                    ref={imgRef}
                                     src={screenshotSrc}
                                     alt={`Step ${index + 1} ${showingAfter ? "after-frame" : "screenshot"}`}
                                     loading="lazy"
                                     decoding="async"
-                                    className="w-full h-auto max-h-[420px] object-contain cursor-pointer hover:opacity-95 transition-opacity"
+                                    className="block w-full h-auto max-h-[420px] object-contain cursor-pointer hover:opacity-95 transition-opacity"
                                     onClick={() => setIsViewerOpen(true)}
                                 />
+                                {marker && sourceSize && (
+                                    <svg
+                                        className="absolute inset-0 h-full w-full pointer-events-none"
+                                        viewBox={`0 0 ${sourceSize.width} ${sourceSize.height}`}
+                                        preserveAspectRatio="xMidYMid meet"
+                                        aria-hidden="true"
+                                    >
+                                        <ClickHighlightMarker
+                                            at={marker}
+                                            highlight={highlight}
+                                            scaleFactor={scaleFactor}
+                                            imageSize={sourceSize}
+                                            maskId={`spotlight-${id}`}
+                                        />
+                                    </svg>
+                                )}
+                                {isPlacingMarker && (
+                                    <div
+                                        className="absolute inset-0 cursor-crosshair bg-black/20 select-none"
+                                        onClick={handlePlaceMarker}
+                                    >
+                                        <div className="absolute top-2 right-2 bg-black/60 px-2 py-1 rounded text-[11px] text-white/85">
+                                            Click where the highlight should go
+                                        </div>
+                                    </div>
+                                )}
                                 <div className="absolute top-2 left-2 bg-black/55 px-2 py-1 rounded text-[11px] text-white/85">
                                     {new Date(step.timestamp).toLocaleTimeString()}
                                 </div>
+                                {isSelectingText && (
+                                    <div
+                                        className="absolute inset-0 cursor-crosshair bg-black/20 select-none"
+                                        onMouseDown={handleSelectionStart}
+                                        onMouseMove={handleSelectionMove}
+                                        onMouseUp={handleSelectionEnd}
+                                        onMouseLeave={() => setSelection(null)}
+                                    >
+                                        {selection ? (
+                                            <div
+                                                className="absolute border-2 border-[#49B8D3] bg-[#49B8D3]/15"
+                                                style={{
+                                                    left: Math.min(selection.startX, selection.endX),
+                                                    top: Math.min(selection.startY, selection.endY),
+                                                    width: Math.abs(selection.endX - selection.startX),
+                                                    height: Math.abs(selection.endY - selection.startY),
+                                                }}
+                                            />
+                                        ) : (
+                                            <div className="absolute top-2 right-2 bg-black/60 px-2 py-1 rounded text-[11px] text-white/85">
+                                                Drag over the text to extract
+                                            </div>
+                                        )}
+                                    </div>
+                                )}
                                 {step.is_cropped && (
                                     <div className="absolute bottom-2 left-2 bg-blue-600/85 px-2 py-1 rounded text-[11px]">
                                         Edited
                                     </div>
                                 )}
+                                {step.is_duplicate && (
+                                    <Tooltip content="Looked the same as the previous step, so its screenshot was reused">
+                                        <div className="absolute top-10 left-2 bg-amber-600/85 px-2 py-1 rounded text-[11px]">
+                                            Duplicate
+                                        </div>
+                                    </Tooltip>
+                                )}
                                 {step.clip_path && (
                                     <Tooltip content="Video clip captured for this step">
                                         <div className="absolute bottom-2 left-20 bg-purple-600/80 px-2 py-1 rounded text-[10px] font-medium">
@@ -269,6 +614,76 @@ const DraggableStepCard = memo(function DraggableStepCard({
 
                     {/* Image action buttons (under screenshot) */}
                     <div className="mt-3 flex items-center justify-end gap-2">
+                        {ocrError && (
+                            <span className="mr-auto text-xs text-red-400">{ocrError}</span>
+                        )}
+                        {hasScreenshot && (
+                            <Tooltip content="Select an area of the screenshot to add its text to the description">
+                                <button
+                                    onClick={() => {
+                                        setOcrError(null);
+                                        setIsPlacingMarker(false);
+                                        setIsSelectingText(!isSelectingText);
+                                    }}
+                                    disabled={isReadingText}
+                                    className={`inline-flex items-center gap-1.5 rounded-full border px-3 py-1.5 text-xs font-medium transition-colors disabled:opacity-50 ${
+                                        isSelectingText
+                                            ? "border-[#49B8D3] bg-[#49B8D3]/20 text-[#49B8D3]"
+                                            : "border-white/15 bg-white/5 text-white/70 hover:bg-white/10"
+                                    }`}
+                                >
+                                    {isReadingText ? <Spinner size="sm" /> : <ScanText size={13} />}
+                                    {isSelectingText ? "Cancel" : "Extract text"}
+                                </button>
+                            </Tooltip>
+                        )}
+                        {hasScreenshot && !showingAfter && onUpdateClickMarker && (
+                            <Tooltip content={step.click_marker ? "Move the click highlight" : "Add a click highlight"}>
+                                <button
+                                    onClick={() => {
+                                        setIsSelectingText(false);
+                                        setIsPlacingMarker(!isPlacingMarker);
+                                    }}
+                                    className={`inline-flex items-center gap-1.5 rounded-full border px-3 py-1.5 text-xs font-medium transition-colors ${
+                                        isPlacingMarker
+                                            ? "border-[#49B8D3] bg-[#49B8D3]/20 text-[#49B8D3]"
+                                            : "border-white/15 bg-white/5 text-white/70 hover:bg-white/10"
+                                    }`}
+                                >
+                                    <Crosshair size={13} />
+                                    {isPlacingMarker ? "Cancel" : step.click_marker ? "Move highlight" : "Add highlight"}
+                                </button>
+                            </Tooltip>
+                        )}
+                        {hasScreenshot && !showingAfter && onUpdateClickMarker && step.click_marker && (
+                            <Tooltip content="Remove the click highlight">
+                                <button
+                                    onClick={() => {
+                                        setIsPlacingMarker(false);
+                                        onUpdateClickMarker(null);
+                                    }}
+                                    aria-label="Remove click highlight"
+                                    className="inline-flex items-center rounded-full border border-white/15 bg-white/5 p-1.5 text-white/70 hover:bg-white/10 transition-colors"
+                                >
+                                    <X size={13} />
+                                </button>
+                            </Tooltip>
+                        )}
+                        {hasScreenshot && onEditImage && (
+                            <div className="inline-flex items-center gap-1">
+                                {IMAGE_EDIT_BUTTONS.map(({ edit, label, Icon }) => (
+                                    <Tooltip key={edit.kind} content={label}>
+                                        <button
+                                            onClick={() => onEditImage(edit, showingAfter ? "after" : "before")}
+                                            aria-label={label}
+                                            className="inline-flex items-center rounded-full border border-white/15 bg-white/5 p-1.5 text-white/70 hover:bg-white/10 transition-colors"
+                                        >
+                                            <Icon size={13} />
+                                        </button>
+                                    </Tooltip>
+                                ))}
+                            </div>
+                        )}
                         {hasScreenshot && onCrop && (
                             <Tooltip content={showingAfter ? "Edit after-frame" : "Edit screenshot"}>
                                 <button
@@ -0,0 +1,120 @@
+import { useState } from "react";
+import { invoke } from "@tauri-apps/api/core";
+import { X } from "lucide-react";
+
+import { useToastStore } from "../store/toastStore";
+
+interface EmailRecordingModalProps {
+    recordingId: string;
+    /** Default subject */
+    recordingName: string;
+    onClose: () => void;
+}
+
+const inputClass =
+    "w-full px-4 py-2 bg-[#161316]/70 border border-white/10 rounded-md text-white placeholder-white/50 focus:outline-none focus:border-[#2721E8]";
+
+export default function EmailRecordingModal({ recordingId, recordingName, onClose }: EmailRecordingModalProps) {
+    const [to, setTo] = useState("");
+    const [subject, setSubject] = useState(recordingName);
+    const [note, setNote] = useState("");
+    const [attachBundle, setAttachBundle] = useState(false);
+    const [sending, setSending] = useState(false);
+    const [error, setError] = useState<string | null>(null);
+
+    const handleSend = async () => {
+        setSending(true);
+        setError(null);
+        try {
+            const recipients = to.split(/[,;\s]+/).filter(Boolean);
+            await invoke("send_recording_email", {
+                recordingId,
+                to: recipients,
+                subject,
+                note,
+                attachBundle,
+            });
+            useToastStore.getState().showToast({
+                message: `Emailed to ${recipients.join(", ")}`,
+                variant: "success",
+            });
+            onClose();
+        } catch (sendError) {
+            setError(String(sendError));
+        } finally {
+            setSending(false);
+        }
+    };
+
+    return (
+        <div className="fixed inset-0 z-50 flex items-center justify-center bg-black/80 p-8">
+            <div className="glass-surface-1 w-full max-w-md space-y-4 rounded-xl p-6">
+                <div className="flex items-start justify-between">
+                    <div>
+                        <h3 className="text-lg font-medium text-white">Email Recording</h3>
+                        <p className="mt-1 text-xs text-white/50">
+                            Sent through the mail server in Settings, with the screenshots in the message.
+                        </p>
+                    </div>
+                    <button
+                        aria-label="Close"
+                        onClick={onClose}
+                        className="rounded-md p-1 text-white/60 transition-colors hover:bg-white/10 hover:text-white"
+                    >
+                        <X size={18} />
+                    </button>
+                </div>
+
+                <div>
+                    <label className="mb-2 block text-sm font-medium text-white/60">To</label>
+                    <input
+                        type="text"
+                        value={to}
+                        onChange={(e) => setTo(e.target.value)}
+                        placeholder="colleague@example.com, ..."
+                        className={inputClass}
+                        autoFocus
+                    />
+                </div>
+
+                <div>
+                    <label className="mb-2 block text-sm font-medium text-white/60">Subject</label>
+                    <input type="text" value={subject} onChange={(e) => setSubject(e.target.value)} className={inputClass} />
+                </div>
+
+                <div>
+                    <label className="mb-2 block text-sm font-medium text-white/60">Message</label>
+                    <textarea
+                        value={note}
+                        onChange={(e) => setNote(e.target.value)}
+                        placeholder="Optional, shown above the steps"
+                        rows={3}
+                        className={`${inputClass} resize-none`}
+                    />
+                </div>
+
+                <label className="flex items-center gap-3 text-sm text-white/80">
+                    <input
+                        type="checkbox"
+                        checked={attachBundle}
+                        onChange={(e) => setAttachBundle(e.target.checked)}
+                        className="accent-[#2721E8]"
+                    />
+                    Attach the recording so it can be imported
+                </label>
+
+                <button
+                    onClick={() => {
+                        void handleSend();
+                    }}
+                    disabled={sending || !to.trim()}
+                    className="w-full rounded-md bg-[#2721E8] px-4 py-2 text-sm text-white transition-colors hover:bg-[#2721E8]/90 disabled:opacity-50"
+                >
+                    {sending ? "Sending..." : "Send"}
+                </button>
+
+                {error && <p className="text-xs text-red-400">{error}</p>}
+            </div>
+        </div>
+    );
+}
@@ -1,17 +1,47 @@
 import { useState, useRef, useEffect } from "react";
-import { Download, FileText, FileCode, FileType } from "lucide-react";
+import { Download, FileText, FileCode, FileType, GitCommit, Link, Mail, MessageSquare, Package, Paperclip, Video } from "lucide-react";
+import { invoke } from "@tauri-apps/api/core";
+import { save } from "@tauri-apps/plugin-dialog";
 import Tooltip from "./Tooltip";
+import EmailRecordingModal from "./EmailRecordingModal";
+import AttachToTicketModal from "./AttachToTicketModal";
+import ShareLinkModal from "./ShareLinkModal";
+import PostToChatModal from "./PostToChatModal";
+import NarratedVideoModal from "./NarratedVideoModal";
+import { useToastStore } from "../store/toastStore";
+
+/** Mirrors `ExporterPluginInfo` in the backend. */
+interface ExporterPlugin {
+    id: string;
+    name: string;
+    extension: string;
+    description: string;
+}
 
 interface ExportDropdownProps {
     markdown: string;
     fileName: string;
+    /** Recording whose annotated screenshots replace the originals in exports */
+    recordingId?: string;
 }
 
-export default function ExportDropdown({ markdown, fileName }: ExportDropdownProps) {
+export default function ExportDropdown({ markdown, fileName, recordingId }: ExportDropdownProps) {
     const [isOpen, setIsOpen] = useState(false);
     const dropdownRef = useRef<HTMLDivElement>(null);
     const [isExporting, setIsExporting] = useState(false);
     const [exportingFormat, setExportingFormat] = useState<string | null>(null);
+    const [showEmail, setShowEmail] = useState(false);
+    const [showAttach, setShowAttach] = useState(false);
+    const [showShare, setShowShare] = useState(false);
+    const [showChat, setShowChat] = useState(false);
+    const [showVideo, setShowVideo] = useState(false);
+    const [plugins, setPlugins] = useState<ExporterPlugin[]>([]);
+
+    useEffect(() => {
+        invoke<ExporterPlugin[]>("list_exporter_plugins")
+            .then(setPlugins)
+            .catch((err) => console.error("Failed to list exporter plugins:", err));
+    }, []);
 
     useEffect(() => {
         function handleClickOutside(event: MouseEvent) {
@@ -23,11 +53,12 @@ export default function ExportDropdown({ markdown, fileName }: ExportDropdownPro
         return () => document.removeEventListener("mousedown", handleClickOutside);
     }, []);
 
-    const runExport = async (format: string, exporter: () => Promise<void>) => {
+    const runExport = async (format: string, exporter: (markdown: string) => Promise<void>) => {
         setIsExporting(true);
         setExportingFormat(format);
         try {
-            await exporter();
+            const { withAnnotatedScreenshots } = await import("../lib/export/utils");
+            await exporter(await withAnnotatedScreenshots(markdown, recordingId));
             setIsOpen(false);
         } catch (e) {
             console.error(`${format} export failed`, e);
@@ -37,32 +68,113 @@ export default function ExportDropdown({ markdown, fileName }: ExportDropdownPro
         }
     };
 
+    // Screenshots linked from the image host when hosted images are on;
+    // `null` when they're off
+    const hostImages = async (exportMarkdown: string) => {
+        const { withHostedImages } = await import("../lib/export/utils");
+        try {
+            return await withHostedImages(exportMarkdown);
+        } catch (e) {
+            useToastStore.getState().showToast({
+                title: "Uploading screenshots failed",
+                message: String(e),
+                variant: "error",
+                durationMs: 15000,
+            });
+            throw e;
+        }
+    };
+
     const handleExportMarkdown = async () => {
-        await runExport("Markdown", async () => {
+        await runExport("Markdown", async (exportMarkdown) => {
             const { exportToMarkdown } = await import("../lib/export/markdownExporter");
-            await exportToMarkdown(markdown, fileName);
+            const hosted = await hostImages(exportMarkdown);
+            await exportToMarkdown(hosted ?? exportMarkdown, fileName);
         });
     };
 
     const handleExportHtml = async () => {
-        await runExport("HTML", async () => {
+        await runExport("HTML", async (exportMarkdown) => {
             const { exportToHtml } = await import("../lib/export/htmlExporter");
-            await exportToHtml(markdown, fileName);
+            const hosted = await hostImages(exportMarkdown);
+            await exportToHtml(hosted ?? exportMarkdown, fileName, hosted !== null);
         });
     };
 
     const handleExportPdf = async () => {
-        await runExport("PDF", async () => {
+        await runExport("PDF", async (exportMarkdown) => {
             const { exportToPdf } = await import("../lib/export/pdfExporter");
-            await exportToPdf(markdown, fileName);
+            await exportToPdf(exportMarkdown, fileName);
         });
     };
 
     const handleExportWord = async () => {
-        await runExport("Word", async () => {
+        await runExport("Word", async (exportMarkdown) => {
             const { exportToWord } = await import("../lib/export/wordExporter");
-            await exportToWord(markdown, fileName);
+            await exportToWord(exportMarkdown, fileName);
+        });
+    };
+
+    // Written by the backend from the steps, not from `markdown`
+    const handleExportWithPlugin = async (plugin: ExporterPlugin) => {
+        if (!recordingId) {
+            return;
+        }
+        const outputPath = await save({
+            defaultPath: `${fileName}.${plugin.extension}`,
+            filters: [{ name: plugin.name, extensions: [plugin.extension] }],
         });
+        if (!outputPath) {
+            return;
+        }
+        setIsExporting(true);
+        setExportingFormat(plugin.name);
+        try {
+            await invoke("export_with_plugin", { pluginId: plugin.id, recordingId, outputPath });
+            useToastStore.getState().showToast({
+                message: `Saved ${plugin.name} export to ${outputPath}`,
+                variant: "success",
+            });
+            setIsOpen(false);
+        } catch (e) {
+            useToastStore.getState().showToast({
+                title: `${plugin.name} export failed`,
+                message: String(e),
+                variant: "error",
+                durationMs: 15000,
+            });
+        } finally {
+            setIsExporting(false);
+            setExportingFormat(null);
+        }
+    };
+
+    const handlePublishToGit = async () => {
+        if (!recordingId) {
+            return;
+        }
+        setIsExporting(true);
+        setExportingFormat("Git");
+        try {
+            const result = await invoke<{ commit: string | null; pushed: boolean }>("publish_recording_to_git", { recordingId });
+            useToastStore.getState().showToast({
+                message: result.commit
+                    ? `Committed ${result.commit.slice(0, 7)}${result.pushed ? " and pushed" : ""}`
+                    : "The repository is already up to date",
+                variant: "success",
+            });
+            setIsOpen(false);
+        } catch (e) {
+            useToastStore.getState().showToast({
+                title: "Publishing to Git failed",
+                message: String(e),
+                variant: "error",
+                durationMs: 15000,
+            });
+        } finally {
+            setIsExporting(false);
+            setExportingFormat(null);
+        }
     };
 
     return (
@@ -113,13 +225,130 @@ export default function ExportDropdown({ markdown, fileName }: ExportDropdownPro
                     <button
                         onClick={handleExportWord}
                         disabled={isExporting}
-                        className="w-full flex items-center gap-2 px-4 py-2 text-sm text-white/70 hover:bg-white/10 hover:text-white transition-colors text-left disabled:opacity-50 disabled:cursor-not-allowed rounded-b-xl"
+                        className="w-full flex items-center gap-2 px-4 py-2 text-sm text-white/70 hover:bg-white/10 hover:text-white transition-colors text-left disabled:opacity-50 disabled:cursor-not-allowed"
                     >
                         <FileText size={16} />
                         Export to Word
                     </button>
+                    {recordingId && (
+                        <button
+                            onClick={() => {
+                                setShowVideo(true);
+                                setIsOpen(false);
+                            }}
+                            disabled={isExporting}
+                            className="w-full flex items-center gap-2 px-4 py-2 text-sm text-white/70 hover:bg-white/10 hover:text-white transition-colors text-left disabled:opacity-50 disabled:cursor-not-allowed"
+                        >
+                            <Video size={16} />
+                            Narrated Video...
+                        </button>
+                    )}
+                    {recordingId &&
+                        plugins.map((plugin) => (
+                            <button
+                                key={plugin.id}
+                                onClick={() => {
+                                    void handleExportWithPlugin(plugin);
+                                }}
+                                disabled={isExporting}
+                                title={plugin.description || undefined}
+                                className="w-full flex items-center gap-2 px-4 py-2 text-sm text-white/70 hover:bg-white/10 hover:text-white transition-colors text-left disabled:opacity-50 disabled:cursor-not-allowed"
+                            >
+                                <Package size={16} />
+                                Export to {plugin.name}
+                            </button>
+                        ))}
+                    <button
+                        onClick={() => {
+                            setShowAttach(true);
+                            setIsOpen(false);
+                        }}
+                        disabled={isExporting}
+                        className={`w-full flex items-center gap-2 px-4 py-2 text-sm text-white/70 hover:bg-white/10 hover:text-white transition-colors text-left disabled:opacity-50 disabled:cursor-not-allowed ${recordingId ? "" : "rounded-b-xl"}`}
+                    >
+                        <Paperclip size={16} />
+                        Attach to Ticket...
+                    </button>
+                    {recordingId && (
+                        <button
+                            onClick={handlePublishToGit}
+                            disabled={isExporting}
+                            className="w-full flex items-center gap-2 px-4 py-2 text-sm text-white/70 hover:bg-white/10 hover:text-white transition-colors text-left disabled:opacity-50 disabled:cursor-not-allowed border-t border-white/10"
+                        >
+                            <GitCommit size={16} />
+                            Publish to Git
+                        </button>
+                    )}
+                    {recordingId && (
+                        <button
+                            onClick={() => {
+                                setShowEmail(true);
+                                setIsOpen(false);
+                            }}
+                            disabled={isExporting}
+                            className="w-full flex items-center gap-2 px-4 py-2 text-sm text-white/70 hover:bg-white/10 hover:text-white transition-colors text-left disabled:opacity-50 disabled:cursor-not-allowed"
+                        >
+                            <Mail size={16} />
+                            Email...
+                        </button>
+                    )}
+                    {recordingId && (
+                        <button
+                            onClick={() => {
+                                setShowChat(true);
+                                setIsOpen(false);
+                            }}
+                            disabled={isExporting}
+                            className="w-full flex items-center gap-2 px-4 py-2 text-sm text-white/70 hover:bg-white/10 hover:text-white transition-colors text-left disabled:opacity-50 disabled:cursor-not-allowed"
+                        >
+                            <MessageSquare size={16} />
+                            Post to Slack / Teams...
+                        </button>
+                    )}
+                    {recordingId && (
+                        <button
+                            onClick={() => {
+                                setShowShare(true);
+                                setIsOpen(false);
+                            }}
+                            disabled={isExporting}
+                            className="w-full flex items-center gap-2 px-4 py-2 text-sm text-white/70 hover:bg-white/10 hover:text-white transition-colors text-left disabled:opacity-50 disabled:cursor-not-allowed rounded-b-xl"
+                        >
+                            <Link size={16} />
+                            Share Link...
+                        </button>
+                    )}
                 </div>
             )}
+
+            {showAttach && (
+                <AttachToTicketModal
+                    markdown={markdown}
+                    fileName={fileName}
+                    recordingId={recordingId}
+                    onClose={() => setShowAttach(false)}
+                />
+            )}
+
+            {showEmail && recordingId && (
+                <EmailRecordingModal
+                    recordingId={recordingId}
+                    recordingName={fileName}
+                    onClose={() => setShowEmail(false)}
+                />
+            )}
+
+            {showVideo && recordingId && (
+                <NarratedVideoModal recordingId={recordingId} fileName={fileName} onClose={() => setShowVideo(false)} />
+            )}
+
+            {showChat && recordingId && (
+                <PostToChatModal recordingId={recordingId} onClose={() => setShowChat(false)} />
+            )}
+
+            {showShare && recordingId && (
+                <ShareLinkModal recordingId={recordingId} onClose={() => setShowShare(false)} />
+            )}
         </div>
     );
 }
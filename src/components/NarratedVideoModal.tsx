@@ -0,0 +1,125 @@
+import { useEffect, useState } from "react";
+import { invoke } from "@tauri-apps/api/core";
+import { listen } from "@tauri-apps/api/event";
+import { save } from "@tauri-apps/plugin-dialog";
+import { X } from "lucide-react";
+
+import { useToastStore } from "../store/toastStore";
+
+/** Mirrors `NarratedVideoProgress` in the backend. */
+interface NarratedVideoProgress {
+    recording_id: string;
+    index: number;
+    total: number;
+}
+
+interface NarratedVideoModalProps {
+    recordingId: string;
+    fileName: string;
+    onClose: () => void;
+}
+
+const inputClass =
+    "w-full px-4 py-2 bg-[#161316]/70 border border-white/10 rounded-md text-white placeholder-white/50 focus:outline-none focus:border-[#2721E8]";
+
+export default function NarratedVideoModal({ recordingId, fileName, onClose }: NarratedVideoModalProps) {
+    const [voices, setVoices] = useState<string[]>([]);
+    const [voice, setVoice] = useState("");
+    const [exporting, setExporting] = useState(false);
+    const [progress, setProgress] = useState<NarratedVideoProgress | null>(null);
+    const [error, setError] = useState<string | null>(null);
+
+    useEffect(() => {
+        invoke<string[]>("list_tts_voices")
+            .then(setVoices)
+            .catch((err) => setError(String(err)));
+        const unlisten = listen<NarratedVideoProgress>("narrated-video-progress", (event) => {
+            if (event.payload.recording_id === recordingId) {
+                setProgress(event.payload);
+            }
+        });
+        return () => {
+            unlisten.then((f) => f());
+        };
+    }, [recordingId]);
+
+    const handleExport = async () => {
+        const outputPath = await save({
+            defaultPath: `${fileName}.mp4`,
+            filters: [{ name: "MP4 video", extensions: ["mp4"] }],
+        });
+        if (!outputPath) {
+            return;
+        }
+        setExporting(true);
+        setError(null);
+        try {
+            await invoke("export_narrated_video", {
+                recordingId,
+                outputPath,
+                options: { voice: voice || null },
+            });
+            useToastStore.getState().showToast({
+                message: `Saved the video to ${outputPath}`,
+                variant: "success",
+            });
+            onClose();
+        } catch (exportError) {
+            setError(String(exportError));
+        } finally {
+            setExporting(false);
+            setProgress(null);
+        }
+    };
+
+    return (
+        <div className="fixed inset-0 z-50 flex items-center justify-center bg-black/80 p-8">
+            <div className="glass-surface-1 w-full max-w-md space-y-4 rounded-xl p-6">
+                <div className="flex items-start justify-between">
+                    <div>
+                        <h3 className="text-lg font-medium text-white">Narrated Video</h3>
+                        <p className="mt-1 text-xs text-white/50">
+                            Shows each screenshot while a system voice reads its description. Needs FFmpeg on the PATH.
+                        </p>
+                    </div>
+                    <button
+                        aria-label="Close"
+                        onClick={onClose}
+                        disabled={exporting}
+                        className="rounded-md p-1 text-white/60 transition-colors hover:bg-white/10 hover:text-white disabled:opacity-50"
+                    >
+                        <X size={18} />
+                    </button>
+                </div>
+
+                <div>
+                    <label className="mb-2 block text-sm font-medium text-white/60">Voice</label>
+                    <select value={voice} onChange={(e) => setVoice(e.target.value)} className={inputClass}>
+                        <option value="">System default</option>
+                        {voices.map((name) => (
+                            <option key={name} value={name}>
+                                {name}
+                            </option>
+                        ))}
+                    </select>
+                </div>
+
+                <button
+                    onClick={() => {
+                        void handleExport();
+                    }}
+                    disabled={exporting}
+                    className="w-full rounded-md bg-[#2721E8] px-4 py-2 text-sm text-white transition-colors hover:bg-[#2721E8]/90 disabled:opacity-50"
+                >
+                    {exporting
+                        ? progress
+                            ? `Rendering slide ${progress.index + 1} of ${progress.total}...`
+                            : "Exporting..."
+                        : "Export MP4"}
+                </button>
+
+                {error && <p className="text-xs text-red-400">{error}</p>}
+            </div>
+        </div>
+    );
+}
@@ -0,0 +1,85 @@
+import { ShieldAlert, X } from 'lucide-react';
+import { usePermissionsStore, type PermissionKind } from '../store/permissionsStore';
+
+const PERMISSIONS: { kind: PermissionKind; label: string; effect: string }[] = [
+    {
+        kind: 'screen_recording',
+        label: 'Screen Recording',
+        effect: 'Screenshots will be blank.',
+    },
+    {
+        kind: 'accessibility',
+        label: 'Accessibility',
+        effect: 'Steps will not name the clicked buttons and fields.',
+    },
+];
+
+export default function PermissionNotice() {
+    const { status, environment, dismissed, request, openSettings, dismiss } = usePermissionsStore();
+
+    const missing = status ? PERMISSIONS.filter(({ kind }) => !status[kind]) : [];
+    // Failed checks the permission rows above don't already explain
+    const problems = (environment?.checks ?? []).filter((check) =>
+        check.status === 'failed'
+        && !missing.some(({ kind }) => (kind === 'screen_recording' ? 'screen_capture' : kind) === check.id)
+    );
+    if ((missing.length === 0 && problems.length === 0) || dismissed) {
+        return null;
+    }
+
+    return (
+        <div className="fixed bottom-6 right-6 z-[9998] w-80">
+            <div
+                className="glass-surface-2 rounded-xl shadow-xl border border-white/10 text-white overflow-hidden"
+                style={{ borderLeft: '4px solid #F5A524' }}
+            >
+                <div className="p-4">
+                    <div className="flex items-start justify-between gap-2 mb-3">
+                        <div className="flex items-center gap-2">
+                            <ShieldAlert size={18} className="text-[#F5A524]" />
+                            <span className="font-medium">
+                                {missing.length > 0 ? 'Permissions needed' : 'Recording may not work'}
+                            </span>
+                        </div>
+                        <button
+                            onClick={dismiss}
+                            className="p-1 text-white/60 hover:text-white hover:bg-white/10 rounded-md transition-colors"
+                            aria-label="Dismiss"
+                        >
+                            <X size={14} />
+                        </button>
+                    </div>
+
+                    {missing.map(({ kind, label, effect }) => (
+                        <div key={kind} className="mb-3 last:mb-0">
+                            <p className="text-sm text-white/80">
+                                <span className="font-medium">{label}</span> is not granted. {effect}
+                            </p>
+                            <div className="flex gap-2 mt-2">
+                                <button
+                                    onClick={() => request(kind)}
+                                    className="px-3 py-1 text-xs rounded-md bg-white/10 hover:bg-white/20 transition-colors"
+                                >
+                                    Request
+                                </button>
+                                <button
+                                    onClick={() => openSettings(kind)}
+                                    className="px-3 py-1 text-xs rounded-md text-[#49B8D3] hover:bg-white/10 transition-colors"
+                                >
+                                    Open System Settings
+                                </button>
+                            </div>
+                        </div>
+                    ))}
+
+                    {problems.map((check) => (
+                        <div key={check.id} className="mb-3 last:mb-0">
+                            <p className="text-sm text-white/80">{check.detail}</p>
+                            {check.fix && <p className="text-xs text-white/60 mt-1">{check.fix}</p>}
+                        </div>
+                    ))}
+                </div>
+            </div>
+        </div>
+    );
+}
@@ -0,0 +1,110 @@
+import { useState } from "react";
+import { invoke } from "@tauri-apps/api/core";
+import { X } from "lucide-react";
+
+import { useToastStore } from "../store/toastStore";
+
+interface PostToChatModalProps {
+    recordingId: string;
+    onClose: () => void;
+}
+
+type Platform = "slack" | "teams";
+
+const inputClass =
+    "w-full px-4 py-2 bg-[#161316]/70 border border-white/10 rounded-md text-white placeholder-white/50 focus:outline-none focus:border-[#2721E8]";
+
+const PLATFORM_LABELS: Record<Platform, string> = { slack: "Slack", teams: "Microsoft Teams" };
+
+export default function PostToChatModal({ recordingId, onClose }: PostToChatModalProps) {
+    const [platform, setPlatform] = useState<Platform>("slack");
+    const [note, setNote] = useState("");
+    const [includeLink, setIncludeLink] = useState(true);
+    const [posting, setPosting] = useState(false);
+    const [error, setError] = useState<string | null>(null);
+
+    const handlePost = async () => {
+        setPosting(true);
+        setError(null);
+        try {
+            await invoke("post_recording_to_chat", { platform, recordingId, note, includeLink });
+            useToastStore.getState().showToast({
+                message: `Posted to ${PLATFORM_LABELS[platform]}`,
+                variant: "success",
+            });
+            onClose();
+        } catch (postError) {
+            setError(String(postError));
+        } finally {
+            setPosting(false);
+        }
+    };
+
+    return (
+        <div className="fixed inset-0 z-50 flex items-center justify-center bg-black/80 p-8">
+            <div className="glass-surface-1 w-full max-w-md space-y-4 rounded-xl p-6">
+                <div className="flex items-start justify-between">
+                    <div>
+                        <h3 className="text-lg font-medium text-white">Post to Chat</h3>
+                        <p className="mt-1 text-xs text-white/50">
+                            Posts the title, step count and first screenshot to the channel set up in Settings.
+                        </p>
+                    </div>
+                    <button
+                        aria-label="Close"
+                        onClick={onClose}
+                        className="rounded-md p-1 text-white/60 transition-colors hover:bg-white/10 hover:text-white"
+                    >
+                        <X size={18} />
+                    </button>
+                </div>
+
+                <div>
+                    <label className="mb-2 block text-sm font-medium text-white/60">Post to</label>
+                    <select
+                        value={platform}
+                        onChange={(e) => setPlatform(e.target.value as Platform)}
+                        className={inputClass}
+                    >
+                        <option value="slack">{PLATFORM_LABELS.slack}</option>
+                        <option value="teams">{PLATFORM_LABELS.teams}</option>
+                    </select>
+                </div>
+
+                <div>
+                    <label className="mb-2 block text-sm font-medium text-white/60">Message</label>
+                    <textarea
+                        value={note}
+                        onChange={(e) => setNote(e.target.value)}
+                        placeholder="Optional"
+                        rows={3}
+                        className={`${inputClass} resize-none`}
+                        autoFocus
+                    />
+                </div>
+
+                <label className="flex items-center gap-3 text-sm text-white/80">
+                    <input
+                        type="checkbox"
+                        checked={includeLink}
+                        onChange={(e) => setIncludeLink(e.target.checked)}
+                        className="accent-[#2721E8]"
+                    />
+                    Include a share link to the full guide
+                </label>
+
+                <button
+                    onClick={() => {
+                        void handlePost();
+                    }}
+                    disabled={posting}
+                    className="w-full rounded-md bg-[#2721E8] px-4 py-2 text-sm text-white transition-colors hover:bg-[#2721E8]/90 disabled:opacity-50"
+                >
+                    {posting ? "Posting..." : "Post"}
+                </button>
+
+                {error && <p className="text-xs text-red-400">{error}</p>}
+            </div>
+        </div>
+    );
+}
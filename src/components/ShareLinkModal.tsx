@@ -0,0 +1,200 @@
+import { useCallback, useEffect, useState } from "react";
+import { invoke } from "@tauri-apps/api/core";
+import { Copy, Trash2, X } from "lucide-react";
+
+import { useToastStore } from "../store/toastStore";
+
+interface ShareLinkModalProps {
+    recordingId: string;
+    onClose: () => void;
+}
+
+/** Mirrors `ShareLink` in the backend. */
+interface ShareLink {
+    token: string;
+    recording_id: string;
+    url: string;
+    created_at: number;
+    expires_at: number;
+    hosted: boolean;
+    has_password: boolean;
+}
+
+const inputClass =
+    "w-full px-4 py-2 bg-[#161316]/70 border border-white/10 rounded-md text-white placeholder-white/50 focus:outline-none focus:border-[#2721E8]";
+
+const EXPIRY_OPTIONS = [
+    { label: "1 hour", hours: 1 },
+    { label: "1 day", hours: 24 },
+    { label: "1 week", hours: 24 * 7 },
+    { label: "30 days", hours: 24 * 30 },
+];
+
+const copyLink = (url: string) => {
+    void navigator.clipboard.writeText(url);
+    useToastStore.getState().showToast({ message: "Link copied", variant: "success" });
+};
+
+export default function ShareLinkModal({ recordingId, onClose }: ShareLinkModalProps) {
+    const [hours, setHours] = useState(24 * 7);
+    const [password, setPassword] = useState("");
+    const [upload, setUpload] = useState(false);
+    const [links, setLinks] = useState<ShareLink[]>([]);
+    const [creating, setCreating] = useState(false);
+    const [error, setError] = useState<string | null>(null);
+
+    const refreshLinks = useCallback(() => {
+        invoke<ShareLink[]>("list_share_links", { recordingId })
+            .then(setLinks)
+            .catch((err) => console.error("Failed to list share links:", err));
+    }, [recordingId]);
+
+    useEffect(() => {
+        invoke<{ share: { default_expiry_hours: number } }>("get_settings")
+            .then((settings) => setHours(settings.share.default_expiry_hours))
+            .catch((err) => console.error("Failed to read share settings:", err));
+        refreshLinks();
+    }, [refreshLinks]);
+
+    const handleCreate = async () => {
+        setCreating(true);
+        setError(null);
+        try {
+            const link = await invoke<ShareLink>("create_share_link", {
+                recordingId,
+                expiresInHours: hours,
+                password: upload ? null : password,
+                upload,
+            });
+            copyLink(link.url);
+            setPassword("");
+        } catch (createError) {
+            setError(String(createError));
+        } finally {
+            setCreating(false);
+            refreshLinks();
+        }
+    };
+
+    const handleRevoke = async (token: string) => {
+        try {
+            await invoke("revoke_share_link", { token });
+            setError(null);
+        } catch (revokeError) {
+            setError(String(revokeError));
+        } finally {
+            refreshLinks();
+        }
+    };
+
+    const expiryOptions = EXPIRY_OPTIONS.some((option) => option.hours === hours)
+        ? EXPIRY_OPTIONS
+        : [...EXPIRY_OPTIONS, { label: `${hours} hours`, hours }];
+
+    return (
+        <div className="fixed inset-0 z-50 flex items-center justify-center bg-black/80 p-8">
+            <div className="glass-surface-1 w-full max-w-md space-y-4 rounded-xl p-6">
+                <div className="flex items-start justify-between">
+                    <div>
+                        <h3 className="text-lg font-medium text-white">Share Link</h3>
+                        <p className="mt-1 text-xs text-white/50">
+                            Anyone with the link can read this recording in a browser, as it is now.
+                        </p>
+                    </div>
+                    <button
+                        aria-label="Close"
+                        onClick={onClose}
+                        className="rounded-md p-1 text-white/60 transition-colors hover:bg-white/10 hover:text-white"
+                    >
+                        <X size={18} />
+                    </button>
+                </div>
+
+                <div className="flex gap-2">
+                    <div className="flex-1">
+                        <label className="mb-2 block text-sm font-medium text-white/60">Expires after</label>
+                        <select value={hours} onChange={(e) => setHours(Number(e.target.value))} className={inputClass}>
+                            {expiryOptions.map((option) => (
+                                <option key={option.hours} value={option.hours}>
+                                    {option.label}
+                                </option>
+                            ))}
+                        </select>
+                    </div>
+                    <div className="flex-1">
+                        <label className="mb-2 block text-sm font-medium text-white/60">Password</label>
+                        <input
+                            type="password"
+                            value={password}
+                            onChange={(e) => setPassword(e.target.value)}
+                            placeholder={upload ? "Not for uploads" : "Optional"}
+                            disabled={upload}
+                            className={`${inputClass} disabled:opacity-50`}
+                        />
+                    </div>
+                </div>
+
+                <label className="flex items-center gap-3 text-sm text-white/80">
+                    <input
+                        type="checkbox"
+                        checked={upload}
+                        onChange={(e) => setUpload(e.target.checked)}
+                        className="accent-[#2721E8]"
+                    />
+                    Upload to the static host, so it works while this app is closed
+                </label>
+
+                <button
+                    onClick={() => {
+                        void handleCreate();
+                    }}
+                    disabled={creating}
+                    className="w-full rounded-md bg-[#2721E8] px-4 py-2 text-sm text-white transition-colors hover:bg-[#2721E8]/90 disabled:opacity-50"
+                >
+                    {creating ? "Creating..." : "Create and Copy Link"}
+                </button>
+
+                {error && <p className="text-xs text-red-400">{error}</p>}
+
+                {links.length > 0 && (
+                    <div className="space-y-2">
+                        <label className="block text-sm font-medium text-white/60">Active links</label>
+                        {links.map((link) => (
+                            <div
+                                key={link.token}
+                                className="flex items-center justify-between gap-3 rounded-md bg-white/5 px-3 py-2"
+                            >
+                                <div className="min-w-0">
+                                    <p className="truncate text-xs text-white/80">{link.url}</p>
+                                    <p className="text-xs text-white/50">
+                                        {link.hosted ? "Uploaded" : "Served by this app"}
+                                        {link.has_password && ", password protected"}, expires{" "}
+                                        {new Date(link.expires_at).toLocaleString()}
+                                    </p>
+                                </div>
+                                <div className="flex flex-shrink-0 gap-1">
+                                    <button
+                                        aria-label="Copy link"
+                                        onClick={() => copyLink(link.url)}
+                                        className="rounded-md p-1 text-white/60 hover:bg-white/10 hover:text-white"
+                                    >
+                                        <Copy size={14} />
+                                    </button>
+                                    <button
+                                        aria-label="Revoke link"
+                                        onClick={() => {
+                                            void handleRevoke(link.token);
+                                        }}
+                                        className="rounded-md p-1 text-white/60 hover:bg-white/10 hover:text-red-400"
+                                    >
+                                        <Trash2 size={14} />
+                                    </button>
+                                </div>
+                            </div>
+                        ))}
+                    </div>
+                )}
+            </div>
+        </div>
+    );
+}
@@ -1,6 +1,6 @@
 import { useEffect, useState } from "react";
 import { FileText } from "lucide-react";
-import { resolveDisplayImageSrc } from "../../lib/localAssets";
+import { resolveScreenshotSrc } from "../../lib/localAssets";
 
 interface RecordingThumbnailProps {
     /** Recording id — used to seed the gradient fallback so empty rows stay stable. */
@@ -18,6 +18,9 @@ const FALLBACK_GRADIENTS = [
     "from-[#2721E8]/35 via-[#FF6B35]/15 to-[#49B8D3]/25",
 ];
 
+/** Wide enough for the largest tile the thumbnail is shown in. */
+const THUMBNAIL_WIDTH = 320;
+
 function hashIndex(id: string, mod: number): number {
     let h = 0;
     for (let i = 0; i < id.length; i++) {
@@ -39,7 +42,7 @@ export default function RecordingThumbnail({
         setFailed(false);
         setSrc(null);
         if (!screenshotPath) return;
-        resolveDisplayImageSrc(screenshotPath)
+        resolveScreenshotSrc(screenshotPath, THUMBNAIL_WIDTH)
             .then((resolved) => {
                 if (!cancelled) setSrc(resolved || null);
             })
@@ -326,7 +326,7 @@ export default function AiProviderSection() {
                         <p className="mt-1 text-xs text-red-500">{apiKeyError}</p>
                     )}
                     <p className="mt-1 text-xs text-white/50">
-                        Your API key is stored securely on your device
+                        Your API key is stored in your system keychain
                     </p>
                 </div>
             )}
@@ -0,0 +1,119 @@
+import { useEffect, useState } from "react";
+import { invoke } from "@tauri-apps/api/core";
+
+import {
+    CREDENTIAL_SLACK_BOT_TOKEN,
+    CREDENTIAL_SLACK_WEBHOOK,
+    CREDENTIAL_TEAMS_WEBHOOK,
+    setCredential,
+} from "../../lib/credentials";
+
+/** Mirrors `ChatSettings` in the backend. */
+interface ChatConfig {
+    slack_channel_id: string;
+}
+
+const inputClass =
+    "w-full px-4 py-2 bg-[#161316]/70 border border-white/10 rounded-md text-white placeholder-white/50 focus:outline-none focus:border-[#2721E8]";
+
+/** Webhooks and the Slack bot recordings are posted to chat with. */
+export default function ChatSettings() {
+    const [chat, setChat] = useState<ChatConfig | null>(null);
+    const [slackWebhook, setSlackWebhook] = useState("");
+    const [slackToken, setSlackToken] = useState("");
+    const [teamsWebhook, setTeamsWebhook] = useState("");
+    const [error, setError] = useState<string | null>(null);
+    const [saved, setSaved] = useState(false);
+
+    useEffect(() => {
+        invoke<{ chat: ChatConfig }>("get_settings")
+            .then((settings) => setChat(settings.chat))
+            .catch((err) => console.error("Failed to read chat settings:", err));
+    }, []);
+
+    if (!chat) {
+        return null;
+    }
+
+    const handleSave = async () => {
+        try {
+            await invoke("update_settings", { patch: { chat } });
+            const secrets: [string, string, (value: string) => void][] = [
+                [CREDENTIAL_SLACK_WEBHOOK, slackWebhook, setSlackWebhook],
+                [CREDENTIAL_SLACK_BOT_TOKEN, slackToken, setSlackToken],
+                [CREDENTIAL_TEAMS_WEBHOOK, teamsWebhook, setTeamsWebhook],
+            ];
+            for (const [name, secret, clear] of secrets) {
+                if (secret.trim()) {
+                    await setCredential(name, secret.trim());
+                    clear("");
+                }
+            }
+            setError(null);
+            setSaved(true);
+        } catch (err) {
+            setError(String(err));
+        }
+    };
+
+    const secretInput = (value: string, onChange: (value: string) => void, placeholder: string) => (
+        <input
+            type="password"
+            value={value}
+            onChange={(e) => {
+                onChange(e.target.value);
+                setSaved(false);
+            }}
+            placeholder={placeholder}
+            className={inputClass}
+        />
+    );
+
+    return (
+        <div className="border-t border-white/8 pt-6 space-y-4">
+            <div>
+                <label className="block text-sm font-medium text-white/80">Slack & Teams</label>
+                <p className="text-xs text-white/50 mt-1">
+                    Where "Post to Slack / Teams" sends a recording's summary. URLs and tokens are stored in your system keychain.
+                </p>
+            </div>
+
+            <div className="space-y-3">
+                <label className="block text-sm font-medium text-white/60">Slack</label>
+                {secretInput(slackWebhook, setSlackWebhook, "Incoming webhook URL (unchanged)")}
+                <div className="flex gap-2">
+                    {secretInput(slackToken, setSlackToken, "Bot token (unchanged)")}
+                    <input
+                        type="text"
+                        value={chat.slack_channel_id}
+                        onChange={(e) => {
+                            setChat({ slack_channel_id: e.target.value });
+                            setSaved(false);
+                        }}
+                        placeholder="Channel ID"
+                        className={inputClass}
+                    />
+                </div>
+                <p className="text-xs text-white/50">
+                    With a bot token (chat:write and files:write) and channel, posts include the first screenshot.
+                </p>
+            </div>
+
+            <div className="space-y-3">
+                <label className="block text-sm font-medium text-white/60">Microsoft Teams</label>
+                {secretInput(teamsWebhook, setTeamsWebhook, "Channel webhook URL (unchanged)")}
+            </div>
+
+            <div className="flex items-center gap-3">
+                <button
+                    onClick={handleSave}
+                    className="px-4 py-2 rounded-md text-sm bg-[#2721E8] text-white hover:bg-[#2721E8]/90 transition-colors"
+                >
+                    Save
+                </button>
+                {saved && <span className="text-xs text-white/60">Saved</span>}
+                {error && <span className="text-xs text-red-400">{error}</span>}
+            </div>
+        </div>
+    );
+}
@@ -0,0 +1,171 @@
+import { useEffect, useState } from "react";
+import { invoke } from "@tauri-apps/api/core";
+
+import { CREDENTIAL_SMTP_PASSWORD, setCredential } from "../../lib/credentials";
+
+/** Mirrors `EmailSettings` in the backend. */
+interface EmailConfig {
+    host: string;
+    port: number;
+    security: "starttls" | "tls" | "none";
+    username: string;
+    from_address: string;
+    from_name: string;
+}
+
+const inputClass =
+    "w-full px-4 py-2 bg-[#161316]/70 border border-white/10 rounded-md text-white placeholder-white/50 focus:outline-none focus:border-[#2721E8]";
+
+const SECURITY_PORTS: Record<EmailConfig["security"], number> = { starttls: 587, tls: 465, none: 25 };
+
+/** The SMTP server "Email" sends recordings through. */
+export default function EmailSettings() {
+    const [email, setEmail] = useState<EmailConfig | null>(null);
+    const [password, setPassword] = useState("");
+    const [error, setError] = useState<string | null>(null);
+    const [saved, setSaved] = useState(false);
+
+    useEffect(() => {
+        invoke<{ email: EmailConfig }>("get_settings")
+            .then((settings) => setEmail(settings.email))
+            .catch((err) => console.error("Failed to read email settings:", err));
+    }, []);
+
+    if (!email) {
+        return null;
+    }
+
+    const edit = (changes: Partial<EmailConfig>) => {
+        setEmail({ ...email, ...changes });
+        setSaved(false);
+    };
+
+    const handleSecurity = (security: EmailConfig["security"]) => {
+        // Follow the usual port unless a custom one was set
+        const usualPort = Object.values(SECURITY_PORTS).includes(email.port);
+        edit({ security, port: usualPort ? SECURITY_PORTS[security] : email.port });
+    };
+
+    const handleSave = async () => {
+        try {
+            await invoke("update_settings", { patch: { email } });
+            if (password) {
+                await setCredential(CREDENTIAL_SMTP_PASSWORD, password);
+                setPassword("");
+            }
+            setError(null);
+            setSaved(true);
+        } catch (err) {
+            setError(String(err));
+        }
+    };
+
+    return (
+        <div className="border-t border-white/8 pt-6 space-y-4">
+            <div>
+                <label className="block text-sm font-medium text-white/80">Email</label>
+                <p className="text-xs text-white/50 mt-1">
+                    "Email" sends a recording as a formatted message with its screenshots through your mail server.
+                </p>
+            </div>
+
+            <div className="flex gap-2">
+                <div className="flex-1">
+                    <label className="block text-sm font-medium text-white/60 mb-2">SMTP Server</label>
+                    <input
+                        type="text"
+                        value={email.host}
+                        onChange={(e) => edit({ host: e.target.value })}
+                        placeholder="smtp.example.com"
+                        className={inputClass}
+                    />
+                </div>
+                <div className="w-24">
+                    <label className="block text-sm font-medium text-white/60 mb-2">Port</label>
+                    <input
+                        type="number"
+                        min="1"
+                        max="65535"
+                        value={email.port}
+                        onChange={(e) => edit({ port: parseInt(e.target.value) || 0 })}
+                        className={inputClass}
+                    />
+                </div>
+            </div>
+
+            <div>
+                <label className="block text-sm font-medium text-white/60 mb-2">Security</label>
+                <select
+                    value={email.security}
+                    onChange={(e) => handleSecurity(e.target.value as EmailConfig["security"])}
+                    className={inputClass}
+                >
+                    <option value="starttls">STARTTLS</option>
+                    <option value="tls">TLS</option>
+                    <option value="none">None (local relays only)</option>
+                </select>
+            </div>
+
+            <div className="flex gap-2">
+                <div className="flex-1">
+                    <label className="block text-sm font-medium text-white/60 mb-2">Username</label>
+                    <input
+                        type="text"
+                        value={email.username}
+                        onChange={(e) => edit({ username: e.target.value })}
+                        placeholder="None"
+                        className={inputClass}
+                    />
+                </div>
+                <div className="flex-1">
+                    <label className="block text-sm font-medium text-white/60 mb-2">Password</label>
+                    <input
+                        type="password"
+                        value={password}
+                        onChange={(e) => {
+                            setPassword(e.target.value);
+                            setSaved(false);
+                        }}
+                        placeholder="Unchanged"
+                        className={inputClass}
+                    />
+                </div>
+            </div>
+            <p className="-mt-2 text-xs text-white/50">The password is stored in your system keychain.</p>
+
+            <div className="flex gap-2">
+                <div className="flex-1">
+                    <label className="block text-sm font-medium text-white/60 mb-2">From Address</label>
+                    <input
+                        type="email"
+                        value={email.from_address}
+                        onChange={(e) => edit({ from_address: e.target.value })}
+                        placeholder="you@example.com"
+                        className={inputClass}
+                    />
+                </div>
+                <div className="flex-1">
+                    <label className="block text-sm font-medium text-white/60 mb-2">From Name</label>
+                    <input
+                        type="text"
+                        value={email.from_name}
+                        onChange={(e) => edit({ from_name: e.target.value })}
+                        placeholder="Optional"
+                        className={inputClass}
+                    />
+                </div>
+            </div>
+
+            <div className="flex items-center gap-3">
+                <button
+                    onClick={handleSave}
+                    className="px-4 py-2 rounded-md text-sm bg-[#2721E8] text-white hover:bg-[#2721E8]/90 transition-colors"
+                >
+                    Save
+                </button>
+                {saved && <span className="text-xs text-white/60">Saved</span>}
+                {error && <span className="text-xs text-red-400">{error}</span>}
+            </div>
+        </div>
+    );
+}
@@ -1,21 +1,307 @@
-import { useState } from "react";
-import { FolderOpen, RotateCcw, FileText } from "lucide-react";
-import { open } from "@tauri-apps/plugin-dialog";
+import { useEffect, useState } from "react";
+import { FolderOpen, RotateCcw, FileText, LifeBuoy } from "lucide-react";
+import { open, save } from "@tauri-apps/plugin-dialog";
 import { invoke } from "@tauri-apps/api/core";
-import { useSettingsStore } from "../../store/settingsStore";
+import { listen } from "@tauri-apps/api/event";
+import {
+    CLICK_HIGHLIGHT_PRESETS,
+    useSettingsStore,
+    type BadgePlacement,
+    type ClickHighlightPreset,
+    type DuplicateFrames,
+    type HighlightShape,
+    type InsetPlacement,
+    type OcrBenchmark,
+    type OcrProvider,
+    type SmartCropMode,
+    type WatermarkKind,
+    type WatermarkPosition,
+} from "../../store/settingsStore";
 import Tooltip from "../Tooltip";
 
+interface OcrLanguage {
+    id: string;
+    label: string;
+    installed: boolean;
+    downloadable: boolean;
+}
+
+const BADGE_PLACEMENT_LABELS: Record<BadgePlacement, string> = {
+    click: "By the click",
+    top_left: "Top left",
+    top_right: "Top right",
+};
+
+type LogLevel = "error" | "warn" | "info" | "debug";
+
+const LOG_LEVELS: { value: LogLevel; label: string }[] = [
+    { value: "error", label: "Errors" },
+    { value: "warn", label: "Warnings" },
+    { value: "info", label: "Normal" },
+    { value: "debug", label: "Debug" },
+];
+
+/** Mirrors `CaptureBackendKind` in the backend. */
+type CaptureBackend = "standard" | "native";
+
+const CAPTURE_BACKEND_LABELS: Record<CaptureBackend, string> = {
+    standard: "Standard",
+    native: "Native (faster)",
+};
+
+interface CaptureBenchmark {
+    backend: CaptureBackend;
+    runs: number;
+    monitors: {
+        index: number;
+        name: string;
+        width: number;
+        height: number;
+        times: {
+            capture_ms: number;
+            annotate_ms: number;
+            encode_ms: number;
+            write_ms: number;
+            total_ms: number;
+        } | null;
+        jpeg_bytes: number;
+        error: string | null;
+    }[];
+}
+
+const SMART_CROP_LABELS: Record<SmartCropMode, string> = {
+    off: "Off",
+    capture: "While recording",
+    export: "At export",
+};
+
+const DUPLICATE_FRAME_LABELS: Record<DuplicateFrames, string> = {
+    off: "Keep all",
+    flag: "Reuse & mark",
+    skip: "Skip clicks",
+};
+
+const INSET_PLACEMENT_LABELS: Record<InsetPlacement, string> = {
+    auto: "Away from click",
+    top_left: "Top left",
+    top_right: "Top right",
+    bottom_left: "Bottom left",
+    bottom_right: "Bottom right",
+};
+
+const WATERMARK_KIND_LABELS: Record<WatermarkKind, string> = {
+    text: "Text",
+    image: "Image",
+};
+
+const WATERMARK_POSITION_LABELS: Record<WatermarkPosition, string> = {
+    center: "Centre",
+    tiled: "Tiled",
+    top_left: "Top left",
+    top_right: "Top right",
+    bottom_left: "Bottom left",
+    bottom_right: "Bottom right",
+};
+
+const HIGHLIGHT_SHAPE_LABELS: Record<HighlightShape, string> = {
+    ring: "Ring",
+    arrow: "Arrow",
+    spotlight: "Spotlight",
+};
+
+const OCR_PROVIDER_LABELS: Record<OcrProvider, string> = {
+    auto: "Auto",
+    onnx: "ONNX (CPU)",
+    windows: "Windows OCR",
+    vision: "Apple Vision",
+};
+
+interface OcrModelProgress {
+    language: string;
+    file: string;
+    downloaded: number;
+    total: number | null;
+}
+
 export default function GeneralSection() {
     const {
         screenshotPath,
         sendScreenshotsToAi,
+        keepUnredactedOriginals,
+        stepBadgeStyle,
+        smartCrop,
+        zoomInset,
+        watermark,
+        duplicateFrames,
+        clickHighlightStyle,
+        clickHighlightPresets,
+        ocrLanguage,
+        ocrParallelism,
+        ocrProvider,
+        ocrParallelModels,
         setScreenshotPath,
         setSendScreenshotsToAi,
+        setKeepUnredactedOriginals,
+        setStepBadgeStyle,
+        setSmartCrop,
+        setZoomInset,
+        setWatermark,
+        setDuplicateFrames,
+        setClickHighlightStyle,
+        saveClickHighlightPreset,
+        deleteClickHighlightPreset,
+        setOcrLanguage,
+        setOcrParallelism,
+        setOcrProvider,
+        setOcrParallelModels,
+        benchmarkOcrProviders,
         getDefaultScreenshotPath,
     } = useSettingsStore();
 
     const [pathError, setPathError] = useState<string | null>(null);
     const [validatingPath, setValidatingPath] = useState(false);
+    const [ocrLanguages, setOcrLanguages] = useState<OcrLanguage[]>([]);
+    const [modelProgress, setModelProgress] = useState<OcrModelProgress | null>(null);
+    const [modelError, setModelError] = useState<string | null>(null);
+    const [updatingModels, setUpdatingModels] = useState(false);
+    const [ocrProviders, setOcrProviders] = useState<OcrProvider[]>(["onnx"]);
+    const [benchmark, setBenchmark] = useState<OcrBenchmark | null>(null);
+    const [benchmarking, setBenchmarking] = useState(false);
+    const [presetName, setPresetName] = useState("");
+    const [logLevel, setLogLevel] = useState<LogLevel>("info");
+    const [captureBackend, setCaptureBackend] = useState<CaptureBackend>("standard");
+    const [captureBenchmark, setCaptureBenchmark] = useState<CaptureBenchmark | null>(null);
+    const [captureBenchmarkError, setCaptureBenchmarkError] = useState<string | null>(null);
+    const [benchmarkingCapture, setBenchmarkingCapture] = useState(false);
+    const [diagnosticsMessage, setDiagnosticsMessage] = useState<string | null>(null);
+
+    const refreshOcrLanguages = () =>
+        invoke<OcrLanguage[]>("get_ocr_languages")
+            .then(setOcrLanguages)
+            .catch((error) => console.error("Failed to list OCR languages:", error));
+
+    useEffect(() => {
+        invoke<{ log_level: LogLevel; capture_backend: CaptureBackend }>("get_settings")
+            .then((settings) => {
+                setLogLevel(settings.log_level);
+                setCaptureBackend(settings.capture_backend);
+            })
+            .catch((error) => console.error("Failed to read settings:", error));
+    }, []);
+
+    const createDiagnosticsBundle = async () => {
+        const date = new Date().toISOString().slice(0, 10);
+        const destination = await save({
+            defaultPath: `stepsnap-diagnostics-${date}.zip`,
+            filters: [{ name: "Zip archive", extensions: ["zip"] }],
+        });
+        if (!destination) return;
+        try {
+            const files = await invoke<string[]>("create_diagnostics_bundle", { destination });
+            setDiagnosticsMessage(`Saved ${files.length} files to ${destination}`);
+        } catch (error) {
+            setDiagnosticsMessage(`Failed to create the bundle: ${error}`);
+        }
+    };
+
+    const changeCaptureBackend = async (backend: CaptureBackend) => {
+        try {
+            await invoke("update_settings", { patch: { capture_backend: backend } });
+            setCaptureBackend(backend);
+        } catch (error) {
+            console.error("Failed to set the capture backend:", error);
+        }
+    };
+
+    const runCaptureBenchmark = async () => {
+        setBenchmarkingCapture(true);
+        setCaptureBenchmarkError(null);
+        try {
+            setCaptureBenchmark(await invoke<CaptureBenchmark>("run_capture_benchmark"));
+        } catch (error) {
+            setCaptureBenchmarkError(String(error));
+        } finally {
+            setBenchmarkingCapture(false);
+        }
+    };
+
+    const changeLogLevel = async (level: LogLevel) => {
+        try {
+            await invoke("set_log_level", { level });
+            setLogLevel(level);
+        } catch (error) {
+            console.error("Failed to set the log level:", error);
+        }
+    };
+
+    useEffect(() => {
+        refreshOcrLanguages();
+        invoke<OcrProvider[]>("get_ocr_providers")
+            .then(setOcrProviders)
+            .catch((error) => console.error("Failed to list OCR providers:", error));
+        const unlisten = listen<OcrModelProgress>("ocr-model-progress", (event) => {
+            setModelProgress(event.payload);
+        });
+        return () => {
+            unlisten.then((f) => f());
+        };
+    }, []);
+
+    const handleBenchmark = async () => {
+        setBenchmarking(true);
+        setBenchmark(await benchmarkOcrProviders());
+        setBenchmarking(false);
+    };
+
+    const isActivePreset = (preset: ClickHighlightPreset) =>
+        preset.style.shape === clickHighlightStyle.shape &&
+        preset.style.color.toLowerCase() === clickHighlightStyle.color.toLowerCase() &&
+        preset.style.radius === clickHighlightStyle.radius &&
+        preset.style.width === clickHighlightStyle.width;
+
+    const handleSavePreset = () => {
+        const name = presetName.trim();
+        if (!name) return;
+        saveClickHighlightPreset(name);
+        setPresetName("");
+    };
+
+    const benchmarkLabel = (provider: OcrProvider) => {
+        const result = benchmark?.results.find((entry) => entry.provider === provider);
+        if (!result) return null;
+        return result.millis !== null ? `${result.millis} ms` : "unavailable";
+    };
+
+    const handleOcrLanguage = async (option: OcrLanguage) => {
+        if (option.installed) {
+            setOcrLanguage(option.id);
+            return;
+        }
+        setModelError(null);
+        try {
+            await invoke("download_ocr_model", { language: option.id });
+            setOcrLanguage(option.id);
+        } catch (error) {
+            setModelError(error as string);
+        } finally {
+            setModelProgress(null);
+            refreshOcrLanguages();
+        }
+    };
+
+    const handleUpdateModels = async () => {
+        setModelError(null);
+        setUpdatingModels(true);
+        try {
+            await invoke<string[]>("update_ocr_models");
+        } catch (error) {
+            setModelError(error as string);
+        } finally {
+            setUpdatingModels(false);
+            setModelProgress(null);
+            refreshOcrLanguages();
+        }
+    };
 
     const validatePath = async (path: string) => {
         if (!path) {
@@ -49,6 +335,21 @@ export default function GeneralSection() {
         }
     };
 
+    const handleBrowseWatermarkImage = async () => {
+        try {
+            const selected = await open({
+                multiple: false,
+                title: "Select Watermark Image",
+                filters: [{ name: "Images", extensions: ["png", "jpg", "jpeg"] }],
+            });
+            if (selected && typeof selected === "string") {
+                setWatermark({ image_path: selected });
+            }
+        } catch (error) {
+            console.error("Failed to open image dialog:", error);
+        }
+    };
+
     const handleResetPath = async () => {
         const defaultPath = await getDefaultScreenshotPath();
         if (defaultPath) {
@@ -137,6 +438,708 @@ export default function GeneralSection() {
                 </button>
             </div>
 
+            <div className="flex items-center justify-between">
+                <div className="pr-4">
+                    <label className="block text-sm font-medium text-white/80">
+                        Keep Unredacted Originals
+                    </label>
+                    <p className="text-xs text-white/50 mt-1">
+                        Apply blur and pixelate regions only when exporting. When disabled, they are burned into the saved screenshots
+                    </p>
+                </div>
+                <button
+                    aria-label={`Keep unredacted originals: ${keepUnredactedOriginals ? 'enabled' : 'disabled'}`}
+                    onClick={() => setKeepUnredactedOriginals(!keepUnredactedOriginals)}
+                    className={`relative inline-flex h-6 w-11 items-center rounded-full transition-colors flex-shrink-0 ${
+                        keepUnredactedOriginals ? 'bg-[#2721E8]' : 'bg-white/20'
+                    }`}
+                >
+                    <span
+                        className={`inline-block h-4 w-4 transform rounded-full bg-white transition-transform ${
+                            keepUnredactedOriginals ? 'translate-x-6' : 'translate-x-1'
+                        }`}
+                    />
+                </button>
+            </div>
+
+            <div>
+                <div className="flex items-center justify-between">
+                    <div className="pr-4">
+                        <label className="block text-sm font-medium text-white/80">
+                            Highlight Clicks
+                        </label>
+                        <p className="text-xs text-white/50 mt-1">
+                            Mark where each click landed with a ring, arrow or spotlight, on step cards and in exports. Markers can be moved or removed per step
+                        </p>
+                    </div>
+                    <button
+                        aria-label={`Highlight clicks: ${clickHighlightStyle.enabled ? 'enabled' : 'disabled'}`}
+                        onClick={() => setClickHighlightStyle({ enabled: !clickHighlightStyle.enabled })}
+                        className={`relative inline-flex h-6 w-11 items-center rounded-full transition-colors flex-shrink-0 ${
+                            clickHighlightStyle.enabled ? 'bg-[#2721E8]' : 'bg-white/20'
+                        }`}
+                    >
+                        <span
+                            className={`inline-block h-4 w-4 transform rounded-full bg-white transition-transform ${
+                                clickHighlightStyle.enabled ? 'translate-x-6' : 'translate-x-1'
+                            }`}
+                        />
+                    </button>
+                </div>
+                {clickHighlightStyle.enabled && (
+                    <div className="mt-3 space-y-3">
+                        <div className="flex flex-wrap gap-2">
+                            {[...CLICK_HIGHLIGHT_PRESETS, ...clickHighlightPresets].map((preset) => {
+                                const custom = clickHighlightPresets.includes(preset);
+                                return (
+                                    <span
+                                        key={`${custom ? "custom" : "builtin"}-${preset.name}`}
+                                        className={`inline-flex items-center gap-1.5 rounded-lg text-xs transition-colors ${
+                                            isActivePreset(preset)
+                                                ? "bg-[#2721E8] text-white"
+                                                : "bg-white/10 text-white/60 hover:bg-white/20"
+                                        }`}
+                                    >
+                                        <button
+                                            onClick={() => setClickHighlightStyle(preset.style)}
+                                            className="inline-flex items-center gap-1.5 py-1 pl-2.5 pr-1"
+                                        >
+                                            <span
+                                                className="h-2.5 w-2.5 rounded-full"
+                                                style={{ backgroundColor: preset.style.color }}
+                                            />
+                                            {preset.name}
+                                            {preset.colorblindSafe && (
+                                                <Tooltip content="Colourblind-friendly colour">
+                                                    <span className="text-white/40">CB</span>
+                                                </Tooltip>
+                                            )}
+                                        </button>
+                                        {custom ? (
+                                            <button
+                                                onClick={() => deleteClickHighlightPreset(preset.name)}
+                                                aria-label={`Delete preset ${preset.name}`}
+                                                className="pr-2 text-white/40 hover:text-white"
+                                            >
+                                                ×
+                                            </button>
+                                        ) : (
+                                            <span className="pr-1.5" />
+                                        )}
+                                    </span>
+                                );
+                            })}
+                        </div>
+                        <div className="flex gap-2">
+                            {(Object.keys(HIGHLIGHT_SHAPE_LABELS) as HighlightShape[]).map((shape) => (
+                                <button
+                                    key={shape}
+                                    onClick={() => setClickHighlightStyle({ shape })}
+                                    className={`px-3 py-1.5 rounded-lg text-xs transition-colors ${
+                                        clickHighlightStyle.shape === shape
+                                            ? "bg-[#2721E8] text-white"
+                                            : "bg-white/10 text-white/60 hover:bg-white/20"
+                                    }`}
+                                >
+                                    {HIGHLIGHT_SHAPE_LABELS[shape]}
+                                </button>
+                            ))}
+                        </div>
+                        <div className="flex items-center gap-4">
+                            <label className="flex items-center gap-2 text-xs text-white/60">
+                                Colour
+                                <input
+                                    type="color"
+                                    value={clickHighlightStyle.color}
+                                    onChange={(e) => setClickHighlightStyle({ color: e.target.value })}
+                                    className="h-6 w-10 cursor-pointer rounded border border-white/10 bg-transparent"
+                                />
+                            </label>
+                            <label
+                                className="flex items-center gap-2 text-xs text-white/60"
+                                title="Use a light or dark colour instead when this one blends into the screenshot"
+                            >
+                                <input
+                                    type="checkbox"
+                                    checked={clickHighlightStyle.auto_contrast}
+                                    onChange={(e) => setClickHighlightStyle({ auto_contrast: e.target.checked })}
+                                    className="accent-[#2721E8]"
+                                />
+                                Adapt to background
+                            </label>
+                            <label className="flex flex-1 items-center gap-2 text-xs text-white/60">
+                                Size
+                                <input
+                                    type="range"
+                                    min={12}
+                                    max={80}
+                                    value={clickHighlightStyle.radius}
+                                    onChange={(e) => setClickHighlightStyle({ radius: Number(e.target.value) })}
+                                    className="flex-1"
+                                />
+                            </label>
+                            <label className="flex flex-1 items-center gap-2 text-xs text-white/60">
+                                Thickness
+                                <input
+                                    type="range"
+                                    min={2}
+                                    max={16}
+                                    value={clickHighlightStyle.width}
+                                    onChange={(e) => setClickHighlightStyle({ width: Number(e.target.value) })}
+                                    className="flex-1"
+                                />
+                            </label>
+                        </div>
+                        <div className="flex items-center gap-2">
+                            <input
+                                type="text"
+                                value={presetName}
+                                onChange={(e) => setPresetName(e.target.value)}
+                                onKeyDown={(e) => e.key === "Enter" && handleSavePreset()}
+                                placeholder="Preset name"
+                                className="flex-1 px-3 py-1.5 bg-[#161316]/70 border border-white/10 rounded-md text-xs text-white placeholder-white/40 focus:outline-none focus:border-[#2721E8]"
+                            />
+                            <button
+                                onClick={handleSavePreset}
+                                disabled={!presetName.trim()}
+                                className="px-3 py-1.5 rounded-lg text-xs bg-white/10 text-white/70 hover:bg-white/20 transition-colors disabled:opacity-50"
+                            >
+                                Save as preset
+                            </button>
+                        </div>
+                    </div>
+                )}
+            </div>
+
+            <div>
+                <div className="flex items-center justify-between">
+                    <div className="pr-4">
+                        <label className="block text-sm font-medium text-white/80">
+                            Number Screenshots in Exports
+                        </label>
+                        <p className="text-xs text-white/50 mt-1">
+                            Draw each step's number on its screenshot so printed and PDF documents can be followed without the step list
+                        </p>
+                    </div>
+                    <button
+                        aria-label={`Number screenshots in exports: ${stepBadgeStyle.enabled ? 'enabled' : 'disabled'}`}
+                        onClick={() => setStepBadgeStyle({ enabled: !stepBadgeStyle.enabled })}
+                        className={`relative inline-flex h-6 w-11 items-center rounded-full transition-colors flex-shrink-0 ${
+                            stepBadgeStyle.enabled ? 'bg-[#2721E8]' : 'bg-white/20'
+                        }`}
+                    >
+                        <span
+                            className={`inline-block h-4 w-4 transform rounded-full bg-white transition-transform ${
+                                stepBadgeStyle.enabled ? 'translate-x-6' : 'translate-x-1'
+                            }`}
+                        />
+                    </button>
+                </div>
+                {stepBadgeStyle.enabled && (
+                    <div className="mt-3 space-y-3">
+                        <div className="grid grid-cols-3 gap-2">
+                            {(Object.keys(BADGE_PLACEMENT_LABELS) as BadgePlacement[]).map((placement) => (
+                                <button
+                                    key={placement}
+                                    onClick={() => setStepBadgeStyle({ placement })}
+                                    className={`px-3 py-2 rounded-md text-sm transition-all ${
+                                        stepBadgeStyle.placement === placement
+                                            ? 'bg-[#2721E8] text-white'
+                                            : 'bg-[#161316]/70 text-white/70 hover:bg-white/10'
+                                    }`}
+                                >
+                                    {BADGE_PLACEMENT_LABELS[placement]}
+                                </button>
+                            ))}
+                        </div>
+                        <div className="flex items-center gap-4">
+                            <label className="flex items-center gap-2 text-xs text-white/60">
+                                Colour
+                                <input
+                                    type="color"
+                                    value={stepBadgeStyle.color}
+                                    onChange={(e) => setStepBadgeStyle({ color: e.target.value })}
+                                    className="h-6 w-10 cursor-pointer rounded border border-white/10 bg-transparent"
+                                />
+                            </label>
+                            <label
+                                className="flex items-center gap-2 text-xs text-white/60"
+                                title="Use a light or dark colour instead when this one blends into the screenshot"
+                            >
+                                <input
+                                    type="checkbox"
+                                    checked={stepBadgeStyle.auto_contrast}
+                                    onChange={(e) => setStepBadgeStyle({ auto_contrast: e.target.checked })}
+                                    className="accent-[#2721E8]"
+                                />
+                                Adapt to background
+                            </label>
+                            <label className="flex flex-1 items-center gap-2 text-xs text-white/60">
+                                Size
+                                <input
+                                    type="range"
+                                    min={10}
+                                    max={40}
+                                    value={stepBadgeStyle.size}
+                                    onChange={(e) => setStepBadgeStyle({ size: Number(e.target.value) })}
+                                    className="flex-1"
+                                />
+                            </label>
+                        </div>
+                    </div>
+                )}
+            </div>
+
+            <div>
+                <label className="block text-sm font-medium text-white/80 mb-2">
+                    Smart Crop
+                </label>
+                <div className="grid grid-cols-3 gap-2">
+                    {(Object.keys(SMART_CROP_LABELS) as SmartCropMode[]).map((mode) => (
+                        <button
+                            key={mode}
+                            onClick={() => setSmartCrop({ mode })}
+                            className={`px-3 py-2 rounded-md text-sm transition-all ${
+                                smartCrop.mode === mode
+                                    ? 'bg-[#2721E8] text-white'
+                                    : 'bg-[#161316]/70 text-white/70 hover:bg-white/10'
+                            }`}
+                        >
+                            {SMART_CROP_LABELS[mode]}
+                        </button>
+                    ))}
+                </div>
+                {smartCrop.mode !== "off" && (
+                    <label className="mt-3 flex items-center gap-2 text-xs text-white/60">
+                        Context
+                        <input
+                            type="range"
+                            min={40}
+                            max={480}
+                            step={20}
+                            value={smartCrop.padding}
+                            onChange={(e) => setSmartCrop({ padding: Number(e.target.value) })}
+                            className="flex-1"
+                        />
+                        <span className="w-12 text-right">{smartCrop.padding}px</span>
+                    </label>
+                )}
+                <p className="mt-1 text-xs text-white/50">
+                    Focus screenshots on the clicked element, or around the click when the element is unknown. While recording crops the saved screenshot; at export keeps the full capture and crops only exported copies.
+                </p>
+            </div>
+
+            <div>
+                <label className="block text-sm font-medium text-white/80 mb-2">
+                    Screen Capture
+                </label>
+                <div className="grid grid-cols-2 gap-2">
+                    {(Object.keys(CAPTURE_BACKEND_LABELS) as CaptureBackend[]).map((backend) => (
+                        <button
+                            key={backend}
+                            onClick={() => changeCaptureBackend(backend)}
+                            className={`px-3 py-2 rounded-md text-sm transition-all ${
+                                captureBackend === backend
+                                    ? 'bg-[#2721E8] text-white'
+                                    : 'bg-[#161316]/70 text-white/70 hover:bg-white/10'
+                            }`}
+                        >
+                            {CAPTURE_BACKEND_LABELS[backend]}
+                        </button>
+                    ))}
+                </div>
+                <p className="mt-1 text-xs text-white/50">
+                    Native keeps a capture session open while recording (DXGI on Windows, ScreenCaptureKit on macOS, PipeWire on Wayland), taking screenshots in milliseconds on high-resolution screens. On Wayland it asks once which screens to share. Falls back to standard capture when unavailable.
+                </p>
+                <div className="mt-2 flex items-center gap-2">
+                    <button
+                        onClick={runCaptureBenchmark}
+                        disabled={benchmarkingCapture}
+                        className="px-3 py-1 text-xs rounded-md bg-white/10 hover:bg-white/20 transition-colors disabled:opacity-40"
+                    >
+                        {benchmarkingCapture ? "Measuring..." : "Measure capture speed"}
+                    </button>
+                    {captureBenchmark && (
+                        <button
+                            onClick={() => navigator.clipboard.writeText(JSON.stringify(captureBenchmark, null, 2))}
+                            className="px-3 py-1 text-xs rounded-md bg-white/10 hover:bg-white/20 transition-colors"
+                        >
+                            Copy results
+                        </button>
+                    )}
+                </div>
+                {captureBenchmarkError && (
+                    <p className="mt-1 text-xs text-red-400">{captureBenchmarkError}</p>
+                )}
+                {captureBenchmark && (
+                    <ul className="mt-1 space-y-0.5 text-xs text-white/60">
+                        {captureBenchmark.monitors.map((monitor) => (
+                            <li key={monitor.index}>
+                                Monitor {monitor.index + 1} ({monitor.width} × {monitor.height}):{" "}
+                                {monitor.times
+                                    ? `capture ${monitor.times.capture_ms.toFixed(0)} ms · annotate ${monitor.times.annotate_ms.toFixed(0)} ms · encode ${monitor.times.encode_ms.toFixed(0)} ms · write ${monitor.times.write_ms.toFixed(0)} ms = ${monitor.times.total_ms.toFixed(0)} ms`
+                                    : monitor.error}
+                            </li>
+                        ))}
+                    </ul>
+                )}
+            </div>
+
+            <div>
+                <div className="flex items-center justify-between">
+                    <div className="pr-4">
+                        <label className="block text-sm font-medium text-white/80">
+                            Zoom Inset in Exports
+                        </label>
+                        <p className="text-xs text-white/50 mt-1">
+                            Add an enlarged view of the area around each click in a corner of exported screenshots, so small controls stay readable on large screens
+                        </p>
+                    </div>
+                    <button
+                        aria-label={`Zoom inset in exports: ${zoomInset.enabled ? 'enabled' : 'disabled'}`}
+                        onClick={() => setZoomInset({ enabled: !zoomInset.enabled })}
+                        className={`relative inline-flex h-6 w-11 items-center rounded-full transition-colors flex-shrink-0 ${
+                            zoomInset.enabled ? 'bg-[#2721E8]' : 'bg-white/20'
+                        }`}
+                    >
+                        <span
+                            className={`inline-block h-4 w-4 transform rounded-full bg-white transition-transform ${
+                                zoomInset.enabled ? 'translate-x-6' : 'translate-x-1'
+                            }`}
+                        />
+                    </button>
+                </div>
+                {zoomInset.enabled && (
+                    <div className="mt-3 space-y-3">
+                        <div className="grid grid-cols-3 gap-2">
+                            {(Object.keys(INSET_PLACEMENT_LABELS) as InsetPlacement[]).map((placement) => (
+                                <button
+                                    key={placement}
+                                    onClick={() => setZoomInset({ placement })}
+                                    className={`px-3 py-2 rounded-md text-sm transition-all ${
+                                        zoomInset.placement === placement
+                                            ? 'bg-[#2721E8] text-white'
+                                            : 'bg-[#161316]/70 text-white/70 hover:bg-white/10'
+                                    }`}
+                                >
+                                    {INSET_PLACEMENT_LABELS[placement]}
+                                </button>
+                            ))}
+                        </div>
+                        <div className="flex items-center gap-4">
+                            <label className="flex items-center gap-2 text-xs text-white/60">
+                                Border
+                                <input
+                                    type="color"
+                                    value={zoomInset.border_color}
+                                    onChange={(e) => setZoomInset({ border_color: e.target.value })}
+                                    className="h-6 w-10 cursor-pointer rounded border border-white/10 bg-transparent"
+                                />
+                            </label>
+                            <label className="flex flex-1 items-center gap-2 text-xs text-white/60">
+                                Zoom
+                                <input
+                                    type="range"
+                                    min={1.5}
+                                    max={5}
+                                    step={0.5}
+                                    value={zoomInset.zoom}
+                                    onChange={(e) => setZoomInset({ zoom: Number(e.target.value) })}
+                                    className="flex-1"
+                                />
+                                <span className="w-8 text-right">{zoomInset.zoom}x</span>
+                            </label>
+                            <label className="flex flex-1 items-center gap-2 text-xs text-white/60">
+                                Size
+                                <input
+                                    type="range"
+                                    min={120}
+                                    max={480}
+                                    step={20}
+                                    value={zoomInset.size}
+                                    onChange={(e) => setZoomInset({ size: Number(e.target.value) })}
+                                    className="flex-1"
+                                />
+                            </label>
+                        </div>
+                    </div>
+                )}
+            </div>
+
+            <div>
+                <div className="flex items-center justify-between">
+                    <div className="pr-4">
+                        <label className="block text-sm font-medium text-white/80">
+                            Watermark in Exports
+                        </label>
+                        <p className="text-xs text-white/50 mt-1">
+                            Stamp text such as "CONFIDENTIAL" or a logo onto exported screenshots. Screenshots in the app are left unmarked
+                        </p>
+                    </div>
+                    <button
+                        aria-label={`Watermark in exports: ${watermark.enabled ? 'enabled' : 'disabled'}`}
+                        onClick={() => setWatermark({ enabled: !watermark.enabled })}
+                        className={`relative inline-flex h-6 w-11 items-center rounded-full transition-colors flex-shrink-0 ${
+                            watermark.enabled ? 'bg-[#2721E8]' : 'bg-white/20'
+                        }`}
+                    >
+                        <span
+                            className={`inline-block h-4 w-4 transform rounded-full bg-white transition-transform ${
+                                watermark.enabled ? 'translate-x-6' : 'translate-x-1'
+                            }`}
+                        />
+                    </button>
+                </div>
+                {watermark.enabled && (
+                    <div className="mt-3 space-y-3">
+                        <div className="flex gap-2">
+                            {(Object.keys(WATERMARK_KIND_LABELS) as WatermarkKind[]).map((kind) => (
+                                <button
+                                    key={kind}
+                                    onClick={() => setWatermark({ kind })}
+                                    className={`px-3 py-1.5 rounded-lg text-xs transition-colors ${
+                                        watermark.kind === kind
+                                            ? "bg-[#2721E8] text-white"
+                                            : "bg-white/10 text-white/60 hover:bg-white/20"
+                                    }`}
+                                >
+                                    {WATERMARK_KIND_LABELS[kind]}
+                                </button>
+                            ))}
+                        </div>
+                        {watermark.kind === "text" ? (
+                            <div className="flex items-center gap-4">
+                                <input
+                                    type="text"
+                                    value={watermark.text}
+                                    onChange={(e) => setWatermark({ text: e.target.value })}
+                                    placeholder="CONFIDENTIAL"
+                                    className="flex-1 px-3 py-1.5 bg-[#161316]/70 border border-white/10 rounded-md text-xs text-white placeholder-white/40 focus:outline-none focus:border-[#2721E8]"
+                                />
+                                <label className="flex items-center gap-2 text-xs text-white/60">
+                                    Colour
+                                    <input
+                                        type="color"
+                                        value={watermark.color}
+                                        onChange={(e) => setWatermark({ color: e.target.value })}
+                                        className="h-6 w-10 cursor-pointer rounded border border-white/10 bg-transparent"
+                                    />
+                                </label>
+                            </div>
+                        ) : (
+                            <div className="flex items-center gap-2">
+                                <span className="flex-1 truncate px-3 py-1.5 bg-[#161316]/70 border border-white/10 rounded-md text-xs text-white/70">
+                                    {watermark.image_path || "No image selected"}
+                                </span>
+                                <button
+                                    onClick={handleBrowseWatermarkImage}
+                                    className="flex items-center gap-1.5 px-3 py-1.5 rounded-lg text-xs bg-white/10 text-white/60 hover:bg-white/20 transition-colors"
+                                >
+                                    <FolderOpen size={14} />
+                                    Browse
+                                </button>
+                            </div>
+                        )}
+                        <div className="grid grid-cols-3 gap-2">
+                            {(Object.keys(WATERMARK_POSITION_LABELS) as WatermarkPosition[]).map((position) => (
+                                <button
+                                    key={position}
+                                    onClick={() => setWatermark({ position })}
+                                    className={`px-3 py-2 rounded-md text-sm transition-all ${
+                                        watermark.position === position
+                                            ? 'bg-[#2721E8] text-white'
+                                            : 'bg-[#161316]/70 text-white/70 hover:bg-white/10'
+                                    }`}
+                                >
+                                    {WATERMARK_POSITION_LABELS[position]}
+                                </button>
+                            ))}
+                        </div>
+                        <div className="flex items-center gap-4">
+                            <label className="flex flex-1 items-center gap-2 text-xs text-white/60">
+                                Opacity
+                                <input
+                                    type="range"
+                                    min={0.05}
+                                    max={1}
+                                    step={0.05}
+                                    value={watermark.opacity}
+                                    onChange={(e) => setWatermark({ opacity: Number(e.target.value) })}
+                                    className="flex-1"
+                                />
+                                <span className="w-8 text-right">{Math.round(watermark.opacity * 100)}%</span>
+                            </label>
+                            <label className="flex flex-1 items-center gap-2 text-xs text-white/60">
+                                Size
+                                <input
+                                    type="range"
+                                    min={16}
+                                    max={400}
+                                    step={8}
+                                    value={watermark.size}
+                                    onChange={(e) => setWatermark({ size: Number(e.target.value) })}
+                                    className="flex-1"
+                                />
+                            </label>
+                        </div>
+                    </div>
+                )}
+            </div>
+
+            <div>
+                <label className="block text-sm font-medium text-white/80 mb-2">
+                    Repeated Screenshots
+                </label>
+                <div className="grid grid-cols-3 gap-2">
+                    {(Object.keys(DUPLICATE_FRAME_LABELS) as DuplicateFrames[]).map((mode) => (
+                        <button
+                            key={mode}
+                            onClick={() => setDuplicateFrames(mode)}
+                            className={`px-3 py-2 rounded-md text-sm transition-all ${
+                                duplicateFrames === mode
+                                    ? 'bg-[#2721E8] text-white'
+                                    : 'bg-[#161316]/70 text-white/70 hover:bg-white/10'
+                            }`}
+                        >
+                            {DUPLICATE_FRAME_LABELS[mode]}
+                        </button>
+                    ))}
+                </div>
+                <p className="mt-1 text-xs text-white/50">
+                    When a step's screen looks the same as the previous step's, reuse that screenshot and mark the step, or leave the click out altogether. Typed text is always kept.
+                </p>
+            </div>
+
+            <div>
+                <label className="block text-sm font-medium text-white/80 mb-2">
+                    OCR Language
+                </label>
+                <div className="grid grid-cols-3 gap-2">
+                    {[{ id: "auto", label: "Auto-detect", installed: true, downloadable: false }, ...ocrLanguages].map((option) => (
+                        <button
+                            key={option.id}
+                            disabled={(!option.installed && !option.downloadable) || modelProgress !== null || updatingModels}
+                            onClick={() => handleOcrLanguage(option)}
+                            className={`px-3 py-2 rounded-md text-sm text-left transition-all disabled:opacity-40 disabled:cursor-not-allowed ${
+                                ocrLanguage === option.id
+                                    ? 'bg-[#2721E8] text-white'
+                                    : 'bg-[#161316]/70 text-white/70 hover:bg-white/10'
+                            }`}
+                        >
+                            <div className="font-medium">{option.label}</div>
+                            {!option.installed && (
+                                <div className="text-xs opacity-70 mt-0.5">
+                                    {modelProgress?.language === option.id
+                                        ? modelProgress.total
+                                            ? `Downloading ${Math.round((modelProgress.downloaded / modelProgress.total) * 100)}%`
+                                            : "Downloading..."
+                                        : option.downloadable
+                                            ? "Click to download"
+                                            : "Model not installed"}
+                                </div>
+                            )}
+                        </button>
+                    ))}
+                </div>
+                {modelError && (
+                    <p className="mt-1 text-xs text-red-500">{modelError}</p>
+                )}
+                <button
+                    onClick={handleUpdateModels}
+                    disabled={modelProgress !== null || updatingModels}
+                    className="mt-2 px-3 py-1 text-xs rounded-md bg-white/10 hover:bg-white/20 transition-colors disabled:opacity-40"
+                >
+                    {updatingModels ? "Updating models..." : "Update downloaded models"}
+                </button>
+                <p className="mt-1 text-xs text-white/50">
+                    Applies to steps recorded from now on. Auto-detect tries each installed model until one reads the text confidently.
+                </p>
+            </div>
+
+            <div>
+                <label className="block text-sm font-medium text-white/80 mb-2">
+                    OCR Parallelism
+                </label>
+                <div className="grid grid-cols-4 gap-2">
+                    {[1, 2, 3, 4].map((value) => (
+                        <button
+                            key={value}
+                            onClick={() => setOcrParallelism(value)}
+                            className={`px-3 py-2 rounded-md text-sm transition-all ${
+                                ocrParallelism === value
+                                    ? 'bg-[#2721E8] text-white'
+                                    : 'bg-[#161316]/70 text-white/70 hover:bg-white/10'
+                            }`}
+                        >
+                            {value}
+                        </button>
+                    ))}
+                </div>
+                <p className="mt-1 text-xs text-white/50">
+                    Screenshots read at once after recording stops. While recording, OCR reads one at a time.
+                </p>
+            </div>
+
+            <div>
+                <label className="block text-sm font-medium text-white/80 mb-2">
+                    OCR Engine
+                </label>
+                <div className="grid grid-cols-4 gap-2">
+                    {(["auto", ...ocrProviders] as OcrProvider[]).map((provider) => (
+                        <button
+                            key={provider}
+                            onClick={() => setOcrProvider(provider)}
+                            className={`px-3 py-2 rounded-md text-sm transition-all ${
+                                ocrProvider === provider
+                                    ? 'bg-[#2721E8] text-white'
+                                    : 'bg-[#161316]/70 text-white/70 hover:bg-white/10'
+                            }`}
+                        >
+                            {OCR_PROVIDER_LABELS[provider]}
+                            {provider !== "auto" && benchmarkLabel(provider) && (
+                                <span className="block text-xs opacity-70">{benchmarkLabel(provider)}</span>
+                            )}
+                        </button>
+                    ))}
+                </div>
+                <button
+                    onClick={handleBenchmark}
+                    disabled={benchmarking}
+                    className="mt-2 px-3 py-1 text-xs rounded-md bg-white/10 hover:bg-white/20 transition-colors disabled:opacity-40"
+                >
+                    {benchmarking ? "Benchmarking..." : "Benchmark and pick fastest"}
+                </button>
+                <p className="mt-1 text-xs text-white/50">
+                    Auto uses the ONNX models when installed and the system OCR otherwise. The benchmark runs on your latest screenshot.
+                </p>
+            </div>
+
+            {ocrProvider !== "windows" && ocrProvider !== "vision" && (
+                <div>
+                    <label className="block text-sm font-medium text-white/80 mb-2">
+                        Parallel Models
+                    </label>
+                    <div className="grid grid-cols-4 gap-2">
+                        {[1, 2, 3, 4].map((value) => (
+                            <button
+                                key={value}
+                                onClick={() => setOcrParallelModels(value)}
+                                className={`px-3 py-2 rounded-md text-sm transition-all ${
+                                    ocrParallelModels === value
+                                        ? 'bg-[#2721E8] text-white'
+                                        : 'bg-[#161316]/70 text-white/70 hover:bg-white/10'
+                                }`}
+                            >
+                                {value}
+                            </button>
+                        ))}
+                    </div>
+                    <p className="mt-1 text-xs text-white/50">
+                        With Auto-detect language, how many installed models the ONNX engine tries at once, each on its own thread.
+                    </p>
+                </div>
+            )}
+
             <div className="border-t border-white/8 pt-6">
                 <label className="block text-sm font-medium text-white/80 mb-2">
                     Application Logs
@@ -157,9 +1160,40 @@ export default function GeneralSection() {
                         <FileText size={14} />
                         Open logs folder
                     </button>
+                    <button
+                        onClick={createDiagnosticsBundle}
+                        className="px-3 py-2 bg-white/10 border border-white/10 rounded-md hover:bg-white/15 transition-colors text-sm text-white inline-flex items-center gap-2"
+                    >
+                        <LifeBuoy size={14} />
+                        Create diagnostics bundle
+                    </button>
+                </div>
+                {diagnosticsMessage && (
+                    <p className="mt-2 text-xs text-white/70">{diagnosticsMessage}</p>
+                )}
+                <p className="mt-1 text-xs text-white/50">
+                    Logs are split per category (ai, recorder, database, etc.) and rotated daily. Files older than 30 days are deleted automatically. The diagnostics bundle adds your settings, with API keys removed, and system details for a bug report.
+                </p>
+                <label className="block text-sm font-medium text-white/80 mt-4 mb-2">
+                    Log Detail
+                </label>
+                <div className="grid grid-cols-4 gap-2">
+                    {LOG_LEVELS.map(({ value, label }) => (
+                        <button
+                            key={value}
+                            onClick={() => changeLogLevel(value)}
+                            className={`px-3 py-2 rounded-md text-sm transition-all ${
+                                logLevel === value
+                                    ? 'bg-[#2721E8] text-white'
+                                    : 'bg-[#161316]/70 text-white/70 hover:bg-white/10'
+                            }`}
+                        >
+                            {label}
+                        </button>
+                    ))}
                 </div>
                 <p className="mt-1 text-xs text-white/50">
-                    Logs are split per category (ai, recorder, database, etc.) and rotated daily. Files older than 30 days are deleted automatically.
+                    Use Debug while tracking down a capture problem, then switch back to keep the files small.
                 </p>
             </div>
         </div>
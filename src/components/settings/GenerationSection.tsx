@@ -1,3 +1,5 @@
+import { useEffect, useState } from "react";
+import { invoke } from "@tauri-apps/api/core";
 import { RotateCcw } from "lucide-react";
 import { useSettingsStore } from "../../store/settingsStore";
 import {
@@ -6,6 +8,15 @@ import {
     VERBOSITY_OPTIONS,
     BRAND_VOICE_OPTIONS,
 } from "../../lib/promptConstants";
+import MetadataGenerationSettings from "./MetadataGenerationSettings";
+
+/** Languages the backend has a catalog for (`i18n::Locale`). */
+const LOCALE_OPTIONS = [
+    { value: "en", label: "English" },
+    { value: "de", label: "Deutsch" },
+    { value: "fr", label: "Français" },
+    { value: "es", label: "Español" },
+];
 
 export default function GenerationSection() {
     const {
@@ -15,6 +26,7 @@ export default function GenerationSection() {
         enableMultiStagePrompting,
         afterFrameMaxWaitMs,
         enableVideoClips,
+        enableAxTreeSnapshots,
         setWritingStyleTone,
         setWritingStyleAudience,
         setWritingStyleVerbosity,
@@ -25,8 +37,26 @@ export default function GenerationSection() {
         setEnableMultiStagePrompting,
         setAfterFrameMaxWaitMs,
         setEnableVideoClips,
+        setEnableAxTreeSnapshots,
     } = useSettingsStore();
 
+    const [locale, setLocale] = useState("en");
+
+    useEffect(() => {
+        invoke<{ locale: string }>("get_settings")
+            .then((settings) => setLocale(settings.locale))
+            .catch(() => {});
+    }, []);
+
+    const handleLocaleChange = async (value: string) => {
+        try {
+            await invoke("update_settings", { patch: { locale: value } });
+            setLocale(value);
+        } catch (err) {
+            console.error("Failed to change document language:", err);
+        }
+    };
+
     return (
         <div className="space-y-6">
             <div>
@@ -34,6 +64,31 @@ export default function GenerationSection() {
                 <p className="text-xs text-white/50">Control which AI passes run and how the resulting documentation reads.</p>
             </div>
 
+            {/* Document language */}
+            <div>
+                <h4 className="text-sm font-medium text-white/80 mb-2">
+                    Document Language
+                </h4>
+                <p className="text-xs text-white/50 mb-4">
+                    Language of default step descriptions, Markdown export headings and recovered recording names. AI-written text is not affected.
+                </p>
+                <div className="flex gap-2">
+                    {LOCALE_OPTIONS.map((option) => (
+                        <button
+                            key={option.value}
+                            onClick={() => handleLocaleChange(option.value)}
+                            className={`px-3 py-1.5 rounded-lg text-sm transition-colors ${
+                                locale === option.value
+                                    ? 'bg-[#2721E8] text-white'
+                                    : 'bg-white/10 text-white/60 hover:bg-white/20'
+                            }`}
+                        >
+                            {option.label}
+                        </button>
+                    ))}
+                </div>
+            </div>
+
             {/* Pipeline toggles */}
             <div>
                 <h4 className="text-sm font-medium text-white/80 mb-2">
@@ -139,6 +194,30 @@ export default function GenerationSection() {
                     </button>
                 </div>
 
+                <div className="flex items-center justify-between mb-4">
+                    <div className="pr-4">
+                        <label className="block text-sm font-medium text-white/80">
+                            Save accessibility tree snapshots
+                        </label>
+                        <p className="text-xs text-white/50 mt-1">
+                            Store the controls around each clicked element (names, roles, ids, positions) with the step, so selectors can be rebuilt later for automation. Field values are never included.
+                        </p>
+                    </div>
+                    <button
+                        aria-label={`Accessibility tree snapshots: ${enableAxTreeSnapshots ? 'enabled' : 'disabled'}`}
+                        onClick={() => setEnableAxTreeSnapshots(!enableAxTreeSnapshots)}
+                        className={`relative inline-flex h-6 w-11 items-center rounded-full transition-colors flex-shrink-0 ${
+                            enableAxTreeSnapshots ? 'bg-[#2721E8]' : 'bg-white/20'
+                        }`}
+                    >
+                        <span
+                            className={`inline-block h-4 w-4 transform rounded-full bg-white transition-transform ${
+                                enableAxTreeSnapshots ? 'translate-x-6' : 'translate-x-1'
+                            }`}
+                        />
+                    </button>
+                </div>
+
                 {enableStateDiff && (
                     <div className="mb-2">
                         <label className="block text-sm font-medium text-white/80 mb-1">
@@ -277,6 +356,8 @@ export default function GenerationSection() {
                     </div>
                 </div>
             </div>
+
+            <MetadataGenerationSettings />
         </div>
     );
 }
@@ -0,0 +1,158 @@
+import { useEffect, useState } from "react";
+import { invoke } from "@tauri-apps/api/core";
+import { open } from "@tauri-apps/plugin-dialog";
+import { FolderOpen } from "lucide-react";
+
+/** Mirrors `GitPublishSettings` in the backend. */
+interface GitSettings {
+    repo_path: string;
+    directory: string;
+    commit_message: string;
+    push: boolean;
+    remote: string;
+}
+
+const inputClass =
+    "w-full px-4 py-2 bg-[#161316]/70 border border-white/10 rounded-md text-white placeholder-white/50 focus:outline-none focus:border-[#2721E8]";
+
+/** Where "Publish to Git" writes and commits recordings. */
+export default function GitPublishSettings() {
+    const [git, setGit] = useState<GitSettings | null>(null);
+    const [error, setError] = useState<string | null>(null);
+    const [saved, setSaved] = useState(false);
+
+    useEffect(() => {
+        invoke<{ git: GitSettings }>("get_settings")
+            .then((settings) => setGit(settings.git))
+            .catch((err) => console.error("Failed to read Git settings:", err));
+    }, []);
+
+    if (!git) {
+        return null;
+    }
+
+    const edit = (changes: Partial<GitSettings>) => {
+        setGit({ ...git, ...changes });
+        setSaved(false);
+    };
+
+    const handleBrowse = async () => {
+        const selected = await open({ directory: true, multiple: false, title: "Choose a Git repository" });
+        if (typeof selected === "string") {
+            edit({ repo_path: selected });
+        }
+    };
+
+    const handleSave = async () => {
+        try {
+            await invoke("update_settings", { patch: { git } });
+            setError(null);
+            setSaved(true);
+        } catch (err) {
+            setError(String(err));
+        }
+    };
+
+    return (
+        <div className="border-t border-white/8 pt-6 space-y-4">
+            <div>
+                <label className="block text-sm font-medium text-white/80">Git Publishing</label>
+                <p className="text-xs text-white/50 mt-1">
+                    "Publish to Git" writes a recording as Markdown with its screenshots into a local clone and commits it, using your Git setup and credentials.
+                </p>
+            </div>
+
+            <div>
+                <label className="block text-sm font-medium text-white/60 mb-2">Repository</label>
+                <div className="flex gap-2">
+                    <input
+                        type="text"
+                        value={git.repo_path}
+                        onChange={(e) => edit({ repo_path: e.target.value })}
+                        placeholder="Path to a local clone"
+                        className={inputClass}
+                    />
+                    <button
+                        aria-label="Choose a repository"
+                        onClick={() => {
+                            void handleBrowse();
+                        }}
+                        className="px-3 py-2 bg-white/10 border border-white/10 rounded-md hover:bg-white/15 transition-colors"
+                    >
+                        <FolderOpen size={16} className="text-white/70" />
+                    </button>
+                </div>
+            </div>
+
+            <div>
+                <label className="block text-sm font-medium text-white/60 mb-2">Folder</label>
+                <input
+                    type="text"
+                    value={git.directory}
+                    onChange={(e) => edit({ directory: e.target.value })}
+                    placeholder="docs"
+                    className={inputClass}
+                />
+            </div>
+
+            <div>
+                <label className="block text-sm font-medium text-white/60 mb-2">Commit Message</label>
+                <input
+                    type="text"
+                    value={git.commit_message}
+                    onChange={(e) => edit({ commit_message: e.target.value })}
+                    className={inputClass}
+                />
+                <p className="mt-1 text-xs text-white/50">
+                    {"{name}"}, {"{id}"}, {"{steps}"} and {"{date}"} are filled in.
+                </p>
+            </div>
+
+            <div className="flex items-center justify-between">
+                <div className="pr-4">
+                    <label className="block text-sm font-medium text-white/80">Push after committing</label>
+                    <p className="text-xs text-white/50 mt-1">
+                        Pushes to the remote below, or the branch's upstream when empty.
+                    </p>
+                </div>
+                <button
+                    aria-label={`Push after committing: ${git.push ? "enabled" : "disabled"}`}
+                    onClick={() => edit({ push: !git.push })}
+                    className={`relative inline-flex h-6 w-11 items-center rounded-full transition-colors flex-shrink-0 ${
+                        git.push ? "bg-[#2721E8]" : "bg-white/20"
+                    }`}
+                >
+                    <span
+                        className={`inline-block h-4 w-4 transform rounded-full bg-white transition-transform ${
+                            git.push ? "translate-x-6" : "translate-x-1"
+                        }`}
+                    />
+                </button>
+            </div>
+
+            {git.push && (
+                <div>
+                    <label className="block text-sm font-medium text-white/60 mb-2">Remote</label>
+                    <input
+                        type="text"
+                        value={git.remote}
+                        onChange={(e) => edit({ remote: e.target.value })}
+                        placeholder="Upstream of the current branch"
+                        className={inputClass}
+                    />
+                </div>
+            )}
+
+            <div className="flex items-center gap-3">
+                <button
+                    onClick={handleSave}
+                    className="px-4 py-2 rounded-md text-sm bg-[#2721E8] text-white hover:bg-[#2721E8]/90 transition-colors"
+                >
+                    Save
+                </button>
+                {saved && <span className="text-xs text-white/60">Saved</span>}
+                {error && <span className="text-xs text-red-400">{error}</span>}
+            </div>
+        </div>
+    );
+}
@@ -0,0 +1,157 @@
+import { useEffect, useState } from "react";
+import { invoke } from "@tauri-apps/api/core";
+import {
+    useSettingsStore,
+    HotkeyAction,
+    HotkeyBinding,
+    HotkeyBindings,
+    HotkeyFailure,
+    HotkeyProfile,
+} from "../../store/settingsStore";
+
+const sameBinding = (a: HotkeyBinding | null | undefined, b: HotkeyBinding | null | undefined) =>
+    (a ?? null) === (b ?? null) ||
+    (!!a &&
+        !!b &&
+        a.ctrl === b.ctrl &&
+        a.shift === b.shift &&
+        a.alt === b.alt &&
+        a.meta === b.meta &&
+        a.key === b.key &&
+        !!a.disabled === !!b.disabled);
+
+/** Whether `profile` binds every action the way `hotkeys` does. */
+const isActive = (profile: HotkeyProfile, hotkeys: HotkeyBindings) =>
+    (Object.keys(hotkeys) as HotkeyAction[]).every((action) => sameBinding(profile.bindings[action], hotkeys[action]));
+
+/** Bound actions only; the backend stores unbound ones as missing. */
+const boundHotkeys = (hotkeys: HotkeyBindings) =>
+    Object.fromEntries(Object.entries(hotkeys).filter(([, binding]) => binding !== null));
+
+export default function HotkeyProfiles() {
+    const { hotkeys, setHotkeys } = useSettingsStore();
+    const [profiles, setProfiles] = useState<HotkeyProfile[]>([]);
+    const [profileName, setProfileName] = useState("");
+    const [error, setError] = useState<string | null>(null);
+
+    const refresh = async () => {
+        try {
+            setProfiles(await invoke<HotkeyProfile[]>("list_hotkey_profiles"));
+        } catch (listError) {
+            console.error("Failed to load hotkey profiles:", listError);
+        }
+    };
+
+    useEffect(() => {
+        void refresh();
+    }, []);
+
+    const run = async (task: () => Promise<void>) => {
+        setError(null);
+        try {
+            await task();
+        } catch (taskError) {
+            setError(String(taskError));
+        }
+    };
+
+    const handleSave = () =>
+        run(async () => {
+            await invoke("save_hotkey_profile", { name: profileName, bindings: boundHotkeys(hotkeys) });
+            setProfileName("");
+            await refresh();
+        });
+
+    const handleApply = (name: string) =>
+        run(async () => {
+            const applied = await invoke<{ profile: HotkeyProfile; failures: HotkeyFailure[] }>("apply_hotkey_profile", { name });
+            setHotkeys(applied.profile.bindings);
+            useSettingsStore.setState({ hotkeyFailures: applied.failures });
+        });
+
+    const handleExport = (name: string) =>
+        run(async () => {
+            await invoke<boolean>("export_hotkey_profile", { name });
+        });
+
+    const handleDelete = (name: string) =>
+        run(async () => {
+            await invoke("delete_hotkey_profile", { name });
+            await refresh();
+        });
+
+    const handleImport = () =>
+        run(async () => {
+            const imported = await invoke<HotkeyProfile | null>("import_hotkey_profile");
+            if (imported) await refresh();
+        });
+
+    return (
+        <div className="space-y-3">
+            <div>
+                <h4 className="text-sm font-medium text-white/80">Profiles</h4>
+                <p className="text-xs text-white/50 mt-1">
+                    Save these shortcuts under a name to switch between sets, e.g. for a laptop and an external keyboard, or share them with your team.
+                </p>
+            </div>
+
+            {profiles.length > 0 && (
+                <div className="space-y-2">
+                    {profiles.map((profile) => (
+                        <div key={profile.name} className="flex items-center gap-2 rounded-md border border-white/10 px-3 py-2">
+                            <span className="flex-1 truncate text-sm text-white">{profile.name}</span>
+                            {isActive(profile, hotkeys) ? (
+                                <span className="text-xs text-[#49B8D3]">In use</span>
+                            ) : (
+                                <button
+                                    onClick={() => void handleApply(profile.name)}
+                                    className="px-3 py-1.5 rounded-lg text-xs bg-[#2721E8] text-white hover:bg-[#2721E8]/80 transition-colors"
+                                >
+                                    Use
+                                </button>
+                            )}
+                            <button
+                                onClick={() => void handleExport(profile.name)}
+                                className="px-3 py-1.5 rounded-lg text-xs bg-white/10 text-white/70 hover:bg-white/20 transition-colors"
+                            >
+                                Export
+                            </button>
+                            <button
+                                onClick={() => void handleDelete(profile.name)}
+                                className="px-3 py-1.5 rounded-lg text-xs bg-white/10 text-white/70 hover:bg-red-500/20 hover:text-red-300 transition-colors"
+                            >
+                                Delete
+                            </button>
+                        </div>
+                    ))}
+                </div>
+            )}
+
+            <div className="flex items-center gap-2">
+                <input
+                    type="text"
+                    value={profileName}
+                    onChange={(e) => setProfileName(e.target.value)}
+                    onKeyDown={(e) => e.key === "Enter" && profileName.trim() && void handleSave()}
+                    placeholder="Profile name"
+                    className="flex-1 px-3 py-1.5 bg-[#161316]/70 border border-white/10 rounded-md text-xs text-white placeholder-white/40 focus:outline-none focus:border-[#2721E8]"
+                />
+                <button
+                    onClick={() => void handleSave()}
+                    disabled={!profileName.trim()}
+                    className="px-3 py-1.5 rounded-lg text-xs bg-white/10 text-white/70 hover:bg-white/20 transition-colors disabled:opacity-50"
+                >
+                    Save current
+                </button>
+                <button
+                    onClick={() => void handleImport()}
+                    className="px-3 py-1.5 rounded-lg text-xs bg-white/10 text-white/70 hover:bg-white/20 transition-colors"
+                >
+                    Import
+                </button>
+            </div>
+
+            {error && <p className="text-xs text-red-500">{error}</p>}
+        </div>
+    );
+}
@@ -0,0 +1,262 @@
+import { useEffect, useState } from "react";
+import { invoke } from "@tauri-apps/api/core";
+
+import {
+    CREDENTIAL_IMAGE_HOST_S3_SECRET,
+    CREDENTIAL_IMAGE_HOST_TOKEN,
+    CREDENTIAL_IMGUR_CLIENT_ID,
+    setCredential,
+} from "../../lib/credentials";
+
+/** Mirrors `ImageHostSettings` in the backend. */
+interface ImageHostConfig {
+    provider: "none" | "s3" | "imgur" | "custom";
+    hosted_images: boolean;
+    s3: {
+        endpoint: string;
+        region: string;
+        bucket: string;
+        prefix: string;
+        access_key_id: string;
+        path_style: boolean;
+    };
+    s3_link_expiry_hours: number;
+    s3_public_url: string;
+    custom_url: string;
+    custom_field: string;
+    custom_url_pointer: string;
+}
+
+const inputClass =
+    "w-full px-4 py-2 bg-[#161316]/70 border border-white/10 rounded-md text-white placeholder-white/50 focus:outline-none focus:border-[#2721E8]";
+
+/** Where Markdown and HTML exports upload their screenshots. */
+export default function ImageHostSettings() {
+    const [host, setHost] = useState<ImageHostConfig | null>(null);
+    const [secret, setSecret] = useState("");
+    const [error, setError] = useState<string | null>(null);
+    const [saved, setSaved] = useState(false);
+
+    useEffect(() => {
+        invoke<{ image_host: ImageHostConfig }>("get_settings")
+            .then((settings) => setHost(settings.image_host))
+            .catch((err) => console.error("Failed to read image host settings:", err));
+    }, []);
+
+    if (!host) {
+        return null;
+    }
+
+    const edit = (changes: Partial<ImageHostConfig>) => {
+        setHost({ ...host, ...changes });
+        setSaved(false);
+    };
+
+    const secretCredential = {
+        none: null,
+        s3: CREDENTIAL_IMAGE_HOST_S3_SECRET,
+        imgur: CREDENTIAL_IMGUR_CLIENT_ID,
+        custom: CREDENTIAL_IMAGE_HOST_TOKEN,
+    }[host.provider];
+
+    const handleSave = async () => {
+        try {
+            await invoke("update_settings", { patch: { image_host: host } });
+            if (secretCredential && secret.trim()) {
+                await setCredential(secretCredential, secret.trim());
+                setSecret("");
+            }
+            setError(null);
+            setSaved(true);
+        } catch (err) {
+            setError(String(err));
+        }
+    };
+
+    const secretInput = (placeholder: string) => (
+        <input
+            type="password"
+            value={secret}
+            onChange={(e) => {
+                setSecret(e.target.value);
+                setSaved(false);
+            }}
+            placeholder={placeholder}
+            className={inputClass}
+        />
+    );
+
+    return (
+        <div className="border-t border-white/8 pt-6 space-y-4">
+            <div className="flex items-center justify-between">
+                <div className="pr-4">
+                    <label className="block text-sm font-medium text-white/80">Hosted Images</label>
+                    <p className="text-xs text-white/50 mt-1">
+                        Markdown and HTML exports upload their screenshots and link them, so the files stay small and work anywhere.
+                    </p>
+                </div>
+                <button
+                    aria-label={`Hosted images: ${host.hosted_images ? "enabled" : "disabled"}`}
+                    onClick={() => edit({ hosted_images: !host.hosted_images })}
+                    className={`relative inline-flex h-6 w-11 items-center rounded-full transition-colors flex-shrink-0 ${
+                        host.hosted_images ? "bg-[#2721E8]" : "bg-white/20"
+                    }`}
+                >
+                    <span
+                        className={`inline-block h-4 w-4 transform rounded-full bg-white transition-transform ${
+                            host.hosted_images ? "translate-x-6" : "translate-x-1"
+                        }`}
+                    />
+                </button>
+            </div>
+
+            <div>
+                <label className="block text-sm font-medium text-white/60 mb-2">Image host</label>
+                <select
+                    value={host.provider}
+                    onChange={(e) => {
+                        edit({ provider: e.target.value as ImageHostConfig["provider"] });
+                        setSecret("");
+                    }}
+                    className={inputClass}
+                >
+                    <option value="none">None</option>
+                    <option value="s3">S3 bucket</option>
+                    <option value="imgur">Imgur</option>
+                    <option value="custom">Custom upload endpoint</option>
+                </select>
+            </div>
+
+            {host.provider === "s3" && (
+                <div className="space-y-3">
+                    <div className="flex gap-2">
+                        <input
+                            type="text"
+                            value={host.s3.bucket}
+                            onChange={(e) => edit({ s3: { ...host.s3, bucket: e.target.value } })}
+                            placeholder="Bucket"
+                            className={inputClass}
+                        />
+                        <input
+                            type="text"
+                            value={host.s3.prefix}
+                            onChange={(e) => edit({ s3: { ...host.s3, prefix: e.target.value } })}
+                            placeholder="Folder (optional)"
+                            className={inputClass}
+                        />
+                    </div>
+                    <div className="flex gap-2">
+                        <input
+                            type="text"
+                            value={host.s3.region}
+                            onChange={(e) => edit({ s3: { ...host.s3, region: e.target.value } })}
+                            placeholder="Region (us-east-1)"
+                            className={inputClass}
+                        />
+                        <input
+                            type="text"
+                            value={host.s3.endpoint}
+                            onChange={(e) => edit({ s3: { ...host.s3, endpoint: e.target.value } })}
+                            placeholder="Endpoint (empty for AWS)"
+                            className={inputClass}
+                        />
+                    </div>
+                    <div className="flex gap-2">
+                        <input
+                            type="text"
+                            value={host.s3.access_key_id}
+                            onChange={(e) => edit({ s3: { ...host.s3, access_key_id: e.target.value } })}
+                            placeholder="Access key ID"
+                            className={inputClass}
+                        />
+                        {secretInput("Secret key (unchanged)")}
+                    </div>
+                    <div className="flex gap-2">
+                        <input
+                            type="text"
+                            value={host.s3_public_url}
+                            onChange={(e) => edit({ s3_public_url: e.target.value })}
+                            placeholder="Public address of the bucket (if different)"
+                            className={inputClass}
+                        />
+                        <div className="w-56">
+                            <input
+                                type="number"
+                                min="0"
+                                max="168"
+                                value={host.s3_link_expiry_hours}
+                                onChange={(e) => edit({ s3_link_expiry_hours: parseInt(e.target.value) || 0 })}
+                                title="Hours presigned links last; 0 for a public bucket"
+                                className={inputClass}
+                            />
+                        </div>
+                    </div>
+                    <label className="flex items-center gap-3 text-sm text-white/80">
+                        <input
+                            type="checkbox"
+                            checked={host.s3.path_style}
+                            onChange={(e) => edit({ s3: { ...host.s3, path_style: e.target.checked } })}
+                            className="accent-[#2721E8]"
+                        />
+                        Path-style URLs (MinIO and most S3-compatible services)
+                    </label>
+                    <p className="text-xs text-white/50">
+                        Link expiry in hours signs each link for up to 7 days; leave it at 0 for a bucket anyone can read.
+                    </p>
+                </div>
+            )}
+
+            {host.provider === "imgur" && (
+                <div className="space-y-3">
+                    {secretInput("Client ID (unchanged)")}
+                    <p className="text-xs text-white/50">
+                        Register an application at api.imgur.com to get a client ID. Uploads are anonymous and public.
+                    </p>
+                </div>
+            )}
+
+            {host.provider === "custom" && (
+                <div className="space-y-3">
+                    <input
+                        type="text"
+                        value={host.custom_url}
+                        onChange={(e) => edit({ custom_url: e.target.value })}
+                        placeholder="Upload URL, e.g. https://img.example.com/upload"
+                        className={inputClass}
+                    />
+                    <div className="flex gap-2">
+                        <input
+                            type="text"
+                            value={host.custom_field}
+                            onChange={(e) => edit({ custom_field: e.target.value })}
+                            placeholder="Form field (file)"
+                            className={inputClass}
+                        />
+                        <input
+                            type="text"
+                            value={host.custom_url_pointer}
+                            onChange={(e) => edit({ custom_url_pointer: e.target.value })}
+                            placeholder="URL in the response, e.g. /data/url"
+                            className={inputClass}
+                        />
+                    </div>
+                    {secretInput("Bearer token (unchanged, optional)")}
+                    <p className="text-xs text-white/50">
+                        Images are sent as a multipart POST. Leave the response path empty when the endpoint answers with just the URL.
+                    </p>
+                </div>
+            )}
+
+            <div className="flex items-center gap-3">
+                <button
+                    onClick={handleSave}
+                    className="px-4 py-2 rounded-md text-sm bg-[#2721E8] text-white hover:bg-[#2721E8]/90 transition-colors"
+                >
+                    Save
+                </button>
+                {saved && <span className="text-xs text-white/60">Saved</span>}
+                {error && <span className="text-xs text-red-400">{error}</span>}
+            </div>
+        </div>
+    );
+}
@@ -0,0 +1,199 @@
+import { useCallback, useEffect, useState } from "react";
+import { invoke } from "@tauri-apps/api/core";
+
+/** Mirrors `UsageReport` in the backend. */
+interface UsageReport {
+    enabled: boolean;
+    days: number;
+    features: { feature: string; count: number }[];
+    daily: { day: string; feature: string; count: number }[];
+    metrics: {
+        metric: string;
+        samples: number;
+        mean_ms: number;
+        p50_ms: number;
+        p95_ms: number;
+        max_ms: number;
+    }[];
+}
+
+const PERIODS = [7, 30, 90];
+
+const FEATURE_LABELS: Record<string, string> = {
+    recording_started: "Recordings started",
+    step_captured: "Steps captured",
+    marker_added: "Markers added",
+    manual_capture: "Manual captures",
+    image_edit: "Image edits",
+    redaction: "Redactions",
+    ocr_region: "Region OCR",
+    ai_generation: "AI generations",
+    markdown_export: "Markdown exports",
+    recording_archived: "Recordings archived",
+    diagnostics_bundle: "Diagnostics bundles",
+    data_relocated: "Data moves",
+};
+
+const METRIC_LABELS: Record<string, string> = {
+    screenshot_capture: "Screenshot capture",
+    screenshot_encode: "JPEG encoding",
+};
+
+const formatMs = (ms: number) => (ms < 10 ? ms.toFixed(1) : Math.round(ms).toString());
+
+export default function InsightsSection() {
+    const [enabled, setEnabled] = useState(false);
+    const [days, setDays] = useState(7);
+    const [report, setReport] = useState<UsageReport | null>(null);
+    const [error, setError] = useState<string | null>(null);
+
+    const loadReport = useCallback(() => {
+        invoke<UsageReport>("get_usage_report", { days })
+            .then((report) => {
+                setReport(report);
+                setEnabled(report.enabled);
+            })
+            .catch((err) => setError(String(err)));
+    }, [days]);
+
+    useEffect(() => {
+        loadReport();
+    }, [loadReport]);
+
+    const handleToggle = async () => {
+        try {
+            await invoke("update_settings", { patch: { analytics_enabled: !enabled } });
+            setEnabled(!enabled);
+            setError(null);
+        } catch (err) {
+            setError(String(err));
+        }
+    };
+
+    const handleClear = async () => {
+        try {
+            await invoke("clear_usage_data");
+            loadReport();
+        } catch (err) {
+            setError(String(err));
+        }
+    };
+
+    const maxCount = Math.max(1, ...(report?.features.map((f) => f.count) ?? []));
+
+    return (
+        <div className="space-y-6">
+            <div>
+                <h3 className="text-lg font-medium text-white mb-1">Insights</h3>
+                <p className="text-xs text-white/50">
+                    How you use StepSnap and how fast captures are, kept on this computer only.
+                </p>
+            </div>
+
+            <div className="flex items-center justify-between">
+                <div className="pr-4">
+                    <label className="block text-sm font-medium text-white/80">Record usage locally</label>
+                    <p className="text-xs text-white/50 mt-1">
+                        Counts feature use per day and times screenshot capture and encoding. Nothing is sent anywhere; data older than 90 days is deleted.
+                    </p>
+                </div>
+                <button
+                    aria-label={`Usage analytics: ${enabled ? "enabled" : "disabled"}`}
+                    onClick={handleToggle}
+                    className={`relative inline-flex h-6 w-11 items-center rounded-full transition-colors flex-shrink-0 ${
+                        enabled ? "bg-[#2721E8]" : "bg-white/20"
+                    }`}
+                >
+                    <span
+                        className={`inline-block h-4 w-4 transform rounded-full bg-white transition-transform ${
+                            enabled ? "translate-x-6" : "translate-x-1"
+                        }`}
+                    />
+                </button>
+            </div>
+
+            <div className="flex items-center justify-between">
+                <div className="flex gap-2">
+                    {PERIODS.map((period) => (
+                        <button
+                            key={period}
+                            onClick={() => setDays(period)}
+                            className={`px-3 py-1.5 rounded-lg text-sm transition-colors ${
+                                days === period
+                                    ? "bg-[#2721E8] text-white"
+                                    : "bg-white/10 text-white/60 hover:bg-white/20"
+                            }`}
+                        >
+                            {period} days
+                        </button>
+                    ))}
+                </div>
+                <button
+                    onClick={handleClear}
+                    disabled={!report || (report.features.length === 0 && report.metrics.length === 0)}
+                    className="text-xs text-white/60 hover:text-white transition-colors disabled:opacity-50"
+                >
+                    Clear data
+                </button>
+            </div>
+
+            <div>
+                <label className="block text-sm font-medium text-white/80 mb-2">Feature Use</label>
+                {!report || report.features.length === 0 ? (
+                    <p className="text-xs text-white/40">
+                        {enabled ? "Nothing recorded in this period yet" : "Turn on local usage recording to see feature use"}
+                    </p>
+                ) : (
+                    <div className="space-y-1.5">
+                        {report.features.map(({ feature, count }) => (
+                            <div key={feature} className="flex items-center gap-3 text-sm">
+                                <span className="w-40 truncate text-white/70">
+                                    {FEATURE_LABELS[feature] ?? feature}
+                                </span>
+                                <div className="flex-1 h-2 rounded-full bg-white/5">
+                                    <div
+                                        className="h-2 rounded-full bg-[#2721E8]"
+                                        style={{ width: `${(count / maxCount) * 100}%` }}
+                                    />
+                                </div>
+                                <span className="w-10 text-right text-xs text-white/50">{count}</span>
+                            </div>
+                        ))}
+                    </div>
+                )}
+            </div>
+
+            <div>
+                <label className="block text-sm font-medium text-white/80 mb-2">Capture Performance</label>
+                {!report || report.metrics.length === 0 ? (
+                    <p className="text-xs text-white/40">No captures timed in this period yet</p>
+                ) : (
+                    <table className="w-full text-sm">
+                        <thead>
+                            <tr className="text-xs text-white/40 text-left">
+                                <th className="font-normal pb-1">Stage</th>
+                                <th className="font-normal pb-1 text-right">Samples</th>
+                                <th className="font-normal pb-1 text-right">Median</th>
+                                <th className="font-normal pb-1 text-right">95th %</th>
+                                <th className="font-normal pb-1 text-right">Max</th>
+                            </tr>
+                        </thead>
+                        <tbody>
+                            {report.metrics.map((metric) => (
+                                <tr key={metric.metric} className="text-white/70">
+                                    <td className="py-0.5">{METRIC_LABELS[metric.metric] ?? metric.metric}</td>
+                                    <td className="py-0.5 text-right">{metric.samples}</td>
+                                    <td className="py-0.5 text-right">{formatMs(metric.p50_ms)} ms</td>
+                                    <td className="py-0.5 text-right">{formatMs(metric.p95_ms)} ms</td>
+                                    <td className="py-0.5 text-right">{formatMs(metric.max_ms)} ms</td>
+                                </tr>
+                            ))}
+                        </tbody>
+                    </table>
+                )}
+            </div>
+
+            {error && <p className="text-xs text-red-500">{error}</p>}
+        </div>
+    );
+}
@@ -0,0 +1,175 @@
+import { useEffect, useState } from "react";
+import { Copy, RefreshCw } from "lucide-react";
+import { invoke } from "@tauri-apps/api/core";
+
+import ChatSettings from "./ChatSettings";
+import EmailSettings from "./EmailSettings";
+import GitPublishSettings from "./GitPublishSettings";
+import ImageHostSettings from "./ImageHostSettings";
+import ShareSettings from "./ShareSettings";
+import SyncSettings from "./SyncSettings";
+import TicketSettings from "./TicketSettings";
+
+/** Mirrors `ApiServerStatus` in the backend. */
+interface ApiServerStatus {
+    running: boolean;
+    url: string | null;
+    token: string | null;
+}
+
+export default function IntegrationsSection() {
+    const [status, setStatus] = useState<ApiServerStatus | null>(null);
+    const [port, setPort] = useState("");
+    const [error, setError] = useState<string | null>(null);
+
+    useEffect(() => {
+        invoke<ApiServerStatus>("get_api_server_status")
+            .then(setStatus)
+            .catch((err) => setError(String(err)));
+        invoke<{ api_server: { port: number } }>("get_settings")
+            .then((settings) => setPort(String(settings.api_server.port)))
+            .catch((err) => console.error("Failed to read API server settings:", err));
+    }, []);
+
+    const handleToggle = async () => {
+        try {
+            const parsedPort = Number.parseInt(port, 10);
+            const updated = await invoke<ApiServerStatus>("set_api_server_enabled", {
+                enabled: !status?.running,
+                port: Number.isNaN(parsedPort) ? null : parsedPort,
+            });
+            setStatus(updated);
+            setError(null);
+        } catch (err) {
+            setError(String(err));
+        }
+    };
+
+    const handleRegenerate = async () => {
+        try {
+            setStatus(await invoke<ApiServerStatus>("regenerate_api_server_token"));
+            setError(null);
+        } catch (err) {
+            setError(String(err));
+        }
+    };
+
+    const running = status?.running ?? false;
+
+    return (
+        <div className="space-y-6">
+            <div>
+                <h3 className="text-lg font-medium text-white mb-1">Integrations</h3>
+                <p className="text-xs text-white/50">
+                    Let scripts and other tools read and update your recordings.
+                </p>
+            </div>
+
+            <div className="flex items-center justify-between">
+                <div className="pr-4">
+                    <label className="block text-sm font-medium text-white/80">Local HTTP API</label>
+                    <p className="text-xs text-white/50 mt-1">
+                        Serves recordings, steps, screenshots and Markdown exports, and an MCP server for AI assistants, on this computer only. Requests must send the token as a Bearer authorization header.
+                    </p>
+                </div>
+                <button
+                    aria-label={`Local HTTP API: ${running ? "enabled" : "disabled"}`}
+                    onClick={handleToggle}
+                    className={`relative inline-flex h-6 w-11 items-center rounded-full transition-colors flex-shrink-0 ${
+                        running ? "bg-[#2721E8]" : "bg-white/20"
+                    }`}
+                >
+                    <span
+                        className={`inline-block h-4 w-4 transform rounded-full bg-white transition-transform ${
+                            running ? "translate-x-6" : "translate-x-1"
+                        }`}
+                    />
+                </button>
+            </div>
+
+            <div>
+                <label className="block text-sm font-medium text-white/80 mb-2">Port</label>
+                <input
+                    type="number"
+                    min={1024}
+                    max={65535}
+                    value={port}
+                    onChange={(e) => setPort(e.target.value)}
+                    disabled={running}
+                    className="w-32 px-4 py-2 bg-[#161316]/70 border border-white/10 rounded-md text-white focus:outline-none focus:border-[#2721E8] disabled:opacity-50"
+                />
+                <p className="mt-1 text-xs text-white/50">Turn the API off to change the port.</p>
+            </div>
+
+            {running && status?.url && status.token && (
+                <div className="space-y-3">
+                    <div>
+                        <label className="block text-sm font-medium text-white/80 mb-2">Address</label>
+                        <code className="block px-4 py-2 bg-[#161316]/70 border border-white/10 rounded-md text-sm text-white/80">
+                            {status.url}/api/v1/recordings
+                        </code>
+                    </div>
+                    <div>
+                        <label className="block text-sm font-medium text-white/80 mb-2">MCP Endpoint</label>
+                        <code className="block px-4 py-2 bg-[#161316]/70 border border-white/10 rounded-md text-sm text-white/80">
+                            {status.url}/mcp
+                        </code>
+                        <p className="mt-1 text-xs text-white/50">
+                            Add it to an AI assistant as a Streamable HTTP MCP server, with the token below, to let it list, search and read your recordings.
+                        </p>
+                    </div>
+                    <div>
+                        <label className="block text-sm font-medium text-white/80 mb-2">Event Stream</label>
+                        <code className="block px-4 py-2 bg-[#161316]/70 border border-white/10 rounded-md text-sm text-white/80 truncate">
+                            {status.url.replace(/^http/, "ws")}/api/v1/events?token=...
+                        </code>
+                        <p className="mt-1 text-xs text-white/50">
+                            A WebSocket that sends new steps, recording start and stop and OCR results as they happen, for overlays and second-screen viewers.
+                        </p>
+                    </div>
+                    <div>
+                        <label className="block text-sm font-medium text-white/80 mb-2">Token</label>
+                        <div className="flex gap-2">
+                            <code className="flex-1 px-4 py-2 bg-[#161316]/70 border border-white/10 rounded-md text-sm text-white/80 truncate">
+                                {status.token}
+                            </code>
+                            <button
+                                aria-label="Copy token"
+                                onClick={() => navigator.clipboard.writeText(status.token ?? "")}
+                                className="px-3 py-2 bg-white/10 border border-white/10 rounded-md hover:bg-white/15 transition-colors"
+                            >
+                                <Copy size={16} className="text-white/70" />
+                            </button>
+                            <button
+                                aria-label="Generate a new token"
+                                onClick={handleRegenerate}
+                                className="px-3 py-2 bg-white/10 border border-white/10 rounded-md hover:bg-white/15 transition-colors"
+                            >
+                                <RefreshCw size={16} className="text-white/70" />
+                            </button>
+                        </div>
+                        <p className="mt-1 text-xs text-white/50">
+                            Kept in your system keychain. A new token locks out clients using the old one.
+                        </p>
+                    </div>
+                </div>
+            )}
+
+            {error && <p className="text-xs text-red-500">{error}</p>}
+
+            <GitPublishSettings />
+
+            <EmailSettings />
+
+            <ChatSettings />
+
+            <TicketSettings />
+
+            <SyncSettings />
+
+            <ShareSettings />
+
+            <ImageHostSettings />
+        </div>
+    );
+}
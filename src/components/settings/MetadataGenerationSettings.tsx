@@ -0,0 +1,173 @@
+import { useEffect, useState } from "react";
+import { invoke } from "@tauri-apps/api/core";
+import { aiProviderCredential, setCredential } from "../../lib/credentials";
+
+type MetadataProvider = "openai" | "anthropic" | "ollama";
+
+interface ProviderConfig {
+    base_url: string;
+    model: string;
+}
+
+/** Mirrors `AiSettings` in the backend. */
+interface AiSettings {
+    provider: MetadataProvider;
+    openai: ProviderConfig;
+    anthropic: ProviderConfig;
+    ollama: ProviderConfig;
+    send_screenshots: boolean;
+}
+
+const PROVIDER_OPTIONS: { value: MetadataProvider; label: string; baseUrl: string; model: string }[] = [
+    { value: "openai", label: "OpenAI", baseUrl: "https://api.openai.com/v1", model: "gpt-4o-mini" },
+    { value: "anthropic", label: "Anthropic", baseUrl: "https://api.anthropic.com/v1", model: "claude-sonnet-4-20250514" },
+    { value: "ollama", label: "Ollama", baseUrl: "http://localhost:11434/v1", model: "llama3.1" },
+];
+
+/**
+ * Settings of "Rewrite step text", which sends step metadata rather than
+ * screenshots to a provider configured separately from the editor's.
+ */
+export default function MetadataGenerationSettings() {
+    const [ai, setAi] = useState<AiSettings | null>(null);
+    const [apiKey, setApiKey] = useState("");
+    const [error, setError] = useState<string | null>(null);
+    const [saved, setSaved] = useState(false);
+
+    useEffect(() => {
+        invoke<{ ai: AiSettings }>("get_settings")
+            .then((settings) => setAi(settings.ai))
+            .catch((err) => console.error("Failed to read AI settings:", err));
+    }, []);
+
+    if (!ai) {
+        return null;
+    }
+
+    const provider = PROVIDER_OPTIONS.find((option) => option.value === ai.provider) ?? PROVIDER_OPTIONS[0];
+    const config = ai[ai.provider];
+
+    const edit = (changes: Partial<AiSettings>) => {
+        setAi({ ...ai, ...changes });
+        setSaved(false);
+    };
+
+    const editConfig = (changes: Partial<ProviderConfig>) => {
+        edit({ [ai.provider]: { ...config, ...changes } });
+    };
+
+    const handleSave = async () => {
+        try {
+            await invoke("update_settings", { patch: { ai } });
+            if (apiKey.trim()) {
+                await setCredential(aiProviderCredential(ai.provider), apiKey.trim());
+                setApiKey("");
+            }
+            setError(null);
+            setSaved(true);
+        } catch (err) {
+            setError(String(err));
+        }
+    };
+
+    return (
+        <div className="border-t border-white/8 pt-6">
+            <div className="mb-4">
+                <h4 className="text-sm font-medium text-white/80">Rewrite Step Text</h4>
+                <p className="text-xs text-white/50 mt-1">
+                    Rewrites step descriptions and writes an introduction from element names, app and page names and recognised screen text, without sending screenshots unless you allow it.
+                </p>
+            </div>
+
+            <div className="space-y-4">
+                <div className="grid grid-cols-3 gap-2">
+                    {PROVIDER_OPTIONS.map((option) => (
+                        <button
+                            key={option.value}
+                            onClick={() => edit({ provider: option.value })}
+                            className={`px-3 py-2 rounded-md text-sm transition-all ${
+                                ai.provider === option.value
+                                    ? 'bg-[#2721E8] text-white'
+                                    : 'bg-[#161316]/70 text-white/70 hover:bg-white/10'
+                            }`}
+                        >
+                            {option.label}
+                        </button>
+                    ))}
+                </div>
+
+                <div>
+                    <label className="block text-sm font-medium text-white/60 mb-2">Base URL</label>
+                    <input
+                        type="text"
+                        value={config.base_url}
+                        onChange={(e) => editConfig({ base_url: e.target.value })}
+                        placeholder={provider.baseUrl}
+                        className="w-full px-4 py-2 bg-[#161316]/70 border border-white/10 rounded-md text-white placeholder-white/50 focus:outline-none focus:border-[#2721E8]"
+                    />
+                </div>
+
+                <div>
+                    <label className="block text-sm font-medium text-white/60 mb-2">Model</label>
+                    <input
+                        type="text"
+                        value={config.model}
+                        onChange={(e) => editConfig({ model: e.target.value })}
+                        placeholder={provider.model}
+                        className="w-full px-4 py-2 bg-[#161316]/70 border border-white/10 rounded-md text-white placeholder-white/50 focus:outline-none focus:border-[#2721E8]"
+                    />
+                </div>
+
+                {ai.provider !== "ollama" && (
+                    <div>
+                        <label className="block text-sm font-medium text-white/60 mb-2">API Key</label>
+                        <input
+                            type="password"
+                            value={apiKey}
+                            onChange={(e) => {
+                                setApiKey(e.target.value);
+                                setSaved(false);
+                            }}
+                            placeholder="Leave empty to use the AI provider's key"
+                            className="w-full px-4 py-2 bg-[#161316]/70 border border-white/10 rounded-md text-white placeholder-white/50 focus:outline-none focus:border-[#2721E8]"
+                        />
+                        <p className="mt-1 text-xs text-white/50">Stored in your system keychain.</p>
+                    </div>
+                )}
+
+                <div className="flex items-center justify-between">
+                    <div className="pr-4">
+                        <label className="block text-sm font-medium text-white/80">Send screenshots</label>
+                        <p className="text-xs text-white/50 mt-1">
+                            Attach each step's screenshot for more accurate text. Screenshots may show private information.
+                        </p>
+                    </div>
+                    <button
+                        aria-label={`Send screenshots: ${ai.send_screenshots ? 'enabled' : 'disabled'}`}
+                        onClick={() => edit({ send_screenshots: !ai.send_screenshots })}
+                        className={`relative inline-flex h-6 w-11 items-center rounded-full transition-colors flex-shrink-0 ${
+                            ai.send_screenshots ? 'bg-[#2721E8]' : 'bg-white/20'
+                        }`}
+                    >
+                        <span
+                            className={`inline-block h-4 w-4 transform rounded-full bg-white transition-transform ${
+                                ai.send_screenshots ? 'translate-x-6' : 'translate-x-1'
+                            }`}
+                        />
+                    </button>
+                </div>
+
+                <div className="flex items-center gap-3">
+                    <button
+                        onClick={handleSave}
+                        className="px-4 py-2 rounded-md text-sm bg-[#2721E8] text-white hover:bg-[#2721E8]/90 transition-colors"
+                    >
+                        Save
+                    </button>
+                    {saved && <span className="text-xs text-white/60">Saved</span>}
+                    {error && <span className="text-xs text-red-400">{error}</span>}
+                </div>
+            </div>
+        </div>
+    );
+}
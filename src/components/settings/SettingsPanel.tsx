@@ -5,6 +5,10 @@ import {
     Wand2,
     ShieldCheck,
     Keyboard,
+    HardDrive,
+    Globe,
+    BarChart3,
+    Plug,
     X,
     type LucideIcon,
 } from "lucide-react";
@@ -16,8 +20,21 @@ import AiProviderSection from "./AiProviderSection";
 import GenerationSection from "./GenerationSection";
 import ReliabilitySection from "./ReliabilitySection";
 import ShortcutsSection from "./ShortcutsSection";
+import StorageSection from "./StorageSection";
+import NetworkSection from "./NetworkSection";
+import InsightsSection from "./InsightsSection";
+import IntegrationsSection from "./IntegrationsSection";
 
-type SectionId = "general" | "ai" | "generation" | "reliability" | "shortcuts";
+type SectionId =
+    | "general"
+    | "ai"
+    | "generation"
+    | "reliability"
+    | "shortcuts"
+    | "storage"
+    | "network"
+    | "insights"
+    | "integrations";
 
 interface SectionDef {
     id: SectionId;
@@ -32,6 +49,10 @@ const SECTIONS: SectionDef[] = [
     { id: "generation", label: "Generation", icon: Wand2, Component: GenerationSection },
     { id: "reliability", label: "Reliability", icon: ShieldCheck, Component: ReliabilitySection },
     { id: "shortcuts", label: "Shortcuts", icon: Keyboard, Component: ShortcutsSection },
+    { id: "storage", label: "Storage", icon: HardDrive, Component: StorageSection },
+    { id: "network", label: "Network", icon: Globe, Component: NetworkSection },
+    { id: "insights", label: "Insights", icon: BarChart3, Component: InsightsSection },
+    { id: "integrations", label: "Integrations", icon: Plug, Component: IntegrationsSection },
 ];
 
 export default function SettingsPanel() {
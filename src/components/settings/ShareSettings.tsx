@@ -0,0 +1,150 @@
+import { useEffect, useState } from "react";
+import { invoke } from "@tauri-apps/api/core";
+
+import { CREDENTIAL_SHARE_UPLOAD_PASSWORD, setCredential } from "../../lib/credentials";
+
+/** Mirrors `ShareSettings` in the backend. */
+interface ShareConfig {
+    port: number;
+    public_url: string;
+    upload_url: string;
+    upload_username: string;
+    upload_public_url: string;
+    default_expiry_hours: number;
+}
+
+const inputClass =
+    "w-full px-4 py-2 bg-[#161316]/70 border border-white/10 rounded-md text-white placeholder-white/50 focus:outline-none focus:border-[#2721E8]";
+
+/** Where shared links are served from or uploaded to. */
+export default function ShareSettings() {
+    const [share, setShare] = useState<ShareConfig | null>(null);
+    const [password, setPassword] = useState("");
+    const [error, setError] = useState<string | null>(null);
+    const [saved, setSaved] = useState(false);
+
+    useEffect(() => {
+        invoke<{ share: ShareConfig }>("get_settings")
+            .then((settings) => setShare(settings.share))
+            .catch((err) => console.error("Failed to read share settings:", err));
+    }, []);
+
+    if (!share) {
+        return null;
+    }
+
+    const edit = (changes: Partial<ShareConfig>) => {
+        setShare({ ...share, ...changes });
+        setSaved(false);
+    };
+
+    const handleSave = async () => {
+        try {
+            await invoke("update_settings", { patch: { share } });
+            if (password) {
+                await setCredential(CREDENTIAL_SHARE_UPLOAD_PASSWORD, password);
+                setPassword("");
+            }
+            setError(null);
+            setSaved(true);
+        } catch (err) {
+            setError(String(err));
+        }
+    };
+
+    return (
+        <div className="border-t border-white/8 pt-6 space-y-4">
+            <div>
+                <label className="block text-sm font-medium text-white/80">Share Links</label>
+                <p className="text-xs text-white/50 mt-1">
+                    Links are served by this app to your network while it runs, or uploaded to a static host.
+                </p>
+            </div>
+
+            <div className="flex gap-2">
+                <div className="flex-1">
+                    <label className="block text-sm font-medium text-white/60 mb-2">Address others use</label>
+                    <input
+                        type="text"
+                        value={share.public_url}
+                        onChange={(e) => edit({ public_url: e.target.value })}
+                        placeholder="Automatic (this computer's network address)"
+                        className={inputClass}
+                    />
+                </div>
+                <div className="w-28">
+                    <label className="block text-sm font-medium text-white/60 mb-2">Port</label>
+                    <input
+                        type="number"
+                        min="1"
+                        max="65535"
+                        value={share.port}
+                        onChange={(e) => edit({ port: parseInt(e.target.value) || 0 })}
+                        className={inputClass}
+                    />
+                </div>
+                <div className="w-36">
+                    <label className="block text-sm font-medium text-white/60 mb-2">Expire after (hours)</label>
+                    <input
+                        type="number"
+                        min="1"
+                        value={share.default_expiry_hours}
+                        onChange={(e) => edit({ default_expiry_hours: parseInt(e.target.value) || 1 })}
+                        className={inputClass}
+                    />
+                </div>
+            </div>
+
+            <div className="space-y-3">
+                <label className="block text-sm font-medium text-white/60">Static host</label>
+                <input
+                    type="text"
+                    value={share.upload_url}
+                    onChange={(e) => edit({ upload_url: e.target.value })}
+                    placeholder="Upload folder, e.g. https://cloud.example.com/remote.php/dav/files/you/shared"
+                    className={inputClass}
+                />
+                <input
+                    type="text"
+                    value={share.upload_public_url}
+                    onChange={(e) => edit({ upload_public_url: e.target.value })}
+                    placeholder="Public address of that folder (if different)"
+                    className={inputClass}
+                />
+                <div className="flex gap-2">
+                    <input
+                        type="text"
+                        value={share.upload_username}
+                        onChange={(e) => edit({ upload_username: e.target.value })}
+                        placeholder="Username"
+                        className={inputClass}
+                    />
+                    <input
+                        type="password"
+                        value={password}
+                        onChange={(e) => {
+                            setPassword(e.target.value);
+                            setSaved(false);
+                        }}
+                        placeholder="Password (unchanged)"
+                        className={inputClass}
+                    />
+                </div>
+                <p className="text-xs text-white/50">
+                    Pages are uploaded with HTTP PUT. The password is stored in your system keychain.
+                </p>
+            </div>
+
+            <div className="flex items-center gap-3">
+                <button
+                    onClick={handleSave}
+                    className="px-4 py-2 rounded-md text-sm bg-[#2721E8] text-white hover:bg-[#2721E8]/90 transition-colors"
+                >
+                    Save
+                </button>
+                {saved && <span className="text-xs text-white/60">Saved</span>}
+                {error && <span className="text-xs text-red-400">{error}</span>}
+            </div>
+        </div>
+    );
+}
@@ -1,168 +1,294 @@
-import { useState } from "react";
-import { useSettingsStore, HotkeyBinding } from "../../store/settingsStore";
+import { useEffect, useState } from "react";
+import { invoke } from "@tauri-apps/api/core";
+import { useSettingsStore, HotkeyAction, HotkeyBinding, HotkeyConflict } from "../../store/settingsStore";
+import HotkeyProfiles from "./HotkeyProfiles";
 
-type HotkeyTarget = "start" | "stop" | "capture";
+const HOTKEY_ACTIONS: { action: HotkeyAction; label: string; optional: boolean }[] = [
+    { action: "start", label: "Start Recording", optional: false },
+    { action: "stop", label: "Stop Recording", optional: false },
+    { action: "capture", label: "Manual Capture (Screenshot)", optional: true },
+    { action: "pause", label: "Pause / Resume Recording", optional: true },
+    { action: "undo", label: "Undo Last Step", optional: true },
+    { action: "marker", label: "Add Marker", optional: true },
+];
 
-const formatHotkey = (hotkey: HotkeyBinding): string => {
+/** `MouseEvent.button` of the mouse buttons that can be bound, by binding key. */
+const MOUSE_BUTTON_KEYS: Record<number, string> = {
+    1: "MouseMiddle",
+    3: "Mouse4",
+    4: "Mouse5",
+};
+
+const isMouseButton = (key: string) => Object.values(MOUSE_BUTTON_KEYS).includes(key);
+
+const describeConflict = (conflict: HotkeyConflict): string => {
+    switch (conflict.kind) {
+        case "unsupported_key":
+            return `${conflict.key} can't be used in a global hotkey`;
+        case "action": {
+            const other = HOTKEY_ACTIONS.find(({ action }) => action === conflict.action);
+            return `Already used for ${other?.label ?? conflict.action}`;
+        }
+        case "system":
+            return `Taken by the system or another application (${conflict.message})`;
+    }
+};
+
+/** A key the backend can register, as returned by `list_supported_hotkeys`. */
+interface SupportedKey {
+    code: string;
+    label: string;
+    group: string;
+}
+
+const IS_MAC = navigator.userAgent.includes("Mac");
+/** What the meta modifier is called on this platform. */
+const META_LABEL = IS_MAC ? "Cmd" : "Win";
+
+const formatHotkey = (hotkey: HotkeyBinding, keyLabels: Map<string, string>): string => {
     const parts: string[] = [];
+    if (hotkey.meta) parts.push(META_LABEL);
     if (hotkey.ctrl) parts.push("Ctrl");
     if (hotkey.shift) parts.push("Shift");
     if (hotkey.alt) parts.push("Alt");
-    const keyName = hotkey.key.replace("Key", "").replace("Digit", "");
-    parts.push(keyName);
+    parts.push(keyLabels.get(hotkey.key) ?? hotkey.key.replace("Key", "").replace("Digit", ""));
     return parts.join(" + ");
 };
 
-const areHotkeysEqual = (a: HotkeyBinding, b: HotkeyBinding): boolean => {
-    return a.ctrl === b.ctrl && a.shift === b.shift && a.alt === b.alt && a.key === b.key;
+const areHotkeysEqual = (a: HotkeyBinding | null, b: HotkeyBinding | null): boolean => {
+    if (!a || !b) return false;
+    return a.ctrl === b.ctrl && a.shift === b.shift && a.alt === b.alt && a.meta === b.meta && a.key === b.key;
 };
 
 const getHotkeyWarning = (hotkey: HotkeyBinding): string | null => {
     const key = hotkey.key;
 
-    if (hotkey.ctrl && hotkey.shift && !hotkey.alt) {
+    if (hotkey.ctrl && hotkey.shift && !hotkey.alt && !hotkey.meta) {
         if (key === "KeyR") return "Conflicts with browser hard reload";
         if (key === "KeyI") return "Conflicts with browser dev tools";
         if (key === "KeyJ") return "Conflicts with browser downloads";
         if (key === "KeyN") return "Conflicts with incognito window";
     }
 
-    if (hotkey.ctrl && !hotkey.shift && !hotkey.alt) {
+    if (hotkey.ctrl && !hotkey.shift && !hotkey.alt && !hotkey.meta) {
         if (key === "KeyW") return "Conflicts with close tab";
         if (key === "KeyT") return "Conflicts with new tab";
         if (key === "KeyN") return "Conflicts with new window";
         if (key === "KeyQ") return "Conflicts with quit application";
     }
 
-    if (hotkey.alt && !hotkey.ctrl && !hotkey.shift) {
+    if (hotkey.alt && !hotkey.ctrl && !hotkey.shift && !hotkey.meta) {
         if (key === "F4") return "Conflicts with close window";
     }
 
-    if (!hotkey.ctrl && !hotkey.shift && !hotkey.alt) {
-        return "Hotkey should include at least one modifier (Ctrl, Shift, or Alt)";
+    if (hotkey.meta && !hotkey.ctrl && !hotkey.shift && !hotkey.alt) {
+        if (IS_MAC) {
+            if (key === "KeyQ") return "Conflicts with quit application";
+            if (key === "KeyW") return "Conflicts with close window";
+            if (key === "KeyH") return "Conflicts with hide application";
+            if (key === "Space") return "Conflicts with Spotlight";
+        } else {
+            if (key === "KeyL") return "Conflicts with lock screen";
+            if (key === "KeyD") return "Conflicts with show desktop";
+            if (key === "KeyE") return "Conflicts with File Explorer";
+            if (key === "KeyR") return "Conflicts with the Run dialog";
+        }
+    }
+
+    if (isMouseButton(key)) {
+        // The click still reaches whatever is under the pointer
+        if (key === "MouseMiddle" && !hotkey.ctrl && !hotkey.shift && !hotkey.alt && !hotkey.meta) {
+            return "Middle click is also used by applications, e.g. to open links in a new tab";
+        }
+        return null;
+    }
+
+    if (!hotkey.ctrl && !hotkey.shift && !hotkey.alt && !hotkey.meta) {
+        return `Hotkey should include at least one modifier (Ctrl, Shift, Alt, or ${META_LABEL})`;
     }
 
     return null;
 };
 
 export default function ShortcutsSection() {
-    const {
-        startRecordingHotkey,
-        stopRecordingHotkey,
-        captureHotkey,
-        setStartRecordingHotkey,
-        setStopRecordingHotkey,
-        setCaptureHotkey,
-    } = useSettingsStore();
-
-    const [capturingHotkey, setCapturingHotkey] = useState<HotkeyTarget | null>(null);
-
-    const handleHotkeyCapture = (e: React.KeyboardEvent, type: HotkeyTarget) => {
+    const { hotkeys, hotkeyFailures, hotkeysSuspended, setHotkey, setHotkeysSuspended } = useSettingsStore();
+
+    const [capturingHotkey, setCapturingHotkey] = useState<HotkeyAction | null>(null);
+    const [keyLabels, setKeyLabels] = useState<Map<string, string>>(new Map());
+    const [captureError, setCaptureError] = useState<string | null>(null);
+
+    // Pressing a current hotkey while rebinding would trigger it instead of
+    // reaching this page, so hotkeys are off until the capture ends
+    useEffect(() => {
+        if (!capturingHotkey) return;
+        invoke("set_hotkeys_suspended", { suspended: true }).catch((error) =>
+            console.error("Failed to suspend hotkeys:", error),
+        );
+        return () => {
+            const { hotkeysSuspended: suspended, setHotkeysSuspended } = useSettingsStore.getState();
+            setHotkeysSuspended(suspended).catch((error) => console.error("Failed to resume hotkeys:", error));
+        };
+    }, [capturingHotkey]);
+
+    useEffect(() => {
+        invoke<SupportedKey[]>("list_supported_hotkeys")
+            .then((keys) => setKeyLabels(new Map(keys.map((key) => [key.code, key.label]))))
+            .catch((error) => console.error("Failed to load supported hotkeys:", error));
+    }, []);
+
+    const handleHotkeyCapture = async (e: React.KeyboardEvent, action: HotkeyAction) => {
         e.preventDefault();
         if (e.key === "Escape") {
             setCapturingHotkey(null);
+            setCaptureError(null);
             return;
         }
         if (["Control", "Shift", "Alt", "Meta"].includes(e.key)) {
             return;
         }
-        const hotkey: HotkeyBinding = {
+        if (keyLabels.size > 0 && !keyLabels.has(e.code)) {
+            setCaptureError(`${e.code || e.key} can't be used in a global hotkey`);
+            return;
+        }
+        await captureHotkey(action, {
             ctrl: e.ctrlKey,
             shift: e.shiftKey,
             alt: e.altKey,
+            meta: e.metaKey,
             key: e.code,
-        };
-        if (type === "start") {
-            setStartRecordingHotkey(hotkey);
-        } else if (type === "stop") {
-            setStopRecordingHotkey(hotkey);
-        } else {
-            setCaptureHotkey(hotkey);
+        });
+    };
+
+    const handleMouseCapture = async (e: React.MouseEvent, action: HotkeyAction) => {
+        const key = MOUSE_BUTTON_KEYS[e.button];
+        if (!key) return;
+        e.preventDefault();
+        await captureHotkey(action, {
+            ctrl: e.ctrlKey,
+            shift: e.shiftKey,
+            alt: e.altKey,
+            meta: e.metaKey,
+            key,
+        });
+    };
+
+    const captureHotkey = async (action: HotkeyAction, hotkey: HotkeyBinding) => {
+        try {
+            const conflict = await invoke<HotkeyConflict | null>("validate_hotkey", { binding: hotkey, action });
+            if (conflict) {
+                setCaptureError(`${formatHotkey(hotkey, keyLabels)}: ${describeConflict(conflict)}`);
+                return;
+            }
+        } catch (error) {
+            console.error("Failed to validate hotkey:", error);
         }
+        setCaptureError(null);
+        setHotkey(action, hotkey);
         setCapturingHotkey(null);
     };
 
-    const startWarning = getHotkeyWarning(startRecordingHotkey);
-    const stopWarning = getHotkeyWarning(stopRecordingHotkey);
-    const captureWarning = getHotkeyWarning(captureHotkey);
-    const hotkeysMatch =
-        areHotkeysEqual(startRecordingHotkey, stopRecordingHotkey) ||
-        areHotkeysEqual(startRecordingHotkey, captureHotkey) ||
-        areHotkeysEqual(stopRecordingHotkey, captureHotkey);
+    const bindings = HOTKEY_ACTIONS.map(({ action }) => hotkeys[action]);
+    const hotkeysMatch = bindings.some((binding, i) => bindings.slice(i + 1).some((other) => areHotkeysEqual(binding, other)));
 
     return (
         <div className="space-y-6">
             <div>
                 <h3 className="text-lg font-medium text-white mb-1">Keyboard Shortcuts</h3>
-                <p className="text-xs text-white/50">Click on a field and press your desired key combination.</p>
+                <p className="text-xs text-white/50">Click on a field and press your desired key combination, or a spare mouse button (middle, back or forward).</p>
             </div>
 
-            <div className="space-y-4">
-                <div>
-                    <label className="block text-sm font-medium text-white/80 mb-2">
-                        Start Recording
-                    </label>
-                    <button
-                        onClick={() => setCapturingHotkey("start")}
-                        onKeyDown={(e) => capturingHotkey === "start" && handleHotkeyCapture(e, "start")}
-                        className={`w-full px-4 py-2 bg-[#161316]/70 backdrop-blur-sm border rounded-md text-left font-mono text-sm transition-colors ${
-                            capturingHotkey === "start"
-                                ? "border-[#2721E8] text-[#49B8D3]"
-                                : startWarning
-                                ? "border-yellow-600 text-white hover:border-yellow-500"
-                                : "border-white/10 text-white hover:border-white/20"
-                        }`}
-                    >
-                        {capturingHotkey === "start" ? "Press keys..." : formatHotkey(startRecordingHotkey)}
-                    </button>
-                    {startWarning && (
-                        <p className="mt-1 text-xs text-yellow-500">{startWarning}</p>
-                    )}
+            <div className="flex items-center justify-between">
+                <div className="pr-4">
+                    <label className="block text-sm font-medium text-white/80">Suspend All Hotkeys</label>
+                    <p className="text-xs text-white/50 mt-1">
+                        Release every shortcut, e.g. while playing a game that needs the same keys. Hotkeys come back when the app restarts.
+                    </p>
                 </div>
-
-                <div>
-                    <label className="block text-sm font-medium text-white/80 mb-2">
-                        Stop Recording
-                    </label>
-                    <button
-                        onClick={() => setCapturingHotkey("stop")}
-                        onKeyDown={(e) => capturingHotkey === "stop" && handleHotkeyCapture(e, "stop")}
-                        className={`w-full px-4 py-2 bg-[#161316]/70 backdrop-blur-sm border rounded-md text-left font-mono text-sm transition-colors ${
-                            capturingHotkey === "stop"
-                                ? "border-[#2721E8] text-[#49B8D3]"
-                                : stopWarning
-                                ? "border-yellow-600 text-white hover:border-yellow-500"
-                                : "border-white/10 text-white hover:border-white/20"
+                <button
+                    aria-label={`Suspend all hotkeys: ${hotkeysSuspended ? "enabled" : "disabled"}`}
+                    onClick={() => {
+                        setHotkeysSuspended(!hotkeysSuspended).catch((error) =>
+                            console.error("Failed to suspend hotkeys:", error),
+                        );
+                    }}
+                    className={`relative inline-flex h-6 w-11 items-center rounded-full transition-colors flex-shrink-0 ${
+                        hotkeysSuspended ? "bg-[#2721E8]" : "bg-white/20"
+                    }`}
+                >
+                    <span
+                        className={`inline-block h-4 w-4 transform rounded-full bg-white transition-transform ${
+                            hotkeysSuspended ? "translate-x-6" : "translate-x-1"
                         }`}
-                    >
-                        {capturingHotkey === "stop" ? "Press keys..." : formatHotkey(stopRecordingHotkey)}
-                    </button>
-                    {stopWarning && (
-                        <p className="mt-1 text-xs text-yellow-500">{stopWarning}</p>
-                    )}
-                </div>
+                    />
+                </button>
+            </div>
 
-                <div>
-                    <label className="block text-sm font-medium text-white/80 mb-2">
-                        Manual Capture (Screenshot)
-                    </label>
-                    <button
-                        onClick={() => setCapturingHotkey("capture")}
-                        onKeyDown={(e) => capturingHotkey === "capture" && handleHotkeyCapture(e, "capture")}
-                        className={`w-full px-4 py-2 bg-[#161316]/70 backdrop-blur-sm border rounded-md text-left font-mono text-sm transition-colors ${
-                            capturingHotkey === "capture"
-                                ? "border-[#2721E8] text-[#49B8D3]"
-                                : captureWarning
-                                ? "border-yellow-600 text-white hover:border-yellow-500"
-                                : "border-white/10 text-white hover:border-white/20"
-                        }`}
-                    >
-                        {capturingHotkey === "capture" ? "Press keys..." : formatHotkey(captureHotkey)}
-                    </button>
-                    {captureWarning && (
-                        <p className="mt-1 text-xs text-yellow-500">{captureWarning}</p>
-                    )}
-                </div>
+            <div className={`space-y-4 ${hotkeysSuspended ? "opacity-50" : ""}`}>
+                {HOTKEY_ACTIONS.map(({ action, label, optional }) => {
+                    const hotkey = hotkeys[action];
+                    const warning = hotkey && !hotkey.disabled ? getHotkeyWarning(hotkey) : null;
+                    const failure = hotkeyFailures.find((f) => f.action === action);
+                    const capturing = capturingHotkey === action;
+                    return (
+                        <div key={action}>
+                            <div className="mb-2 flex items-center justify-between">
+                                <label className="block text-sm font-medium text-white/80">{label}</label>
+                                {hotkey && (
+                                    <label className="flex items-center gap-2 text-xs text-white/60">
+                                        <input
+                                            type="checkbox"
+                                            checked={!hotkey.disabled}
+                                            onChange={(e) => setHotkey(action, { ...hotkey, disabled: !e.target.checked })}
+                                            className="accent-[#2721E8]"
+                                        />
+                                        Enabled
+                                    </label>
+                                )}
+                            </div>
+                            <div className="flex gap-2">
+                                <button
+                                    onClick={() => setCapturingHotkey(action)}
+                                    onKeyDown={(e) => {
+                                        if (capturing) void handleHotkeyCapture(e, action);
+                                    }}
+                                    onMouseDown={(e) => {
+                                        if (capturing) void handleMouseCapture(e, action);
+                                    }}
+                                    // Back and forward would otherwise navigate the app
+                                    onMouseUp={(e) => {
+                                        if (e.button === 3 || e.button === 4) e.preventDefault();
+                                    }}
+                                    className={`w-full px-4 py-2 bg-[#161316]/70 backdrop-blur-sm border rounded-md text-left font-mono text-sm transition-colors ${
+                                        capturing
+                                            ? "border-[#2721E8] text-[#49B8D3]"
+                                            : warning
+                                            ? "border-yellow-600 text-white hover:border-yellow-500"
+                                            : hotkey && !hotkey.disabled
+                                            ? "border-white/10 text-white hover:border-white/20"
+                                            : "border-white/10 text-white/40 hover:border-white/20"
+                                    }`}
+                                >
+                                    {capturing ? "Press keys..." : hotkey ? formatHotkey(hotkey, keyLabels) : "Not set"}
+                                </button>
+                                {optional && hotkey && (
+                                    <button
+                                        onClick={() => setHotkey(action, null)}
+                                        className="px-3 py-1.5 rounded-lg text-sm bg-white/10 text-white/60 hover:bg-white/20 transition-colors"
+                                    >
+                                        Clear
+                                    </button>
+                                )}
+                            </div>
+                            {failure ? (
+                                <p className="mt-1 text-xs text-red-500">Not registered: {describeConflict(failure.conflict)}</p>
+                            ) : warning && (
+                                <p className="mt-1 text-xs text-yellow-500">{warning}</p>
+                            )}
+                        </div>
+                    );
+                })}
+
+                {captureError && <p className="text-xs text-red-500">{captureError}</p>}
 
                 {hotkeysMatch && (
                     <p className="text-xs text-red-500">
@@ -170,6 +296,8 @@ export default function ShortcutsSection() {
                     </p>
                 )}
             </div>
+
+            <HotkeyProfiles />
         </div>
     );
 }
@@ -0,0 +1,357 @@
+import { useEffect, useState } from "react";
+import { invoke } from "@tauri-apps/api/core";
+import { listen } from "@tauri-apps/api/event";
+import { open } from "@tauri-apps/plugin-dialog";
+import type { Recording } from "../../store/recordingsStore";
+import { useSettingsStore } from "../../store/settingsStore";
+
+type ScreenshotFormat = "jpeg" | "png";
+
+const FORMAT_LABELS: Record<ScreenshotFormat, string> = {
+    jpeg: "JPEG",
+    png: "PNG",
+};
+
+const MAX_WIDTH_OPTIONS: { value: number | null; label: string }[] = [
+    { value: null, label: "Original" },
+    { value: 2560, label: "2560px" },
+    { value: 1920, label: "1920px" },
+    { value: 1280, label: "1280px" },
+];
+
+const QUOTA_OPTIONS_MB = [1024, 2048, 5120, 10240];
+
+interface ReencodeProgress {
+    recording_id: string;
+    current: number;
+    total: number;
+}
+
+interface DataRelocation {
+    old_dir: string;
+    new_dir: string;
+    rewritten_paths: number;
+}
+
+interface ReencodeReport {
+    recordings: number;
+    /** Locked recordings, which are left as they are. */
+    locked_skipped: number;
+    screenshots_reencoded: number;
+    bytes_before: number;
+    bytes_after: number;
+}
+
+const formatBytes = (bytes: number) => {
+    if (bytes < 1024 * 1024) return `${(bytes / 1024).toFixed(0)} KB`;
+    return `${(bytes / (1024 * 1024)).toFixed(1)} MB`;
+};
+
+export default function StorageSection() {
+    const {
+        screenshotPath,
+        keepLosslessOriginals,
+        losslessOriginalQuotaMb,
+        setScreenshotPath,
+        setKeepLosslessOriginals,
+        setLosslessOriginalQuotaMb,
+    } = useSettingsStore();
+    const [dataDir, setDataDir] = useState<string | null>(null);
+    const [removeOldData, setRemoveOldData] = useState(false);
+    const [moving, setMoving] = useState(false);
+    const [moveResult, setMoveResult] = useState<string | null>(null);
+    const [moveError, setMoveError] = useState<string | null>(null);
+    const [recordings, setRecordings] = useState<Recording[]>([]);
+    const [selected, setSelected] = useState<Set<string>>(new Set());
+    const [format, setFormat] = useState<ScreenshotFormat>("jpeg");
+    const [quality, setQuality] = useState(75);
+    const [maxWidth, setMaxWidth] = useState<number | null>(null);
+    const [progress, setProgress] = useState<ReencodeProgress | null>(null);
+    const [running, setRunning] = useState(false);
+    const [report, setReport] = useState<ReencodeReport | null>(null);
+    const [error, setError] = useState<string | null>(null);
+
+    useEffect(() => {
+        invoke<string>("get_data_directory")
+            .then(setDataDir)
+            .catch((error) => console.error("Failed to get data directory:", error));
+    }, []);
+
+    useEffect(() => {
+        invoke<Recording[]>("list_recordings")
+            .then(setRecordings)
+            .catch((error) => console.error("Failed to list recordings:", error));
+        const unlisten = listen<ReencodeProgress>("reencode-progress", (event) => {
+            setProgress(event.payload);
+        });
+        return () => {
+            unlisten.then((f) => f());
+        };
+    }, []);
+
+    const toggle = (id: string) => {
+        const next = new Set(selected);
+        if (next.has(id)) {
+            next.delete(id);
+        } else {
+            next.add(id);
+        }
+        setSelected(next);
+    };
+
+    const allSelected = recordings.length > 0 && selected.size === recordings.length;
+
+    const handleReencode = async () => {
+        setRunning(true);
+        setReport(null);
+        setError(null);
+        try {
+            setReport(
+                await invoke<ReencodeReport>("reencode_screenshots", {
+                    recordingIds: recordings.filter((r) => selected.has(r.id)).map((r) => r.id),
+                    settings: { format, quality, max_width: maxWidth },
+                })
+            );
+        } catch (error) {
+            setError(error as string);
+        } finally {
+            setProgress(null);
+            setRunning(false);
+        }
+    };
+
+    const handleMoveData = async () => {
+        const target = await open({ directory: true, multiple: false, title: "Select New Data Location" });
+        if (!target || typeof target !== "string") return;
+        setMoving(true);
+        setMoveResult(null);
+        setMoveError(null);
+        try {
+            const relocation = await invoke<DataRelocation>("relocate_data_directory", {
+                target,
+                removeOld: removeOldData,
+            });
+            // A screenshot folder inside the data directory moved with it
+            if (screenshotPath && screenshotPath.startsWith(relocation.old_dir)) {
+                setScreenshotPath(relocation.new_dir + screenshotPath.slice(relocation.old_dir.length));
+            }
+            setDataDir(relocation.new_dir);
+            setMoveResult(
+                removeOldData
+                    ? `Moved to ${relocation.new_dir}`
+                    : `Copied to ${relocation.new_dir}; the old copy in ${relocation.old_dir} can be deleted`,
+            );
+        } catch (error) {
+            setMoveError(error as string);
+        } finally {
+            setMoving(false);
+        }
+    };
+
+    const progressName = progress
+        ? recordings.find((r) => r.id === progress.recording_id)?.name
+        : null;
+
+    return (
+        <div className="space-y-6">
+            <div>
+                <h3 className="text-lg font-medium text-white mb-1">Storage</h3>
+                <p className="text-xs text-white/50">Re-encode the screenshots of existing recordings to save space.</p>
+            </div>
+
+            <div>
+                <label className="block text-sm font-medium text-white/80 mb-2">Data Location</label>
+                <p className="text-sm text-white/70 break-all">{dataDir ?? "..."}</p>
+                <p className="text-xs text-white/40 mt-1 mb-3">
+                    The database and screenshots. Move them to another folder, e.g. on a synced drive; it must be empty.
+                </p>
+                <label className="flex items-center gap-2 text-xs text-white/60 mb-3">
+                    <input
+                        type="checkbox"
+                        checked={removeOldData}
+                        onChange={(e) => setRemoveOldData(e.target.checked)}
+                        disabled={moving}
+                        className="accent-[#2721E8]"
+                    />
+                    Delete the old copy once moved
+                </label>
+                <button
+                    onClick={handleMoveData}
+                    disabled={moving || running}
+                    className="px-4 py-2 bg-white/10 hover:bg-white/20 text-white text-sm rounded-lg transition-colors disabled:opacity-50"
+                >
+                    {moving ? "Moving..." : "Move Data..."}
+                </button>
+                {moveResult && <p className="text-xs text-white/50 mt-2">{moveResult}</p>}
+                {moveError && <p className="mt-2 text-xs text-red-500">{moveError}</p>}
+            </div>
+
+            <div>
+                <div className="flex items-center justify-between mb-4">
+                    <div className="pr-4">
+                        <label className="block text-sm font-medium text-white/80">
+                            Keep lossless originals
+                        </label>
+                        <p className="text-xs text-white/50 mt-1">
+                            Save a PNG of each new screenshot next to the JPEG. Crops, rotations, redactions and exports start from it, so JPEG artifacts don't build up with every edit.
+                        </p>
+                    </div>
+                    <button
+                        aria-label={`Lossless originals: ${keepLosslessOriginals ? "enabled" : "disabled"}`}
+                        onClick={() => setKeepLosslessOriginals(!keepLosslessOriginals)}
+                        className={`relative inline-flex h-6 w-11 items-center rounded-full transition-colors flex-shrink-0 ${
+                            keepLosslessOriginals ? "bg-[#2721E8]" : "bg-white/20"
+                        }`}
+                    >
+                        <span
+                            className={`inline-block h-4 w-4 transform rounded-full bg-white transition-transform ${
+                                keepLosslessOriginals ? "translate-x-6" : "translate-x-1"
+                            }`}
+                        />
+                    </button>
+                </div>
+                <label className="block text-sm font-medium text-white/80 mb-2">Space for Originals</label>
+                <div className="flex gap-2">
+                    {QUOTA_OPTIONS_MB.map((quotaMb) => (
+                        <button
+                            key={quotaMb}
+                            onClick={() => setLosslessOriginalQuotaMb(quotaMb)}
+                            className={`px-3 py-1.5 rounded-lg text-sm transition-colors ${
+                                losslessOriginalQuotaMb === quotaMb
+                                    ? "bg-[#2721E8] text-white"
+                                    : "bg-white/10 text-white/60 hover:bg-white/20"
+                            }`}
+                        >
+                            {quotaMb / 1024} GB
+                        </button>
+                    ))}
+                </div>
+                <p className="text-xs text-white/40 mt-1">
+                    When originals take more, those of the oldest steps are deleted; their JPEGs are kept
+                </p>
+            </div>
+
+            <div>
+                <div className="flex items-center justify-between mb-2">
+                    <label className="block text-sm font-medium text-white/80">Recordings</label>
+                    <button
+                        onClick={() => setSelected(allSelected ? new Set() : new Set(recordings.map((r) => r.id)))}
+                        disabled={running || recordings.length === 0}
+                        className="text-xs text-white/60 hover:text-white transition-colors disabled:opacity-50"
+                    >
+                        {allSelected ? "Select none" : "Select all"}
+                    </button>
+                </div>
+                <div className="max-h-48 overflow-y-auto rounded-lg bg-white/5 p-2 space-y-1">
+                    {recordings.length === 0 ? (
+                        <p className="text-xs text-white/40 p-2">No recordings yet</p>
+                    ) : (
+                        recordings.map((recording) => (
+                            <label
+                                key={recording.id}
+                                className="flex items-center gap-2 px-2 py-1 rounded text-sm text-white/80 hover:bg-white/5 cursor-pointer"
+                            >
+                                <input
+                                    type="checkbox"
+                                    checked={selected.has(recording.id)}
+                                    onChange={() => toggle(recording.id)}
+                                    disabled={running}
+                                    className="accent-[#2721E8]"
+                                />
+                                <span className="truncate flex-1">{recording.name}</span>
+                                <span className="text-xs text-white/40">{recording.step_count} steps</span>
+                            </label>
+                        ))
+                    )}
+                </div>
+            </div>
+
+            <div>
+                <label className="block text-sm font-medium text-white/80 mb-2">Format</label>
+                <div className="flex gap-2">
+                    {(Object.keys(FORMAT_LABELS) as ScreenshotFormat[]).map((option) => (
+                        <button
+                            key={option}
+                            onClick={() => setFormat(option)}
+                            disabled={running}
+                            className={`px-3 py-1.5 rounded-lg text-sm transition-colors ${
+                                format === option
+                                    ? "bg-[#2721E8] text-white"
+                                    : "bg-white/10 text-white/60 hover:bg-white/20"
+                            }`}
+                        >
+                            {FORMAT_LABELS[option]}
+                        </button>
+                    ))}
+                </div>
+            </div>
+
+            {format === "jpeg" && (
+                <div>
+                    <label className="block text-sm font-medium text-white/80 mb-2">Quality</label>
+                    <div className="flex items-center gap-4">
+                        <input
+                            type="range"
+                            min="10"
+                            max="95"
+                            step="5"
+                            value={quality}
+                            onChange={(e) => setQuality(parseInt(e.target.value))}
+                            disabled={running}
+                            aria-label="JPEG quality"
+                            className="flex-1 h-2 bg-white/10 rounded-lg appearance-none cursor-pointer accent-[#2721E8]"
+                        />
+                        <span className="text-sm text-white/80 w-8 text-center">{quality}</span>
+                    </div>
+                </div>
+            )}
+
+            <div>
+                <label className="block text-sm font-medium text-white/80 mb-2">Maximum Width</label>
+                <div className="flex gap-2">
+                    {MAX_WIDTH_OPTIONS.map((option) => (
+                        <button
+                            key={option.label}
+                            onClick={() => setMaxWidth(option.value)}
+                            disabled={running}
+                            className={`px-3 py-1.5 rounded-lg text-sm transition-colors ${
+                                maxWidth === option.value
+                                    ? "bg-[#2721E8] text-white"
+                                    : "bg-white/10 text-white/60 hover:bg-white/20"
+                            }`}
+                        >
+                            {option.label}
+                        </button>
+                    ))}
+                </div>
+                <p className="text-xs text-white/40 mt-1">
+                    Wider screenshots are scaled down; their annotations and redactions are scaled with them
+                </p>
+            </div>
+
+            <div>
+                <button
+                    onClick={handleReencode}
+                    disabled={running || selected.size === 0}
+                    className="px-4 py-2 bg-[#2721E8] hover:bg-[#2721E8]/80 text-white text-sm rounded-lg transition-colors disabled:opacity-50"
+                >
+                    {running ? "Re-encoding..." : `Re-encode ${selected.size} recording${selected.size === 1 ? "" : "s"}`}
+                </button>
+                {progress && (
+                    <p className="text-xs text-white/50 mt-2">
+                        {progress.current} of {progress.total}
+                        {progressName ? `: ${progressName}` : ""}
+                    </p>
+                )}
+                {report && (
+                    <p className="text-xs text-white/50 mt-2">
+                        {report.screenshots_reencoded} screenshots re-encoded in {report.recordings} recordings,{" "}
+                        {formatBytes(report.bytes_before)} to {formatBytes(report.bytes_after)}
+                        {report.locked_skipped > 0 && `; ${report.locked_skipped} locked recordings skipped`}
+                    </p>
+                )}
+                {error && <p className="mt-2 text-xs text-red-500">{error}</p>}
+            </div>
+        </div>
+    );
+}
@@ -0,0 +1,331 @@
+import { useCallback, useEffect, useState } from "react";
+import { invoke } from "@tauri-apps/api/core";
+import { listen } from "@tauri-apps/api/event";
+import { RefreshCw } from "lucide-react";
+
+import { CREDENTIAL_SYNC_S3_SECRET, CREDENTIAL_SYNC_WEBDAV_PASSWORD, setCredential } from "../../lib/credentials";
+
+/** Mirrors `SyncSettings` in the backend. */
+interface SyncConfig {
+    enabled: boolean;
+    provider: "s3" | "webdav";
+    interval_minutes: number;
+    s3: {
+        endpoint: string;
+        region: string;
+        bucket: string;
+        prefix: string;
+        access_key_id: string;
+        path_style: boolean;
+    };
+    webdav: { url: string; username: string };
+}
+
+/** Mirrors `SyncReport` in the backend. */
+interface SyncReport {
+    pushed: number;
+    pulled: number;
+    deleted: number;
+    conflicts: number;
+    errors: string[];
+    finished_at: number;
+}
+
+/** Mirrors `SyncStatus` in the backend. */
+interface SyncStatus {
+    running: boolean;
+    last_report: SyncReport | null;
+    recordings: { recording_id: string; remote_key: string; conflict: string | null }[];
+}
+
+const inputClass =
+    "w-full px-4 py-2 bg-[#161316]/70 border border-white/10 rounded-md text-white placeholder-white/50 focus:outline-none focus:border-[#2721E8]";
+
+const describeReport = (report: SyncReport) =>
+    `${report.pushed} pushed, ${report.pulled} pulled, ${report.deleted} deleted at ${new Date(report.finished_at).toLocaleTimeString()}`;
+
+/** Where and how often the library syncs, and conflicts waiting on the user. */
+export default function SyncSettings() {
+    const [sync, setSync] = useState<SyncConfig | null>(null);
+    const [secret, setSecret] = useState("");
+    const [status, setStatus] = useState<SyncStatus | null>(null);
+    const [syncing, setSyncing] = useState(false);
+    const [error, setError] = useState<string | null>(null);
+    const [saved, setSaved] = useState(false);
+
+    const refreshStatus = useCallback(() => {
+        invoke<SyncStatus>("get_sync_status")
+            .then(setStatus)
+            .catch((err) => console.error("Failed to read sync status:", err));
+    }, []);
+
+    useEffect(() => {
+        invoke<{ sync: SyncConfig }>("get_settings")
+            .then((settings) => setSync(settings.sync))
+            .catch((err) => console.error("Failed to read sync settings:", err));
+        refreshStatus();
+        const unlisten = listen("sync-finished", refreshStatus);
+        return () => {
+            unlisten.then((f) => f());
+        };
+    }, [refreshStatus]);
+
+    if (!sync) {
+        return null;
+    }
+
+    const edit = (changes: Partial<SyncConfig>) => {
+        setSync({ ...sync, ...changes });
+        setSaved(false);
+    };
+
+    const handleSave = async () => {
+        try {
+            await invoke("update_settings", { patch: { sync } });
+            if (secret) {
+                const name = sync.provider === "s3" ? CREDENTIAL_SYNC_S3_SECRET : CREDENTIAL_SYNC_WEBDAV_PASSWORD;
+                await setCredential(name, secret);
+                setSecret("");
+            }
+            setError(null);
+            setSaved(true);
+        } catch (err) {
+            setError(String(err));
+        }
+    };
+
+    const handleSyncNow = async () => {
+        setSyncing(true);
+        setError(null);
+        try {
+            await invoke<SyncReport>("sync_now");
+        } catch (err) {
+            setError(String(err));
+        } finally {
+            setSyncing(false);
+            refreshStatus();
+        }
+    };
+
+    const handleResolve = async (recordingId: string, keep: "local" | "remote") => {
+        try {
+            await invoke("resolve_sync_conflict", { recordingId, keep });
+            setError(null);
+        } catch (err) {
+            setError(String(err));
+        } finally {
+            refreshStatus();
+        }
+    };
+
+    const conflicts = status?.recordings.filter((recording) => recording.conflict) ?? [];
+    const report = status?.last_report;
+
+    return (
+        <div className="border-t border-white/8 pt-6 space-y-4">
+            <div className="flex items-center justify-between">
+                <div className="pr-4">
+                    <label className="block text-sm font-medium text-white/80">Cloud Sync</label>
+                    <p className="text-xs text-white/50 mt-1">
+                        Keeps recordings in an S3 bucket or WebDAV folder (such as Nextcloud) shared with your other devices.
+                    </p>
+                </div>
+                <button
+                    aria-label={`Sync on a schedule: ${sync.enabled ? "enabled" : "disabled"}`}
+                    onClick={() => edit({ enabled: !sync.enabled })}
+                    className={`relative inline-flex h-6 w-11 items-center rounded-full transition-colors flex-shrink-0 ${
+                        sync.enabled ? "bg-[#2721E8]" : "bg-white/20"
+                    }`}
+                >
+                    <span
+                        className={`inline-block h-4 w-4 transform rounded-full bg-white transition-transform ${
+                            sync.enabled ? "translate-x-6" : "translate-x-1"
+                        }`}
+                    />
+                </button>
+            </div>
+
+            <div className="flex gap-2">
+                <div className="flex-1">
+                    <label className="block text-sm font-medium text-white/60 mb-2">Storage</label>
+                    <select
+                        value={sync.provider}
+                        onChange={(e) => edit({ provider: e.target.value as SyncConfig["provider"] })}
+                        className={inputClass}
+                    >
+                        <option value="s3">S3</option>
+                        <option value="webdav">WebDAV / Nextcloud</option>
+                    </select>
+                </div>
+                <div className="w-36">
+                    <label className="block text-sm font-medium text-white/60 mb-2">Every (minutes)</label>
+                    <input
+                        type="number"
+                        min="1"
+                        value={sync.interval_minutes}
+                        onChange={(e) => edit({ interval_minutes: parseInt(e.target.value) || 1 })}
+                        className={inputClass}
+                    />
+                </div>
+            </div>
+
+            {sync.provider === "s3" ? (
+                <div className="space-y-3">
+                    <div className="flex gap-2">
+                        <input
+                            type="text"
+                            value={sync.s3.bucket}
+                            onChange={(e) => edit({ s3: { ...sync.s3, bucket: e.target.value } })}
+                            placeholder="Bucket"
+                            className={inputClass}
+                        />
+                        <input
+                            type="text"
+                            value={sync.s3.prefix}
+                            onChange={(e) => edit({ s3: { ...sync.s3, prefix: e.target.value } })}
+                            placeholder="Folder (optional)"
+                            className={inputClass}
+                        />
+                    </div>
+                    <div className="flex gap-2">
+                        <input
+                            type="text"
+                            value={sync.s3.region}
+                            onChange={(e) => edit({ s3: { ...sync.s3, region: e.target.value } })}
+                            placeholder="Region (us-east-1)"
+                            className={inputClass}
+                        />
+                        <input
+                            type="text"
+                            value={sync.s3.endpoint}
+                            onChange={(e) => edit({ s3: { ...sync.s3, endpoint: e.target.value } })}
+                            placeholder="Endpoint (empty for AWS)"
+                            className={inputClass}
+                        />
+                    </div>
+                    <div className="flex gap-2">
+                        <input
+                            type="text"
+                            value={sync.s3.access_key_id}
+                            onChange={(e) => edit({ s3: { ...sync.s3, access_key_id: e.target.value } })}
+                            placeholder="Access key ID"
+                            className={inputClass}
+                        />
+                        <input
+                            type="password"
+                            value={secret}
+                            onChange={(e) => {
+                                setSecret(e.target.value);
+                                setSaved(false);
+                            }}
+                            placeholder="Secret key (unchanged)"
+                            className={inputClass}
+                        />
+                    </div>
+                    <label className="flex items-center gap-3 text-sm text-white/80">
+                        <input
+                            type="checkbox"
+                            checked={sync.s3.path_style}
+                            onChange={(e) => edit({ s3: { ...sync.s3, path_style: e.target.checked } })}
+                            className="accent-[#2721E8]"
+                        />
+                        Path-style URLs (MinIO and most S3-compatible services)
+                    </label>
+                </div>
+            ) : (
+                <div className="space-y-3">
+                    <input
+                        type="text"
+                        value={sync.webdav.url}
+                        onChange={(e) => edit({ webdav: { ...sync.webdav, url: e.target.value } })}
+                        placeholder="https://cloud.example.com/remote.php/dav/files/you/StepSnap"
+                        className={inputClass}
+                    />
+                    <div className="flex gap-2">
+                        <input
+                            type="text"
+                            value={sync.webdav.username}
+                            onChange={(e) => edit({ webdav: { ...sync.webdav, username: e.target.value } })}
+                            placeholder="Username"
+                            className={inputClass}
+                        />
+                        <input
+                            type="password"
+                            value={secret}
+                            onChange={(e) => {
+                                setSecret(e.target.value);
+                                setSaved(false);
+                            }}
+                            placeholder="App password (unchanged)"
+                            className={inputClass}
+                        />
+                    </div>
+                </div>
+            )}
+            <p className="-mt-2 text-xs text-white/50">Secrets are stored in your system keychain.</p>
+
+            <div className="flex items-center gap-3">
+                <button
+                    onClick={handleSave}
+                    className="px-4 py-2 rounded-md text-sm bg-[#2721E8] text-white hover:bg-[#2721E8]/90 transition-colors"
+                >
+                    Save
+                </button>
+                <button
+                    onClick={() => {
+                        void handleSyncNow();
+                    }}
+                    disabled={syncing || status?.running}
+                    className="flex items-center gap-2 px-4 py-2 rounded-md text-sm bg-white/10 text-white/80 hover:bg-white/15 transition-colors disabled:opacity-50"
+                >
+                    <RefreshCw size={14} className={syncing || status?.running ? "animate-spin" : ""} />
+                    Sync Now
+                </button>
+                {saved && <span className="text-xs text-white/60">Saved</span>}
+            </div>
+
+            {report && (
+                <p className="text-xs text-white/50">
+                    Last sync: {describeReport(report)}
+                    {report.errors.length > 0 && <span className="text-red-400"> ({report.errors[0]})</span>}
+                </p>
+            )}
+            {error && <p className="text-xs text-red-400">{error}</p>}
+
+            {conflicts.length > 0 && (
+                <div className="space-y-2">
+                    <label className="block text-sm font-medium text-white/80">Conflicts</label>
+                    {conflicts.map((conflict) => (
+                        <div
+                            key={conflict.recording_id}
+                            className="flex items-center justify-between gap-3 rounded-md bg-white/5 px-3 py-2"
+                        >
+                            <div className="min-w-0">
+                                <p className="truncate text-xs text-white/80">{conflict.recording_id}</p>
+                                <p className="text-xs text-white/50">{conflict.conflict}</p>
+                            </div>
+                            <div className="flex flex-shrink-0 gap-2">
+                                <button
+                                    onClick={() => {
+                                        void handleResolve(conflict.recording_id, "local");
+                                    }}
+                                    className="rounded-md bg-white/10 px-2 py-1 text-xs text-white/80 hover:bg-white/15"
+                                >
+                                    Keep This Device's
+                                </button>
+                                <button
+                                    onClick={() => {
+                                        void handleResolve(conflict.recording_id, "remote");
+                                    }}
+                                    className="rounded-md bg-white/10 px-2 py-1 text-xs text-white/80 hover:bg-white/15"
+                                >
+                                    Keep Synced
+                                </button>
+                            </div>
+                        </div>
+                    ))}
+                </div>
+            )}
+        </div>
+    );
+}
@@ -0,0 +1,145 @@
+import { useEffect, useState } from "react";
+import { invoke } from "@tauri-apps/api/core";
+
+import { CREDENTIAL_AZURE_DEVOPS_TOKEN, CREDENTIAL_JIRA_TOKEN, setCredential } from "../../lib/credentials";
+
+/** Mirrors `TicketSettings` in the backend. */
+interface TicketConfig {
+    jira: { base_url: string; email: string };
+    azure_devops: { organization_url: string; project: string };
+}
+
+const inputClass =
+    "w-full px-4 py-2 bg-[#161316]/70 border border-white/10 rounded-md text-white placeholder-white/50 focus:outline-none focus:border-[#2721E8]";
+
+/** Sites and tokens "Attach to Ticket" uploads exports with. */
+export default function TicketSettings() {
+    const [tickets, setTickets] = useState<TicketConfig | null>(null);
+    const [jiraToken, setJiraToken] = useState("");
+    const [azureToken, setAzureToken] = useState("");
+    const [error, setError] = useState<string | null>(null);
+    const [saved, setSaved] = useState(false);
+
+    useEffect(() => {
+        invoke<{ tickets: TicketConfig }>("get_settings")
+            .then((settings) => setTickets(settings.tickets))
+            .catch((err) => console.error("Failed to read ticket settings:", err));
+    }, []);
+
+    if (!tickets) {
+        return null;
+    }
+
+    const editJira = (changes: Partial<TicketConfig["jira"]>) => {
+        setTickets({ ...tickets, jira: { ...tickets.jira, ...changes } });
+        setSaved(false);
+    };
+
+    const editAzure = (changes: Partial<TicketConfig["azure_devops"]>) => {
+        setTickets({ ...tickets, azure_devops: { ...tickets.azure_devops, ...changes } });
+        setSaved(false);
+    };
+
+    const handleSave = async () => {
+        try {
+            await invoke("update_settings", { patch: { tickets } });
+            if (jiraToken.trim()) {
+                await setCredential(CREDENTIAL_JIRA_TOKEN, jiraToken.trim());
+                setJiraToken("");
+            }
+            if (azureToken.trim()) {
+                await setCredential(CREDENTIAL_AZURE_DEVOPS_TOKEN, azureToken.trim());
+                setAzureToken("");
+            }
+            setError(null);
+            setSaved(true);
+        } catch (err) {
+            setError(String(err));
+        }
+    };
+
+    return (
+        <div className="border-t border-white/8 pt-6 space-y-4">
+            <div>
+                <label className="block text-sm font-medium text-white/80">Issue Trackers</label>
+                <p className="text-xs text-white/50 mt-1">
+                    "Attach to Ticket" uploads a PDF or HTML export to a Jira issue or Azure DevOps work item. Tokens are stored in your system keychain.
+                </p>
+            </div>
+
+            <div className="space-y-3">
+                <label className="block text-sm font-medium text-white/60">Jira</label>
+                <input
+                    type="text"
+                    value={tickets.jira.base_url}
+                    onChange={(e) => editJira({ base_url: e.target.value })}
+                    placeholder="https://example.atlassian.net"
+                    className={inputClass}
+                />
+                <div className="flex gap-2">
+                    <input
+                        type="email"
+                        value={tickets.jira.email}
+                        onChange={(e) => editJira({ email: e.target.value })}
+                        placeholder="Account email (Jira Cloud)"
+                        className={inputClass}
+                    />
+                    <input
+                        type="password"
+                        value={jiraToken}
+                        onChange={(e) => {
+                            setJiraToken(e.target.value);
+                            setSaved(false);
+                        }}
+                        placeholder="API token (unchanged)"
+                        className={inputClass}
+                    />
+                </div>
+                <p className="text-xs text-white/50">
+                    Leave the email empty to use a Jira Data Center personal access token.
+                </p>
+            </div>
+
+            <div className="space-y-3">
+                <label className="block text-sm font-medium text-white/60">Azure DevOps</label>
+                <div className="flex gap-2">
+                    <input
+                        type="text"
+                        value={tickets.azure_devops.organization_url}
+                        onChange={(e) => editAzure({ organization_url: e.target.value })}
+                        placeholder="https://dev.azure.com/example"
+                        className={inputClass}
+                    />
+                    <input
+                        type="text"
+                        value={tickets.azure_devops.project}
+                        onChange={(e) => editAzure({ project: e.target.value })}
+                        placeholder="Project"
+                        className={inputClass}
+                    />
+                </div>
+                <input
+                    type="password"
+                    value={azureToken}
+                    onChange={(e) => {
+                        setAzureToken(e.target.value);
+                        setSaved(false);
+                    }}
+                    placeholder="Personal access token with Work Items (read & write) (unchanged)"
+                    className={inputClass}
+                />
+            </div>
+
+            <div className="flex items-center gap-3">
+                <button
+                    onClick={handleSave}
+                    className="px-4 py-2 rounded-md text-sm bg-[#2721E8] text-white hover:bg-[#2721E8]/90 transition-colors"
+                >
+                    Save
+                </button>
+                {saved && <span className="text-xs text-white/60">Saved</span>}
+                {error && <span className="text-xs text-red-400">{error}</span>}
+            </div>
+        </div>
+    );
+}
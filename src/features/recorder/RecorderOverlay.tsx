@@ -5,7 +5,7 @@ import { Square } from "lucide-react";
 import Tooltip from "../../components/Tooltip";
 
 export default function RecorderOverlay() {
-    const { isRecording, setIsRecording } = useRecorderStore();
+    const { isRecording, isPaused, setIsRecording } = useRecorderStore();
     const [isVisible, setIsVisible] = useState(false);
 
     useEffect(() => {
@@ -28,8 +28,8 @@ export default function RecorderOverlay() {
     return (
         <div className="fixed bottom-4 right-4 bg-zinc-900 border border-zinc-700 rounded-lg shadow-xl p-4 flex items-center gap-4 z-50 animate-in slide-in-from-bottom-2">
             <div className="flex items-center gap-2">
-                <div className="w-3 h-3 bg-red-500 rounded-full animate-pulse" />
-                <span className="text-white font-medium text-sm">Recording...</span>
+                <div className={`w-3 h-3 rounded-full ${isPaused ? "bg-yellow-500" : "bg-red-500 animate-pulse"}`} />
+                <span className="text-white font-medium text-sm">{isPaused ? "Paused" : "Recording..."}</span>
             </div>
             <Tooltip content="Stop Recording">
                 <button
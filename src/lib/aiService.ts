@@ -1,4 +1,5 @@
 import { Step } from "../store/recorderStore";
+import type { AnnotationPoint } from "../store/recordingsStore";
 import { invoke } from "@tauri-apps/api/core";
 import { useSettingsStore } from "../store/settingsStore";
 import { getProvider } from "./providers";
@@ -248,6 +249,20 @@ async function fileToBase64(filePath: string): Promise<string> {
     }
 }
 
+// Screenshots don't carry the click ring, so draw it on the copy sent to the
+// AI; the prompts tell the model to look for it
+async function screenshotToBase64(filePath: string, marker?: AnnotationPoint | null): Promise<string> {
+    if (!marker) {
+        return fileToBase64(filePath);
+    }
+    try {
+        return await invoke<string>("read_highlighted_screenshot_base64", { path: filePath, marker });
+    } catch (error) {
+        console.error("Failed to highlight screenshot:", filePath, error);
+        return fileToBase64(filePath);
+    }
+}
+
 // Generate description for a single step
 async function generateStepDescription(
     step: Step & { ocr_text?: string; title?: string },
@@ -274,6 +289,8 @@ async function generateStepDescription(
         if (step.element_name) parts.push(`Target Element: "${step.element_name}"`);
         if (step.element_type) parts.push(`Element Type: ${step.element_type}`);
         if (step.app_name) parts.push(`Application: ${step.app_name}`);
+        if (step.breadcrumb) parts.push(`UI location: ${step.breadcrumb}`);
+        if (step.action) parts.push(`Detected action: ${step.action}`);
 
         // Always include OCR text as supplementary signal — even with screenshots on,
         // the cropped/dense UI may have small or stylized text the vision model misreads.
@@ -292,6 +309,8 @@ async function generateStepDescription(
 Typed text: "${step.text}"
 NOTE: The typed text may be partial (for autocomplete) or abbreviated. If user context provides more specific information (like a full URL, file path, or complete value), use that instead of the literal typed text.
 Write an instruction that achieves the user's intent.`;
+        if (step.element_name) actionDescription += `\nTarget field: "${step.element_name}"`;
+        if (step.breadcrumb) actionDescription += `\nUI location: ${step.breadcrumb}`;
         if (step.ocr_text) {
             const truncatedOcr = step.ocr_text.length > 100
                 ? step.ocr_text.substring(0, 100) + '...'
@@ -510,7 +529,11 @@ async function identifyElement(
     metaParts.push(`ACTION: ${step.type_.toUpperCase()}`);
     if (step.element_name) metaParts.push(`Element name: "${step.element_name}"`);
     if (step.element_type) metaParts.push(`Element type: ${step.element_type}`);
+    if (step.element_value && step.type_ !== "type") metaParts.push(`Element value/state: "${step.element_value}"`);
     if (step.app_name) metaParts.push(`Application: ${step.app_name}`);
+    if (step.breadcrumb) metaParts.push(`UI location: ${step.breadcrumb}`);
+    if (step.action) metaParts.push(`Detected action: ${step.action}`);
+    if (step.page_url) metaParts.push(`Web page: ${step.page_title ? `"${step.page_title}" ` : ""}${step.page_url}`);
     if (step.type_ === "click" && step.x !== undefined && step.y !== undefined) {
         metaParts.push(`Click position: (${Math.round(step.x)}, ${Math.round(step.y)})`);
     }
@@ -963,6 +986,7 @@ interface StepLike {
     app_name?: string;
     description?: string;
     is_cropped?: boolean;
+    click_marker?: AnnotationPoint | null;
     ocr_text?: string;
     ocr_status?: string;
     input_source?: string;
@@ -970,6 +994,10 @@ interface StepLike {
     clip_path?: string;
     /** User-set or previously-AI-generated step title. Drives the H2 heading. */
     title?: string;
+    page_url?: string;
+    page_title?: string;
+    breadcrumb?: string;
+    action?: string;
 }
 
 export async function generateDocumentation(steps: StepLike[], config?: AIConfig): Promise<string> {
@@ -1010,7 +1038,7 @@ export async function generateDocumentation(steps: StepLike[], config?: AIConfig
         steps.map(async (step) => ({
             step,
             screenshotBase64: sendScreenshotsToAi && step.screenshot
-                ? await fileToBase64(step.screenshot)
+                ? await screenshotToBase64(step.screenshot, step.click_marker)
                 : null,
             screenshotAfterBase64: enableStateDiff && sendScreenshotsToAi && step.screenshot_after
                 ? await fileToBase64(step.screenshot_after)
@@ -1251,7 +1279,7 @@ export async function generateDocumentationStreaming(
         steps.map(async (step) => ({
             step,
             screenshotBase64: sendScreenshotsToAi && step.screenshot
-                ? await fileToBase64(step.screenshot)
+                ? await screenshotToBase64(step.screenshot, step.click_marker)
                 : null,
             screenshotAfterBase64: enableStateDiff && sendScreenshotsToAi && step.screenshot_after
                 ? await fileToBase64(step.screenshot_after)
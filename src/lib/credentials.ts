@@ -0,0 +1,47 @@
+import { invoke } from "@tauri-apps/api/core";
+
+/**
+ * Secrets live in the OS keychain through the backend's `credentials`
+ * module, never in the settings file. Names are short identifiers.
+ */
+export const CREDENTIAL_AI_API_KEY = "ai_api_key";
+
+/**
+ * Key for one provider of the backend's metadata-only generation. The
+ * backend falls back to `CREDENTIAL_AI_API_KEY` when it isn't set.
+ */
+export const aiProviderCredential = (provider: string) => `${CREDENTIAL_AI_API_KEY}.${provider}`;
+
+/** Password for the SMTP server recordings are emailed through. */
+export const CREDENTIAL_SMTP_PASSWORD = "smtp_password";
+
+/** Tokens for attaching exports to Jira issues and Azure DevOps work items. */
+export const CREDENTIAL_JIRA_TOKEN = "jira_api_token";
+export const CREDENTIAL_AZURE_DEVOPS_TOKEN = "azure_devops_token";
+
+/** Secrets of the cloud sync providers. */
+export const CREDENTIAL_SYNC_S3_SECRET = "sync_s3_secret_key";
+export const CREDENTIAL_SYNC_WEBDAV_PASSWORD = "sync_webdav_password";
+
+/** Password of the static host shared links are uploaded to. */
+export const CREDENTIAL_SHARE_UPLOAD_PASSWORD = "share_upload_password";
+
+/** Webhook URLs and bot token recordings are posted to chat with. */
+export const CREDENTIAL_SLACK_WEBHOOK = "slack_webhook_url";
+export const CREDENTIAL_SLACK_BOT_TOKEN = "slack_bot_token";
+export const CREDENTIAL_TEAMS_WEBHOOK = "teams_webhook_url";
+
+/** Secrets of the image hosts exported screenshots are uploaded to. */
+export const CREDENTIAL_IMAGE_HOST_S3_SECRET = "image_host_s3_secret";
+export const CREDENTIAL_IMGUR_CLIENT_ID = "imgur_client_id";
+export const CREDENTIAL_IMAGE_HOST_TOKEN = "image_host_token";
+
+export const getCredential = (name: string) =>
+    invoke<string | null>("get_credential", { name });
+
+/** An empty secret deletes the credential. */
+export const setCredential = (name: string, secret: string) =>
+    invoke<void>("set_credential", { name, secret });
+
+export const deleteCredential = (name: string) =>
+    invoke<void>("delete_credential", { name });
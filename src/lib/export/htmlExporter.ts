@@ -2,17 +2,39 @@ import { unified } from "unified";
 import remarkParse from "remark-parse";
 import remarkGfm from "remark-gfm";
 import { getFileBuffer, arrayBufferToBase64, getMimeType, saveFile } from "./utils";
-
-export async function exportToHtml(markdown: string, fileName: string): Promise<void> {
-    const html = await processMarkdownToHtml(markdown, fileName);
-    const data = new TextEncoder().encode(html);
+import { isHttpUrl } from "../pathUtils";
+
+/**
+ * `linkImages` keeps images that are already on the web as links rather than
+ * inlining them, for screenshots uploaded to an image host.
+ */
+export async function exportToHtml(markdown: string, fileName: string, linkImages = false): Promise<void> {
+    const data = await renderHtml(markdown, fileName, linkImages);
     await saveFile(data, `${fileName}.html`, [{ name: "HTML", extensions: ["html"] }]);
 }
 
-async function processMarkdownToHtml(markdown: string, fileName: string): Promise<string> {
+/** The standalone HTML page for `markdown`, for saving or uploading. */
+export async function renderHtml(markdown: string, title: string, linkImages = false): Promise<Uint8Array> {
+    return new TextEncoder().encode(await processMarkdownToHtml(markdown, title, linkImages));
+}
+
+// Flag web images so processNode links them instead of inlining them
+function markLinkedImages(node: any): void {
+    if (node.type === 'image' && isHttpUrl(node.url)) {
+        node.data = { ...node.data, linked: true };
+    }
+    for (const child of node.children ?? []) {
+        markLinkedImages(child);
+    }
+}
+
+async function processMarkdownToHtml(markdown: string, fileName: string, linkImages: boolean): Promise<string> {
     // Parse markdown to AST
     const processor = unified().use(remarkParse).use(remarkGfm);
     const ast = processor.parse(markdown);
+    if (linkImages) {
+        markLinkedImages(ast);
+    }
 
     // Process AST nodes to HTML
     const contentHtml = await processNode(ast);
@@ -103,6 +125,9 @@ async function processNode(node: any): Promise<string> {
     }
 
     if (node.type === 'image') {
+        if (node.data?.linked) {
+            return `<img src="${escapeHtml(node.url)}" alt="${escapeHtml(node.alt || '')}" />`;
+        }
         const buffer = await getFileBuffer(node.url);
         if (buffer) {
             const mimeType = getMimeType(node.url);
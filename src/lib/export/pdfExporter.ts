@@ -10,6 +10,12 @@ import { getFileBuffer, arrayBufferToBase64, getMimeType, saveFile } from "./uti
 pdfMake.vfs = pdfFonts.pdfMake ? pdfFonts.pdfMake.vfs : pdfFonts.vfs;
 
 export async function exportToPdf(markdown: string, fileName: string): Promise<void> {
+    const buffer = await renderPdf(markdown);
+    await saveFile(buffer, `${fileName}.pdf`, [{ name: "PDF", extensions: ["pdf"] }]);
+}
+
+/** The PDF document for `markdown`, for saving or uploading. */
+export async function renderPdf(markdown: string): Promise<Uint8Array> {
     // Parse markdown to AST
     const processor = unified().use(remarkParse).use(remarkGfm);
     const ast = processor.parse(markdown);
@@ -157,11 +163,9 @@ export async function exportToPdf(markdown: string, fileName: string): Promise<v
 
     const pdfDoc = pdfMake.createPdf(docDefinition);
 
-    const buffer = await new Promise<Uint8Array>((resolve) => {
+    return new Promise<Uint8Array>((resolve) => {
         pdfDoc.getBuffer((buffer: Buffer) => {
             resolve(new Uint8Array(buffer));
         });
     });
-
-    await saveFile(buffer, `${fileName}.pdf`, [{ name: "PDF", extensions: ["pdf"] }]);
 }
@@ -1,5 +1,5 @@
 import { invoke } from "@tauri-apps/api/core";
-import { isHttpUrl, normalizeImagePath } from "../pathUtils";
+import { isHttpUrl, normalizeImagePath, normalizePathForMarkdown } from "../pathUtils";
 
 // Helper to read file as Uint8Array
 export async function getFileBuffer(path: string): Promise<Uint8Array | null> {
@@ -47,6 +47,47 @@ export function getMimeType(path: string): string {
     return 'image/png';
 }
 
+// Swap screenshots that have annotations, redactions, click markers or (when
+// enabled) step number badges, zoom insets, watermarks or an export-time smart crop
+// for copies rendered by the backend in the saved export styles. The originals
+// on disk are never modified.
+export async function withAnnotatedScreenshots(markdown: string, recordingId?: string): Promise<string> {
+    if (!recordingId) {
+        return markdown;
+    }
+    try {
+        const rendered = await invoke<Record<string, string>>("render_annotated_screenshots", { recordingId });
+        return Object.entries(rendered).reduce(
+            (result, [original, annotated]) =>
+                result.split(normalizePathForMarkdown(original)).join(normalizePathForMarkdown(annotated)),
+            markdown,
+        );
+    } catch (error) {
+        console.error("Failed to render annotated screenshots", error);
+        return markdown;
+    }
+}
+
+// When hosted images are on, upload the local screenshots in `markdown` to the
+// image host and link them instead; `null` when they're off.
+export async function withHostedImages(markdown: string): Promise<string | null> {
+    const settings = await invoke<{ image_host: { provider: string; hosted_images: boolean } }>("get_settings");
+    if (!settings.image_host.hosted_images || settings.image_host.provider === "none") {
+        return null;
+    }
+    const paths = [...markdown.matchAll(/!\[[^\]]*\]\(([^)\s]+)\)/g)]
+        .map((match) => normalizeImagePath(match[1]))
+        .filter((path) => !isHttpUrl(path));
+    if (paths.length === 0) {
+        return markdown;
+    }
+    const urls = await invoke<Record<string, string>>("upload_screenshots", { paths: [...new Set(paths)] });
+    return Object.entries(urls).reduce(
+        (result, [path, url]) => result.split(normalizePathForMarkdown(path)).join(url),
+        markdown,
+    );
+}
+
 // Helper to save file using native file picker
 export async function saveFile(data: Uint8Array, fileName: string, filters: { name: string; extensions: string[] }[]): Promise<boolean> {
     return invoke<boolean>("save_file_via_dialog", {
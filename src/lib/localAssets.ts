@@ -16,6 +16,26 @@ export async function registerLocalAssetScope(path: string): Promise<void> {
     await invoke("register_asset_scope", { path: parentDirectory });
 }
 
+/**
+ * URL of a local screenshot no wider than `width` CSS pixels, resized and
+ * cached by the backend (see `screenshot_protocol.rs`). Positions on it are
+ * in its own pixels; `get_image_size` gives the original's size.
+ */
+export function screenshotSrc(path: string, width: number): string {
+    const pixels = Math.round(width * (window.devicePixelRatio || 1));
+    return `${convertFileSrc(normalizeImagePath(path), "screenshot")}?width=${pixels}`;
+}
+
+/** Like `resolveDisplayImageSrc`, with local images resized to `width`. */
+export async function resolveScreenshotSrc(src: string, width: number): Promise<string> {
+    if (!src || !isLocalFilePath(src)) {
+        return src || "";
+    }
+
+    await registerLocalAssetScope(src);
+    return screenshotSrc(src, width);
+}
+
 export async function resolveDisplayImageSrc(src: string): Promise<string> {
     if (!src) {
         return "";
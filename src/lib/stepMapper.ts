@@ -1,4 +1,4 @@
-import { Step } from "../store/recordingsStore";
+import { AnnotationPoint, ElementBounds, ElementPathSegment, Step } from "../store/recordingsStore";
 
 /**
  * Interface for steps in the format expected by generateDocumentation()
@@ -17,6 +17,7 @@ export interface StepLike {
     app_name?: string;
     description?: string;
     is_cropped?: boolean;
+    click_marker?: AnnotationPoint | null;
     ocr_text?: string;
     ocr_status?: string;
     input_source?: string;
@@ -24,6 +25,17 @@ export interface StepLike {
     identified_element_json?: string;
     clip_path?: string;
     title?: string;
+    element_bounds?: ElementBounds;
+    page_url?: string;
+    page_title?: string;
+    automation_id?: string;
+    class_name?: string;
+    element_path?: ElementPathSegment[];
+    process_name?: string;
+    exe_path?: string;
+    window_title?: string;
+    action?: string;
+    breadcrumb?: string;
 }
 
 /**
@@ -45,8 +57,11 @@ export function mapStepsForAI(steps: Step[]): StepLike[] {
         element_type: step.element_type,
         element_value: step.element_value,
         app_name: step.app_name,
-        description: step.description,
+        // A generated default restates the element info; only user-written
+        // descriptions carry intent
+        description: step.description_generated ? undefined : step.description,
         is_cropped: step.is_cropped,
+        click_marker: step.click_marker,
         ocr_text: step.ocr_text,
         ocr_status: step.ocr_status,
         input_source: step.input_source,
@@ -54,5 +69,16 @@ export function mapStepsForAI(steps: Step[]): StepLike[] {
         identified_element_json: step.identified_element_json,
         clip_path: step.clip_path,
         title: step.title,
+        element_bounds: step.element_bounds,
+        page_url: step.page_url,
+        page_title: step.page_title,
+        automation_id: step.automation_id,
+        class_name: step.class_name,
+        element_path: step.element_path,
+        process_name: step.process_name,
+        exe_path: step.exe_path,
+        window_title: step.window_title,
+        action: step.action,
+        breadcrumb: step.breadcrumb,
     }));
 }
@@ -1,6 +1,6 @@
 import { useEffect, useState, useRef, useCallback } from "react";
 import { invoke } from "@tauri-apps/api/core";
-import { X, Monitor, AppWindow, Minimize2, ChevronDown } from "lucide-react";
+import { X, Monitor, AppWindow, Minimize2, ChevronDown, ScrollText, Search } from "lucide-react";
 
 interface MonitorInfo {
   index: number;
@@ -21,15 +21,31 @@ interface WindowInfo {
   width: number;
   height: number;
   is_minimized: boolean;
+  icon: string | null;
 }
 
+interface WindowPage {
+  windows: WindowInfo[];
+  total: number;
+  total_windows: number;
+}
+
+const WINDOW_PAGE_SIZE = 50;
+
 export default function MonitorPicker() {
   const [monitors, setMonitors] = useState<MonitorInfo[]>([]);
   const [windows, setWindows] = useState<WindowInfo[]>([]);
+  // Windows matching the search, and capturable windows in all
+  const [windowTotal, setWindowTotal] = useState(0);
+  const [windowCount, setWindowCount] = useState(0);
+  const [windowSearch, setWindowSearch] = useState("");
+  const [includeMinimized, setIncludeMinimized] = useState(true);
   const [hoveredMonitor, setHoveredMonitor] = useState<number | null>(null);
   const [isCapturing, setIsCapturing] = useState(false);
   const [error, setError] = useState<string | null>(null);
   const [windowDropdownOpen, setWindowDropdownOpen] = useState(false);
+  // Scroll the chosen window through and stitch it into one tall screenshot
+  const [fullPage, setFullPage] = useState(false);
 
   // Refs for debouncing window highlights
   const hoverTimeoutRef = useRef<ReturnType<typeof setTimeout> | null>(null);
@@ -64,20 +80,54 @@ export default function MonitorPicker() {
     };
   }, [monitors.length, windowDropdownOpen]);
 
+  const fetchWindows = (offset: number) =>
+    invoke<WindowPage>("get_windows", {
+      query: {
+        search: windowSearch,
+        include_minimized: includeMinimized,
+        offset,
+        limit: WINDOW_PAGE_SIZE
+      }
+    });
+
+  const showWindows = (page: WindowPage, append: boolean) => {
+    setWindows((current) => (append ? [...current, ...page.windows] : page.windows));
+    setWindowTotal(page.total);
+    setWindowCount(page.total_windows);
+  };
+
   const loadData = async () => {
     try {
       const [monitorsResult, windowsResult] = await Promise.all([
         invoke<MonitorInfo[]>("get_monitors"),
-        invoke<WindowInfo[]>("get_windows")
+        fetchWindows(0)
       ]);
       setMonitors(monitorsResult);
-      setWindows(windowsResult);
+      showWindows(windowsResult, false);
     } catch (err) {
       console.error("Failed to load data:", err);
       setError(String(err));
     }
   };
 
+  // Search as the user types, once they pause
+  useEffect(() => {
+    const timeout = setTimeout(() => {
+      fetchWindows(0)
+        .then((page) => showWindows(page, false))
+        .catch((err) => console.error("Failed to search windows:", err));
+    }, 150);
+    return () => clearTimeout(timeout);
+  }, [windowSearch, includeMinimized]);
+
+  const loadMoreWindows = async () => {
+    try {
+      showWindows(await fetchWindows(windows.length), true);
+    } catch (err) {
+      console.error("Failed to load more windows:", err);
+    }
+  };
+
   const handleMonitorEnter = async (index: number) => {
     setHoveredMonitor(index);
     try {
@@ -167,7 +217,8 @@ export default function MonitorPicker() {
     try {
       await invoke("capture_window_and_close_picker", {
         windowId: win.id,
-        isMinimized: win.is_minimized
+        isMinimized: win.is_minimized,
+        fullPage
       });
     } catch (err) {
       console.error("Failed to capture window:", err);
@@ -245,12 +296,26 @@ export default function MonitorPicker() {
 
         {/* Windows Dropdown */}
         <div>
-          <label className="flex items-center gap-2 text-sm text-white/60 uppercase tracking-wide mb-3">
-            <AppWindow size={14} />
-            <span>Window</span>
-          </label>
+          <div className="flex items-center justify-between mb-3">
+            <label className="flex items-center gap-2 text-sm text-white/60 uppercase tracking-wide">
+              <AppWindow size={14} />
+              <span>Window</span>
+            </label>
+            <button
+              onClick={() => setFullPage(!fullPage)}
+              title="Scroll the window from top to bottom and stitch it into one screenshot"
+              className={`flex items-center gap-1.5 px-2.5 py-1 rounded-lg text-xs border transition-colors ${
+                fullPage
+                  ? "border-primary bg-primary/20 text-white"
+                  : "border-white/10 text-white/60 hover:border-white/20 hover:text-white/80"
+              }`}
+            >
+              <ScrollText size={12} />
+              Capture full page
+            </button>
+          </div>
 
-          {windows.length === 0 ? (
+          {windowCount === 0 ? (
             <div className="text-base text-white/40 text-center py-6 glass-surface-3 rounded-xl border border-white/10">
               No capturable windows found
             </div>
@@ -268,7 +333,34 @@ export default function MonitorPicker() {
               </button>
 
               {windowDropdownOpen && (
-                <div className="absolute z-10 w-full mt-2 glass-surface-3 rounded-xl shadow-lg max-h-60 overflow-y-auto border border-white/10">
+                <div className="absolute z-10 w-full mt-2 glass-surface-3 rounded-xl shadow-lg max-h-72 overflow-y-auto border border-white/10">
+                  <div className="sticky top-0 z-10 flex items-center gap-2 px-3 py-2 glass-surface-3 border-b border-white/10">
+                    <Search size={16} className="text-white/40 flex-shrink-0" />
+                    <input
+                      autoFocus
+                      value={windowSearch}
+                      onChange={(e) => setWindowSearch(e.target.value)}
+                      placeholder={`Search ${windowCount} windows...`}
+                      className="flex-1 min-w-0 bg-transparent text-base text-white placeholder:text-white/40 outline-none"
+                    />
+                    <button
+                      onClick={() => setIncludeMinimized(!includeMinimized)}
+                      title={includeMinimized ? "Hide minimized windows" : "Show minimized windows"}
+                      className={`flex items-center gap-1 px-2 py-1 rounded text-xs border transition-colors flex-shrink-0 ${
+                        includeMinimized
+                          ? "border-white/10 text-white/60 hover:border-white/20"
+                          : "border-primary bg-primary/20 text-white"
+                      }`}
+                    >
+                      <Minimize2 size={12} />
+                      {includeMinimized ? "All" : "Open only"}
+                    </button>
+                  </div>
+                  {windows.length === 0 && (
+                    <div className="px-4 py-3 text-sm text-white/40 text-center">
+                      No windows match
+                    </div>
+                  )}
                   {windows.map((win) => (
                     <button
                       key={win.id}
@@ -277,7 +369,11 @@ export default function MonitorPicker() {
                       onClick={() => handleCaptureWindow(win)}
                       className="w-full px-4 py-3 text-left hover:bg-white/10 transition-colors flex items-center gap-3"
                     >
-                      <AppWindow size={18} className="text-white/40 flex-shrink-0" />
+                      {win.icon ? (
+                        <img src={win.icon} alt="" className="w-[18px] h-[18px] flex-shrink-0" />
+                      ) : (
+                        <AppWindow size={18} className="text-white/40 flex-shrink-0" />
+                      )}
                       <div className="flex-1 min-w-0">
                         <div className="text-base text-white truncate">
                           {win.title || win.app_name || "Untitled"}
@@ -296,6 +392,14 @@ export default function MonitorPicker() {
                       )}
                     </button>
                   ))}
+                  {windows.length < windowTotal && (
+                    <button
+                      onClick={loadMoreWindows}
+                      className="w-full px-4 py-2.5 text-sm text-white/60 hover:text-white hover:bg-white/10 transition-colors"
+                    >
+                      Show more ({windowTotal - windows.length} remaining)
+                    </button>
+                  )}
                 </div>
               )}
             </div>
@@ -1,10 +1,10 @@
 import { useEffect, useState } from "react";
-import { useNavigate } from "react-router-dom";
+import { useLocation, useNavigate } from "react-router-dom";
 import { invoke, convertFileSrc } from "@tauri-apps/api/core";
 import { getCurrentWindow } from "@tauri-apps/api/window";
 import { listen } from "@tauri-apps/api/event";
 import { useRecorderStore, Step } from "../store/recorderStore";
-import { useRecordingsStore, StepInput } from "../store/recordingsStore";
+import { useRecordingsStore, StepInput, type OcrLine } from "../store/recordingsStore";
 import { useSettingsStore } from "../store/settingsStore";
 import { Play, Square, Wand2, Save, ArrowLeft, RotateCcw } from "lucide-react";
 import RecorderOverlay from "../features/recorder/RecorderOverlay";
@@ -17,10 +17,12 @@ import { SortableContext, sortableKeyboardCoordinates, rectSortingStrategy } fro
 
 export default function NewRecording() {
     const navigate = useNavigate();
-    const { isRecording, setIsRecording, steps, addStep, removeStep, updateStepDescription, updateStepTitle, updateStepScreenshot, reorderSteps } = useRecorderStore();
+    const location = useLocation();
+    const { isRecording, setIsRecording, steps, addStep, removeStep, updateStepDescription, updateStepTitle, updateStepScreenshot, updateStepClickMarker, updateStepElement, updateStepOcr, reorderSteps } = useRecorderStore();
     const { createRecording, saveStepsWithPath } = useRecordingsStore();
     const { screenshotPath } = useSettingsStore();
-    const [recordingName, setRecordingName] = useState("");
+    // Preset by an openscribe://record link
+    const [recordingName, setRecordingName] = useState<string>(() => (location.state as { name?: string | null } | null)?.name ?? "");
     const [showNameDialog, setShowNameDialog] = useState(false);
     const [saving, setSaving] = useState(false);
     const [generateAfterSave, setGenerateAfterSave] = useState(false);
@@ -28,6 +30,8 @@ export default function NewRecording() {
     const [deletingIndex, setDeletingIndex] = useState<number | null>(null);
     const [croppingTarget, setCroppingTarget] = useState<{ index: number; target: "before" | "after" } | null>(null);
     const [cropTimestamps, setCropTimestamps] = useState<Record<number, number>>({});
+    // Marker added by hotkey that still has its default label
+    const [namingMarker, setNamingMarker] = useState<{ stepId: string; label: string } | null>(null);
 
     const sensors = useSensors(
         useSensor(PointerSensor),
@@ -36,6 +40,13 @@ export default function NewRecording() {
         })
     );
 
+    // Steps scrolled into view jump the OCR queue.
+    const prioritizeOcr = (stepId: string) => {
+        invoke("prioritize_ocr_steps", { stepIds: [stepId] }).catch((error) =>
+            console.error("Failed to prioritize OCR:", error),
+        );
+    };
+
     const deleteStep = async (index: number) => {
         setDeletingIndex(index);
         const step = steps[index];
@@ -55,6 +66,16 @@ export default function NewRecording() {
             addStep(event.payload);
         });
 
+        // Element info from an accessibility lookup that missed the capture deadline.
+        const unlistenStepElement = listen<{ step_id: string; element: Partial<Step> }>("new-step-element", (event) => {
+            updateStepElement(event.payload.step_id, event.payload.element);
+        });
+
+        // OCR text, or accessibility labels when OCR is off or unavailable.
+        const unlistenOcr = listen<{ step_id: string; ocr_text: string | null; ocr_lines: OcrLine[] | null; status: string }>("ocr-step-complete", (event) => {
+            updateStepOcr(event.payload.step_id, event.payload.ocr_text, event.payload.status, event.payload.ocr_lines);
+        });
+
         // Listen for manual captures from the monitor picker
         const unlistenManualCapture = listen<string>("manual-capture-complete", (event) => {
             const screenshotPath = event.payload;
@@ -68,9 +89,50 @@ export default function NewRecording() {
 
         return () => {
             unlisten.then((f) => f());
+            unlistenStepElement.then((f) => f());
+            unlistenOcr.then((f) => f());
             unlistenManualCapture.then((f) => f());
         };
-    }, [addStep]);
+    }, [addStep, updateStepElement, updateStepOcr]);
+
+    useEffect(() => {
+        if (!isRecording) return;
+
+        const unlistenUndo = listen("hotkey-undo", () => {
+            if (steps.length > 0) {
+                void deleteStep(steps.length - 1);
+            }
+        });
+
+        // The backend adds the step (so it's journaled like captured ones)
+        // and reports it in `marker-added`
+        const unlistenMarker = listen("hotkey-marker", () => {
+            const chapter = steps.filter((step) => step.type_ === "marker").length + 1;
+            invoke("add_marker", { label: `Chapter ${chapter}` }).catch((error) =>
+                console.error("Failed to add marker:", error),
+            );
+        });
+
+        const unlistenMarkerAdded = listen<{ step_id: string; label: string }>("marker-added", (event) => {
+            setNamingMarker({ stepId: event.payload.step_id, label: event.payload.label });
+        });
+
+        return () => {
+            unlistenUndo.then((f) => f());
+            unlistenMarker.then((f) => f());
+            unlistenMarkerAdded.then((f) => f());
+        };
+    }, [isRecording, steps]);
+
+    const saveMarkerLabel = () => {
+        if (!namingMarker) return;
+        const index = steps.findIndex((step) => step.id === namingMarker.stepId);
+        const label = namingMarker.label.trim();
+        if (index !== -1 && label) {
+            updateStepTitle(index, label);
+        }
+        setNamingMarker(null);
+    };
 
     const startRecording = async () => {
         try {
@@ -168,6 +230,14 @@ export default function NewRecording() {
                 timestamp: step.timestamp,
                 screenshot: step.screenshot,
                 screenshot_after: step.screenshot_after,
+                element_screenshot: step.element_screenshot,
+                ocr_text: step.ocr_text,
+                ocr_status: step.ocr_status,
+                ocr_lines: step.ocr_lines,
+                click_marker: step.click_marker,
+                scale_factor: step.scale_factor,
+                is_duplicate: step.is_duplicate,
+                screenshot_original: step.screenshot_original,
                 element_name: step.element_name,
                 element_type: step.element_type,
                 element_value: step.element_value,
@@ -177,9 +247,22 @@ export default function NewRecording() {
                 input_source: step.input_source,
                 clip_path: step.clip_path,
                 title: step.title,
+                element_bounds: step.element_bounds,
+                page_url: step.page_url,
+                page_title: step.page_title,
+                automation_id: step.automation_id,
+                class_name: step.class_name,
+                element_path: step.element_path,
+                process_name: step.process_name,
+                exe_path: step.exe_path,
+                window_title: step.window_title,
+                ax_tree_json: step.ax_tree_json,
+                action: step.action,
+                breadcrumb: step.breadcrumb,
             }));
 
             await saveStepsWithPath(recordingId, name, stepInputs, screenshotPath || undefined);
+            await invoke("clear_drafts");
             setShowNameDialog(false);
             setRecordingName("");
 
@@ -204,9 +287,10 @@ export default function NewRecording() {
             {/* Image Editor Modal */}
             {croppingTarget !== null && (() => {
                 const editingStep = steps[croppingTarget.index];
+                // The editor starts from the lossless original when one is kept
                 const editingPath = croppingTarget.target === "after"
                     ? editingStep?.screenshot_after
-                    : editingStep?.screenshot;
+                    : editingStep?.screenshot_original ?? editingStep?.screenshot;
                 return editingPath ? (
                     <ImageEditor
                         imageSrc={convertFileSrc(editingPath)}
@@ -216,6 +300,42 @@ export default function NewRecording() {
                 ) : null;
             })()}
 
+            {/* Marker Label Dialog */}
+            {namingMarker && !showNameDialog && (
+                <div className="fixed inset-0 bg-black/60 backdrop-blur-sm flex items-center justify-center z-50">
+                    <div className="glass-surface-2 rounded-2xl p-6 w-96">
+                        <h3 className="text-lg font-semibold mb-4">Name Marker</h3>
+                        <input
+                            type="text"
+                            value={namingMarker.label}
+                            onChange={(e) => setNamingMarker({ ...namingMarker, label: e.target.value })}
+                            placeholder="Enter chapter name..."
+                            className="w-full px-3 py-2 bg-[#161316] border border-white/10 rounded-md text-white placeholder-white/50 focus:outline-none focus:border-[#2721E8] mb-4"
+                            autoFocus
+                            onKeyDown={(e) => {
+                                if (e.key === 'Enter') {
+                                    saveMarkerLabel();
+                                }
+                            }}
+                        />
+                        <div className="flex justify-end gap-2">
+                            <button
+                                onClick={() => setNamingMarker(null)}
+                                className="px-4 py-2 rounded-md hover:bg-white/10 transition-colors"
+                            >
+                                Skip
+                            </button>
+                            <button
+                                onClick={saveMarkerLabel}
+                                className="px-4 py-2 bg-[#2721E8] hover:bg-[#4a45f5] rounded-md font-medium transition-colors"
+                            >
+                                Save
+                            </button>
+                        </div>
+                    </div>
+                </div>
+            )}
+
             {/* Name Dialog */}
             {showNameDialog && (
                 <div className="fixed inset-0 bg-black/60 backdrop-blur-sm flex items-center justify-center z-50">
@@ -355,8 +475,10 @@ export default function NewRecording() {
                                     onCrop={(target) => setCroppingTarget({ index, target })}
                                     onUpdateDescription={(desc) => updateStepDescription(index, desc)}
                                     onUpdateTitle={(title) => updateStepTitle(index, title)}
+                                    onUpdateClickMarker={(marker) => updateStepClickMarker(index, marker)}
                                     isDeleting={deletingIndex === index}
                                     cropTimestamp={cropTimestamps[index]}
+                                    onVisible={step.id && !step.ocr_status ? () => prioritizeOcr(step.id!) : undefined}
                                 />
                             ))}
                         </div>
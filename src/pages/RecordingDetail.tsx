@@ -8,9 +8,14 @@ import {
     AlertTriangle,
     ArrowLeft,
     Check,
+    Mic,
+    MousePointerClick,
     Pencil,
     Play,
     Save,
+    ShieldAlert,
+    SlidersHorizontal,
+    Sparkles,
     Square,
     Wand2,
     X,
@@ -27,13 +32,26 @@ import { mapStepsForAI } from "../lib/stepMapper";
 import { extractH2s, isDefaultStepHeading, replaceNthH2 } from "../lib/markdownHeadings";
 import { useRecorderStore } from "../store/recorderStore";
 import { useGenerationStore } from "../store/generationStore";
-import { useRecordingsStore, Step as DBStep } from "../store/recordingsStore";
+import {
+    useRecordingsStore,
+    Step as DBStep,
+    type AnnotationPoint,
+    type ImageEdit,
+    type OcrLine,
+    type PiiKind,
+    type RecordingImageSettings,
+    type RecordingOcrStatus,
+    type RedactionSuggestion,
+} from "../store/recordingsStore";
 import { useSettingsStore } from "../store/settingsStore";
 import { useToastStore } from "../store/toastStore";
 import { log, describeError } from "../lib/logger";
 
 const StepsTab = lazy(() => import("./recording-detail/StepsTab"));
 const DocumentationEditor = lazy(() => import("./recording-detail/DocumentationEditor"));
+const ImageSettingsModal = lazy(() => import("./recording-detail/ImageSettingsModal"));
+const ReplayModal = lazy(() => import("./recording-detail/ReplayModal"));
+const NarrationModal = lazy(() => import("./recording-detail/NarrationModal"));
 const LazyImageEditor = lazy(() => import("../components/ImageEditor"));
 const LazyGenerationSplitView = lazy(() => import("../components/generation/GenerationSplitView"));
 
@@ -59,11 +77,28 @@ function DeferredModalFallback({ label }: { label: string }) {
     );
 }
 
+const PII_KIND_LABELS: Record<PiiKind, string> = {
+    email: "email addresses",
+    phone: "phone numbers",
+    card_number: "card numbers",
+    national_id: "ID numbers",
+    iban: "bank details",
+    password: "password fields",
+};
+
+/** "email addresses and password fields" for the kinds across `suggestions`. */
+function describePiiKinds(suggestions: RedactionSuggestion[]): string {
+    const labels = [...new Set(suggestions.flatMap((suggestion) => suggestion.kinds))].map(
+        (kind) => PII_KIND_LABELS[kind],
+    );
+    return labels.length > 1 ? `${labels.slice(0, -1).join(", ")} and ${labels[labels.length - 1]}` : labels[0] ?? "";
+}
+
 export default function RecordingDetail() {
     const navigate = useNavigate();
     const { id } = useParams<{ id: string }>();
     const location = useLocation();
-    const { currentRecording, getRecording, saveDocumentation, updateRecordingName, loading } = useRecordingsStore();
+    const { currentRecording, getRecording, saveDocumentation, updateRecordingName, updateStepOcr, setStepRedactions, loading } = useRecordingsStore();
     const { isRecording, setIsRecording } = useRecorderStore();
     const { openaiApiKey, openaiBaseUrl, openaiModel, screenshotPath } = useSettingsStore();
     const {
@@ -83,6 +118,7 @@ export default function RecordingDetail() {
 
     const [activeTab, setActiveTab] = useState<"steps" | "docs">("docs");
     const [showRegenerationModal, setShowRegenerationModal] = useState(false);
+    const [rewritingStepText, setRewritingStepText] = useState(false);
     const [stepsForRegeneration, setStepsForRegeneration] = useState<ReturnType<typeof mapStepsForAI>>([]);
     const [isEditing, setIsEditing] = useState(false);
     const [editedContent, setEditedContent] = useState("");
@@ -100,6 +136,13 @@ export default function RecordingDetail() {
     const [isEditingName, setIsEditingName] = useState(false);
     const [editedName, setEditedName] = useState("");
     const [nameSaving, setNameSaving] = useState(false);
+    const [ocrStatus, setOcrStatus] = useState<RecordingOcrStatus | null>(null);
+    const [imageSettings, setImageSettings] = useState<RecordingImageSettings | null>(null);
+    const [showImageSettings, setShowImageSettings] = useState(false);
+    const [showReplay, setShowReplay] = useState(false);
+    const [showNarration, setShowNarration] = useState(false);
+    const [redactionSuggestions, setRedactionSuggestions] = useState<RedactionSuggestion[]>([]);
+    const [applyingRedactions, setApplyingRedactions] = useState(false);
     const hasTriggeredGeneration = useRef(false);
     const descriptionSaveTimers = useRef<Map<string, ReturnType<typeof setTimeout>>>(new Map());
     const titleSaveTimers = useRef<Map<string, ReturnType<typeof setTimeout>>>(new Map());
@@ -114,6 +157,27 @@ export default function RecordingDetail() {
         }
     }, [id, getRecording]);
 
+    useEffect(() => {
+        if (!id) {
+            return;
+        }
+        invoke<RecordingImageSettings>("get_recording_image_settings", { recordingId: id })
+            .then(setImageSettings)
+            .catch((settingsError) => console.error("Failed to load image settings:", settingsError));
+    }, [id]);
+
+    const handleImageSettingsSaved = async (settings: RecordingImageSettings, reencoded: boolean) => {
+        setImageSettings(settings);
+        setShowImageSettings(false);
+        if (reencoded && id) {
+            await getRecording(id);
+            const refreshed = useRecordingsStore.getState().currentRecording;
+            if (refreshed) {
+                setLocalSteps(refreshed.steps);
+            }
+        }
+    };
+
     useEffect(() => {
         const descTimers = descriptionSaveTimers.current;
         const titleTimers = titleSaveTimers.current;
@@ -134,6 +198,122 @@ export default function RecordingDetail() {
         }
     }, [currentRecording?.recording.id]);
 
+    const refreshOcrStatus = (recordingId: string) => {
+        invoke<RecordingOcrStatus>("get_recording_ocr_status", { recordingId })
+            .then(setOcrStatus)
+            .catch((statusError) => console.error("Failed to load OCR status:", statusError));
+    };
+
+    useEffect(() => {
+        setOcrStatus(null);
+        if (currentRecording) {
+            refreshOcrStatus(currentRecording.recording.id);
+        }
+    }, [currentRecording?.recording.id]);
+
+    useEffect(() => {
+        setRedactionSuggestions([]);
+        if (currentRecording) {
+            invoke<RedactionSuggestion[]>("get_redaction_suggestions", { recordingId: currentRecording.recording.id })
+                .then(setRedactionSuggestions)
+                .catch((suggestionError) => console.error("Failed to load redaction suggestions:", suggestionError));
+        }
+    }, [currentRecording?.recording.id]);
+
+    const handleAcceptRedactions = async () => {
+        setApplyingRedactions(true);
+        try {
+            for (const suggestion of redactionSuggestions) {
+                const step = currentRecording?.steps.find((candidate) => candidate.id === suggestion.step_id);
+                const updated = await setStepRedactions(suggestion.step_id, [
+                    ...(step?.redactions ?? []),
+                    suggestion.redaction,
+                ]);
+                setLocalSteps((previousSteps) =>
+                    previousSteps.map((candidate) =>
+                        candidate.id === updated.id
+                            ? {
+                                  ...candidate,
+                                  screenshot_path: updated.screenshot_path,
+                                  screenshot_after_path: updated.screenshot_after_path,
+                                  element_screenshot_path: updated.element_screenshot_path,
+                                  redactions: updated.redactions,
+                                  redactions_reviewed: true,
+                              }
+                            : candidate,
+                    ),
+                );
+                setRedactionSuggestions((previous) => previous.filter((item) => item !== suggestion));
+            }
+        } catch (redactionError) {
+            setError(`Failed to apply redactions: ${String(redactionError)}`);
+        } finally {
+            setApplyingRedactions(false);
+        }
+    };
+
+    const handleDismissRedactions = async () => {
+        try {
+            await invoke("dismiss_redaction_suggestions", {
+                stepIds: redactionSuggestions.map((suggestion) => suggestion.step_id),
+            });
+            setRedactionSuggestions([]);
+        } catch (dismissError) {
+            setError(`Failed to dismiss suggestions: ${String(dismissError)}`);
+        }
+    };
+
+    // Saved steps that never got OCR text (e.g. saved before OCR finished, or
+    // dropped from a full queue) are read in the background and persisted as
+    // results arrive.
+    useEffect(() => {
+        const pending = (currentRecording?.steps ?? []).filter(
+            (step) =>
+                step.screenshot_path &&
+                !step.ocr_text &&
+                (!step.ocr_status || step.ocr_status === "pending" || step.ocr_status === "skipped"),
+        );
+        if (pending.length === 0) {
+            return;
+        }
+
+        const pendingIds = new Set(pending.map((step) => step.id));
+        type OcrResultPayload = { step_id: string; ocr_text: string | null; ocr_lines: OcrLine[] | null; status: string };
+        const unlistenOcr = listen<OcrResultPayload>("ocr-step-complete", (event) => {
+            const { step_id: stepId, ocr_text: ocrText, ocr_lines: ocrLines, status } = event.payload;
+            if (!pendingIds.delete(stepId)) return;
+            void updateStepOcr(stepId, ocrText, status, ocrLines).then(() => {
+                if (currentRecording) {
+                    refreshOcrStatus(currentRecording.recording.id);
+                }
+            });
+            setLocalSteps((previousSteps) =>
+                previousSteps.map((step) =>
+                    step.id === stepId
+                        ? { ...step, ocr_text: ocrText ?? undefined, ocr_status: status, ocr_lines: ocrLines ?? undefined }
+                        : step,
+                ),
+            );
+        });
+        unlistenOcr
+            .then(() =>
+                invoke("queue_ocr_backfill", {
+                    steps: pending.map((step) => ({
+                        step_id: step.id,
+                        screenshot_path: step.screenshot_path,
+                        x: step.x ?? null,
+                        y: step.y ?? null,
+                        step_type: step.type_,
+                    })),
+                }),
+            )
+            .catch((backfillError) => console.error("Failed to queue OCR backfill:", backfillError));
+
+        return () => {
+            unlistenOcr.then((stopListening) => stopListening());
+        };
+    }, [currentRecording?.recording.id]);
+
     useEffect(() => {
         if (
             location.state?.triggerGeneration &&
@@ -207,6 +387,14 @@ export default function RecordingDetail() {
             if (newStep.screenshot) {
                 finalScreenshotPath = await copyScreenshotToPermanent(newStep.screenshot);
             }
+            let elementScreenshotPath = newStep.element_screenshot;
+            if (newStep.element_screenshot) {
+                elementScreenshotPath = await copyScreenshotToPermanent(newStep.element_screenshot);
+            }
+            let originalPath = newStep.screenshot_original;
+            if (newStep.screenshot_original) {
+                originalPath = await copyScreenshotToPermanent(newStep.screenshot_original);
+            }
 
             setLocalSteps((previousSteps) => {
                 const nextSteps = [...previousSteps];
@@ -216,6 +404,8 @@ export default function RecordingDetail() {
                     id: tempId,
                     recording_id: id!,
                     screenshot_path: finalScreenshotPath,
+                    element_screenshot_path: elementScreenshotPath,
+                    original_path: originalPath,
                     order_index: insertIndex,
                 });
                 return nextSteps;
@@ -269,6 +459,41 @@ export default function RecordingDetail() {
             setHasUnsavedChanges(true);
         });
 
+        // Element info from an accessibility lookup that missed the capture
+        // deadline; the step was added without it.
+        type StepElementPayload = { step_id: string; element: Partial<DBStep> };
+        const unlistenStepElement = listen<StepElementPayload>("new-step-element", (event) => {
+            const tempId = recorderIdToTempId.current.get(event.payload.step_id);
+            if (!tempId) return;
+            setLocalSteps((previousSteps) =>
+                previousSteps.map((step) =>
+                    step.id === tempId
+                        ? { ...step, ...event.payload.element }
+                        : step,
+                ),
+            );
+            setHasUnsavedChanges(true);
+        });
+
+        // OCR text, or accessibility labels when OCR is off or unavailable.
+        type OcrResultPayload = { step_id: string; ocr_text: string | null; ocr_lines: OcrLine[] | null; status: string };
+        const unlistenOcr = listen<OcrResultPayload>("ocr-step-complete", (event) => {
+            const tempId = recorderIdToTempId.current.get(event.payload.step_id);
+            if (!tempId) return;
+            setLocalSteps((previousSteps) =>
+                previousSteps.map((step) =>
+                    step.id === tempId
+                        ? {
+                              ...step,
+                              ocr_text: event.payload.ocr_text ?? undefined,
+                              ocr_status: event.payload.status,
+                              ocr_lines: event.payload.ocr_lines ?? undefined,
+                          }
+                        : step,
+                ),
+            );
+        });
+
         const unlistenManualCapture = listen<string>("manual-capture-complete", async (event) => {
             const tempScreenshotPath = event.payload;
             const tempId = `temp-${Date.now()}-${Math.random()}`;
@@ -299,6 +524,8 @@ export default function RecordingDetail() {
             unlistenStep.then((stopListening) => stopListening());
             unlistenStepAfter.then((stopListening) => stopListening());
             unlistenStepClip.then((stopListening) => stopListening());
+            unlistenStepElement.then((stopListening) => stopListening());
+            unlistenOcr.then((stopListening) => stopListening());
             unlistenManualCapture.then((stopListening) => stopListening());
             // Clear the lookup table so a subsequent recording session starts fresh.
             recorderIdToTempId.current.clear();
@@ -420,6 +647,33 @@ export default function RecordingDetail() {
         }
     };
 
+    const handleRewriteStepText = async () => {
+        if (!id) {
+            return;
+        }
+        setRewritingStepText(true);
+        try {
+            const result = await invoke<{ intro: string; steps: { step_id: string }[] }>(
+                "generate_step_descriptions",
+                { recordingId: id },
+            );
+            await getRecording(id);
+            useToastStore.getState().showToast({
+                message: `Rewrote ${result.steps.length} step description(s)`,
+                variant: "success",
+            });
+        } catch (err) {
+            useToastStore.getState().showToast({
+                title: "Rewriting step text failed",
+                message: String(err),
+                variant: "error",
+                durationMs: 15000,
+            });
+        } finally {
+            setRewritingStepText(false);
+        }
+    };
+
     const handleCancelRegeneration = () => {
         cancelGeneration();
         setShowRegenerationModal(false);
@@ -612,6 +866,14 @@ export default function RecordingDetail() {
                     timestamp: step.timestamp,
                     screenshot: step.screenshot_path,
                     screenshot_after: step.screenshot_after_path,
+                    element_screenshot: step.element_screenshot_path,
+                    ocr_text: step.ocr_text,
+                    ocr_status: step.ocr_status,
+                    ocr_lines: step.ocr_lines,
+                    click_marker: step.click_marker,
+                    scale_factor: step.scale_factor,
+                    is_duplicate: step.is_duplicate,
+                    screenshot_original: step.original_path,
                     element_name: step.element_name,
                     element_type: step.element_type,
                     element_value: step.element_value,
@@ -624,6 +886,18 @@ export default function RecordingDetail() {
                     input_source: step.input_source,
                     identified_element_json: step.identified_element_json,
                     clip_path: step.clip_path,
+                    element_bounds: step.element_bounds,
+                    page_url: step.page_url,
+                    page_title: step.page_title,
+                    automation_id: step.automation_id,
+                    class_name: step.class_name,
+                    element_path: step.element_path,
+                    process_name: step.process_name,
+                    exe_path: step.exe_path,
+                    window_title: step.window_title,
+                    ax_tree_json: step.ax_tree_json,
+                    action: step.action,
+                    breadcrumb: step.breadcrumb,
                 }));
 
             if (stepsToSave.length > 0) {
@@ -633,6 +907,7 @@ export default function RecordingDetail() {
                     steps: stepsToSave,
                     screenshotPath: screenshotPath || null,
                 });
+                await invoke("clear_drafts");
             }
 
             const existingSteps = localSteps
@@ -720,6 +995,26 @@ export default function RecordingDetail() {
         descriptionSaveTimers.current.set(stepId, timer);
     };
 
+    const handleUpdateClickMarker = async (stepId: string, marker: AnnotationPoint | null) => {
+        setLocalSteps((previousSteps) =>
+            previousSteps.map((step) =>
+                step.id === stepId ? { ...step, click_marker: marker } : step,
+            ),
+        );
+
+        if (stepId.startsWith("temp-")) {
+            setHasUnsavedChanges(true);
+            return;
+        }
+
+        try {
+            await invoke("update_step_click_marker", { stepId, marker });
+        } catch (updateError) {
+            console.error("Failed to update click marker:", updateError);
+            setError(updateError instanceof Error ? updateError.message : "Failed to update click marker");
+        }
+    };
+
     const handleUpdateTitle = (stepId: string, title: string) => {
         // Determine the step index BEFORE updating local state so the doc
         // rewrite targets the correct H2 regardless of any concurrent reorders.
@@ -784,7 +1079,7 @@ export default function RecordingDetail() {
         }
 
         try {
-            await invoke("save_cropped_image", {
+            const savedPath = await invoke<string>("save_cropped_image", {
                 path: targetPath,
                 base64Data: croppedImageBase64,
             });
@@ -792,12 +1087,12 @@ export default function RecordingDetail() {
             if (target === "after") {
                 await invoke("update_step_after_screenshot", {
                     stepId,
-                    screenshotAfterPath: targetPath,
+                    screenshotAfterPath: savedPath,
                 });
             } else {
                 await invoke("update_step_screenshot", {
                     stepId,
-                    screenshotPath: targetPath,
+                    screenshotPath: savedPath,
                     isCropped: true,
                 });
             }
@@ -815,6 +1110,23 @@ export default function RecordingDetail() {
         setCroppingStep(null);
     };
 
+    const handleEditStepImage = async (stepId: string, edit: ImageEdit, target: "before" | "after") => {
+        try {
+            await invoke<string>("edit_step_image", {
+                stepId,
+                image: target === "after" ? "after" : "screenshot",
+                edit,
+            });
+            setCropTimestamps((previousTimestamps) => ({ ...previousTimestamps, [stepId]: Date.now() }));
+            if (id) {
+                await getRecording(id);
+            }
+        } catch (editError) {
+            console.error("Failed to edit image:", editError);
+            setError(editError instanceof Error ? editError.message : "Failed to edit image");
+        }
+    };
+
     const handleStartEditName = () => {
         if (!currentRecording) {
             return;
@@ -868,10 +1180,11 @@ export default function RecordingDetail() {
     const croppingStepRow = croppingStep
         ? currentRecording?.steps.find((step) => step.id === croppingStep.stepId)
         : null;
+    // The editor starts from the lossless original when one is kept
     const croppingSourcePath = croppingStep
         ? (croppingStep.target === "after"
             ? croppingStepRow?.screenshot_after_path
-            : croppingStepRow?.screenshot_path)
+            : croppingStepRow?.original_path ?? croppingStepRow?.screenshot_path)
         : undefined;
 
     const isDocumentationStale = !!(
@@ -916,6 +1229,37 @@ export default function RecordingDetail() {
                 </Suspense>
             )}
 
+            {showImageSettings && id && imageSettings && (
+                <Suspense fallback={<DeferredModalFallback label="Loading image settings..." />}>
+                    <ImageSettingsModal
+                        recordingId={id}
+                        settings={imageSettings}
+                        canReencode={!hasUnsavedChanges && !isRecording}
+                        onClose={() => setShowImageSettings(false)}
+                        onSaved={(settings, reencoded) => {
+                            void handleImageSettingsSaved(settings, reencoded);
+                        }}
+                    />
+                </Suspense>
+            )}
+
+            {showReplay && id && (
+                <Suspense fallback={<DeferredModalFallback label="Loading replay..." />}>
+                    <ReplayModal recordingId={id} onClose={() => setShowReplay(false)} />
+                </Suspense>
+            )}
+
+            {showNarration && id && (
+                <Suspense fallback={<DeferredModalFallback label="Loading transcription..." />}>
+                    <NarrationModal
+                        recordingId={id}
+                        steps={localSteps}
+                        onApply={handleUpdateDescription}
+                        onClose={() => setShowNarration(false)}
+                    />
+                </Suspense>
+            )}
+
             {showRegenerationModal && (
                 <div className="fixed inset-0 z-50 flex items-center justify-center bg-black/80 p-8">
                     <div className="glass-surface-1 h-[80vh] w-full max-w-6xl rounded-xl p-6">
@@ -1045,6 +1389,48 @@ export default function RecordingDetail() {
                                         </button>
                                     </Tooltip>
                                 )}
+                                <Tooltip content="Image settings for this recording">
+                                    <button
+                                        aria-label="Image settings for this recording"
+                                        onClick={() => setShowImageSettings(true)}
+                                        disabled={!imageSettings}
+                                        className="rounded-md bg-white/10 p-2 transition-colors hover:bg-white/15 disabled:opacity-50"
+                                    >
+                                        <SlidersHorizontal size={18} />
+                                    </button>
+                                </Tooltip>
+                                <Tooltip content="Replay the recorded clicks and typing">
+                                    <button
+                                        aria-label="Replay the recorded clicks and typing"
+                                        onClick={() => setShowReplay(true)}
+                                        disabled={isRecording}
+                                        className="rounded-md bg-white/10 p-2 transition-colors hover:bg-white/15 disabled:opacity-50"
+                                    >
+                                        <MousePointerClick size={18} />
+                                    </button>
+                                </Tooltip>
+                                <Tooltip content="Draft step descriptions from recorded narration">
+                                    <button
+                                        aria-label="Draft step descriptions from recorded narration"
+                                        onClick={() => setShowNarration(true)}
+                                        disabled={isRecording || hasUnsavedChanges}
+                                        className="rounded-md bg-white/10 p-2 transition-colors hover:bg-white/15 disabled:opacity-50"
+                                    >
+                                        <Mic size={18} />
+                                    </button>
+                                </Tooltip>
+                                <Tooltip content="Rewrite step text with AI, from step details rather than screenshots">
+                                    <button
+                                        aria-label="Rewrite step text with AI"
+                                        onClick={() => {
+                                            void handleRewriteStepText();
+                                        }}
+                                        disabled={rewritingStepText || hasUnsavedChanges || isGenerating}
+                                        className="rounded-md bg-white/10 p-2 transition-colors hover:bg-white/15 disabled:opacity-50"
+                                    >
+                                        {rewritingStepText ? <Spinner size="sm" /> : <Sparkles size={18} />}
+                                    </button>
+                                </Tooltip>
                                 {isRecording && (
                                     <Tooltip content="Stop recording">
                                         <button
@@ -1096,9 +1482,20 @@ export default function RecordingDetail() {
                                                 <Pencil size={18} />
                                             </button>
                                         </Tooltip>
+                                        {ocrStatus && !ocrStatus.finished && (
+                                            <Tooltip content="Screenshot text is still being read, so exports may be missing it">
+                                                <div className="flex items-center gap-2 text-sm text-white/60">
+                                                    <Spinner size="sm" />
+                                                    <span>
+                                                        Reading text {ocrStatus.total - ocrStatus.pending - ocrStatus.skipped}/{ocrStatus.total}
+                                                    </span>
+                                                </div>
+                                            </Tooltip>
+                                        )}
                                         <ExportDropdown
                                             markdown={currentRecording.recording.documentation}
                                             fileName={currentRecording.recording.name}
+                                            recordingId={currentRecording.recording.id}
                                         />
                                     </>
                                 )}
@@ -1133,6 +1530,39 @@ export default function RecordingDetail() {
                     </div>
                 )}
 
+                {redactionSuggestions.length > 0 && (
+                    <div className="mb-6 flex items-center justify-between rounded-lg border border-amber-500/50 bg-amber-500/20 p-3">
+                        <div className="flex items-center gap-2">
+                            <ShieldAlert size={18} className="text-amber-400" />
+                            <span className="text-sm text-amber-200">
+                                {redactionSuggestions.length} {redactionSuggestions.length === 1 ? "step shows" : "steps show"}{" "}
+                                {describePiiKinds(redactionSuggestions)} that may need hiding.
+                            </span>
+                        </div>
+                        <div className="flex items-center gap-2">
+                            <button
+                                onClick={() => {
+                                    void handleDismissRedactions();
+                                }}
+                                disabled={applyingRedactions}
+                                className="rounded-md px-3 py-1 text-xs text-amber-200 transition-colors hover:bg-white/10 disabled:opacity-50"
+                            >
+                                Dismiss
+                            </button>
+                            <button
+                                onClick={() => {
+                                    void handleAcceptRedactions();
+                                }}
+                                disabled={applyingRedactions}
+                                className="flex items-center gap-2 rounded-md bg-amber-600 px-3 py-1 text-xs text-white transition-colors hover:bg-amber-700 disabled:opacity-50"
+                            >
+                                {applyingRedactions && <Spinner size="sm" />}
+                                Redact all
+                            </button>
+                        </div>
+                    </div>
+                )}
+
                 {activeTab === "docs" ? (
                     <div className={`glass-surface-scroll rounded-xl print-content ${isEditing ? "" : "p-6"}`}>
                         {isDocumentationStale && !isEditing && (
@@ -1192,17 +1622,32 @@ export default function RecordingDetail() {
                             insertPosition={insertPosition}
                             deletingStepId={deletingStepId}
                             cropTimestamps={cropTimestamps}
+                            highlightStyle={imageSettings?.highlight}
                             onDeleteStep={(stepId) => {
                                 void handleDeleteStep(stepId);
                             }}
                             onCropStep={(stepId, target) => setCroppingStep({ stepId, target })}
+                            onEditStepImage={(stepId, edit, target) => {
+                                void handleEditStepImage(stepId, edit, target);
+                            }}
                             onUpdateDescription={(stepId, description) => {
                                 void handleUpdateDescription(stepId, description);
                             }}
                             onUpdateTitle={(stepId, title) => {
                                 void handleUpdateTitle(stepId, title);
                             }}
+                            onUpdateClickMarker={(stepId, marker) => {
+                                void handleUpdateClickMarker(stepId, marker);
+                            }}
                             onSelectInsertPosition={handleSelectInsertPosition}
+                            onStepVisible={(stepId) => {
+                                const step = localSteps.find((candidate) => candidate.id === stepId);
+                                if (step && !step.ocr_text && step.ocr_status !== "completed") {
+                                    invoke("prioritize_ocr_steps", { stepIds: [stepId] }).catch((prioritizeError) =>
+                                        console.error("Failed to prioritize OCR:", prioritizeError),
+                                    );
+                                }
+                            }}
                             onReorder={handleReorderSteps}
                         />
                     </Suspense>
@@ -22,7 +22,8 @@ export default function RecordingsList() {
         nextPage,
         prevPage,
         deletionProgress,
-        deletingRecordingName
+        deletingRecordingName,
+        stepMatches
     } = useRecordingsStore();
     const { clearSteps } = useRecorderStore();
     const [searchQuery, setSearchQuery] = useState("");
@@ -125,6 +126,26 @@ export default function RecordingsList() {
                     </div>
                 }
             >
+                {searchQuery && stepMatches.length > 0 && (
+                    <div className="mb-4">
+                        <h3 className="text-sm font-medium text-white/70 mb-2">Matching steps</h3>
+                        <ul className="space-y-1">
+                            {stepMatches.map((match) => (
+                                <li key={match.step_id}>
+                                    <button
+                                        onClick={() => navigate(`/recordings/${match.recording_id}`)}
+                                        onMouseEnter={handlePreloadRecording}
+                                        className="w-full text-left px-3 py-2 bg-[#161316]/70 border border-white/10 rounded-md hover:border-[#2721E8] transition-colors"
+                                    >
+                                        <span className="text-white">{match.recording_name}</span>
+                                        <span className="text-white/50"> · Step {match.order_index + 1}</span>
+                                        <p className="text-sm text-white/60 truncate">{match.snippet}</p>
+                                    </button>
+                                </li>
+                            ))}
+                        </ul>
+                    </div>
+                )}
                 {loading && recordings.length === 0 ? (
                     <div className="flex items-center justify-center h-64">
                         <div className="text-white/50">Loading recordings...</div>
@@ -0,0 +1,257 @@
+import { useState } from "react";
+import { invoke } from "@tauri-apps/api/core";
+import { X } from "lucide-react";
+
+import Spinner from "../../components/Spinner";
+import type { RecordingImageSettings, ScreenshotFormat } from "../../store/recordingsStore";
+import { useSettingsStore, type HighlightShape } from "../../store/settingsStore";
+
+const FORMAT_OPTIONS: { value: ScreenshotFormat | null; label: string }[] = [
+    { value: null, label: "Default" },
+    { value: "jpeg", label: "JPEG" },
+    { value: "png", label: "PNG" },
+];
+
+const MAX_WIDTH_OPTIONS: { value: number | null; label: string }[] = [
+    { value: null, label: "Original" },
+    { value: 2560, label: "2560px" },
+    { value: 1920, label: "1920px" },
+    { value: 1280, label: "1280px" },
+];
+
+const HIGHLIGHT_SHAPE_LABELS: Record<HighlightShape, string> = {
+    ring: "Ring",
+    arrow: "Arrow",
+    spotlight: "Spotlight",
+};
+
+/** JPEG quality the recorder captures at, shown until the recording picks its own. */
+const CAPTURE_QUALITY = 85;
+
+interface ImageSettingsModalProps {
+    recordingId: string;
+    settings: RecordingImageSettings;
+    /** Existing screenshots can only be re-encoded with no unsaved step edits. */
+    canReencode: boolean;
+    onClose: () => void;
+    /** `reencoded` is set when existing screenshots were rewritten. */
+    onSaved: (settings: RecordingImageSettings, reencoded: boolean) => void;
+}
+
+const buttonClass = (selected: boolean) =>
+    `px-3 py-1.5 rounded-lg text-sm transition-colors ${
+        selected ? "bg-[#2721E8] text-white" : "bg-white/10 text-white/60 hover:bg-white/20"
+    }`;
+
+export default function ImageSettingsModal({ recordingId, settings, canReencode, onClose, onSaved }: ImageSettingsModalProps) {
+    const globalHighlight = useSettingsStore((state) => state.clickHighlightStyle);
+    const [draft, setDraft] = useState<RecordingImageSettings>(settings);
+    const [reencode, setReencode] = useState(false);
+    const [saving, setSaving] = useState(false);
+    const [error, setError] = useState<string | null>(null);
+
+    const update = (changes: Partial<RecordingImageSettings>) => setDraft((current) => ({ ...current, ...changes }));
+    const overridesEncoding = draft.format !== null || draft.quality !== null || draft.max_width !== null;
+    const highlight = draft.highlight;
+
+    const handleSave = async () => {
+        setSaving(true);
+        setError(null);
+        try {
+            await invoke("set_recording_image_settings", { recordingId, settings: draft });
+            const reencoded = reencode && canReencode && overridesEncoding;
+            if (reencoded) {
+                await invoke("reencode_screenshots", {
+                    recordingIds: [recordingId],
+                    settings: {
+                        format: draft.format ?? "jpeg",
+                        quality: draft.quality ?? CAPTURE_QUALITY,
+                        max_width: draft.max_width,
+                    },
+                });
+            }
+            onSaved(draft, reencoded);
+        } catch (saveError) {
+            setError(String(saveError));
+        } finally {
+            setSaving(false);
+        }
+    };
+
+    return (
+        <div className="fixed inset-0 z-50 flex items-center justify-center bg-black/80 p-8">
+            <div className="glass-surface-1 w-full max-w-lg space-y-5 rounded-xl p-6">
+                <div className="flex items-start justify-between">
+                    <div>
+                        <h3 className="text-lg font-medium text-white">Image Settings</h3>
+                        <p className="mt-1 text-xs text-white/50">
+                            Override the defaults for this recording only. New screenshots are saved this way; existing ones can be re-encoded.
+                        </p>
+                    </div>
+                    <button
+                        aria-label="Close"
+                        onClick={onClose}
+                        className="rounded-md p-1 text-white/60 transition-colors hover:bg-white/10 hover:text-white"
+                    >
+                        <X size={18} />
+                    </button>
+                </div>
+
+                <div>
+                    <label className="mb-2 block text-sm font-medium text-white/80">Format</label>
+                    <div className="flex gap-2">
+                        {FORMAT_OPTIONS.map((option) => (
+                            <button
+                                key={option.label}
+                                onClick={() => update({ format: option.value, quality: option.value === "png" ? null : draft.quality })}
+                                className={buttonClass(draft.format === option.value)}
+                            >
+                                {option.label}
+                            </button>
+                        ))}
+                    </div>
+                </div>
+
+                {draft.format !== "png" && (
+                    <div>
+                        <label className="mb-2 block text-sm font-medium text-white/80">Quality</label>
+                        <div className="flex items-center gap-4">
+                            <input
+                                type="range"
+                                min="10"
+                                max="95"
+                                step="5"
+                                value={draft.quality ?? CAPTURE_QUALITY}
+                                onChange={(e) => update({ quality: parseInt(e.target.value) })}
+                                aria-label="JPEG quality"
+                                className="h-2 flex-1 cursor-pointer appearance-none rounded-lg bg-white/10 accent-[#2721E8]"
+                            />
+                            <span className="w-8 text-center text-sm text-white/80">{draft.quality ?? CAPTURE_QUALITY}</span>
+                            {draft.quality !== null && (
+                                <button onClick={() => update({ quality: null })} className="text-xs text-white/50 hover:text-white">
+                                    Reset
+                                </button>
+                            )}
+                        </div>
+                    </div>
+                )}
+
+                <div>
+                    <label className="mb-2 block text-sm font-medium text-white/80">Maximum Width</label>
+                    <div className="flex gap-2">
+                        {MAX_WIDTH_OPTIONS.map((option) => (
+                            <button
+                                key={option.label}
+                                onClick={() => update({ max_width: option.value })}
+                                className={buttonClass(draft.max_width === option.value)}
+                            >
+                                {option.label}
+                            </button>
+                        ))}
+                    </div>
+                </div>
+
+                <div>
+                    <div className="flex items-center justify-between">
+                        <label className="block text-sm font-medium text-white/80">Own Click Highlight</label>
+                        <button
+                            aria-label={`Own click highlight: ${highlight ? "enabled" : "disabled"}`}
+                            onClick={() => update({ highlight: highlight ? null : { ...globalHighlight } })}
+                            className={`relative inline-flex h-6 w-11 flex-shrink-0 items-center rounded-full transition-colors ${
+                                highlight ? "bg-[#2721E8]" : "bg-white/20"
+                            }`}
+                        >
+                            <span
+                                className={`inline-block h-4 w-4 transform rounded-full bg-white transition-transform ${
+                                    highlight ? "translate-x-6" : "translate-x-1"
+                                }`}
+                            />
+                        </button>
+                    </div>
+                    {highlight && (
+                        <div className="mt-3 space-y-3">
+                            <div className="flex gap-2">
+                                {(Object.keys(HIGHLIGHT_SHAPE_LABELS) as HighlightShape[]).map((shape) => (
+                                    <button
+                                        key={shape}
+                                        onClick={() => update({ highlight: { ...highlight, shape } })}
+                                        className={buttonClass(highlight.shape === shape)}
+                                    >
+                                        {HIGHLIGHT_SHAPE_LABELS[shape]}
+                                    </button>
+                                ))}
+                            </div>
+                            <div className="flex items-center gap-4">
+                                <label className="flex items-center gap-2 text-xs text-white/60">
+                                    <input
+                                        type="checkbox"
+                                        checked={highlight.enabled}
+                                        onChange={(e) => update({ highlight: { ...highlight, enabled: e.target.checked } })}
+                                        className="accent-[#2721E8]"
+                                    />
+                                    Show highlight
+                                </label>
+                                <label className="flex items-center gap-2 text-xs text-white/60">
+                                    Colour
+                                    <input
+                                        type="color"
+                                        value={highlight.color}
+                                        onChange={(e) => update({ highlight: { ...highlight, color: e.target.value } })}
+                                        className="h-6 w-10 cursor-pointer rounded border border-white/10 bg-transparent"
+                                    />
+                                </label>
+                                <label className="flex flex-1 items-center gap-2 text-xs text-white/60">
+                                    Size
+                                    <input
+                                        type="range"
+                                        min={12}
+                                        max={80}
+                                        value={highlight.radius}
+                                        onChange={(e) => update({ highlight: { ...highlight, radius: Number(e.target.value) } })}
+                                        className="flex-1"
+                                    />
+                                </label>
+                            </div>
+                        </div>
+                    )}
+                </div>
+
+                {overridesEncoding && (
+                    <label className={`flex items-center gap-2 text-xs ${canReencode ? "text-white/60" : "text-white/30"}`}>
+                        <input
+                            type="checkbox"
+                            checked={reencode && canReencode}
+                            onChange={(e) => setReencode(e.target.checked)}
+                            disabled={!canReencode}
+                            className="accent-[#2721E8]"
+                        />
+                        Re-encode existing screenshots now
+                        {!canReencode && " (save or discard step changes first)"}
+                    </label>
+                )}
+
+                {error && <p className="text-xs text-red-400">{error}</p>}
+
+                <div className="flex justify-end gap-2">
+                    <button
+                        onClick={onClose}
+                        disabled={saving}
+                        className="rounded-lg bg-white/10 px-4 py-2 text-sm text-white/70 transition-colors hover:bg-white/20 disabled:opacity-50"
+                    >
+                        Cancel
+                    </button>
+                    <button
+                        onClick={() => {
+                            void handleSave();
+                        }}
+                        disabled={saving}
+                        className="flex items-center gap-2 rounded-lg bg-[#2721E8] px-4 py-2 text-sm text-white transition-colors hover:bg-[#2721E8]/80 disabled:opacity-50"
+                    >
+                        {saving && <Spinner size="sm" />}
+                        Save
+                    </button>
+                </div>
+            </div>
+        </div>
+    );
+}
@@ -0,0 +1,245 @@
+import { useEffect, useState } from "react";
+import { invoke } from "@tauri-apps/api/core";
+import { listen } from "@tauri-apps/api/event";
+import { open } from "@tauri-apps/plugin-dialog";
+import { X } from "lucide-react";
+
+import type { Step } from "../../store/recordingsStore";
+
+/** Mirrors `WhisperModelStatus` in the backend. */
+interface WhisperModel {
+    id: string;
+    size_mb: number;
+    installed: boolean;
+}
+
+/** Mirrors `WhisperModelProgress` in the backend. */
+interface WhisperModelProgress {
+    model: string;
+    downloaded: number;
+    total: number | null;
+}
+
+/** Mirrors `NarrationDraft` in the backend. */
+interface NarrationDraft {
+    step_id: string;
+    text: string;
+}
+
+interface NarrationModalProps {
+    recordingId: string;
+    steps: Step[];
+    onApply: (stepId: string, description: string) => void;
+    onClose: () => void;
+}
+
+const inputClass =
+    "w-full px-4 py-2 bg-[#161316]/70 border border-white/10 rounded-md text-white placeholder-white/50 focus:outline-none focus:border-[#2721E8]";
+
+export default function NarrationModal({ recordingId, steps, onApply, onClose }: NarrationModalProps) {
+    const [models, setModels] = useState<WhisperModel[]>([]);
+    const [model, setModel] = useState("base");
+    const [audioPath, setAudioPath] = useState<string | null>(null);
+    const [language, setLanguage] = useState("auto");
+    const [firstStepAtSeconds, setFirstStepAtSeconds] = useState(0);
+    const [drafts, setDrafts] = useState<NarrationDraft[]>([]);
+    const [applied, setApplied] = useState<Set<string>>(new Set());
+    const [download, setDownload] = useState<WhisperModelProgress | null>(null);
+    const [transcribing, setTranscribing] = useState(false);
+    const [error, setError] = useState<string | null>(null);
+
+    const refreshModels = () =>
+        invoke<WhisperModel[]>("list_whisper_models")
+            .then(setModels)
+            .catch((err) => setError(String(err)));
+
+    useEffect(() => {
+        void refreshModels();
+        invoke<NarrationDraft[]>("get_narration_drafts", { recordingId })
+            .then(setDrafts)
+            .catch((err) => setError(String(err)));
+        const unlisten = listen<WhisperModelProgress>("whisper-model-progress", (event) => {
+            setDownload(event.payload);
+        });
+        return () => {
+            unlisten.then((f) => f());
+        };
+    }, [recordingId]);
+
+    const selectedModel = models.find((m) => m.id === model);
+
+    const handleBrowse = async () => {
+        const selected = await open({
+            multiple: false,
+            title: "Choose the narration audio",
+            filters: [{ name: "WAV audio", extensions: ["wav"] }],
+        });
+        if (typeof selected === "string") {
+            setAudioPath(selected);
+        }
+    };
+
+    const handleDownload = async () => {
+        setError(null);
+        try {
+            await invoke("download_whisper_model", { model });
+            await refreshModels();
+        } catch (downloadError) {
+            setError(String(downloadError));
+        } finally {
+            setDownload(null);
+        }
+    };
+
+    const handleTranscribe = async () => {
+        if (!audioPath) {
+            return;
+        }
+        setTranscribing(true);
+        setError(null);
+        try {
+            const result = await invoke<NarrationDraft[]>("transcribe_narration", {
+                recordingId,
+                audioPath,
+                model,
+                language,
+                firstStepAtMs: Math.round(firstStepAtSeconds * 1000),
+            });
+            setDrafts(result);
+            setApplied(new Set());
+        } catch (transcribeError) {
+            setError(String(transcribeError));
+        } finally {
+            setTranscribing(false);
+        }
+    };
+
+    const apply = (draft: NarrationDraft) => {
+        onApply(draft.step_id, draft.text);
+        setApplied((previous) => new Set(previous).add(draft.step_id));
+    };
+
+    const stepNumber = (stepId: string) => steps.findIndex((step) => step.id === stepId) + 1;
+
+    return (
+        <div className="fixed inset-0 z-50 flex items-center justify-center bg-black/80 p-8">
+            <div className="glass-surface-1 flex max-h-[85vh] w-full max-w-lg flex-col space-y-4 rounded-xl p-6">
+                <div className="flex items-start justify-between">
+                    <div>
+                        <h3 className="text-lg font-medium text-white">Transcribe Narration</h3>
+                        <p className="mt-1 text-xs text-white/50">
+                            Turns narration recorded alongside the steps into draft descriptions. Transcription runs on this computer.
+                        </p>
+                    </div>
+                    <button
+                        aria-label="Close"
+                        onClick={onClose}
+                        className="rounded-md p-1 text-white/60 transition-colors hover:bg-white/10 hover:text-white"
+                    >
+                        <X size={18} />
+                    </button>
+                </div>
+
+                <div>
+                    <label className="mb-2 block text-sm font-medium text-white/60">Audio</label>
+                    <div className="flex gap-2">
+                        <input
+                            type="text"
+                            value={audioPath ?? ""}
+                            readOnly
+                            placeholder="WAV file"
+                            className={inputClass}
+                        />
+                        <button
+                            onClick={() => {
+                                void handleBrowse();
+                            }}
+                            className="rounded-md bg-white/10 px-4 py-2 text-sm text-white/80 transition-colors hover:bg-white/15"
+                        >
+                            Browse
+                        </button>
+                    </div>
+                </div>
+
+                <div className="flex gap-2">
+                    <div className="flex-1">
+                        <label className="mb-2 block text-sm font-medium text-white/60">Model</label>
+                        <select value={model} onChange={(e) => setModel(e.target.value)} className={inputClass}>
+                            {models.map((m) => (
+                                <option key={m.id} value={m.id}>
+                                    {m.id} ({m.size_mb} MB){m.installed ? "" : " - not downloaded"}
+                                </option>
+                            ))}
+                        </select>
+                    </div>
+                    <div className="w-28">
+                        <label className="mb-2 block text-sm font-medium text-white/60">Language</label>
+                        <input
+                            type="text"
+                            value={language}
+                            onChange={(e) => setLanguage(e.target.value)}
+                            placeholder="auto"
+                            className={inputClass}
+                        />
+                    </div>
+                </div>
+
+                <div>
+                    <label className="mb-2 block text-sm font-medium text-white/60">First step happens at (seconds into the audio)</label>
+                    <input
+                        type="number"
+                        min="0"
+                        step="0.5"
+                        value={firstStepAtSeconds}
+                        onChange={(e) => setFirstStepAtSeconds(Number(e.target.value) || 0)}
+                        className={inputClass}
+                    />
+                </div>
+
+                {selectedModel && !selectedModel.installed ? (
+                    <button
+                        onClick={() => {
+                            void handleDownload();
+                        }}
+                        disabled={download !== null}
+                        className="w-full rounded-md bg-white/10 px-4 py-2 text-sm text-white/80 transition-colors hover:bg-white/15 disabled:opacity-50"
+                    >
+                        {download
+                            ? `Downloading... ${download.total ? Math.round((download.downloaded / download.total) * 100) : 0}%`
+                            : `Download ${selectedModel.id} model`}
+                    </button>
+                ) : (
+                    <button
+                        onClick={() => {
+                            void handleTranscribe();
+                        }}
+                        disabled={!audioPath || transcribing}
+                        className="w-full rounded-md bg-[#2721E8] px-4 py-2 text-sm text-white transition-colors hover:bg-[#2721E8]/90 disabled:opacity-50"
+                    >
+                        {transcribing ? "Transcribing..." : "Transcribe"}
+                    </button>
+                )}
+
+                {error && <p className="text-xs text-red-400">{error}</p>}
+
+                {drafts.length > 0 && (
+                    <div className="min-h-0 space-y-2 overflow-y-auto border-t border-white/8 pt-4">
+                        {drafts.map((draft) => (
+                            <div key={draft.step_id} className="flex items-start gap-3 rounded-md bg-white/5 p-3">
+                                <span className="text-xs font-medium text-white/50">Step {stepNumber(draft.step_id)}</span>
+                                <p className="flex-1 text-sm text-white/80">{draft.text}</p>
+                                <button
+                                    onClick={() => apply(draft)}
+                                    disabled={applied.has(draft.step_id)}
+                                    className="rounded-md bg-white/10 px-3 py-1 text-xs text-white/80 transition-colors hover:bg-white/15 disabled:opacity-50"
+                                >
+                                    {applied.has(draft.step_id) ? "Used" : "Use"}
+                                </button>
+                            </div>
+                        ))}
+                    </div>
+                )}
+            </div>
+        </div>
+    );
+}
@@ -0,0 +1,195 @@
+import { useEffect, useRef, useState } from "react";
+import { invoke } from "@tauri-apps/api/core";
+import { listen } from "@tauri-apps/api/event";
+import { X } from "lucide-react";
+
+import { useToastStore } from "../../store/toastStore";
+
+/** Mirrors `ReplayProgress` in the backend. */
+interface ReplayProgress {
+    recording_id: string;
+    step_id: string;
+    index: number;
+    total: number;
+    state: "waiting" | "running" | "skipped";
+}
+
+/** Mirrors `ReplayFinished` in the backend. */
+interface ReplayFinished {
+    recording_id: string;
+    played: number;
+    cancelled: boolean;
+    error: string | null;
+}
+
+interface ReplayModalProps {
+    recordingId: string;
+    onClose: () => void;
+}
+
+export default function ReplayModal({ recordingId, onClose }: ReplayModalProps) {
+    const [delayMs, setDelayMs] = useState(1000);
+    const [confirmEachStep, setConfirmEachStep] = useState(true);
+    const [running, setRunning] = useState(false);
+    const [progress, setProgress] = useState<ReplayProgress | null>(null);
+    const [error, setError] = useState<string | null>(null);
+    // Read from the listeners, which live as long as the modal
+    const onCloseRef = useRef(onClose);
+    onCloseRef.current = onClose;
+
+    useEffect(() => {
+        const unlistenProgress = listen<ReplayProgress>("replay-progress", (event) => {
+            if (event.payload.recording_id === recordingId) {
+                setProgress(event.payload);
+            }
+        });
+        const unlistenFinished = listen<ReplayFinished>("replay-finished", (event) => {
+            const finished = event.payload;
+            if (finished.recording_id !== recordingId) {
+                return;
+            }
+            setRunning(false);
+            setProgress(null);
+            if (finished.error) {
+                setError(finished.error);
+                return;
+            }
+            useToastStore.getState().showToast({
+                message: finished.cancelled
+                    ? `Replay stopped after ${finished.played} step(s)`
+                    : `Replayed ${finished.played} step(s)`,
+                variant: finished.cancelled ? "info" : "success",
+            });
+            onCloseRef.current();
+        });
+
+        return () => {
+            unlistenProgress.then((f) => f());
+            unlistenFinished.then((f) => f());
+        };
+    }, [recordingId]);
+
+    const handleStart = async () => {
+        setError(null);
+        try {
+            await invoke("start_replay", {
+                recordingId,
+                options: { delay_ms: delayMs, confirm_each_step: confirmEachStep },
+            });
+            setRunning(true);
+        } catch (startError) {
+            setError(String(startError));
+        }
+    };
+
+    const decide = (decision: "run" | "skip") => {
+        invoke("confirm_replay_step", { decision }).catch((decideError) => setError(String(decideError)));
+    };
+
+    const handleClose = () => {
+        if (running) {
+            void invoke("stop_replay");
+        }
+        onClose();
+    };
+
+    const waiting = progress?.state === "waiting";
+
+    return (
+        <div className="fixed inset-0 z-50 flex items-center justify-center bg-black/80 p-8">
+            <div className="glass-surface-1 w-full max-w-md space-y-5 rounded-xl p-6">
+                <div className="flex items-start justify-between">
+                    <div>
+                        <h3 className="text-lg font-medium text-white">Replay Steps</h3>
+                        <p className="mt-1 text-xs text-white/50">
+                            Repeats the recorded clicks and typing on your screen. Open the apps and windows the recording starts in first.
+                        </p>
+                    </div>
+                    <button
+                        aria-label="Close"
+                        onClick={handleClose}
+                        className="rounded-md p-1 text-white/60 transition-colors hover:bg-white/10 hover:text-white"
+                    >
+                        <X size={18} />
+                    </button>
+                </div>
+
+                {!running ? (
+                    <>
+                        <div>
+                            <label className="mb-2 block text-sm font-medium text-white/80">Delay Between Steps</label>
+                            <div className="flex items-center gap-4">
+                                <input
+                                    type="range"
+                                    min="0"
+                                    max="5000"
+                                    step="250"
+                                    value={delayMs}
+                                    onChange={(e) => setDelayMs(parseInt(e.target.value))}
+                                    aria-label="Delay between steps"
+                                    className="h-2 flex-1 cursor-pointer appearance-none rounded-lg bg-white/10 accent-[#2721E8]"
+                                />
+                                <span className="w-12 text-right text-sm text-white/80">{(delayMs / 1000).toFixed(2)}s</span>
+                            </div>
+                        </div>
+
+                        <label className="flex items-center gap-3 text-sm text-white/80">
+                            <input
+                                type="checkbox"
+                                checked={confirmEachStep}
+                                onChange={(e) => setConfirmEachStep(e.target.checked)}
+                                className="accent-[#2721E8]"
+                            />
+                            Confirm each step before it runs
+                        </label>
+
+                        <button
+                            onClick={() => {
+                                void handleStart();
+                            }}
+                            className="w-full rounded-md bg-[#2721E8] px-4 py-2 text-sm text-white transition-colors hover:bg-[#2721E8]/90"
+                        >
+                            Start Replay
+                        </button>
+                    </>
+                ) : (
+                    <>
+                        <p className="text-sm text-white/80">
+                            {progress
+                                ? `Step ${progress.index + 1} of ${progress.total}${waiting ? ": run it?" : ""}`
+                                : "Starting..."}
+                        </p>
+                        <div className="flex gap-2">
+                            {waiting && (
+                                <>
+                                    <button
+                                        onClick={() => decide("run")}
+                                        className="flex-1 rounded-md bg-[#2721E8] px-4 py-2 text-sm text-white transition-colors hover:bg-[#2721E8]/90"
+                                    >
+                                        Run Step
+                                    </button>
+                                    <button
+                                        onClick={() => decide("skip")}
+                                        className="flex-1 rounded-md bg-white/10 px-4 py-2 text-sm text-white/80 transition-colors hover:bg-white/15"
+                                    >
+                                        Skip
+                                    </button>
+                                </>
+                            )}
+                            <button
+                                onClick={() => {
+                                    void invoke("stop_replay");
+                                }}
+                                className="flex-1 rounded-md bg-red-600 px-4 py-2 text-sm text-white transition-colors hover:bg-red-700"
+                            >
+                                Stop
+                            </button>
+                        </div>
+                    </>
+                )}
+
+                {error && <p className="text-xs text-red-400">{error}</p>}
+            </div>
+        </div>
+    );
+}
@@ -4,7 +4,8 @@ import { MapPin, Plus } from "lucide-react";
 
 import DraggableStepCard from "../../components/DraggableStepCard";
 import Tooltip from "../../components/Tooltip";
-import type { Step } from "../../store/recordingsStore";
+import type { AnnotationPoint, ImageEdit, Step } from "../../store/recordingsStore";
+import type { ClickHighlightStyle } from "../../store/settingsStore";
 
 interface StepsTabProps {
     steps: Step[];
@@ -12,12 +13,17 @@ interface StepsTabProps {
     insertPosition: number | null;
     deletingStepId: string | null;
     cropTimestamps: Record<string, number>;
+    /** The recording's own click highlight, in place of the global one. */
+    highlightStyle?: ClickHighlightStyle | null;
     onDeleteStep: (stepId: string) => void;
     onCropStep: (stepId: string, target: "before" | "after") => void;
+    onEditStepImage?: (stepId: string, edit: ImageEdit, target: "before" | "after") => void;
     onUpdateDescription: (stepId: string, description: string) => void;
     onUpdateTitle?: (stepId: string, title: string) => void;
+    onUpdateClickMarker?: (stepId: string, marker: AnnotationPoint | null) => void;
     onSelectInsertPosition: (index: number) => void;
     onReorder: (activeId: string, overId: string) => void;
+    onStepVisible?: (stepId: string) => void;
 }
 
 export default function StepsTab({
@@ -26,12 +32,16 @@ export default function StepsTab({
     insertPosition,
     deletingStepId,
     cropTimestamps,
+    highlightStyle,
     onDeleteStep,
     onCropStep,
+    onEditStepImage,
     onUpdateDescription,
     onUpdateTitle,
+    onUpdateClickMarker,
     onSelectInsertPosition,
     onReorder,
+    onStepVisible,
 }: StepsTabProps) {
     const sensors = useSensors(
         useSensor(PointerSensor),
@@ -103,10 +113,14 @@ export default function StepsTab({
                                 index={index}
                                 onDelete={() => onDeleteStep(step.id)}
                                 onCrop={(target) => onCropStep(step.id, target)}
+                                onEditImage={onEditStepImage ? (edit, target) => onEditStepImage(step.id, edit, target) : undefined}
                                 onUpdateDescription={(description) => onUpdateDescription(step.id, description)}
                                 onUpdateTitle={onUpdateTitle ? (title) => onUpdateTitle(step.id, title) : undefined}
+                                onUpdateClickMarker={onUpdateClickMarker ? (marker) => onUpdateClickMarker(step.id, marker) : undefined}
                                 isDeleting={deletingStepId === step.id}
                                 cropTimestamp={cropTimestamps[step.id]}
+                                onVisible={onStepVisible ? () => onStepVisible(step.id) : undefined}
+                                highlightStyle={highlightStyle}
                             />
                         </div>
                     ))}
@@ -0,0 +1,92 @@
+import { create } from 'zustand';
+import { invoke } from '@tauri-apps/api/core';
+
+/** macOS privacy permissions; always granted on other platforms. */
+export interface PermissionStatus {
+    screen_recording: boolean;
+    accessibility: boolean;
+}
+
+export type PermissionKind = 'screen_recording' | 'accessibility';
+
+/** One requirement checked by `run_environment_check`. */
+export interface EnvironmentCheck {
+    id: 'screen_capture' | 'accessibility' | 'input' | 'data_dir' | 'screenshot_dir' | 'ocr';
+    status: 'passed' | 'warning' | 'failed';
+    detail: string;
+    fix: string | null;
+}
+
+export interface EnvironmentReport {
+    ready: boolean;
+    checks: EnvironmentCheck[];
+}
+
+interface PermissionsState {
+    status: PermissionStatus | null;
+    environment: EnvironmentReport | null;
+    dismissed: boolean;
+
+    setStatus: (status: PermissionStatus) => void;
+    refresh: () => Promise<void>;
+    checkEnvironment: () => Promise<EnvironmentReport | null>;
+    request: (permission: PermissionKind) => Promise<void>;
+    openSettings: (permission: PermissionKind) => Promise<void>;
+    dismiss: () => void;
+}
+
+export const usePermissionsStore = create<PermissionsState>((set, get) => ({
+    status: null,
+    environment: null,
+    dismissed: false,
+
+    setStatus: (status) => {
+        const previous = get().status;
+        // A newly revoked permission brings the notice back.
+        const revoked = previous !== null && (
+            (previous.screen_recording && !status.screen_recording) ||
+            (previous.accessibility && !status.accessibility)
+        );
+        set({ status, dismissed: revoked ? false : get().dismissed });
+    },
+
+    refresh: async () => {
+        try {
+            const status = await invoke<PermissionStatus>('get_permission_status');
+            get().setStatus(status);
+        } catch (error) {
+            console.error('Failed to check permissions:', error);
+        }
+    },
+
+    checkEnvironment: async () => {
+        try {
+            const environment = await invoke<EnvironmentReport>('run_environment_check');
+            set({ environment });
+            return environment;
+        } catch (error) {
+            console.error('Failed to check the environment:', error);
+            return null;
+        }
+    },
+
+    request: async (permission) => {
+        try {
+            await invoke(permission === 'accessibility'
+                ? 'request_accessibility_permission'
+                : 'request_screen_recording_permission');
+        } catch (error) {
+            console.error(`Failed to request ${permission} permission:`, error);
+        }
+    },
+
+    openSettings: async (permission) => {
+        try {
+            await invoke('open_permission_settings', { permission });
+        } catch (error) {
+            console.error('Failed to open System Settings:', error);
+        }
+    },
+
+    dismiss: () => set({ dismissed: true }),
+}));
@@ -1,4 +1,6 @@
 import { create } from 'zustand';
+import { invoke } from '@tauri-apps/api/core';
+import type { AnnotationPoint, ElementBounds, ElementPathSegment, OcrLine } from './recordingsStore';
 
 export interface Step {
     id?: string; // Unique ID from backend (for OCR tracking)
@@ -9,40 +11,75 @@ export interface Step {
     timestamp: number;
     screenshot?: string; // File path
     screenshot_after?: string; // After-frame file path (state-diff)
+    element_screenshot?: string; // Crop of the clicked control
     element_name?: string;
     element_type?: string;
     element_value?: string;
     app_name?: string;
     description?: string;
     is_cropped?: boolean;
+    /** Click highlight position in screenshot pixels; null once removed. */
+    click_marker?: AnnotationPoint | null;
+    /** Display scale of the captured monitor; coordinates are physical pixels. */
+    scale_factor?: number;
+    /** The frame repeated the previous step's, so its screenshot was reused. */
+    is_duplicate?: boolean;
+    /** Temp path of the lossless PNG kept alongside the screenshot. */
+    screenshot_original?: string;
     ocr_text?: string;
     ocr_status?: string;
+    ocr_lines?: OcrLine[];
     input_source?: string;
     clip_path?: string;
     title?: string;
+    element_bounds?: ElementBounds;
+    page_url?: string;
+    page_title?: string;
+    automation_id?: string;
+    class_name?: string;
+    element_path?: ElementPathSegment[];
+    process_name?: string;
+    exe_path?: string;
+    window_title?: string;
+    ax_tree_json?: string;
+    action?: string;
+    breadcrumb?: string;
+    /** "timeout" when element info was not available in time; see `new-step-element`. */
+    element_lookup?: string;
 }
 
 interface RecorderState {
     isRecording: boolean;
+    /** Recording but not capturing steps until resumed; see the pause hotkey. */
+    isPaused: boolean;
     steps: Step[];
     setIsRecording: (isRecording: boolean) => void;
+    setIsPaused: (isPaused: boolean) => void;
     addStep: (step: Step) => void;
     removeStep: (index: number) => void;
     clearSteps: () => void;
     updateStepDescription: (index: number, description: string) => void;
     updateStepTitle: (index: number, title: string) => void;
     updateStepScreenshot: (index: number, screenshot: string, is_cropped: boolean) => void;
-    updateStepOcr: (stepId: string, ocrText: string | null, ocrStatus: string) => void;
+    updateStepClickMarker: (index: number, marker: AnnotationPoint | null) => void;
+    updateStepOcr: (stepId: string, ocrText: string | null, ocrStatus: string, ocrLines?: OcrLine[] | null) => void;
+    updateStepElement: (stepId: string, element: Partial<Step>) => void;
     reorderSteps: (sourceIndex: number, destinationIndex: number) => void;
 }
 
 export const useRecorderStore = create<RecorderState>((set) => ({
     isRecording: false,
+    isPaused: false,
     steps: [],
-    setIsRecording: (isRecording) => set({ isRecording }),
+    setIsRecording: (isRecording) => set({ isRecording, isPaused: false }),
+    setIsPaused: (isPaused) => set({ isPaused }),
     addStep: (step) => set((state) => ({ steps: [...state.steps, step] })),
     removeStep: (index) => set((state) => ({ steps: state.steps.filter((_, i) => i !== index) })),
-    clearSteps: () => set({ steps: [] }),
+    clearSteps: () => {
+        // The in-flight steps are abandoned or already saved; drop the crash journal.
+        invoke('clear_drafts').catch((error) => console.error('Failed to clear drafts:', error));
+        set({ steps: [] });
+    },
     updateStepDescription: (index, description) => set((state) => ({
         steps: state.steps.map((step, i) =>
             i === index ? { ...step, description } : step
@@ -55,13 +92,26 @@ export const useRecorderStore = create<RecorderState>((set) => ({
     })),
     updateStepScreenshot: (index, screenshot, is_cropped) => set((state) => ({
         steps: state.steps.map((step, i) =>
-            i === index ? { ...step, screenshot, is_cropped } : step
+            // The edited screenshot no longer matches its original
+            i === index ? { ...step, screenshot, is_cropped, screenshot_original: undefined } : step
         )
     })),
-    updateStepOcr: (stepId, ocrText, ocrStatus) => set((state) => ({
+    updateStepClickMarker: (index, marker) => set((state) => ({
+        steps: state.steps.map((step, i) =>
+            i === index ? { ...step, click_marker: marker } : step
+        )
+    })),
+    updateStepOcr: (stepId, ocrText, ocrStatus, ocrLines) => set((state) => ({
+        steps: state.steps.map((step) =>
+            step.id === stepId
+                ? { ...step, ocr_text: ocrText ?? undefined, ocr_status: ocrStatus, ocr_lines: ocrLines ?? undefined }
+                : step
+        )
+    })),
+    updateStepElement: (stepId, element) => set((state) => ({
         steps: state.steps.map((step) =>
             step.id === stepId
-                ? { ...step, ocr_text: ocrText ?? undefined, ocr_status: ocrStatus }
+                ? { ...step, ...element, element_lookup: undefined }
                 : step
         )
     })),
@@ -3,6 +3,7 @@ import { invoke } from '@tauri-apps/api/core';
 import { listen, UnlistenFn } from '@tauri-apps/api/event';
 
 import { useToastStore } from './toastStore';
+import { useSettingsStore, type ClickHighlightStyle } from './settingsStore';
 import type { DeleteProgress } from '../components/DeleteProgressModal';
 
 export interface Recording {
@@ -15,6 +16,9 @@ export interface Recording {
     step_count: number;
     first_screenshot_path?: string | null;
     duration_ms?: number | null;
+    favorite?: boolean;
+    archived_at?: number | null;
+    locked?: boolean;
 }
 
 export interface Step {
@@ -27,19 +31,117 @@ export interface Step {
     timestamp: number;
     screenshot_path?: string;
     screenshot_after_path?: string;
+    /** Crop of just the clicked control, for zoomed insets. */
+    element_screenshot_path?: string;
     element_name?: string;
     element_type?: string;
     element_value?: string;
     app_name?: string;
     order_index: number;
     description?: string;
+    /** True while `description` is the default built from element/OCR info. */
+    description_generated?: boolean;
     is_cropped?: boolean;
     ocr_text?: string;
     ocr_status?: string;
+    ocr_lines?: OcrLine[];
+    annotations?: Annotation[];
+    redactions?: Redaction[];
+    redactions_reviewed?: boolean;
+    /** Where the click highlight is drawn; null once the user removed it. */
+    click_marker?: AnnotationPoint | null;
+    /** Display scale of the captured monitor; coordinates are physical pixels. */
+    scale_factor?: number | null;
+    /** The frame repeated the previous step's, so its screenshot was reused. */
+    is_duplicate?: boolean;
+    /** Lossless PNG of the screenshot that edits and exports start from. */
+    original_path?: string | null;
     input_source?: string;
     identified_element_json?: string;
     clip_path?: string;
     title?: string;
+    element_bounds?: ElementBounds;
+    page_url?: string;
+    page_title?: string;
+    automation_id?: string;
+    class_name?: string;
+    element_path?: ElementPathSegment[];
+    process_name?: string;
+    exe_path?: string;
+    window_title?: string;
+    ax_tree_json?: string;
+    action?: string;
+    breadcrumb?: string;
+}
+
+/** Clicked element rectangle, relative to the step screenshot. */
+export interface ElementBounds {
+    x: number;
+    y: number;
+    width: number;
+    height: number;
+}
+
+/** A line of OCR text and how confidently it was read. */
+export interface OcrLine {
+    text: string;
+    confidence: "high" | "medium" | "low";
+}
+
+/** Point or area in screenshot pixels. */
+export interface AnnotationPoint {
+    x: number;
+    y: number;
+}
+
+/** A rotate, flip or resize edit of a step image, applied by `edit_step_image`. */
+export type ImageEdit =
+    | { kind: "rotate_clockwise" }
+    | { kind: "rotate_counterclockwise" }
+    | { kind: "rotate180" }
+    | { kind: "flip_horizontal" }
+    | { kind: "flip_vertical" }
+    | { kind: "resize"; width: number };
+
+export interface AnnotationRect extends AnnotationPoint {
+    width: number;
+    height: number;
+}
+
+/** Vector markup drawn over a screenshot at export time. */
+export type Annotation = (
+    | { kind: "arrow"; from: AnnotationPoint; to: AnnotationPoint }
+    | { kind: "rectangle"; rect: AnnotationRect }
+    | { kind: "ellipse"; rect: AnnotationRect }
+    | { kind: "text"; at: AnnotationPoint; text: string; size?: number }
+    | { kind: "badge"; at: AnnotationPoint; label: string }
+) & {
+    /** `#RRGGBB` or `#RRGGBBAA` */
+    color?: string;
+    stroke_width?: number;
+};
+
+/** Area of a screenshot hidden by blurring or pixelating it. */
+export interface Redaction {
+    rect: AnnotationRect;
+    style?: "blur" | "pixelate";
+}
+
+export type PiiKind = "email" | "phone" | "card_number" | "national_id" | "iban" | "password";
+
+/** Redaction proposed for a step whose field shows a password or personal data. */
+export interface RedactionSuggestion {
+    step_id: string;
+    kinds: PiiKind[];
+    redaction: Redaction;
+}
+
+/** One ancestor of a clicked element, outermost first. */
+export interface ElementPathSegment {
+    role: string;
+    name?: string;
+    automation_id?: string;
+    class_name?: string;
 }
 
 export interface RecordingWithSteps {
@@ -55,6 +157,14 @@ export interface StepInput {
     timestamp: number;
     screenshot?: string;
     screenshot_after?: string;
+    element_screenshot?: string;
+    ocr_text?: string;
+    ocr_status?: string;
+    ocr_lines?: OcrLine[];
+    click_marker?: AnnotationPoint | null;
+    scale_factor?: number | null;
+    is_duplicate?: boolean;
+    screenshot_original?: string | null;
     element_name?: string;
     element_type?: string;
     element_value?: string;
@@ -65,6 +175,18 @@ export interface StepInput {
     identified_element_json?: string;
     clip_path?: string;
     title?: string;
+    element_bounds?: ElementBounds;
+    page_url?: string;
+    page_title?: string;
+    automation_id?: string;
+    class_name?: string;
+    element_path?: ElementPathSegment[];
+    process_name?: string;
+    exe_path?: string;
+    window_title?: string;
+    ax_tree_json?: string;
+    action?: string;
+    breadcrumb?: string;
 }
 
 export interface PaginatedRecordings {
@@ -75,6 +197,36 @@ export interface PaginatedRecordings {
     total_pages: number;
 }
 
+export interface RecordingOcrStatus {
+    total: number;
+    completed: number;
+    accessibility: number;
+    failed: number;
+    skipped: number;
+    pending: number;
+    finished: boolean;
+}
+
+export type ScreenshotFormat = "jpeg" | "png";
+
+/** Image settings a recording overrides the global defaults with; null follows the default. */
+export interface RecordingImageSettings {
+    format: ScreenshotFormat | null;
+    /** JPEG quality, 10-95. */
+    quality: number | null;
+    /** Wider screenshots are scaled down to this width. */
+    max_width: number | null;
+    highlight: ClickHighlightStyle | null;
+}
+
+export interface StepTextMatch {
+    recording_id: string;
+    recording_name: string;
+    step_id: string;
+    order_index: number;
+    snippet: string;
+}
+
 interface RecordingsState {
     recordings: Recording[];
     currentRecording: RecordingWithSteps | null;
@@ -87,6 +239,8 @@ interface RecordingsState {
     totalCount: number;
     totalPages: number;
     searchQuery: string;
+    // Steps whose recognised screenshot text matches searchQuery
+    stepMatches: StepTextMatch[];
 
     // Deletion progress state
     deletionProgress: DeleteProgress | null;
@@ -103,7 +257,9 @@ interface RecordingsState {
     deleteRecording: (id: string, recordingName: string) => Promise<void>;
     updateRecordingName: (id: string, name: string) => Promise<void>;
     reorderRecordingSteps: (recordingId: string, stepIds: string[]) => Promise<void>;
-    updateStepOcr: (stepId: string, ocrText: string | null, ocrStatus: string) => Promise<void>;
+    updateStepOcr: (stepId: string, ocrText: string | null, ocrStatus: string, ocrLines?: OcrLine[] | null) => Promise<void>;
+    updateStepAnnotations: (stepId: string, annotations: Annotation[]) => Promise<void>;
+    setStepRedactions: (stepId: string, redactions: Redaction[]) => Promise<Step>;
     setCurrentRecording: (recording: RecordingWithSteps | null) => void;
     clearError: () => void;
     fetchRecordingsPaginated: (page?: number, search?: string) => Promise<void>;
@@ -126,6 +282,7 @@ export const useRecordingsStore = create<RecordingsState>((set, get) => ({
     totalCount: 0,
     totalPages: 0,
     searchQuery: "",
+    stepMatches: [],
 
     // Deletion progress state
     deletionProgress: null,
@@ -294,15 +451,27 @@ export const useRecordingsStore = create<RecordingsState>((set, get) => ({
         }
     },
 
-    updateStepOcr: async (stepId: string, ocrText: string | null, ocrStatus: string) => {
+    updateStepOcr: async (stepId: string, ocrText: string | null, ocrStatus: string, ocrLines?: OcrLine[] | null) => {
         try {
-            await invoke('update_step_ocr', { stepId, ocrText, ocrStatus });
+            // The backend may fill in a default description from the new text
+            const description = await invoke<string | null>('update_step_ocr', {
+                stepId,
+                ocrText,
+                ocrLines: ocrLines ?? null,
+                ocrStatus,
+            });
             // Update local state if we have a current recording
             const currentRecording = get().currentRecording;
             if (currentRecording) {
                 const updatedSteps = currentRecording.steps.map(step =>
                     step.id === stepId
-                        ? { ...step, ocr_text: ocrText ?? undefined, ocr_status: ocrStatus }
+                        ? {
+                            ...step,
+                            ocr_text: ocrText ?? undefined,
+                            ocr_status: ocrStatus,
+                            ocr_lines: ocrLines ?? undefined,
+                            description: description ?? undefined,
+                        }
                         : step
                 );
                 set({
@@ -317,6 +486,55 @@ export const useRecordingsStore = create<RecordingsState>((set, get) => ({
         }
     },
 
+    updateStepAnnotations: async (stepId: string, annotations: Annotation[]) => {
+        try {
+            await invoke('update_step_annotations', { stepId, annotations });
+            const currentRecording = get().currentRecording;
+            if (currentRecording) {
+                set({
+                    currentRecording: {
+                        ...currentRecording,
+                        steps: currentRecording.steps.map(step =>
+                            step.id === stepId
+                                ? { ...step, annotations: annotations.length > 0 ? annotations : undefined }
+                                : step
+                        )
+                    }
+                });
+            }
+        } catch (error) {
+            console.error('Failed to update step annotations:', error);
+            throw error;
+        }
+    },
+
+    setStepRedactions: async (stepId: string, redactions: Redaction[]) => {
+        try {
+            // Burned in unless the user keeps originals; either way the
+            // returned step carries the current screenshot paths.
+            const updated = await invoke<Step>('set_step_redactions', {
+                stepId,
+                redactions,
+                keepOriginal: useSettingsStore.getState().keepUnredactedOriginals,
+            });
+            const currentRecording = get().currentRecording;
+            if (currentRecording) {
+                set({
+                    currentRecording: {
+                        ...currentRecording,
+                        steps: currentRecording.steps.map(step =>
+                            step.id === stepId ? updated : step
+                        )
+                    }
+                });
+            }
+            return updated;
+        } catch (error) {
+            console.error('Failed to set step redactions:', error);
+            throw error;
+        }
+    },
+
     setCurrentRecording: (recording: RecordingWithSteps | null) => {
         set({ currentRecording: recording });
     },
@@ -332,13 +550,19 @@ export const useRecordingsStore = create<RecordingsState>((set, get) => ({
         
         set({ loading: true, error: null });
         try {
-            const result = await invoke<PaginatedRecordings>('list_recordings_paginated', {
-                page: targetPage,
-                perPage: state.perPage,
-                search: searchTerm || null
-            });
+            const [result, stepMatches] = await Promise.all([
+                invoke<PaginatedRecordings>('list_recordings_paginated', {
+                    page: targetPage,
+                    perPage: state.perPage,
+                    search: searchTerm || null
+                }),
+                searchTerm.trim()
+                    ? invoke<StepTextMatch[]>('search_step_text', { query: searchTerm, limit: 20 })
+                    : Promise.resolve([]),
+            ]);
             set({
                 recordings: result.recordings,
+                stepMatches,
                 currentPage: result.page,
                 totalCount: result.total_count,
                 totalPages: result.total_pages,
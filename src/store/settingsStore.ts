@@ -3,6 +3,7 @@ import { load, Store } from "@tauri-apps/plugin-store";
 import { invoke } from "@tauri-apps/api/core";
 import { getProvider, getDefaultProvider } from "../lib/providers";
 import { getDefaultAdvancedAiSettings, type AdvancedAiSettings } from "../lib/aiPolicy";
+import { CREDENTIAL_AI_API_KEY, getCredential, setCredential } from "../lib/credentials";
 import {
     WritingStyleOptions,
     ToneOption,
@@ -16,7 +17,35 @@ export interface HotkeyBinding {
     ctrl: boolean;
     shift: boolean;
     alt: boolean;
+    /** Cmd on macOS, the Windows key on Windows. */
+    meta: boolean;
     key: string;
+    /** Kept but not registered, so it does nothing. */
+    disabled?: boolean;
+}
+
+/** What a global hotkey does; "capture" opens the capture picker. */
+export type HotkeyAction = "start" | "stop" | "capture" | "pause" | "undo" | "marker";
+
+/** The binding of each hotkey action, or null when it has none. */
+export type HotkeyBindings = Record<HotkeyAction, HotkeyBinding | null>;
+
+/** Why a hotkey can't be registered, from `validate_hotkey` and `set_hotkeys`. */
+export type HotkeyConflict =
+    | { kind: "unsupported_key"; key: string }
+    | { kind: "action"; action: HotkeyAction }
+    | { kind: "system"; message: string };
+
+export interface HotkeyFailure {
+    action: HotkeyAction;
+    conflict: HotkeyConflict;
+}
+
+/** Named hotkey bindings stored in the backend; unbound actions are missing. */
+export interface HotkeyProfile {
+    name: string;
+    bindings: Partial<Record<HotkeyAction, HotkeyBinding>>;
+    updated_at: number;
 }
 
 export interface SettingsHydrationResult {
@@ -24,6 +53,116 @@ export interface SettingsHydrationResult {
     ocrEnabled: boolean;
 }
 
+/** Engine OCR runs on; "auto" prefers installed ONNX models. */
+export type OcrProvider = "auto" | "onnx" | "windows" | "vision";
+
+export interface OcrProviderBenchmark {
+    provider: Exclude<OcrProvider, "auto">;
+    millis: number | null;
+    error: string | null;
+}
+
+export interface OcrBenchmark {
+    results: OcrProviderBenchmark[];
+    fastest: Exclude<OcrProvider, "auto"> | null;
+}
+
+/** Where step number badges go; "click" falls back to the top-left corner. */
+export type BadgePlacement = "click" | "top_left" | "top_right";
+
+export interface StepBadgeStyle {
+    enabled: boolean;
+    /** `#RRGGBB` */
+    color: string;
+    /** Badge radius in logical pixels. */
+    size: number;
+    placement: BadgePlacement;
+    /** Swap `color` for a light or dark one when it blends into the screenshot. */
+    auto_contrast: boolean;
+}
+
+/** When screenshots are cropped around the clicked element. */
+export type SmartCropMode = "off" | "capture" | "export";
+
+export interface SmartCropSettings {
+    mode: SmartCropMode;
+    /** Context kept around the element or click, in logical pixels. */
+    padding: number;
+}
+
+export type InsetPlacement = "auto" | "top_left" | "top_right" | "bottom_left" | "bottom_right";
+
+/** Magnified inset of the area around the click, pasted into exported screenshots. */
+export interface ZoomInsetStyle {
+    enabled: boolean;
+    /** Magnification of the area around the click. */
+    zoom: number;
+    /** Side of the square inset in logical pixels. */
+    size: number;
+    /** `#RRGGBB` */
+    border_color: string;
+    placement: InsetPlacement;
+}
+
+export type WatermarkKind = "text" | "image";
+
+export type WatermarkPosition = "center" | "top_left" | "top_right" | "bottom_left" | "bottom_right" | "tiled";
+
+/** Text or image stamped onto exported screenshots, e.g. "CONFIDENTIAL". */
+export interface WatermarkStyle {
+    enabled: boolean;
+    kind: WatermarkKind;
+    text: string;
+    /** Image stamped when `kind` is "image". */
+    image_path: string | null;
+    /** `#RRGGBB` text colour. */
+    color: string;
+    position: WatermarkPosition;
+    /** 0 (invisible) to 1 (opaque). */
+    opacity: number;
+    /** Text height, or image width, in logical pixels. */
+    size: number;
+}
+
+/** What the recorder does with a step whose frame repeats the previous one. */
+export type DuplicateFrames = "off" | "flag" | "skip";
+
+/** How each click marker is highlighted on screenshots. */
+export type HighlightShape = "ring" | "arrow" | "spotlight";
+
+/** The highlight drawn at each click marker on screenshots. */
+export interface ClickHighlightStyle {
+    enabled: boolean;
+    shape: HighlightShape;
+    /** `#RRGGBB` */
+    color: string;
+    /** Ring or spotlight radius in logical pixels; the arrow is longer. */
+    radius: number;
+    /** Line thickness in logical pixels. */
+    width: number;
+    /** Swap `color` for a light or dark one when it blends into the screenshot. */
+    auto_contrast: boolean;
+}
+
+/** A named click highlight look, applied in one go. */
+export interface ClickHighlightPreset {
+    name: string;
+    style: Omit<ClickHighlightStyle, "enabled" | "auto_contrast">;
+    /** Uses colours from the Okabe-Ito palette, distinguishable with colour blindness. */
+    colorblindSafe?: boolean;
+}
+
+export const CLICK_HIGHLIGHT_PRESETS: ClickHighlightPreset[] = [
+    { name: "Classic", style: { shape: "ring", color: "#FF4500", radius: 32, width: 6 } },
+    { name: "Subtle", style: { shape: "ring", color: "#FFFFFF", radius: 24, width: 3 } },
+    { name: "Pointer", style: { shape: "arrow", color: "#FF3B30", radius: 32, width: 6 } },
+    { name: "Spotlight", style: { shape: "spotlight", color: "#FFFFFF", radius: 60, width: 4 } },
+    { name: "Blue ring", style: { shape: "ring", color: "#0072B2", radius: 32, width: 6 }, colorblindSafe: true },
+    { name: "Orange ring", style: { shape: "ring", color: "#E69F00", radius: 32, width: 6 }, colorblindSafe: true },
+    { name: "Sky arrow", style: { shape: "arrow", color: "#56B4E9", radius: 32, width: 6 }, colorblindSafe: true },
+    { name: "Yellow spotlight", style: { shape: "spotlight", color: "#F0E442", radius: 60, width: 4 }, colorblindSafe: true },
+];
+
 export interface SettingsSyncResult {
     assetScope: boolean;
     ocrSync: boolean;
@@ -41,6 +180,12 @@ interface SettingsState {
     contextWindowOverride: number | null;
     screenshotPath: string;
     sendScreenshotsToAi: boolean;
+    ocrLanguage: string;
+    ocrParallelism: number;
+    ocrProvider: OcrProvider;
+    ocrParallelModels: number;
+    /** Set once the first-run provider benchmark has picked a provider. */
+    ocrProviderBenchmarked: boolean;
     // Structured writing style options
     writingStyle: WritingStyleOptions;
     // Rate limit mitigation settings
@@ -55,9 +200,27 @@ interface SettingsState {
     enableMultiStagePrompting: boolean;
     afterFrameMaxWaitMs: number;
     enableVideoClips: boolean;
-    startRecordingHotkey: HotkeyBinding;
-    stopRecordingHotkey: HotkeyBinding;
-    captureHotkey: HotkeyBinding;
+    enableAxTreeSnapshots: boolean;
+    /** Keep the unredacted capture and apply redactions at export instead. */
+    keepUnredactedOriginals: boolean;
+    /** Step number badges burned into exported screenshots. */
+    stepBadgeStyle: StepBadgeStyle;
+    smartCrop: SmartCropSettings;
+    zoomInset: ZoomInsetStyle;
+    watermark: WatermarkStyle;
+    duplicateFrames: DuplicateFrames;
+    /** Also keep each new screenshot as a lossless PNG that edits and exports start from. */
+    keepLosslessOriginals: boolean;
+    /** Disk space lossless originals may take before the oldest are dropped. */
+    losslessOriginalQuotaMb: number;
+    clickHighlightStyle: ClickHighlightStyle;
+    /** Highlight presets saved by the user, shown after the built-in ones. */
+    clickHighlightPresets: ClickHighlightPreset[];
+    hotkeys: HotkeyBindings;
+    /** Hotkeys the backend couldn't register at the last sync; not saved. */
+    hotkeyFailures: HotkeyFailure[];
+    /** Every hotkey is unregistered until resumed; not saved. */
+    hotkeysSuspended: boolean;
     isLoaded: boolean;
     setAiProvider: (provider: string) => void;
     setOpenaiBaseUrl: (url: string) => void;
@@ -69,6 +232,11 @@ interface SettingsState {
     setContextWindowOverride: (value: number | null) => void;
     setScreenshotPath: (path: string) => void;
     setSendScreenshotsToAi: (enabled: boolean) => void;
+    setOcrLanguage: (language: string) => void;
+    setOcrParallelism: (parallelism: number) => void;
+    setOcrProvider: (provider: OcrProvider) => void;
+    setOcrParallelModels: (models: number) => void;
+    benchmarkOcrProviders: () => Promise<OcrBenchmark | null>;
     setWritingStyleTone: (tone: ToneOption) => void;
     setWritingStyleAudience: (audience: AudienceOption) => void;
     setWritingStyleVerbosity: (verbosity: VerbosityOption) => void;
@@ -84,9 +252,22 @@ interface SettingsState {
     setEnableMultiStagePrompting: (enabled: boolean) => void;
     setAfterFrameMaxWaitMs: (ms: number) => void;
     setEnableVideoClips: (enabled: boolean) => void;
-    setStartRecordingHotkey: (hotkey: HotkeyBinding) => void;
-    setStopRecordingHotkey: (hotkey: HotkeyBinding) => void;
-    setCaptureHotkey: (hotkey: HotkeyBinding) => void;
+    setEnableAxTreeSnapshots: (enabled: boolean) => void;
+    setKeepUnredactedOriginals: (enabled: boolean) => void;
+    setStepBadgeStyle: (style: Partial<StepBadgeStyle>) => void;
+    setSmartCrop: (settings: Partial<SmartCropSettings>) => void;
+    setZoomInset: (style: Partial<ZoomInsetStyle>) => void;
+    setWatermark: (style: Partial<WatermarkStyle>) => void;
+    setDuplicateFrames: (mode: DuplicateFrames) => void;
+    setKeepLosslessOriginals: (enabled: boolean) => void;
+    setLosslessOriginalQuotaMb: (quotaMb: number) => void;
+    setClickHighlightStyle: (style: Partial<ClickHighlightStyle>) => void;
+    saveClickHighlightPreset: (name: string) => void;
+    deleteClickHighlightPreset: (name: string) => void;
+    setHotkey: (action: HotkeyAction, hotkey: HotkeyBinding | null) => void;
+    /** Replace every binding, e.g. with a hotkey profile's; missing actions are unbound. */
+    setHotkeys: (hotkeys: Partial<HotkeyBindings>) => void;
+    setHotkeysSuspended: (suspended: boolean) => Promise<void>;
     hydrateSettings: () => Promise<SettingsHydrationResult>;
     syncSettingsToBackend: () => Promise<SettingsSyncResult>;
     loadSettings: () => Promise<SettingsHydrationResult>;
@@ -103,9 +284,234 @@ async function getStore(): Promise<Store> {
     return store;
 }
 
-const defaultStartHotkey: HotkeyBinding = { ctrl: true, shift: false, alt: true, key: "KeyR" };
-const defaultStopHotkey: HotkeyBinding = { ctrl: true, shift: false, alt: true, key: "KeyS" };
-const defaultCaptureHotkey: HotkeyBinding = { ctrl: true, shift: false, alt: true, key: "KeyC" };
+/** The API key last written to the keychain, to skip rewriting it on every save. */
+let savedApiKey: string | null = null;
+
+/**
+ * The AI API key from the OS keychain. Older versions kept it in the settings
+ * file as `openaiApiKey`; such a key is moved to the keychain.
+ */
+const loadApiKey = async (store: Store, legacyKey: string | undefined): Promise<string> => {
+    try {
+        let key = await getCredential(CREDENTIAL_AI_API_KEY);
+        if (key === null && legacyKey) {
+            await setCredential(CREDENTIAL_AI_API_KEY, legacyKey);
+            key = legacyKey;
+        }
+        if (legacyKey !== undefined) {
+            await store.delete("openaiApiKey");
+            await store.save();
+        }
+        savedApiKey = key ?? "";
+        return savedApiKey;
+    } catch (error) {
+        console.error("Failed to read the API key from the keychain:", error);
+        return legacyKey || "";
+    }
+};
+
+const ctrlAlt = (key: string): HotkeyBinding => ({ ctrl: true, shift: false, alt: true, meta: false, key });
+const defaultHotkeys: HotkeyBindings = {
+    start: ctrlAlt("KeyR"),
+    stop: ctrlAlt("KeyS"),
+    capture: ctrlAlt("KeyC"),
+    pause: ctrlAlt("KeyP"),
+    undo: ctrlAlt("KeyZ"),
+    marker: ctrlAlt("KeyM"),
+};
+
+/** Bindings saved before `meta` existed lack it. */
+const migrateHotkey = (stored: HotkeyBinding | null): HotkeyBinding | null =>
+    stored ? { ...stored, meta: stored.meta ?? false } : null;
+
+/** Before the `hotkeys` map, only start, stop and capture were stored, each under its own key. */
+const LEGACY_HOTKEY_KEYS: [HotkeyAction, string][] = [
+    ["start", "startRecordingHotkey"],
+    ["stop", "stopRecordingHotkey"],
+    ["capture", "captureHotkey"],
+];
+
+const loadHotkeys = async (store: Store): Promise<HotkeyBindings> => {
+    const stored = (await store.get<Partial<HotkeyBindings>>("hotkeys")) ?? {};
+    for (const [action, legacyKey] of LEGACY_HOTKEY_KEYS) {
+        if (stored[action] === undefined) {
+            const legacy = await store.get<HotkeyBinding>(legacyKey);
+            if (legacy) stored[action] = legacy;
+        }
+    }
+    const hotkeys = { ...defaultHotkeys };
+    for (const action of Object.keys(defaultHotkeys) as HotkeyAction[]) {
+        if (stored[action] !== undefined) hotkeys[action] = migrateHotkey(stored[action]);
+    }
+    return hotkeys;
+};
+const defaultStepBadgeStyle: StepBadgeStyle = { enabled: false, color: "#FF3B30", size: 18, placement: "click", auto_contrast: false };
+const defaultSmartCrop: SmartCropSettings = { mode: "off", padding: 160 };
+const defaultZoomInset: ZoomInsetStyle = { enabled: false, zoom: 2.5, size: 240, border_color: "#FFFFFF", placement: "auto" };
+const defaultWatermark: WatermarkStyle = {
+    enabled: false,
+    kind: "text",
+    text: "CONFIDENTIAL",
+    image_path: null,
+    color: "#FF3B30",
+    position: "center",
+    opacity: 0.3,
+    size: 64,
+};
+const defaultClickHighlightStyle: ClickHighlightStyle = { enabled: true, shape: "ring", color: "#FF4500", radius: 32, width: 6, auto_contrast: false };
+const defaultLosslessOriginalQuotaMb = 2048;
+const BYTES_PER_MB = 1024 * 1024;
+
+/** The settings the backend keeps and reads itself (see `settings.rs`), as it names them. */
+interface BackendSettings {
+    screenshot_path: string | null;
+    ocr_enabled: boolean;
+    ocr_language: string;
+    ocr_parallelism: number;
+    ocr_provider: Exclude<OcrProvider, "auto"> | null;
+    ocr_parallel_models: number;
+    state_diff_enabled: boolean;
+    after_frame_max_wait_ms: number;
+    video_clips_enabled: boolean;
+    ax_tree_snapshot_enabled: boolean;
+    smart_crop: SmartCropSettings;
+    step_badge_style: StepBadgeStyle;
+    click_highlight_style: ClickHighlightStyle;
+    zoom_inset: ZoomInsetStyle;
+    watermark: WatermarkStyle;
+    duplicate_frames: DuplicateFrames;
+    keep_lossless_originals: boolean;
+    /** In bytes. */
+    lossless_original_quota: number;
+}
+
+/** The store's fields that mirror `BackendSettings`; they're not written to the settings file. */
+type BackendOwnedSettings = Pick<
+    SettingsState,
+    | "screenshotPath"
+    | "sendScreenshotsToAi"
+    | "ocrLanguage"
+    | "ocrParallelism"
+    | "ocrProvider"
+    | "ocrParallelModels"
+    | "enableStateDiff"
+    | "afterFrameMaxWaitMs"
+    | "enableVideoClips"
+    | "enableAxTreeSnapshots"
+    | "smartCrop"
+    | "stepBadgeStyle"
+    | "clickHighlightStyle"
+    | "zoomInset"
+    | "watermark"
+    | "duplicateFrames"
+    | "keepLosslessOriginals"
+    | "losslessOriginalQuotaMb"
+>;
+
+const BACKEND_OWNED_KEYS: (keyof BackendOwnedSettings)[] = [
+    "screenshotPath",
+    "sendScreenshotsToAi",
+    "ocrLanguage",
+    "ocrParallelism",
+    "ocrProvider",
+    "ocrParallelModels",
+    "enableStateDiff",
+    "afterFrameMaxWaitMs",
+    "enableVideoClips",
+    "enableAxTreeSnapshots",
+    "smartCrop",
+    "stepBadgeStyle",
+    "clickHighlightStyle",
+    "zoomInset",
+    "watermark",
+    "duplicateFrames",
+    "keepLosslessOriginals",
+    "losslessOriginalQuotaMb",
+];
+
+/** `update_settings` patch for the given store fields. */
+const toBackendPatch = (settings: Partial<BackendOwnedSettings>): Partial<BackendSettings> => {
+    const patch: Partial<BackendSettings> = {};
+    if (settings.screenshotPath !== undefined) patch.screenshot_path = settings.screenshotPath || null;
+    if (settings.sendScreenshotsToAi !== undefined) patch.ocr_enabled = settings.sendScreenshotsToAi;
+    if (settings.ocrLanguage !== undefined) patch.ocr_language = settings.ocrLanguage;
+    if (settings.ocrParallelism !== undefined) patch.ocr_parallelism = settings.ocrParallelism;
+    if (settings.ocrProvider !== undefined) {
+        patch.ocr_provider = settings.ocrProvider === "auto" ? null : settings.ocrProvider;
+    }
+    if (settings.ocrParallelModels !== undefined) patch.ocr_parallel_models = settings.ocrParallelModels;
+    if (settings.enableStateDiff !== undefined) patch.state_diff_enabled = settings.enableStateDiff;
+    if (settings.afterFrameMaxWaitMs !== undefined) patch.after_frame_max_wait_ms = settings.afterFrameMaxWaitMs;
+    if (settings.enableVideoClips !== undefined) patch.video_clips_enabled = settings.enableVideoClips;
+    if (settings.enableAxTreeSnapshots !== undefined) patch.ax_tree_snapshot_enabled = settings.enableAxTreeSnapshots;
+    // Style fields missing from older versions fall back to the backend's defaults
+    if (settings.smartCrop !== undefined) patch.smart_crop = settings.smartCrop;
+    if (settings.stepBadgeStyle !== undefined) patch.step_badge_style = settings.stepBadgeStyle;
+    if (settings.clickHighlightStyle !== undefined) patch.click_highlight_style = settings.clickHighlightStyle;
+    if (settings.zoomInset !== undefined) patch.zoom_inset = settings.zoomInset;
+    if (settings.watermark !== undefined) patch.watermark = settings.watermark;
+    if (settings.duplicateFrames !== undefined) patch.duplicate_frames = settings.duplicateFrames;
+    if (settings.keepLosslessOriginals !== undefined) patch.keep_lossless_originals = settings.keepLosslessOriginals;
+    if (settings.losslessOriginalQuotaMb !== undefined) {
+        patch.lossless_original_quota = settings.losslessOriginalQuotaMb * BYTES_PER_MB;
+    }
+    return patch;
+};
+
+const fromBackend = (settings: BackendSettings): BackendOwnedSettings => ({
+    screenshotPath: settings.screenshot_path ?? "",
+    sendScreenshotsToAi: settings.ocr_enabled,
+    ocrLanguage: settings.ocr_language,
+    ocrParallelism: settings.ocr_parallelism,
+    ocrProvider: settings.ocr_provider ?? "auto",
+    ocrParallelModels: settings.ocr_parallel_models,
+    enableStateDiff: settings.state_diff_enabled,
+    afterFrameMaxWaitMs: settings.after_frame_max_wait_ms,
+    enableVideoClips: settings.video_clips_enabled,
+    enableAxTreeSnapshots: settings.ax_tree_snapshot_enabled,
+    smartCrop: settings.smart_crop,
+    stepBadgeStyle: settings.step_badge_style,
+    clickHighlightStyle: settings.click_highlight_style,
+    zoomInset: settings.zoom_inset,
+    watermark: settings.watermark,
+    duplicateFrames: settings.duplicate_frames,
+    keepLosslessOriginals: settings.keep_lossless_originals,
+    losslessOriginalQuotaMb: Math.round(settings.lossless_original_quota / BYTES_PER_MB),
+});
+
+/**
+ * The settings the backend owns. Older versions also kept them in the
+ * settings file; values found there are handed to the backend and removed.
+ */
+const loadBackendSettings = async (store: Store): Promise<BackendOwnedSettings | null> => {
+    const legacy: Partial<Record<keyof BackendOwnedSettings, unknown>> = {};
+    for (const key of BACKEND_OWNED_KEYS) {
+        const value = await store.get(key);
+        if (value !== undefined && value !== null) legacy[key] = value;
+    }
+    const legacyKeys = Object.keys(legacy);
+    if (legacyKeys.length > 0) {
+        try {
+            const settings = await invoke<BackendSettings>("update_settings", {
+                patch: toBackendPatch(legacy as Partial<BackendOwnedSettings>),
+            });
+            for (const key of legacyKeys) {
+                await store.delete(key);
+            }
+            await store.save();
+            return fromBackend(settings);
+        } catch (error) {
+            // Left in the file to try again next time
+            console.error("Failed to move saved settings to the backend:", error);
+        }
+    }
+    try {
+        return fromBackend(await invoke<BackendSettings>("get_settings"));
+    } catch (error) {
+        console.error("Failed to load settings from the backend:", error);
+        return null;
+    }
+};
 
 // Rate limit mitigation defaults
 const defaultEnableAutoRetry = true;
@@ -144,6 +550,11 @@ export const useSettingsStore = create<SettingsState>((set, get) => ({
     contextWindowOverride: defaultAdvancedAiSettings.contextWindowOverride,
     screenshotPath: "",
     sendScreenshotsToAi: true, // Default: send screenshots to AI
+    ocrLanguage: "auto",
+    ocrParallelism: 2,
+    ocrProvider: "auto",
+    ocrParallelModels: 2,
+    ocrProviderBenchmarked: false,
     writingStyle: { ...DEFAULT_WRITING_STYLE },
     enableAutoRetry: defaultEnableAutoRetry,
     maxRetryAttempts: defaultMaxRetryAttempts,
@@ -155,9 +566,20 @@ export const useSettingsStore = create<SettingsState>((set, get) => ({
     enableMultiStagePrompting: false,
     afterFrameMaxWaitMs: 2000,
     enableVideoClips: false,
-    startRecordingHotkey: defaultStartHotkey,
-    stopRecordingHotkey: defaultStopHotkey,
-    captureHotkey: defaultCaptureHotkey,
+    enableAxTreeSnapshots: false,
+    keepUnredactedOriginals: false,
+    stepBadgeStyle: defaultStepBadgeStyle,
+    smartCrop: defaultSmartCrop,
+    zoomInset: defaultZoomInset,
+    watermark: defaultWatermark,
+    duplicateFrames: "flag",
+    keepLosslessOriginals: false,
+    losslessOriginalQuotaMb: defaultLosslessOriginalQuotaMb,
+    clickHighlightStyle: defaultClickHighlightStyle,
+    clickHighlightPresets: [],
+    hotkeys: defaultHotkeys,
+    hotkeyFailures: [],
+    hotkeysSuspended: false,
     isLoaded: false,
 
     setAiProvider: (provider) => {
@@ -195,6 +617,25 @@ export const useSettingsStore = create<SettingsState>((set, get) => ({
     }),
     setScreenshotPath: (path) => set({ screenshotPath: path }),
     setSendScreenshotsToAi: (enabled) => set({ sendScreenshotsToAi: enabled }),
+    setOcrLanguage: (language) => set({ ocrLanguage: language }),
+    setOcrParallelism: (parallelism) => set({ ocrParallelism: Math.max(1, Math.min(4, Math.round(parallelism))) }),
+    // A manual choice also stops the first-run benchmark from overriding it
+    setOcrProvider: (provider) => set({ ocrProvider: provider, ocrProviderBenchmarked: true }),
+    setOcrParallelModels: (models) => set({ ocrParallelModels: Math.max(1, Math.min(4, Math.round(models))) }),
+    benchmarkOcrProviders: async () => {
+        try {
+            const benchmark = await invoke<OcrBenchmark>("benchmark_ocr_providers");
+            if (benchmark.fastest) {
+                // Auto-save persists the choice and syncs it to the backend
+                set({ ocrProvider: benchmark.fastest, ocrProviderBenchmarked: true });
+            }
+            return benchmark;
+        } catch (error) {
+            // Nothing to benchmark on until the first screenshot is recorded
+            console.error("Failed to benchmark OCR providers:", error);
+            return null;
+        }
+    },
     setWritingStyleTone: (tone) => set((state) => ({
         writingStyle: { ...state.writingStyle, tone }
     })),
@@ -218,9 +659,38 @@ export const useSettingsStore = create<SettingsState>((set, get) => ({
     setEnableMultiStagePrompting: (enabled) => set({ enableMultiStagePrompting: enabled }),
     setAfterFrameMaxWaitMs: (ms) => set({ afterFrameMaxWaitMs: Math.max(500, Math.min(5000, Math.round(ms))) }),
     setEnableVideoClips: (enabled) => set({ enableVideoClips: enabled }),
-    setStartRecordingHotkey: (hotkey) => set({ startRecordingHotkey: hotkey }),
-    setStopRecordingHotkey: (hotkey) => set({ stopRecordingHotkey: hotkey }),
-    setCaptureHotkey: (hotkey) => set({ captureHotkey: hotkey }),
+    setEnableAxTreeSnapshots: (enabled) => set({ enableAxTreeSnapshots: enabled }),
+    setKeepUnredactedOriginals: (enabled) => set({ keepUnredactedOriginals: enabled }),
+    setStepBadgeStyle: (style) => set((state) => ({ stepBadgeStyle: { ...state.stepBadgeStyle, ...style } })),
+    setSmartCrop: (settings) => set((state) => ({ smartCrop: { ...state.smartCrop, ...settings } })),
+    setZoomInset: (style) => set((state) => ({ zoomInset: { ...state.zoomInset, ...style } })),
+    setWatermark: (style) => set((state) => ({ watermark: { ...state.watermark, ...style } })),
+    setDuplicateFrames: (mode) => set({ duplicateFrames: mode }),
+    setKeepLosslessOriginals: (enabled) => set({ keepLosslessOriginals: enabled }),
+    setLosslessOriginalQuotaMb: (quotaMb) => set({ losslessOriginalQuotaMb: quotaMb }),
+    setClickHighlightStyle: (style) => set((state) => ({ clickHighlightStyle: { ...state.clickHighlightStyle, ...style } })),
+    saveClickHighlightPreset: (name) => set((state) => {
+        const { shape, color, radius, width } = state.clickHighlightStyle;
+        return {
+            clickHighlightPresets: [
+                ...state.clickHighlightPresets.filter((preset) => preset.name !== name),
+                { name, style: { shape, color, radius, width } },
+            ],
+        };
+    }),
+    deleteClickHighlightPreset: (name) => set((state) => ({
+        clickHighlightPresets: state.clickHighlightPresets.filter((preset) => preset.name !== name),
+    })),
+    setHotkey: (action, hotkey) => set((state) => ({ hotkeys: { ...state.hotkeys, [action]: hotkey } })),
+    setHotkeys: (hotkeys) => set({
+        hotkeys: Object.fromEntries(
+            (Object.keys(defaultHotkeys) as HotkeyAction[]).map((action) => [action, hotkeys[action] ?? null]),
+        ) as HotkeyBindings,
+    }),
+    setHotkeysSuspended: async (suspended) => {
+        const hotkeyFailures = await invoke<HotkeyFailure[]>("set_hotkeys_suspended", { suspended });
+        set(suspended ? { hotkeysSuspended: true } : { hotkeysSuspended: false, hotkeyFailures });
+    },
 
     getDefaultScreenshotPath: async () => {
         try {
@@ -243,22 +713,18 @@ export const useSettingsStore = create<SettingsState>((set, get) => ({
                 temperatureOverride,
                 outputTokenLimitOverride,
                 contextWindowOverride,
-                screenshotPath,
-                sendScreenshotsToAi,
+                ocrProviderBenchmarked,
                 writingStyle,
                 enableAutoRetry,
                 maxRetryAttempts,
                 initialRetryDelayMs,
                 enableRequestThrottling,
                 throttleDelayMs,
-                enableStateDiff,
                 enableCoherencePass,
                 enableMultiStagePrompting,
-                afterFrameMaxWaitMs,
-                enableVideoClips,
-                startHotkey,
-                stopHotkey,
-                captureHotkey,
+                keepUnredactedOriginals,
+                clickHighlightPresets,
+                hotkeys,
             ] = await Promise.all([
                 store.get<string>("aiProvider"),
                 store.get<string>("openaiBaseUrl"),
@@ -268,26 +734,26 @@ export const useSettingsStore = create<SettingsState>((set, get) => ({
                 store.get<number>("temperatureOverride"),
                 store.get<number>("outputTokenLimitOverride"),
                 store.get<number>("contextWindowOverride"),
-                store.get<string>("screenshotPath"),
-                store.get<boolean>("sendScreenshotsToAi"),
+                store.get<boolean>("ocrProviderBenchmarked"),
                 store.get<WritingStyleOptions>("writingStyle"),
                 store.get<boolean>("enableAutoRetry"),
                 store.get<number>("maxRetryAttempts"),
                 store.get<number>("initialRetryDelayMs"),
                 store.get<boolean>("enableRequestThrottling"),
                 store.get<number>("throttleDelayMs"),
-                store.get<boolean>("enableStateDiff"),
                 store.get<boolean>("enableCoherencePass"),
                 store.get<boolean>("enableMultiStagePrompting"),
-                store.get<number>("afterFrameMaxWaitMs"),
-                store.get<boolean>("enableVideoClips"),
-                store.get<HotkeyBinding>("startRecordingHotkey"),
-                store.get<HotkeyBinding>("stopRecordingHotkey"),
-                store.get<HotkeyBinding>("captureHotkey"),
+                store.get<boolean>("keepUnredactedOriginals"),
+                store.get<ClickHighlightPreset[]>("clickHighlightPresets"),
+                loadHotkeys(store),
             ]);
 
+            const storedApiKey = await loadApiKey(store, apiKey);
+            // Left at their defaults if the backend can't be reached
+            const backendSettings = await loadBackendSettings(store);
+
             // Get default screenshot path if not set
-            let finalScreenshotPath = screenshotPath || "";
+            let finalScreenshotPath = backendSettings?.screenshotPath || "";
             if (!finalScreenshotPath) {
                 try {
                     finalScreenshotPath = await invoke<string>("get_default_screenshot_path");
@@ -299,7 +765,7 @@ export const useSettingsStore = create<SettingsState>((set, get) => ({
             // Get provider defaults for any missing values
             const providerConfig = getProvider(aiProvider || getDefaultProvider().id);
             const defaultProvider = getDefaultProvider();
-            const ocrEnabled = sendScreenshotsToAi !== false; // Default to true if not set
+            const ocrEnabled = backendSettings?.sendScreenshotsToAi ?? get().sendScreenshotsToAi;
 
             // Merge loaded writing style with defaults (handles missing fields from old versions)
             const mergedWritingStyle: WritingStyleOptions = {
@@ -308,32 +774,28 @@ export const useSettingsStore = create<SettingsState>((set, get) => ({
             };
 
             set({
+                ...backendSettings,
                 aiProvider: aiProvider || defaultProvider.id,
                 openaiBaseUrl: baseUrl || providerConfig?.defaultBaseUrl || defaultProvider.defaultBaseUrl,
-                openaiApiKey: apiKey || "",
+                openaiApiKey: storedApiKey,
                 openaiModel: model || providerConfig?.defaultModel || "",
                 useProviderDefaults: useProviderDefaults ?? defaultAdvancedAiSettings.useProviderDefaults,
                 temperatureOverride: temperatureOverride ?? defaultAdvancedAiSettings.temperatureOverride,
                 outputTokenLimitOverride: outputTokenLimitOverride ?? defaultAdvancedAiSettings.outputTokenLimitOverride,
                 contextWindowOverride: contextWindowOverride ?? defaultAdvancedAiSettings.contextWindowOverride,
                 screenshotPath: finalScreenshotPath,
-                sendScreenshotsToAi: ocrEnabled,
+                ocrProviderBenchmarked: ocrProviderBenchmarked ?? false,
                 writingStyle: mergedWritingStyle,
                 enableAutoRetry: enableAutoRetry ?? defaultEnableAutoRetry,
                 maxRetryAttempts: maxRetryAttempts ?? defaultMaxRetryAttempts,
                 initialRetryDelayMs: initialRetryDelayMs ?? defaultInitialRetryDelayMs,
                 enableRequestThrottling: enableRequestThrottling ?? defaultEnableRequestThrottling,
                 throttleDelayMs: throttleDelayMs ?? defaultThrottleDelayMs,
-                enableStateDiff: enableStateDiff ?? true,
                 enableCoherencePass: enableCoherencePass ?? true,
                 enableMultiStagePrompting: enableMultiStagePrompting ?? false,
-                afterFrameMaxWaitMs: typeof afterFrameMaxWaitMs === "number" && afterFrameMaxWaitMs > 0
-                    ? Math.max(500, Math.min(5000, Math.round(afterFrameMaxWaitMs)))
-                    : 2000,
-                enableVideoClips: enableVideoClips ?? false,
-                startRecordingHotkey: startHotkey || defaultStartHotkey,
-                stopRecordingHotkey: stopHotkey || defaultStopHotkey,
-                captureHotkey: captureHotkey || defaultCaptureHotkey,
+                keepUnredactedOriginals: keepUnredactedOriginals ?? false,
+                clickHighlightPresets: clickHighlightPresets || [],
+                hotkeys,
                 isLoaded: true,
             });
             return { success: true, ocrEnabled };
@@ -348,16 +810,8 @@ export const useSettingsStore = create<SettingsState>((set, get) => ({
     },
 
     syncSettingsToBackend: async () => {
-        const {
-            screenshotPath,
-            sendScreenshotsToAi,
-            enableStateDiff,
-            afterFrameMaxWaitMs,
-            enableVideoClips,
-            startRecordingHotkey,
-            stopRecordingHotkey,
-            captureHotkey,
-        } = get();
+        const settings = get();
+        const { screenshotPath, hotkeys: hotkeyBindings } = settings;
 
         let assetScope = true;
         let ocrSync = true;
@@ -372,37 +826,20 @@ export const useSettingsStore = create<SettingsState>((set, get) => ({
             }
         }
 
+        // The backend saves the settings it reads itself (see `settings.rs`);
+        // they're not in the settings file. If this fails, it keeps the last
+        // saved values.
         try {
-            await invoke("set_ocr_enabled", { enabled: sendScreenshotsToAi });
+            await invoke("update_settings", { patch: toBackendPatch(settings) });
         } catch (error) {
             ocrSync = false;
-            console.error("Failed to sync OCR state with backend:", error);
+            console.error("Failed to sync settings with backend:", error);
         }
 
-        // Push the recorder-relevant generation-pipeline toggles. Failures here are
-        // non-fatal — recorder reads defaults until next sync succeeds.
-        try {
-            await invoke("set_state_diff_enabled", { enabled: enableStateDiff });
-        } catch (error) {
-            console.error("Failed to sync state-diff toggle with backend:", error);
-        }
         try {
-            await invoke("set_after_frame_max_wait_ms", { ms: afterFrameMaxWaitMs });
-        } catch (error) {
-            console.error("Failed to sync after-frame wait cap with backend:", error);
-        }
-        try {
-            await invoke("set_video_clips_enabled", { enabled: enableVideoClips });
-        } catch (error) {
-            console.error("Failed to sync video-clips toggle with backend:", error);
-        }
-
-        try {
-            await invoke("set_hotkeys", {
-                start: startRecordingHotkey,
-                stop: stopRecordingHotkey,
-                capture: captureHotkey,
-            });
+            const hotkeyFailures = await invoke<HotkeyFailure[]>("set_hotkeys", { bindings: hotkeyBindings });
+            set({ hotkeyFailures });
+            hotkeys = hotkeyFailures.length === 0;
         } catch (error) {
             hotkeys = false;
             console.error("Failed to sync hotkeys with backend:", error);
@@ -429,50 +866,53 @@ export const useSettingsStore = create<SettingsState>((set, get) => ({
                 temperatureOverride,
                 outputTokenLimitOverride,
                 contextWindowOverride,
-                screenshotPath,
-                sendScreenshotsToAi,
+                ocrProviderBenchmarked,
                 writingStyle,
                 enableAutoRetry,
                 maxRetryAttempts,
                 initialRetryDelayMs,
                 enableRequestThrottling,
                 throttleDelayMs,
-                enableStateDiff,
                 enableCoherencePass,
                 enableMultiStagePrompting,
-                afterFrameMaxWaitMs,
-                enableVideoClips,
-                startRecordingHotkey,
-                stopRecordingHotkey,
-                captureHotkey,
+                keepUnredactedOriginals,
+                clickHighlightPresets,
+                hotkeys,
             } = get();
 
             await store.set("aiProvider", aiProvider);
             await store.set("openaiBaseUrl", openaiBaseUrl);
-            await store.set("openaiApiKey", openaiApiKey);
             await store.set("openaiModel", openaiModel);
             await store.set("useProviderDefaults", useProviderDefaults);
             await store.set("temperatureOverride", temperatureOverride);
             await store.set("outputTokenLimitOverride", outputTokenLimitOverride);
             await store.set("contextWindowOverride", contextWindowOverride);
-            await store.set("screenshotPath", screenshotPath);
-            await store.set("sendScreenshotsToAi", sendScreenshotsToAi);
+            await store.set("ocrProviderBenchmarked", ocrProviderBenchmarked);
             await store.set("writingStyle", writingStyle);
             await store.set("enableAutoRetry", enableAutoRetry);
             await store.set("maxRetryAttempts", maxRetryAttempts);
             await store.set("initialRetryDelayMs", initialRetryDelayMs);
             await store.set("enableRequestThrottling", enableRequestThrottling);
             await store.set("throttleDelayMs", throttleDelayMs);
-            await store.set("enableStateDiff", enableStateDiff);
             await store.set("enableCoherencePass", enableCoherencePass);
             await store.set("enableMultiStagePrompting", enableMultiStagePrompting);
-            await store.set("afterFrameMaxWaitMs", afterFrameMaxWaitMs);
-            await store.set("enableVideoClips", enableVideoClips);
-            await store.set("startRecordingHotkey", startRecordingHotkey);
-            await store.set("stopRecordingHotkey", stopRecordingHotkey);
-            await store.set("captureHotkey", captureHotkey);
+            await store.set("keepUnredactedOriginals", keepUnredactedOriginals);
+            await store.set("clickHighlightPresets", clickHighlightPresets);
+            await store.set("hotkeys", hotkeys);
+            for (const [, legacyKey] of LEGACY_HOTKEY_KEYS) {
+                await store.delete(legacyKey);
+            }
             await store.save();
 
+            if (openaiApiKey !== savedApiKey) {
+                try {
+                    await setCredential(CREDENTIAL_AI_API_KEY, openaiApiKey);
+                    savedApiKey = openaiApiKey;
+                } catch (error) {
+                    console.error("Failed to save the API key to the keychain:", error);
+                }
+            }
+
             await get().syncSettingsToBackend();
         } catch (error) {
             console.error("Failed to save settings:", error);
@@ -493,8 +933,8 @@ useSettingsStore.subscribe((state, prevState) => {
     // Exclude functions and isLoaded from comparison
     const settingsChanged = Object.keys(state).some((key) => {
         const value = state[key as keyof typeof state];
-        // Skip functions and isLoaded flag
-        if (typeof value === "function" || key === "isLoaded") {
+        // Skip functions, the isLoaded flag and state reported by the backend
+        if (typeof value === "function" || key === "isLoaded" || key === "hotkeyFailures" || key === "hotkeysSuspended") {
             return false;
         }
         return value !== prevState[key as keyof typeof prevState];